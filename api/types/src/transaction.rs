@@ -1130,6 +1130,7 @@ impl VerifyInput for Secp256k1EcdsaSignature {
 pub enum Signature {
     Ed25519(HexEncodedBytes),
     Secp256k1Ecdsa(HexEncodedBytes),
+    WebAuthn(HexEncodedBytes),
 }
 
 impl TryFrom<Signature> for AnySignature {
@@ -1139,6 +1140,7 @@ impl TryFrom<Signature> for AnySignature {
         Ok(match signature {
             Signature::Ed25519(s) => AnySignature::ed25519(s.inner().try_into()?),
             Signature::Secp256k1Ecdsa(s) => AnySignature::secp256k1_ecdsa(s.inner().try_into()?),
+            Signature::WebAuthn(s) => AnySignature::webauthn(s.inner().try_into()?),
         })
     }
 }
@@ -1152,6 +1154,9 @@ impl From<AnySignature> for Signature {
             AnySignature::Secp256k1Ecdsa { signature } => {
                 Signature::Secp256k1Ecdsa(signature.to_bytes().to_vec().into())
             },
+            AnySignature::WebAuthn { signature } => {
+                Signature::WebAuthn(signature.to_bytes().into())
+            },
         }
     }
 }
@@ -1162,6 +1167,7 @@ impl From<AnySignature> for Signature {
 pub enum PublicKey {
     Ed25519(HexEncodedBytes),
     Secp256k1Ecdsa(HexEncodedBytes),
+    Secp256r1Ecdsa(HexEncodedBytes),
 }
 
 impl TryFrom<PublicKey> for AnyPublicKey {
@@ -1171,6 +1177,7 @@ impl TryFrom<PublicKey> for AnyPublicKey {
         Ok(match public_key {
             PublicKey::Ed25519(p) => AnyPublicKey::ed25519(p.inner().try_into()?),
             PublicKey::Secp256k1Ecdsa(p) => AnyPublicKey::secp256k1_ecdsa(p.inner().try_into()?),
+            PublicKey::Secp256r1Ecdsa(p) => AnyPublicKey::secp256r1_ecdsa(p.inner().try_into()?),
         })
     }
 }
@@ -1184,6 +1191,9 @@ impl From<AnyPublicKey> for PublicKey {
             AnyPublicKey::Secp256k1Ecdsa { public_key } => {
                 PublicKey::Secp256k1Ecdsa(public_key.to_bytes().to_vec().into())
             },
+            AnyPublicKey::Secp256r1Ecdsa { public_key } => {
+                PublicKey::Secp256r1Ecdsa(public_key.to_bytes().to_vec().into())
+            },
         }
     }
 }
@@ -1210,6 +1220,10 @@ impl VerifyInput for SingleKeySignature {
                 }
                 .verify()
             },
+            (PublicKey::Secp256r1Ecdsa(_), Signature::WebAuthn(_)) => {
+                let _: AccountAuthenticator = self.clone().try_into()?;
+                Ok(())
+            },
             _ => bail!("Invalid public key, signature match."),
         }
     }
@@ -1243,6 +1257,13 @@ impl TryFrom<SingleKeySignature> for AccountAuthenticator {
                     .context("Failed to parse given public_key bytes as Secp256k1EcdsaPublicKey")?;
                 AnyPublicKey::secp256k1_ecdsa(key)
             },
+            PublicKey::Secp256r1Ecdsa(p) => {
+                let key = p
+                    .inner()
+                    .try_into()
+                    .context("Failed to parse given public_key bytes as P256PublicKey")?;
+                AnyPublicKey::secp256r1_ecdsa(key)
+            },
         };
 
         let signature = match value.signature {
@@ -1260,6 +1281,13 @@ impl TryFrom<SingleKeySignature> for AccountAuthenticator {
                     .context("Failed to parse given public_key bytes as Secp256k1EcdsaSignature")?;
                 AnySignature::secp256k1_ecdsa(signature)
             },
+            Signature::WebAuthn(s) => {
+                let signature = s
+                    .inner()
+                    .try_into()
+                    .context("Failed to parse given signature bytes as WebAuthn assertion")?;
+                AnySignature::webauthn(signature)
+            },
         };
 
         let auth = SingleKeyAuthenticator::new(key, signature);
@@ -1317,6 +1345,13 @@ impl TryFrom<MultiKeySignature> for AccountAuthenticator {
                     )?;
                     AnyPublicKey::secp256k1_ecdsa(key)
                 },
+                PublicKey::Secp256r1Ecdsa(p) => {
+                    let key = p
+                        .inner()
+                        .try_into()
+                        .context("Failed to parse given public_key bytes as P256PublicKey")?;
+                    AnyPublicKey::secp256r1_ecdsa(key)
+                },
             };
             public_keys.push(key);
         }
@@ -1337,6 +1372,13 @@ impl TryFrom<MultiKeySignature> for AccountAuthenticator {
                         )?;
                         AnySignature::secp256k1_ecdsa(signature)
                     },
+                    Signature::WebAuthn(s) => {
+                        let signature = s
+                            .inner()
+                            .try_into()
+                            .context("Failed to parse given signature as WebAuthn assertion")?;
+                        AnySignature::webauthn(signature)
+                    },
                 };
             signatures.push((indexed_signature.index, signature));
         }
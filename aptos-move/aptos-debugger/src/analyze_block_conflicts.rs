@@ -0,0 +1,57 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{aptos_debugger::AptosDebugger, common::Opts};
+use anyhow::{ensure, Result};
+use aptos_rest_client::Client;
+use aptos_vm::AptosVM;
+use clap::Parser;
+use url::Url;
+
+/// Replays a committed block with parallel execution and prints the conflicts between its
+/// transactions: the hottest keys and the reader -> writer conflict graph.
+#[derive(Parser)]
+pub struct Command {
+    #[clap(flatten)]
+    opts: Opts,
+
+    /// Version of the block metadata transaction that starts the block.
+    #[clap(long)]
+    begin_version: u64,
+
+    /// Maximum number of transactions to fetch when looking for the end of the block.
+    #[clap(long, default_value_t = 10000)]
+    max_block_size: u64,
+
+    /// Number of hot keys to export to the conflict metrics.
+    #[clap(long, default_value_t = 20)]
+    top_n: usize,
+}
+
+impl Command {
+    pub async fn run(self) -> Result<()> {
+        ensure!(
+            self.opts.concurrency_level > 1,
+            "Conflicts are only observed with parallel execution, set --concurrency-level > 1"
+        );
+        AptosVM::set_concurrency_level_once(self.opts.concurrency_level);
+
+        let debugger = if let Some(rest_endpoint) = self.opts.target.rest_endpoint {
+            AptosDebugger::rest_client(Client::new(Url::parse(&rest_endpoint)?))?
+        } else if let Some(db_path) = self.opts.target.db_path {
+            AptosDebugger::db(db_path)?
+        } else {
+            unreachable!("Must provide one target.");
+        };
+
+        match debugger
+            .analyze_block_conflicts(self.begin_version, self.max_block_size, self.top_n)
+            .await?
+        {
+            Some(report) => println!("{report}"),
+            None => println!("The block was executed sequentially, no conflicts to report."),
+        }
+
+        Ok(())
+    }
+}
@@ -1,9 +1,10 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use anyhow::{format_err, Result};
+use anyhow::{ensure, format_err, Result};
+use aptos_block_executor::txn_commit_hook::NoOpTransactionCommitHook;
 use aptos_gas_meter::{StandardGasAlgebra, StandardGasMeter};
-use aptos_gas_profiling::{GasProfiler, TransactionGasLog};
+use aptos_gas_profiling::{GasProfiler, TableItemLoads, TransactionGasLog};
 use aptos_gas_schedule::{MiscGasParameters, NativeGasParameters, LATEST_GAS_FEATURE_VERSION};
 use aptos_memory_usage_tracker::MemoryTrackedGasMeter;
 use aptos_resource_viewer::{AnnotatedAccountStateBlob, AptosValueAnnotator};
@@ -23,13 +24,17 @@ use aptos_validator_interface::{
     AptosValidatorInterface, DBDebuggerInterface, DebuggerStateView, RestDebuggerInterface,
 };
 use aptos_vm::{
+    aptos_vm::RAYON_EXEC_POOL,
+    block_executor::{AptosBlockConflictReport, AptosTransactionOutput, BlockAptosVM},
     data_cache::AsMoveResolver,
+    execution_trace::{ExecutionTrace, ExecutionTraceRecorder},
     move_vm_ext::{MoveVmExt, SessionExt, SessionId},
     AptosVM, VMExecutor,
 };
 use aptos_vm_logging::log_schema::AdapterLogSchema;
 use aptos_vm_types::{change_set::VMChangeSet, output::VMOutput, storage::ChangeSetConfigs};
 use move_binary_format::errors::VMResult;
+use move_vm_runtime::execution_tracer::share_tracer;
 use std::{path::Path, sync::Arc};
 
 pub struct AptosDebugger {
@@ -76,7 +81,8 @@ impl AptosDebugger {
 
         // TODO(Gas): revisit this.
         let resolver = state_view.as_move_resolver();
-        let vm = AptosVM::new(&resolver);
+        let table_item_loads = TableItemLoads::default();
+        let vm = AptosVM::new(&resolver).with_table_item_load_hook(table_item_loads.hook());
 
         let (status, output, gas_profiler) = vm.execute_user_transaction_with_custom_gas_meter(
             &resolver,
@@ -101,13 +107,95 @@ impl AptosDebugger {
                     TransactionPayload::ModuleBundle(..) => unreachable!("not supported"),
                     TransactionPayload::Multisig(..) => unimplemented!("not supported yet"),
                 };
-                Ok(gas_profiler)
+                Ok(gas_profiler.with_table_item_loads(table_item_loads.clone()))
             },
         )?;
 
         Ok((status, output, gas_profiler.finish()))
     }
 
+    /// Executes the transaction at the given version and records the trace of its execution.
+    pub fn execute_transaction_at_version_with_trace(
+        &self,
+        version: Version,
+        txn: SignedTransaction,
+    ) -> Result<(VMStatus, TransactionOutput, ExecutionTrace)> {
+        let state_view = DebuggerStateView::new(self.debugger.clone(), version);
+        let log_context = AdapterLogSchema::new(state_view.id(), 0);
+        let txn = txn
+            .check_signature()
+            .map_err(|err| format_err!("Unexpected VM Error: {:?}", err))?;
+
+        let resolver = state_view.as_move_resolver();
+        let recorder = share_tracer(ExecutionTraceRecorder::new());
+        let vm = AptosVM::new(&resolver).with_execution_tracer(recorder.clone());
+
+        let (status, output, _gas_meter) = vm.execute_user_transaction_with_custom_gas_meter(
+            &resolver,
+            &txn,
+            &log_context,
+            |gas_feature_version, gas_params, storage_gas_params, balance| {
+                Ok(MemoryTrackedGasMeter::new(StandardGasMeter::new(
+                    StandardGasAlgebra::new(
+                        gas_feature_version,
+                        gas_params,
+                        storage_gas_params,
+                        balance,
+                    ),
+                )))
+            },
+        )?;
+        let output = output.try_into_transaction_output(&resolver)?;
+
+        let mut recorder = recorder.lock();
+        recorder.record_output(&output);
+        Ok((status, output, recorder.take_trace()))
+    }
+
+    /// Re-executes the block starting at `begin` in parallel and reports the conflicts between
+    /// its transactions. Returns None if the block was executed sequentially.
+    pub async fn analyze_block_conflicts(
+        &self,
+        begin: Version,
+        max_block_size: u64,
+        top_n: usize,
+    ) -> Result<Option<AptosBlockConflictReport>> {
+        let (mut txns, _) = self
+            .debugger
+            .get_committed_transactions(begin, max_block_size)
+            .await?;
+        ensure!(
+            matches!(txns.first(), Some(Transaction::BlockMetadata(_))),
+            "Version {} is not the start of a block",
+            begin
+        );
+        if let Some(block_end) = txns
+            .iter()
+            .skip(1)
+            .position(|txn| matches!(txn, Transaction::BlockMetadata(_)))
+        {
+            txns.truncate(block_end + 1);
+        }
+
+        let sig_verified_txns: Vec<SignatureVerifiedTransaction> =
+            txns.into_iter().map(|x| x.into()).collect::<Vec<_>>();
+        let state_view = DebuggerStateView::new(self.debugger.clone(), begin);
+        let (_, report) = BlockAptosVM::execute_block_with_conflict_report::<
+            _,
+            NoOpTransactionCommitHook<AptosTransactionOutput, VMStatus>,
+        >(
+            Arc::clone(&RAYON_EXEC_POOL),
+            &sig_verified_txns,
+            &state_view,
+            AptosVM::get_concurrency_level(),
+            None,
+            None,
+            top_n,
+        )
+        .map_err(|err| format_err!("Unexpected VM Error: {:?}", err))?;
+        Ok(report)
+    }
+
     pub async fn execute_past_transactions(
         &self,
         mut begin: Version,
@@ -1,7 +1,7 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{execute_past_transactions, execute_pending_block};
+use crate::{analyze_block_conflicts, execute_past_transactions, execute_pending_block};
 use anyhow::Result;
 use clap::Parser;
 use std::path::PathBuf;
@@ -33,6 +33,7 @@ pub struct Opts {
 
 #[derive(Parser)]
 pub enum Command {
+    AnalyzeBlockConflicts(analyze_block_conflicts::Command),
     ExecutePastTransactions(execute_past_transactions::Command),
     ExecutePendingBlock(execute_pending_block::Command),
 }
@@ -40,6 +41,7 @@ pub enum Command {
 impl Command {
     pub async fn run(self) -> Result<()> {
         match self {
+            Command::AnalyzeBlockConflicts(cmd) => cmd.run().await,
             Command::ExecutePastTransactions(cmd) => cmd.run().await,
             Command::ExecutePendingBlock(cmd) => cmd.run().await,
         }
@@ -1,6 +1,7 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod analyze_block_conflicts;
 pub mod aptos_debugger;
 pub mod bcs_txn_decoder;
 pub mod common;
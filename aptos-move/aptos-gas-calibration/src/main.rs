@@ -5,17 +5,28 @@ mod math;
 mod math_interface;
 mod measurements;
 mod measurements_helpers;
+mod proposal;
 mod solve;
 use aptos_abstract_gas_usage::{aggregate_terms, expand_terms};
 use aptos_gas_algebra::DynamicExpression;
 use clap::Parser;
 use math_interface::{convert_to_matrix_format, total_num_of_cols, total_num_rows};
 use measurements::compile_and_run;
+use proposal::{generate_proposal, CurrentGasSchedule};
 use solve::{build_coefficient_matrix, build_constant_matrix, least_squares};
-use std::collections::BTreeMap;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::PathBuf,
+};
+use url::Url;
 
 /// Automated Gas Calibration to calibrate Move bytecode and Native Functions
 #[derive(Parser, Debug)]
+#[clap(group(clap::ArgGroup::new("current_gas_schedule")
+        .multiple(false)
+        .requires("proposal_output")
+        .args(&["current_gas_schedule_endpoint", "current_gas_schedule_file"]),
+))]
 struct Args {
     /// Specific Calibration Function tests to run that match a given pattern
     #[clap(short, long, default_value = "")]
@@ -28,6 +39,22 @@ struct Args {
     /// Maximum execution time in milliseconds
     #[clap(short, long, default_value_t = 300)]
     max_execution_time: u64,
+
+    /// Write a release config proposing the calibrated gas schedule to this path
+    #[clap(long, requires = "current_gas_schedule")]
+    proposal_output: Option<PathBuf>,
+
+    /// Fetch the gas schedule the proposal changes from the REST API of this node
+    #[clap(long)]
+    current_gas_schedule_endpoint: Option<Url>,
+
+    /// Read the gas schedule the proposal changes from this YAML file of a `GasScheduleV2`
+    #[clap(long)]
+    current_gas_schedule_file: Option<PathBuf>,
+
+    /// Gas parameters that the proposal may make cheaper than they currently are
+    #[clap(long, requires = "proposal_output")]
+    allow_decrease: Vec<String>,
 }
 
 fn main() {
@@ -77,10 +104,34 @@ fn main() {
 
     // Solve the system of linear equations
     least_squares(
-        mappings,
+        mappings.clone(),
         &mut coeff_matrix,
         &mut const_matrix,
         measurements.equation_names,
         max_execution_time,
     );
+
+    // Propose the calibrated gas schedule
+    if let Some(output) = args.proposal_output {
+        let allow_decrease: BTreeSet<String> = args.allow_decrease.into_iter().collect();
+        let current_gas_schedule = match (
+            args.current_gas_schedule_endpoint,
+            args.current_gas_schedule_file,
+        ) {
+            (Some(endpoint), _) => CurrentGasSchedule::Endpoint(endpoint),
+            (None, Some(path)) => CurrentGasSchedule::File(path),
+            (None, None) => unreachable!("A proposal output requires a current gas schedule"),
+        };
+        if let Err(err) = generate_proposal(
+            mappings,
+            &mut coeff_matrix,
+            &mut const_matrix,
+            max_execution_time,
+            &allow_decrease,
+            &current_gas_schedule,
+            &output,
+        ) {
+            println!("\nno proposal generated: {}", err);
+        }
+    }
 }
@@ -7,6 +7,8 @@ use nalgebra::{self, DMatrix};
 use std::ops::{Div, Mul};
 
 const MARGIN_OF_ERROR: f64 = 0.2;
+/// Two-sided 95% quantile of the standard normal distribution.
+const Z_95: f64 = 1.96;
 
 /// Add a gas formula to the coefficient matrix
 ///
@@ -92,6 +94,36 @@ pub fn compute_least_square_solutions(
     Ok(x_hat)
 }
 
+/// Compute the half-widths of the 95% confidence intervals of the least squares
+/// solution, from the variance of the residuals. Returns `None` if there are not
+/// more equations than variables, as the variance cannot be estimated then.
+///
+/// ### Arguments
+///
+/// * `A` - Coefficient matrix
+/// * `b` - Constant matrix
+/// * `x_hat` - Least squares solution
+#[allow(non_snake_case)]
+pub fn compute_confidence_intervals(
+    A: &DMatrix<f64>,
+    b: &DMatrix<f64>,
+    x_hat: &DMatrix<f64>,
+) -> Option<Vec<f64>> {
+    let (nrows, ncols) = A.shape();
+    if nrows <= ncols {
+        return None;
+    }
+
+    let residuals = b - A * x_hat;
+    let variance = residuals.norm_squared() / (nrows - ncols) as f64;
+    let covariance = (A.transpose() * A).try_inverse()? * variance;
+    Some(
+        (0..ncols)
+            .map(|j| Z_95 * covariance[(j, j)].max(0.0).sqrt())
+            .collect(),
+    )
+}
+
 /// Find all free variables / linear dependent combinations
 ///
 /// ### Arguments
@@ -0,0 +1,393 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Turns the solved gas parameters into a proposal for changing the gas schedule.
+//!
+//! The estimates are compared with the gas schedule currently in effect, which is fetched from a
+//! node or read from a file. Only the entries whose costs change are written to the release
+//! config, and the release builder applies them to the gas schedule on chain when it generates
+//! the proposal.
+//!
+//! The estimates are constrained before they are proposed:
+//! - A parameter is never proposed below its current cost, unless decreasing it is allowed
+//!   explicitly and the whole confidence interval of the estimate lies below the current cost.
+//! - Orderings between related parameters that hold in the current schedule are preserved,
+//!   i.e. a generic instruction never becomes cheaper than its non-generic counterpart, and an
+//!   operation on a wider integer never becomes cheaper than the same one on a narrower integer.
+
+use crate::{
+    math::{compute_confidence_intervals, compute_least_square_solutions},
+    math_interface::generic_map,
+    solve::internal_gas_per_microsecond,
+};
+use anyhow::{anyhow, Result};
+use aptos_gas_schedule::AptosGasParameters;
+use aptos_release_builder::{
+    components::{fetch_config, Proposal, ProposalMetadata},
+    ExecutionMode, ReleaseConfig, ReleaseEntry,
+};
+use aptos_rest_client::Client;
+use aptos_types::on_chain_config::GasScheduleV2;
+use nalgebra::DMatrix;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+};
+use url::Url;
+
+const INTEGER_WIDTHS: [&str; 6] = ["U8", "U16", "U32", "U64", "U128", "U256"];
+
+/// Where the gas schedule currently in effect is read from.
+pub enum CurrentGasSchedule {
+    /// The REST API of a node of the network the proposal is for.
+    Endpoint(Url),
+    /// A YAML file of a `GasScheduleV2`.
+    File(PathBuf),
+}
+
+impl CurrentGasSchedule {
+    fn fetch(&self) -> Result<GasScheduleV2> {
+        match self {
+            Self::Endpoint(endpoint) => fetch_config(&Client::new(endpoint.clone())),
+            Self::File(path) => Ok(serde_yaml::from_slice(&std::fs::read(path)?)?),
+        }
+    }
+
+    fn endpoint(&self) -> Option<Url> {
+        match self {
+            Self::Endpoint(endpoint) => Some(endpoint.clone()),
+            Self::File(_) => None,
+        }
+    }
+}
+
+/// The calibrated cost of a gas parameter, in internal gas units.
+struct Estimate {
+    cost: f64,
+    /// Half-width of the 95% confidence interval, if it could be computed.
+    margin: Option<f64>,
+}
+
+/// Solve the gas parameters, report how their costs would change, and write a release
+/// config with the gas schedule entries whose costs change to `output`.
+///
+/// ### Arguments
+///
+/// * `input` - Collection of like-terms
+/// * `coeff_matrix` - Coefficient Matrix
+/// * `const_matrix` - Constant Matrix
+/// * `max_execution_time` - Configurable flag for max execution time of txn
+/// * `allow_decrease` - Gas parameters that may become cheaper than they currently are
+/// * `current_gas_schedule` - Where the gas schedule currently in effect is read from
+/// * `output` - Path of the release config to write
+pub fn generate_proposal(
+    input: Vec<BTreeMap<String, u64>>,
+    coeff_matrix: &mut DMatrix<f64>,
+    const_matrix: &mut DMatrix<f64>,
+    max_execution_time: u64,
+    allow_decrease: &BTreeSet<String>,
+    current_gas_schedule: &CurrentGasSchedule,
+    output: &Path,
+) -> Result<()> {
+    let x_hat = compute_least_square_solutions(coeff_matrix, const_matrix)
+        .map_err(|e| anyhow!("Failed to solve the gas parameters: {}", e))?;
+    let margins = compute_confidence_intervals(coeff_matrix, const_matrix, &x_hat);
+
+    let internal_gas_per_microsecond = internal_gas_per_microsecond(max_execution_time) as f64;
+    let estimates: BTreeMap<String, Estimate> = generic_map(input)
+        .into_keys()
+        .enumerate()
+        .map(|(idx, name)| {
+            let estimate = Estimate {
+                cost: x_hat[(idx, 0)] * internal_gas_per_microsecond,
+                margin: margins
+                    .as_ref()
+                    .map(|margins| margins[idx] * internal_gas_per_microsecond),
+            };
+            (name, estimate)
+        })
+        .collect();
+
+    let current_schedule = current_gas_schedule.fetch()?;
+    let keys = AptosGasParameters::keys_by_expression_name(current_schedule.feature_version);
+    let costs_by_key: BTreeMap<&str, u64> = current_schedule
+        .entries
+        .iter()
+        .map(|(key, cost)| (key.as_str(), *cost))
+        .collect();
+    let current: BTreeMap<String, u64> = keys
+        .iter()
+        .filter_map(|(name, key)| {
+            costs_by_key
+                .get(key.as_str())
+                .map(|cost| (name.clone(), *cost))
+        })
+        .collect();
+
+    for name in estimates.keys() {
+        if !current.contains_key(name) {
+            println!(
+                "- gas parameter {} is not in the current gas schedule\n",
+                name
+            );
+        }
+    }
+
+    let proposed = propose(&current, &estimates, allow_decrease);
+    report_changes(&current, &estimates, &proposed, &keys);
+
+    let changes = changed_entries(&current, &proposed, &keys);
+    if changes.is_empty() {
+        println!("\nno gas schedule changes to propose");
+        return Ok(());
+    }
+    write_release_config(changes, current_gas_schedule.endpoint(), output)
+}
+
+/// Propose the costs of all gas parameters in the current schedule, constrained as described
+/// in the module documentation.
+fn propose(
+    current: &BTreeMap<String, u64>,
+    estimates: &BTreeMap<String, Estimate>,
+    allow_decrease: &BTreeSet<String>,
+) -> BTreeMap<String, u64> {
+    let mut proposed = current.clone();
+    for (name, estimate) in estimates {
+        if let Some(current_cost) = current.get(name) {
+            let cost = constrain_to_current(*current_cost, estimate, allow_decrease.contains(name));
+            proposed.insert(name.clone(), cost);
+        }
+    }
+    enforce_monotonicity(current, &mut proposed);
+    proposed
+}
+
+fn constrain_to_current(current: u64, estimate: &Estimate, allow_decrease: bool) -> u64 {
+    let cost = estimate.cost.max(0.0).round() as u64;
+    if cost >= current {
+        return cost;
+    }
+
+    // Only decrease if the estimate is significantly below the current cost.
+    let significant = matches!(
+        estimate.margin,
+        Some(margin) if estimate.cost + margin < current as f64
+    );
+    if allow_decrease && significant {
+        cost
+    } else {
+        current
+    }
+}
+
+/// Pairs of related gas parameters `(cheaper, costlier)`.
+fn related_pairs<'a>(names: impl Iterator<Item = &'a String>) -> Vec<(String, String)> {
+    let names: BTreeSet<&str> = names.map(|name| name.as_str()).collect();
+    let mut pairs = vec![];
+    for name in &names {
+        let non_generic = name.replace("_GENERIC", "");
+        if non_generic != *name && names.contains(non_generic.as_str()) {
+            pairs.push((non_generic, name.to_string()));
+        }
+
+        let segments: Vec<&str> = name.split('_').collect();
+        for (idx, segment) in segments.iter().enumerate() {
+            let width = INTEGER_WIDTHS.iter().position(|width| width == segment);
+            if let Some(width) = width.filter(|width| *width > 0) {
+                let mut narrower = segments.clone();
+                narrower[idx] = INTEGER_WIDTHS[width - 1];
+                let narrower = narrower.join("_");
+                if names.contains(narrower.as_str()) {
+                    pairs.push((narrower, name.to_string()));
+                }
+            }
+        }
+    }
+    pairs
+}
+
+/// Raise the costlier parameter of each related pair until it is no cheaper than the other
+/// one, for the pairs which are ordered in the current schedule.
+fn enforce_monotonicity(current: &BTreeMap<String, u64>, proposed: &mut BTreeMap<String, u64>) {
+    let pairs: Vec<_> = related_pairs(current.keys())
+        .into_iter()
+        .filter(|(cheaper, costlier)| current[cheaper] <= current[costlier])
+        .collect();
+
+    // Costs are only ever raised to costs of other parameters, so this terminates.
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (cheaper, costlier) in &pairs {
+            let cost = proposed[cheaper];
+            if proposed[costlier] < cost {
+                proposed.insert(costlier.clone(), cost);
+                changed = true;
+            }
+        }
+    }
+}
+
+/// The proposed costs that differ from the current ones, by on-chain key.
+fn changed_entries(
+    current: &BTreeMap<String, u64>,
+    proposed: &BTreeMap<String, u64>,
+    keys: &BTreeMap<String, String>,
+) -> BTreeMap<String, u64> {
+    proposed
+        .iter()
+        .filter(|(name, cost)| current[*name] != **cost)
+        .map(|(name, cost)| (keys[name].clone(), *cost))
+        .collect()
+}
+
+/// display the estimated and proposed costs of the gas parameters that were solved or
+/// have to change
+fn report_changes(
+    current: &BTreeMap<String, u64>,
+    estimates: &BTreeMap<String, Estimate>,
+    proposed: &BTreeMap<String, u64>,
+    keys: &BTreeMap<String, String>,
+) {
+    println!("\nproposed gas schedule (InternalGas, with 95% confidence intervals):\n");
+    for (name, proposed_cost) in proposed {
+        let current_cost = current[name];
+        let estimate = match estimates.get(name) {
+            Some(Estimate {
+                cost,
+                margin: Some(margin),
+            }) => format!("{:.0} ± {:.0}", cost, margin),
+            Some(Estimate { cost, margin: None }) => format!("{:.0} ± ?", cost),
+            None if *proposed_cost != current_cost => "- (raised to keep ordering)".to_string(),
+            None => continue,
+        };
+        let change = if current_cost == 0 {
+            "n/a".to_string()
+        } else {
+            format!(
+                "{:+.1}%",
+                (*proposed_cost as f64 - current_cost as f64) / current_cost as f64 * 100.0
+            )
+        };
+        println!(
+            "- {} ({}) | Current {} | Estimated {} | Proposed {} | Change {}\n",
+            name, keys[name], current_cost, estimate, proposed_cost, change
+        );
+    }
+}
+
+fn write_release_config(
+    changes: BTreeMap<String, u64>,
+    remote_endpoint: Option<Url>,
+    output: &Path,
+) -> Result<()> {
+    let has_remote_endpoint = remote_endpoint.is_some();
+    let config = ReleaseConfig {
+        name: "gas-calibration".to_string(),
+        remote_endpoint,
+        proposals: vec![Proposal {
+            name: "gas_schedule".to_string(),
+            metadata: ProposalMetadata::default(),
+            execution_mode: ExecutionMode::MultiStep,
+            update_sequence: vec![ReleaseEntry::GasScheduleChanges(changes)],
+        }],
+    };
+    config.save_config(output)?;
+    println!("release config written to {}", output.display());
+    if !has_remote_endpoint {
+        println!("set its remote_endpoint to a node of the network before generating the proposal");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule(costs: &[(&str, u64)]) -> BTreeMap<String, u64> {
+        costs
+            .iter()
+            .map(|(name, cost)| (name.to_string(), *cost))
+            .collect()
+    }
+
+    fn estimates(costs: &[(&str, f64, f64)]) -> BTreeMap<String, Estimate> {
+        costs
+            .iter()
+            .map(|(name, cost, margin)| {
+                (name.to_string(), Estimate {
+                    cost: *cost,
+                    margin: Some(*margin),
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_decrease_only_if_allowed_and_significant() {
+        let current = schedule(&[("ADD", 100), ("SUB", 100), ("MUL", 100)]);
+        let estimates = estimates(&[
+            ("ADD", 50.0, 10.0),
+            ("SUB", 50.0, 60.0),
+            ("MUL", 50.0, 10.0),
+        ]);
+        let allow_decrease = BTreeSet::from(["ADD".to_string(), "SUB".to_string()]);
+
+        let proposed = propose(&current, &estimates, &allow_decrease);
+        assert_eq!(
+            proposed,
+            schedule(&[("ADD", 50), ("SUB", 100), ("MUL", 100)])
+        );
+    }
+
+    #[test]
+    fn test_only_changed_entries() {
+        let current = schedule(&[("ADD", 100), ("SUB", 100), ("MUL", 100)]);
+        let proposed = schedule(&[("ADD", 150), ("SUB", 100), ("MUL", 120)]);
+        let keys = current
+            .keys()
+            .map(|name| (name.clone(), format!("instr.{}", name.to_lowercase())))
+            .collect();
+
+        assert_eq!(
+            changed_entries(&current, &proposed, &keys),
+            BTreeMap::from([
+                ("instr.add".to_string(), 150),
+                ("instr.mul".to_string(), 120),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_orderings_are_preserved() {
+        let current = schedule(&[
+            ("CALL_BASE", 100),
+            ("CALL_GENERIC_BASE", 100),
+            ("LD_U8", 10),
+            ("LD_U16", 10),
+            ("LD_U32", 10),
+            ("CAST_U8", 20),
+            ("CAST_U16", 10),
+        ]);
+        let estimates = estimates(&[
+            ("CALL_BASE", 200.0, 1.0),
+            ("LD_U8", 30.0, 1.0),
+            ("CAST_U8", 30.0, 1.0),
+        ]);
+
+        let proposed = propose(&current, &estimates, &BTreeSet::new());
+        assert_eq!(
+            proposed,
+            schedule(&[
+                ("CALL_BASE", 200),
+                ("CALL_GENERIC_BASE", 200),
+                ("LD_U8", 30),
+                ("LD_U16", 30),
+                ("LD_U32", 30),
+                // Not ordered in the current schedule.
+                ("CAST_U8", 30),
+                ("CAST_U16", 10),
+            ])
+        );
+    }
+}
@@ -167,6 +167,16 @@ fn report_undetermined_gas_params(
     }
 }
 
+/// the amount of InternalGas charged for one microsecond of running time
+///
+/// ### Arguments
+///
+/// * `max_execution_time` - Configurable flag for max execution time of txn
+pub fn internal_gas_per_microsecond(max_execution_time: u64) -> u64 {
+    let max_execution_gas = u64::from(TransactionGasParameters::initial().max_execution_gas);
+    (max_execution_gas / max_execution_time) / MILLISECONDS_TO_MICROSECONDS
+}
+
 /// convert gas usage per instruction to gas cost (InternalGas)
 ///
 /// ### Arguments
@@ -179,9 +189,7 @@ fn convert_to_internal_gas_cost(
     max_execution_time: u64,
     gas_params: Vec<String>,
 ) {
-    let max_execution_gas = u64::from(TransactionGasParameters::initial().max_execution_gas);
-    let one_microsec_per_internal_gas =
-        (max_execution_gas / max_execution_time) / MILLISECONDS_TO_MICROSECONDS;
+    let one_microsec_per_internal_gas = internal_gas_per_microsecond(max_execution_time);
 
     println!(
         "\ninternal gas cost ({} InternalGas per 1µ):\n",
@@ -2,11 +2,15 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    log::{ExecutionAndIOCosts, ExecutionGasEvent},
+    log::{ExecutionAndIOCosts, ExecutionGasEvent, StateKeyCosts, TransactionGasLog},
     render::{Render, TableKey},
 };
 use aptos_gas_algebra::{GasQuantity, GasScalingFactor, InternalGas};
-use aptos_types::state_store::state_key::StateKeyInner;
+use aptos_types::{
+    access_path::AccessPath,
+    state_store::state_key::{StateKey, StateKeyInner},
+};
+use move_core_types::language_storage::TypeTag;
 use std::{
     collections::{btree_map, BTreeMap},
     ops::Deref,
@@ -65,6 +69,26 @@ where
     v
 }
 
+fn storage_item_name(key: &StateKey) -> String {
+    use StateKeyInner::*;
+
+    match key.deref() {
+        AccessPath(ap) => format!("{}", Render(&ap.get_path())),
+        TableItem { handle, key } => {
+            format!("table_item<{},{}>", Render(handle), TableKey { bytes: key },)
+        },
+        Raw(..) => panic!("not supported"),
+    }
+}
+
+fn state_key_costs<'a>(
+    map: &'a mut BTreeMap<StateKey, StateKeyCosts>,
+    key: &StateKey,
+) -> &'a mut StateKeyCosts {
+    map.entry(key.clone())
+        .or_insert_with(|| StateKeyCosts::new(key.clone()))
+}
+
 impl ExecutionAndIOCosts {
     /// Counts the number of hits and aggregates the gas costs for each type of event.
     pub fn aggregate_gas_events(&self) -> AggregatedExecutionGasEvents {
@@ -100,21 +124,18 @@ impl ExecutionAndIOCosts {
                     ty,
                     cost,
                 } => insert_or_add(&mut storage_reads, format!("{}", ty), *cost),
+                LoadTableItem { key, cost } => {
+                    insert_or_add(&mut storage_reads, storage_item_name(key), *cost)
+                },
             }
         }
 
         for write in &self.write_set_transient {
-            use StateKeyInner::*;
-
-            let key = match write.key.deref() {
-                AccessPath(ap) => format!("{}", Render(&ap.get_path())),
-                TableItem { handle, key } => {
-                    format!("table_item<{},{}>", Render(handle), TableKey { bytes: key },)
-                },
-                Raw(..) => panic!("not supported"),
-            };
-
-            insert_or_add(&mut storage_writes, key, write.cost);
+            insert_or_add(
+                &mut storage_writes,
+                storage_item_name(&write.key),
+                write.cost,
+            );
         }
 
         AggregatedExecutionGasEvents {
@@ -127,3 +148,176 @@ impl ExecutionAndIOCosts {
         }
     }
 }
+
+impl TransactionGasLog {
+    /// Attributes the IO costs and the storage fees & refunds to the state items they were
+    /// charged for.
+    ///
+    /// Resource reads are attributed to the key of the resource itself, even if it is stored
+    /// in a resource group.
+    ///
+    /// The items are sorted by their storage fees and then by their IO costs, from high to low.
+    pub fn aggregate_state_key_costs(&self) -> Vec<StateKeyCosts> {
+        let mut costs = BTreeMap::new();
+
+        for event in self.exec_io.gas_events() {
+            match event {
+                ExecutionGasEvent::LoadResource {
+                    addr,
+                    ty: TypeTag::Struct(struct_tag),
+                    cost,
+                } => {
+                    if let Ok(ap) =
+                        AccessPath::resource_access_path(*addr, struct_tag.as_ref().clone())
+                    {
+                        state_key_costs(&mut costs, &StateKey::access_path(ap)).io_read += *cost;
+                    }
+                },
+                ExecutionGasEvent::LoadTableItem { key, cost } => {
+                    state_key_costs(&mut costs, key).io_read += *cost;
+                },
+                _ => (),
+            }
+        }
+
+        for write in &self.exec_io.write_set_transient {
+            state_key_costs(&mut costs, &write.key).io_write += write.cost;
+        }
+
+        for write in &self.storage.write_set_storage {
+            let item = state_key_costs(&mut costs, &write.key);
+            item.slot_fee += write.slot_fee;
+            item.bytes_fee += write.bytes_fee;
+            item.refund += write.refund;
+        }
+
+        let mut costs = costs
+            .into_values()
+            .filter(|item| !item.io().is_zero() || !item.fee().is_zero() || !item.refund.is_zero())
+            .collect::<Vec<_>>();
+        // Sort in descending order.
+        costs.sort_by(|item1, item2| (item2.fee(), item2.io()).cmp(&(item1.fee(), item1.io())));
+        costs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::log::{CallFrame, StorageFees, WriteOpType, WriteStorage, WriteTransient};
+    use aptos_types::state_store::table::TableHandle;
+    use move_core_types::{
+        account_address::AccountAddress, identifier::Identifier, language_storage::StructTag,
+    };
+
+    fn table_item(key: u8) -> StateKey {
+        StateKey::table_item(TableHandle(AccountAddress::ONE), vec![key])
+    }
+
+    fn resource_tag() -> StructTag {
+        StructTag {
+            address: AccountAddress::ONE,
+            module: Identifier::new("M").unwrap(),
+            name: Identifier::new("R").unwrap(),
+            type_params: vec![],
+        }
+    }
+
+    fn gas_log() -> TransactionGasLog {
+        use ExecutionGasEvent::*;
+
+        let mut call_graph = CallFrame::new_script();
+        call_graph.events = vec![
+            LoadTableItem {
+                key: table_item(1),
+                cost: 10.into(),
+            },
+            LoadResource {
+                addr: AccountAddress::ONE,
+                ty: TypeTag::Struct(Box::new(resource_tag())),
+                cost: 50.into(),
+            },
+            LoadTableItem {
+                key: table_item(2),
+                cost: 3.into(),
+            },
+            LoadTableItem {
+                key: table_item(1),
+                cost: 5.into(),
+            },
+        ];
+
+        TransactionGasLog {
+            exec_io: ExecutionAndIOCosts {
+                gas_scaling_factor: 1.into(),
+                total: 75.into(),
+                intrinsic_cost: 0.into(),
+                call_graph,
+                write_set_transient: vec![
+                    WriteTransient {
+                        key: table_item(2),
+                        op_type: WriteOpType::Modification,
+                        cost: 7.into(),
+                    },
+                    WriteTransient {
+                        key: table_item(3),
+                        op_type: WriteOpType::Deletion,
+                        cost: 0.into(),
+                    },
+                ],
+            },
+            storage: StorageFees {
+                total: 120.into(),
+                total_refund: 0.into(),
+                write_set_storage: vec![WriteStorage {
+                    key: table_item(2),
+                    op_type: WriteOpType::Modification,
+                    slot_fee: 100.into(),
+                    bytes_fee: 20.into(),
+                    refund: 0.into(),
+                }],
+                events: vec![],
+                event_discount: 0.into(),
+                txn_storage: 0.into(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_state_key_costs_include_table_item_reads() {
+        let costs = gas_log().aggregate_state_key_costs();
+
+        // Sorted by storage fees first and IO costs second, items without any costs dropped.
+        let resource = StateKey::access_path(
+            AccessPath::resource_access_path(AccountAddress::ONE, resource_tag()).unwrap(),
+        );
+        assert_eq!(
+            costs
+                .iter()
+                .map(|item| item.key.clone())
+                .collect::<Vec<_>>(),
+            vec![table_item(2), resource, table_item(1)]
+        );
+
+        assert_eq!(costs[0].io_read, 3.into());
+        assert_eq!(costs[0].io_write, 7.into());
+        assert_eq!(costs[0].fee(), 120.into());
+        assert_eq!(costs[1].io(), 50.into());
+        assert_eq!(costs[2].io_read, 15.into());
+        assert!(costs[2].fee().is_zero());
+    }
+
+    #[test]
+    fn test_storage_reads_include_table_items() {
+        let reads = gas_log().exec_io.aggregate_gas_events().storage_reads;
+
+        assert_eq!(
+            reads
+                .iter()
+                .map(|(_name, count, cost)| (*count, *cost))
+                .collect::<Vec<_>>(),
+            vec![(1, 50.into()), (2, 15.into()), (1, 3.into())]
+        );
+        assert_eq!(reads[1].0, storage_item_name(&table_item(1)));
+    }
+}
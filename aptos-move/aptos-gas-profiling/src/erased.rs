@@ -151,6 +151,7 @@ impl ExecutionGasEvent {
             LoadResource { addr, ty, cost } => {
                 Node::new(format!("load<{}::{}>", Render(addr), ty), *cost)
             },
+            LoadTableItem { key, cost } => Node::new(format!("load<{}>", Render(key)), *cost),
         }
     }
 }
@@ -219,7 +220,7 @@ impl WriteStorage {
     fn to_erased(&self) -> Node<StoragePair> {
         Node::new(
             format!("{}<{}>", Render(&self.op_type), Render(&self.key)),
-            (self.cost, self.refund),
+            (self.cost(), self.refund),
         )
     }
 }
@@ -39,10 +39,9 @@ impl StorageFees {
         lines.push("transaction", self.txn_storage);
 
         for item in &self.write_set_storage {
-            lines.push(
-                format!("write_set;{}<{}>", Render(&item.op_type), Render(&item.key)),
-                item.cost,
-            )
+            let path = format!("write_set;{}<{}>", Render(&item.op_type), Render(&item.key));
+            lines.push(format!("{};slot", path), item.slot_fee);
+            lines.push(format!("{};bytes", path), item.bytes_fee);
         }
 
         for event in &self.events {
@@ -138,6 +137,10 @@ impl ExecutionAndIOCosts {
                             format!("{};load<{}::{}>", self.path(), Render(addr), ty),
                             *cost,
                         ),
+                        LoadTableItem { key, cost } => self.lines.push(
+                            format!("{};load<{}>", self.path(), Render(key)),
+                            *cost,
+                        ),
                     }
                 }
 
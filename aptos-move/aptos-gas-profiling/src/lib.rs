@@ -11,4 +11,4 @@ mod render;
 mod report;
 
 pub use log::{FrameName, TransactionGasLog};
-pub use profiler::GasProfiler;
+pub use profiler::{GasProfiler, TableItemLoads};
@@ -35,6 +35,12 @@ pub enum ExecutionGasEvent {
         ty: TypeTag,
         cost: InternalGas,
     },
+    /// A table item loaded by a native function. Its cost is part of what the native
+    /// function charged and is not included in the cost of the native call.
+    LoadTableItem {
+        key: StateKey,
+        cost: InternalGas,
+    },
 }
 
 /// An enum representing the name of a call frame.
@@ -80,7 +86,8 @@ pub struct WriteTransient {
 pub struct WriteStorage {
     pub key: StateKey,
     pub op_type: WriteOpType,
-    pub cost: Fee,
+    pub slot_fee: Fee,
+    pub bytes_fee: Fee,
     pub refund: Fee,
 }
 
@@ -113,6 +120,21 @@ pub struct StorageFees {
     pub txn_storage: Fee,
 }
 
+/// Struct representing all IO costs and storage fees attributed to a single state item.
+///
+/// Note: IO costs of reading table items are only attributed if the table item loads have
+/// been reported to the profiler (see `GasProfiler::with_table_item_loads`). Module loads
+/// are not charged by the gas schedule and therefore do not show up here.
+#[derive(Debug)]
+pub struct StateKeyCosts {
+    pub key: StateKey,
+    pub io_read: InternalGas,
+    pub io_write: InternalGas,
+    pub slot_fee: Fee,
+    pub bytes_fee: Fee,
+    pub refund: Fee,
+}
+
 /// A complete log that contains all gas-related information about a transaction, including
 /// the intrinsic cost, a detailed execution log and the write set costs.
 #[derive(Debug)]
@@ -170,13 +192,40 @@ impl CallFrame {
     }
 }
 
+impl WriteStorage {
+    pub fn cost(&self) -> Fee {
+        self.slot_fee + self.bytes_fee
+    }
+}
+
+impl StateKeyCosts {
+    pub fn new(key: StateKey) -> Self {
+        Self {
+            key,
+            io_read: 0.into(),
+            io_write: 0.into(),
+            slot_fee: 0.into(),
+            bytes_fee: 0.into(),
+            refund: 0.into(),
+        }
+    }
+
+    pub fn io(&self) -> InternalGas {
+        self.io_read + self.io_write
+    }
+
+    pub fn fee(&self) -> Fee {
+        self.slot_fee + self.bytes_fee
+    }
+}
+
 impl StorageFees {
     pub(crate) fn assert_consistency(&self) {
         let mut total = Fee::zero();
         let mut total_refund = Fee::zero();
 
         for write in &self.write_set_storage {
-            total += write.cost;
+            total += write.cost();
             total_refund += write.refund;
         }
 
@@ -210,7 +259,7 @@ impl ExecutionAndIOCosts {
     }
 
     pub(crate) fn assert_consistency(&self) {
-        use ExecutionGasEvent::{Bytecode, Call, CallNative, LoadResource, Loc};
+        use ExecutionGasEvent::{Bytecode, Call, CallNative, LoadResource, LoadTableItem, Loc};
 
         let mut total = InternalGas::zero();
 
@@ -219,9 +268,10 @@ impl ExecutionAndIOCosts {
         for op in self.gas_events() {
             match op {
                 Loc(..) | Call(..) => (),
-                Bytecode { cost, .. } | CallNative { cost, .. } | LoadResource { cost, .. } => {
-                    total += *cost
-                },
+                Bytecode { cost, .. }
+                | CallNative { cost, .. }
+                | LoadResource { cost, .. }
+                | LoadTableItem { cost, .. } => total += *cost,
             }
         }
 
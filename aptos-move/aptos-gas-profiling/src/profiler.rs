@@ -7,6 +7,7 @@ use crate::log::{
 };
 use aptos_gas_algebra::{Fee, FeePerGasUnit, InternalGas, NumArgs, NumBytes};
 use aptos_gas_meter::AptosGasMeter;
+use aptos_infallible::Mutex;
 use aptos_types::{
     contract_event::ContractEvent, state_store::state_key::StateKey, write_set::WriteOp,
 };
@@ -25,6 +26,25 @@ use move_vm_types::{
     gas::{GasMeter, SimpleInstruction},
     views::{TypeView, ValueView},
 };
+use std::sync::Arc;
+
+/// Collects the table items loaded by the table natives, along with the gas charged for
+/// loading them, so that the profiler can attribute these costs to the items.
+///
+/// The hook is meant to be installed on the VM that executes the profiled transaction.
+#[derive(Clone, Default)]
+pub struct TableItemLoads(Arc<Mutex<Vec<(StateKey, InternalGas)>>>);
+
+impl TableItemLoads {
+    pub fn hook(&self) -> Arc<dyn Fn(StateKey, InternalGas) + Send + Sync> {
+        let loads = self.0.clone();
+        Arc::new(move |key, cost| loads.lock().push((key, cost)))
+    }
+
+    fn take(&self) -> Vec<(StateKey, InternalGas)> {
+        std::mem::take(&mut *self.0.lock())
+    }
+}
 
 /// A special gas meter adapter that records all gas-related events, along with the associated costs
 /// assessed by the underlying gas meter.
@@ -36,6 +56,7 @@ pub struct GasProfiler<G> {
     frames: Vec<CallFrame>,
     write_set_transient: Vec<WriteTransient>,
     storage_fees: Option<StorageFees>,
+    table_item_loads: Option<TableItemLoads>,
 }
 
 // TODO: consider switching to a library like https://docs.rs/delegate/latest/delegate/.
@@ -90,6 +111,7 @@ impl<G> GasProfiler<G> {
             frames: vec![CallFrame::new_script()],
             write_set_transient: vec![],
             storage_fees: None,
+            table_item_loads: None,
         }
     }
 
@@ -107,8 +129,16 @@ impl<G> GasProfiler<G> {
             frames: vec![CallFrame::new_function(module_id, func_name, ty_args)],
             write_set_transient: vec![],
             storage_fees: None,
+            table_item_loads: None,
         }
     }
+
+    /// Attributes the IO costs of the table items reported to the given collector to the
+    /// items themselves, rather than to the native functions loading them.
+    pub fn with_table_item_loads(mut self, loads: TableItemLoads) -> Self {
+        self.table_item_loads = Some(loads);
+        self
+    }
 }
 
 impl<G> GasProfiler<G>
@@ -125,7 +155,10 @@ where
         match &event {
             Loc(..) => (),
             Call(..) => unreachable!("call frames are handled separately"),
-            Bytecode { cost, .. } | CallNative { cost, .. } | LoadResource { cost, .. } => {
+            Bytecode { cost, .. }
+            | CallNative { cost, .. }
+            | LoadResource { cost, .. }
+            | LoadTableItem { cost, .. } => {
                 self.total_exec_io += *cost;
             },
         }
@@ -164,13 +197,6 @@ where
         fn charge_ld_const_after_deserialization(&mut self, val: impl ValueView)
             -> PartialVMResult<()>;
 
-        // Note: we don't use this to charge gas so no need to record anything.
-        fn charge_native_function_before_execution(
-            &mut self,
-            ty_args: impl ExactSizeIterator<Item = impl TypeView> + Clone,
-            args: impl ExactSizeIterator<Item = impl ValueView> + Clone,
-        ) -> PartialVMResult<()>;
-
         // Note: we don't use this to charge gas so no need to record anything.
         fn charge_drop_frame(
             &mut self,
@@ -313,12 +339,27 @@ where
         self.base.balance_internal()
     }
 
+    fn charge_native_function_before_execution(
+        &mut self,
+        ty_args: impl ExactSizeIterator<Item = impl TypeView> + Clone,
+        args: impl ExactSizeIterator<Item = impl ValueView> + Clone,
+    ) -> PartialVMResult<()> {
+        // Note: we don't use this to charge gas, but drop the loads reported outside of
+        // the profiled native calls (e.g. in the prologue) so they don't get misattributed.
+        if let Some(loads) = &self.table_item_loads {
+            loads.take();
+        }
+
+        self.base
+            .charge_native_function_before_execution(ty_args, args)
+    }
+
     fn charge_native_function(
         &mut self,
         amount: InternalGas,
         ret_vals: Option<impl ExactSizeIterator<Item = impl ValueView> + Clone>,
     ) -> PartialVMResult<()> {
-        let (cost, res) =
+        let (mut cost, res) =
             self.delegate_charge(|base| base.charge_native_function(amount, ret_vals));
 
         let cur = self.frames.pop().expect("frame must exist");
@@ -331,6 +372,24 @@ where
             FrameName::Script => unreachable!(),
         };
 
+        // Split the costs of loading table items off the native call. If the native call
+        // ran out of gas, only the part of the costs that has actually been charged counts.
+        let loads = self
+            .table_item_loads
+            .as_ref()
+            .map(TableItemLoads::take)
+            .unwrap_or_default();
+        for (key, load_cost) in loads {
+            let load_cost = std::cmp::min(load_cost, cost);
+            cost = cost
+                .checked_sub(load_cost)
+                .expect("load cost must not exceed the cost of the native call");
+            self.record_gas_event(ExecutionGasEvent::LoadTableItem {
+                key,
+                cost: load_cost,
+            });
+        }
+
         self.record_gas_event(ExecutionGasEvent::CallNative {
             module_id,
             fn_name: name,
@@ -571,7 +630,8 @@ where
             write_set_storage.push(WriteStorage {
                 key: key.clone(),
                 op_type: write_op_type(op),
-                cost: fee,
+                slot_fee,
+                bytes_fee,
                 refund: slot_refund,
             });
             // TODO(gas): track storage refund in the profiler
@@ -595,7 +655,8 @@ where
             write_set_storage.push(WriteStorage {
                 key: key.clone(),
                 op_type: write_op_type(group_write.metadata_op()),
-                cost: fee,
+                slot_fee,
+                bytes_fee,
                 refund,
             });
 
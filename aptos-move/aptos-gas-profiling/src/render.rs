@@ -122,6 +122,21 @@ impl<'a> Display for Render<'a, StateKey> {
     }
 }
 
+/// Returns what kind of state item the key refers to.
+pub(crate) fn state_key_kind(key: &StateKey) -> &'static str {
+    use StateKeyInner::*;
+
+    match key.deref() {
+        AccessPath(ap) => match ap.get_path() {
+            Path::Code(..) => "module",
+            Path::Resource(..) => "resource",
+            Path::ResourceGroup(..) => "resource group",
+        },
+        TableItem { .. } => "table item",
+        Raw(..) => panic!("not supported"),
+    }
+}
+
 impl<'a> Display for Render<'a, WriteOpType> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         use WriteOpType::*;
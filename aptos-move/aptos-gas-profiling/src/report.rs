@@ -1,7 +1,10 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{log::TransactionGasLog, render::Render};
+use crate::{
+    log::TransactionGasLog,
+    render::{state_key_kind, Render},
+};
 use anyhow::Result;
 use aptos_gas_algebra::{Fee, InternalGas};
 use handlebars::Handlebars;
@@ -13,6 +16,8 @@ use std::{
 };
 
 const TEMPLATE: &str = include_str!("../templates/index.html");
+/// The number of state items listed as the top storage consumers.
+const TOP_STORAGE_CONSUMERS: usize = 20;
 
 fn ensure_dirs_exist(path: impl AsRef<Path>) -> Result<()> {
     if let Err(err) = fs::create_dir_all(&path) {
@@ -202,8 +207,8 @@ impl TransactionGasLog {
 
                         json!({
                             "name":  format!("{}", Render(&write.key)),
-                            "cost": fmt_storage_fee(write.cost),
-                            "cost-percentage": fmt_storage_fee_percentage(write.cost),
+                            "cost": fmt_storage_fee(write.cost()),
+                            "cost-percentage": fmt_storage_fee_percentage(write.cost()),
                             "refund": refund_scaled,
                             "refund-percentage": refund_percentage
                         })
@@ -240,6 +245,32 @@ impl TransactionGasLog {
             );
         }
 
+        // IO costs and storage fees & refunds by state item
+        let fmt_gas = |cost: InternalGas| -> String {
+            let scaled = format!("{:.8}", (u64::from(cost) as f64 / scaling_factor));
+            crate::misc::strip_trailing_zeros_and_decimal_point(&scaled).to_string()
+        };
+        data.insert(
+            "storage-consumers".to_string(),
+            Value::Array(
+                self.aggregate_state_key_costs()
+                    .into_iter()
+                    .take(TOP_STORAGE_CONSUMERS)
+                    .map(|item| {
+                        json!({
+                            "kind": state_key_kind(&item.key),
+                            "name": format!("{}", Render(&item.key)),
+                            "io-read": fmt_gas(item.io_read),
+                            "io-write": fmt_gas(item.io_write),
+                            "slot-fee": fmt_storage_fee(item.slot_fee),
+                            "bytes-fee": fmt_storage_fee(item.bytes_fee),
+                            "refund": fmt_storage_fee(item.refund),
+                        })
+                    })
+                    .collect(),
+            ),
+        );
+
         // Execution trace
         let mut tree = self.exec_io.to_erased().tree;
         tree.include_child_costs();
@@ -66,6 +66,21 @@ macro_rules! define_gas_parameters {
                     $($name: 0.into()),*
                 }
             }
+
+            /// Returns the on-chain keys of the parameters at the given feature version, each
+            /// paired with the name of the type representing the parameter in gas expressions.
+            #[allow(unused)]
+            pub fn keys_by_expression_name(feature_version: u64) -> Vec<(String, String)> {
+                let mut output = vec![];
+
+                $(
+                    if let Some(key) = $crate::gas_schedule::macros::define_gas_parameters_extract_key_at_version!($key_bindings, feature_version) {
+                        output.push((stringify!($name).to_uppercase(), format!("{}.{}", $prefix, key)))
+                    }
+                )*
+
+                output
+            }
         }
 
         impl $crate::traits::InitialGasSchedule for $params_name {
@@ -71,6 +71,26 @@ impl AptosGasParameters {
             natives: NativeGasParameters::zeros(),
         }
     }
+
+    /// Returns the on-chain keys of all parameters at the given feature version, by the names
+    /// of the types representing them in gas expressions, e.g. `ADD` => `instr.add`.
+    pub fn keys_by_expression_name(feature_version: u64) -> BTreeMap<String, String> {
+        let mut keys = AbstractValueSizeGasParameters::keys_by_expression_name(feature_version);
+        keys.extend(InstructionGasParameters::keys_by_expression_name(
+            feature_version,
+        ));
+        keys.extend(TransactionGasParameters::keys_by_expression_name(
+            feature_version,
+        ));
+        keys.extend(MoveStdlibGasParameters::keys_by_expression_name(
+            feature_version,
+        ));
+        keys.extend(TableGasParameters::keys_by_expression_name(feature_version));
+        keys.extend(AptosFrameworkGasParameters::keys_by_expression_name(
+            feature_version,
+        ));
+        keys.into_iter().collect()
+    }
 }
 
 impl InitialGasSchedule for AptosGasParameters {
@@ -93,6 +93,9 @@ pub enum FeatureFlag {
     VMBinaryFormatV7,
     ResourceGroupsChargeAsSizeSum,
     CommissionChangeDelegationPool,
+    Bn254Structures,
+    WebAuthnSignature,
+    PoseidonNatives,
 }
 
 fn generate_features_blob(writer: &CodeWriter, data: &[u64]) {
@@ -244,6 +247,9 @@ impl From<FeatureFlag> for AptosFeatureFlag {
             FeatureFlag::CommissionChangeDelegationPool => {
                 AptosFeatureFlag::COMMISSION_CHANGE_DELEGATION_POOL
             },
+            FeatureFlag::Bn254Structures => AptosFeatureFlag::BN254_STRUCTURES,
+            FeatureFlag::WebAuthnSignature => AptosFeatureFlag::WEBAUTHN_SIGNATURE,
+            FeatureFlag::PoseidonNatives => AptosFeatureFlag::POSEIDON_NATIVES,
         }
     }
 }
@@ -318,6 +324,9 @@ impl From<AptosFeatureFlag> for FeatureFlag {
             AptosFeatureFlag::COMMISSION_CHANGE_DELEGATION_POOL => {
                 FeatureFlag::CommissionChangeDelegationPool
             },
+            AptosFeatureFlag::BN254_STRUCTURES => FeatureFlag::Bn254Structures,
+            AptosFeatureFlag::WEBAUTHN_SIGNATURE => FeatureFlag::WebAuthnSignature,
+            AptosFeatureFlag::POSEIDON_NATIVES => FeatureFlag::PoseidonNatives,
         }
     }
 }
@@ -2,9 +2,34 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::utils::*;
-use anyhow::Result;
+use anyhow::{ensure, Result};
 use aptos_types::on_chain_config::GasScheduleV2;
 use move_model::{code_writer::CodeWriter, emit, emitln, model::Loc};
+use std::collections::BTreeMap;
+
+/// Returns the gas schedule with the entries in `changes` set to their new values. All of them
+/// must already be in the gas schedule.
+pub fn apply_gas_schedule_changes(
+    mut gas_schedule: GasScheduleV2,
+    changes: &BTreeMap<String, u64>,
+) -> Result<GasScheduleV2> {
+    let unknown_keys: Vec<_> = changes
+        .keys()
+        .filter(|key| !gas_schedule.entries.iter().any(|(name, _)| name == *key))
+        .collect();
+    ensure!(
+        unknown_keys.is_empty(),
+        "Gas schedule entries {:?} are not in the gas schedule",
+        unknown_keys
+    );
+
+    for (name, val) in gas_schedule.entries.iter_mut() {
+        if let Some(new_val) = changes.get(name) {
+            *val = *new_val;
+        }
+    }
+    Ok(gas_schedule)
+}
 
 pub fn generate_gas_upgrade_proposal(
     gas_schedule: &GasScheduleV2,
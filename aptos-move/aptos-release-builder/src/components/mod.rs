@@ -18,7 +18,7 @@ use futures::executor::block_on;
 use handlebars::Handlebars;
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     fs::File,
     io::{Read, Write},
     path::{Path, PathBuf},
@@ -59,6 +59,7 @@ impl Proposal {
                 },
                 ReleaseEntry::Framework(_)
                 | ReleaseEntry::CustomGas(_)
+                | ReleaseEntry::GasScheduleChanges(_)
                 | ReleaseEntry::DefaultGas
                 | ReleaseEntry::Version(_)
                 | ReleaseEntry::Consensus(_)
@@ -99,6 +100,9 @@ pub enum ExecutionMode {
 pub enum ReleaseEntry {
     Framework(FrameworkReleaseConfig),
     CustomGas(GasScheduleV2),
+    /// New values of some entries of the gas schedule on chain, by key. The other entries keep
+    /// their values.
+    GasScheduleChanges(BTreeMap<String, u64>),
     DefaultGas,
     Version(Version),
     FeatureFlag(Features),
@@ -146,6 +150,25 @@ impl ReleaseEntry {
                     )?);
                 }
             },
+            ReleaseEntry::GasScheduleChanges(changes) => {
+                let client = client.ok_or_else(|| {
+                    anyhow!("Changing the gas schedule on chain requires a remote endpoint")
+                })?;
+                let current_gas_schedule = fetch_config::<GasScheduleV2>(client)?;
+                let gas_schedule =
+                    gas::apply_gas_schedule_changes(current_gas_schedule.clone(), changes)?;
+                if gas_schedule != current_gas_schedule {
+                    result.append(&mut gas::generate_gas_upgrade_proposal(
+                        &gas_schedule,
+                        is_testnet,
+                        if is_multi_step {
+                            get_execution_hash(result)
+                        } else {
+                            "".to_owned().into_bytes()
+                        },
+                    )?);
+                }
+            },
             ReleaseEntry::DefaultGas => {
                 let gas_schedule = aptos_gas_schedule_updator::current_gas_schedule();
                 if !fetch_and_equals::<GasScheduleV2>(client, &gas_schedule)? {
@@ -284,6 +307,15 @@ impl ReleaseEntry {
                     bail!("Gas schedule config mismatch: Expected {:?}", gas_schedule);
                 }
             },
+            ReleaseEntry::GasScheduleChanges(changes) => {
+                let gas_schedule = fetch_config::<GasScheduleV2>(client)?;
+                if gas::apply_gas_schedule_changes(gas_schedule.clone(), changes)? != gas_schedule {
+                    bail!(
+                        "Gas schedule config mismatch: Expected changes {:?}",
+                        changes
+                    );
+                }
+            },
             ReleaseEntry::DefaultGas => {
                 if !fetch_and_equals(
                     client_opt,
@@ -8,6 +8,7 @@ use crate::{
     counters::*,
     data_cache::{AsMoveResolver, StorageAdapter},
     errors::expect_only_successful_execution,
+    execution_trace::{ExecutionTrace, ExecutionTraceRecorder},
     move_vm_ext::{
         get_max_binary_format_version, AptosMoveResolver, RespawnedSession, SessionExt, SessionId,
     },
@@ -26,6 +27,7 @@ use aptos_gas_schedule::VMGasParameters;
 use aptos_logger::{enabled, prelude::*, Level};
 use aptos_memory_usage_tracker::MemoryTrackedGasMeter;
 use aptos_state_view::StateView;
+use aptos_table_natives::TableItemLoadHook;
 use aptos_types::{
     account_config,
     account_config::new_block_event_key,
@@ -35,6 +37,7 @@ use aptos_types::{
     on_chain_config::{new_epoch_event_key, FeatureFlag, TimedFeatureOverride},
     system_txn::SystemTransaction,
     transaction::{
+        authenticator::AccountAuthenticator,
         signature_verified_transaction::SignatureVerifiedTransaction,
         EntryFunction, ExecutionError, ExecutionStatus, ModuleBundle, Multisig,
         MultisigTransactionPayload, SignatureCheckedTransaction, SignedTransaction, Transaction,
@@ -73,7 +76,10 @@ use move_core_types::{
     value::{serialize_values, MoveValue},
     vm_status::StatusType,
 };
-use move_vm_runtime::session::SerializedReturnValues;
+use move_vm_runtime::{
+    execution_tracer::{share_tracer, SharedExecutionTracer},
+    session::SerializedReturnValues,
+};
 use move_vm_types::gas::UnmeteredGasMeter;
 use num_cpus;
 use once_cell::sync::{Lazy, OnceCell};
@@ -92,6 +98,8 @@ static NUM_EXECUTION_SHARD: OnceCell<usize> = OnceCell::new();
 static NUM_PROOF_READING_THREADS: OnceCell<usize> = OnceCell::new();
 static PARANOID_TYPE_CHECKS: OnceCell<bool> = OnceCell::new();
 static PROCESSED_TRANSACTIONS_DETAILED_COUNTERS: OnceCell<bool> = OnceCell::new();
+static CONFLICT_ANALYTICS_TOP_N: OnceCell<usize> = OnceCell::new();
+static RECORD_READ_SETS: OnceCell<bool> = OnceCell::new();
 static TIMED_FEATURE_OVERRIDE: OnceCell<TimedFeatureOverride> = OnceCell::new();
 
 // TODO: Don't expose this in AptosVM, and use only in BlockAptosVM!
@@ -148,6 +156,18 @@ impl AptosVM {
         self
     }
 
+    /// Traces the execution of all sessions of this VM with the given tracer.
+    pub fn with_execution_tracer(mut self, tracer: SharedExecutionTracer) -> Self {
+        self.vm_impl.set_execution_tracer(tracer);
+        self
+    }
+
+    /// Reports the table items loaded in all sessions of this VM to the given hook.
+    pub fn with_table_item_load_hook(mut self, hook: TableItemLoadHook) -> Self {
+        self.vm_impl.set_table_item_load_hook(hook);
+        self
+    }
+
     /// Sets execution concurrency level when invoked the first time.
     pub fn set_concurrency_level_once(mut concurrency_level: usize) {
         concurrency_level = min(concurrency_level, num_cpus::get());
@@ -233,6 +253,30 @@ impl AptosVM {
         }
     }
 
+    /// Enables Block-STM conflict analytics, exporting the given number of hottest keys of
+    /// each block as metrics, when invoked the first time.
+    pub fn set_conflict_analytics_top_n_once(top_n: usize) {
+        // Only the first call succeeds, due to OnceCell semantics.
+        CONFLICT_ANALYTICS_TOP_N.set(top_n).ok();
+    }
+
+    /// Get the number of hot keys to export if conflict analytics are enabled.
+    pub fn get_conflict_analytics_top_n() -> Option<usize> {
+        CONFLICT_ANALYTICS_TOP_N.get().copied()
+    }
+
+    /// Enables recording the keys read by each transaction in its output, when invoked the
+    /// first time.
+    pub fn set_record_read_sets_once() {
+        // Only the first call succeeds, due to OnceCell semantics.
+        RECORD_READ_SETS.set(true).ok();
+    }
+
+    /// Get whether the keys read by each transaction are recorded in its output.
+    pub fn get_record_read_sets() -> bool {
+        RECORD_READ_SETS.get().copied().unwrap_or(false)
+    }
+
     pub fn internals(&self) -> AptosVMInternals {
         AptosVMInternals::new(&self.vm_impl)
     }
@@ -1155,6 +1199,19 @@ impl AptosVM {
         transaction: &SignedTransaction,
         log_context: &AdapterLogSchema,
     ) -> Result<(), VMStatus> {
+        if !self
+            .vm_impl
+            .get_features()
+            .is_enabled(FeatureFlag::WEBAUTHN_SIGNATURE)
+            && transaction
+                .authenticator_ref()
+                .all_signers()
+                .iter()
+                .any(AccountAuthenticator::has_webauthn_signature)
+        {
+            return Err(VMStatus::error(StatusCode::FEATURE_UNDER_GATING, None));
+        }
+
         // Check transaction format.
         if transaction.contains_duplicate_signers() {
             return Err(VMStatus::error(
@@ -1545,9 +1602,33 @@ impl AptosVM {
     pub fn simulate_signed_transaction(
         txn: &SignedTransaction,
         state_view: &impl StateView,
+    ) -> (VMStatus, TransactionOutput) {
+        Self::simulate_signed_transaction_impl(txn, state_view, None)
+    }
+
+    /// Same as `simulate_signed_transaction`, but also returns the trace of the execution.
+    pub fn simulate_signed_transaction_with_trace(
+        txn: &SignedTransaction,
+        state_view: &impl StateView,
+    ) -> (VMStatus, TransactionOutput, ExecutionTrace) {
+        let recorder = share_tracer(ExecutionTraceRecorder::new());
+        let (vm_status, output) =
+            Self::simulate_signed_transaction_impl(txn, state_view, Some(recorder.clone()));
+        let mut recorder = recorder.lock();
+        recorder.record_output(&output);
+        (vm_status, output, recorder.take_trace())
+    }
+
+    fn simulate_signed_transaction_impl(
+        txn: &SignedTransaction,
+        state_view: &impl StateView,
+        tracer: Option<SharedExecutionTracer>,
     ) -> (VMStatus, TransactionOutput) {
         let resolver = state_view.as_move_resolver();
-        let vm = AptosVM::new(&resolver).for_simulation();
+        let mut vm = AptosVM::new(&resolver).for_simulation();
+        if let Some(tracer) = tracer {
+            vm = vm.with_execution_tracer(tracer);
+        }
         let simulation_vm = AptosSimulationVM(vm);
         let log_context = AdapterLogSchema::new(state_view.id(), 0);
 
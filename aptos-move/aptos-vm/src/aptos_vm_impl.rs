@@ -23,6 +23,7 @@ use aptos_gas_schedule::{
 use aptos_logger::{enabled, prelude::*, Level};
 use aptos_metrics_core::TimerHelper;
 use aptos_state_view::StateViewId;
+use aptos_table_natives::TableItemLoadHook;
 use aptos_types::{
     account_config::CORE_CODE_ADDRESS,
     chain_id::ChainId,
@@ -46,7 +47,9 @@ use move_core_types::{
     language_storage::ModuleId,
     value::{serialize_values, MoveValue},
 };
-use move_vm_runtime::logging::expect_no_verification_errors;
+use move_vm_runtime::{
+    execution_tracer::SharedExecutionTracer, logging::expect_no_verification_errors,
+};
 use move_vm_types::gas::UnmeteredGasMeter;
 use std::sync::Arc;
 
@@ -176,6 +179,14 @@ impl AptosVMImpl {
         }
     }
 
+    pub(crate) fn set_execution_tracer(&mut self, tracer: SharedExecutionTracer) {
+        self.move_vm.set_execution_tracer(tracer);
+    }
+
+    pub(crate) fn set_table_item_load_hook(&mut self, hook: TableItemLoadHook) {
+        self.move_vm.set_table_item_load_hook(hook);
+    }
+
     pub(crate) fn mark_loader_cache_as_invalid(&self) {
         self.move_vm.mark_loader_cache_as_invalid();
     }
@@ -7,12 +7,13 @@ pub(crate) mod vm_wrapper;
 use crate::{
     block_executor::vm_wrapper::AptosExecutorTask,
     counters::{BLOCK_EXECUTOR_CONCURRENCY, BLOCK_EXECUTOR_EXECUTE_BLOCK_SECONDS},
+    AptosVM,
 };
 use aptos_aggregator::{
     delayed_change::DelayedChange, delta_change_set::DeltaOp, types::DelayedFieldID,
 };
 use aptos_block_executor::{
-    errors::Error, executor::BlockExecutor,
+    conflict_analytics::BlockConflictReport, errors::Error, executor::BlockExecutor,
     task::TransactionOutput as BlockExecutorTransactionOutput,
     txn_commit_hook::TransactionCommitHook,
 };
@@ -34,7 +35,10 @@ use aptos_vm_types::output::VMOutput;
 use move_core_types::{language_storage::StructTag, value::MoveTypeLayout, vm_status::VMStatus};
 use once_cell::sync::OnceCell;
 use rayon::ThreadPool;
-use std::{collections::BTreeMap, sync::Arc};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    sync::Arc,
+};
 
 /// Output type wrapper used by block executor. VM output is stored first, then
 /// transformed into TransactionOutput type that is returned.
@@ -43,6 +47,7 @@ pub struct AptosTransactionOutput {
     // Note: should these mutexes be changed to ExplicitSyncSwapper?
     vm_output: Mutex<Option<VMOutput>>,
     committed_output: OnceCell<TransactionOutput>,
+    read_set: Mutex<BTreeSet<StateKey>>,
 }
 
 impl AptosTransactionOutput {
@@ -50,9 +55,14 @@ impl AptosTransactionOutput {
         Self {
             vm_output: Mutex::new(Some(output)),
             committed_output: OnceCell::new(),
+            read_set: Mutex::new(BTreeSet::new()),
         }
     }
 
+    fn take_read_set(&self) -> BTreeSet<StateKey> {
+        std::mem::take(&mut *self.read_set.lock())
+    }
+
     pub(crate) fn committed_output(&self) -> &TransactionOutput {
         self.committed_output.get().unwrap()
     }
@@ -72,7 +82,8 @@ impl AptosTransactionOutput {
                     BTreeMap::new(),
                     vec![],
                     vec![],
-                ),
+                )
+                .with_read_set(self.take_read_set()),
         }
     }
 }
@@ -86,6 +97,10 @@ impl BlockExecutorTransactionOutput for AptosTransactionOutput {
         Self::new(VMOutput::empty_with_status(TransactionStatus::Retry))
     }
 
+    fn set_read_set(&self, read_set: HashSet<StateKey>) {
+        *self.read_set.lock() = read_set.into_iter().collect();
+    }
+
     // TODO: get rid of the cloning data-structures in the following APIs.
 
     /// Should never be called after incorporating materialized output, as that consumes vm_output.
@@ -258,7 +273,8 @@ impl BlockExecutorTransactionOutput for AptosTransactionOutput {
                             patched_resource_write_set,
                             patched_events,
                             serialized_groups,
-                        ),
+                        )
+                        .with_read_set(self.take_read_set()),
                 )
                 .is_ok(),
             "Could not combine VMOutput with the patched resource and event data"
@@ -274,7 +290,8 @@ impl BlockExecutorTransactionOutput for AptosTransactionOutput {
                         .take()
                         .expect("Output must be set to incorporate materialized data")
                         .into_transaction_output()
-                        .expect("We should be able to always convert to transaction output"),
+                        .expect("We should be able to always convert to transaction output")
+                        .with_read_set(self.take_read_set()),
                 )
                 .is_ok(),
             "Could not combine VMOutput with the patched resource and event data"
@@ -293,6 +310,9 @@ impl BlockExecutorTransactionOutput for AptosTransactionOutput {
     }
 }
 
+/// The conflicts observed while executing a block of Aptos transactions in parallel.
+pub type AptosBlockConflictReport = BlockConflictReport<StateKey, DelayedFieldID>;
+
 pub struct BlockAptosVM();
 
 impl BlockAptosVM {
@@ -307,6 +327,55 @@ impl BlockAptosVM {
         maybe_block_gas_limit: Option<u64>,
         transaction_commit_listener: Option<L>,
     ) -> Result<Vec<TransactionOutput>, VMStatus> {
+        Self::execute_block_impl(
+            executor_thread_pool,
+            signature_verified_block,
+            state_view,
+            concurrency_level,
+            maybe_block_gas_limit,
+            transaction_commit_listener,
+            AptosVM::get_conflict_analytics_top_n(),
+        )
+        .map(|(outputs, _)| outputs)
+    }
+
+    /// Same as `execute_block`, but always records the conflicts between transactions and
+    /// returns them, if the block was executed in parallel.
+    pub fn execute_block_with_conflict_report<
+        S: StateView + Sync,
+        L: TransactionCommitHook<Output = AptosTransactionOutput>,
+    >(
+        executor_thread_pool: Arc<ThreadPool>,
+        signature_verified_block: &[SignatureVerifiedTransaction],
+        state_view: &S,
+        concurrency_level: usize,
+        maybe_block_gas_limit: Option<u64>,
+        transaction_commit_listener: Option<L>,
+        hot_keys_top_n: usize,
+    ) -> Result<(Vec<TransactionOutput>, Option<AptosBlockConflictReport>), VMStatus> {
+        Self::execute_block_impl(
+            executor_thread_pool,
+            signature_verified_block,
+            state_view,
+            concurrency_level,
+            maybe_block_gas_limit,
+            transaction_commit_listener,
+            Some(hot_keys_top_n),
+        )
+    }
+
+    fn execute_block_impl<
+        S: StateView + Sync,
+        L: TransactionCommitHook<Output = AptosTransactionOutput>,
+    >(
+        executor_thread_pool: Arc<ThreadPool>,
+        signature_verified_block: &[SignatureVerifiedTransaction],
+        state_view: &S,
+        concurrency_level: usize,
+        maybe_block_gas_limit: Option<u64>,
+        transaction_commit_listener: Option<L>,
+        conflict_analytics_top_n: Option<usize>,
+    ) -> Result<(Vec<TransactionOutput>, Option<AptosBlockConflictReport>), VMStatus> {
         let _timer = BLOCK_EXECUTOR_EXECUTE_BLOCK_SECONDS.start_timer();
         let num_txns = signature_verified_block.len();
         if state_view.id() != StateViewId::Miscellaneous {
@@ -316,7 +385,7 @@ impl BlockAptosVM {
         }
 
         BLOCK_EXECUTOR_CONCURRENCY.set(concurrency_level as i64);
-        let executor = BlockExecutor::<
+        let mut executor = BlockExecutor::<
             SignatureVerifiedTransaction,
             AptosExecutorTask<S>,
             S,
@@ -328,8 +397,18 @@ impl BlockAptosVM {
             maybe_block_gas_limit,
             transaction_commit_listener,
         );
+        if let Some(top_n) = conflict_analytics_top_n {
+            executor = executor.with_conflict_analytics(top_n);
+        }
+        if AptosVM::get_record_read_sets() {
+            executor = executor.with_read_set_recording();
+        }
 
-        let ret = executor.execute_block(state_view, signature_verified_block, state_view);
+        let (ret, conflict_report) = executor.execute_block_with_conflict_report(
+            state_view,
+            signature_verified_block,
+            state_view,
+        );
         match ret {
             Ok(outputs) => {
                 let output_vec: Vec<TransactionOutput> = outputs
@@ -346,7 +425,7 @@ impl BlockAptosVM {
                     flush_speculative_logs(pos);
                 }
 
-                Ok(output_vec)
+                Ok((output_vec, conflict_report))
             },
             Err(Error::FallbackToSequential(e)) => {
                 unreachable!(
@@ -0,0 +1,364 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Recording of transaction execution traces, and their export as JSON or in the Chrome trace
+//! event format (which can be opened in `chrome://tracing` or Perfetto).
+//!
+//! An [`ExecutionTraceRecorder`] is registered as the [`ExecutionTracer`] of all sessions of an
+//! [`AptosVM`](crate::AptosVM) via `AptosVM::with_execution_tracer`. It records Move function
+//! calls and returns, native calls with their arguments, and resources read from storage while
+//! the transaction executes. The resources written and events emitted are added from the output
+//! once the transaction has finished.
+
+use aptos_types::{
+    access_path::AccessPath, contract_event::ContractEvent, state_store::state_key::StateKey,
+    transaction::TransactionOutput, write_set::WriteOp,
+};
+use move_core_types::{
+    account_address::AccountAddress,
+    language_storage::{ModuleId, StructTag, TypeTag},
+};
+use move_vm_runtime::execution_tracer::ExecutionTracer;
+use serde::{Serialize, Serializer};
+use serde_json::{json, Value};
+use std::{fmt::Debug, time::Instant};
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TraceEventKind {
+    Call {
+        function: String,
+        ty_args: Vec<String>,
+    },
+    Return {
+        function: String,
+    },
+    NativeCall {
+        function: String,
+        ty_args: Vec<String>,
+        args: Vec<String>,
+    },
+    ResourceRead {
+        #[serde(serialize_with = "serialize_debug")]
+        state_key: StateKey,
+        exists: bool,
+        bytes: u64,
+    },
+    ResourceWrite {
+        #[serde(serialize_with = "serialize_debug")]
+        state_key: StateKey,
+        op: WriteOpKind,
+        bytes: u64,
+    },
+    Event {
+        type_tag: String,
+        data: String,
+    },
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WriteOpKind {
+    Creation,
+    Modification,
+    Deletion,
+}
+
+impl From<&WriteOp> for WriteOpKind {
+    fn from(op: &WriteOp) -> Self {
+        match op {
+            WriteOp::Creation(_) | WriteOp::CreationWithMetadata { .. } => Self::Creation,
+            WriteOp::Modification(_) | WriteOp::ModificationWithMetadata { .. } => {
+                Self::Modification
+            },
+            WriteOp::Deletion | WriteOp::DeletionWithMetadata { .. } => Self::Deletion,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TraceEvent {
+    /// Nanoseconds since the recording started.
+    pub elapsed_ns: u64,
+    #[serde(flatten)]
+    pub kind: TraceEventKind,
+}
+
+/// The events recorded while executing a transaction, in order.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ExecutionTrace {
+    pub events: Vec<TraceEvent>,
+}
+
+impl ExecutionTrace {
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Exports the trace in the Chrome trace event format. Function calls become duration events,
+    /// everything else becomes instant events. Calls that did not return, e.g. because the
+    /// transaction aborted, end with the last event.
+    pub fn to_chrome_trace(&self) -> serde_json::Result<String> {
+        let mut trace_events = vec![];
+        let mut open_frames = vec![];
+        for event in &self.events {
+            let ts = event.elapsed_ns as f64 / 1000.0;
+            let trace_event = match &event.kind {
+                TraceEventKind::Call { function, ty_args } => {
+                    open_frames.push(function.clone());
+                    chrome_event(function, "call", "B", ts, json!({ "ty_args": ty_args }))
+                },
+                TraceEventKind::Return { function } => {
+                    open_frames.pop();
+                    chrome_event(function, "call", "E", ts, json!({}))
+                },
+                TraceEventKind::NativeCall {
+                    function,
+                    ty_args,
+                    args,
+                } => chrome_event(
+                    function,
+                    "native",
+                    "i",
+                    ts,
+                    json!({ "ty_args": ty_args, "args": args }),
+                ),
+                TraceEventKind::ResourceRead {
+                    state_key,
+                    exists,
+                    bytes,
+                } => chrome_event(
+                    "read",
+                    "storage",
+                    "i",
+                    ts,
+                    json!({
+                        "state_key": format!("{:?}", state_key),
+                        "exists": exists,
+                        "bytes": bytes,
+                    }),
+                ),
+                TraceEventKind::ResourceWrite {
+                    state_key,
+                    op,
+                    bytes,
+                } => chrome_event(
+                    "write",
+                    "storage",
+                    "i",
+                    ts,
+                    json!({
+                        "state_key": format!("{:?}", state_key),
+                        "op": op,
+                        "bytes": bytes,
+                    }),
+                ),
+                TraceEventKind::Event { type_tag, data } => {
+                    chrome_event(type_tag, "event", "i", ts, json!({ "data": data }))
+                },
+            };
+            trace_events.push(trace_event);
+        }
+
+        let end_ts = self
+            .events
+            .last()
+            .map_or(0.0, |event| event.elapsed_ns as f64 / 1000.0);
+        while let Some(function) = open_frames.pop() {
+            trace_events.push(chrome_event(&function, "call", "E", end_ts, json!({})));
+        }
+
+        serde_json::to_string(&json!({ "traceEvents": trace_events }))
+    }
+}
+
+/// An event in the Chrome trace event format. Instant events are scoped to the thread.
+fn chrome_event(name: &str, category: &str, phase: &str, ts: f64, args: Value) -> Value {
+    let mut event = json!({
+        "name": name,
+        "cat": category,
+        "ph": phase,
+        "ts": ts,
+        "pid": 0,
+        "tid": 0,
+        "args": args,
+    });
+    if phase == "i" {
+        event["s"] = json!("t");
+    }
+    event
+}
+
+/// Records an [`ExecutionTrace`] as the tracer of the sessions of a transaction.
+pub struct ExecutionTraceRecorder {
+    start: Instant,
+    trace: ExecutionTrace,
+}
+
+impl ExecutionTraceRecorder {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            trace: ExecutionTrace::default(),
+        }
+    }
+
+    fn record(&mut self, kind: TraceEventKind) {
+        self.trace.events.push(TraceEvent {
+            elapsed_ns: self.start.elapsed().as_nanos() as u64,
+            kind,
+        });
+    }
+
+    /// Records the resources written and the events emitted by the executed transaction.
+    pub fn record_output(&mut self, output: &TransactionOutput) {
+        for (state_key, op) in output.write_set() {
+            self.record(TraceEventKind::ResourceWrite {
+                state_key: state_key.clone(),
+                op: op.into(),
+                bytes: op.bytes().map_or(0, |bytes| bytes.len() as u64),
+            });
+        }
+        for event in output.events() {
+            self.record(Self::event_kind(event));
+        }
+    }
+
+    fn event_kind(event: &ContractEvent) -> TraceEventKind {
+        TraceEventKind::Event {
+            type_tag: event.type_tag().to_string(),
+            data: format!("0x{}", hex::encode(event.event_data())),
+        }
+    }
+
+    /// Returns the trace recorded so far, and starts a new one.
+    pub fn take_trace(&mut self) -> ExecutionTrace {
+        self.start = Instant::now();
+        std::mem::take(&mut self.trace)
+    }
+}
+
+impl Default for ExecutionTraceRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn function_name(module_id: Option<&ModuleId>, function: &str) -> String {
+    match module_id {
+        Some(module_id) => format!("{}::{}", module_id.short_str_lossless(), function),
+        None => format!("script::{}", function),
+    }
+}
+
+fn type_arg_names(ty_args: &[TypeTag]) -> Vec<String> {
+    ty_args.iter().map(|ty_arg| ty_arg.to_string()).collect()
+}
+
+impl ExecutionTracer for ExecutionTraceRecorder {
+    fn on_call(&mut self, module_id: Option<&ModuleId>, function: &str, ty_args: &[TypeTag]) {
+        self.record(TraceEventKind::Call {
+            function: function_name(module_id, function),
+            ty_args: type_arg_names(ty_args),
+        });
+    }
+
+    fn on_return(&mut self, module_id: Option<&ModuleId>, function: &str) {
+        self.record(TraceEventKind::Return {
+            function: function_name(module_id, function),
+        });
+    }
+
+    fn on_native_call(
+        &mut self,
+        module_id: &ModuleId,
+        function: &str,
+        ty_args: &[TypeTag],
+        args: &[String],
+    ) {
+        self.record(TraceEventKind::NativeCall {
+            function: function_name(Some(module_id), function),
+            ty_args: type_arg_names(ty_args),
+            args: args.to_vec(),
+        });
+    }
+
+    fn on_resource_read(
+        &mut self,
+        address: AccountAddress,
+        struct_tag: &StructTag,
+        exists: bool,
+        bytes_loaded: u64,
+    ) {
+        // Resources in resource groups are reported under their own key rather than the key of
+        // the group they are stored in.
+        if let Ok(access_path) = AccessPath::resource_access_path(address, struct_tag.clone()) {
+            self.record(TraceEventKind::ResourceRead {
+                state_key: StateKey::access_path(access_path),
+                exists,
+                bytes: bytes_loaded,
+            });
+        }
+    }
+}
+
+fn serialize_debug<T: Debug, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format!("{:?}", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_core_types::identifier::Identifier;
+
+    fn module_id() -> ModuleId {
+        ModuleId::new(AccountAddress::ONE, Identifier::new("coin").unwrap())
+    }
+
+    #[test]
+    fn test_json_export() {
+        let mut recorder = ExecutionTraceRecorder::new();
+        recorder.on_call(Some(&module_id()), "transfer", &[TypeTag::U64]);
+        recorder.on_native_call(&module_id(), "balance", &[], &["42".to_string()]);
+        recorder.on_return(Some(&module_id()), "transfer");
+        let trace = recorder.take_trace();
+
+        let json: Value = serde_json::from_str(&trace.to_json().unwrap()).unwrap();
+        let events = json["events"].as_array().unwrap();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0]["kind"], "call");
+        assert_eq!(events[0]["function"], "0x1::coin::transfer");
+        assert_eq!(events[0]["ty_args"][0], "u64");
+        assert_eq!(events[1]["kind"], "native_call");
+        assert_eq!(events[1]["args"][0], "42");
+        assert_eq!(events[2]["kind"], "return");
+        assert!(recorder.take_trace().events.is_empty());
+    }
+
+    #[test]
+    fn test_chrome_trace_closes_open_frames() {
+        let mut recorder = ExecutionTraceRecorder::new();
+        recorder.on_call(None, "main", &[]);
+        recorder.on_call(Some(&module_id()), "transfer", &[]);
+        let trace = recorder.take_trace();
+
+        let json: Value = serde_json::from_str(&trace.to_chrome_trace().unwrap()).unwrap();
+        let phases = json["traceEvents"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|event| {
+                (
+                    event["ph"].as_str().unwrap().to_string(),
+                    event["name"].as_str().unwrap().to_string(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(phases, vec![
+            ("B".to_string(), "script::main".to_string()),
+            ("B".to_string(), "0x1::coin::transfer".to_string()),
+            ("E".to_string(), "0x1::coin::transfer".to_string()),
+            ("E".to_string(), "script::main".to_string()),
+        ]);
+    }
+}
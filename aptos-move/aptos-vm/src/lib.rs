@@ -113,6 +113,7 @@ pub mod aptos_vm;
 mod aptos_vm_impl;
 pub mod block_executor;
 mod errors;
+pub mod execution_trace;
 pub mod move_vm_ext;
 pub mod natives;
 pub mod sharded_block_executor;
@@ -13,7 +13,7 @@ use aptos_framework::natives::{
 use aptos_gas_algebra::DynamicExpression;
 use aptos_gas_schedule::{MiscGasParameters, NativeGasParameters};
 use aptos_native_interface::SafeNativeBuilder;
-use aptos_table_natives::NativeTableContext;
+use aptos_table_natives::{NativeTableContext, TableItemLoadHook};
 use aptos_types::on_chain_config::{FeatureFlag, Features, TimedFeatureFlag, TimedFeatures};
 use move_binary_format::{
     deserializer::DeserializerConfig,
@@ -23,7 +23,10 @@ use move_binary_format::{
 };
 use move_bytecode_verifier::VerifierConfig;
 use move_vm_runtime::{
-    config::VMConfig, move_vm::MoveVM, native_extensions::NativeContextExtensions,
+    config::VMConfig,
+    execution_tracer::{ExecutionTracerExtension, SharedExecutionTracer},
+    move_vm::MoveVM,
+    native_extensions::NativeContextExtensions,
 };
 use std::{ops::Deref, sync::Arc};
 
@@ -31,6 +34,10 @@ pub struct MoveVmExt {
     inner: MoveVM,
     chain_id: u8,
     features: Arc<Features>,
+    // The tracer of all sessions created by this VM, if tracing is enabled.
+    execution_tracer: Option<SharedExecutionTracer>,
+    // Reports the table items loaded in all sessions created by this VM, if set.
+    table_item_load_hook: Option<TableItemLoadHook>,
 }
 
 pub fn get_max_binary_format_version(
@@ -135,6 +142,8 @@ impl MoveVmExt {
             )?,
             chain_id,
             features: Arc::new(features),
+            execution_tracer: None,
+            table_item_load_hook: None,
         })
     }
 
@@ -184,6 +193,14 @@ impl MoveVmExt {
         )
     }
 
+    pub fn set_execution_tracer(&mut self, tracer: SharedExecutionTracer) {
+        self.execution_tracer = Some(tracer);
+    }
+
+    pub fn set_table_item_load_hook(&mut self, hook: TableItemLoadHook) {
+        self.table_item_load_hook = Some(hook);
+    }
+
     pub fn new_session<'r, S: AptosMoveResolver>(
         &self,
         resolver: &'r S,
@@ -196,7 +213,11 @@ impl MoveVmExt {
             .try_into()
             .expect("HashValue should convert to [u8; 32]");
 
-        extensions.add(NativeTableContext::new(txn_hash, resolver));
+        let table_context = NativeTableContext::new(txn_hash, resolver);
+        extensions.add(match &self.table_item_load_hook {
+            Some(hook) => table_context.with_load_hook(hook.clone()),
+            None => table_context,
+        });
         extensions.add(NativeRistrettoPointContext::new());
         extensions.add(AlgebraContext::new());
         extensions.add(NativeAggregatorContext::new(txn_hash, resolver, resolver));
@@ -228,6 +249,9 @@ impl MoveVmExt {
         extensions.add(NativeCodeContext::default());
         extensions.add(NativeStateStorageContext::new(resolver));
         extensions.add(NativeEventContext::default());
+        if let Some(tracer) = &self.execution_tracer {
+            extensions.add(ExecutionTracerExtension::new(tracer.clone()));
+        }
 
         // The VM code loader has bugs around module upgrade. After a module upgrade, the internal
         // cache needs to be flushed to work around those bugs.
@@ -0,0 +1,280 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Optional analytics of the conflicts observed during parallel execution of a block.
+//!
+//! When enabled, the executor records the reads that failed validation (together with the
+//! transaction whose write invalidated them, when known), the number of incarnations of each
+//! transaction and the time transactions spent waiting on read dependencies. At the end of the
+//! block, the records are assembled into a [`BlockConflictReport`], which is used to export the
+//! hottest keys as metrics and can be inspected offline, e.g. by replaying a block in the debugger.
+
+use aptos_infallible::Mutex;
+use aptos_mvhashmap::types::{Incarnation, TxnIndex};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::{self, Debug, Display},
+    hash::Hash,
+    sync::atomic::{AtomicU32, Ordering},
+    time::Duration,
+};
+
+/// A read of a key that failed validation.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct KeyConflict<K> {
+    pub key: K,
+    /// The transaction whose write (or estimate) invalidated the read, or None if the read was
+    /// invalidated by a value from storage or by a resolved aggregator delta.
+    pub writer: Option<TxnIndex>,
+}
+
+/// A failed validation of an incarnation, which led to it being aborted and re-executed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ValidationFailure<K, I> {
+    pub txn_idx: TxnIndex,
+    pub incarnation: Incarnation,
+    pub keys: Vec<KeyConflict<K>>,
+    pub delayed_fields: Vec<I>,
+}
+
+/// A transaction that was suspended while waiting for a lower transaction to finish executing.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct DependencyWait {
+    pub txn_idx: TxnIndex,
+    pub dep_txn_idx: TxnIndex,
+    pub duration: Duration,
+}
+
+/// Aggregated conflicts between a reading transaction and a lower, writing transaction.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ConflictEdge {
+    pub num_validation_failures: usize,
+    pub num_dependency_waits: usize,
+    pub total_wait: Duration,
+}
+
+/// The conflicts observed while executing a block in parallel.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BlockConflictReport<K, I> {
+    /// The number of incarnations that were executed, for each transaction of the block.
+    pub incarnations: Vec<u32>,
+    pub validation_failures: Vec<ValidationFailure<K, I>>,
+    pub dependency_waits: Vec<DependencyWait>,
+}
+
+impl<K: Clone + Eq + Hash + Ord, I: Clone + Eq + Hash + Ord> BlockConflictReport<K, I> {
+    /// The number of executions beyond the first incarnation of each transaction.
+    pub fn num_reexecutions(&self) -> u64 {
+        self.incarnations
+            .iter()
+            .map(|incarnations| incarnations.saturating_sub(1) as u64)
+            .sum()
+    }
+
+    pub fn total_dependency_wait(&self) -> Duration {
+        self.dependency_waits.iter().map(|wait| wait.duration).sum()
+    }
+
+    /// Returns up to `n` keys involved in the most failed validations, most conflicting first.
+    pub fn hot_keys(&self, n: usize) -> Vec<(K, usize)> {
+        top_n(
+            self.validation_failures
+                .iter()
+                .flat_map(|failure| failure.keys.iter().map(|conflict| &conflict.key)),
+            n,
+        )
+    }
+
+    /// Returns up to `n` delayed fields involved in the most failed validations.
+    pub fn hot_delayed_fields(&self, n: usize) -> Vec<(I, usize)> {
+        top_n(
+            self.validation_failures
+                .iter()
+                .flat_map(|failure| failure.delayed_fields.iter()),
+            n,
+        )
+    }
+
+    /// Returns the conflict graph, with an edge from each reading transaction to each lower
+    /// transaction it waited on or whose writes invalidated its reads.
+    pub fn conflict_graph(&self) -> BTreeMap<(TxnIndex, TxnIndex), ConflictEdge> {
+        let mut graph: BTreeMap<(TxnIndex, TxnIndex), ConflictEdge> = BTreeMap::new();
+        for failure in &self.validation_failures {
+            for conflict in &failure.keys {
+                if let Some(writer) = conflict.writer {
+                    graph
+                        .entry((failure.txn_idx, writer))
+                        .or_default()
+                        .num_validation_failures += 1;
+                }
+            }
+        }
+        for wait in &self.dependency_waits {
+            let edge = graph.entry((wait.txn_idx, wait.dep_txn_idx)).or_default();
+            edge.num_dependency_waits += 1;
+            edge.total_wait += wait.duration;
+        }
+        graph
+    }
+}
+
+impl<K, I> Display for BlockConflictReport<K, I>
+where
+    K: Clone + Debug + Eq + Hash + Ord,
+    I: Clone + Debug + Eq + Hash + Ord,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} transactions, {} re-executions, {} failed validations, {} dependency waits ({:?})",
+            self.incarnations.len(),
+            self.num_reexecutions(),
+            self.validation_failures.len(),
+            self.dependency_waits.len(),
+            self.total_dependency_wait(),
+        )?;
+
+        let hot_keys = self.hot_keys(usize::MAX);
+        if !hot_keys.is_empty() {
+            writeln!(f, "Hot keys:")?;
+            for (key, count) in hot_keys {
+                writeln!(f, "  {:>6}  {:?}", count, key)?;
+            }
+        }
+
+        let hot_delayed_fields = self.hot_delayed_fields(usize::MAX);
+        if !hot_delayed_fields.is_empty() {
+            writeln!(f, "Hot delayed fields:")?;
+            for (id, count) in hot_delayed_fields {
+                writeln!(f, "  {:>6}  {:?}", count, id)?;
+            }
+        }
+
+        let graph = self.conflict_graph();
+        if !graph.is_empty() {
+            writeln!(f, "Conflict graph (reader -> writer):")?;
+            for ((reader, writer), edge) in graph {
+                writeln!(
+                    f,
+                    "  {} -> {}: {} failed validations, {} dependency waits ({:?})",
+                    reader,
+                    writer,
+                    edge.num_validation_failures,
+                    edge.num_dependency_waits,
+                    edge.total_wait,
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Counts the occurrences of each item and returns the `n` most frequent, ties broken by order.
+fn top_n<'a, T: 'a + Clone + Eq + Hash + Ord>(
+    items: impl Iterator<Item = &'a T>,
+    n: usize,
+) -> Vec<(T, usize)> {
+    let mut counts: HashMap<&T, usize> = HashMap::new();
+    for item in items {
+        *counts.entry(item).or_default() += 1;
+    }
+    let mut counts: Vec<_> = counts.into_iter().collect();
+    counts.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then_with(|| a.cmp(b)));
+    counts
+        .into_iter()
+        .take(n)
+        .map(|(item, count)| (item.clone(), count))
+        .collect()
+}
+
+/// Collects the conflicts of a block while it is being executed by multiple workers.
+pub(crate) struct ConflictRecorder<K, I> {
+    incarnations: Vec<AtomicU32>,
+    validation_failures: Mutex<Vec<ValidationFailure<K, I>>>,
+}
+
+impl<K, I> ConflictRecorder<K, I> {
+    pub(crate) fn new(num_txns: usize) -> Self {
+        Self {
+            incarnations: (0..num_txns).map(|_| AtomicU32::new(0)).collect(),
+            validation_failures: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub(crate) fn record_execution(&self, txn_idx: TxnIndex, incarnation: Incarnation) {
+        self.incarnations[txn_idx as usize].fetch_max(incarnation + 1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_validation_failure(&self, failure: ValidationFailure<K, I>) {
+        self.validation_failures.lock().push(failure);
+    }
+
+    pub(crate) fn into_report(
+        self,
+        dependency_waits: Vec<DependencyWait>,
+    ) -> BlockConflictReport<K, I> {
+        BlockConflictReport {
+            incarnations: self
+                .incarnations
+                .into_iter()
+                .map(AtomicU32::into_inner)
+                .collect(),
+            validation_failures: self.validation_failures.into_inner(),
+            dependency_waits,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failure(txn_idx: TxnIndex, keys: &[(u32, Option<TxnIndex>)]) -> ValidationFailure<u32, u64> {
+        ValidationFailure {
+            txn_idx,
+            incarnation: 0,
+            keys: keys
+                .iter()
+                .map(|(key, writer)| KeyConflict {
+                    key: *key,
+                    writer: *writer,
+                })
+                .collect(),
+            delayed_fields: vec![],
+        }
+    }
+
+    #[test]
+    fn report_aggregation() {
+        let recorder = ConflictRecorder::<u32, u64>::new(4);
+        recorder.record_execution(0, 0);
+        recorder.record_execution(1, 0);
+        recorder.record_execution(1, 1);
+        recorder.record_execution(2, 0);
+        recorder.record_execution(2, 2);
+        recorder.record_execution(3, 0);
+        recorder.record_validation_failure(failure(1, &[(7, Some(0))]));
+        recorder.record_validation_failure(failure(2, &[(7, Some(1)), (5, None)]));
+        recorder.record_validation_failure(failure(2, &[(7, Some(1)), (3, Some(0))]));
+
+        let report = recorder.into_report(vec![DependencyWait {
+            txn_idx: 2,
+            dep_txn_idx: 1,
+            duration: Duration::from_millis(3),
+        }]);
+
+        assert_eq!(report.incarnations, vec![1, 2, 3, 1]);
+        assert_eq!(report.num_reexecutions(), 3);
+        assert_eq!(report.hot_keys(2), vec![(7, 3), (3, 1)]);
+
+        let graph = report.conflict_graph();
+        assert_eq!(graph.len(), 3);
+        assert_eq!(graph[&(1, 0)].num_validation_failures, 1);
+        assert_eq!(graph[&(2, 0)].num_validation_failures, 1);
+        assert_eq!(graph[&(2, 1)], ConflictEdge {
+            num_validation_failures: 2,
+            num_dependency_waits: 1,
+            total_wait: Duration::from_millis(3),
+        });
+    }
+}
@@ -1,12 +1,15 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::conflict_analytics::BlockConflictReport;
 use aptos_metrics_core::{
     exponential_buckets, register_histogram, register_histogram_vec, register_int_counter,
-    register_int_counter_vec, Histogram, HistogramVec, IntCounter, IntCounterVec,
+    register_int_counter_vec, register_int_gauge_vec, Histogram, HistogramVec, IntCounter,
+    IntCounterVec, IntGaugeVec,
 };
 use aptos_types::fee_statement::FeeStatement;
 use once_cell::sync::Lazy;
+use std::{fmt::Debug, hash::Hash};
 
 pub struct GasType;
 
@@ -72,6 +75,27 @@ pub static SPECULATIVE_ABORT_COUNT: Lazy<IntCounter> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Number of failed validations involving each of the hottest keys of the last block executed
+/// in parallel with conflict analytics enabled. Only the top keys are kept to bound cardinality.
+pub static HOT_KEY_CONFLICTS: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "aptos_execution_hot_key_conflicts",
+        "Number of failed validations involving the hottest keys in the last block (Block STM)",
+        &["rank", "key"]
+    )
+    .unwrap()
+});
+
+/// Number of incarnations executed per transaction, with conflict analytics enabled.
+pub static TXN_INCARNATIONS: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        "aptos_execution_txn_incarnations",
+        "Number of incarnations executed per transaction in parallel execution (Block STM)",
+        exponential_buckets(/*start=*/ 1.0, /*factor=*/ 2.0, /*count=*/ 12).unwrap(),
+    )
+    .unwrap()
+});
+
 /// Count of times the BlockSTM is early halted due to exceeding the per-block gas limit.
 pub static EXCEED_PER_BLOCK_GAS_LIMIT_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
@@ -282,3 +306,21 @@ pub(crate) fn update_sequential_txn_gas_counters(fee_statement: &FeeStatement) {
         GasType::STORAGE_FEE_REFUND,
     );
 }
+
+pub(crate) fn update_conflict_counters<K, I>(report: &BlockConflictReport<K, I>, top_n: usize)
+where
+    K: Clone + Debug + Eq + Hash + Ord,
+    I: Clone + Eq + Hash + Ord,
+{
+    for incarnations in &report.incarnations {
+        TXN_INCARNATIONS.observe(*incarnations as f64);
+    }
+
+    // Reset, so that the gauge only reports the hot keys of the latest block.
+    HOT_KEY_CONFLICTS.reset();
+    for (rank, (key, count)) in report.hot_keys(top_n).into_iter().enumerate() {
+        HOT_KEY_CONFLICTS
+            .with_label_values(&[&rank.to_string(), &format!("{:?}", key)])
+            .set(count as i64);
+    }
+}
@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    conflict_analytics::{BlockConflictReport, ConflictRecorder, ValidationFailure},
     counters,
     counters::{
         PARALLEL_EXECUTION_SECONDS, RAYON_EXECUTION_SECONDS, TASK_EXECUTE_SECONDS,
@@ -10,6 +11,7 @@ use crate::{
     },
     errors::*,
     explicit_sync_wrapper::ExplicitSyncWrapper,
+    schedule_replay::{ReplayStep, Schedule, ScheduledTask},
     scheduler::{DependencyStatus, ExecutionTaskType, Scheduler, SchedulerTask, Wave},
     task::{ExecutionStatus, ExecutorTask, TransactionOutput},
     txn_commit_hook::TransactionCommitHook,
@@ -42,6 +44,7 @@ use aptos_vm_logging::{clear_speculative_txn_logs, init_speculative_logs};
 use bytes::Bytes;
 use claims::assert_none;
 use core::panic;
+use crossbeam::channel::{unbounded, Receiver, Sender};
 use move_core_types::value::MoveTypeLayout;
 use num_cpus;
 use rand::{thread_rng, Rng};
@@ -50,6 +53,8 @@ use std::{
     cell::RefCell,
     collections::{BTreeMap, HashMap, HashSet},
     marker::{PhantomData, Sync},
+    panic::AssertUnwindSafe,
+    path::PathBuf,
     sync::{atomic::AtomicU32, Arc},
 };
 
@@ -60,6 +65,15 @@ pub struct BlockExecutor<T, E, S, L, X> {
     executor_thread_pool: Arc<ThreadPool>,
     maybe_block_gas_limit: Option<u64>,
     transaction_commit_hook: Option<L>,
+    // If set, conflicts are recorded during parallel execution, and the given number of
+    // hottest keys is exported as metrics.
+    conflict_analytics_top_n: Option<usize>,
+    // If set, the schedule of each block executed in parallel is saved to the given path.
+    schedule_recording: Option<PathBuf>,
+    // If set, parallel execution replays the given schedule instead.
+    schedule_replay: Option<Schedule>,
+    // If set, the keys read by each transaction are recorded in its output.
+    record_read_sets: bool,
     phantom: PhantomData<(T, E, S, L, X)>,
 }
 
@@ -89,10 +103,44 @@ where
             executor_thread_pool,
             maybe_block_gas_limit,
             transaction_commit_hook,
+            conflict_analytics_top_n: None,
+            schedule_recording: None,
+            schedule_replay: None,
+            record_read_sets: false,
             phantom: PhantomData,
         }
     }
 
+    /// Enables recording the conflicts of each block executed in parallel, and exporting the
+    /// `top_n` hottest keys as metrics. Recording adds synchronization on validation failures
+    /// and dependency waits, so it is meant for diagnosing contention.
+    pub fn with_conflict_analytics(mut self, top_n: usize) -> Self {
+        self.conflict_analytics_top_n = Some(top_n);
+        self
+    }
+
+    /// Records the tasks handed out by the scheduler during parallel execution, and saves the
+    /// schedule of each block to the given path, overwriting the previous one. The schedule can
+    /// then be replayed deterministically with `with_schedule_replay`.
+    pub fn with_schedule_recording(mut self, path: PathBuf) -> Self {
+        self.schedule_recording = Some(path);
+        self
+    }
+
+    /// Replays the given recorded schedule with a single thread stepping through it, instead of
+    /// executing blocks in parallel. Meant for reproducing the interleaving of a failed run.
+    pub fn with_schedule_replay(mut self, schedule: Schedule) -> Self {
+        self.schedule_replay = Some(schedule);
+        self
+    }
+
+    /// Records the keys read by each transaction in its output, for its access list. Disabled
+    /// by default, as it adds work to every read.
+    pub fn with_read_set_recording(mut self) -> Self {
+        self.record_read_sets = true;
+        self
+    }
+
     fn execute(
         idx_to_execute: TxnIndex,
         incarnation: Incarnation,
@@ -102,12 +150,14 @@ where
         executor: &E,
         base_view: &S,
         latest_view: ParallelState<T, X>,
+        record_read_sets: bool,
     ) -> ::std::result::Result<bool, PanicOr<IntentionalFallbackToSequential>> {
         let _timer = TASK_EXECUTE_SECONDS.start_timer();
         let txn = &signature_verified_block[idx_to_execute as usize];
 
         // VM execution.
-        let sync_view = LatestView::new(base_view, ViewState::Sync(latest_view), idx_to_execute);
+        let sync_view = LatestView::new(base_view, ViewState::Sync(latest_view), idx_to_execute)
+            .with_read_key_recording(record_read_sets);
         let execute_result = executor.execute_transaction(&sync_view, txn, idx_to_execute, false);
 
         let mut prev_modified_keys = last_input_output
@@ -119,6 +169,13 @@ where
             .map_or(HashSet::new(), |keys| keys.collect());
 
         let mut read_set = sync_view.take_reads();
+        if let (
+            ExecutionStatus::Success(output) | ExecutionStatus::SkipRest(output),
+            Some(read_keys),
+        ) = (&execute_result, sync_view.take_read_keys())
+        {
+            output.set_read_set(read_keys);
+        }
 
         // For tracking whether the recent execution wrote outside of the previous write/delta set.
         let mut updates_outside = false;
@@ -355,10 +412,28 @@ where
         last_input_output: &TxnLastInputOutput<T, E::Output, E::Error>,
         versioned_cache: &MVHashMap<T::Key, T::Tag, T::Value, X, T::Identifier>,
         scheduler: &Scheduler,
+        conflict_recorder: Option<&ConflictRecorder<T::Key, T::Identifier>>,
     ) -> SchedulerTask {
         let aborted = !valid && scheduler.try_abort(txn_idx, incarnation);
 
         if aborted {
+            if let Some(conflict_recorder) = conflict_recorder {
+                // The versioned cache may have changed since validation, so the recorded
+                // reads are a best-effort attribution of the failure.
+                let read_set = last_input_output
+                    .read_set(txn_idx)
+                    .expect("[BlockSTM]: Prior read-set must be recorded");
+                conflict_recorder.record_validation_failure(ValidationFailure {
+                    txn_idx,
+                    incarnation,
+                    keys: read_set.failed_reads(
+                        versioned_cache.data(),
+                        versioned_cache.group_data(),
+                        txn_idx,
+                    ),
+                    delayed_fields: vec![],
+                });
+            }
             Self::update_transaction_on_abort(txn_idx, last_input_output, versioned_cache);
             scheduler.finish_abort(txn_idx, incarnation)
         } else {
@@ -429,6 +504,7 @@ where
         shared_counter: &AtomicU32,
         executor: &E,
         block: &[T],
+        conflict_recorder: Option<&ConflictRecorder<T::Key, T::Identifier>>,
     ) -> ::std::result::Result<(), PanicOr<IntentionalFallbackToSequential>> {
         let mut shared_commit_state_guard = shared_commit_state.acquire();
         let (accumulated_fee_statement, txn_fee_statements, shared_maybe_error) =
@@ -460,6 +536,22 @@ where
             if !Self::validate_commit_ready(txn_idx, versioned_cache, last_input_output)? {
                 // Transaction needs to be re-executed, one final time.
 
+                if let Some(conflict_recorder) = conflict_recorder {
+                    let read_set = last_input_output
+                        .read_set(txn_idx)
+                        .expect("Read set must be recorded");
+                    conflict_recorder.record_validation_failure(ValidationFailure {
+                        txn_idx,
+                        incarnation,
+                        keys: vec![],
+                        delayed_fields: read_set.failed_delayed_field_reads(
+                            versioned_cache.delayed_fields(),
+                            txn_idx,
+                        )?,
+                    });
+                    conflict_recorder.record_execution(txn_idx, incarnation + 1);
+                }
+
                 Self::update_transaction_on_abort(txn_idx, last_input_output, versioned_cache);
                 // We are going to skip reducing validation index here, as we
                 // are executing immediately, and will reduce it unconditionally
@@ -479,6 +571,7 @@ where
                         start_shared_counter,
                         shared_counter,
                     ),
+                    self.record_read_sets,
                 )?;
 
                 scheduler.finish_execution_during_commit(txn_idx);
@@ -949,6 +1042,7 @@ where
             Option<Error<E::Error>>,
         )>,
         final_results: &ExplicitSyncWrapper<Vec<E::Output>>,
+        conflict_recorder: Option<&ConflictRecorder<T::Key, T::Identifier>>,
     ) -> ::std::result::Result<(), PanicOr<IntentionalFallbackToSequential>> {
         // Make executor for each task. TODO: fast concurrent executor.
         let init_timer = VM_INIT_SECONDS.start_timer();
@@ -990,6 +1084,7 @@ where
                     shared_counter,
                     &executor,
                     block,
+                    conflict_recorder,
                 )?;
                 scheduler.queueing_commits_mark_done();
             }
@@ -1007,6 +1102,7 @@ where
                         last_input_output,
                         versioned_cache,
                         scheduler,
+                        conflict_recorder,
                     )
                 },
                 SchedulerTask::ExecutionTask(
@@ -1014,6 +1110,9 @@ where
                     incarnation,
                     ExecutionTaskType::Execution,
                 ) => {
+                    if let Some(conflict_recorder) = conflict_recorder {
+                        conflict_recorder.record_execution(txn_idx, incarnation);
+                    }
                     let updates_outside = Self::execute(
                         txn_idx,
                         incarnation,
@@ -1028,6 +1127,7 @@ where
                             start_shared_counter,
                             shared_counter,
                         ),
+                        self.record_read_sets,
                     )?;
                     scheduler.finish_execution(txn_idx, incarnation, updates_outside)
                 },
@@ -1050,12 +1150,303 @@ where
         }
     }
 
+    /// Forces the recorded schedule, see schedule_replay. Errors are recorded in the shared
+    /// commit state.
+    fn replay_loop(
+        &self,
+        schedule: &Schedule,
+        replay_step_sender: &Sender<ReplayStep>,
+        replay_steps: &Receiver<ReplayStep>,
+        executor_arguments: &E::Argument,
+        block: &[T],
+        last_input_output: &TxnLastInputOutput<T, E::Output, E::Error>,
+        versioned_cache: &MVHashMap<T::Key, T::Tag, T::Value, X, T::Identifier>,
+        scheduler: &Scheduler,
+        base_view: &S,
+        start_shared_counter: u32,
+        shared_counter: &AtomicU32,
+        shared_commit_state: &ExplicitSyncWrapper<(
+            FeeStatement,
+            Vec<FeeStatement>,
+            Option<Error<E::Error>>,
+        )>,
+        final_results: &ExplicitSyncWrapper<Vec<E::Output>>,
+        conflict_recorder: Option<&ConflictRecorder<T::Key, T::Identifier>>,
+    ) {
+        let executor = E::init(*executor_arguments);
+
+        std::thread::scope(|s| {
+            if let Err(e) = self.replay_tasks(
+                s,
+                schedule,
+                replay_step_sender,
+                replay_steps,
+                &executor,
+                block,
+                last_input_output,
+                versioned_cache,
+                scheduler,
+                base_view,
+                start_shared_counter,
+                shared_counter,
+                shared_commit_state,
+                final_results,
+                conflict_recorder,
+            ) {
+                // Halting also wakes up the executions that are still suspended, so that the
+                // scope can join their threads.
+                if scheduler.halt() {
+                    let mut shared_commit_state_guard = shared_commit_state.acquire();
+                    let (_, _, maybe_error) = shared_commit_state_guard.dereference_mut();
+                    *maybe_error = Some(Error::FallbackToSequential(e));
+                }
+            }
+        });
+    }
+
+    /// Performs the recorded tasks in order, waiting for each to finish before starting the
+    /// next, and commits as soon as possible after each task. Executions are spawned on their
+    /// own threads, so that they can be suspended on a read dependency until the recorded task
+    /// that resumes them.
+    fn replay_tasks<'scope, 'env>(
+        &'env self,
+        s: &'scope std::thread::Scope<'scope, 'env>,
+        schedule: &'env Schedule,
+        replay_step_sender: &'env Sender<ReplayStep>,
+        replay_steps: &'env Receiver<ReplayStep>,
+        executor: &'env E,
+        block: &'env [T],
+        last_input_output: &'env TxnLastInputOutput<T, E::Output, E::Error>,
+        versioned_cache: &'env MVHashMap<T::Key, T::Tag, T::Value, X, T::Identifier>,
+        scheduler: &'env Scheduler,
+        base_view: &'env S,
+        start_shared_counter: u32,
+        shared_counter: &'env AtomicU32,
+        shared_commit_state: &'env ExplicitSyncWrapper<(
+            FeeStatement,
+            Vec<FeeStatement>,
+            Option<Error<E::Error>>,
+        )>,
+        final_results: &'env ExplicitSyncWrapper<Vec<E::Output>>,
+        conflict_recorder: Option<&'env ConflictRecorder<T::Key, T::Identifier>>,
+    ) -> ::std::result::Result<(), PanicOr<IntentionalFallbackToSequential>> {
+        if schedule.num_txns != scheduler.num_txns() {
+            return Err(code_invariant_error(format!(
+                "Recorded schedule is for {} txns, but the block has {}",
+                schedule.num_txns,
+                scheduler.num_txns()
+            ))
+            .into());
+        }
+
+        // Tasks returned by the scheduler upon finishing an execution or an abort, to be
+        // performed when the corresponding recorded task is reached.
+        let mut returned_tasks: Vec<SchedulerTask> = Vec::new();
+
+        for task in &schedule.tasks {
+            if scheduler.replay_done() {
+                break;
+            }
+            let divergence =
+                || code_invariant_error(format!("Schedule replay diverged at {:?}", task));
+
+            match *task {
+                ScheduledTask::Execute {
+                    txn_idx,
+                    incarnation,
+                }
+                | ScheduledTask::ExecuteAfterAbort {
+                    txn_idx,
+                    incarnation,
+                } => {
+                    let returned_task = returned_tasks.iter().position(|returned| {
+                        matches!(returned, SchedulerTask::ExecutionTask(idx, inc, _)
+                            if *idx == txn_idx && *inc == incarnation)
+                    });
+                    let execution_task_type = match returned_task {
+                        Some(pos) => match returned_tasks.swap_remove(pos) {
+                            SchedulerTask::ExecutionTask(_, _, execution_task_type) => {
+                                execution_task_type
+                            },
+                            _ => unreachable!("Position of an execution task"),
+                        },
+                        None => scheduler
+                            .replay_try_incarnate(
+                                txn_idx,
+                                incarnation,
+                                matches!(task, ScheduledTask::Execute { .. }),
+                            )
+                            .ok_or_else(divergence)?,
+                    };
+
+                    match execution_task_type {
+                        ExecutionTaskType::Execution => {
+                            if let Some(conflict_recorder) = conflict_recorder {
+                                conflict_recorder.record_execution(txn_idx, incarnation);
+                            }
+                            let replay_step_sender = replay_step_sender.clone();
+                            s.spawn(move || {
+                                let execute_and_finish = || -> ::std::result::Result<
+                                    SchedulerTask,
+                                    PanicOr<IntentionalFallbackToSequential>,
+                                > {
+                                    let updates_outside = Self::execute(
+                                        txn_idx,
+                                        incarnation,
+                                        block,
+                                        last_input_output,
+                                        versioned_cache,
+                                        executor,
+                                        base_view,
+                                        ParallelState::new(
+                                            versioned_cache,
+                                            scheduler,
+                                            start_shared_counter,
+                                            shared_counter,
+                                        ),
+                                        self.record_read_sets,
+                                    )?;
+                                    Ok(scheduler.finish_execution(
+                                        txn_idx,
+                                        incarnation,
+                                        updates_outside,
+                                    ))
+                                };
+                                let result =
+                                    std::panic::catch_unwind(AssertUnwindSafe(execute_and_finish))
+                                        .unwrap_or_else(|_| {
+                                            Err(code_invariant_error(format!(
+                                                "Execution of txn {} panicked during replay",
+                                                txn_idx
+                                            ))
+                                            .into())
+                                        });
+                                // The send only fails if the replay was already aborted.
+                                let _ = replay_step_sender.send(ReplayStep::Finished(result));
+                            });
+                        },
+                        ExecutionTaskType::Wakeup(condvar) => {
+                            let (lock, cvar) = &*condvar;
+                            // Mark dependency resolved.
+                            let mut lock = lock.lock();
+                            *lock = DependencyStatus::Resolved;
+                            // Wake up the execution waiting for dependency.
+                            cvar.notify_one();
+                        },
+                    }
+
+                    // Wait until the execution finishes or is suspended again.
+                    match replay_steps
+                        .recv()
+                        .expect("Scheduler holds a sender of replay steps")
+                    {
+                        ReplayStep::Suspended => {},
+                        ReplayStep::Finished(result) => returned_tasks.push(result?),
+                    }
+                },
+                ScheduledTask::Validate {
+                    txn_idx,
+                    incarnation,
+                    wave,
+                }
+                | ScheduledTask::ValidateAfterExecution {
+                    txn_idx,
+                    incarnation,
+                    wave,
+                } => {
+                    if matches!(task, ScheduledTask::Validate { .. }) {
+                        scheduler.replay_claim_validation(txn_idx, wave);
+                    } else if let Some(pos) = returned_tasks.iter().position(|returned| {
+                        matches!(returned, SchedulerTask::ValidationTask(idx, inc, _)
+                            if *idx == txn_idx && *inc == incarnation)
+                    }) {
+                        returned_tasks.swap_remove(pos);
+                    } else {
+                        scheduler.replay_require_validation(txn_idx, wave);
+                    }
+                    if !scheduler.replay_can_validate(txn_idx) {
+                        return Err(divergence().into());
+                    }
+
+                    let valid = Self::validate(txn_idx, last_input_output, versioned_cache)?;
+                    returned_tasks.push(Self::update_on_validation(
+                        txn_idx,
+                        incarnation,
+                        valid,
+                        wave,
+                        last_input_output,
+                        versioned_cache,
+                        scheduler,
+                        conflict_recorder,
+                    ));
+                },
+            }
+
+            while scheduler.should_coordinate_commits() {
+                self.prepare_and_queue_commit_ready_txns(
+                    self.maybe_block_gas_limit,
+                    scheduler,
+                    versioned_cache,
+                    &mut SchedulerTask::NoTask,
+                    last_input_output,
+                    shared_commit_state,
+                    base_view,
+                    start_shared_counter,
+                    shared_counter,
+                    executor,
+                    block,
+                    conflict_recorder,
+                )?;
+                scheduler.queueing_commits_mark_done();
+            }
+            while let Ok(txn_idx) = scheduler.pop_from_commit_queue() {
+                self.materialize_txn_commit(
+                    txn_idx,
+                    versioned_cache,
+                    scheduler,
+                    start_shared_counter,
+                    shared_counter,
+                    last_input_output,
+                    base_view,
+                    final_results,
+                )?;
+            }
+        }
+
+        if !scheduler.replay_done() {
+            return Err(code_invariant_error(
+                "Schedule replay ended before all txns were committed",
+            )
+            .into());
+        }
+        Ok(())
+    }
+
     pub(crate) fn execute_transactions_parallel(
         &self,
         executor_initial_arguments: E::Argument,
         signature_verified_block: &[T],
         base_view: &S,
     ) -> Result<Vec<E::Output>, E::Error> {
+        self.execute_transactions_parallel_with_conflict_report(
+            executor_initial_arguments,
+            signature_verified_block,
+            base_view,
+        )
+        .0
+    }
+
+    /// Executes the block in parallel, and returns the observed conflicts if conflict analytics
+    /// are enabled.
+    pub(crate) fn execute_transactions_parallel_with_conflict_report(
+        &self,
+        executor_initial_arguments: E::Argument,
+        signature_verified_block: &[T],
+        base_view: &S,
+    ) -> (
+        Result<Vec<E::Output>, E::Error>,
+        Option<BlockConflictReport<T::Key, T::Identifier>>,
+    ) {
         let _timer = PARALLEL_EXECUTION_SECONDS.start_timer();
         // Using parallel execution with 1 thread currently will not work as it
         // will only have a coordinator role but no workers for rolling commit.
@@ -1068,7 +1459,7 @@ where
         let shared_counter = AtomicU32::new(start_shared_counter);
 
         if signature_verified_block.is_empty() {
-            return Ok(vec![]);
+            return (Ok(vec![]), None);
         }
 
         let num_txns = signature_verified_block.len();
@@ -1090,41 +1481,99 @@ where
         let num_txns = num_txns as u32;
 
         let last_input_output = TxnLastInputOutput::new(num_txns);
-        let scheduler = Scheduler::new(num_txns);
+        let mut scheduler = Scheduler::new(num_txns);
+        let conflict_recorder = self.conflict_analytics_top_n.map(|_| {
+            scheduler.enable_dependency_wait_tracking();
+            ConflictRecorder::new(num_txns as usize)
+        });
+        if self.schedule_recording.is_some() {
+            scheduler.enable_schedule_recording();
+        }
+        let replay_steps = self.schedule_replay.as_ref().map(|_| {
+            let (replay_step_sender, replay_steps) = unbounded();
+            scheduler.enable_schedule_replay(replay_step_sender.clone());
+            (replay_step_sender, replay_steps)
+        });
 
         let timer = RAYON_EXECUTION_SECONDS.start_timer();
-        self.executor_thread_pool.scope(|s| {
-            for _ in 0..self.concurrency_level {
-                s.spawn(|_| {
-                    if let Err(e) = self.worker_loop(
-                        &executor_initial_arguments,
-                        signature_verified_block,
-                        &last_input_output,
-                        &versioned_cache,
-                        &scheduler,
-                        base_view,
-                        start_shared_counter,
-                        &shared_counter,
-                        &shared_commit_state,
-                        &final_results,
-                    ) {
-                        if scheduler.halt() {
-                            let mut shared_commit_state_guard = shared_commit_state.acquire();
-                            let (_, _, maybe_error) = shared_commit_state_guard.dereference_mut();
-                            *maybe_error = Some(Error::FallbackToSequential(e));
+        if let Some((schedule, (replay_step_sender, replay_steps))) =
+            self.schedule_replay.as_ref().zip(replay_steps.as_ref())
+        {
+            self.replay_loop(
+                schedule,
+                replay_step_sender,
+                replay_steps,
+                &executor_initial_arguments,
+                signature_verified_block,
+                &last_input_output,
+                &versioned_cache,
+                &scheduler,
+                base_view,
+                start_shared_counter,
+                &shared_counter,
+                &shared_commit_state,
+                &final_results,
+                conflict_recorder.as_ref(),
+            );
+        } else {
+            self.executor_thread_pool.scope(|s| {
+                for _ in 0..self.concurrency_level {
+                    s.spawn(|_| {
+                        if let Err(e) = self.worker_loop(
+                            &executor_initial_arguments,
+                            signature_verified_block,
+                            &last_input_output,
+                            &versioned_cache,
+                            &scheduler,
+                            base_view,
+                            start_shared_counter,
+                            &shared_counter,
+                            &shared_commit_state,
+                            &final_results,
+                            conflict_recorder.as_ref(),
+                        ) {
+                            if scheduler.halt() {
+                                let mut shared_commit_state_guard = shared_commit_state.acquire();
+                                let (_, _, maybe_error) =
+                                    shared_commit_state_guard.dereference_mut();
+                                *maybe_error = Some(Error::FallbackToSequential(e));
+                            }
                         }
-                    }
-                });
-            }
-        });
+                    });
+                }
+            });
+        }
         drop(timer);
+
+        if let Some(path) = &self.schedule_recording {
+            let schedule = Schedule {
+                num_txns,
+                tasks: scheduler.take_recorded_tasks(),
+            };
+            if let Err(err) = schedule.save(path) {
+                error!(
+                    "Failed to save the recorded schedule to {:?}: {:?}",
+                    path, err
+                );
+            }
+        }
+
+        let conflict_report = conflict_recorder.zip(self.conflict_analytics_top_n).map(
+            |(conflict_recorder, top_n)| {
+                let report = conflict_recorder.into_report(scheduler.take_dependency_waits());
+                counters::update_conflict_counters(&report, top_n);
+                report
+            },
+        );
+
         // Explicit async drops.
         DEFAULT_DROPPER.schedule_drop((last_input_output, scheduler, versioned_cache));
         let (_, _, maybe_error) = shared_commit_state.into_inner();
-        match maybe_error {
+        let ret = match maybe_error {
             Some(err) => Err(err),
             None => Ok(final_results.into_inner()),
-        }
+        };
+        (ret, conflict_report)
     }
 
     fn apply_output_sequential(
@@ -1233,7 +1682,8 @@ where
                     dynamic_change_set_optimizations_enabled,
                 )),
                 idx as TxnIndex,
-            );
+            )
+            .with_read_key_recording(self.record_read_sets);
             let res = executor.execute_transaction(&latest_view, txn, idx as TxnIndex, true);
 
             let must_skip = matches!(res, ExecutionStatus::SkipRest(_));
@@ -1244,6 +1694,9 @@ where
                         0,
                         "Sequential execution must materialize deltas"
                     );
+                    if let Some(read_keys) = latest_view.take_read_keys() {
+                        output.set_read_set(read_keys);
+                    }
 
                     // Calculating the accumulated gas costs of the committed txns.
                     let fee_statement = output.fee_statement();
@@ -1421,23 +1874,44 @@ where
         signature_verified_block: &[T],
         base_view: &S,
     ) -> Result<Vec<E::Output>, E::Error> {
+        self.execute_block_with_conflict_report(
+            executor_arguments,
+            signature_verified_block,
+            base_view,
+        )
+        .0
+    }
+
+    /// Same as `execute_block`, but also returns the conflicts observed during parallel
+    /// execution, if conflict analytics are enabled and the block was executed in parallel.
+    pub fn execute_block_with_conflict_report(
+        &self,
+        executor_arguments: E::Argument,
+        signature_verified_block: &[T],
+        base_view: &S,
+    ) -> (
+        Result<Vec<E::Output>, E::Error>,
+        Option<BlockConflictReport<T::Key, T::Identifier>>,
+    ) {
         let dynamic_change_set_optimizations_enabled = signature_verified_block.len() != 1
             || E::is_transaction_dynamic_change_set_capable(&signature_verified_block[0]);
 
-        let mut ret = if self.concurrency_level > 1 && dynamic_change_set_optimizations_enabled {
-            self.execute_transactions_parallel(
-                executor_arguments,
-                signature_verified_block,
-                base_view,
-            )
-        } else {
-            self.execute_transactions_sequential(
-                executor_arguments,
-                signature_verified_block,
-                base_view,
-                dynamic_change_set_optimizations_enabled,
-            )
-        };
+        let (mut ret, conflict_report) =
+            if self.concurrency_level > 1 && dynamic_change_set_optimizations_enabled {
+                self.execute_transactions_parallel_with_conflict_report(
+                    executor_arguments,
+                    signature_verified_block,
+                    base_view,
+                )
+            } else {
+                let ret = self.execute_transactions_sequential(
+                    executor_arguments,
+                    signature_verified_block,
+                    base_view,
+                    dynamic_change_set_optimizations_enabled,
+                );
+                (ret, None)
+            };
 
         // Sequential execution fallback
         // Only worth doing if we did parallel before, i.e. if we did a different pass.
@@ -1482,7 +1956,7 @@ where
             panic!("Sequential execution failed with {:?}", e);
         }
 
-        ret
+        (ret, conflict_report)
     }
 }
 
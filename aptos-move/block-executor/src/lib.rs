@@ -140,12 +140,14 @@ subsequent incarnation to finish.
 extern crate scopeguard;
 
 mod captured_reads;
+pub mod conflict_analytics;
 pub mod counters;
 pub mod errors;
 pub mod executor;
 pub mod explicit_sync_wrapper;
 #[cfg(any(test, feature = "fuzzing"))]
 pub mod proptest_types;
+pub mod schedule_replay;
 mod scheduler;
 pub mod task;
 pub mod txn_commit_hook;
@@ -13,9 +13,11 @@ use crate::{
             MAX_GAS_PER_TXN,
         },
     },
+    schedule_replay::Schedule,
     txn_commit_hook::NoOpTransactionCommitHook,
 };
 use aptos_aggregator::types::PanicOr;
+use aptos_temppath::TempPath;
 use aptos_types::{contract_event::TransactionEvent, executable::ExecutableTestType};
 use claims::assert_ok;
 use num_cpus;
@@ -605,6 +607,83 @@ fn module_publishing_races() {
     }
 }
 
+// Executes the block in parallel while recording the schedule, then replays the recorded
+// schedule, and checks both outputs against the baseline. A schedule recorded for a failing
+// run can be kept (see TempPath::persist) and replayed with the same transactions.
+fn record_and_replay_schedule(
+    universe_size: usize,
+    num_txns: usize,
+    maybe_block_gas_limit: Option<u64>,
+) {
+    let mut runner = TestRunner::default();
+
+    let universe = vec(any::<[u8; 32]>(), universe_size)
+        .new_tree(&mut runner)
+        .expect("creating a new value should succeed")
+        .current();
+    let transaction_gen = vec(
+        any_with::<TransactionGen<[u8; 32]>>(TransactionGenParams::new_dynamic()),
+        num_txns,
+    )
+    .new_tree(&mut runner)
+    .expect("creating a new value should succeed")
+    .current();
+    let transactions: Vec<_> = transaction_gen
+        .into_iter()
+        .map(|txn_gen| txn_gen.materialize(&universe, (false, false)))
+        .collect();
+
+    let data_view = EmptyDataView::<KeyType<[u8; 32]>> {
+        phantom: PhantomData,
+    };
+    let executor_thread_pool = Arc::new(
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(num_cpus::get())
+            .build()
+            .unwrap(),
+    );
+    let block_executor = || {
+        BlockExecutor::<
+            MockTransaction<KeyType<[u8; 32]>, MockEvent>,
+            MockTask<KeyType<[u8; 32]>, MockEvent>,
+            EmptyDataView<KeyType<[u8; 32]>>,
+            NoOpTransactionCommitHook<MockOutput<KeyType<[u8; 32]>, MockEvent>, usize>,
+            ExecutableTestType,
+        >::new(
+            num_cpus::get(),
+            executor_thread_pool.clone(),
+            maybe_block_gas_limit,
+            None,
+        )
+    };
+    let baseline = BaselineOutput::generate(&transactions, maybe_block_gas_limit);
+
+    let schedule_path = TempPath::new();
+    let output = block_executor()
+        .with_schedule_recording(schedule_path.path().to_path_buf())
+        .execute_transactions_parallel((), &transactions, &data_view);
+    baseline.assert_output(&output);
+
+    let schedule = Schedule::load(schedule_path.path()).unwrap();
+    assert_eq!(schedule.num_txns as usize, num_txns);
+    let output = block_executor()
+        .with_schedule_replay(schedule)
+        .execute_transactions_parallel((), &transactions, &data_view);
+    baseline.assert_output(&output);
+}
+
+#[test]
+fn record_and_replay_schedule_test() {
+    record_and_replay_schedule(100, 1000, None);
+    // Contended, to record many aborts and dependencies.
+    record_and_replay_schedule(10, 1000, None);
+    record_and_replay_schedule(
+        10,
+        1000,
+        Some(rand::thread_rng().gen_range(0, 1000 * MAX_GAS_PER_TXN / 2)),
+    );
+}
+
 // The following set of tests are the same tests as above with per-block gas limit.
 proptest! {
     #![proptest_config(ProptestConfig::with_cases(32))]
@@ -1093,6 +1093,8 @@ where
         }
     }
 
+    fn set_read_set(&self, _read_set: HashSet<<Self::Txn as Transaction>::Key>) {}
+
     fn incorporate_materialized_txn_output(
         &self,
         aggregator_v1_writes: Vec<(<Self::Txn as Transaction>::Key, WriteOp)>,
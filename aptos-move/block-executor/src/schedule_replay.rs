@@ -0,0 +1,118 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Deterministic record and replay of parallel execution schedules.
+//!
+//! The tasks that Block-STM workers perform, and the order in which they perform them, depend on
+//! thread timing, so an interleaving that exposed a bug can usually not be reproduced. In record
+//! mode, the scheduler logs every task it hands out to a worker. In replay mode, a single thread
+//! steps through a recorded [`Schedule`], forcing the scheduler to hand out the same tasks in the
+//! same order.
+//!
+//! During replay, exactly one task makes progress at any time. An execution that is suspended on
+//! a read dependency is parked on its own thread until the recorded task that resumes it, and
+//! transactions are committed as soon as possible after each step. If the scheduler cannot hand
+//! out a recorded task, the replay is aborted with a code invariant error describing the
+//! divergence.
+
+use crate::{errors::IntentionalFallbackToSequential, scheduler::SchedulerTask};
+use anyhow::Result;
+use aptos_aggregator::types::PanicOr;
+use aptos_mvhashmap::types::{Incarnation, TxnIndex};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+/// A task handed out by the scheduler to a worker.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ScheduledTask {
+    /// An execution (or the resumption of a suspended execution) of an incarnation, claimed
+    /// from the execution index.
+    Execute {
+        txn_idx: TxnIndex,
+        incarnation: Incarnation,
+    },
+    /// A re-execution of an incarnation, returned to the worker that aborted the previous one.
+    ExecuteAfterAbort {
+        txn_idx: TxnIndex,
+        incarnation: Incarnation,
+    },
+    /// A validation of an incarnation, claimed from the validation index.
+    Validate {
+        txn_idx: TxnIndex,
+        incarnation: Incarnation,
+        wave: u32,
+    },
+    /// A validation of an incarnation, returned to the worker that finished executing it.
+    ValidateAfterExecution {
+        txn_idx: TxnIndex,
+        incarnation: Incarnation,
+        wave: u32,
+    },
+}
+
+/// The tasks handed out while executing a block in parallel, in order.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Schedule {
+    pub num_txns: TxnIndex,
+    pub tasks: Vec<ScheduledTask>,
+}
+
+impl Schedule {
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, bcs::to_bytes(self)?)?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        Ok(bcs::from_bytes(&fs::read(path)?)?)
+    }
+}
+
+/// Reported to the replaying thread by the thread of the execution that made progress.
+pub(crate) enum ReplayStep {
+    /// The execution was suspended on a read dependency.
+    Suspended,
+    /// The execution finished, with the task returned by the scheduler upon finishing it.
+    Finished(Result<SchedulerTask, PanicOr<IntentionalFallbackToSequential>>),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_temppath::TempPath;
+
+    #[test]
+    fn schedule_roundtrip() {
+        let schedule = Schedule {
+            num_txns: 2,
+            tasks: vec![
+                ScheduledTask::Execute {
+                    txn_idx: 0,
+                    incarnation: 0,
+                },
+                ScheduledTask::Execute {
+                    txn_idx: 1,
+                    incarnation: 0,
+                },
+                ScheduledTask::ValidateAfterExecution {
+                    txn_idx: 1,
+                    incarnation: 0,
+                    wave: 0,
+                },
+                ScheduledTask::ExecuteAfterAbort {
+                    txn_idx: 1,
+                    incarnation: 1,
+                },
+                ScheduledTask::Validate {
+                    txn_idx: 1,
+                    incarnation: 1,
+                    wave: 1,
+                },
+            ],
+        };
+
+        let path = TempPath::new();
+        schedule.save(path.path()).unwrap();
+        assert_eq!(Schedule::load(path.path()).unwrap(), schedule);
+    }
+}
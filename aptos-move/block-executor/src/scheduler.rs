@@ -2,11 +2,15 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::explicit_sync_wrapper::ExplicitSyncWrapper;
+use crate::{
+    conflict_analytics::DependencyWait,
+    explicit_sync_wrapper::ExplicitSyncWrapper,
+    schedule_replay::{ReplayStep, ScheduledTask},
+};
 use aptos_infallible::Mutex;
 use aptos_mvhashmap::types::{Incarnation, TxnIndex};
 use concurrent_queue::{ConcurrentQueue, PopError};
-use crossbeam::utils::CachePadded;
+use crossbeam::{channel::Sender, utils::CachePadded};
 use parking_lot::{RwLock, RwLockUpgradableReadGuard};
 use std::{
     cmp::{max, min},
@@ -14,6 +18,7 @@ use std::{
         atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering},
         Arc, Condvar,
     },
+    time::Duration,
 };
 
 const TXN_IDX_MASK: u64 = (1 << 32) - 1;
@@ -229,6 +234,15 @@ impl ValidationStatus {
 
 pub trait TWaitForDependency {
     fn wait_for_dependency(&self, txn_idx: TxnIndex, dep_txn_idx: TxnIndex) -> DependencyResult;
+
+    /// Called after txn_idx was suspended on the dependency for the given duration.
+    fn record_dependency_wait(
+        &self,
+        _txn_idx: TxnIndex,
+        _dep_txn_idx: TxnIndex,
+        _duration: Duration,
+    ) {
+    }
 }
 
 pub struct Scheduler {
@@ -277,6 +291,15 @@ pub struct Scheduler {
     queueing_commits_lock: CachePadded<ArmedLock>,
 
     commit_queue: ConcurrentQueue<u32>,
+
+    /// If set, the dependency waits of the block are recorded for conflict analytics.
+    dependency_waits: Option<Mutex<Vec<DependencyWait>>>,
+
+    /// If set, every task handed out to a worker is recorded, see schedule_replay.
+    recorded_tasks: Option<Mutex<Vec<ScheduledTask>>>,
+    /// If set, a recorded schedule is being replayed, and suspending an execution on a read
+    /// dependency is reported to the replaying thread.
+    replay_steps: Option<Sender<ReplayStep>>,
 }
 
 /// Public Interfaces for the Scheduler
@@ -305,9 +328,38 @@ impl Scheduler {
             has_halted: CachePadded::new(AtomicBool::new(false)),
             queueing_commits_lock: CachePadded::new(ArmedLock::new()),
             commit_queue: ConcurrentQueue::<u32>::bounded(num_txns as usize),
+            dependency_waits: None,
+            recorded_tasks: None,
+            replay_steps: None,
         }
     }
 
+    pub(crate) fn enable_dependency_wait_tracking(&mut self) {
+        self.dependency_waits = Some(Mutex::new(Vec::new()));
+    }
+
+    /// Returns the recorded dependency waits, empty if tracking is not enabled.
+    pub(crate) fn take_dependency_waits(&self) -> Vec<DependencyWait> {
+        self.dependency_waits
+            .as_ref()
+            .map_or_else(Vec::new, |waits| std::mem::take(&mut *waits.lock()))
+    }
+
+    pub(crate) fn enable_schedule_recording(&mut self) {
+        self.recorded_tasks = Some(Mutex::new(Vec::new()));
+    }
+
+    /// Returns the tasks handed out so far, empty if recording is not enabled.
+    pub(crate) fn take_recorded_tasks(&self) -> Vec<ScheduledTask> {
+        self.recorded_tasks
+            .as_ref()
+            .map_or_else(Vec::new, |tasks| std::mem::take(&mut *tasks.lock()))
+    }
+
+    pub(crate) fn enable_schedule_replay(&mut self, replay_steps: Sender<ReplayStep>) {
+        self.replay_steps = Some(replay_steps);
+    }
+
     pub fn num_txns(&self) -> TxnIndex {
         self.num_txns
     }
@@ -438,6 +490,11 @@ impl Scheduler {
                 if let Some((txn_idx, incarnation, wave)) =
                     self.try_validate_next_version(idx_to_validate, wave)
                 {
+                    self.record_task(ScheduledTask::Validate {
+                        txn_idx,
+                        incarnation,
+                        wave,
+                    });
                     return SchedulerTask::ValidationTask(txn_idx, incarnation, wave);
                 }
             }
@@ -446,6 +503,10 @@ impl Scheduler {
                 if let Some((txn_idx, incarnation, execution_task_type)) =
                     self.try_execute_next_version()
                 {
+                    self.record_task(ScheduledTask::Execute {
+                        txn_idx,
+                        incarnation,
+                    });
                     return SchedulerTask::ExecutionTask(txn_idx, incarnation, execution_task_type);
                 }
             }
@@ -524,6 +585,11 @@ impl Scheduler {
             }
             // Update the minimum wave this txn needs to pass.
             validation_status.required_wave = cur_wave;
+            self.record_task(ScheduledTask::ValidateAfterExecution {
+                txn_idx,
+                incarnation,
+                wave: cur_wave,
+            });
             return SchedulerTask::ValidationTask(txn_idx, incarnation, cur_wave);
         }
 
@@ -573,6 +639,10 @@ impl Scheduler {
             // nothing to do, as another thread must have succeeded to incarnate and
             // obtain the task for re-execution.
             if let Some((new_incarnation, execution_task_type)) = self.try_incarnate(txn_idx) {
+                self.record_task(ScheduledTask::ExecuteAfterAbort {
+                    txn_idx,
+                    incarnation: new_incarnation,
+                });
                 return SchedulerTask::ExecutionTask(txn_idx, new_incarnation, execution_task_type);
             }
         }
@@ -651,13 +721,97 @@ impl TWaitForDependency for Scheduler {
         stored_deps.push(txn_idx);
 
         // Stored deps gets unlocked here.
+        drop(stored_deps);
+
+        if let Some(replay_steps) = &self.replay_steps {
+            // Let the replaying thread step through the next recorded task while the execution
+            // waits. The send only fails if the replay was already aborted.
+            let _ = replay_steps.send(ReplayStep::Suspended);
+        }
 
         DependencyResult::Dependency(dep_condvar)
     }
+
+    fn record_dependency_wait(&self, txn_idx: TxnIndex, dep_txn_idx: TxnIndex, duration: Duration) {
+        if let Some(dependency_waits) = &self.dependency_waits {
+            dependency_waits.lock().push(DependencyWait {
+                txn_idx,
+                dep_txn_idx,
+                duration,
+            });
+        }
+    }
+}
+
+/// Functions used to force a recorded schedule, see schedule_replay.
+impl Scheduler {
+    /// Claims the execution of the given incarnation, if the transaction is ready for it, and
+    /// returns its type. If claim_execution_idx is set, the execution index is moved past the
+    /// transaction, as if the task was handed out by next_task.
+    pub(crate) fn replay_try_incarnate(
+        &self,
+        txn_idx: TxnIndex,
+        incarnation: Incarnation,
+        claim_execution_idx: bool,
+    ) -> Option<ExecutionTaskType> {
+        if txn_idx >= self.num_txns {
+            return None;
+        }
+        if claim_execution_idx {
+            self.execution_idx.store(txn_idx + 1, Ordering::SeqCst);
+        }
+
+        let mut status = self.txn_status[txn_idx as usize].0.write();
+        match &*status {
+            ExecutionStatus::Ready(ready_incarnation, execution_task_type)
+                if *ready_incarnation == incarnation =>
+            {
+                let execution_task_type = execution_task_type.clone();
+                *status = ExecutionStatus::Executing(incarnation);
+                Some(execution_task_type)
+            },
+            _ => None,
+        }
+    }
+
+    /// Moves the validation index past the transaction, as if a validation task in the given
+    /// wave was handed out by next_task. Has no effect if a new wave has started since.
+    pub(crate) fn replay_claim_validation(&self, txn_idx: TxnIndex, wave: Wave) {
+        let _ = self
+            .validation_idx
+            .fetch_update(Ordering::SeqCst, Ordering::Acquire, |val_idx| {
+                let (idx, cur_wave) = Self::unpack_validation_idx(val_idx);
+                (cur_wave == wave && idx <= txn_idx)
+                    .then(|| Self::pack_into_validation_index(txn_idx + 1, wave))
+            });
+    }
+
+    /// Requires a successful validation in the given wave before the transaction can be
+    /// committed, as if the validation task was returned by finish_execution.
+    pub(crate) fn replay_require_validation(&self, txn_idx: TxnIndex, wave: Wave) {
+        let mut validation_status = self.txn_status[txn_idx as usize].1.write();
+        validation_status.required_wave = max(validation_status.required_wave, wave);
+    }
+
+    /// Returns true iff an incarnation of the transaction has been executed, i.e. it has a
+    /// read-set that can be validated.
+    pub(crate) fn replay_can_validate(&self, txn_idx: TxnIndex) -> bool {
+        txn_idx < self.num_txns && !self.never_executed(txn_idx)
+    }
+
+    pub(crate) fn replay_done(&self) -> bool {
+        self.done()
+    }
 }
 
 /// Private functions of the Scheduler
 impl Scheduler {
+    fn record_task(&self, task: ScheduledTask) {
+        if let Some(recorded_tasks) = &self.recorded_tasks {
+            recorded_tasks.lock().push(task);
+        }
+    }
+
     /// Helper function to be called from Scheduler::halt(); Sets the
     /// transaction status to Halted. If the transaction is suspended,
     /// it will wake it up.
@@ -10,7 +10,11 @@ use aptos_types::{
 };
 use aptos_vm_types::resolver::{TExecutorView, TResourceGroupView};
 use move_core_types::value::MoveTypeLayout;
-use std::{collections::BTreeMap, fmt::Debug, sync::Arc};
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt::Debug,
+    sync::Arc,
+};
 
 /// The execution result of a transaction
 #[derive(Debug)]
@@ -159,6 +163,11 @@ pub trait TransactionOutput: Send + Sync + Debug {
     /// Execution output for transactions that comes after SkipRest signal.
     fn skip_output() -> Self;
 
+    /// Records the keys the transaction read when it was executed, to be included in the
+    /// access list of the final output. Called before the output is materialized, and only
+    /// if read set recording is enabled for the block executor.
+    fn set_read_set(&self, read_set: HashSet<<Self::Txn as Transaction>::Key>);
+
     /// Will be called once per transaction when the output is ready to be committed.
     /// Ensures that any writes corresponding to materialized deltas and group updates
     /// (recorded in output separately) are incorporated into the transaction output.
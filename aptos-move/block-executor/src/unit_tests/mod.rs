@@ -321,6 +321,60 @@ fn early_skips() {
     run_and_assert(transactions)
 }
 
+#[test]
+fn conflict_report() {
+    // Every transaction reads and writes the same key, so all conflicts are on that key.
+    let hot_key = KeyType(random::<[u8; 32]>(), false);
+    let transactions: Vec<_> = (0..TXN_PER_BLOCK)
+        .map(|_| {
+            MockTransaction::from_behavior(MockIncarnation::<KeyType<[u8; 32]>, MockEvent>::new(
+                vec![hot_key],                        // reads
+                vec![(hot_key, random_value(false))], // writes
+                vec![],
+                vec![],
+                1, // gas
+            ))
+        })
+        .collect();
+
+    let data_view = DeltaDataView::<KeyType<[u8; 32]>> {
+        phantom: PhantomData,
+    };
+    let executor_thread_pool = Arc::new(
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(num_cpus::get())
+            .build()
+            .unwrap(),
+    );
+
+    let (output, report) = BlockExecutor::<
+        MockTransaction<KeyType<[u8; 32]>, MockEvent>,
+        MockTask<KeyType<[u8; 32]>, MockEvent>,
+        DeltaDataView<KeyType<[u8; 32]>>,
+        NoOpTransactionCommitHook<MockOutput<KeyType<[u8; 32]>, MockEvent>, usize>,
+        ExecutableTestType,
+    >::new(num_cpus::get(), executor_thread_pool, None, None)
+    .with_conflict_analytics(10)
+    .execute_transactions_parallel_with_conflict_report((), &transactions, &data_view);
+
+    BaselineOutput::generate(&transactions, None).assert_output(&output);
+
+    let report = report.expect("Conflict analytics are enabled");
+    assert_eq!(report.incarnations.len(), TXN_PER_BLOCK as usize);
+    assert!(report
+        .incarnations
+        .iter()
+        .all(|incarnations| *incarnations >= 1));
+    assert!(report
+        .hot_keys(10)
+        .iter()
+        .all(|(key, count)| *key == hot_key && *count > 0));
+    assert!(report
+        .conflict_graph()
+        .keys()
+        .all(|(reader, writer)| writer < reader));
+}
+
 #[test]
 fn scheduler_tasks() {
     let s = Scheduler::new(5);
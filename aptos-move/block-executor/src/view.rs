@@ -64,6 +64,7 @@ use std::{
         atomic::{AtomicU32, Ordering},
         Arc,
     },
+    time::Instant,
 };
 
 /// A struct which describes the result of the read from the proxy. The client
@@ -404,6 +405,7 @@ fn wait_for_dependency(
     match wait_for.wait_for_dependency(txn_idx, dep_idx) {
         DependencyResult::Dependency(dep_condition) => {
             let _timer = counters::DEPENDENCY_WAIT_SECONDS.start_timer();
+            let wait_start = Instant::now();
             // Wait on a condition variable corresponding to the encountered
             // read dependency. Once the dep_idx finishes re-execution, scheduler
             // will mark the dependency as resolved, and then the txn_idx will be
@@ -423,6 +425,7 @@ fn wait_for_dependency(
             while let DependencyStatus::Unresolved = *dep_resolved {
                 dep_resolved = cvar.wait(dep_resolved).unwrap();
             }
+            wait_for.record_dependency_wait(txn_idx, dep_idx, wait_start.elapsed());
             // dep resolved status is either resolved or execution halted.
             matches!(*dep_resolved, DependencyStatus::Resolved)
         },
@@ -925,10 +928,13 @@ impl<'a, T: Transaction, X: Executable> ViewState<'a, T, X> {
 /// all necessary traits, LatestView is provided to the VM and used to intercept the reads.
 /// In the Sync case, also records captured reads for later validation. latest_txn_idx
 /// must be set according to the latest transaction that the worker was / is executing.
+/// In both cases, the keys read can also be recorded for the access list of the transaction.
 pub(crate) struct LatestView<'a, T: Transaction, S: TStateView<Key = T::Key>, X: Executable> {
     base_view: &'a S,
     latest_view: ViewState<'a, T, X>,
     txn_idx: TxnIndex,
+    // Only set if the keys read are recorded.
+    read_keys: Option<RefCell<HashSet<T::Key>>>,
 }
 
 impl<'a, T: Transaction, S: TStateView<Key = T::Key>, X: Executable> LatestView<'a, T, S, X> {
@@ -941,9 +947,31 @@ impl<'a, T: Transaction, S: TStateView<Key = T::Key>, X: Executable> LatestView<
             base_view,
             latest_view,
             txn_idx,
+            read_keys: None,
         }
     }
 
+    /// Enables recording the keys read through the view, if `enabled`.
+    pub(crate) fn with_read_key_recording(mut self, enabled: bool) -> Self {
+        self.read_keys = enabled.then(|| RefCell::new(HashSet::new()));
+        self
+    }
+
+    fn record_read_key(&self, key: &T::Key) {
+        if let Some(read_keys) = &self.read_keys {
+            let mut read_keys = read_keys.borrow_mut();
+            if !read_keys.contains(key) {
+                read_keys.insert(key.clone());
+            }
+        }
+    }
+
+    /// Drains the keys of all resources, resource groups and modules read so far, if they
+    /// are recorded.
+    pub(crate) fn take_read_keys(&self) -> Option<HashSet<T::Key>> {
+        self.read_keys.as_ref().map(RefCell::take)
+    }
+
     #[cfg(test)]
     fn get_resource_with_layout_read_set_sequential(&self) -> HashSet<T::Key> {
         match &self.latest_view {
@@ -1313,6 +1341,7 @@ impl<'a, T: Transaction, S: TStateView<Key = T::Key>, X: Executable> LatestView<
             "Reading a module {:?} using ResourceView",
             state_key,
         );
+        self.record_read_key(state_key);
 
         let layout = if self.is_delayed_field_optimization_capable() {
             layout
@@ -1449,6 +1478,7 @@ impl<'a, T: Transaction, S: TStateView<Key = T::Key>, X: Executable> TResourceGr
     type ResourceTag = T::Tag;
 
     fn resource_group_size(&self, group_key: &Self::GroupKey) -> anyhow::Result<u64> {
+        self.record_read_key(group_key);
         let mut group_read = match &self.latest_view {
             ViewState::Sync(state) => state.read_group_size(group_key, self.txn_idx)?,
             ViewState::Unsync(state) => state.unsync_map.get_group_size(group_key)?,
@@ -1473,6 +1503,7 @@ impl<'a, T: Transaction, S: TStateView<Key = T::Key>, X: Executable> TResourceGr
         maybe_layout: Option<&Self::Layout>,
     ) -> anyhow::Result<Option<Bytes>> {
         let maybe_layout = maybe_layout.filter(|_| self.is_delayed_field_optimization_capable());
+        self.record_read_key(group_key);
 
         let mut group_read = self
             .latest_view
@@ -1544,6 +1575,7 @@ impl<'a, T: Transaction, S: TStateView<Key = T::Key>, X: Executable> TModuleView
             "Reading a resource {:?} using ModuleView",
             state_key,
         );
+        self.record_read_key(state_key);
 
         match &self.latest_view {
             ViewState::Sync(state) => {
@@ -3018,4 +3050,34 @@ mod test {
         // let data_read = DataRead::Versioned(Ok((1,0)), Arc::new(TransactionWrite::from_state_value(Some(state_value_4))), Some(Arc::new(layout)));
         // assert!(read_set_with_delayed_fields.any(|x| x == (&KeyType::<u32>(4, false), &data_read)));
     }
+
+    #[test]
+    fn test_read_keys_recorded() {
+        let unsync_map = UnsyncMap::new();
+        let counter = RefCell::new(5);
+        let base_view = MockStateView::new(HashMap::from([(
+            KeyType::<u32>(1, false),
+            StateValue::new_legacy(Bytes::from_static(b"value")),
+        )]));
+        let latest_view = LatestView::<TestTransactionType, MockStateView, MockExecutable>::new(
+            &base_view,
+            ViewState::Unsync(SequentialState::new(&unsync_map, 5, &counter, false)),
+            1,
+        );
+        assert_ok!(latest_view.get_resource_state_value(&KeyType::<u32>(1, false), None));
+        assert_none!(latest_view.take_read_keys());
+
+        let latest_view = latest_view.with_read_key_recording(true);
+        assert_ok!(latest_view.get_resource_state_value(&KeyType::<u32>(1, false), None));
+        assert_ok_eq!(
+            latest_view.resource_exists(&KeyType::<u32>(2, false)),
+            false
+        );
+        assert_ok!(latest_view.get_resource_state_value(&KeyType::<u32>(1, false), None));
+        assert_some_eq!(
+            latest_view.take_read_keys(),
+            HashSet::from([KeyType(1, false), KeyType(2, false)])
+        );
+        assert_some_eq!(latest_view.take_read_keys(), HashSet::new());
+    }
 }
@@ -20,7 +20,7 @@ use aptos_crypto::HashValue;
 use aptos_framework::ReleaseBundle;
 use aptos_gas_algebra::DynamicExpression;
 use aptos_gas_meter::{StandardGasAlgebra, StandardGasMeter};
-use aptos_gas_profiling::{GasProfiler, TransactionGasLog};
+use aptos_gas_profiling::{GasProfiler, TableItemLoads, TransactionGasLog};
 use aptos_gas_schedule::{
     InitialGasSchedule, MiscGasParameters, NativeGasParameters, LATEST_GAS_FEATURE_VERSION,
 };
@@ -595,7 +595,8 @@ impl FakeExecutor {
 
         // TODO(Gas): revisit this.
         let resolver = self.data_store.as_move_resolver();
-        let vm = AptosVM::new(&resolver);
+        let table_item_loads = TableItemLoads::default();
+        let vm = AptosVM::new(&resolver).with_table_item_load_hook(table_item_loads.hook());
 
         let (_status, output, gas_profiler) = vm.execute_user_transaction_with_custom_gas_meter(
             &resolver,
@@ -620,7 +621,7 @@ impl FakeExecutor {
                     TransactionPayload::ModuleBundle(..) => unreachable!("not supported"),
                     TransactionPayload::Multisig(..) => unimplemented!("not supported yet"),
                 };
-                Ok(gas_profiler)
+                Ok(gas_profiler.with_table_item_loads(table_item_loads.clone()))
             },
         )?;
 
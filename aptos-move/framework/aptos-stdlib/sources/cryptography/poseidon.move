@@ -0,0 +1,142 @@
+/// This module implements the Poseidon hash function over the scalar fields of the BLS12-381 and BN254 curves.
+///
+/// Poseidon is designed to be cheap to evaluate inside zero-knowledge circuits, so contracts can recompute on chain
+/// the same commitments (e.g., Merkle roots or nullifiers) that a zkSNARK proves statements about. The parameters
+/// follow circomlib: `bn254_fr_hash` matches circomlib's `Poseidon(n)` template and `bls12381_fr_hash` uses the same
+/// construction and round numbers, with constants derived for the BLS12-381 scalar field.
+///
+/// Inputs and outputs are field elements serialized as 32 bytes in little-endian order, matching the serialization
+/// of `Fr` elements in `aptos_std::bls12381_algebra` and `aptos_std::bn254_algebra`.
+module aptos_std::poseidon {
+    use std::features;
+
+    //
+    // Constants
+    //
+
+    /// A newly-added native function is not yet enabled.
+    const E_NATIVE_FUN_NOT_AVAILABLE: u64 = 1;
+
+    /// The number of inputs must be between 1 and `MAX_INPUTS`.
+    const E_WRONG_NUMBER_OF_INPUTS: u64 = 2;
+
+    /// An input is not the canonical 32-byte little-endian encoding of a field element.
+    const E_NON_CANONICAL_INPUT: u64 = 3;
+
+    /// The maximum number of field elements that can be hashed at once.
+    const MAX_INPUTS: u64 = 16;
+
+    //
+    // Functions
+    //
+
+    /// Returns the Poseidon hash of between 1 and `MAX_INPUTS` elements of the BLS12-381 scalar field.
+    ///
+    /// Aborts with `E_NON_CANONICAL_INPUT` if an input is not a canonical field element.
+    public fun bls12381_fr_hash(inputs: vector<vector<u8>>): vector<u8> {
+        if(!features::poseidon_natives_enabled()) {
+            abort(std::error::invalid_state(E_NATIVE_FUN_NOT_AVAILABLE))
+        };
+        assert_num_inputs(&inputs);
+
+        bls12381_fr_hash_internal(inputs)
+    }
+
+    /// Returns the Poseidon hash of between 1 and `MAX_INPUTS` elements of the BN254 scalar field.
+    ///
+    /// Aborts with `E_NON_CANONICAL_INPUT` if an input is not a canonical field element.
+    public fun bn254_fr_hash(inputs: vector<vector<u8>>): vector<u8> {
+        if(!features::poseidon_natives_enabled()) {
+            abort(std::error::invalid_state(E_NATIVE_FUN_NOT_AVAILABLE))
+        };
+        assert_num_inputs(&inputs);
+
+        bn254_fr_hash_internal(inputs)
+    }
+
+    fun assert_num_inputs(inputs: &vector<vector<u8>>) {
+        let num_inputs = std::vector::length(inputs);
+        assert!(num_inputs > 0 && num_inputs <= MAX_INPUTS, std::error::invalid_argument(E_WRONG_NUMBER_OF_INPUTS));
+    }
+
+    //
+    // Private native functions
+    //
+
+    native fun bls12381_fr_hash_internal(inputs: vector<vector<u8>>): vector<u8>;
+
+    native fun bn254_fr_hash_internal(inputs: vector<vector<u8>>): vector<u8>;
+
+    //
+    // Testing
+    //
+
+    #[test_only]
+    const ONE: vector<u8> = x"0100000000000000000000000000000000000000000000000000000000000000";
+
+    #[test_only]
+    const TWO: vector<u8> = x"0200000000000000000000000000000000000000000000000000000000000000";
+
+    #[test(fx = @aptos_std)]
+    fun bn254_fr_hash_test(fx: signer) {
+        // We need to enable the feature in order for the native call to be allowed.
+        features::change_feature_flags(&fx, vector[features::get_poseidon_natives_feature()], vector[]);
+
+        // From circomlib's `poseidon` test vectors, serialized in little-endian order.
+        assert!(
+            bn254_fr_hash(vector[ONE]) == x"33018202c57d898b84338b16d1a4960e133c6a4d656cfec1bd62a9ea00611729",
+            1
+        );
+        assert!(
+            bn254_fr_hash(vector[ONE, TWO]) == x"9a1817447a60199e51453274f217362acfe962966b4cf63d4190d6e7f5c05c11",
+            2
+        );
+    }
+
+    #[test(fx = @aptos_std)]
+    fun bls12381_fr_hash_test(fx: signer) {
+        features::change_feature_flags(&fx, vector[features::get_poseidon_natives_feature()], vector[]);
+
+        // Cross-checked against the Rust implementation in `aptos_crypto::poseidon`.
+        assert!(
+            bls12381_fr_hash(vector[ONE]) == x"ea6ce53dad0362cf20a921c8f2169942947b020e925f1a0d44c6db016b6fa649",
+            1
+        );
+        assert!(
+            bls12381_fr_hash(vector[ONE, TWO]) == x"8aa7d27d314e4bcbe4e9182cbe6671d6c9f5988c1ead5355a046c20f4219ce28",
+            2
+        );
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 0x030001, location = Self)]
+    fun hash_fails_when_feature_disabled() {
+        bn254_fr_hash(vector[ONE]);
+    }
+
+    #[test(fx = @aptos_std)]
+    #[expected_failure(abort_code = 0x010002, location = Self)]
+    fun hash_fails_on_no_inputs(fx: signer) {
+        features::change_feature_flags(&fx, vector[features::get_poseidon_natives_feature()], vector[]);
+        bls12381_fr_hash(vector[]);
+    }
+
+    #[test(fx = @aptos_std)]
+    #[expected_failure(abort_code = 0x010002, location = Self)]
+    fun hash_fails_on_too_many_inputs(fx: signer) {
+        features::change_feature_flags(&fx, vector[features::get_poseidon_natives_feature()], vector[]);
+        let inputs = vector[];
+        while (std::vector::length(&inputs) <= MAX_INPUTS) {
+            std::vector::push_back(&mut inputs, ONE);
+        };
+        bn254_fr_hash(inputs);
+    }
+
+    #[test(fx = @aptos_std)]
+    #[expected_failure(abort_code = 0x010003, location = Self)]
+    fun hash_fails_on_non_canonical_input(fx: signer) {
+        features::change_feature_flags(&fx, vector[features::get_poseidon_natives_feature()], vector[]);
+        // The BN254 scalar field modulus itself.
+        bn254_fr_hash(vector[x"010000f093f5e1439170b97948e833285d588181b64550b829a031e1724e6430"]);
+    }
+}
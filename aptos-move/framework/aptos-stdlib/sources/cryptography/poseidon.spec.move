@@ -0,0 +1,19 @@
+spec aptos_std::poseidon {
+    spec module {
+        /// `spec_bls12381_fr_hash_internal` is not assumed to be injective, since it compresses field elements.
+        fun spec_bls12381_fr_hash_internal(inputs: vector<vector<u8>>): vector<u8>;
+
+        /// `spec_bn254_fr_hash_internal` is not assumed to be injective, since it compresses field elements.
+        fun spec_bn254_fr_hash_internal(inputs: vector<vector<u8>>): vector<u8>;
+    }
+
+    spec bls12381_fr_hash_internal(inputs: vector<vector<u8>>): vector<u8> {
+        pragma opaque;
+        ensures [abstract] result == spec_bls12381_fr_hash_internal(inputs);
+    }
+
+    spec bn254_fr_hash_internal(inputs: vector<vector<u8>>): vector<u8> {
+        pragma opaque;
+        ensures [abstract] result == spec_bn254_fr_hash_internal(inputs);
+    }
+}
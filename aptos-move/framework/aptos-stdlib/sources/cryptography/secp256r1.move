@@ -0,0 +1,107 @@
+/// This module implements ECDSA signatures based on the NIST P-256 elliptic curve (a.k.a. secp256r1 or prime256v1).
+///
+/// These are the signatures produced by WebAuthn passkeys and by most hardware secure enclaves.
+module aptos_std::secp256r1 {
+    /// An error occurred while deserializing, for example due to wrong input size.
+    const E_DESERIALIZE: u64 = 1;
+
+    /// The size of a secp256r1-based ECDSA public key, in bytes.
+    const RAW_PUBLIC_KEY_NUM_BYTES: u64 = 64;
+
+    /// The size of a secp256r1-based ECDSA signature, in bytes.
+    const SIGNATURE_NUM_BYTES: u64 = 64;
+
+    /// A 64-byte ECDSA public key, i.e., the SEC1 uncompressed encoding of a point without its leading 0x04 tag.
+    struct ECDSARawPublicKey has copy, drop, store {
+        bytes: vector<u8>
+    }
+
+    /// A 64-byte ECDSA signature, i.e., the big-endian encoding of `r` followed by `s`.
+    struct ECDSASignature has copy, drop, store {
+        bytes: vector<u8>
+    }
+
+    /// Constructs an ECDSASignature struct from the given 64 bytes.
+    public fun ecdsa_signature_from_bytes(bytes: vector<u8>): ECDSASignature {
+        assert!(std::vector::length(&bytes) == SIGNATURE_NUM_BYTES, std::error::invalid_argument(E_DESERIALIZE));
+        ECDSASignature { bytes }
+    }
+
+    /// Constructs an ECDSARawPublicKey struct, given a 64-byte raw representation.
+    public fun ecdsa_raw_public_key_from_64_bytes(bytes: vector<u8>): ECDSARawPublicKey {
+        assert!(std::vector::length(&bytes) == RAW_PUBLIC_KEY_NUM_BYTES, std::error::invalid_argument(E_DESERIALIZE));
+        ECDSARawPublicKey { bytes }
+    }
+
+    /// Serializes an ECDSARawPublicKey struct to 64-bytes.
+    public fun ecdsa_raw_public_key_to_bytes(pk: &ECDSARawPublicKey): vector<u8> {
+        pk.bytes
+    }
+
+    /// Serializes an ECDSASignature struct to 64-bytes.
+    public fun ecdsa_signature_to_bytes(sig: &ECDSASignature): vector<u8> {
+        sig.bytes
+    }
+
+    /// Returns `true` if `signature` is a valid secp256r1 ECDSA signature on `message` under `public_key`.
+    ///
+    /// The `message` is hashed with SHA2-256 before verification, as mandated by ES256. Non-canonical signatures
+    /// (i.e., with `s` greater than half the group order) as well as public keys that are not on the curve are rejected.
+    public fun ecdsa_verify(
+        signature: &ECDSASignature,
+        public_key: &ECDSARawPublicKey,
+        message: vector<u8>,
+    ): bool {
+        ecdsa_verify_internal(signature.bytes, public_key.bytes, message)
+    }
+
+    //
+    // Native functions
+    //
+
+    /// Returns `true` if `signature` verifies on `message` under `public_key` and returns `false` otherwise.
+    native fun ecdsa_verify_internal(
+        signature: vector<u8>,
+        public_key: vector<u8>,
+        message: vector<u8>
+    ): bool;
+
+    //
+    // Tests
+    //
+
+    #[test_only]
+    const TEST_PUBLIC_KEY: vector<u8> = x"d8cd12ea5c67f2f8a00c1124893edcfa6754c4d6cede6be13bdf2295c810a97fa5a89d2d2a360c0ca9a4d6c7c9ed4b28d3e199d6627f2e696d689c310a5b0f48";
+
+    #[test_only]
+    const TEST_SIGNATURE: vector<u8> = x"d0dea7309f1f45ea1470e4fd1aeed069569fef9b6d2bf00c1c8f4f87a667b1d227c049a1dd466d266268bfe9a4720a1e41720876f401c23af672ae6c2de34bab";
+
+    #[test]
+    /// Test on a valid secp256r1 ECDSA signature created using sk = x"0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef"
+    fun test_ecdsa_verify() {
+        let pk = ecdsa_raw_public_key_from_64_bytes(TEST_PUBLIC_KEY);
+        let sig = ecdsa_signature_from_bytes(TEST_SIGNATURE);
+        assert!(ecdsa_verify(&sig, &pk, b"test aptos secp256r1"), 1);
+
+        // Wrong message
+        assert!(!ecdsa_verify(&sig, &pk, b"test aptos secp256k1"), 2);
+
+        // Flipped bits; signature becomes invalid
+        let sig = ecdsa_signature_from_bytes(x"ffdea7309f1f45ea1470e4fd1aeed069569fef9b6d2bf00c1c8f4f87a667b1d227c049a1dd466d266268bfe9a4720a1e41720876f401c23af672ae6c2de34bab");
+        assert!(!ecdsa_verify(&sig, &pk, b"test aptos secp256r1"), 3);
+
+        // The malleated (high-s) version of the valid signature is rejected
+        let sig = ecdsa_signature_from_bytes(x"d0dea7309f1f45ea1470e4fd1aeed069569fef9b6d2bf00c1c8f4f87a667b1d2d83fb65d22b992da9d9740165b8df5e17b74f236b315dc49fd471c56ce7fd9a6");
+        assert!(!ecdsa_verify(&sig, &pk, b"test aptos secp256r1"), 4);
+
+        // A point that is not on the curve is rejected
+        let pk = ecdsa_raw_public_key_from_64_bytes(x"d8cd12ea5c67f2f8a00c1124893edcfa6754c4d6cede6be13bdf2295c810a97fa5a89d2d2a360c0ca9a4d6c7c9ed4b28d3e199d6627f2e696d689c310a5b0f49");
+        assert!(!ecdsa_verify(&ecdsa_signature_from_bytes(TEST_SIGNATURE), &pk, b"test aptos secp256r1"), 5);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 0x10001, location = Self)]
+    fun test_ecdsa_signature_from_bytes_wrong_size() {
+        ecdsa_signature_from_bytes(x"d0dea7");
+    }
+}
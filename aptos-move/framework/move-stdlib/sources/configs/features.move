@@ -320,6 +320,39 @@ module std::features {
         is_enabled(COMMISSION_CHANGE_DELEGATION_POOL)
     }
 
+    /// Whether the generic algebra implementation for BN254 operations are enabled.
+    ///
+    /// Lifetime: transient
+    const BN254_STRUCTURES: u64 = 43;
+
+    public fun get_bn254_structures_feature(): u64 { BN254_STRUCTURES }
+
+    public fun bn254_structures_enabled(): bool acquires Features {
+        is_enabled(BN254_STRUCTURES)
+    }
+
+    /// Whether transactions may be authenticated with WebAuthn (passkey) assertions over secp256r1 keys.
+    ///
+    /// Lifetime: transient
+    const WEBAUTHN_SIGNATURE: u64 = 44;
+
+    public fun get_webauthn_signature_feature(): u64 { WEBAUTHN_SIGNATURE }
+
+    public fun webauthn_signature_enabled(): bool acquires Features {
+        is_enabled(WEBAUTHN_SIGNATURE)
+    }
+
+    /// Whether the Poseidon hash natives over the BLS12-381 and BN254 scalar fields are enabled.
+    ///
+    /// Lifetime: transient
+    const POSEIDON_NATIVES: u64 = 45;
+
+    public fun get_poseidon_natives_feature(): u64 { POSEIDON_NATIVES }
+
+    public fun poseidon_natives_enabled(): bool acquires Features {
+        is_enabled(POSEIDON_NATIVES)
+    }
+
     // ============================================================================================
     // Feature Flag Implementation
 
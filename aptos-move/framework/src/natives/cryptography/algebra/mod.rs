@@ -25,6 +25,7 @@ use arithmetics::{
     inv::inv_internal,
     scalar_mul::{multi_scalar_mul_internal, scalar_mul_internal},
 };
+use ark_ec::{pairing::Pairing, AffineRepr};
 use ark_serialize::CanonicalDeserialize;
 use better_any::{Tid, TidAble};
 use move_binary_format::errors::PartialVMError;
@@ -58,6 +59,13 @@ pub enum Structure {
     BLS12381G2,
     BLS12381Gt,
     BLS12381Fr,
+
+    BN254Fr,
+    BN254Fq,
+    BN254Fq12,
+    BN254G1,
+    BN254G2,
+    BN254Gt,
 }
 
 impl TryFrom<TypeTag> for Structure {
@@ -70,6 +78,13 @@ impl TryFrom<TypeTag> for Structure {
             "0x1::bls12381_algebra::G1" => Ok(Structure::BLS12381G1),
             "0x1::bls12381_algebra::G2" => Ok(Structure::BLS12381G2),
             "0x1::bls12381_algebra::Gt" => Ok(Structure::BLS12381Gt),
+
+            "0x1::bn254_algebra::Fr" => Ok(Structure::BN254Fr),
+            "0x1::bn254_algebra::Fq" => Ok(Structure::BN254Fq),
+            "0x1::bn254_algebra::Fq12" => Ok(Structure::BN254Fq12),
+            "0x1::bn254_algebra::G1" => Ok(Structure::BN254G1),
+            "0x1::bn254_algebra::G2" => Ok(Structure::BN254G2),
+            "0x1::bn254_algebra::Gt" => Ok(Structure::BN254Gt),
             _ => Err(()),
         }
     }
@@ -94,6 +109,17 @@ pub enum SerializationFormat {
     BLS12381Gt,
     BLS12381FrLsb,
     BLS12381FrMsb,
+
+    BN254G1Uncompressed,
+    BN254G1Compressed,
+    BN254G2Uncompressed,
+    BN254G2Compressed,
+    BN254Gt,
+    BN254FrLsb,
+    BN254FrMsb,
+    BN254FqLsb,
+    BN254FqMsb,
+    BN254Fq12LscLsb,
 }
 
 impl TryFrom<TypeTag> for SerializationFormat {
@@ -115,6 +141,17 @@ impl TryFrom<TypeTag> for SerializationFormat {
             "0x1::bls12381_algebra::FormatGt" => Ok(SerializationFormat::BLS12381Gt),
             "0x1::bls12381_algebra::FormatFrLsb" => Ok(SerializationFormat::BLS12381FrLsb),
             "0x1::bls12381_algebra::FormatFrMsb" => Ok(SerializationFormat::BLS12381FrMsb),
+
+            "0x1::bn254_algebra::FormatG1Uncompr" => Ok(SerializationFormat::BN254G1Uncompressed),
+            "0x1::bn254_algebra::FormatG1Compr" => Ok(SerializationFormat::BN254G1Compressed),
+            "0x1::bn254_algebra::FormatG2Uncompr" => Ok(SerializationFormat::BN254G2Uncompressed),
+            "0x1::bn254_algebra::FormatG2Compr" => Ok(SerializationFormat::BN254G2Compressed),
+            "0x1::bn254_algebra::FormatGt" => Ok(SerializationFormat::BN254Gt),
+            "0x1::bn254_algebra::FormatFrLsb" => Ok(SerializationFormat::BN254FrLsb),
+            "0x1::bn254_algebra::FormatFrMsb" => Ok(SerializationFormat::BN254FrMsb),
+            "0x1::bn254_algebra::FormatFqLsb" => Ok(SerializationFormat::BN254FqLsb),
+            "0x1::bn254_algebra::FormatFqMsb" => Ok(SerializationFormat::BN254FqMsb),
+            "0x1::bn254_algebra::FormatFq12LscLsb" => Ok(SerializationFormat::BN254Fq12LscLsb),
             _ => Err(()),
         }
     }
@@ -209,6 +246,12 @@ fn feature_flag_from_structure(structure_opt: Option<Structure>) -> Option<Featu
         | Some(Structure::BLS12381G1)
         | Some(Structure::BLS12381G2)
         | Some(Structure::BLS12381Gt) => Some(FeatureFlag::BLS12_381_STRUCTURES),
+        Some(Structure::BN254Fr)
+        | Some(Structure::BN254Fq)
+        | Some(Structure::BN254Fq12)
+        | Some(Structure::BN254G1)
+        | Some(Structure::BN254G2)
+        | Some(Structure::BN254Gt) => Some(FeatureFlag::BN254_STRUCTURES),
         _ => None,
     }
 }
@@ -259,6 +302,30 @@ static BLS12381_Q12_LENDIAN: Lazy<Vec<u8>> = Lazy::new(|| {
     hex::decode("1175f55da544c7625f8ccb1360e2b1d3ca40747811c8f5ed04440afe232b476c0215676aec05f2a44ac2da6b6d1b7cff075e7b2a587e0aab601a8d3db4f0d29906e5e4d0d78119f396d5a59f0f8d1ca8bca62540be6ab9c12d0ca00de1f311f106278d000e55a393c9766a74e0d08a298450f60d7e666575e3354bf14b8731f4e721c0c180a5ed55c2f8f51f815baecbf96b5fc717eb58ac161a27d1d5f2bdc1a079609b9d6449165b2466b32a01eac7992a1ea0cac2f223cde1d56f9bbccc67afe44621daf858df3fc0eb837818f3e42ab3e131ce4e492efa63c108e6ef91c29ed63b3045baebcb0ab8d203c7f558beaffccba31b12aca7f54b58d0c28340e4fdb3c7c94fe9c4fef9d640ff2fcff02f1748416cbed0981fbff49f0e39eaf8a30273e67ed851944d33d6a593ef5ddcd62da84568822a6045b633bf6a513b3cfe8f9de13e76f8dcbd915980dec205eab6a5c0c72dcebd9afff1d25509ddbf33f8e24131fbd74cda93336514340cf8036b66b09ed9e6a6ac37e22fb3ac407e321beae8cd9fe74c8aaeb4edaa9a7272848fc623f6fe835a2e647379f547fc5ec6371318a85bfa60009cb20ccbb8a467492988a87633c14c0324ba0d0c3e1798ed29c8494cea35023746da05e35d184b4a301d5b2238d665495c6318b5af8653758008952d06cb9e62487b196d64383c73c06d6e1cccdf9b3ce8f95679e7050d949004a55f4ccf95b2552880ae36d1f7e09504d2338316d87d14a064511a295d768113e301bdf9d4383a8be32192d3f2f3b2de14181c73839a7cb4af5301").unwrap()
 });
 
+static BN254_GT_GENERATOR: Lazy<ark_bn254::Fq12> = Lazy::new(|| {
+    ark_bn254::Bn254::pairing(
+        ark_bn254::G1Affine::generator(),
+        ark_bn254::G2Affine::generator(),
+    )
+    .0
+});
+
+static BN254_R_LENDIAN: Lazy<Vec<u8>> = Lazy::new(|| {
+    hex::decode("010000f093f5e1439170b97948e833285d588181b64550b829a031e1724e6430").unwrap()
+});
+
+static BN254_R_SCALAR: Lazy<ark_ff::BigInteger256> = Lazy::new(|| {
+    ark_ff::BigInteger256::deserialize_uncompressed(BN254_R_LENDIAN.as_slice()).unwrap()
+});
+
+static BN254_Q_LENDIAN: Lazy<Vec<u8>> = Lazy::new(|| {
+    hex::decode("47fd7cd8168c203c8dca7168916a81975d588181b64550b829a031e1724e6430").unwrap()
+});
+
+static BN254_Q12_LENDIAN: Lazy<Vec<u8>> = Lazy::new(|| {
+    hex::decode("21f186cad2e2d4c1dbaf8a066b0ebf41f734e3f859b1c523a6c1f4d457413fdbe3cd44add090135d3ae519acc30ee3bdb6bfac6573b767e975b18a77d53cdcddebf3672c74da9d1409d51b2b2db7ff000d59e3aa7cf09220159f925c86b65459ca6558c4eaa703bf45d85030ff85cc6a879c7e2c4034f7045faf20e4d3dcfffac5eb6634c3e7b939b69b2be70bdf6b9a4680297839b4e3a48cd746bd4d0ea82749ffb7e71bd9b3fb10aa684d71e6adab1250b1d8604d91b51c76c256a50b60ddba2f52b6cc853ac926c6ea86d09d400b2f2330e5c8e92e38905ba50a50c9e11cd979c284bf1327ccdc051a6da1a4a7eac5cec16757a27a1a2311bedd108a9b21ac0814269e7523a5dd3a1f5f4767ffe504a6cb3994fb0ec98d5cd5da00b9cb1188a85f2aa871ecb8a0f9d64141f1ccd2699c138e0ef9ac4d8d6a692b29db0f38b60eb08426ab46109fbab9a5221bb44dd338aafebcc4e6c10dd933597f3ff44ba41d04e82871447f3a759cfa9397c22c0c77f13618dfb65adc8aacf008").unwrap()
+});
+
 pub fn make_all(
     builder: &SafeNativeBuilder,
 ) -> impl Iterator<Item = (String, NativeFunction)> + '_ {
@@ -30,6 +30,9 @@ fn feature_flag_of_pairing(
         (Some(Structure::BLS12381G1), Some(Structure::BLS12381G2), Some(Structure::BLS12381Gt)) => {
             Some(FeatureFlag::BLS12_381_STRUCTURES)
         },
+        (Some(Structure::BN254G1), Some(Structure::BN254G2), Some(Structure::BN254Gt)) => {
+            Some(FeatureFlag::BN254_STRUCTURES)
+        },
         _ => None,
     }
 }
@@ -41,6 +44,88 @@ macro_rules! abort_unless_pairing_enabled {
     };
 }
 
+macro_rules! ark_multi_pairing_internal {
+    (
+        $context:expr,
+        $args:ident,
+        $pairing:ty,
+        $g1_projective:ty,
+        $g2_projective:ty,
+        $g1_proj_to_affine_gas:expr,
+        $g2_proj_to_affine_gas:expr,
+        $pairing_base_gas:expr,
+        $pairing_per_pair_gas:expr
+    ) => {{
+        let g2_element_handles = safely_pop_arg!($args, Vec<u64>);
+        let g1_element_handles = safely_pop_arg!($args, Vec<u64>);
+        let num_entries = g1_element_handles.len();
+        if num_entries != g2_element_handles.len() {
+            return Err(SafeNativeError::Abort {
+                abort_code: MOVE_ABORT_CODE_INPUT_VECTOR_SIZES_NOT_MATCHING,
+            });
+        }
+
+        $context.charge($g1_proj_to_affine_gas.per::<Arg>() * NumArgs::from(num_entries as u64))?;
+        let mut g1_elements_affine = Vec::with_capacity(num_entries);
+        for handle in g1_element_handles {
+            safe_borrow_element!($context, handle as usize, $g1_projective, ptr, element);
+            g1_elements_affine.push(element.into_affine());
+        }
+
+        $context.charge($g2_proj_to_affine_gas.per::<Arg>() * NumArgs::from(num_entries as u64))?;
+        let mut g2_elements_affine = Vec::with_capacity(num_entries);
+        for handle in g2_element_handles {
+            safe_borrow_element!($context, handle as usize, $g2_projective, ptr, element);
+            g2_elements_affine.push(element.into_affine());
+        }
+
+        $context.charge(
+            $pairing_base_gas + $pairing_per_pair_gas * NumArgs::from(num_entries as u64),
+        )?;
+        let new_element = <$pairing>::multi_pairing(g1_elements_affine, g2_elements_affine).0;
+        let new_handle = store_element!($context, new_element)?;
+        Ok(smallvec![Value::u64(new_handle as u64)])
+    }};
+}
+
+macro_rules! ark_pairing_internal {
+    (
+        $context:expr,
+        $args:ident,
+        $pairing:ty,
+        $g1_projective:ty,
+        $g2_projective:ty,
+        $g1_proj_to_affine_gas:expr,
+        $g2_proj_to_affine_gas:expr,
+        $pairing_gas:expr
+    ) => {{
+        let g2_element_handle = safely_pop_arg!($args, u64) as usize;
+        let g1_element_handle = safely_pop_arg!($args, u64) as usize;
+        safe_borrow_element!(
+            $context,
+            g1_element_handle,
+            $g1_projective,
+            g1_element_ptr,
+            g1_element
+        );
+        $context.charge($g1_proj_to_affine_gas)?;
+        let g1_element_affine = g1_element.into_affine();
+        safe_borrow_element!(
+            $context,
+            g2_element_handle,
+            $g2_projective,
+            g2_element_ptr,
+            g2_element
+        );
+        $context.charge($g2_proj_to_affine_gas)?;
+        let g2_element_affine = g2_element.into_affine();
+        $context.charge($pairing_gas)?;
+        let new_element = <$pairing>::pairing(g1_element_affine, g2_element_affine).0;
+        let new_handle = store_element!($context, new_element)?;
+        Ok(smallvec![Value::u64(new_handle as u64)])
+    }};
+}
+
 pub fn multi_pairing_internal(
     context: &mut SafeNativeContext,
     ty_args: Vec<Type>,
@@ -104,6 +189,19 @@ pub fn multi_pairing_internal(
             let new_handle = store_element!(context, new_element)?;
             Ok(smallvec![Value::u64(new_handle as u64)])
         },
+        (Some(Structure::BN254G1), Some(Structure::BN254G2), Some(Structure::BN254Gt)) => {
+            ark_multi_pairing_internal!(
+                context,
+                args,
+                ark_bn254::Bn254,
+                ark_bn254::G1Projective,
+                ark_bn254::G2Projective,
+                ALGEBRA_ARK_BN254_G1_PROJ_TO_AFFINE,
+                ALGEBRA_ARK_BN254_G2_PROJ_TO_AFFINE,
+                ALGEBRA_ARK_BN254_MULTI_PAIRING_BASE,
+                ALGEBRA_ARK_BN254_MULTI_PAIRING_PER_PAIR
+            )
+        },
         _ => Err(SafeNativeError::Abort {
             abort_code: MOVE_ABORT_CODE_NOT_IMPLEMENTED,
         }),
@@ -148,6 +246,18 @@ pub fn pairing_internal(
             let new_handle = store_element!(context, new_element)?;
             Ok(smallvec![Value::u64(new_handle as u64)])
         },
+        (Some(Structure::BN254G1), Some(Structure::BN254G2), Some(Structure::BN254Gt)) => {
+            ark_pairing_internal!(
+                context,
+                args,
+                ark_bn254::Bn254,
+                ark_bn254::G1Projective,
+                ark_bn254::G2Projective,
+                ALGEBRA_ARK_BN254_G1_PROJ_TO_AFFINE,
+                ALGEBRA_ARK_BN254_G2_PROJ_TO_AFFINE,
+                ALGEBRA_ARK_BN254_PAIRING
+            )
+        },
         _ => Err(SafeNativeError::Abort {
             abort_code: MOVE_ABORT_CODE_NOT_IMPLEMENTED,
         }),
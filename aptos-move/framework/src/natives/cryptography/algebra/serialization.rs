@@ -4,7 +4,7 @@ use crate::{
     abort_unless_feature_flag_enabled,
     natives::cryptography::algebra::{
         abort_invariant_violated, AlgebraContext, SerializationFormat, Structure,
-        BLS12381_R_SCALAR, E_TOO_MUCH_MEMORY_USED, MEMORY_LIMIT_IN_BYTES,
+        BLS12381_R_SCALAR, BN254_R_SCALAR, E_TOO_MUCH_MEMORY_USED, MEMORY_LIMIT_IN_BYTES,
         MOVE_ABORT_CODE_NOT_IMPLEMENTED,
     },
     safe_borrow_element, store_element, structure_from_ty_arg,
@@ -37,6 +37,16 @@ pub fn feature_flag_of_serialization_format(
         | Some(SerializationFormat::BLS12381G2Uncompressed)
         | Some(SerializationFormat::BLS12381G2Compressed)
         | Some(SerializationFormat::BLS12381Gt) => Some(FeatureFlag::BLS12_381_STRUCTURES),
+        Some(SerializationFormat::BN254FrLsb)
+        | Some(SerializationFormat::BN254FrMsb)
+        | Some(SerializationFormat::BN254FqLsb)
+        | Some(SerializationFormat::BN254FqMsb)
+        | Some(SerializationFormat::BN254Fq12LscLsb)
+        | Some(SerializationFormat::BN254G1Uncompressed)
+        | Some(SerializationFormat::BN254G1Compressed)
+        | Some(SerializationFormat::BN254G2Uncompressed)
+        | Some(SerializationFormat::BN254G2Compressed)
+        | Some(SerializationFormat::BN254Gt) => Some(FeatureFlag::BN254_STRUCTURES),
         _ => None,
     }
 }
@@ -55,6 +65,33 @@ macro_rules! format_from_ty_arg {
     }};
 }
 
+macro_rules! ark_serialize_internal {
+    ($context:expr, $args:ident, $ark_typ:ty, $ark_ser_func:ident, $gas:expr) => {{
+        let handle = safely_pop_arg!($args, u64) as usize;
+        safe_borrow_element!($context, handle, $ark_typ, element_ptr, element);
+        let mut buf = vec![];
+        $context.charge($gas)?;
+        element
+            .$ark_ser_func(&mut buf)
+            .map_err(|_e| abort_invariant_violated())?;
+        buf
+    }};
+}
+
+macro_rules! ark_ec_point_serialize_internal {
+    ($context:expr, $args:ident, $typ:ty, $ser_func:ident, $gas:expr) => {{
+        let handle = safely_pop_arg!($args, u64) as usize;
+        safe_borrow_element!($context, handle, $typ, element_ptr, element);
+        let element_affine = element.into_affine();
+        let mut buf = Vec::new();
+        $context.charge($gas)?;
+        element_affine
+            .$ser_func(&mut buf)
+            .map_err(|_e| abort_invariant_violated())?;
+        buf
+    }};
+}
+
 pub fn serialize_internal(
     context: &mut SafeNativeContext,
     ty_args: Vec<Type>,
@@ -174,6 +211,108 @@ pub fn serialize_internal(
                 .map_err(|_e| abort_invariant_violated())?;
             Ok(smallvec![Value::vector_u8(buf)])
         },
+        (Some(Structure::BN254Fr), Some(SerializationFormat::BN254FrLsb)) => {
+            let buf = ark_serialize_internal!(
+                context,
+                args,
+                ark_bn254::Fr,
+                serialize_uncompressed,
+                ALGEBRA_ARK_BN254_FR_SERIALIZE
+            );
+            Ok(smallvec![Value::vector_u8(buf)])
+        },
+        (Some(Structure::BN254Fr), Some(SerializationFormat::BN254FrMsb)) => {
+            let mut buf = ark_serialize_internal!(
+                context,
+                args,
+                ark_bn254::Fr,
+                serialize_uncompressed,
+                ALGEBRA_ARK_BN254_FR_SERIALIZE
+            );
+            buf.reverse();
+            Ok(smallvec![Value::vector_u8(buf)])
+        },
+        (Some(Structure::BN254Fq), Some(SerializationFormat::BN254FqLsb)) => {
+            let buf = ark_serialize_internal!(
+                context,
+                args,
+                ark_bn254::Fq,
+                serialize_uncompressed,
+                ALGEBRA_ARK_BN254_FQ_SERIALIZE
+            );
+            Ok(smallvec![Value::vector_u8(buf)])
+        },
+        (Some(Structure::BN254Fq), Some(SerializationFormat::BN254FqMsb)) => {
+            let mut buf = ark_serialize_internal!(
+                context,
+                args,
+                ark_bn254::Fq,
+                serialize_uncompressed,
+                ALGEBRA_ARK_BN254_FQ_SERIALIZE
+            );
+            buf.reverse();
+            Ok(smallvec![Value::vector_u8(buf)])
+        },
+        (Some(Structure::BN254Fq12), Some(SerializationFormat::BN254Fq12LscLsb)) => {
+            let buf = ark_serialize_internal!(
+                context,
+                args,
+                ark_bn254::Fq12,
+                serialize_uncompressed,
+                ALGEBRA_ARK_BN254_FQ12_SERIALIZE
+            );
+            Ok(smallvec![Value::vector_u8(buf)])
+        },
+        (Some(Structure::BN254G1), Some(SerializationFormat::BN254G1Uncompressed)) => {
+            let buf = ark_ec_point_serialize_internal!(
+                context,
+                args,
+                ark_bn254::G1Projective,
+                serialize_uncompressed,
+                ALGEBRA_ARK_BN254_G1_AFFINE_SERIALIZE_UNCOMP
+            );
+            Ok(smallvec![Value::vector_u8(buf)])
+        },
+        (Some(Structure::BN254G1), Some(SerializationFormat::BN254G1Compressed)) => {
+            let buf = ark_ec_point_serialize_internal!(
+                context,
+                args,
+                ark_bn254::G1Projective,
+                serialize_compressed,
+                ALGEBRA_ARK_BN254_G1_AFFINE_SERIALIZE_COMP
+            );
+            Ok(smallvec![Value::vector_u8(buf)])
+        },
+        (Some(Structure::BN254G2), Some(SerializationFormat::BN254G2Uncompressed)) => {
+            let buf = ark_ec_point_serialize_internal!(
+                context,
+                args,
+                ark_bn254::G2Projective,
+                serialize_uncompressed,
+                ALGEBRA_ARK_BN254_G2_AFFINE_SERIALIZE_UNCOMP
+            );
+            Ok(smallvec![Value::vector_u8(buf)])
+        },
+        (Some(Structure::BN254G2), Some(SerializationFormat::BN254G2Compressed)) => {
+            let buf = ark_ec_point_serialize_internal!(
+                context,
+                args,
+                ark_bn254::G2Projective,
+                serialize_compressed,
+                ALGEBRA_ARK_BN254_G2_AFFINE_SERIALIZE_COMP
+            );
+            Ok(smallvec![Value::vector_u8(buf)])
+        },
+        (Some(Structure::BN254Gt), Some(SerializationFormat::BN254Gt)) => {
+            let buf = ark_serialize_internal!(
+                context,
+                args,
+                ark_bn254::Fq12,
+                serialize_uncompressed,
+                ALGEBRA_ARK_BN254_FQ12_SERIALIZE
+            );
+            Ok(smallvec![Value::vector_u8(buf)])
+        },
         _ => Err(SafeNativeError::Abort {
             abort_code: MOVE_ABORT_CODE_NOT_IMPLEMENTED,
         }),
@@ -350,6 +489,148 @@ pub fn deserialize_internal(
                 _ => Ok(smallvec![Value::bool(false), Value::u64(0)]),
             }
         },
+        (Some(Structure::BN254Fr), Some(SerializationFormat::BN254FrLsb)) => {
+            // Valid BN254FrLsb serialization should be 32-byte.
+            if bytes.len() != 32 {
+                return Ok(smallvec![Value::bool(false), Value::u64(0)]);
+            }
+            ark_deserialize_internal!(
+                context,
+                bytes,
+                ark_bn254::Fr,
+                deserialize_uncompressed,
+                ALGEBRA_ARK_BN254_FR_DESER
+            )
+        },
+        (Some(Structure::BN254Fr), Some(SerializationFormat::BN254FrMsb)) => {
+            // Valid BN254FrMsb serialization should be 32-byte.
+            if bytes.len() != 32 {
+                return Ok(smallvec![Value::bool(false), Value::u64(0)]);
+            }
+            let mut bytes_copy: Vec<u8> = bytes.to_vec();
+            bytes_copy.reverse();
+            let bytes = bytes_copy.as_slice();
+            ark_deserialize_internal!(
+                context,
+                bytes,
+                ark_bn254::Fr,
+                deserialize_uncompressed,
+                ALGEBRA_ARK_BN254_FR_DESER
+            )
+        },
+        (Some(Structure::BN254Fq), Some(SerializationFormat::BN254FqLsb)) => {
+            // Valid BN254FqLsb serialization should be 32-byte.
+            if bytes.len() != 32 {
+                return Ok(smallvec![Value::bool(false), Value::u64(0)]);
+            }
+            ark_deserialize_internal!(
+                context,
+                bytes,
+                ark_bn254::Fq,
+                deserialize_uncompressed,
+                ALGEBRA_ARK_BN254_FQ_DESER
+            )
+        },
+        (Some(Structure::BN254Fq), Some(SerializationFormat::BN254FqMsb)) => {
+            // Valid BN254FqMsb serialization should be 32-byte.
+            if bytes.len() != 32 {
+                return Ok(smallvec![Value::bool(false), Value::u64(0)]);
+            }
+            let mut bytes_copy: Vec<u8> = bytes.to_vec();
+            bytes_copy.reverse();
+            let bytes = bytes_copy.as_slice();
+            ark_deserialize_internal!(
+                context,
+                bytes,
+                ark_bn254::Fq,
+                deserialize_uncompressed,
+                ALGEBRA_ARK_BN254_FQ_DESER
+            )
+        },
+        (Some(Structure::BN254Fq12), Some(SerializationFormat::BN254Fq12LscLsb)) => {
+            // Valid BN254Fq12LscLsb serialization should be 384-byte.
+            if bytes.len() != 384 {
+                return Ok(smallvec![Value::bool(false), Value::u64(0)]);
+            }
+            ark_deserialize_internal!(
+                context,
+                bytes,
+                ark_bn254::Fq12,
+                deserialize_uncompressed,
+                ALGEBRA_ARK_BN254_FQ12_DESER
+            )
+        },
+        (Some(Structure::BN254G1), Some(SerializationFormat::BN254G1Uncompressed)) => {
+            // Valid BN254G1AffineUncompressed serialization should be 64-byte.
+            if bytes.len() != 64 {
+                return Ok(smallvec![Value::bool(false), Value::u64(0)]);
+            }
+            ark_ec_point_deserialize_internal!(
+                context,
+                bytes,
+                ark_bn254::G1Affine,
+                deserialize_uncompressed,
+                ALGEBRA_ARK_BN254_G1_AFFINE_DESER_UNCOMP
+            )
+        },
+        (Some(Structure::BN254G1), Some(SerializationFormat::BN254G1Compressed)) => {
+            // Valid BN254G1AffineCompressed serialization should be 32-byte.
+            if bytes.len() != 32 {
+                return Ok(smallvec![Value::bool(false), Value::u64(0)]);
+            }
+            ark_ec_point_deserialize_internal!(
+                context,
+                bytes,
+                ark_bn254::G1Affine,
+                deserialize_compressed,
+                ALGEBRA_ARK_BN254_G1_AFFINE_DESER_COMP
+            )
+        },
+        (Some(Structure::BN254G2), Some(SerializationFormat::BN254G2Uncompressed)) => {
+            // Valid BN254G2AffineUncompressed serialization should be 128-byte.
+            if bytes.len() != 128 {
+                return Ok(smallvec![Value::bool(false), Value::u64(0)]);
+            }
+            ark_ec_point_deserialize_internal!(
+                context,
+                bytes,
+                ark_bn254::G2Affine,
+                deserialize_uncompressed,
+                ALGEBRA_ARK_BN254_G2_AFFINE_DESER_UNCOMP
+            )
+        },
+        (Some(Structure::BN254G2), Some(SerializationFormat::BN254G2Compressed)) => {
+            // Valid BN254G2AffineCompressed serialization should be 64-byte.
+            if bytes.len() != 64 {
+                return Ok(smallvec![Value::bool(false), Value::u64(0)]);
+            }
+            ark_ec_point_deserialize_internal!(
+                context,
+                bytes,
+                ark_bn254::G2Affine,
+                deserialize_compressed,
+                ALGEBRA_ARK_BN254_G2_AFFINE_DESER_COMP
+            )
+        },
+        (Some(Structure::BN254Gt), Some(SerializationFormat::BN254Gt)) => {
+            // Valid BN254Gt serialization should be 384-byte.
+            if bytes.len() != 384 {
+                return Ok(smallvec![Value::bool(false), Value::u64(0)]);
+            }
+            context.charge(ALGEBRA_ARK_BN254_FQ12_DESER)?;
+            match <ark_bn254::Fq12>::deserialize_uncompressed(bytes) {
+                Ok(element) => {
+                    context.charge(ALGEBRA_ARK_BN254_FQ12_POW_U256 + ALGEBRA_ARK_BN254_FQ12_EQ)?;
+                    if element.pow(BN254_R_SCALAR.0) == ark_bn254::Fq12::one() {
+                        let handle = store_element!(context, element)?;
+                        Ok(smallvec![Value::bool(true), Value::u64(handle as u64)])
+                    } else {
+                        Ok(smallvec![Value::bool(false), Value::u64(0)])
+                    }
+                },
+                _ => Ok(smallvec![Value::bool(false), Value::u64(0)]),
+            }
+        },
         _ => Err(SafeNativeError::Abort {
             abort_code: MOVE_ABORT_CODE_NOT_IMPLEMENTED,
         }),
@@ -0,0 +1,137 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_crypto::poseidon::{self, PoseidonField};
+use aptos_gas_schedule::gas_params::natives::aptos_framework::*;
+use aptos_native_interface::{
+    safely_pop_arg, RawSafeNative, SafeNativeBuilder, SafeNativeContext, SafeNativeError,
+    SafeNativeResult,
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use move_core_types::gas_algebra::{InternalGas, InternalGasPerArg, NumArgs};
+use move_vm_runtime::native_functions::NativeFunction;
+use move_vm_types::{loaded_data::runtime_types::Type, values::Value};
+use smallvec::{smallvec, SmallVec};
+use std::collections::VecDeque;
+
+/// Abort codes for the Poseidon natives.
+/// NOTE: These must match the codes in the Move implementation
+pub mod abort_codes {
+    /// Abort code when the number of inputs is out of range (0x01 == INVALID_ARGUMENT)
+    pub const E_WRONG_NUMBER_OF_INPUTS: u64 = 0x01_0002;
+    /// Abort code when an input is not a canonical field element (0x01 == INVALID_ARGUMENT)
+    pub const E_NON_CANONICAL_INPUT: u64 = 0x01_0003;
+}
+
+/// The size of a serialized scalar field element of either supported curve.
+const FIELD_ELEMENT_NUM_BYTES: usize = 32;
+
+/***************************************************************************************************
+ * native fun {bls12381,bn254}_fr_hash_internal
+ *
+ *   gas cost: base_cost + per_input_cost * num_inputs
+ *             + per_round_cell_cost * width^2 * num_rounds
+ *
+ *   where the width and the number of rounds of the permutation depend on the number of inputs,
+ *   and the last term accounts for the matrix multiplication of every round, which dominates.
+ *
+ **************************************************************************************************/
+fn poseidon_hash_internal<F: PoseidonField>(
+    context: &mut SafeNativeContext,
+    mut arguments: VecDeque<Value>,
+    base_cost: InternalGas,
+    per_input_cost: InternalGasPerArg,
+    per_round_cell_cost: InternalGasPerArg,
+) -> SafeNativeResult<SmallVec<[Value; 1]>> {
+    let inputs = safely_pop_arg!(arguments, Vec<Value>);
+
+    context.charge(base_cost)?;
+
+    if inputs.is_empty() || inputs.len() > poseidon::MAX_INPUTS {
+        return Err(SafeNativeError::Abort {
+            abort_code: abort_codes::E_WRONG_NUMBER_OF_INPUTS,
+        });
+    }
+
+    let params = F::parameters(inputs.len());
+    let num_round_cells = params.width() * params.width() * params.num_rounds();
+    context.charge(
+        per_input_cost * NumArgs::new(inputs.len() as u64)
+            + per_round_cell_cost * NumArgs::new(num_round_cells as u64),
+    )?;
+
+    let mut elements = Vec::with_capacity(inputs.len());
+    for input in inputs {
+        let bytes = input.value_as::<Vec<u8>>()?;
+        // Only the canonical, 32-byte little-endian encoding is accepted.
+        let element = match F::deserialize_uncompressed(bytes.as_slice()) {
+            Ok(element) if bytes.len() == FIELD_ELEMENT_NUM_BYTES => element,
+            _ => {
+                return Err(SafeNativeError::Abort {
+                    abort_code: abort_codes::E_NON_CANONICAL_INPUT,
+                });
+            },
+        };
+        elements.push(element);
+    }
+
+    let digest = params.hash(&elements);
+    let mut output = Vec::with_capacity(FIELD_ELEMENT_NUM_BYTES);
+    digest
+        .serialize_uncompressed(&mut output)
+        .expect("Serializing a field element into a vector cannot fail");
+
+    Ok(smallvec![Value::vector_u8(output)])
+}
+
+fn native_bls12381_fr_hash(
+    context: &mut SafeNativeContext,
+    _ty_args: Vec<Type>,
+    arguments: VecDeque<Value>,
+) -> SafeNativeResult<SmallVec<[Value; 1]>> {
+    debug_assert!(_ty_args.is_empty());
+    debug_assert!(arguments.len() == 1);
+
+    poseidon_hash_internal::<ark_bls12_381::Fr>(
+        context,
+        arguments,
+        POSEIDON_BLS12381_FR_BASE,
+        POSEIDON_BLS12381_FR_PER_INPUT,
+        POSEIDON_BLS12381_FR_PER_ROUND_CELL,
+    )
+}
+
+fn native_bn254_fr_hash(
+    context: &mut SafeNativeContext,
+    _ty_args: Vec<Type>,
+    arguments: VecDeque<Value>,
+) -> SafeNativeResult<SmallVec<[Value; 1]>> {
+    debug_assert!(_ty_args.is_empty());
+    debug_assert!(arguments.len() == 1);
+
+    poseidon_hash_internal::<ark_bn254::Fr>(
+        context,
+        arguments,
+        POSEIDON_BN254_FR_BASE,
+        POSEIDON_BN254_FR_PER_INPUT,
+        POSEIDON_BN254_FR_PER_ROUND_CELL,
+    )
+}
+
+/***************************************************************************************************
+ * module
+ *
+ **************************************************************************************************/
+pub fn make_all(
+    builder: &SafeNativeBuilder,
+) -> impl Iterator<Item = (String, NativeFunction)> + '_ {
+    let natives = [
+        (
+            "bls12381_fr_hash_internal",
+            native_bls12381_fr_hash as RawSafeNative,
+        ),
+        ("bn254_fr_hash_internal", native_bn254_fr_hash),
+    ];
+
+    builder.make_named_natives(natives)
+}
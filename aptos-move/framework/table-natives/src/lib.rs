@@ -14,12 +14,17 @@ use aptos_native_interface::{
     safely_pop_arg, RawSafeNative, SafeNativeBuilder, SafeNativeContext, SafeNativeError,
     SafeNativeResult,
 };
+use aptos_types::state_store::state_key::StateKey;
 use better_any::{Tid, TidAble};
 use bytes::Bytes;
 use move_binary_format::errors::{PartialVMError, PartialVMResult};
 use move_core_types::{
-    account_address::AccountAddress, effects::Op, gas_algebra::NumBytes, identifier::Identifier,
-    value::MoveTypeLayout, vm_status::StatusCode,
+    account_address::AccountAddress,
+    effects::Op,
+    gas_algebra::{InternalGas, NumBytes},
+    identifier::Identifier,
+    value::MoveTypeLayout,
+    vm_status::StatusCode,
 };
 // ===========================================================================================
 // Public Data Structures and Constants
@@ -46,8 +51,13 @@ pub struct NativeTableContext<'a> {
     resolver: &'a dyn TableResolver,
     txn_hash: [u8; 32],
     table_data: RefCell<TableData>,
+    load_hook: Option<TableItemLoadHook>,
 }
 
+/// Called with each table item loaded from storage and the gas charged for loading it, e.g. for
+/// the gas profiler to attribute the IO costs to the items.
+pub type TableItemLoadHook = Arc<dyn Fn(StateKey, InternalGas) + Send + Sync>;
+
 // See stdlib/Error.move
 const _ECATEGORY_INVALID_STATE: u8 = 0;
 const ECATEGORY_INVALID_ARGUMENT: u8 = 7;
@@ -85,6 +95,14 @@ struct Table {
     content: BTreeMap<Vec<u8>, GlobalValue>,
 }
 
+/// A table item loaded from storage, as opposed to one already in the table.
+struct LoadedItem {
+    /// The size of the item, or `None` if there is no item with the key.
+    num_bytes: Option<NumBytes>,
+    /// The hook to report the load to and the key of the item, if there is a hook.
+    hook: Option<(TableItemLoadHook, StateKey)>,
+}
+
 /// The field index of the `handle` field in the `Table` Move struct.
 const HANDLE_FIELD_INDEX: usize = 0;
 
@@ -112,9 +130,16 @@ impl<'a> NativeTableContext<'a> {
             resolver,
             txn_hash,
             table_data: Default::default(),
+            load_hook: None,
         }
     }
 
+    /// Reports each table item loaded from storage to the given hook.
+    pub fn with_load_hook(mut self, hook: TableItemLoadHook) -> Self {
+        self.load_hook = Some(hook);
+        self
+    }
+
     /// Computes the change set from a NativeTableContext.
     pub fn into_change_set(self) -> PartialVMResult<TableChangeSet> {
         let NativeTableContext { table_data, .. } = self;
@@ -212,7 +237,7 @@ impl Table {
         &mut self,
         context: &NativeTableContext,
         key: Vec<u8>,
-    ) -> PartialVMResult<(&mut GlobalValue, Option<Option<NumBytes>>)> {
+    ) -> PartialVMResult<(&mut GlobalValue, Option<LoadedItem>)> {
         Ok(match self.content.entry(key) {
             Entry::Vacant(entry) => {
                 // If there is an identifier mapping, we need to pass layout to
@@ -230,7 +255,7 @@ impl Table {
                     partial_extension_error(format!("remote table resolver failure: {}", err))
                 })?;
 
-                let (gv, loaded) = match data {
+                let (gv, num_bytes) = match data {
                     Some(val_bytes) => {
                         let val = deserialize(&self.value_layout_info.layout, &val_bytes)?;
                         (
@@ -240,7 +265,11 @@ impl Table {
                     },
                     None => (GlobalValue::none(), None),
                 };
-                (entry.insert(gv), Some(loaded))
+                let hook = context.load_hook.as_ref().map(|hook| {
+                    let state_key = StateKey::table_item(self.handle.into(), entry.key().clone());
+                    (hook.clone(), state_key)
+                });
+                (entry.insert(gv), Some(LoadedItem { num_bytes, hook }))
             },
             Entry::Occupied(entry) => (entry.into_mut(), None),
         })
@@ -281,17 +310,31 @@ pub fn table_natives(
 
 fn charge_load_cost(
     context: &mut SafeNativeContext,
-    loaded: Option<Option<NumBytes>>,
+    loaded: Option<LoadedItem>,
 ) -> SafeNativeResult<()> {
     context.charge(COMMON_LOAD_BASE_LEGACY)?;
 
-    match loaded {
-        Some(Some(num_bytes)) => {
-            context.charge(COMMON_LOAD_BASE_NEW + COMMON_LOAD_PER_BYTE * num_bytes)
+    let LoadedItem { num_bytes, hook } = match loaded {
+        Some(loaded) => loaded,
+        None => return Ok(()),
+    };
+    // The cost is only evaluated if there's a hook to report it to.
+    let cost = match num_bytes {
+        Some(num_bytes) => {
+            let cost = COMMON_LOAD_BASE_NEW + COMMON_LOAD_PER_BYTE * num_bytes;
+            context.charge(cost.clone())?;
+            hook.is_some().then(|| context.eval_gas(cost))
         },
-        Some(None) => context.charge(COMMON_LOAD_BASE_NEW + COMMON_LOAD_FAILURE),
-        None => Ok(()),
+        None => {
+            let cost = COMMON_LOAD_BASE_NEW + COMMON_LOAD_FAILURE;
+            context.charge(cost.clone())?;
+            hook.is_some().then(|| context.eval_gas(cost))
+        },
+    };
+    if let (Some((hook, state_key)), Some(cost)) = (hook, cost) {
+        hook(state_key, cost);
     }
+    Ok(())
 }
 
 fn native_new_table_handle(
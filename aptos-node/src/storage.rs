@@ -1,10 +1,14 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, ensure, Result};
 use aptos_backup_service::start_backup_service;
 use aptos_config::{config::NodeConfig, utils::get_genesis_txn};
-use aptos_db::{fast_sync_storage_wrapper::FastSyncStorageWrapper, AptosDB};
+use aptos_db::{
+    fast_sync_storage_wrapper::FastSyncStorageWrapper,
+    snapshot_bundle::{db_exists, SnapshotBundle},
+    AptosDB,
+};
 use aptos_executor::db_bootstrapper::maybe_bootstrap;
 use aptos_logger::{debug, info};
 use aptos_storage_interface::{DbReader, DbReaderWriter};
@@ -135,6 +139,51 @@ fn create_rocksdb_checkpoint_and_change_working_dir(
         .expect("StateSyncDB checkpoint creation failed.");
 }
 
+/// Installs the configured snapshot bundle into an empty DB, once the bundle has been verified
+/// against the configured waypoint.
+fn maybe_install_snapshot_bundle(node_config: &NodeConfig) -> Result<()> {
+    let bundle_path = match &node_config.storage.snapshot_bundle.path {
+        Some(bundle_path) => bundle_path,
+        None => return Ok(()),
+    };
+    let db_path = node_config.storage.dir();
+    if db_exists(&db_path) {
+        info!(
+            "DB already exists at {:?}, not installing snapshot bundle {:?}.",
+            db_path, bundle_path
+        );
+        return Ok(());
+    }
+    ensure!(
+        node_config.storage.db_path_overrides.is_none(),
+        "Snapshot bundles can't be installed with db_path_overrides."
+    );
+
+    let instant = Instant::now();
+    let bundle = SnapshotBundle::open(bundle_path)?;
+    ensure!(
+        bundle.manifest().enable_storage_sharding
+            == node_config.storage.rocksdb_configs.enable_storage_sharding,
+        "Snapshot bundle enable_storage_sharding {} doesn't match the config.",
+        bundle.manifest().enable_storage_sharding,
+    );
+    bundle
+        .verify(
+            node_config.base.waypoint.waypoint(),
+            node_config.storage.snapshot_bundle.full_verification,
+        )
+        .map_err(|err| anyhow!("Snapshot bundle failed to verify: {}", err))?;
+    bundle.install(&db_path)?;
+    info!(
+        "Installed snapshot bundle {:?} at version {} in {} ms.",
+        bundle_path,
+        bundle.manifest().version,
+        instant.elapsed().as_millis()
+    );
+
+    Ok(())
+}
+
 /// Creates any rocksdb checkpoints, opens the storage database,
 /// starts the backup service, handles genesis initialization and returns
 /// the various handles.
@@ -147,6 +196,9 @@ pub fn initialize_database_and_checkpoints(
         create_rocksdb_checkpoint_and_change_working_dir(node_config, working_dir);
     }
 
+    // Bootstrap an empty database from a snapshot bundle, if configured
+    maybe_install_snapshot_bundle(node_config)?;
+
     // Open the database
     let instant = Instant::now();
     let (aptos_db, db_rw, backup_service) = bootstrap_db(node_config)?;
@@ -3,6 +3,8 @@
 
 use anyhow::anyhow;
 use aptos_config::config::NodeConfig;
+use aptos_executor::components::access_hints::AccessHints;
+use aptos_executor_service::remote_executor_client;
 use aptos_state_view::account_with_state_view::AsAccountWithStateView;
 use aptos_storage_interface::{state_view::LatestDbStateCheckpointView, DbReaderWriter};
 use aptos_types::{
@@ -60,4 +62,17 @@ pub fn set_aptos_vm_configurations(node_config: &NodeConfig) {
     {
         AptosVM::set_processed_transactions_detailed_counters();
     }
+
+    if let Some(top_n) = node_config.execution.conflict_analytics_top_n {
+        AptosVM::set_conflict_analytics_top_n_once(top_n);
+    }
+
+    if let Some(process_sharding) = &node_config.execution.process_sharding {
+        AptosVM::set_num_shards_once(process_sharding.num_shards);
+        remote_executor_client::set_process_sharding_config(process_sharding.clone());
+    }
+
+    if node_config.execution.prefetch_access_hints {
+        AccessHints::enable_once(node_config.execution.access_hints_path.clone());
+    }
 }
@@ -15,6 +15,7 @@ pub const OWNER_ACCOUNT: &str = "owner_account";
 
 /// Definitions of global data items (e.g., as held in secure storage)
 pub const SAFETY_DATA: &str = "safety_data";
+pub const SLASHING_PROTECTION: &str = "slashing_protection";
 pub const WAYPOINT: &str = "waypoint";
 pub const GENESIS_WAYPOINT: &str = "genesis-waypoint";
 
@@ -66,6 +66,10 @@ pub struct ConsensusConfig {
     pub window_for_chain_health: usize,
     pub chain_health_backoff: Vec<ChainHealthBackoffValues>,
     pub qc_aggregator_type: QcAggregatorType,
+    // With decoupled execution, start executing proposed blocks before they are ordered. The
+    // results are reused once a block is ordered, and discarded if it never is.
+    pub speculative_execution: bool,
+    pub audit_log: ConsensusAuditLogConfig,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
@@ -112,6 +116,45 @@ impl Default for DelayedQcAggregatorConfig {
     }
 }
 
+/// An append-only log of proposals, votes, timeouts, certificates and commit decisions, to
+/// reconstruct what happened in a round after the fact
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConsensusAuditLogConfig {
+    pub enabled: bool,
+    // Relative to the data directory, unless absolute
+    pub path: PathBuf,
+    // Records waiting to be written beyond this are dropped, consensus never waits on the log
+    pub max_pending_records: usize,
+    #[serde(skip)]
+    data_dir: PathBuf,
+}
+
+impl Default for ConsensusAuditLogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: PathBuf::from("consensus_audit.log"),
+            max_pending_records: 10_000,
+            data_dir: PathBuf::from("/opt/aptos/data"),
+        }
+    }
+}
+
+impl ConsensusAuditLogConfig {
+    pub fn path(&self) -> PathBuf {
+        if self.path.is_relative() {
+            self.data_dir.join(&self.path)
+        } else {
+            self.path.clone()
+        }
+    }
+
+    pub fn set_data_dir(&mut self, data_dir: PathBuf) {
+        self.data_dir = data_dir;
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
 pub struct PipelineBackpressureValues {
     pub back_pressure_pipeline_latency_limit_ms: u64,
@@ -252,12 +295,15 @@ impl Default for ConsensusConfig {
             ],
 
             qc_aggregator_type: QcAggregatorType::default(),
+            speculative_execution: false,
+            audit_log: ConsensusAuditLogConfig::default(),
         }
     }
 }
 
 impl ConsensusConfig {
     pub fn set_data_dir(&mut self, data_dir: PathBuf) {
+        self.audit_log.set_data_dir(data_dir.clone());
         self.safety_rules.set_data_dir(data_dir);
     }
 
@@ -11,6 +11,7 @@ use serde::{Deserialize, Serialize};
 use std::{
     fs::File,
     io::{Read, Write},
+    net::{IpAddr, Ipv4Addr, SocketAddr},
     path::PathBuf,
 };
 
@@ -34,6 +35,16 @@ pub struct ExecutionConfig {
     pub paranoid_hot_potato_verification: bool,
     /// Enables enhanced metrics around processed transactions
     pub processed_transactions_detailed_counters: bool,
+    /// If set, records the conflicts between transactions during parallel execution and
+    /// exports the given number of most conflicting state keys of each block as metrics
+    pub conflict_analytics_top_n: Option<usize>,
+    /// Enables prefetching the state a block is expected to access before executing it, based
+    /// on the state accessed by earlier transactions calling the same entry functions
+    pub prefetch_access_hints: bool,
+    /// If set, the access hints are loaded from and periodically persisted to this file
+    pub access_hints_path: Option<PathBuf>,
+    /// If set, sharded blocks are executed by executor shard processes launched by this node
+    pub process_sharding: Option<ProcessShardingConfig>,
     /// Enables filtering of transactions before they are sent to execution
     pub transaction_filter: Filter,
 }
@@ -65,11 +76,47 @@ impl Default for ExecutionConfig {
             paranoid_type_verification: true,
             paranoid_hot_potato_verification: true,
             processed_transactions_detailed_counters: false,
+            conflict_analytics_top_n: None,
+            prefetch_access_hints: false,
+            access_hints_path: None,
+            process_sharding: None,
             transaction_filter: Filter::empty(),
         }
     }
 }
 
+/// Configuration for executing sharded blocks in separate executor shard processes on this machine
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProcessShardingConfig {
+    /// Path to the `aptos-executor-service` binary that is launched for every shard
+    pub executor_service_binary: PathBuf,
+    /// Number of shard processes to launch
+    pub num_shards: usize,
+    /// Number of execution threads of every shard process
+    pub num_threads_per_shard: usize,
+    /// Address on which the coordinator receives messages from the shards
+    pub coordinator_address: SocketAddr,
+    /// Maximum number of cross-shard messages queued for sending per destination shard and
+    /// round, after which the sending shard blocks
+    pub cross_shard_channel_capacity: usize,
+    /// Time to wait for the results of all shards, after which the block is re-executed locally
+    pub shard_result_timeout_ms: u64,
+}
+
+impl Default for ProcessShardingConfig {
+    fn default() -> Self {
+        Self {
+            executor_service_binary: PathBuf::from("aptos-executor-service"),
+            num_shards: 4,
+            num_threads_per_shard: 8,
+            coordinator_address: SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 52200),
+            cross_shard_channel_capacity: 1024,
+            shard_result_timeout_ms: 60_000,
+        }
+    }
+}
+
 impl ExecutionConfig {
     pub fn load_from_path(&mut self, root_dir: &RootPath) -> Result<(), Error> {
         if !self.genesis_file_location.as_os_str().is_empty() {
@@ -156,6 +203,19 @@ impl ConfigSanitizer for ExecutionConfig {
             }
         }
 
+        // Ensure that process sharding can make progress
+        if let Some(process_sharding) = &execution_config.process_sharding {
+            if process_sharding.num_shards == 0
+                || process_sharding.num_threads_per_shard == 0
+                || process_sharding.cross_shard_channel_capacity == 0
+            {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    "num_shards, num_threads_per_shard and cross_shard_channel_capacity of process_sharding must be non-zero!".into(),
+                ));
+            }
+        }
+
         Ok(())
     }
 }
@@ -224,6 +284,25 @@ mod test {
         assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
     }
 
+    #[test]
+    fn test_sanitize_process_sharding_no_shards() {
+        // Create a node config with process sharding but no shards
+        let node_config = NodeConfig {
+            execution: ExecutionConfig {
+                process_sharding: Some(ProcessShardingConfig {
+                    num_shards: 0,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Sanitize the config and verify that it fails
+        let error = ExecutionConfig::sanitize(&node_config, NodeType::Validator, None).unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+    }
+
     #[test]
     fn test_no_genesis() {
         let (mut config, path) = generate_config();
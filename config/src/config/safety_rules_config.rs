@@ -11,7 +11,7 @@ use crate::{
     },
     keys::ConfigKey,
 };
-use aptos_crypto::{bls12381, Uniform};
+use aptos_crypto::{bls12381, x25519, Uniform};
 use aptos_types::{chain_id::ChainId, network_address::NetworkAddress, waypoint::Waypoint, PeerId};
 use rand::rngs::StdRng;
 use serde::{Deserialize, Serialize};
@@ -29,8 +29,12 @@ pub struct SafetyRulesConfig {
     pub test: Option<SafetyRulesTestConfig>,
     // Read/Write/Connect networking operation timeout in milliseconds.
     pub network_timeout_ms: u64,
+    // Deadline in milliseconds for a single request to a remote safety rules service, including
+    // all reconnection attempts.
+    pub request_timeout_ms: u64,
     pub enable_cached_safety_data: bool,
     pub initial_safety_rules_config: InitialSafetyRulesConfig,
+    pub signer: ConsensusSignerConfig,
 }
 
 impl Default for SafetyRulesConfig {
@@ -42,8 +46,11 @@ impl Default for SafetyRulesConfig {
             test: None,
             // Default value of 30 seconds for a timeout
             network_timeout_ms: 30_000,
+            // Default value of 60 seconds, enough to survive a network timeout and reconnect
+            request_timeout_ms: 60_000,
             enable_cached_safety_data: true,
             initial_safety_rules_config: InitialSafetyRulesConfig::None,
+            signer: ConsensusSignerConfig::Local,
         }
     }
 }
@@ -103,6 +110,31 @@ impl ConfigSanitizer for SafetyRulesConfig {
                 ));
             }
 
+            // Verify that a remote safety rules service is authenticated outside of tests
+            if let SafetyRulesService::Process(remote_service) = &safety_rules_config.service {
+                if (chain_id.is_mainnet() || chain_id.is_testnet())
+                    && remote_service.noise.is_none()
+                {
+                    return Err(Error::ConfigSanitizerFailed(
+                        sanitizer_name,
+                        "The remote safety rules service must be configured with noise keys!"
+                            .to_string(),
+                    ));
+                }
+            }
+
+            // Verify that a remote signer is authenticated outside of tests
+            if let ConsensusSignerConfig::Remote(remote_signer) = &safety_rules_config.signer {
+                if (chain_id.is_mainnet() || chain_id.is_testnet()) && remote_signer.noise.is_none()
+                {
+                    return Err(Error::ConfigSanitizerFailed(
+                        sanitizer_name,
+                        "The remote consensus signer must be configured with noise keys!"
+                            .to_string(),
+                    ));
+                }
+            }
+
             // Verify that the safety rules test config is not enabled in mainnet
             if chain_id.is_mainnet() && safety_rules_config.test.is_some() {
                 return Err(Error::ConfigSanitizerFailed(
@@ -153,7 +185,7 @@ impl InitialSafetyRulesConfig {
 }
 
 /// Defines how safety rules should be executed
-#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(rename_all = "snake_case", tag = "type")]
 pub enum SafetyRulesService {
     /// This runs safety rules in the same thread as event processor
@@ -174,10 +206,74 @@ impl SafetyRulesService {
     }
 }
 
-#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+/// Defines where the consensus key lives and how consensus messages get signed
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum ConsensusSignerConfig {
+    /// The consensus key is read from the safety rules backend and used in process
+    Local,
+    /// Signing is delegated to a remote signer daemon, the consensus key never needs to be in the
+    /// safety rules backend
+    Remote(RemoteService),
+}
+
+impl Default for ConsensusSignerConfig {
+    fn default() -> Self {
+        ConsensusSignerConfig::Local
+    }
+}
+
+/// The config of the remote signer daemon, which holds the consensus key and enforces its own
+/// slashing protection on everything it signs
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RemoteSignerConfig {
+    /// The validator the daemon signs for
+    pub author: PeerId,
+    /// Holds the consensus key and the slashing protection data
+    pub backend: SecureBackend,
+    pub listen_address: NetworkAddress,
+    pub logger: LoggerConfig,
+    // Read/Write/Connect networking operation timeout in milliseconds.
+    #[serde(default = "RemoteSignerConfig::default_network_timeout_ms")]
+    pub network_timeout_ms: u64,
+    /// The keys authenticating safety rules, this end uses its own key and pins the key of safety
+    /// rules
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub noise: Option<RemoteServiceNoiseConfig>,
+    /// If set, the consensus key is written to the backend on startup if it holds no key yet
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initial_consensus_key: Option<ConfigKey<bls12381::PrivateKey>>,
+}
+
+impl RemoteSignerConfig {
+    fn default_network_timeout_ms() -> u64 {
+        30_000
+    }
+
+    pub fn listen_address(&self) -> SocketAddr {
+        self.listen_address
+            .to_socket_addrs()
+            .expect("listen_address invalid")
+            .next()
+            .expect("listen_address invalid")
+    }
+
+    pub fn set_data_dir(&mut self, data_dir: PathBuf) {
+        if let SecureBackend::OnDiskStorage(backend) = &mut self.backend {
+            backend.set_data_dir(data_dir);
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct RemoteService {
     pub server_address: NetworkAddress,
+    /// If set, the channel between consensus and safety rules is authenticated and encrypted
+    /// with Noise IK. Both ends use the same config layout, each with its own key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub noise: Option<RemoteServiceNoiseConfig>,
 }
 
 impl RemoteService {
@@ -190,6 +286,26 @@ impl RemoteService {
     }
 }
 
+/// The static keys of one end of the remote safety rules channel
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RemoteServiceNoiseConfig {
+    /// The static x25519 key of this end
+    pub private_key: ConfigKey<x25519::PrivateKey>,
+    /// The pinned static public key of the other end, connections from or to any other key are
+    /// rejected
+    pub remote_public_key: x25519::PublicKey,
+}
+
+impl RemoteServiceNoiseConfig {
+    pub fn new(private_key: x25519::PrivateKey, remote_public_key: x25519::PublicKey) -> Self {
+        Self {
+            private_key: ConfigKey::new(private_key),
+            remote_public_key,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct SafetyRulesTestConfig {
     pub author: PeerId,
@@ -292,6 +408,68 @@ mod tests {
         assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
     }
 
+    #[test]
+    fn test_sanitize_unauthenticated_process_service() {
+        // Create a node config with a remote service that has no noise keys
+        let service = SafetyRulesService::Process(RemoteService {
+            server_address: "/ip4/127.0.0.1/tcp/5555".parse().unwrap(),
+            noise: None,
+        });
+        let node_config = NodeConfig {
+            consensus: ConsensusConfig {
+                safety_rules: SafetyRulesConfig {
+                    backend: SecureBackend::OnDiskStorage(Default::default()),
+                    service,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Verify that the config sanitizer fails for testnet, but passes for test chains
+        let error = SafetyRulesConfig::sanitize(
+            &node_config,
+            NodeType::Validator,
+            Some(ChainId::testnet()),
+        )
+        .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+        SafetyRulesConfig::sanitize(&node_config, NodeType::Validator, Some(ChainId::test()))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_sanitize_unauthenticated_remote_signer() {
+        // Create a node config with a remote signer that has no noise keys
+        let signer = ConsensusSignerConfig::Remote(RemoteService {
+            server_address: "/ip4/127.0.0.1/tcp/5556".parse().unwrap(),
+            noise: None,
+        });
+        let node_config = NodeConfig {
+            consensus: ConsensusConfig {
+                safety_rules: SafetyRulesConfig {
+                    backend: SecureBackend::OnDiskStorage(Default::default()),
+                    signer,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Verify that the config sanitizer fails for mainnet, but passes for test chains
+        let error = SafetyRulesConfig::sanitize(
+            &node_config,
+            NodeType::Validator,
+            Some(ChainId::mainnet()),
+        )
+        .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+        SafetyRulesConfig::sanitize(&node_config, NodeType::Validator, Some(ChainId::test()))
+            .unwrap();
+    }
+
     #[test]
     fn test_sanitize_test_config_on_mainnet() {
         // Create a node config with a test config
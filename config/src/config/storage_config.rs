@@ -23,12 +23,20 @@ pub const DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD: usize = 1 << 13;
 
 pub const BUFFERED_STATE_TARGET_ITEMS: usize = 100_000;
 
+// Prune windows below these are flagged by the sanitizer and refused at runtime.
+pub const MIN_LEDGER_PRUNE_WINDOW: u64 = 50_000_000;
+pub const MIN_STATE_MERKLE_PRUNE_WINDOW: u64 = 100_000;
+pub const MIN_EPOCH_SNAPSHOT_PRUNE_WINDOW: u64 = 50_000_000;
+
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct DbPathConfig {
     pub ledger_db_path: Option<PathBuf>,
     pub state_kv_db_path: Option<ShardedDbPathConfig>,
     pub state_merkle_db_path: Option<ShardedDbPathConfig>,
+    /// Where the archive tier puts ledger data moved out of the ledger db, see
+    /// `LedgerArchiveConfig`.
+    pub cold_ledger_db_path: Option<PathBuf>,
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
@@ -125,6 +133,9 @@ pub struct RocksdbConfigs {
     pub state_merkle_db_config: RocksdbConfig,
     pub state_kv_db_config: RocksdbConfig,
     pub index_db_config: RocksdbConfig,
+    /// The archive tier gets its own block cache so scans over old data don't evict the hot
+    /// ledger data from the main one.
+    pub cold_ledger_db_config: RocksdbConfig,
     // Note: Not ready for production use yet.
     pub enable_storage_sharding: bool,
 }
@@ -139,6 +150,10 @@ impl Default for RocksdbConfigs {
                 max_open_files: 1000,
                 ..Default::default()
             },
+            cold_ledger_db_config: RocksdbConfig {
+                max_open_files: 1000,
+                ..Default::default()
+            },
             enable_storage_sharding: false,
         }
     }
@@ -174,6 +189,8 @@ pub struct StorageConfig {
     /// If not specificed, will use `dir` as default.
     /// Only allowed when sharding is enabled.
     pub db_path_overrides: Option<DbPathConfig>,
+    /// Bootstraps an empty DB from a snapshot bundle.
+    pub snapshot_bundle: SnapshotBundleConfig,
 }
 
 pub const NO_OP_STORAGE_PRUNER_CONFIG: PrunerConfig = PrunerConfig {
@@ -193,6 +210,11 @@ pub const NO_OP_STORAGE_PRUNER_CONFIG: PrunerConfig = PrunerConfig {
         prune_window: 0,
         batch_size: 0,
     },
+    ledger_archive_config: LedgerArchiveConfig {
+        enable: false,
+        hot_window: 0,
+        batch_size: 0,
+    },
 };
 
 #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -249,12 +271,51 @@ impl From<EpochSnapshotPrunerConfig> for StateMerklePrunerConfig {
     }
 }
 
+/// Moves ledger data (transactions, transaction infos, write sets and events) older than
+/// `hot_window` versions out of the ledger db into a secondary "cold" db, instead of deleting it
+/// like the ledger pruner does. Reads fall through to the cold db transparently. Accumulators and
+/// indices stay in the ledger db.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LedgerArchiveConfig {
+    /// Boolean to enable/disable moving data to the cold db. Data already moved is readable
+    /// regardless.
+    pub enable: bool,
+    /// The number of latest versions kept in the ledger db.
+    pub hot_window: u64,
+    /// Number of versions moved in one batch.
+    pub batch_size: usize,
+}
+
+/// A snapshot bundle is a DB checkpoint made by `db-tool debug snapshot-bundle create`, along
+/// with the ledger info it's at and an epoch change proof for it. It's verified against the
+/// waypoint before the node uses it, so it can come from anyone.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SnapshotBundleConfig {
+    /// The bundle to bootstrap from if there is no DB yet. Ignored once the DB exists.
+    pub path: Option<PathBuf>,
+    /// Besides checking the ledger info and the root hashes, check every transaction, index and
+    /// state Merkle node in the bundle. Makes bootstrapping much slower.
+    pub full_verification: bool,
+}
+
+impl Default for SnapshotBundleConfig {
+    fn default() -> Self {
+        Self {
+            path: None,
+            full_verification: true,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, Default)]
 #[serde(default, deny_unknown_fields)]
 pub struct PrunerConfig {
     pub ledger_pruner_config: LedgerPrunerConfig,
     pub state_merkle_pruner_config: StateMerklePrunerConfig,
     pub epoch_snapshot_pruner_config: EpochSnapshotPrunerConfig,
+    pub ledger_archive_config: LedgerArchiveConfig,
 }
 
 impl Default for LedgerPrunerConfig {
@@ -305,6 +366,18 @@ impl Default for EpochSnapshotPrunerConfig {
     }
 }
 
+impl Default for LedgerArchiveConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            // Roughly a few days of history on a busy network, enough for most API reads to be
+            // served from the ledger db.
+            hot_window: 10_000_000,
+            batch_size: 5_000,
+        }
+    }
+}
+
 impl Default for StorageConfig {
     fn default() -> StorageConfig {
         StorageConfig {
@@ -321,6 +394,7 @@ impl Default for StorageConfig {
             rocksdb_configs: RocksdbConfigs::default(),
             enable_indexer: false,
             db_path_overrides: None,
+            snapshot_bundle: SnapshotBundleConfig::default(),
             buffered_state_target_items: BUFFERED_STATE_TARGET_ITEMS,
             max_num_nodes_per_lru_cache_shard: DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
         }
@@ -339,11 +413,13 @@ impl StorageConfig {
     pub fn get_dir_paths(&self) -> StorageDirPaths {
         let default_dir = self.dir();
         let mut ledger_db_path = None;
+        let mut cold_ledger_db_path = None;
         let mut state_kv_db_paths = ShardedDbPaths::default();
         let mut state_merkle_db_paths = ShardedDbPaths::default();
 
         if let Some(db_path_overrides) = self.db_path_overrides.as_ref() {
             ledger_db_path = db_path_overrides.ledger_db_path.clone();
+            cold_ledger_db_path = db_path_overrides.cold_ledger_db_path.clone();
 
             if let Some(state_kv_db_path) = db_path_overrides.state_kv_db_path.as_ref() {
                 state_kv_db_paths = ShardedDbPaths::new(state_kv_db_path);
@@ -357,6 +433,7 @@ impl StorageConfig {
         StorageDirPaths::new(
             default_dir,
             ledger_db_path,
+            cold_ledger_db_path,
             state_kv_db_paths,
             state_merkle_db_paths,
         )
@@ -375,6 +452,7 @@ impl StorageConfig {
 pub struct StorageDirPaths {
     default_path: PathBuf,
     ledger_db_path: Option<PathBuf>,
+    cold_ledger_db_path: Option<PathBuf>,
     state_kv_db_paths: ShardedDbPaths,
     state_merkle_db_paths: ShardedDbPaths,
 }
@@ -392,6 +470,12 @@ impl StorageDirPaths {
         }
     }
 
+    pub fn cold_ledger_db_root_path(&self) -> &PathBuf {
+        self.cold_ledger_db_path
+            .as_ref()
+            .unwrap_or(&self.default_path)
+    }
+
     pub fn state_kv_db_metadata_root_path(&self) -> &PathBuf {
         self.state_kv_db_paths
             .metadata_path()
@@ -420,6 +504,7 @@ impl StorageDirPaths {
         Self {
             default_path: path.as_ref().to_path_buf(),
             ledger_db_path: None,
+            cold_ledger_db_path: None,
             state_kv_db_paths: Default::default(),
             state_merkle_db_paths: Default::default(),
         }
@@ -428,12 +513,14 @@ impl StorageDirPaths {
     fn new(
         default_path: PathBuf,
         ledger_db_path: Option<PathBuf>,
+        cold_ledger_db_path: Option<PathBuf>,
         state_kv_db_paths: ShardedDbPaths,
         state_merkle_db_paths: ShardedDbPaths,
     ) -> Self {
         Self {
             default_path,
             ledger_db_path,
+            cold_ledger_db_path,
             state_kv_db_paths,
             state_merkle_db_paths,
         }
@@ -494,13 +581,13 @@ impl ConfigSanitizer for StorageConfig {
             .ledger_pruner_config
             .user_pruning_window_offset;
 
-        if ledger_prune_window < 50_000_000 {
+        if ledger_prune_window < MIN_LEDGER_PRUNE_WINDOW {
             warn!("Ledger prune_window is too small, harming network data availability.");
         }
-        if state_merkle_prune_window < 100_000 {
+        if state_merkle_prune_window < MIN_STATE_MERKLE_PRUNE_WINDOW {
             warn!("State Merkle prune_window is too small, node might stop functioning.");
         }
-        if epoch_snapshot_prune_window < 50_000_000 {
+        if epoch_snapshot_prune_window < MIN_EPOCH_SNAPSHOT_PRUNE_WINDOW {
             warn!("Epoch snapshot prune_window is too small, harming network data availability.");
         }
         if user_pruning_window_offset > 1_000_000 {
@@ -516,14 +603,39 @@ impl ConfigSanitizer for StorageConfig {
             ));
         }
 
+        if config.storage_pruner_config.ledger_archive_config.enable
+            && config.storage_pruner_config.ledger_pruner_config.enable
+        {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "ledger_archive_config and ledger_pruner_config can't be both enabled, the pruner would delete data before it's archived.".to_string(),
+            ));
+        }
+
         if let Some(db_path_overrides) = config.db_path_overrides.as_ref() {
-            if !config.rocksdb_configs.enable_storage_sharding {
+            // The cold ledger db is not sharded, so its path can be overridden regardless.
+            if (db_path_overrides.ledger_db_path.is_some()
+                || db_path_overrides.state_kv_db_path.is_some()
+                || db_path_overrides.state_merkle_db_path.is_some())
+                && !config.rocksdb_configs.enable_storage_sharding
+            {
                 return Err(Error::ConfigSanitizerFailed(
                     sanitizer_name,
                     "db_path_overrides is allowed only if sharding is enabled.".to_string(),
                 ));
             }
 
+            if let Some(cold_ledger_db_path) = db_path_overrides.cold_ledger_db_path.as_ref() {
+                if !cold_ledger_db_path.is_absolute() {
+                    return Err(Error::ConfigSanitizerFailed(
+                        sanitizer_name,
+                        format!(
+                            "Path {cold_ledger_db_path:?} in db_path_overrides is not an absolute path."
+                        ),
+                    ));
+                }
+            }
+
             if let Some(ledger_db_path) = db_path_overrides.ledger_db_path.as_ref() {
                 if !ledger_db_path.is_absolute() {
                     return Err(Error::ConfigSanitizerFailed(
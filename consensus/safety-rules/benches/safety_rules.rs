@@ -112,7 +112,7 @@ fn thread(n: u64) {
     );
     // Test value, in milliseconds
     let timeout_ms = 5_000;
-    let safety_rules_manager = SafetyRulesManager::new_thread(storage, timeout_ms);
+    let safety_rules_manager = SafetyRulesManager::new_thread(storage, timeout_ms, 2 * timeout_ms);
     lsr(safety_rules_manager.client(), signer, n);
 }
 
@@ -132,7 +132,7 @@ fn vault(n: u64) {
     );
     // Test value in milliseconds.
     let timeout_ms = 5_000;
-    let safety_rules_manager = SafetyRulesManager::new_thread(storage, timeout_ms);
+    let safety_rules_manager = SafetyRulesManager::new_thread(storage, timeout_ms, 2 * timeout_ms);
     lsr(safety_rules_manager.client(), signer, n);
 }
 
@@ -0,0 +1,35 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_config::config::{PersistableConfig, RemoteSignerConfig};
+use aptos_logger::info;
+use aptos_safety_rules::remote_signer;
+use clap::Parser;
+use std::path::PathBuf;
+
+/// Runs the remote signer daemon, which holds the consensus key, enforces its own slashing
+/// protection and signs consensus messages on behalf of SafetyRules.
+#[derive(Debug, Parser)]
+struct Args {
+    /// Path to the remote signer config
+    #[clap(value_parser)]
+    pub config: PathBuf,
+}
+
+fn main() {
+    let args = Args::parse();
+    let config = RemoteSignerConfig::load_config(&args.config)
+        .unwrap_or_else(|error| panic!("Unable to load config {:?}: {}", args.config, error));
+    aptos_logger::Logger::new()
+        .level(config.logger.level)
+        .init();
+
+    info!("Starting the remote signer with config {:?}", args.config);
+    remote_signer::start(config);
+}
+
+#[test]
+fn verify_tool() {
+    use clap::CommandFactory;
+    Args::command().debug_assert()
+}
@@ -0,0 +1,126 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::Error;
+use aptos_consensus_types::{
+    block_data::BlockData, common::Author, timeout_2chain::TimeoutSigningRepr, vote_data::VoteData,
+};
+use aptos_crypto::{bls12381, hash::CryptoHash};
+use aptos_types::{ledger_info::LedgerInfo, validator_signer::ValidatorSigner};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+/// Every message SafetyRules signs with the consensus key. The messages are typed, rather than
+/// opaque bytes, so that a signer can independently enforce slashing protection on them. Local
+/// signing borrows the messages, they are only copied when sent to a remote signer.
+#[derive(Debug, Deserialize, Serialize)]
+pub enum SigningRequest<'a> {
+    /// A block proposal
+    Proposal(Cow<'a, BlockData>),
+    /// A vote, the signature is on the ledger info whose consensus data hash is the hash of the
+    /// vote data
+    Vote(Cow<'a, VoteData>, Cow<'a, LedgerInfo>),
+    /// A 2-chain timeout
+    Timeout(TimeoutSigningRepr),
+    /// A commit decision
+    CommitVote(Cow<'a, LedgerInfo>),
+}
+
+impl<'a> SigningRequest<'a> {
+    /// A name for the kind of message, used in logs and metrics
+    pub fn name(&self) -> &'static str {
+        match self {
+            SigningRequest::Proposal(_) => "proposal",
+            SigningRequest::Vote(..) => "vote",
+            SigningRequest::Timeout(_) => "timeout",
+            SigningRequest::CommitVote(_) => "commit_vote",
+        }
+    }
+
+    /// Borrows the messages of this request, e.g., to serialize them without copying
+    pub fn reborrow(&self) -> SigningRequest<'_> {
+        match self {
+            SigningRequest::Proposal(block_data) => {
+                SigningRequest::Proposal(Cow::Borrowed(block_data.as_ref()))
+            },
+            SigningRequest::Vote(vote_data, ledger_info) => SigningRequest::Vote(
+                Cow::Borrowed(vote_data.as_ref()),
+                Cow::Borrowed(ledger_info.as_ref()),
+            ),
+            SigningRequest::Timeout(timeout) => SigningRequest::Timeout(TimeoutSigningRepr {
+                epoch: timeout.epoch,
+                round: timeout.round,
+                hqc_round: timeout.hqc_round,
+            }),
+            SigningRequest::CommitVote(ledger_info) => {
+                SigningRequest::CommitVote(Cow::Borrowed(ledger_info.as_ref()))
+            },
+        }
+    }
+
+    /// Returns the epoch and round the signed message commits the signer to
+    pub fn epoch_and_round(&self) -> (u64, u64) {
+        match self {
+            SigningRequest::Proposal(block_data) => (block_data.epoch(), block_data.round()),
+            SigningRequest::Vote(vote_data, _) => {
+                (vote_data.proposed().epoch(), vote_data.proposed().round())
+            },
+            SigningRequest::Timeout(timeout) => (timeout.epoch, timeout.round),
+            SigningRequest::CommitVote(ledger_info) => {
+                (ledger_info.epoch(), ledger_info.commit_info().round())
+            },
+        }
+    }
+}
+
+/// Produces the BLS12-381 consensus signatures of a validator. The key itself may live in this
+/// process or behind a remote signer.
+pub trait ConsensusSigner: Send + Sync {
+    /// The validator this signer signs for
+    fn author(&self) -> Author;
+
+    /// The public key of the consensus key used for signing
+    fn public_key(&self) -> bls12381::PublicKey;
+
+    /// Signs the given request with the consensus key
+    fn sign(&self, request: &SigningRequest) -> Result<bls12381::Signature, Error>;
+}
+
+/// Signs with a consensus key held in this process
+pub struct LocalSigner {
+    validator_signer: ValidatorSigner,
+}
+
+impl LocalSigner {
+    pub fn new(validator_signer: ValidatorSigner) -> Self {
+        Self { validator_signer }
+    }
+
+    fn sign_message<T: Serialize + CryptoHash>(
+        &self,
+        message: &T,
+    ) -> Result<bls12381::Signature, Error> {
+        self.validator_signer
+            .sign(message)
+            .map_err(|err| Error::SerializationError(err.to_string()))
+    }
+}
+
+impl ConsensusSigner for LocalSigner {
+    fn author(&self) -> Author {
+        self.validator_signer.author()
+    }
+
+    fn public_key(&self) -> bls12381::PublicKey {
+        self.validator_signer.public_key()
+    }
+
+    fn sign(&self, request: &SigningRequest) -> Result<bls12381::Signature, Error> {
+        match request {
+            SigningRequest::Proposal(block_data) => self.sign_message(block_data.as_ref()),
+            SigningRequest::Vote(_, ledger_info) => self.sign_message(ledger_info.as_ref()),
+            SigningRequest::Timeout(timeout) => self.sign_message(timeout),
+            SigningRequest::CommitVote(ledger_info) => self.sign_message(ledger_info.as_ref()),
+        }
+    }
+}
@@ -31,6 +31,24 @@ static QUERY_COUNTER: Lazy<IntCounterVec> = Lazy::new(|| {
     .unwrap()
 });
 
+static REMOTE_REQUEST_COUNTER: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_safety_rules_remote_requests",
+        "Outcome of requests and request attempts to a remote LSR",
+        &["result"]
+    )
+    .unwrap()
+});
+
+static REMOTE_SIGNER_REQUEST_COUNTER: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_safety_rules_remote_signer_requests",
+        "Outcome of signing requests handled by the remote signer",
+        &["request", "result"]
+    )
+    .unwrap()
+});
+
 static STATE_GAUGE: Lazy<IntGaugeVec> = Lazy::new(|| {
     register_int_gauge_vec!(
         "aptos_safety_rules_state",
@@ -44,6 +62,16 @@ pub fn increment_query(method: &str, result: &str) {
     QUERY_COUNTER.with_label_values(&[method, result]).inc();
 }
 
+pub fn increment_remote_request(result: &str) {
+    REMOTE_REQUEST_COUNTER.with_label_values(&[result]).inc();
+}
+
+pub fn increment_remote_signer_request(request: &str, result: &str) {
+    REMOTE_SIGNER_REQUEST_COUNTER
+        .with_label_values(&[request, result])
+        .inc();
+}
+
 pub fn start_timer(source: &str, field: &str) -> HistogramTimer {
     LATENCY.with_label_values(&[source, field]).start_timer()
 }
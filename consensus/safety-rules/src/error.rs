@@ -56,6 +56,14 @@ pub enum Error {
     WaypointOutOfDate(u64, u64, u64, u64),
     #[error("Invalid Timeout: {0}")]
     InvalidTimeout(String),
+    #[error("Remote SafetyRules state regressed: epoch {0}, last voted round {1}, previously observed epoch {2}, last voted round {3}")]
+    RemoteStateRegressed(u64, u64, u64, u64),
+    #[error("Request to remote SafetyRules timed out after {0} ms: {1}")]
+    RemoteRequestTimeout(u64, String),
+    #[error("Remote signer refused to sign, slashing protection violated: {0}")]
+    SlashingProtectionViolation(String),
+    #[error("Invalid remote signer: {0}")]
+    InvalidRemoteSigner(String),
 }
 
 impl From<serde_json::Error> for Error {
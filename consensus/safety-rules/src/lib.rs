@@ -4,6 +4,7 @@
 
 #![forbid(unsafe_code)]
 
+mod consensus_signer;
 mod consensus_state;
 mod counters;
 mod error;
@@ -12,17 +13,23 @@ mod logging;
 mod persistent_safety_storage;
 mod process;
 mod remote_service;
+pub mod remote_signer;
 mod safety_rules;
 mod safety_rules_2chain;
 mod safety_rules_manager;
 mod serializer;
+mod slashing_protection;
 mod t_safety_rules;
 mod thread;
 
 pub use crate::{
-    consensus_state::ConsensusState, error::Error,
-    persistent_safety_storage::PersistentSafetyStorage, process::Process,
-    safety_rules::SafetyRules, safety_rules_manager::SafetyRulesManager,
+    consensus_signer::{ConsensusSigner, LocalSigner, SigningRequest},
+    consensus_state::ConsensusState,
+    error::Error,
+    persistent_safety_storage::PersistentSafetyStorage,
+    process::Process,
+    safety_rules::SafetyRules,
+    safety_rules_manager::SafetyRulesManager,
     t_safety_rules::TSafetyRules,
 };
 
@@ -0,0 +1,36 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_config::config::{PersistableConfig, SafetyRulesConfig};
+use aptos_logger::info;
+use aptos_safety_rules::Process;
+use clap::Parser;
+use std::path::PathBuf;
+
+/// Runs SafetyRules as a standalone process, serving a remote consensus over an (optionally
+/// Noise authenticated) channel as configured by a `SafetyRulesService::Process` service.
+#[derive(Debug, Parser)]
+struct Args {
+    /// Path to the SafetyRules config
+    #[clap(value_parser)]
+    pub config: PathBuf,
+}
+
+fn main() {
+    let args = Args::parse();
+    let config = SafetyRulesConfig::load_config(&args.config)
+        .unwrap_or_else(|error| panic!("Unable to load config {:?}: {}", args.config, error));
+    aptos_logger::Logger::new()
+        .level(config.logger.level)
+        .init();
+
+    info!("Starting SafetyRules process with config {:?}", args.config);
+    let mut process = Process::new(config);
+    process.start();
+}
+
+#[test]
+fn verify_tool() {
+    use clap::CommandFactory;
+    Args::command().debug_assert()
+}
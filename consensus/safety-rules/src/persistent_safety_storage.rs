@@ -32,11 +32,44 @@ impl PersistentSafetyStorage {
     /// Use this to instantiate a PersistentStorage for a new data store, one that has no
     /// SafetyRules values set.
     pub fn initialize(
-        mut internal_store: Storage,
+        internal_store: Storage,
         author: Author,
         consensus_private_key: bls12381::PrivateKey,
         waypoint: Waypoint,
         enable_cached_safety_data: bool,
+    ) -> Self {
+        Self::initialize_internal(
+            internal_store,
+            author,
+            Some(consensus_private_key),
+            waypoint,
+            enable_cached_safety_data,
+        )
+    }
+
+    /// Use this to instantiate a PersistentStorage for a new data store when signing is delegated
+    /// to a remote signer, the consensus key is never stored.
+    pub fn initialize_without_consensus_key(
+        internal_store: Storage,
+        author: Author,
+        waypoint: Waypoint,
+        enable_cached_safety_data: bool,
+    ) -> Self {
+        Self::initialize_internal(
+            internal_store,
+            author,
+            None,
+            waypoint,
+            enable_cached_safety_data,
+        )
+    }
+
+    fn initialize_internal(
+        mut internal_store: Storage,
+        author: Author,
+        consensus_private_key: Option<bls12381::PrivateKey>,
+        waypoint: Waypoint,
+        enable_cached_safety_data: bool,
     ) -> Self {
         // Initialize the keys and accounts
         Self::initialize_keys_and_accounts(&mut internal_store, author, consensus_private_key)
@@ -64,17 +97,19 @@ impl PersistentSafetyStorage {
     fn initialize_keys_and_accounts(
         internal_store: &mut Storage,
         author: Author,
-        consensus_private_key: bls12381::PrivateKey,
+        consensus_private_key: Option<bls12381::PrivateKey>,
     ) -> Result<(), Error> {
-        let result = internal_store.set(CONSENSUS_KEY, consensus_private_key);
-        // Attempting to re-initialize existing storage. This can happen in environments like
-        // forge. Rather than be rigid here, leave it up to the developer to detect
-        // inconsistencies or why they did not reset storage between rounds. Do not repeat the
-        // checks again below, because it is just too strange to have a partially configured
-        // storage.
-        if let Err(aptos_secure_storage::Error::KeyAlreadyExists(_)) = result {
-            warn!("Attempted to re-initialize existing storage");
-            return Ok(());
+        if let Some(consensus_private_key) = consensus_private_key {
+            let result = internal_store.set(CONSENSUS_KEY, consensus_private_key);
+            // Attempting to re-initialize existing storage. This can happen in environments like
+            // forge. Rather than be rigid here, leave it up to the developer to detect
+            // inconsistencies or why they did not reset storage between rounds. Do not repeat the
+            // checks again below, because it is just too strange to have a partially configured
+            // storage.
+            if let Err(aptos_secure_storage::Error::KeyAlreadyExists(_)) = result {
+                warn!("Attempted to re-initialize existing storage");
+                return Ok(());
+            }
         }
 
         internal_store.set(OWNER_ACCOUNT, author)?;
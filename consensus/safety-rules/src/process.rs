@@ -3,11 +3,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    persistent_safety_storage::PersistentSafetyStorage,
     remote_service::{self, RemoteService},
-    safety_rules_manager,
+    safety_rules_manager, SafetyRules,
 };
-use aptos_config::config::{SafetyRulesConfig, SafetyRulesService};
+use aptos_config::config::{RemoteServiceNoiseConfig, SafetyRulesConfig, SafetyRulesService};
 use std::net::SocketAddr;
 
 pub struct Process {
@@ -16,7 +15,7 @@ pub struct Process {
 
 impl Process {
     pub fn new(config: SafetyRulesConfig) -> Self {
-        let storage = safety_rules_manager::storage(&config);
+        let safety_rules = safety_rules_manager::safety_rules(&config);
 
         let service = match &config.service {
             SafetyRulesService::Process(service) => service,
@@ -27,35 +26,51 @@ impl Process {
         Self {
             data: Some(ProcessData {
                 server_addr,
-                storage,
+                safety_rules,
                 network_timeout: config.network_timeout_ms,
+                noise_config: service.noise.clone(),
             }),
         }
     }
 
     pub fn start(&mut self) {
         let data = self.data.take().expect("Unable to retrieve ProcessData");
-        remote_service::execute(data.storage, data.server_addr, data.network_timeout);
+        remote_service::execute(
+            data.safety_rules,
+            data.server_addr,
+            data.network_timeout,
+            data.noise_config,
+        );
     }
 }
 
 struct ProcessData {
     server_addr: SocketAddr,
-    storage: PersistentSafetyStorage,
+    safety_rules: SafetyRules,
     // Timeout in Seconds for network operations
     network_timeout: u64,
+    noise_config: Option<RemoteServiceNoiseConfig>,
 }
 
 pub struct ProcessService {
     server_addr: SocketAddr,
     network_timeout_ms: u64,
+    request_timeout_ms: u64,
+    noise_config: Option<RemoteServiceNoiseConfig>,
 }
 
 impl ProcessService {
-    pub fn new(server_addr: SocketAddr, network_timeout: u64) -> Self {
+    pub fn new(
+        server_addr: SocketAddr,
+        network_timeout: u64,
+        request_timeout: u64,
+        noise_config: Option<RemoteServiceNoiseConfig>,
+    ) -> Self {
         Self {
             server_addr,
             network_timeout_ms: network_timeout,
+            request_timeout_ms: request_timeout,
+            noise_config,
         }
     }
 }
@@ -68,4 +83,12 @@ impl RemoteService for ProcessService {
     fn network_timeout_ms(&self) -> u64 {
         self.network_timeout_ms
     }
+
+    fn request_timeout_ms(&self) -> u64 {
+        self.request_timeout_ms
+    }
+
+    fn noise_config(&self) -> Option<&RemoteServiceNoiseConfig> {
+        self.noise_config.as_ref()
+    }
 }
@@ -3,22 +3,41 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    persistent_safety_storage::PersistentSafetyStorage,
+    counters,
     serializer::{SafetyRulesInput, SerializerClient, SerializerService, TSerializerClient},
-    Error, SafetyRules, TSafetyRules,
+    ConsensusState, Error, SafetyRules, TSafetyRules,
 };
-use aptos_logger::warn;
-use aptos_secure_net::{NetworkClient, NetworkServer};
-use std::net::SocketAddr;
+use aptos_config::config::RemoteServiceNoiseConfig;
+use aptos_consensus_types::common::Round;
+use aptos_logger::{info, warn};
+use aptos_secure_net::{NetworkClient, NetworkServer, NoiseKeys};
+use std::{
+    net::SocketAddr,
+    thread,
+    time::{Duration, Instant},
+};
+
+const SERVICE_NAME: &str = "safety-rules";
+
+/// Delay between two attempts of the same request
+const RETRY_INTERVAL: Duration = Duration::from_millis(100);
 
 pub trait RemoteService {
     fn client(&self) -> SerializerClient {
-        let network_client = NetworkClient::new(
-            "safety-rules".to_string(),
-            self.server_address(),
-            self.network_timeout_ms(),
-        );
-        let service = Box::new(RemoteClient::new(network_client));
+        let network_client = match self.noise_config() {
+            Some(noise_config) => NetworkClient::new_with_noise(
+                SERVICE_NAME.to_string(),
+                self.server_address(),
+                self.network_timeout_ms(),
+                noise_keys(noise_config),
+            ),
+            None => NetworkClient::new(
+                SERVICE_NAME.to_string(),
+                self.server_address(),
+                self.network_timeout_ms(),
+            ),
+        };
+        let service = Box::new(RemoteClient::new(network_client, self.request_timeout_ms()));
         SerializerClient::new_client(service)
     }
 
@@ -26,17 +45,44 @@ pub trait RemoteService {
 
     /// Network Timeout in milliseconds.
     fn network_timeout_ms(&self) -> u64;
+
+    /// Deadline for a single request, including reconnects, in milliseconds.
+    fn request_timeout_ms(&self) -> u64;
+
+    /// The keys used to authenticate the channel, if any.
+    fn noise_config(&self) -> Option<&RemoteServiceNoiseConfig>;
 }
 
-pub fn execute(storage: PersistentSafetyStorage, listen_addr: SocketAddr, network_timeout_ms: u64) {
-    let mut safety_rules = SafetyRules::new(storage);
+pub fn noise_keys(noise_config: &RemoteServiceNoiseConfig) -> NoiseKeys {
+    NoiseKeys::new(
+        noise_config.private_key.private_key(),
+        noise_config.remote_public_key,
+    )
+}
+
+pub fn execute(
+    mut safety_rules: SafetyRules,
+    listen_addr: SocketAddr,
+    network_timeout_ms: u64,
+    noise_config: Option<RemoteServiceNoiseConfig>,
+) {
     if let Err(e) = safety_rules.consensus_state() {
         warn!("Unable to print consensus state: {}", e);
     }
 
     let mut serializer_service = SerializerService::new(safety_rules);
-    let mut network_server =
-        NetworkServer::new("safety-rules".to_string(), listen_addr, network_timeout_ms);
+    let mut network_server = match &noise_config {
+        Some(noise_config) => NetworkServer::new_with_noise(
+            SERVICE_NAME.to_string(),
+            listen_addr,
+            network_timeout_ms,
+            noise_keys(noise_config),
+        ),
+        None => {
+            warn!("SafetyRules service is running without an authenticated channel");
+            NetworkServer::new(SERVICE_NAME.to_string(), listen_addr, network_timeout_ms)
+        },
+    };
 
     loop {
         if let Err(e) = process_one_message(&mut network_server, &mut serializer_service) {
@@ -57,26 +103,113 @@ fn process_one_message(
 
 struct RemoteClient {
     network_client: NetworkClient,
+    request_timeout: Duration,
+    /// The connection on which the state of the remote service was last checked
+    checked_connection: Option<u64>,
+    /// The highest (epoch, last voted round) the remote service reported or signed so far
+    highest_observed_state: Option<(u64, Round)>,
 }
 
 impl RemoteClient {
-    pub fn new(network_client: NetworkClient) -> Self {
-        Self { network_client }
+    pub fn new(network_client: NetworkClient, request_timeout_ms: u64) -> Self {
+        Self {
+            network_client,
+            request_timeout: Duration::from_millis(request_timeout_ms),
+            checked_connection: None,
+            highest_observed_state: None,
+        }
     }
 
     fn process_one_message(&mut self, input: &[u8]) -> Result<Vec<u8>, Error> {
         self.network_client.write(input)?;
         self.network_client.read().map_err(|e| e.into())
     }
+
+    /// On every new connection, and before sending any other request on it, verifies that the
+    /// remote service did not lose state, e.g., because it was restarted on a stale copy of its
+    /// storage. Signing on top of regressed safety data could lead to equivocation.
+    fn check_remote_state(&mut self) -> Result<(), Error> {
+        if self.network_client.is_connected()
+            && self.checked_connection == Some(self.network_client.num_connections())
+        {
+            return Ok(());
+        }
+
+        let request = serde_json::to_vec(&SafetyRulesInput::ConsensusState)?;
+        let response = self.process_one_message(&request)?;
+        let state: ConsensusState = serde_json::from_slice::<Result<_, Error>>(&response)??;
+        let observed_state = (state.epoch(), state.last_voted_round());
+
+        if let Some(highest_observed_state) = self.highest_observed_state {
+            if observed_state < highest_observed_state {
+                counters::increment_remote_request("state_regressed");
+                return Err(Error::RemoteStateRegressed(
+                    observed_state.0,
+                    observed_state.1,
+                    highest_observed_state.0,
+                    highest_observed_state.1,
+                ));
+            }
+        }
+
+        info!(
+            "Connected to SafetyRules service with epoch {} and last voted round {}",
+            observed_state.0, observed_state.1
+        );
+        self.observe_state(observed_state);
+        self.checked_connection = Some(self.network_client.num_connections());
+        Ok(())
+    }
+
+    fn observe_state(&mut self, observed_state: (u64, Round)) {
+        self.highest_observed_state =
+            std::cmp::max(self.highest_observed_state, Some(observed_state));
+    }
+
+    /// Once the remote service successfully signed a vote or a timeout, its last voted round is
+    /// at least the round of that vote or timeout.
+    fn observe_response(&mut self, input: &SafetyRulesInput, response: &[u8]) {
+        let voted_state = match input {
+            SafetyRulesInput::ConstructAndSignVoteTwoChain(vote_proposal, _) => {
+                (vote_proposal.block().epoch(), vote_proposal.block().round())
+            },
+            SafetyRulesInput::SignTimeoutWithQC(timeout, _) => (timeout.epoch(), timeout.round()),
+            _ => return,
+        };
+        if let Ok(Ok(_)) = serde_json::from_slice::<Result<serde_json::Value, Error>>(response) {
+            self.observe_state(voted_state);
+        }
+    }
 }
 
 impl TSerializerClient for RemoteClient {
     fn request(&mut self, input: SafetyRulesInput) -> Result<Vec<u8>, Error> {
+        let _timer = counters::start_timer("remote", "request");
         let input_message = serde_json::to_vec(&input)?;
+        let deadline = Instant::now() + self.request_timeout;
         loop {
-            match self.process_one_message(&input_message) {
-                Err(err) => warn!("Failed to communicate with SafetyRules service: {}", err),
-                Ok(value) => return Ok(value),
+            let result = self
+                .check_remote_state()
+                .and_then(|_| self.process_one_message(&input_message));
+            match result {
+                Ok(value) => {
+                    counters::increment_remote_request("success");
+                    self.observe_response(&input, &value);
+                    return Ok(value);
+                },
+                Err(err @ Error::RemoteStateRegressed(..)) => return Err(err),
+                Err(err) => {
+                    counters::increment_remote_request("failure");
+                    warn!("Failed to communicate with SafetyRules service: {}", err);
+                    if Instant::now() >= deadline {
+                        counters::increment_remote_request("timeout");
+                        return Err(Error::RemoteRequestTimeout(
+                            self.request_timeout.as_millis() as u64,
+                            err.to_string(),
+                        ));
+                    }
+                    thread::sleep(RETRY_INTERVAL);
+                },
             }
         }
     }
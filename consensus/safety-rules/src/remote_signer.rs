@@ -0,0 +1,424 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! A small protocol that lets SafetyRules delegate consensus signatures to a separate signer
+//! daemon, so that the consensus key never has to live in the validator process. The daemon does
+//! not trust SafetyRules: it verifies what it is asked to sign and enforces its own slashing
+//! protection, persisted next to the consensus key.
+//!
+//! The protocol uses the same framing as the remote SafetyRules service: JSON encoded requests,
+//! answered by a JSON encoded `Result<T, Error>`, over an optionally Noise authenticated channel.
+
+use crate::{
+    consensus_signer::{ConsensusSigner, LocalSigner, SigningRequest},
+    counters,
+    remote_service::noise_keys,
+    slashing_protection::SlashingProtectionData,
+    Error,
+};
+use aptos_config::config::{RemoteServiceNoiseConfig, RemoteSignerConfig};
+use aptos_consensus_types::common::Author;
+use aptos_crypto::{bls12381, hash::CryptoHash, PrivateKey};
+use aptos_global_constants::{CONSENSUS_KEY, SLASHING_PROTECTION};
+use aptos_infallible::Mutex;
+use aptos_logger::{info, warn};
+use aptos_secure_net::{NetworkClient, NetworkServer};
+use aptos_secure_storage::{KVStorage, Storage};
+use aptos_types::validator_signer::ValidatorSigner;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{convert::TryInto, net::SocketAddr};
+
+const SERVICE_NAME: &str = "consensus-signer";
+
+/// A failed request is retried once on a fresh connection, e.g., after the daemon restarted
+const REQUEST_ATTEMPTS: usize = 2;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub enum RemoteSignerInput<'a> {
+    /// Returns the author the daemon signs for
+    Author,
+    /// Returns whether the daemon holds the private key of the given consensus key
+    HasKey(bls12381::PublicKey),
+    /// Signs the request with the given consensus key
+    Sign(bls12381::PublicKey, SigningRequest<'a>),
+}
+
+/// Where to find the remote signer daemon
+#[derive(Clone)]
+pub struct RemoteSignerEndpoint {
+    server_address: SocketAddr,
+    network_timeout_ms: u64,
+    noise_config: Option<RemoteServiceNoiseConfig>,
+}
+
+impl RemoteSignerEndpoint {
+    pub fn new(
+        server_address: SocketAddr,
+        network_timeout_ms: u64,
+        noise_config: Option<RemoteServiceNoiseConfig>,
+    ) -> Self {
+        Self {
+            server_address,
+            network_timeout_ms,
+            noise_config,
+        }
+    }
+
+    fn network_client(&self) -> NetworkClient {
+        match &self.noise_config {
+            Some(noise_config) => NetworkClient::new_with_noise(
+                SERVICE_NAME.to_string(),
+                self.server_address,
+                self.network_timeout_ms,
+                noise_keys(noise_config),
+            ),
+            None => NetworkClient::new(
+                SERVICE_NAME.to_string(),
+                self.server_address,
+                self.network_timeout_ms,
+            ),
+        }
+    }
+}
+
+/// Signs through a remote signer daemon that holds the consensus key
+pub struct RemoteSigner {
+    author: Author,
+    public_key: bls12381::PublicKey,
+    network_client: Mutex<NetworkClient>,
+}
+
+impl RemoteSigner {
+    /// Connects to the remote signer and verifies that it signs for the author with the expected
+    /// consensus key
+    pub fn connect(
+        endpoint: &RemoteSignerEndpoint,
+        author: Author,
+        expected_key: bls12381::PublicKey,
+    ) -> Result<Self, Error> {
+        let mut network_client = endpoint.network_client();
+
+        let remote_author: Author = request(&mut network_client, &RemoteSignerInput::Author)?;
+        if remote_author != author {
+            return Err(Error::InvalidRemoteSigner(format!(
+                "expected author {}, found {}",
+                author, remote_author
+            )));
+        }
+
+        let has_key: bool = request(
+            &mut network_client,
+            &RemoteSignerInput::HasKey(expected_key.clone()),
+        )?;
+        if !has_key {
+            return Err(Error::SecureStorageMissingDataError(format!(
+                "PrivateKey for {:?} not found on the remote signer",
+                expected_key
+            )));
+        }
+
+        info!("Connected to the remote signer for {}", author);
+        Ok(Self {
+            author,
+            public_key: expected_key,
+            network_client: Mutex::new(network_client),
+        })
+    }
+}
+
+impl ConsensusSigner for RemoteSigner {
+    fn author(&self) -> Author {
+        self.author
+    }
+
+    fn public_key(&self) -> bls12381::PublicKey {
+        self.public_key.clone()
+    }
+
+    fn sign(&self, request: &SigningRequest) -> Result<bls12381::Signature, Error> {
+        let _timer = counters::start_timer("remote_signer", request.name());
+        let input = RemoteSignerInput::Sign(self.public_key.clone(), request.reborrow());
+        self::request(&mut self.network_client.lock(), &input)
+    }
+}
+
+/// Sends a request and parses the response, retrying on network failures only
+fn request<T: DeserializeOwned>(
+    network_client: &mut NetworkClient,
+    input: &RemoteSignerInput,
+) -> Result<T, Error> {
+    let input_message = serde_json::to_vec(input)?;
+    let mut attempt = 0;
+    let response = loop {
+        attempt += 1;
+        let result = network_client
+            .write(&input_message)
+            .and_then(|_| network_client.read());
+        match result {
+            Ok(response) => break response,
+            Err(error) if attempt < REQUEST_ATTEMPTS => {
+                warn!("Failed to communicate with the remote signer: {}", error);
+            },
+            Err(error) => return Err(error.into()),
+        }
+    };
+    serde_json::from_slice::<Result<T, Error>>(&response)?
+}
+
+/// The daemon side of the protocol
+pub struct RemoteSignerService {
+    author: Author,
+    storage: Storage,
+    /// The signer of the consensus key last read from storage
+    consensus_signer: Option<LocalSigner>,
+    /// A copy of the persisted slashing protection data
+    slashing_protection: Option<SlashingProtectionData>,
+}
+
+impl RemoteSignerService {
+    pub fn new(author: Author, storage: Storage) -> Self {
+        Self {
+            author,
+            storage,
+            consensus_signer: None,
+            slashing_protection: None,
+        }
+    }
+
+    pub fn handle_message(&mut self, input_message: Vec<u8>) -> Result<Vec<u8>, Error> {
+        let input = serde_json::from_slice(&input_message)?;
+
+        let output = match input {
+            RemoteSignerInput::Author => serde_json::to_vec(&Ok::<_, Error>(self.author)),
+            RemoteSignerInput::HasKey(public_key) => serde_json::to_vec(&self.has_key(public_key)),
+            RemoteSignerInput::Sign(public_key, request) => {
+                let result = self.sign(public_key, &request);
+                let label = if result.is_ok() { "success" } else { "error" };
+                counters::increment_remote_signer_request(request.name(), label);
+                if let Err(error) = &result {
+                    warn!("Refused to sign {}: {}", request.name(), error);
+                }
+                serde_json::to_vec(&result)
+            },
+        };
+
+        Ok(output?)
+    }
+
+    fn has_key(&mut self, public_key: bls12381::PublicKey) -> Result<bool, Error> {
+        match self.signer_for(public_key) {
+            Ok(_) => Ok(true),
+            Err(Error::SecureStorageMissingDataError(_)) => Ok(false),
+            Err(error) => Err(error),
+        }
+    }
+
+    fn signer_for(&mut self, public_key: bls12381::PublicKey) -> Result<&LocalSigner, Error> {
+        let cached = self
+            .consensus_signer
+            .as_ref()
+            .map_or(false, |signer| signer.public_key() == public_key);
+        if !cached {
+            let consensus_key: bls12381::PrivateKey =
+                self.storage.get(CONSENSUS_KEY).map(|v| v.value)?;
+            if consensus_key.public_key() != public_key {
+                return Err(Error::SecureStorageMissingDataError(format!(
+                    "PrivateKey for {:?} not found",
+                    public_key
+                )));
+            }
+            self.consensus_signer = Some(LocalSigner::new(ValidatorSigner::new(
+                self.author,
+                consensus_key,
+            )));
+        }
+        Ok(self
+            .consensus_signer
+            .as_ref()
+            .expect("The consensus signer was just set"))
+    }
+
+    fn slashing_protection(&self) -> Result<SlashingProtectionData, Error> {
+        if let Some(slashing_protection) = &self.slashing_protection {
+            return Ok(slashing_protection.clone());
+        }
+        match self.storage.get(SLASHING_PROTECTION) {
+            Ok(response) => Ok(response.value),
+            Err(aptos_secure_storage::Error::KeyNotSet(_)) => Ok(SlashingProtectionData::new(0)),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    fn sign(
+        &mut self,
+        public_key: bls12381::PublicKey,
+        request: &SigningRequest,
+    ) -> Result<bls12381::Signature, Error> {
+        // Make sure the signature on a vote actually covers the vote data that slashing
+        // protection looks at
+        if let SigningRequest::Vote(vote_data, ledger_info) = request {
+            if ledger_info.consensus_data_hash() != vote_data.hash() {
+                return Err(Error::SlashingProtectionViolation(
+                    "the ledger info of the vote does not match the vote data".into(),
+                ));
+            }
+        }
+        self.signer_for(public_key)?;
+
+        let mut slashing_protection = self.slashing_protection()?;
+        slashing_protection.check_and_update(request)?;
+        // Persist before the signature leaves the signer
+        self.storage
+            .set(SLASHING_PROTECTION, slashing_protection.clone())?;
+        self.slashing_protection = Some(slashing_protection);
+
+        self.consensus_signer
+            .as_ref()
+            .ok_or_else(|| Error::NotInitialized("consensus_signer".into()))?
+            .sign(request)
+    }
+}
+
+/// Serves signing requests until the process is stopped
+pub fn execute(
+    mut service: RemoteSignerService,
+    listen_addr: SocketAddr,
+    network_timeout_ms: u64,
+    noise_config: Option<RemoteServiceNoiseConfig>,
+) {
+    let mut network_server = match &noise_config {
+        Some(noise_config) => NetworkServer::new_with_noise(
+            SERVICE_NAME.to_string(),
+            listen_addr,
+            network_timeout_ms,
+            noise_keys(noise_config),
+        ),
+        None => {
+            warn!("Remote signer is running without an authenticated channel");
+            NetworkServer::new(SERVICE_NAME.to_string(), listen_addr, network_timeout_ms)
+        },
+    };
+
+    loop {
+        if let Err(e) = process_one_message(&mut network_server, &mut service) {
+            warn!("Failed to process message: {}", e);
+        }
+    }
+}
+
+fn process_one_message(
+    network_server: &mut NetworkServer,
+    service: &mut RemoteSignerService,
+) -> Result<(), Error> {
+    let request = network_server.read()?;
+    let response = service.handle_message(request)?;
+    network_server.write(&response)?;
+    Ok(())
+}
+
+/// Starts the remote signer daemon described by the config
+pub fn start(config: RemoteSignerConfig) {
+    let mut storage: Storage = (&config.backend)
+        .try_into()
+        .expect("Unable to initialize storage");
+    if let Err(error) = storage.available() {
+        panic!("Storage is not available: {:?}", error);
+    }
+
+    if let Some(consensus_key) = &config.initial_consensus_key {
+        match storage.set(CONSENSUS_KEY, consensus_key.private_key()) {
+            Ok(()) => info!("Initialized the consensus key of the remote signer"),
+            Err(aptos_secure_storage::Error::KeyAlreadyExists(_)) => (),
+            Err(error) => panic!("Unable to initialize the consensus key: {:?}", error),
+        }
+    }
+
+    let service = RemoteSignerService::new(config.author, storage);
+    execute(
+        service,
+        config.listen_address(),
+        config.network_timeout_ms,
+        config.noise,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils;
+    use aptos_consensus_types::common::Payload;
+    use aptos_secure_storage::InMemoryStorage;
+    use std::borrow::Cow;
+
+    fn handle<T: DeserializeOwned>(
+        service: &mut RemoteSignerService,
+        input: &RemoteSignerInput,
+    ) -> Result<T, Error> {
+        let response = service
+            .handle_message(serde_json::to_vec(input).unwrap())
+            .unwrap();
+        serde_json::from_slice::<Result<T, Error>>(&response).unwrap()
+    }
+
+    #[test]
+    fn test_remote_signer_service() {
+        let signer = ValidatorSigner::from_int(0);
+        let mut storage = Storage::from(InMemoryStorage::new());
+        storage
+            .set(CONSENSUS_KEY, signer.private_key().clone())
+            .unwrap();
+        let mut service = RemoteSignerService::new(signer.author(), storage);
+
+        let author: Author = handle(&mut service, &RemoteSignerInput::Author).unwrap();
+        assert_eq!(author, signer.author());
+        let other_key = ValidatorSigner::from_int(1).public_key();
+        assert!(handle::<bool>(
+            &mut service,
+            &RemoteSignerInput::HasKey(signer.public_key())
+        )
+        .unwrap());
+        assert!(
+            !handle::<bool>(&mut service, &RemoteSignerInput::HasKey(other_key.clone())).unwrap()
+        );
+
+        let (_, genesis_qc) = test_utils::make_genesis(&signer);
+        let proposal = test_utils::make_proposal_with_qc(1, genesis_qc, &signer);
+        let block_data = proposal.block().block_data();
+        let request = SigningRequest::Proposal(Cow::Borrowed(block_data));
+
+        // Only the held key signs, and the signature matches a local signature
+        handle::<bls12381::Signature>(
+            &mut service,
+            &RemoteSignerInput::Sign(other_key, request.reborrow()),
+        )
+        .unwrap_err();
+        let signature: bls12381::Signature = handle(
+            &mut service,
+            &RemoteSignerInput::Sign(signer.public_key(), request.reborrow()),
+        )
+        .unwrap();
+        assert_eq!(signature, signer.sign(block_data).unwrap());
+
+        // Slashing protection survives a restart of the daemon
+        let storage =
+            std::mem::replace(&mut service.storage, Storage::from(InMemoryStorage::new()));
+        let mut service = RemoteSignerService::new(signer.author(), storage);
+        let (_, genesis_qc) = test_utils::make_genesis(&signer);
+        let equivocation = test_utils::make_proposal_with_qc_and_proof(
+            Payload::empty(true),
+            1,
+            test_utils::empty_proof(),
+            genesis_qc,
+            &signer,
+        );
+        let request = SigningRequest::Proposal(Cow::Borrowed(equivocation.block().block_data()));
+        assert!(matches!(
+            handle::<bls12381::Signature>(
+                &mut service,
+                &RemoteSignerInput::Sign(signer.public_key(), request),
+            )
+            .unwrap_err(),
+            Error::SlashingProtectionViolation(_)
+        ));
+    }
+}
@@ -3,11 +3,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    consensus_signer::{ConsensusSigner, LocalSigner, SigningRequest},
     consensus_state::ConsensusState,
     counters,
     error::Error,
     logging::{LogEntry, LogEvent, SafetyLogSchema},
     persistent_safety_storage::PersistentSafetyStorage,
+    remote_signer::{RemoteSigner, RemoteSignerEndpoint},
     t_safety_rules::TSafetyRules,
 };
 use aptos_consensus_types::{
@@ -20,7 +22,7 @@ use aptos_consensus_types::{
     vote_data::VoteData,
     vote_proposal::VoteProposal,
 };
-use aptos_crypto::{bls12381, hash::CryptoHash};
+use aptos_crypto::bls12381;
 use aptos_logger::prelude::*;
 use aptos_types::{
     epoch_change::EpochChangeProof,
@@ -29,8 +31,7 @@ use aptos_types::{
     validator_signer::ValidatorSigner,
     waypoint::Waypoint,
 };
-use serde::Serialize;
-use std::cmp::Ordering;
+use std::{borrow::Cow, cmp::Ordering};
 
 pub(crate) fn next_round(round: Round) -> Result<Round, Error> {
     u64::checked_add(round, 1).ok_or(Error::IncorrectRound(round))
@@ -39,8 +40,11 @@ pub(crate) fn next_round(round: Round) -> Result<Round, Error> {
 /// @TODO consider a cache of verified QCs to cut down on verification costs
 pub struct SafetyRules {
     pub(crate) persistent_storage: PersistentSafetyStorage,
-    pub(crate) validator_signer: Option<ValidatorSigner>,
+    pub(crate) validator_signer: Option<Box<dyn ConsensusSigner>>,
     pub(crate) epoch_state: Option<EpochState>,
+    /// If set, signing is delegated to this remote signer instead of using the consensus key in
+    /// the persistent storage
+    remote_signer: Option<RemoteSignerEndpoint>,
 }
 
 impl SafetyRules {
@@ -51,6 +55,19 @@ impl SafetyRules {
             persistent_storage,
             validator_signer: None,
             epoch_state: None,
+            remote_signer: None,
+        }
+    }
+
+    /// Constructs a new instance of SafetyRules that delegates all signing to a remote signer,
+    /// the consensus private keys never need to be present in the persistent storage
+    pub fn new_with_remote_signer(
+        persistent_storage: PersistentSafetyStorage,
+        remote_signer: RemoteSignerEndpoint,
+    ) -> Self {
+        Self {
+            remote_signer: Some(remote_signer),
+            ..Self::new(persistent_storage)
         }
     }
 
@@ -77,22 +94,41 @@ impl SafetyRules {
             .map_err(|error| Error::InvalidAccumulatorExtension(error.to_string()))
     }
 
-    pub(crate) fn sign<T: Serialize + CryptoHash>(
-        &self,
-        message: &T,
-    ) -> Result<bls12381::Signature, Error> {
-        let signer = self.signer()?;
-        signer
-            .sign(message)
-            .map_err(|err| Error::SerializationError(err.to_string()))
+    pub(crate) fn sign(&self, request: &SigningRequest) -> Result<bls12381::Signature, Error> {
+        self.signer()?.sign(request)
     }
 
-    pub(crate) fn signer(&self) -> Result<&ValidatorSigner, Error> {
+    pub(crate) fn signer(&self) -> Result<&dyn ConsensusSigner, Error> {
         self.validator_signer
-            .as_ref()
+            .as_deref()
             .ok_or_else(|| Error::NotInitialized("validator_signer".into()))
     }
 
+    /// Finds a signer for the expected consensus key, either from the persistent storage or
+    /// through the remote signer
+    fn consensus_signer(
+        &self,
+        author: Author,
+        expected_key: bls12381::PublicKey,
+    ) -> Result<Box<dyn ConsensusSigner>, Error> {
+        match &self.remote_signer {
+            Some(remote_signer) => Ok(Box::new(RemoteSigner::connect(
+                remote_signer,
+                author,
+                expected_key,
+            )?)),
+            None => {
+                let consensus_key = self
+                    .persistent_storage
+                    .consensus_key_for_version(expected_key)?;
+                Ok(Box::new(LocalSigner::new(ValidatorSigner::new(
+                    author,
+                    consensus_key,
+                ))))
+            },
+        }
+    }
+
     pub(crate) fn epoch_state(&self) -> Result<&EpochState, Error> {
         self.epoch_state
             .as_ref()
@@ -272,14 +308,11 @@ impl SafetyRules {
                     );
                     Ok(())
                 } else {
-                    // Try to export the consensus key directly from storage.
-                    match self
-                        .persistent_storage
-                        .consensus_key_for_version(expected_key)
-                    {
-                        Ok(consensus_key) => {
-                            self.validator_signer =
-                                Some(ValidatorSigner::new(author, consensus_key));
+                    // Try to export the consensus key directly from storage, or find it on the
+                    // remote signer.
+                    match self.consensus_signer(author, expected_key.clone()) {
+                        Ok(consensus_signer) => {
+                            self.validator_signer = Some(consensus_signer);
                             Ok(())
                         },
                         Err(Error::SecureStorageMissingDataError(error)) => {
@@ -321,7 +354,7 @@ impl SafetyRules {
         self.verify_and_update_preferred_round(block_data.quorum_cert(), &mut safety_data)?;
         // we don't persist the updated preferred round to save latency (it'd be updated upon voting)
 
-        let signature = self.sign(block_data)?;
+        let signature = self.sign(&SigningRequest::Proposal(Cow::Borrowed(block_data)))?;
         Ok(signature)
     }
 
@@ -356,7 +389,7 @@ impl SafetyRules {
         // TODO: add guarding rules in unhappy path
         // TODO: add extension check
 
-        let signature = self.sign(&new_ledger_info)?;
+        let signature = self.sign(&SigningRequest::CommitVote(Cow::Borrowed(&new_ledger_info)))?;
 
         Ok(signature)
     }
@@ -2,7 +2,9 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{error::Error, safety_rules::next_round, SafetyRules};
+use crate::{
+    consensus_signer::SigningRequest, error::Error, safety_rules::next_round, SafetyRules,
+};
 use aptos_consensus_types::{
     block::Block,
     safety_data::SafetyData,
@@ -12,6 +14,7 @@ use aptos_consensus_types::{
 };
 use aptos_crypto::{bls12381, hash::CryptoHash, HashValue};
 use aptos_types::{block_info::BlockInfo, ledger_info::LedgerInfo};
+use std::borrow::Cow;
 
 /// 2-chain safety rules implementation
 impl SafetyRules {
@@ -42,7 +45,7 @@ impl SafetyRules {
             self.persistent_storage.set_safety_data(safety_data)?;
         }
 
-        let signature = self.sign(&timeout.signing_format())?;
+        let signature = self.sign(&SigningRequest::Timeout(timeout.signing_format()))?;
         Ok(signature)
     }
 
@@ -81,7 +84,10 @@ impl SafetyRules {
         // Construct and sign vote
         let author = self.signer()?.author();
         let ledger_info = self.construct_ledger_info_2chain(proposed_block, vote_data.hash())?;
-        let signature = self.sign(&ledger_info)?;
+        let signature = self.sign(&SigningRequest::Vote(
+            Cow::Borrowed(&vote_data),
+            Cow::Borrowed(&ledger_info),
+        ))?;
         let vote = Vote::new_with_signature(vote_data, author, ledger_info, signature);
 
         safety_data.last_vote = Some(vote.clone());
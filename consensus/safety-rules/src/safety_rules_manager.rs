@@ -7,11 +7,15 @@ use crate::{
     persistent_safety_storage::PersistentSafetyStorage,
     process::ProcessService,
     remote_service::RemoteService,
+    remote_signer::RemoteSignerEndpoint,
     serializer::{SerializerClient, SerializerService},
     thread::ThreadService,
     SafetyRules, TSafetyRules,
 };
-use aptos_config::config::{InitialSafetyRulesConfig, SafetyRulesConfig, SafetyRulesService};
+use aptos_config::config::{
+    ConsensusSignerConfig, InitialSafetyRulesConfig, RemoteServiceNoiseConfig, SafetyRulesConfig,
+    SafetyRulesService,
+};
 use aptos_infallible::RwLock;
 use aptos_secure_storage::{KVStorage, Storage};
 use std::{convert::TryInto, net::SocketAddr, sync::Arc};
@@ -55,17 +59,29 @@ pub fn storage(config: &SafetyRulesConfig) -> PersistentSafetyStorage {
             let backend = &config.backend;
             let internal_storage: Storage =
                 backend.try_into().expect("Unable to initialize storage");
-            PersistentSafetyStorage::initialize(
-                internal_storage,
-                identity_blob
-                    .account_address
-                    .expect("AccountAddress needed for safety rules"),
-                identity_blob
-                    .consensus_private_key
-                    .expect("Consensus key needed for safety rules"),
-                waypoint,
-                config.enable_cached_safety_data,
-            )
+            let author = identity_blob
+                .account_address
+                .expect("AccountAddress needed for safety rules");
+            match (&config.signer, identity_blob.consensus_private_key) {
+                // The consensus key is held by the remote signer, keep it out of this storage
+                (ConsensusSignerConfig::Remote(_), _) => {
+                    PersistentSafetyStorage::initialize_without_consensus_key(
+                        internal_storage,
+                        author,
+                        waypoint,
+                        config.enable_cached_safety_data,
+                    )
+                },
+                (ConsensusSignerConfig::Local, consensus_private_key) => {
+                    PersistentSafetyStorage::initialize(
+                        internal_storage,
+                        author,
+                        consensus_private_key.expect("Consensus key needed for safety rules"),
+                        waypoint,
+                        config.enable_cached_safety_data,
+                    )
+                },
+            }
         } else {
             panic!(
                 "Safety rules storage is not initialized, provide an initial safety rules config"
@@ -74,6 +90,22 @@ pub fn storage(config: &SafetyRulesConfig) -> PersistentSafetyStorage {
     }
 }
 
+/// Creates SafetyRules on top of the configured storage, signing with the configured signer
+pub fn safety_rules(config: &SafetyRulesConfig) -> SafetyRules {
+    let storage = storage(config);
+    match &config.signer {
+        ConsensusSignerConfig::Local => SafetyRules::new(storage),
+        ConsensusSignerConfig::Remote(remote_signer) => SafetyRules::new_with_remote_signer(
+            storage,
+            RemoteSignerEndpoint::new(
+                remote_signer.server_address(),
+                config.network_timeout_ms,
+                remote_signer.noise.clone(),
+            ),
+        ),
+    }
+}
+
 enum SafetyRulesWrapper {
     Local(Arc<RwLock<SafetyRules>>),
     Process(ProcessService),
@@ -88,34 +120,55 @@ pub struct SafetyRulesManager {
 impl SafetyRulesManager {
     pub fn new(config: &SafetyRulesConfig) -> Self {
         if let SafetyRulesService::Process(conf) = &config.service {
-            return Self::new_process(conf.server_address(), config.network_timeout_ms);
+            return Self::new_process(
+                conf.server_address(),
+                config.network_timeout_ms,
+                config.request_timeout_ms,
+                conf.noise.clone(),
+            );
         }
 
-        let storage = storage(config);
+        let safety_rules = safety_rules(config);
         match config.service {
-            SafetyRulesService::Local => Self::new_local(storage),
-            SafetyRulesService::Serializer => Self::new_serializer(storage),
-            SafetyRulesService::Thread => Self::new_thread(storage, config.network_timeout_ms),
+            SafetyRulesService::Local => Self::local(safety_rules),
+            SafetyRulesService::Serializer => Self::serializer(safety_rules),
+            SafetyRulesService::Thread => Self::thread(
+                safety_rules,
+                config.network_timeout_ms,
+                config.request_timeout_ms,
+            ),
             _ => panic!("Unimplemented SafetyRulesService: {:?}", config.service),
         }
     }
 
     pub fn new_local(storage: PersistentSafetyStorage) -> Self {
-        let safety_rules = SafetyRules::new(storage);
+        Self::local(SafetyRules::new(storage))
+    }
+
+    fn local(safety_rules: SafetyRules) -> Self {
         Self {
             internal_safety_rules: SafetyRulesWrapper::Local(Arc::new(RwLock::new(safety_rules))),
         }
     }
 
-    pub fn new_process(server_addr: SocketAddr, timeout_ms: u64) -> Self {
-        let process_service = ProcessService::new(server_addr, timeout_ms);
+    pub fn new_process(
+        server_addr: SocketAddr,
+        timeout_ms: u64,
+        request_timeout_ms: u64,
+        noise_config: Option<RemoteServiceNoiseConfig>,
+    ) -> Self {
+        let process_service =
+            ProcessService::new(server_addr, timeout_ms, request_timeout_ms, noise_config);
         Self {
             internal_safety_rules: SafetyRulesWrapper::Process(process_service),
         }
     }
 
     pub fn new_serializer(storage: PersistentSafetyStorage) -> Self {
-        let safety_rules = SafetyRules::new(storage);
+        Self::serializer(SafetyRules::new(storage))
+    }
+
+    fn serializer(safety_rules: SafetyRules) -> Self {
         let serializer_service = SerializerService::new(safety_rules);
         Self {
             internal_safety_rules: SafetyRulesWrapper::Serializer(Arc::new(RwLock::new(
@@ -124,8 +177,16 @@ impl SafetyRulesManager {
         }
     }
 
-    pub fn new_thread(storage: PersistentSafetyStorage, timeout_ms: u64) -> Self {
-        let thread = ThreadService::new(storage, timeout_ms);
+    pub fn new_thread(
+        storage: PersistentSafetyStorage,
+        timeout_ms: u64,
+        request_timeout_ms: u64,
+    ) -> Self {
+        Self::thread(SafetyRules::new(storage), timeout_ms, request_timeout_ms)
+    }
+
+    fn thread(safety_rules: SafetyRules, timeout_ms: u64, request_timeout_ms: u64) -> Self {
+        let thread = ThreadService::new(safety_rules, timeout_ms, request_timeout_ms);
         Self {
             internal_safety_rules: SafetyRulesWrapper::Thread(thread),
         }
@@ -0,0 +1,268 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! The remote signer cannot trust SafetyRules to only ask for safe signatures, so it keeps its own
+//! record of what it signed in the current epoch and refuses to sign anything that could
+//! equivocate with it. The rules are deliberately simpler and more conservative than the voting
+//! rules of SafetyRules: they only look at rounds, and at message hashes to allow re-signing the
+//! exact same message (e.g., after a restart of SafetyRules).
+
+use crate::{consensus_signer::SigningRequest, Error};
+use aptos_consensus_types::common::Round;
+use aptos_crypto::{hash::CryptoHash, HashValue};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// A message that was signed, identified by its round and hash
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SignedMessage {
+    pub round: Round,
+    pub hash: HashValue,
+}
+
+/// Everything the remote signer signed in the current epoch that is relevant to slashing
+/// protection. It is persisted before any signature leaves the signer.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SlashingProtectionData {
+    pub epoch: u64,
+    /// The highest round of a signed vote or timeout
+    pub last_voted_round: Round,
+    pub last_proposal: Option<SignedMessage>,
+    pub last_vote: Option<SignedMessage>,
+    pub last_commit_vote: Option<SignedMessage>,
+}
+
+impl SlashingProtectionData {
+    pub fn new(epoch: u64) -> Self {
+        Self {
+            epoch,
+            last_voted_round: 0,
+            last_proposal: None,
+            last_vote: None,
+            last_commit_vote: None,
+        }
+    }
+
+    /// Verifies that signing the request cannot equivocate with anything signed before and
+    /// records it. The caller must persist the updated data before releasing the signature.
+    pub fn check_and_update(&mut self, request: &SigningRequest) -> Result<(), Error> {
+        let (epoch, round) = request.epoch_and_round();
+        match epoch.cmp(&self.epoch) {
+            Ordering::Less => {
+                return Err(Error::SlashingProtectionViolation(format!(
+                    "epoch {} is older than the current epoch {}",
+                    epoch, self.epoch
+                )))
+            },
+            Ordering::Greater => *self = Self::new(epoch),
+            Ordering::Equal => (),
+        }
+
+        let signed_message = SignedMessage {
+            round,
+            hash: message_hash(request),
+        };
+        match request {
+            SigningRequest::Proposal(_) => {
+                if self.last_proposal == Some(signed_message) {
+                    return Ok(());
+                }
+                check_after("proposal", round, self.last_proposal)?;
+                if round <= self.last_voted_round {
+                    return Err(Error::SlashingProtectionViolation(format!(
+                        "proposal round {} is not after last voted round {}",
+                        round, self.last_voted_round
+                    )));
+                }
+                self.last_proposal = Some(signed_message);
+            },
+            SigningRequest::Vote(..) => {
+                if self.last_vote == Some(signed_message) {
+                    return Ok(());
+                }
+                if round <= self.last_voted_round {
+                    return Err(Error::SlashingProtectionViolation(format!(
+                        "vote round {} is not after last voted round {}",
+                        round, self.last_voted_round
+                    )));
+                }
+                self.last_voted_round = round;
+                self.last_vote = Some(signed_message);
+            },
+            SigningRequest::Timeout(_) => {
+                // A timeout may be signed for the round of the last vote, but no vote may be
+                // signed for the round of a timeout afterwards
+                if round < self.last_voted_round {
+                    return Err(Error::SlashingProtectionViolation(format!(
+                        "timeout round {} is older than last voted round {}",
+                        round, self.last_voted_round
+                    )));
+                }
+                self.last_voted_round = round;
+            },
+            SigningRequest::CommitVote(_) => {
+                if self.last_commit_vote == Some(signed_message) {
+                    return Ok(());
+                }
+                check_after("commit vote", round, self.last_commit_vote)?;
+                self.last_commit_vote = Some(signed_message);
+            },
+        }
+        Ok(())
+    }
+}
+
+fn check_after(name: &str, round: Round, last_signed: Option<SignedMessage>) -> Result<(), Error> {
+    match last_signed {
+        Some(last_signed) if round <= last_signed.round => {
+            Err(Error::SlashingProtectionViolation(format!(
+                "{} round {} is not after the last signed {} round {}",
+                name, round, name, last_signed.round
+            )))
+        },
+        _ => Ok(()),
+    }
+}
+
+fn message_hash(request: &SigningRequest) -> HashValue {
+    match request {
+        SigningRequest::Proposal(block_data) => block_data.hash(),
+        SigningRequest::Vote(_, ledger_info) => ledger_info.hash(),
+        SigningRequest::Timeout(timeout) => timeout.hash(),
+        SigningRequest::CommitVote(ledger_info) => ledger_info.hash(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils;
+    use aptos_consensus_types::{
+        block_data::{BlockData, BlockType},
+        timeout_2chain::TimeoutSigningRepr,
+        vote_data::VoteData,
+    };
+    use aptos_types::{
+        block_info::BlockInfo, ledger_info::LedgerInfo, validator_signer::ValidatorSigner,
+    };
+    use std::borrow::Cow;
+
+    fn proposal(round: Round, timestamp_usecs: u64) -> SigningRequest<'static> {
+        let signer = ValidatorSigner::from_int(0);
+        let (_, genesis_qc) = test_utils::make_genesis(&signer);
+        let block_data = BlockData::new_for_testing(
+            1,
+            round,
+            timestamp_usecs,
+            genesis_qc,
+            BlockType::NilBlock {
+                failed_authors: vec![],
+            },
+        );
+        SigningRequest::Proposal(Cow::Owned(block_data))
+    }
+
+    fn vote(epoch: u64, round: Round, commit_round: Round) -> SigningRequest<'static> {
+        let vote_data = VoteData::new(
+            BlockInfo::random_with_epoch(epoch, round),
+            BlockInfo::random_with_epoch(epoch, round.saturating_sub(1)),
+        );
+        let ledger_info = LedgerInfo::new(
+            BlockInfo::random_with_epoch(epoch, commit_round),
+            vote_data.hash(),
+        );
+        SigningRequest::Vote(Cow::Owned(vote_data), Cow::Owned(ledger_info))
+    }
+
+    fn timeout(epoch: u64, round: Round, hqc_round: Round) -> SigningRequest<'static> {
+        SigningRequest::Timeout(TimeoutSigningRepr {
+            epoch,
+            round,
+            hqc_round,
+        })
+    }
+
+    fn commit_vote(epoch: u64, round: Round, version: u64) -> SigningRequest<'static> {
+        let block_info = BlockInfo::new(
+            epoch,
+            round,
+            HashValue::zero(),
+            HashValue::zero(),
+            version,
+            0,
+            None,
+        );
+        SigningRequest::CommitVote(Cow::Owned(LedgerInfo::new(block_info, HashValue::zero())))
+    }
+
+    #[test]
+    fn test_votes_and_timeouts() {
+        let mut data = SlashingProtectionData::new(1);
+
+        data.check_and_update(&vote(1, 2, 0)).unwrap();
+        // Re-signing the exact same vote is fine
+        data.check_and_update(&vote(1, 2, 0)).unwrap();
+        // A different vote in the same round is an equivocation
+        data.check_and_update(&vote(1, 2, 1)).unwrap_err();
+        data.check_and_update(&vote(1, 1, 0)).unwrap_err();
+
+        // A timeout may follow a vote in the same round, but not precede one
+        data.check_and_update(&timeout(1, 2, 1)).unwrap();
+        data.check_and_update(&timeout(1, 3, 1)).unwrap();
+        data.check_and_update(&vote(1, 3, 0)).unwrap_err();
+        data.check_and_update(&timeout(1, 2, 1)).unwrap_err();
+        data.check_and_update(&vote(1, 4, 0)).unwrap();
+        assert_eq!(data.last_voted_round, 4);
+    }
+
+    #[test]
+    fn test_proposals() {
+        let mut data = SlashingProtectionData::new(1);
+
+        data.check_and_update(&proposal(1, 1)).unwrap();
+        data.check_and_update(&proposal(1, 1)).unwrap();
+        data.check_and_update(&proposal(1, 2)).unwrap_err();
+
+        // A leader votes for its own proposal, and may then re-sign it
+        data.check_and_update(&vote(1, 1, 0)).unwrap();
+        data.check_and_update(&proposal(1, 1)).unwrap();
+
+        // No proposal may be signed for a round that was already voted on
+        data.check_and_update(&vote(1, 2, 0)).unwrap();
+        data.check_and_update(&proposal(2, 1)).unwrap_err();
+        data.check_and_update(&proposal(3, 1)).unwrap();
+    }
+
+    #[test]
+    fn test_commit_votes() {
+        let mut data = SlashingProtectionData::new(1);
+
+        data.check_and_update(&commit_vote(1, 3, 10)).unwrap();
+        data.check_and_update(&commit_vote(1, 3, 10)).unwrap();
+        data.check_and_update(&commit_vote(1, 3, 11)).unwrap_err();
+        data.check_and_update(&commit_vote(1, 2, 9)).unwrap_err();
+        data.check_and_update(&commit_vote(1, 5, 20)).unwrap();
+    }
+
+    #[test]
+    fn test_epochs() {
+        let mut data = SlashingProtectionData::new(1);
+
+        data.check_and_update(&vote(1, 10, 0)).unwrap();
+        // A new epoch starts from scratch
+        data.check_and_update(&vote(2, 1, 0)).unwrap();
+        assert_eq!(
+            data,
+            SlashingProtectionData {
+                epoch: 2,
+                last_voted_round: 1,
+                last_proposal: None,
+                last_vote: data.last_vote,
+                last_commit_vote: None,
+            }
+        );
+        // Nothing may be signed for an older epoch
+        data.check_and_update(&vote(1, 11, 0)).unwrap_err();
+        data.check_and_update(&timeout(1, 11, 0)).unwrap_err();
+    }
+}
@@ -3,9 +3,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    persistent_safety_storage::PersistentSafetyStorage, serializer::SerializerService, SafetyRules,
-    TSafetyRules,
+    persistent_safety_storage::PersistentSafetyStorage,
+    remote_signer::{self, RemoteSignerEndpoint, RemoteSignerService},
+    serializer::SerializerService,
+    SafetyRules, TSafetyRules,
 };
+use aptos_config::{config::RemoteServiceNoiseConfig, utils};
 use aptos_consensus_types::{
     block::Block,
     common::{Payload, Round},
@@ -17,8 +20,12 @@ use aptos_consensus_types::{
     vote_data::VoteData,
     vote_proposal::VoteProposal,
 };
-use aptos_crypto::hash::{CryptoHash, TransactionAccumulatorHasher};
-use aptos_secure_storage::{InMemoryStorage, Storage};
+use aptos_crypto::{
+    hash::{CryptoHash, TransactionAccumulatorHasher},
+    x25519, Uniform,
+};
+use aptos_global_constants::CONSENSUS_KEY;
+use aptos_secure_storage::{InMemoryStorage, KVStorage, Storage};
 use aptos_types::{
     aggregate_signature::{AggregateSignature, PartialSignatures},
     block_info::BlockInfo,
@@ -32,6 +39,10 @@ use aptos_types::{
     validator_verifier::generate_validator_verifier,
     waypoint::Waypoint,
 };
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    thread,
+};
 
 pub type Proof = AccumulatorExtensionProof<TransactionAccumulatorHasher>;
 
@@ -242,6 +253,51 @@ pub fn test_storage(signer: &ValidatorSigner) -> PersistentSafetyStorage {
     )
 }
 
+/// Returns a storage for a validator whose consensus key is held by a remote signer.
+pub fn test_storage_without_consensus_key(signer: &ValidatorSigner) -> PersistentSafetyStorage {
+    let waypoint = validator_signers_to_waypoint(&[signer]);
+    let storage = Storage::from(InMemoryStorage::new());
+    PersistentSafetyStorage::initialize_without_consensus_key(
+        storage,
+        signer.author(),
+        waypoint,
+        true,
+    )
+}
+
+/// Starts a remote signer holding the consensus key of the signer on a separate thread and
+/// returns how to reach it. Both ends of the channel are authenticated with fresh keys.
+pub fn test_remote_signer(
+    signer: &ValidatorSigner,
+    network_timeout_ms: u64,
+) -> RemoteSignerEndpoint {
+    let mut storage = Storage::from(InMemoryStorage::new());
+    storage
+        .set(CONSENSUS_KEY, signer.private_key().clone())
+        .unwrap();
+    let service = RemoteSignerService::new(signer.author(), storage);
+
+    let mut rng = rand::rngs::OsRng;
+    let client_key = x25519::PrivateKey::generate(&mut rng);
+    let server_key = x25519::PrivateKey::generate(&mut rng);
+    let client_public_key = client_key.public_key();
+    let server_public_key = server_key.public_key();
+    let client_noise_config = RemoteServiceNoiseConfig::new(client_key, server_public_key);
+    let server_noise_config = RemoteServiceNoiseConfig::new(server_key, client_public_key);
+
+    let listen_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), utils::get_available_port());
+    thread::spawn(move || {
+        remote_signer::execute(
+            service,
+            listen_addr,
+            network_timeout_ms,
+            Some(server_noise_config),
+        )
+    });
+
+    RemoteSignerEndpoint::new(listen_addr, network_timeout_ms, Some(client_noise_config))
+}
+
 /// Returns a safety rules instance for testing purposes.
 pub fn test_safety_rules() -> SafetyRules {
     let signer = ValidatorSigner::from_int(0);
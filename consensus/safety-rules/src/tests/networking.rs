@@ -2,8 +2,10 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{test_utils, SafetyRulesManager};
+use crate::{test_utils, Error, SafetyRulesManager};
+use aptos_config::utils;
 use aptos_types::validator_signer::ValidatorSigner;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 
 #[test]
 fn test_reconnect() {
@@ -11,10 +13,24 @@ fn test_reconnect() {
     let storage = test_utils::test_storage(&signer);
     // test value for network timeout, in milliseconds.
     let network_timeout = 5_000;
-    let safety_rules_manager = SafetyRulesManager::new_thread(storage, network_timeout);
+    let safety_rules_manager =
+        SafetyRulesManager::new_thread(storage, network_timeout, 2 * network_timeout);
 
     // Verify that after a client has disconnected a new client will connect and resume operations
     let state0 = safety_rules_manager.client().consensus_state().unwrap();
     let state1 = safety_rules_manager.client().consensus_state().unwrap();
     assert_eq!(state0, state1);
 }
+
+#[test]
+fn test_request_timeout() {
+    // Nothing listens on this address
+    let server_port = utils::get_available_port();
+    let server_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), server_port);
+    // test values for network and request timeouts, in milliseconds.
+    let safety_rules_manager = SafetyRulesManager::new_process(server_addr, 200, 1_000, None);
+
+    // Verify that requests give up once the deadline has passed
+    let error = safety_rules_manager.client().consensus_state().unwrap_err();
+    assert!(matches!(error, Error::RemoteRequestTimeout(..)));
+}
@@ -0,0 +1,67 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    local_client::LocalClient, test_utils, tests::suite, Error, SafetyRules, TSafetyRules,
+};
+use aptos_consensus_types::common::Payload;
+use aptos_infallible::RwLock;
+use aptos_types::validator_signer::ValidatorSigner;
+use std::sync::Arc;
+
+/// Test value for network_timeout, in milliseconds.
+const NETWORK_TIMEOUT_MS: u64 = 5_000;
+
+#[test]
+fn test() {
+    suite::run_test_suite(&safety_rules());
+}
+
+fn safety_rules() -> suite::Callback {
+    Box::new(move || {
+        let signer = ValidatorSigner::from_int(0);
+        let storage = test_utils::test_storage_without_consensus_key(&signer);
+        let remote_signer = test_utils::test_remote_signer(&signer, NETWORK_TIMEOUT_MS);
+        let safety_rules = SafetyRules::new_with_remote_signer(storage, remote_signer);
+        let safety_rules: Box<dyn TSafetyRules + Send + Sync> =
+            Box::new(LocalClient::new(Arc::new(RwLock::new(safety_rules))));
+        (safety_rules, signer)
+    })
+}
+
+#[test]
+fn test_slashing_protection_outlives_safety_rules() {
+    let signer = ValidatorSigner::from_int(0);
+    let remote_signer = test_utils::test_remote_signer(&signer, NETWORK_TIMEOUT_MS);
+    let (proof, genesis_qc) = test_utils::make_genesis(&signer);
+    let a1 = test_utils::make_proposal_with_qc(1, genesis_qc, &signer);
+
+    let mut safety_rules = SafetyRules::new_with_remote_signer(
+        test_utils::test_storage_without_consensus_key(&signer),
+        remote_signer.clone(),
+    );
+    safety_rules.initialize(&proof).unwrap();
+    safety_rules
+        .construct_and_sign_vote_two_chain(&a1, None)
+        .unwrap();
+
+    // SafetyRules lost its safety data, but the remote signer still refuses to vote again
+    let mut safety_rules = SafetyRules::new_with_remote_signer(
+        test_utils::test_storage_without_consensus_key(&signer),
+        remote_signer,
+    );
+    safety_rules.initialize(&proof).unwrap();
+    let a1_prime = test_utils::make_proposal_with_qc_and_proof(
+        Payload::empty(true),
+        1,
+        test_utils::empty_proof(),
+        a1.block().quorum_cert().clone(),
+        &signer,
+    );
+    assert!(matches!(
+        safety_rules
+            .construct_and_sign_vote_two_chain(&a1_prime, None)
+            .unwrap_err(),
+        Error::SlashingProtectionViolation(_)
+    ));
+}
@@ -16,7 +16,8 @@ fn safety_rules() -> suite::Callback {
         let storage = test_utils::test_storage(&signer);
         // Test value for network_timeout, in milliseconds.
         let network_timeout = 5_000;
-        let safety_rules_manager = SafetyRulesManager::new_thread(storage, network_timeout);
+        let safety_rules_manager =
+            SafetyRulesManager::new_thread(storage, network_timeout, 2 * network_timeout);
         let safety_rules = safety_rules_manager.client();
         (safety_rules, signer)
     })
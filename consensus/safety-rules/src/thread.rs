@@ -9,10 +9,11 @@
 //! in testing correctness of the communication layer between Consensus and SafetyRules.
 
 use crate::{
-    persistent_safety_storage::PersistentSafetyStorage,
     remote_service::{self, RemoteService},
+    SafetyRules,
 };
-use aptos_config::utils;
+use aptos_config::{config::RemoteServiceNoiseConfig, utils};
+use aptos_crypto::{x25519, Uniform};
 use std::{
     net::{IpAddr, Ipv4Addr, SocketAddr},
     thread::{self, JoinHandle},
@@ -24,20 +25,40 @@ pub struct ThreadService {
     _child: JoinHandle<()>,
     server_addr: SocketAddr,
     network_timeout: u64,
+    request_timeout: u64,
+    noise_config: RemoteServiceNoiseConfig,
 }
 
 impl ThreadService {
-    pub fn new(storage: PersistentSafetyStorage, timeout: u64) -> Self {
+    pub fn new(safety_rules: SafetyRules, timeout: u64, request_timeout: u64) -> Self {
         let listen_port = utils::get_available_port();
         let listen_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), listen_port);
         let server_addr = listen_addr;
 
-        let child = thread::spawn(move || remote_service::execute(storage, listen_addr, timeout));
+        // Both ends live in this process, so fresh keys are pinned on every start
+        let mut rng = rand::rngs::OsRng;
+        let client_key = x25519::PrivateKey::generate(&mut rng);
+        let server_key = x25519::PrivateKey::generate(&mut rng);
+        let client_public_key = client_key.public_key();
+        let server_public_key = server_key.public_key();
+        let client_noise_config = RemoteServiceNoiseConfig::new(client_key, server_public_key);
+        let server_noise_config = RemoteServiceNoiseConfig::new(server_key, client_public_key);
+
+        let child = thread::spawn(move || {
+            remote_service::execute(
+                safety_rules,
+                listen_addr,
+                timeout,
+                Some(server_noise_config),
+            )
+        });
 
         Self {
             _child: child,
             server_addr,
             network_timeout: timeout,
+            request_timeout,
+            noise_config: client_noise_config,
         }
     }
 }
@@ -50,4 +71,12 @@ impl RemoteService for ThreadService {
     fn network_timeout_ms(&self) -> u64 {
         self.network_timeout
     }
+
+    fn request_timeout_ms(&self) -> u64 {
+        self.request_timeout
+    }
+
+    fn noise_config(&self) -> Option<&RemoteServiceNoiseConfig> {
+        Some(&self.noise_config)
+    }
 }
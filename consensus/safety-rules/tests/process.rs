@@ -0,0 +1,232 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Runs SafetyRules in a child process, the way it is deployed in production, and drives it from
+//! a consensus client over the Noise authenticated channel.
+
+use aptos_config::{
+    config::{
+        OnDiskStorageConfig, PersistableConfig, RemoteService, RemoteServiceNoiseConfig,
+        SafetyRulesConfig, SafetyRulesService, SafetyRulesTestConfig, SecureBackend,
+    },
+    utils,
+};
+use aptos_consensus_types::common::Payload;
+use aptos_crypto::{x25519, Uniform};
+use aptos_safety_rules::{test_utils, Error, SafetyRulesManager, TSafetyRules};
+use aptos_temppath::TempPath;
+use aptos_types::validator_signer::ValidatorSigner;
+use rand::{rngs::StdRng, SeedableRng};
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    path::PathBuf,
+    process::{Child, Command},
+};
+
+/// Read, Write, Connect timeout in milliseconds.
+const NETWORK_TIMEOUT_MS: u64 = 5_000;
+/// Deadline for a single request in milliseconds, long enough to cover a process restart.
+const REQUEST_TIMEOUT_MS: u64 = 30_000;
+
+/// Kills the SafetyRules process once the test no longer needs it.
+struct SafetyRulesProcess(Child);
+
+impl SafetyRulesProcess {
+    fn start(config_path: &PathBuf) -> Self {
+        let child = Command::new(env!("CARGO_BIN_EXE_safety-rules"))
+            .arg(config_path)
+            .spawn()
+            .expect("Unable to start the SafetyRules process");
+        Self(child)
+    }
+}
+
+impl Drop for SafetyRulesProcess {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+struct TestEnvironment {
+    signer: ValidatorSigner,
+    server_addr: SocketAddr,
+    client_noise_config: RemoteServiceNoiseConfig,
+    server_noise_config: RemoteServiceNoiseConfig,
+    _temp_dir: TempPath,
+    temp_path: PathBuf,
+}
+
+impl TestEnvironment {
+    fn new(seed: u8) -> Self {
+        let mut rng = StdRng::from_seed([seed; 32]);
+        let client_key = x25519::PrivateKey::generate(&mut rng);
+        let server_key = x25519::PrivateKey::generate(&mut rng);
+        let client_public_key = client_key.public_key();
+        let server_public_key = server_key.public_key();
+
+        let temp_dir = TempPath::new();
+        temp_dir.create_as_dir().unwrap();
+        let temp_path = temp_dir.path().to_path_buf();
+
+        let server_port = utils::get_available_port();
+        Self {
+            signer: ValidatorSigner::from_int(0),
+            server_addr: SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), server_port),
+            client_noise_config: RemoteServiceNoiseConfig::new(client_key, server_public_key),
+            server_noise_config: RemoteServiceNoiseConfig::new(server_key, client_public_key),
+            _temp_dir: temp_dir,
+            temp_path,
+        }
+    }
+
+    /// Writes a process config backed by the given storage file and returns its path. If
+    /// `initialize` is set, the process (re)initializes the storage with the test signer.
+    fn write_config(&self, storage_name: &str, initialize: bool) -> PathBuf {
+        let test_config = initialize.then(|| {
+            let mut test_config = SafetyRulesTestConfig::new(self.signer.author());
+            test_config.consensus_key(self.signer.private_key().clone());
+            test_config.waypoint = Some(test_utils::validator_signers_to_waypoint(&[&self.signer]));
+            test_config
+        });
+
+        let mut storage_config = OnDiskStorageConfig::default();
+        storage_config.path = self.temp_path.join(storage_name);
+
+        let config = SafetyRulesConfig {
+            backend: SecureBackend::OnDiskStorage(storage_config),
+            service: SafetyRulesService::Process(RemoteService {
+                server_address: format!("/ip4/127.0.0.1/tcp/{}", self.server_addr.port())
+                    .parse()
+                    .unwrap(),
+                noise: Some(self.server_noise_config.clone()),
+            }),
+            test: test_config,
+            network_timeout_ms: NETWORK_TIMEOUT_MS,
+            request_timeout_ms: REQUEST_TIMEOUT_MS,
+            ..Default::default()
+        };
+
+        let config_path = self
+            .temp_path
+            .join(format!("{}_{}.yaml", storage_name, initialize));
+        config.save_config(&config_path).unwrap();
+        config_path
+    }
+
+    fn client(
+        &self,
+        noise_config: RemoteServiceNoiseConfig,
+    ) -> Box<dyn TSafetyRules + Send + Sync> {
+        SafetyRulesManager::new_process(
+            self.server_addr,
+            NETWORK_TIMEOUT_MS,
+            REQUEST_TIMEOUT_MS,
+            Some(noise_config),
+        )
+        .client()
+    }
+}
+
+#[test]
+fn test_consensus_with_safety_rules_process() {
+    let env = TestEnvironment::new(0);
+    let process = SafetyRulesProcess::start(&env.write_config("safety_rules_storage", true));
+    let mut safety_rules = env.client(env.client_noise_config.clone());
+
+    let (proof, genesis_qc) = test_utils::make_genesis(&env.signer);
+    let round = genesis_qc.certified_block().round();
+    let p0 = test_utils::make_proposal_with_qc(round + 1, genesis_qc, &env.signer);
+    let p1 = test_utils::make_proposal_with_parent(
+        Payload::empty(false),
+        round + 2,
+        &p0,
+        None,
+        &env.signer,
+    );
+    let p2 = test_utils::make_proposal_with_parent(
+        Payload::empty(false),
+        round + 3,
+        &p1,
+        None,
+        &env.signer,
+    );
+
+    safety_rules.initialize(&proof).unwrap();
+    safety_rules.sign_proposal(p0.block().block_data()).unwrap();
+    safety_rules
+        .construct_and_sign_vote_two_chain(&p0, None)
+        .unwrap();
+    safety_rules
+        .construct_and_sign_vote_two_chain(&p1, None)
+        .unwrap();
+    let state = safety_rules.consensus_state().unwrap();
+    assert_eq!(state.last_voted_round(), round + 2);
+
+    // Restart the process on the same storage, the client reconnects, verifies that the safety
+    // data survived the restart and resumes voting.
+    drop(process);
+    let _process = SafetyRulesProcess::start(&env.write_config("safety_rules_storage", false));
+    safety_rules
+        .construct_and_sign_vote_two_chain(&p2, None)
+        .unwrap();
+    let state = safety_rules.consensus_state().unwrap();
+    assert_eq!(state.last_voted_round(), round + 3);
+}
+
+#[test]
+fn test_safety_rules_process_with_lost_state() {
+    let env = TestEnvironment::new(1);
+    let process = SafetyRulesProcess::start(&env.write_config("safety_rules_storage", true));
+    let mut safety_rules = env.client(env.client_noise_config.clone());
+
+    let (proof, genesis_qc) = test_utils::make_genesis(&env.signer);
+    let round = genesis_qc.certified_block().round();
+    let p0 = test_utils::make_proposal_with_qc(round + 1, genesis_qc, &env.signer);
+    let p1 = test_utils::make_proposal_with_parent(
+        Payload::empty(false),
+        round + 2,
+        &p0,
+        None,
+        &env.signer,
+    );
+
+    safety_rules.initialize(&proof).unwrap();
+    safety_rules
+        .construct_and_sign_vote_two_chain(&p0, None)
+        .unwrap();
+
+    // Restart the process on fresh storage, the client must refuse to use it
+    drop(process);
+    let _process = SafetyRulesProcess::start(&env.write_config("fresh_storage", true));
+    let error = safety_rules
+        .construct_and_sign_vote_two_chain(&p1, None)
+        .unwrap_err();
+    assert!(matches!(error, Error::RemoteStateRegressed(..)));
+}
+
+#[test]
+fn test_safety_rules_process_rejects_unpinned_client() {
+    let env = TestEnvironment::new(2);
+    let _process = SafetyRulesProcess::start(&env.write_config("safety_rules_storage", true));
+
+    // A client with a key the process did not pin can never complete a request
+    let mut rng = StdRng::from_seed([3; 32]);
+    let rogue_noise_config = RemoteServiceNoiseConfig::new(
+        x25519::PrivateKey::generate(&mut rng),
+        env.client_noise_config.remote_public_key,
+    );
+    let mut safety_rules = SafetyRulesManager::new_process(
+        env.server_addr,
+        NETWORK_TIMEOUT_MS,
+        NETWORK_TIMEOUT_MS,
+        Some(rogue_noise_config),
+    )
+    .client();
+    let error = safety_rules.consensus_state().unwrap_err();
+    assert!(matches!(error, Error::RemoteRequestTimeout(..)));
+
+    // While the pinned client is served
+    let mut safety_rules = env.client(env.client_noise_config.clone());
+    safety_rules.consensus_state().unwrap();
+}
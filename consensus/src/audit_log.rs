@@ -0,0 +1,391 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! An optional append-only log of what a validator saw and did in each round: proposals
+//! received, votes and timeouts cast, certificates formed and commit decisions. Unlike the
+//! counters and logs, records are keyed by epoch and round, so the logs of several validators can
+//! be merged into per-round timelines after the fact.
+//!
+//! A log is a sequence of frames, each one a BCS encoded value prefixed with its length as a
+//! little endian u32. The first frame of a log is an `AuditLogHeader`, all others are
+//! `AuditRecord`s. A torn frame at the end of a log (e.g., after a crash) is ignored by readers.
+//!
+//! Records are handed over to a dedicated writer thread through a bounded channel, and dropped
+//! when the channel is full: consensus never waits on the audit log.
+
+use crate::counters;
+use anyhow::{ensure, Context, Result};
+use aptos_config::config::ConsensusAuditLogConfig;
+use aptos_consensus_types::common::{Author, Round};
+use aptos_crypto::HashValue;
+use aptos_infallible::duration_since_epoch;
+use aptos_logger::prelude::*;
+use aptos_types::transaction::Version;
+use once_cell::sync::OnceCell;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs::{File, OpenOptions},
+    io::{BufReader, BufWriter, ErrorKind, Read, Write},
+    path::Path,
+    sync::mpsc::{self, Receiver, SyncSender, TrySendError},
+    thread,
+};
+
+const FORMAT_VERSION: u8 = 1;
+/// Frames larger than this can only come from a corrupted log
+const MAX_FRAME_SIZE: usize = 1 << 20;
+
+static AUDIT_LOG: OnceCell<AuditLog> = OnceCell::new();
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct AuditLogHeader {
+    pub format_version: u8,
+    /// The validator writing the log
+    pub author: Author,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct AuditRecord {
+    pub epoch: u64,
+    pub round: Round,
+    /// Local time at which the event happened
+    pub timestamp_usecs: u64,
+    pub event: AuditEvent,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum AuditEvent {
+    /// The round started, after a timeout certificate or a quorum certificate for the previous
+    /// round
+    RoundStarted {
+        timeout_ms: u64,
+        after_timeout: bool,
+    },
+    ProposalReceived {
+        block_id: HashValue,
+        proposer: Author,
+        block_timestamp_usecs: u64,
+    },
+    VoteCast {
+        block_id: HashValue,
+    },
+    /// The round timed out locally and a timeout was broadcast, possibly for a NIL block
+    TimeoutCast {
+        hqc_round: Round,
+        nil_block: bool,
+    },
+    /// Votes aggregated locally into a quorum certificate
+    QcFormed {
+        block_id: HashValue,
+    },
+    /// Timeouts aggregated locally into a timeout certificate
+    TcFormed {
+        hqc_round: Round,
+    },
+    CommitDecision {
+        block_id: HashValue,
+        version: Version,
+        source: CommitSource,
+    },
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum CommitSource {
+    /// Commit votes aggregated locally
+    CommitVotes,
+    /// A commit decision broadcast by the proposer
+    CommitDecision,
+    /// Synced to a commit certificate from a peer
+    StateSync,
+}
+
+struct AuditLog {
+    sender: SyncSender<AuditRecord>,
+}
+
+/// Starts the audit log if it's enabled. Failing to open the log is not fatal, consensus runs
+/// without it.
+pub fn init(config: &ConsensusAuditLogConfig, author: Author) {
+    if !config.enabled || AUDIT_LOG.get().is_some() {
+        return;
+    }
+    let path = config.path();
+    let writer = match AuditLogWriter::open(&path, author) {
+        Ok(writer) => writer,
+        Err(error) => {
+            error!(
+                error = ?error,
+                "Unable to open the consensus audit log at {}",
+                path.display()
+            );
+            return;
+        },
+    };
+    let (sender, receiver) = mpsc::sync_channel(config.max_pending_records);
+    thread::Builder::new()
+        .name("consensus-audit-log".into())
+        .spawn(move || writer.run(receiver))
+        .expect("Unable to spawn the consensus audit log writer");
+    let _ = AUDIT_LOG.set(AuditLog { sender });
+    info!("Consensus audit log written to {}", path.display());
+}
+
+/// Records an event of the given round, if the audit log is enabled
+pub fn record(epoch: u64, round: Round, event: AuditEvent) {
+    if let Some(audit_log) = AUDIT_LOG.get() {
+        let record = AuditRecord {
+            epoch,
+            round,
+            timestamp_usecs: duration_since_epoch().as_micros() as u64,
+            event,
+        };
+        match audit_log.sender.try_send(record) {
+            Ok(()) => (),
+            Err(TrySendError::Full(_)) => counters::AUDIT_LOG_DROPPED_RECORDS.inc(),
+            Err(TrySendError::Disconnected(_)) => (),
+        }
+    }
+}
+
+pub struct AuditLogWriter {
+    file: BufWriter<File>,
+}
+
+impl AuditLogWriter {
+    /// Opens the log for appending, writing the header if the log is new
+    pub fn open(path: &Path, author: Author) -> Result<Self> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(path)?;
+        truncate_torn_frame(&mut file)?;
+        let is_new = file.metadata()?.len() == 0;
+        let mut writer = Self {
+            file: BufWriter::new(file),
+        };
+        if is_new {
+            writer.write(&AuditLogHeader {
+                format_version: FORMAT_VERSION,
+                author,
+            })?;
+            writer.flush()?;
+        }
+        Ok(writer)
+    }
+
+    pub fn write<T: Serialize>(&mut self, value: &T) -> Result<()> {
+        let bytes = bcs::to_bytes(value)?;
+        self.file.write_all(&(bytes.len() as u32).to_le_bytes())?;
+        self.file.write_all(&bytes)?;
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        Ok(self.file.flush()?)
+    }
+
+    /// Writes records until consensus goes away, flushing whenever it catches up
+    fn run(mut self, receiver: Receiver<AuditRecord>) {
+        while let Ok(record) = receiver.recv() {
+            let result = std::iter::once(record)
+                .chain(receiver.try_iter())
+                .try_for_each(|record| self.write(&record))
+                .and_then(|()| self.flush());
+            if let Err(error) = result {
+                error!(error = ?error, "Failed to write the consensus audit log");
+            }
+        }
+    }
+}
+
+/// Cuts a torn frame left at the end of the log by a crash, so appended records stay readable
+fn truncate_torn_frame(file: &mut File) -> Result<()> {
+    let file_len = file.metadata()?.len();
+    let mut reader = BufReader::new(&*file);
+    let mut end = 0;
+    let mut frame_len = [0u8; 4];
+    while read_exact_or_eof(&mut reader, &mut frame_len)? {
+        let frame_len = u32::from_le_bytes(frame_len);
+        if end + 4 + frame_len as u64 > file_len {
+            break;
+        }
+        reader.seek_relative(frame_len as i64)?;
+        end += 4 + frame_len as u64;
+    }
+    if end < file_len {
+        warn!("Truncating a torn frame at the end of the consensus audit log");
+        file.set_len(end)?;
+    }
+    Ok(())
+}
+
+fn read_frame<T: DeserializeOwned>(reader: &mut impl Read) -> Result<Option<T>> {
+    let mut len = [0u8; 4];
+    if !read_exact_or_eof(reader, &mut len)? {
+        return Ok(None);
+    }
+    let len = u32::from_le_bytes(len) as usize;
+    ensure!(len <= MAX_FRAME_SIZE, "Frame of {} bytes is too large", len);
+    let mut bytes = vec![0u8; len];
+    if !read_exact_or_eof(reader, &mut bytes)? {
+        return Ok(None);
+    }
+    Ok(Some(bcs::from_bytes(&bytes)?))
+}
+
+/// Returns false at the end of the log, including when it ends with a torn frame
+fn read_exact_or_eof(reader: &mut impl Read, buf: &mut [u8]) -> Result<bool> {
+    match reader.read_exact(buf) {
+        Ok(()) => Ok(true),
+        Err(error) if error.kind() == ErrorKind::UnexpectedEof => Ok(false),
+        Err(error) => Err(error.into()),
+    }
+}
+
+/// Reads a whole log
+pub fn read_log(path: &Path) -> Result<(AuditLogHeader, Vec<AuditRecord>)> {
+    let mut reader = BufReader::new(
+        File::open(path).with_context(|| format!("Unable to open {}", path.display()))?,
+    );
+    let header: AuditLogHeader = read_frame(&mut reader)?
+        .with_context(|| format!("{} is not an audit log", path.display()))?;
+    ensure!(
+        header.format_version == FORMAT_VERSION,
+        "Unsupported audit log format version {}",
+        header.format_version
+    );
+    let mut records = vec![];
+    while let Some(record) = read_frame(&mut reader)? {
+        records.push(record);
+    }
+    Ok((header, records))
+}
+
+/// Merges the logs of several validators into the events of each round, in time order
+pub fn timelines(
+    logs: impl IntoIterator<Item = (AuditLogHeader, Vec<AuditRecord>)>,
+) -> BTreeMap<(u64, Round), Vec<(Author, AuditRecord)>> {
+    let mut timelines: BTreeMap<_, Vec<_>> = BTreeMap::new();
+    for (header, records) in logs {
+        for record in records {
+            timelines
+                .entry((record.epoch, record.round))
+                .or_default()
+                .push((header.author, record));
+        }
+    }
+    for events in timelines.values_mut() {
+        events.sort_by_key(|(_, record)| record.timestamp_usecs);
+    }
+    timelines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_temppath::TempPath;
+
+    fn record(epoch: u64, round: Round, timestamp_usecs: u64, event: AuditEvent) -> AuditRecord {
+        AuditRecord {
+            epoch,
+            round,
+            timestamp_usecs,
+            event,
+        }
+    }
+
+    #[test]
+    fn test_read_write() {
+        let path = TempPath::new();
+        let author = Author::random();
+        let records = vec![
+            record(
+                1,
+                1,
+                10,
+                AuditEvent::VoteCast {
+                    block_id: HashValue::random(),
+                },
+            ),
+            record(
+                1,
+                2,
+                20,
+                AuditEvent::TimeoutCast {
+                    hqc_round: 1,
+                    nil_block: true,
+                },
+            ),
+        ];
+
+        let mut writer = AuditLogWriter::open(path.path(), author).unwrap();
+        writer.write(&records[0]).unwrap();
+        writer.flush().unwrap();
+        // Reopening appends to the log without a second header
+        let mut writer = AuditLogWriter::open(path.path(), Author::random()).unwrap();
+        writer.write(&records[1]).unwrap();
+        writer.flush().unwrap();
+
+        let (header, read_records) = read_log(path.path()).unwrap();
+        assert_eq!(header.author, author);
+        assert_eq!(read_records, records);
+
+        // A torn frame at the end is ignored, and cut before appending more records
+        let mut file = OpenOptions::new().append(true).open(path.path()).unwrap();
+        file.write_all(&100u32.to_le_bytes()).unwrap();
+        file.write_all(&[1, 2, 3]).unwrap();
+        assert_eq!(read_log(path.path()).unwrap().1, records);
+        let mut writer = AuditLogWriter::open(path.path(), author).unwrap();
+        writer.write(&records[0]).unwrap();
+        writer.flush().unwrap();
+        assert_eq!(
+            read_log(path.path()).unwrap().1,
+            vec![records[0].clone(), records[1].clone(), records[0].clone()]
+        );
+    }
+
+    #[test]
+    fn test_timelines() {
+        let authors = [Author::random(), Author::random()];
+        let block_id = HashValue::random();
+        let proposal = AuditEvent::ProposalReceived {
+            block_id,
+            proposer: authors[0],
+            block_timestamp_usecs: 5,
+        };
+        let logs = vec![
+            (
+                AuditLogHeader {
+                    format_version: FORMAT_VERSION,
+                    author: authors[0],
+                },
+                vec![
+                    record(1, 3, 30, AuditEvent::QcFormed { block_id }),
+                    record(1, 4, 40, AuditEvent::VoteCast { block_id }),
+                ],
+            ),
+            (
+                AuditLogHeader {
+                    format_version: FORMAT_VERSION,
+                    author: authors[1],
+                },
+                vec![record(1, 3, 10, proposal.clone())],
+            ),
+        ];
+
+        let timelines = timelines(logs);
+        assert_eq!(timelines.keys().collect::<Vec<_>>(), vec![&(1, 3), &(1, 4)]);
+        assert_eq!(
+            timelines[&(1, 3)],
+            vec![
+                (authors[1], record(1, 3, 10, proposal)),
+                (
+                    authors[0],
+                    record(1, 3, 30, AuditEvent::QcFormed { block_id })
+                ),
+            ]
+        );
+    }
+}
@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    audit_log::{self, AuditEvent, CommitSource},
     block_storage::{BlockReader, BlockStore},
     epoch_manager::LivenessStorageData,
     logging::{LogEvent, LogSchema},
@@ -324,6 +325,16 @@ impl BlockStore {
         state_computer
             .sync_to(highest_commit_cert.ledger_info().clone())
             .await?;
+        let commit_info = highest_commit_cert.commit_info();
+        audit_log::record(
+            commit_info.epoch(),
+            commit_info.round(),
+            AuditEvent::CommitDecision {
+                block_id: commit_info.id(),
+                version: commit_info.version(),
+                source: CommitSource::StateSync,
+            },
+        );
 
         // we do not need to update block_tree.highest_commit_decision_ledger_info here
         // because the block_tree is going to rebuild itself.
@@ -81,6 +81,15 @@ pub static COMMITTED_BLOCKS_COUNT: Lazy<IntCounter> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Count of the audit log records dropped because the writer fell behind.
+pub static AUDIT_LOG_DROPPED_RECORDS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_consensus_audit_log_dropped_records",
+        "Count of the audit log records dropped because the writer fell behind."
+    )
+    .unwrap()
+});
+
 /// Count of the committed transactions since last restart.
 pub static COMMITTED_TXNS_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
@@ -765,6 +774,17 @@ pub static BUFFER_MANAGER_RETRY_COUNT: Lazy<IntCounter> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counters(hit,miss,discarded) of speculatively executed blocks. A hit is an ordered block whose
+/// speculative result is reused, a miss one that is executed after ordering.
+pub static SPECULATIVE_EXECUTION_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_consensus_speculative_execution_count",
+        "Counters(hit,miss,discarded) of speculatively executed blocks",
+        &["result"]
+    )
+    .unwrap()
+});
+
 const PROPSER_ELECTION_DURATION_BUCKETS: [f64; 17] = [
     0.001, 0.002, 0.003, 0.004, 0.006, 0.008, 0.01, 0.012, 0.014, 0.0175, 0.02, 0.025, 0.05, 0.25,
     0.5, 1.0, 2.0,
@@ -15,6 +15,7 @@ use crate::{
         decoupled_execution_utils::prepare_phases_and_buffer_manager,
         ordering_state_computer::{DagStateSyncComputer, OrderingStateComputer},
         signing_phase::CommitSignerProvider,
+        speculative_execution::SpeculativeExecution,
     },
     liveness::{
         cached_proposer_election::CachedProposerElection,
@@ -542,9 +543,11 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
         &mut self,
         commit_signer_provider: Arc<dyn CommitSignerProvider>,
         verifier: ValidatorVerifier,
+        enable_speculative_execution: bool,
     ) -> (
         UnboundedSender<OrderedBlocks>,
         UnboundedSender<ResetRequest>,
+        Option<Arc<SpeculativeExecution>>,
     ) {
         let network_sender = NetworkSender::new(
             self.author,
@@ -572,6 +575,7 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
             signing_phase,
             persisting_phase,
             buffer_manager,
+            speculative_execution,
         ) = prepare_phases_and_buffer_manager(
             self.author,
             self.commit_state_computer.clone(),
@@ -582,6 +586,7 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
             block_rx,
             reset_rx,
             verifier,
+            enable_speculative_execution,
         );
 
         tokio::spawn(execution_schedule_phase.start());
@@ -590,7 +595,7 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
         tokio::spawn(persisting_phase.start());
         tokio::spawn(buffer_manager.start());
 
-        (block_tx, reset_tx)
+        (block_tx, reset_tx, speculative_execution)
     }
 
     async fn shutdown_current_processor(&mut self) {
@@ -754,12 +759,16 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
         commit_signer_provider: Arc<dyn CommitSignerProvider>,
     ) -> Arc<dyn StateComputer> {
         if onchain_consensus_config.decoupled_execution() {
-            let (block_tx, reset_tx) = self
-                .spawn_decoupled_execution(commit_signer_provider, epoch_state.verifier.clone());
+            let (block_tx, reset_tx, speculative_execution) = self.spawn_decoupled_execution(
+                commit_signer_provider,
+                epoch_state.verifier.clone(),
+                self.config.speculative_execution,
+            );
             Arc::new(OrderingStateComputer::new(
                 block_tx,
                 self.commit_state_computer.clone(),
                 reset_tx,
+                speculative_execution,
             ))
         } else {
             self.commit_state_computer.clone()
@@ -1045,8 +1054,8 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
             onchain_consensus_config.decoupled_execution(),
             "decoupled execution must be enabled"
         );
-        let (block_tx, reset_tx) =
-            self.spawn_decoupled_execution(commit_signer, epoch_state.verifier.clone());
+        let (block_tx, reset_tx, _) =
+            self.spawn_decoupled_execution(commit_signer, epoch_state.verifier.clone(), false);
         let state_computer = Arc::new(DagStateSyncComputer::new(
             self.commit_state_computer.clone(),
             reset_tx,
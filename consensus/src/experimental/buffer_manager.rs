@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    audit_log::{self, AuditEvent, CommitSource},
     block_storage::tracing::{observe_block, BlockStage},
     counters,
     experimental::{
@@ -28,7 +29,7 @@ use aptos_logger::prelude::*;
 use aptos_reliable_broadcast::ReliableBroadcast;
 use aptos_time_service::TimeService;
 use aptos_types::{
-    account_address::AccountAddress, epoch_change::EpochChangeProof,
+    account_address::AccountAddress, block_info::BlockInfo, epoch_change::EpochChangeProof,
     ledger_info::LedgerInfoWithSignatures, validator_verifier::ValidatorVerifier,
 };
 use futures::{
@@ -457,6 +458,12 @@ impl BufferManager {
             self.end_epoch_timestamp.get().cloned(),
         );
         let aggregated = new_item.is_aggregated();
+        if aggregated {
+            record_commit(
+                &new_item.get_blocks().last().unwrap().block_info(),
+                CommitSource::CommitVotes,
+            );
+        }
         self.buffer.set(&current_cursor, new_item);
         if aggregated {
             self.advance_head(block_id).await;
@@ -565,6 +572,7 @@ impl BufferManager {
                     };
                     self.buffer.set(&current_cursor, new_item);
                     if self.buffer.get(&current_cursor).is_aggregated() {
+                        record_commit(&commit_info, CommitSource::CommitVotes);
                         return Some(target_block_id);
                     }
                 }
@@ -586,6 +594,10 @@ impl BufferManager {
                     let aggregated = new_item.is_aggregated();
                     self.buffer.set(&cursor, new_item);
                     if aggregated {
+                        record_commit(
+                            commit_proof.ledger_info().commit_info(),
+                            CommitSource::CommitDecision,
+                        );
                         let response =
                             ConsensusMsg::CommitMessage(Box::new(CommitMessage::Ack(())));
                         if let Ok(bytes) = protocol.to_bytes(&response) {
@@ -744,3 +756,15 @@ impl BufferManager {
         info!("Buffer manager stops.");
     }
 }
+
+fn record_commit(commit_info: &BlockInfo, source: CommitSource) {
+    audit_log::record(
+        commit_info.epoch(),
+        commit_info.round(),
+        AuditEvent::CommitDecision {
+            block_id: commit_info.id(),
+            version: commit_info.version(),
+            source,
+        },
+    );
+}
@@ -10,6 +10,7 @@ use crate::{
         persisting_phase::{PersistingPhase, PersistingRequest},
         pipeline_phase::{CountedRequest, PipelinePhase},
         signing_phase::{CommitSignerProvider, SigningPhase, SigningRequest, SigningResponse},
+        speculative_execution::SpeculativeExecution,
     },
     network::{IncomingCommitRequest, NetworkSender},
     state_replication::StateComputer,
@@ -31,21 +32,31 @@ pub fn prepare_phases_and_buffer_manager(
     block_rx: UnboundedReceiver<OrderedBlocks>,
     sync_rx: UnboundedReceiver<ResetRequest>,
     verifier: ValidatorVerifier,
+    enable_speculative_execution: bool,
 ) -> (
     PipelinePhase<ExecutionSchedulePhase>,
     PipelinePhase<ExecutionWaitPhase>,
     PipelinePhase<SigningPhase>,
     PipelinePhase<PersistingPhase>,
     BufferManager,
+    Option<Arc<SpeculativeExecution>>,
 ) {
     let ongoing_tasks = Arc::new(AtomicU64::new(0));
 
+    let speculative_execution = enable_speculative_execution.then(|| {
+        Arc::new(SpeculativeExecution::new(
+            execution_proxy.clone(),
+            ongoing_tasks.clone(),
+        ))
+    });
+
     // Execution Phase
     let (execution_schedule_phase_request_tx, execution_schedule_phase_request_rx) =
         create_channel::<CountedRequest<ExecutionRequest>>();
     let (execution_schedule_phase_response_tx, execution_schedule_phase_response_rx) =
         create_channel::<ExecutionWaitRequest>();
-    let execution_schedule_phase_processor = ExecutionSchedulePhase::new(execution_proxy);
+    let execution_schedule_phase_processor =
+        ExecutionSchedulePhase::new(execution_proxy, speculative_execution.clone());
     let execution_schedule_phase = PipelinePhase::new(
         execution_schedule_phase_request_rx,
         Some(execution_schedule_phase_response_tx),
@@ -108,5 +119,6 @@ pub fn prepare_phases_and_buffer_manager(
             verifier,
             ongoing_tasks,
         ),
+        speculative_execution,
     )
 }
@@ -5,6 +5,7 @@ use crate::{
     experimental::{
         execution_wait_phase::ExecutionWaitRequest,
         pipeline_phase::{CountedRequest, StatelessPipeline},
+        speculative_execution::SpeculativeExecution,
     },
     state_replication::StateComputer,
 };
@@ -21,7 +22,8 @@ use std::{
 
 /// [ This class is used when consensus.decoupled = true ]
 /// ExecutionSchedulePhase is a singleton that receives ordered blocks from
-/// the buffer manager and send them to the ExecutionPipeline.
+/// the buffer manager and send them to the ExecutionPipeline. Blocks already
+/// executed speculatively are not sent again, their results are reused.
 
 pub struct ExecutionRequest {
     pub ordered_blocks: Vec<ExecutedBlock>,
@@ -44,11 +46,18 @@ impl Display for ExecutionRequest {
 
 pub struct ExecutionSchedulePhase {
     execution_proxy: Arc<dyn StateComputer>,
+    speculative_execution: Option<Arc<SpeculativeExecution>>,
 }
 
 impl ExecutionSchedulePhase {
-    pub fn new(execution_proxy: Arc<dyn StateComputer>) -> Self {
-        Self { execution_proxy }
+    pub fn new(
+        execution_proxy: Arc<dyn StateComputer>,
+        speculative_execution: Option<Arc<SpeculativeExecution>>,
+    ) -> Self {
+        Self {
+            execution_proxy,
+            speculative_execution,
+        }
     }
 }
 
@@ -78,12 +87,23 @@ impl StatelessPipeline for ExecutionSchedulePhase {
         // make sure they are scheduled in order.
         let mut futs = vec![];
         for b in &ordered_blocks {
-            let fut = self
-                .execution_proxy
-                .schedule_compute(b.block(), b.parent_id())
-                .await;
+            let speculative_result = match &self.speculative_execution {
+                Some(speculative_execution) => speculative_execution.take_result(b).await,
+                None => None,
+            };
+            let fut = match speculative_result {
+                Some(fut) => fut,
+                None => {
+                    self.execution_proxy
+                        .schedule_compute(b.block(), b.parent_id())
+                        .await
+                },
+            };
             futs.push(fut)
         }
+        if let Some(speculative_execution) = &self.speculative_execution {
+            speculative_execution.on_ordered(&ordered_blocks);
+        }
 
         // In the future being returned, wait for the compute results in order.
         // n.b. Must `spawn()` here to make sure lifetime_guard will be released even if
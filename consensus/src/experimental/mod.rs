@@ -35,6 +35,7 @@ pub mod ordering_state_computer;
 pub mod persisting_phase;
 pub mod pipeline_phase;
 pub mod signing_phase;
+pub mod speculative_execution;
 
 #[cfg(test)]
 mod tests;
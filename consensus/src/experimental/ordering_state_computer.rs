@@ -7,6 +7,7 @@ use crate::{
     experimental::{
         buffer_manager::{OrderedBlocks, ResetAck, ResetRequest},
         errors::Error,
+        speculative_execution::SpeculativeExecution,
     },
     payload_manager::PayloadManager,
     state_replication::{StateComputer, StateComputerCommitCallBackType},
@@ -37,6 +38,8 @@ pub struct OrderingStateComputer {
     executor_channel: UnboundedSender<OrderedBlocks>,
     state_computer_for_sync: Arc<dyn StateComputer>,
     reset_event_channel_tx: UnboundedSender<ResetRequest>,
+    // proposed blocks are handed over to it before they are ordered, if enabled
+    speculative_execution: Option<Arc<SpeculativeExecution>>,
 }
 
 impl OrderingStateComputer {
@@ -44,11 +47,13 @@ impl OrderingStateComputer {
         executor_channel: UnboundedSender<OrderedBlocks>,
         state_computer_for_sync: Arc<dyn StateComputer>,
         reset_event_channel_tx: UnboundedSender<ResetRequest>,
+        speculative_execution: Option<Arc<SpeculativeExecution>>,
     ) -> Self {
         Self {
             executor_channel,
             state_computer_for_sync,
             reset_event_channel_tx,
+            speculative_execution,
         }
     }
 }
@@ -58,10 +63,13 @@ impl StateComputer for OrderingStateComputer {
     async fn compute(
         &self,
         // The block to be executed.
-        _block: &Block,
+        block: &Block,
         // The parent block id.
-        _parent_block_id: HashValue,
+        parent_block_id: HashValue,
     ) -> ExecutorResult<StateComputeResult> {
+        if let Some(speculative_execution) = &self.speculative_execution {
+            speculative_execution.speculate(block.clone(), parent_block_id);
+        }
         // Return dummy block and bypass the execution phase.
         // This will break the e2e smoke test (for now because
         // no one is actually handling the next phase) if the
@@ -105,7 +113,11 @@ impl StateComputer for OrderingStateComputer {
             Err(anyhow::anyhow!("Injected error in sync_to").into())
         });
 
-        // reset execution phase and commit phase
+        // reset execution phase and commit phase, the speculative results don't survive the
+        // executor reset
+        if let Some(speculative_execution) = &self.speculative_execution {
+            speculative_execution.reset();
+        }
         let (tx, rx) = oneshot::channel::<ResetAck>();
         self.reset_event_channel_tx
             .clone()
@@ -154,6 +166,7 @@ impl DagStateSyncComputer {
                 executor_channel: sender_tx,
                 state_computer_for_sync,
                 reset_event_channel_tx,
+                speculative_execution: None,
             },
         }
     }
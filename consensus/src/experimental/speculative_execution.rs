@@ -0,0 +1,174 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    counters::SPECULATIVE_EXECUTION_COUNT, experimental::pipeline_phase::CountedRequest,
+    state_computer::StateComputeResultFut, state_replication::StateComputer,
+};
+use aptos_consensus_types::{block::Block, common::Round, executed_block::ExecutedBlock};
+use aptos_crypto::HashValue;
+use aptos_executor_types::{ExecutorError, ExecutorResult, StateComputeResult};
+use aptos_infallible::Mutex;
+use aptos_logger::debug;
+use futures::{channel::oneshot, future::Shared, FutureExt, TryFutureExt};
+use std::{
+    collections::HashMap,
+    sync::{atomic::AtomicU64, Arc},
+};
+use tokio::task::JoinHandle;
+
+/// [ This class is used when consensus.decoupled = true and consensus.speculative_execution = true ]
+/// SpeculativeExecution schedules proposed blocks for execution as soon as they are inserted in
+/// the block store, on top of their (possibly also speculative) parents. When a block is ordered,
+/// ExecutionSchedulePhase takes over its speculative result instead of executing it again.
+/// Speculative results of blocks that lost to a fork are discarded once a block at the same or a
+/// higher round is ordered, and all of them on reset.
+pub struct SpeculativeExecution {
+    execution_proxy: Arc<dyn StateComputer>,
+    // Shared with the buffer manager, so a reset waits for the speculative blocks in flight
+    ongoing_tasks: Arc<AtomicU64>,
+    // Blocks sent or about to be sent to the executor, a block is only speculated on top of one of
+    // them
+    scheduled: Mutex<HashMap<HashValue, ScheduledBlock>>,
+}
+
+// Resolves once a speculative block has been sent to the executor
+type SentToExecutor = Shared<oneshot::Receiver<()>>;
+
+struct ScheduledBlock {
+    round: Round,
+    // None for ordered blocks, which the execution schedule phase has sent already
+    sent: Option<SentToExecutor>,
+    // The speculative result, until the block is ordered. Dropping it cancels the execution of a
+    // block that hasn't been sent to the executor yet.
+    result: Option<oneshot::Receiver<ExecutorResult<StateComputeResult>>>,
+}
+
+impl SpeculativeExecution {
+    pub fn new(execution_proxy: Arc<dyn StateComputer>, ongoing_tasks: Arc<AtomicU64>) -> Self {
+        Self {
+            execution_proxy,
+            ongoing_tasks,
+            scheduled: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Starts executing a proposed block, unless its parent is unknown to the executor or the
+    /// block is scheduled already. Returns the task executing the block.
+    pub fn speculate(
+        self: &Arc<Self>,
+        block: Block,
+        parent_block_id: HashValue,
+    ) -> Option<JoinHandle<()>> {
+        let (sent_tx, sent_rx) = oneshot::channel();
+        let (result_tx, result_rx) = oneshot::channel();
+        let parent_sent = {
+            let mut scheduled = self.scheduled.lock();
+            if scheduled.contains_key(&block.id()) {
+                return None;
+            }
+            let parent_sent = scheduled.get(&parent_block_id)?.sent.clone();
+            // Claimed together with its result, so the block is never scheduled twice, even if
+            // it's ordered before the task below runs
+            scheduled.insert(block.id(), ScheduledBlock {
+                round: block.round(),
+                sent: Some(sent_rx.shared()),
+                result: Some(result_rx),
+            });
+            parent_sent
+        };
+        let lifetime_guard = CountedRequest::new((), self.ongoing_tasks.clone());
+        let execution_proxy = self.execution_proxy.clone();
+        Some(tokio::spawn(async move {
+            // The executor has to know the parent before the block is sent to it
+            if let Some(parent_sent) = parent_sent {
+                let _ = parent_sent.await;
+            }
+            // Discarded in the meantime, by a reset or because it can't be ordered anymore
+            if result_tx.is_canceled() {
+                return;
+            }
+            let fut = execution_proxy
+                .schedule_compute(&block, parent_block_id)
+                .await;
+            let _ = sent_tx.send(());
+            debug!("Speculatively executing block {}", block.id());
+            // Keep the lifetime guard until the executor is done with the block
+            let _ = result_tx.send(fut.await);
+            drop(lifetime_guard);
+        }))
+    }
+
+    /// Takes over the speculative result of an ordered block, if there's one that didn't fail.
+    /// Waits until the block is sent to the executor, so the blocks ordered after it are sent
+    /// after it.
+    pub async fn take_result(&self, block: &ExecutedBlock) -> Option<StateComputeResultFut> {
+        let speculative_result = self
+            .scheduled
+            .lock()
+            .get_mut(&block.id())
+            .and_then(|scheduled| Some((scheduled.sent.clone(), scheduled.result.take()?)));
+        let fut: Option<StateComputeResultFut> = match speculative_result {
+            Some((sent, mut result)) => {
+                if let Some(sent) = sent {
+                    let _ = sent.await;
+                }
+                match result.try_recv() {
+                    Ok(Some(Ok(compute_result))) => {
+                        Some(Box::pin(async move { Ok(compute_result) }))
+                    },
+                    Ok(None) => Some(Box::pin(
+                        result
+                            .map_err(ExecutorError::internal_err)
+                            .and_then(|res| async { res }),
+                    )),
+                    // Failed, most likely because its parent was pruned, execute it again
+                    Ok(Some(Err(_))) | Err(_) => None,
+                }
+            },
+            None => None,
+        };
+        let label = if fut.is_some() { "hit" } else { "miss" };
+        SPECULATIVE_EXECUTION_COUNT
+            .with_label_values(&[label])
+            .inc();
+        fut
+    }
+
+    /// Records ordered blocks as sent to the executor, and discards the speculative blocks that
+    /// can't be ordered anymore
+    pub fn on_ordered(&self, ordered_blocks: &[ExecutedBlock]) {
+        let last_block = match ordered_blocks.last() {
+            Some(block) => block,
+            None => return,
+        };
+        let mut scheduled = self.scheduled.lock();
+        // Only the last ordered block can be the parent of blocks ordered later
+        scheduled.insert(last_block.id(), ScheduledBlock {
+            round: last_block.round(),
+            sent: None,
+            result: None,
+        });
+        let num_pending = Self::num_pending(&scheduled);
+        scheduled.retain(|id, block| block.round > last_block.round() || *id == last_block.id());
+        SPECULATIVE_EXECUTION_COUNT
+            .with_label_values(&["discarded"])
+            .inc_by((num_pending - Self::num_pending(&scheduled)) as u64);
+    }
+
+    /// Discards all speculative results, e.g. before the executor is reset for state sync
+    pub fn reset(&self) {
+        let mut scheduled = self.scheduled.lock();
+        SPECULATIVE_EXECUTION_COUNT
+            .with_label_values(&["discarded"])
+            .inc_by(Self::num_pending(&scheduled) as u64);
+        scheduled.clear();
+    }
+
+    fn num_pending(scheduled: &HashMap<HashValue, ScheduledBlock>) -> usize {
+        scheduled
+            .values()
+            .filter(|block| block.result.is_some())
+            .count()
+    }
+}
@@ -130,6 +130,7 @@ pub fn prepare_buffer_manager() -> (
         result_tx,
         Arc::new(EmptyStateComputer),
         reset_tx,
+        None,
     ));
 
     let (block_tx, block_rx) = create_channel::<OrderedBlocks>();
@@ -144,6 +145,7 @@ pub fn prepare_buffer_manager() -> (
         signing_phase_pipeline,
         persisting_phase_pipeline,
         buffer_manager,
+        _,
     ) = prepare_phases_and_buffer_manager(
         author,
         mocked_execution_proxy,
@@ -154,6 +156,7 @@ pub fn prepare_buffer_manager() -> (
         block_rx,
         buffer_reset_rx,
         validators.clone(),
+        false,
     );
 
     (
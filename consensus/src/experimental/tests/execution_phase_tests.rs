@@ -3,27 +3,45 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    error::StateSyncError,
     experimental::{
         buffer_manager::create_channel,
         execution_schedule_phase::{ExecutionRequest, ExecutionSchedulePhase},
         execution_wait_phase::{ExecutionResponse, ExecutionWaitPhase},
         pipeline_phase::{CountedRequest, PipelinePhase, StatelessPipeline},
+        speculative_execution::SpeculativeExecution,
         tests::phase_tester::PhaseTester,
     },
-    state_replication::StateComputer,
+    payload_manager::PayloadManager,
+    state_computer::StateComputeResultFut,
+    state_replication::{StateComputer, StateComputerCommitCallBackType},
     test_utils::{consensus_runtime, RandomComputeResultStateComputer},
+    transaction_deduper::TransactionDeduper,
+    transaction_shuffler::TransactionShuffler,
 };
 use aptos_consensus_types::{
-    block::{block_test_utils::certificate_for_genesis, Block},
-    common::Payload,
+    block::{
+        block_test_utils::{certificate_for_genesis, placeholder_certificate_for_block},
+        Block,
+    },
+    common::{Payload, Round},
     executed_block::ExecutedBlock,
     quorum_cert::QuorumCert,
 };
 use aptos_crypto::HashValue;
-use aptos_executor_types::{ExecutorError, StateComputeResult};
-use aptos_types::{ledger_info::LedgerInfo, validator_verifier::random_validator_verifier};
+use aptos_executor_types::{ExecutorError, ExecutorResult, StateComputeResult};
+use aptos_infallible::Mutex;
+use aptos_types::{
+    epoch_state::EpochState,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    validator_signer::ValidatorSigner,
+    validator_verifier::random_validator_verifier,
+};
 use async_trait::async_trait;
-use std::sync::{atomic::AtomicU64, Arc};
+use std::{
+    collections::HashMap,
+    sync::{atomic::AtomicU64, Arc},
+};
 
 // ExecutionSchedulePhase and ExecutionWaitPhase chained together.
 // In BufferManager they are chained through the main loop.
@@ -34,7 +52,7 @@ pub struct ExecutionPhaseForTest {
 
 impl ExecutionPhaseForTest {
     pub fn new(execution_proxy: Arc<dyn StateComputer>) -> Self {
-        let schedule_phase = ExecutionSchedulePhase::new(execution_proxy);
+        let schedule_phase = ExecutionSchedulePhase::new(execution_proxy, None);
         let wait_phase = ExecutionWaitPhase;
         Self {
             schedule_phase,
@@ -152,3 +170,216 @@ fn execution_phase_tests() {
     add_execution_phase_test_cases(&mut e2e_phase_tester, random_hash_value);
     e2e_phase_tester.e2e_test(in_channel_tx, out_channel_rx);
 }
+
+// Counts how many times each block was sent to the executor.
+struct CountingStateComputer {
+    inner: RandomComputeResultStateComputer,
+    num_scheduled: Mutex<HashMap<HashValue, usize>>,
+}
+
+impl CountingStateComputer {
+    fn new() -> Self {
+        Self {
+            inner: RandomComputeResultStateComputer::new(),
+            num_scheduled: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn num_scheduled(&self, block: &Block) -> usize {
+        self.num_scheduled
+            .lock()
+            .get(&block.id())
+            .copied()
+            .unwrap_or(0)
+    }
+}
+
+#[async_trait]
+impl StateComputer for CountingStateComputer {
+    async fn schedule_compute(
+        &self,
+        block: &Block,
+        parent_block_id: HashValue,
+    ) -> StateComputeResultFut {
+        *self.num_scheduled.lock().entry(block.id()).or_default() += 1;
+        self.inner.schedule_compute(block, parent_block_id).await
+    }
+
+    async fn commit(
+        &self,
+        blocks: &[Arc<ExecutedBlock>],
+        finality_proof: LedgerInfoWithSignatures,
+        callback: StateComputerCommitCallBackType,
+    ) -> ExecutorResult<()> {
+        self.inner.commit(blocks, finality_proof, callback).await
+    }
+
+    async fn sync_to(&self, target: LedgerInfoWithSignatures) -> Result<(), StateSyncError> {
+        self.inner.sync_to(target).await
+    }
+
+    fn new_epoch(
+        &self,
+        epoch_state: &EpochState,
+        payload_manager: Arc<PayloadManager>,
+        transaction_shuffler: Arc<dyn TransactionShuffler>,
+        block_gas_limit: Option<u64>,
+        transaction_deduper: Arc<dyn TransactionDeduper>,
+    ) {
+        self.inner.new_epoch(
+            epoch_state,
+            payload_manager,
+            transaction_shuffler,
+            block_gas_limit,
+            transaction_deduper,
+        )
+    }
+
+    fn end_epoch(&self) {
+        self.inner.end_epoch()
+    }
+}
+
+/// A proposal at `round` extending `parent`
+fn proposal(signer: &ValidatorSigner, parent: &Block, round: Round) -> Block {
+    let qc = placeholder_certificate_for_block(
+        &[signer.clone()],
+        parent.id(),
+        parent.round(),
+        parent.parent_id(),
+        parent.quorum_cert().certified_block().round(),
+    );
+    Block::new_proposal(Payload::empty(false), round, round, qc, signer, Vec::new()).unwrap()
+}
+
+fn executed(block: &Block) -> ExecutedBlock {
+    ExecutedBlock::new(block.clone(), StateComputeResult::new_dummy())
+}
+
+#[test]
+fn speculative_execution_test() {
+    let runtime = consensus_runtime();
+    let execution_proxy = Arc::new(CountingStateComputer::new());
+    let random_hash_value = execution_proxy.inner.get_root_hash();
+    let speculative_execution = Arc::new(SpeculativeExecution::new(
+        execution_proxy.clone(),
+        Arc::new(AtomicU64::new(0)),
+    ));
+    let schedule_phase =
+        ExecutionSchedulePhase::new(execution_proxy.clone(), Some(speculative_execution.clone()));
+
+    let (signers, _validators) = random_validator_verifier(1, None, false);
+    let block_1 = Block::new_proposal(
+        Payload::empty(false),
+        1,
+        1,
+        certificate_for_genesis(),
+        &signers[0],
+        Vec::new(),
+    )
+    .unwrap();
+    // Two proposals extending block 1, only the one of round 3 gets ordered
+    let block_3 = proposal(&signers[0], &block_1, 3);
+    let fork = proposal(&signers[0], &block_1, 2);
+    let execute = |blocks: Vec<&Block>| {
+        let wait_req = runtime.block_on(schedule_phase.process(ExecutionRequest {
+            ordered_blocks: blocks.into_iter().map(executed).collect(),
+            lifetime_guard: dummy_guard(),
+        }));
+        runtime.block_on(wait_req.fut).unwrap()
+    };
+
+    // Nothing is known to be executed yet, so block 1 is not speculated on
+    let _guard = runtime.enter();
+    assert!(speculative_execution
+        .speculate(block_1.clone(), block_1.parent_id())
+        .is_none());
+    execute(vec![&block_1]);
+    assert_eq!(execution_proxy.num_scheduled(&block_1), 1);
+
+    let tasks = vec![
+        speculative_execution.speculate(block_3.clone(), block_1.id()),
+        speculative_execution.speculate(fork.clone(), block_1.id()),
+    ];
+    // Blocks already scheduled are not speculated on again
+    assert!(speculative_execution
+        .speculate(block_3.clone(), block_1.id())
+        .is_none());
+    for task in tasks {
+        runtime.block_on(task.unwrap()).unwrap();
+    }
+    assert_eq!(execution_proxy.num_scheduled(&block_3), 1);
+    assert_eq!(execution_proxy.num_scheduled(&fork), 1);
+
+    // The speculative result of block 3 is taken over instead of executing it again
+    let result = execute(vec![&block_3]);
+    assert_eq!(result[0].compute_result().root_hash(), random_hash_value);
+    assert_eq!(execution_proxy.num_scheduled(&block_3), 1);
+
+    // The fork can't be ordered after block 3 anymore, so its result is discarded
+    assert!(runtime
+        .block_on(speculative_execution.take_result(&executed(&fork)))
+        .is_none());
+}
+
+#[test]
+fn speculative_execution_before_task_runs_test() {
+    // A single-threaded runtime only runs the spawned tasks while the test waits on it, so
+    // blocks can be ordered before their speculative tasks run
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let execution_proxy = Arc::new(CountingStateComputer::new());
+    let random_hash_value = execution_proxy.inner.get_root_hash();
+    let speculative_execution = Arc::new(SpeculativeExecution::new(
+        execution_proxy.clone(),
+        Arc::new(AtomicU64::new(0)),
+    ));
+    let schedule_phase =
+        ExecutionSchedulePhase::new(execution_proxy.clone(), Some(speculative_execution.clone()));
+
+    let (signers, _validators) = random_validator_verifier(1, None, false);
+    let block_1 = Block::new_proposal(
+        Payload::empty(false),
+        1,
+        1,
+        certificate_for_genesis(),
+        &signers[0],
+        Vec::new(),
+    )
+    .unwrap();
+    let block_3 = proposal(&signers[0], &block_1, 3);
+    let fork = proposal(&signers[0], &block_1, 2);
+    let block_4 = proposal(&signers[0], &block_3, 4);
+    let execute = |blocks: Vec<&Block>| {
+        let wait_req = runtime.block_on(schedule_phase.process(ExecutionRequest {
+            ordered_blocks: blocks.into_iter().map(executed).collect(),
+            lifetime_guard: dummy_guard(),
+        }));
+        runtime.block_on(wait_req.fut).unwrap()
+    };
+
+    let _guard = runtime.enter();
+    execute(vec![&block_1]);
+    let fork_task = speculative_execution
+        .speculate(fork.clone(), block_1.id())
+        .unwrap();
+
+    // The fork is discarded before its task runs, so it's never sent to the executor
+    execute(vec![&block_3]);
+    runtime.block_on(fork_task).unwrap();
+    assert_eq!(execution_proxy.num_scheduled(&fork), 0);
+    assert!(runtime
+        .block_on(speculative_execution.take_result(&executed(&fork)))
+        .is_none());
+
+    // Block 4 is ordered before its task runs, its speculative result is still taken over
+    let task = speculative_execution
+        .speculate(block_4.clone(), block_3.id())
+        .unwrap();
+    let result = execute(vec![&block_4]);
+    assert_eq!(result[0].compute_result().root_hash(), random_hash_value);
+    runtime.block_on(task).unwrap();
+    assert_eq!(execution_proxy.num_scheduled(&block_4), 1);
+}
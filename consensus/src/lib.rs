@@ -17,6 +17,7 @@ extern crate scopeguard;
 
 extern crate core;
 
+pub mod audit_log;
 mod block_storage;
 mod consensusdb;
 mod dag;
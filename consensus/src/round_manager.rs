@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    audit_log::{self, AuditEvent},
     block_storage::{
         tracing::{observe_block, BlockStage},
         BlockReader, BlockRetriever, BlockStore,
@@ -268,6 +269,14 @@ impl RoundManager {
             self.new_log(LogEvent::NewRound),
             reason = new_round_event.reason
         );
+        audit_log::record(
+            self.epoch_state.epoch,
+            new_round_event.round,
+            AuditEvent::RoundStarted {
+                timeout_ms: new_round_event.timeout.as_millis() as u64,
+                after_timeout: matches!(new_round_event.reason, NewRoundReason::Timeout),
+            },
+        );
 
         if self
             .proposer_election
@@ -405,6 +414,15 @@ impl RoundManager {
             block_hash = proposal_msg.proposal().id(),
             block_parent_hash = proposal_msg.proposal().quorum_cert().certified_block().id(),
         );
+        audit_log::record(
+            self.epoch_state.epoch,
+            proposal_msg.proposal().round(),
+            AuditEvent::ProposalReceived {
+                block_id: proposal_msg.proposal().id(),
+                proposer: proposal_msg.proposer(),
+                block_timestamp_usecs: proposal_msg.proposal().timestamp_usecs(),
+            },
+        );
 
         if self
             .ensure_round_and_sync_up(
@@ -601,6 +619,16 @@ impl RoundManager {
             timeout_vote.add_2chain_timeout(timeout, signature);
         }
 
+        if let Some((timeout, _)) = timeout_vote.two_chain_timeout() {
+            audit_log::record(
+                self.epoch_state.epoch,
+                round,
+                AuditEvent::TimeoutCast {
+                    hqc_round: timeout.hqc_round(),
+                    nil_block: is_nil_vote,
+                },
+            );
+        }
         self.round_state.record_vote(timeout_vote.clone());
         let timeout_vote_msg = VoteMsg::new(timeout_vote, self.block_store.sync_info());
         self.network.broadcast_timeout_vote(timeout_vote_msg).await;
@@ -757,6 +785,13 @@ impl RoundManager {
             "{}", vote
         );
 
+        audit_log::record(
+            self.epoch_state.epoch,
+            proposal_round,
+            AuditEvent::VoteCast {
+                block_id: vote.vote_data().proposed().id(),
+            },
+        );
         self.round_state.record_vote(vote.clone());
         let vote_msg = VoteMsg::new(vote, self.block_store.sync_info());
         self.network.send_vote(vote_msg, vec![recipient]).await;
@@ -893,9 +928,23 @@ impl RoundManager {
                         BlockStage::QC_AGGREGATED,
                     );
                 }
+                audit_log::record(
+                    self.epoch_state.epoch,
+                    qc.certified_block().round(),
+                    AuditEvent::QcFormed {
+                        block_id: qc.certified_block().id(),
+                    },
+                );
                 self.new_qc_aggregated(qc, vote.author()).await
             },
             VoteReceptionResult::New2ChainTimeoutCertificate(tc) => {
+                audit_log::record(
+                    self.epoch_state.epoch,
+                    tc.round(),
+                    AuditEvent::TcFormed {
+                        hqc_round: tc.highest_hqc_round(),
+                    },
+                );
                 self.new_2chain_tc_aggregated(tc).await
             },
             VoteReceptionResult::EchoTimeout(_) if !self.round_state.is_vote_timeout() => {
@@ -13,24 +13,199 @@ use crate::{
     transaction_shuffler::TransactionShuffler,
 };
 use anyhow::{format_err, Result};
-use aptos_consensus_types::{block::Block, common::Payload, executed_block::ExecutedBlock};
-use aptos_crypto::HashValue;
+use aptos_channels::aptos_channel;
+use aptos_consensus_types::{
+    block::Block,
+    common::{Author, Round},
+    executed_block::ExecutedBlock,
+};
+use aptos_crypto::{bls12381, HashValue};
+use aptos_event_notifications::ReconfigNotification;
 use aptos_executor_types::{ExecutorError, ExecutorResult, StateComputeResult};
+use aptos_global_constants::CONSENSUS_KEY;
 use aptos_infallible::Mutex;
 use aptos_logger::prelude::*;
+use aptos_secure_storage::{KVStorage, Storage};
 use aptos_types::{
-    epoch_state::EpochState, ledger_info::LedgerInfoWithSignatures, transaction::SignedTransaction,
+    epoch_state::EpochState,
+    ledger_info::LedgerInfoWithSignatures,
+    on_chain_config::{
+        InMemoryOnChainConfig, OnChainConfig, OnChainConfigPayload, OnChainConsensusConfig,
+        ValidatorSet,
+    },
+    transaction::SignedTransaction,
 };
+use fail::fail_point;
 use futures::{channel::mpsc, SinkExt};
 use futures_channel::mpsc::UnboundedSender;
 use std::{collections::HashMap, sync::Arc};
 
+/// The on-chain configs of an epoch
+#[derive(Clone)]
+pub struct EpochConfig {
+    pub validator_set: ValidatorSet,
+    pub consensus_config: OnChainConsensusConfig,
+    /// Consensus keys that validators rotated to during the previous epoch
+    pub rotated_keys: HashMap<Author, bls12381::PrivateKey>,
+}
+
+impl EpochConfig {
+    pub fn new(validator_set: ValidatorSet, consensus_config: OnChainConsensusConfig) -> Self {
+        Self {
+            validator_set,
+            consensus_config,
+            rotated_keys: HashMap::new(),
+        }
+    }
+
+    pub fn epoch_state(&self, epoch: u64) -> EpochState {
+        EpochState {
+            epoch,
+            verifier: (&self.validator_set).into(),
+        }
+    }
+
+    pub fn payload(&self, epoch: u64) -> OnChainConfigPayload<InMemoryOnChainConfig> {
+        let mut configs = HashMap::new();
+        configs.insert(
+            ValidatorSet::CONFIG_ID,
+            bcs::to_bytes(&self.validator_set).unwrap(),
+        );
+        configs.insert(
+            OnChainConsensusConfig::CONFIG_ID,
+            // Requires double serialization, check deserialize_into_config for more details
+            bcs::to_bytes(&bcs::to_bytes(&self.consensus_config).unwrap()).unwrap(),
+        );
+        OnChainConfigPayload::new(epoch, InMemoryOnChainConfig::new(configs))
+    }
+}
+
+/// Scripts the reconfigurations of a MockStateComputer: epoch `i + 1` runs with `epochs[i]`, and
+/// every epoch but the last one is ended by the first block of round `epoch_length` or later.
+pub struct ScriptedReconfig {
+    author: Author,
+    epoch_length: Round,
+    epochs: Vec<EpochConfig>,
+    reconfig_sender: aptos_channel::Sender<(), ReconfigNotification<InMemoryOnChainConfig>>,
+    /// The safety rules storage of the node, where rotated consensus keys are installed
+    safety_rules_storage: Mutex<Storage>,
+    last_notified_epoch: Mutex<u64>,
+}
+
+impl ScriptedReconfig {
+    pub fn new(
+        author: Author,
+        epoch_length: Round,
+        epochs: Vec<EpochConfig>,
+        reconfig_sender: aptos_channel::Sender<(), ReconfigNotification<InMemoryOnChainConfig>>,
+        safety_rules_storage: Storage,
+    ) -> Self {
+        assert!(!epochs.is_empty(), "The genesis epoch must be scripted");
+        Self {
+            author,
+            epoch_length,
+            epochs,
+            reconfig_sender,
+            safety_rules_storage: Mutex::new(safety_rules_storage),
+            last_notified_epoch: Mutex::new(0),
+        }
+    }
+
+    fn epoch_config(&self, epoch: u64) -> Option<&EpochConfig> {
+        epoch
+            .checked_sub(1)
+            .and_then(|index| self.epochs.get(index as usize))
+    }
+
+    /// Executes one transaction per block, so versions are deterministic across nodes. Blocks
+    /// after a reconfiguration are a suffix and keep the result of their parent.
+    fn compute(
+        &self,
+        block: &Block,
+        parent_result: Option<StateComputeResult>,
+        committed_version: u64,
+    ) -> StateComputeResult {
+        if let Some(parent_result) = &parent_result {
+            if parent_result.has_reconfiguration() {
+                return parent_result.clone();
+            }
+        }
+        let parent_num_leaves =
+            parent_result.map_or(committed_version + 1, |result| result.version() + 1);
+        let next_epoch = block.epoch() + 1;
+        let epoch_state = self
+            .epoch_config(next_epoch)
+            .filter(|_| block.round() >= self.epoch_length)
+            .map(|config| config.epoch_state(next_epoch));
+        StateComputeResult::new(
+            block.id(),
+            vec![],
+            parent_num_leaves + 1,
+            vec![],
+            parent_num_leaves,
+            epoch_state,
+            vec![],
+            vec![],
+            vec![],
+        )
+    }
+
+    /// Plays the part of state sync and the on-chain config subscription: once the ledger reaches
+    /// the end of an epoch, installs rotated keys and notifies consensus of the new epoch.
+    fn notify_reconfig(&self, ledger_info: &LedgerInfoWithSignatures) {
+        let ledger_info = ledger_info.ledger_info();
+        if !ledger_info.ends_epoch() {
+            return;
+        }
+        let epoch = ledger_info.next_block_epoch();
+        let mut last_notified_epoch = self.last_notified_epoch.lock();
+        if epoch <= *last_notified_epoch {
+            return;
+        }
+        let config = self
+            .epoch_config(epoch)
+            .unwrap_or_else(|| panic!("Epoch {} is not scripted", epoch));
+        if let Some(key) = config.rotated_keys.get(&self.author) {
+            self.safety_rules_storage
+                .lock()
+                .set(CONSENSUS_KEY, key.clone())
+                .expect("Unable to install rotated consensus key");
+        }
+        self.notify_epoch(epoch, ledger_info.version());
+        *last_notified_epoch = epoch;
+    }
+
+    fn notify_epoch(&self, epoch: u64, version: u64) {
+        let config = self
+            .epoch_config(epoch)
+            .unwrap_or_else(|| panic!("Epoch {} is not scripted", epoch));
+        self.reconfig_sender
+            .push(
+                (),
+                ReconfigNotification {
+                    version,
+                    on_chain_configs: config.payload(epoch),
+                },
+            )
+            .expect("Unable to send reconfig notification");
+    }
+
+    /// Starts the genesis epoch
+    pub fn start(&self) {
+        self.notify_epoch(1, 1);
+        *self.last_notified_epoch.lock() = 1;
+    }
+}
+
 pub struct MockStateComputer {
     state_sync_client: mpsc::UnboundedSender<Vec<SignedTransaction>>,
     executor_channel: UnboundedSender<OrderedBlocks>,
     consensus_db: Arc<MockStorage>,
-    block_cache: Mutex<HashMap<HashValue, Payload>>,
-    payload_manager: Arc<PayloadManager>,
+    /// The payload manager of the epoch in which each block was computed
+    block_cache: Mutex<HashMap<HashValue, Arc<PayloadManager>>>,
+    payload_manager: Mutex<Arc<PayloadManager>>,
+    reconfig: Option<ScriptedReconfig>,
+    compute_results: Mutex<HashMap<HashValue, StateComputeResult>>,
 }
 
 impl MockStateComputer {
@@ -44,7 +219,22 @@ impl MockStateComputer {
             executor_channel,
             consensus_db,
             block_cache: Mutex::new(HashMap::new()),
-            payload_manager: Arc::from(PayloadManager::DirectMempool),
+            payload_manager: Mutex::new(Arc::from(PayloadManager::DirectMempool)),
+            reconfig: None,
+            compute_results: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// A state computer that ends epochs as scripted by `reconfig`
+    pub fn new_with_reconfig(
+        state_sync_client: mpsc::UnboundedSender<Vec<SignedTransaction>>,
+        executor_channel: UnboundedSender<OrderedBlocks>,
+        consensus_db: Arc<MockStorage>,
+        reconfig: ScriptedReconfig,
+    ) -> Self {
+        MockStateComputer {
+            reconfig: Some(reconfig),
+            ..Self::new(state_sync_client, executor_channel, consensus_db)
         }
     }
 
@@ -55,16 +245,17 @@ impl MockStateComputer {
             callback,
         } = blocks;
 
-        self.consensus_db
-            .commit_to_storage(ordered_proof.ledger_info().clone());
+        self.consensus_db.commit_to_storage(ordered_proof.clone());
         // mock sending commit notif to state sync
         let mut txns = vec![];
         for block in &ordered_blocks {
-            self.block_cache
+            let payload_manager = self
+                .block_cache
                 .lock()
                 .remove(&block.id())
                 .ok_or_else(|| format_err!("Cannot find block"))?;
-            let mut payload_txns = self.payload_manager.get_transactions(block.block()).await?;
+            self.compute_results.lock().remove(&block.parent_id());
+            let mut payload_txns = payload_manager.get_transactions(block.block()).await?;
             txns.append(&mut payload_txns);
         }
         // they may fail during shutdown
@@ -84,13 +275,29 @@ impl StateComputer for MockStateComputer {
     async fn compute(
         &self,
         block: &Block,
-        _parent_block_id: HashValue,
+        parent_block_id: HashValue,
     ) -> ExecutorResult<StateComputeResult> {
-        self.block_cache.lock().insert(
-            block.id(),
-            block.payload().unwrap_or(&Payload::empty(false)).clone(),
-        );
-        let result = StateComputeResult::new_dummy();
+        // Same failpoint as the execution pipeline, which the mock stands in for
+        fail_point!("consensus::compute", |_| {
+            Err(ExecutorError::InternalError {
+                error: "Injected error in compute".into(),
+            })
+        });
+        self.block_cache
+            .lock()
+            .insert(block.id(), self.payload_manager.lock().clone());
+        let result = match &self.reconfig {
+            Some(reconfig) => {
+                let parent_result = self.compute_results.lock().get(&parent_block_id).cloned();
+                let committed_version = self.consensus_db.get_ledger_info().version();
+                let result = reconfig.compute(block, parent_result, committed_version);
+                self.compute_results
+                    .lock()
+                    .insert(block.id(), result.clone());
+                result
+            },
+            None => StateComputeResult::new_dummy(),
+        };
         Ok(result)
     }
 
@@ -130,19 +337,24 @@ impl StateComputer for MockStateComputer {
             "Fake sync to block id {}",
             commit.ledger_info().consensus_block_id()
         );
-        self.consensus_db
-            .commit_to_storage(commit.ledger_info().clone());
+        self.consensus_db.commit_to_storage(commit.clone());
+        // EpochManager always syncs to the epoch-ending ledger info before it waits for the
+        // reconfiguration, with the processors of the old epoch already shut down
+        if let Some(reconfig) = &self.reconfig {
+            reconfig.notify_reconfig(&commit);
+        }
         Ok(())
     }
 
     fn new_epoch(
         &self,
         _: &EpochState,
-        _: Arc<PayloadManager>,
+        payload_manager: Arc<PayloadManager>,
         _: Arc<dyn TransactionShuffler>,
         _: Option<u64>,
         _: Arc<dyn TransactionDeduper>,
     ) {
+        *self.payload_manager.lock() = payload_manager;
     }
 
     fn end_epoch(&self) {}
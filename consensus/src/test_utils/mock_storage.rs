@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    consensusdb::ConsensusDB,
     epoch_manager::LivenessStorageData,
     persistent_liveness_storage::{
         LedgerRecoveryData, PersistentLivenessStorage, RecoveryData, RootMetadata,
@@ -14,13 +15,18 @@ use aptos_consensus_types::{
 };
 use aptos_crypto::HashValue;
 use aptos_infallible::Mutex;
-use aptos_storage_interface::DbReader;
+use aptos_storage_interface::{DbReader, Order};
+use aptos_temppath::TempPath;
 use aptos_types::{
     aggregate_signature::AggregateSignature,
+    contract_event::EventWithVersion,
     epoch_change::EpochChangeProof,
+    event::EventKey,
     ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
     on_chain_config::ValidatorSet,
+    transaction::Version,
 };
+use once_cell::sync::OnceCell;
 use std::{collections::HashMap, sync::Arc};
 
 pub struct MockSharedStorage {
@@ -33,6 +39,9 @@ pub struct MockSharedStorage {
     // Liveness state
     pub highest_2chain_timeout_certificate: Mutex<Option<TwoChainTimeoutCertificate>>,
     pub validator_set: ValidatorSet,
+
+    // DAG state, only created once a DAG epoch starts
+    consensus_db: OnceCell<(TempPath, Arc<ConsensusDB>)>,
 }
 
 impl MockSharedStorage {
@@ -44,15 +53,25 @@ impl MockSharedStorage {
             last_vote: Mutex::new(None),
             highest_2chain_timeout_certificate: Mutex::new(None),
             validator_set,
+            consensus_db: OnceCell::new(),
         }
     }
+
+    fn consensus_db(&self) -> Arc<ConsensusDB> {
+        let (_, db) = self.consensus_db.get_or_init(|| {
+            let path = TempPath::new();
+            let db = Arc::new(ConsensusDB::new(&path));
+            (path, db)
+        });
+        db.clone()
+    }
 }
 
 /// A storage that simulates the operations in-memory, used in the tests that cares about storage
 /// consistency.
 pub struct MockStorage {
     pub shared_storage: Arc<MockSharedStorage>,
-    storage_ledger: Mutex<LedgerInfo>,
+    storage_ledger: Arc<Mutex<LedgerInfo>>,
 }
 
 impl MockStorage {
@@ -73,7 +92,7 @@ impl MockStorage {
             .insert(lis.ledger_info().version(), lis);
         MockStorage {
             shared_storage,
-            storage_ledger: Mutex::new(ledger_info),
+            storage_ledger: Arc::new(Mutex::new(ledger_info)),
         }
     }
 
@@ -81,8 +100,15 @@ impl MockStorage {
         self.storage_ledger.lock().clone()
     }
 
-    pub fn commit_to_storage(&self, ledger: LedgerInfo) {
-        *self.storage_ledger.lock() = ledger;
+    pub fn commit_to_storage(&self, ledger: LedgerInfoWithSignatures) {
+        *self.storage_ledger.lock() = ledger.ledger_info().clone();
+        // Keep the epoch-ending ledger infos around to serve epoch change proofs
+        if ledger.ledger_info().ends_epoch() {
+            self.shared_storage
+                .lis
+                .lock()
+                .insert(ledger.ledger_info().version(), ledger);
+        }
 
         if let Err(e) = self.verify_consistency() {
             panic!("invalid db after commit: {}", e);
@@ -220,22 +246,81 @@ impl PersistentLivenessStorage for MockStorage {
     }
 
     fn retrieve_epoch_change_proof(&self, version: u64) -> Result<EpochChangeProof> {
-        let lis = self
+        let mut lis: Vec<_> = self
             .shared_storage
             .lis
             .lock()
-            .get(&version)
+            .values()
+            .filter(|li| li.ledger_info().version() >= version)
             .cloned()
-            .ok_or_else(|| anyhow::anyhow!("LedgerInfo for version not found"))?;
-        Ok(EpochChangeProof::new(vec![lis], false))
+            .collect();
+        if lis.is_empty() {
+            anyhow::bail!("LedgerInfo for version not found");
+        }
+        lis.sort_by_key(|li| li.ledger_info().version());
+        Ok(EpochChangeProof::new(lis, false))
     }
 
     fn aptos_db(&self) -> Arc<dyn DbReader> {
-        unimplemented!()
+        Arc::new(MockDbReader {
+            shared_storage: self.shared_storage.clone(),
+            storage_ledger: self.storage_ledger.clone(),
+        })
     }
 
-    fn consensus_db(&self) -> Arc<crate::consensusdb::ConsensusDB> {
-        unimplemented!()
+    fn consensus_db(&self) -> Arc<ConsensusDB> {
+        self.shared_storage.consensus_db()
+    }
+}
+
+/// Serves the few AptosDB reads consensus does from the ledger of a MockStorage: the epoch
+/// change proofs requested by peers lagging behind, the latest ledger info, and the commit
+/// history DAG bootstraps its anchor election from.
+struct MockDbReader {
+    shared_storage: Arc<MockSharedStorage>,
+    storage_ledger: Arc<Mutex<LedgerInfo>>,
+}
+
+impl DbReader for MockDbReader {
+    fn get_epoch_ending_ledger_infos(
+        &self,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<EpochChangeProof> {
+        let mut lis: Vec<_> = self
+            .shared_storage
+            .lis
+            .lock()
+            .values()
+            .filter(|li| (start_epoch..end_epoch).contains(&li.ledger_info().epoch()))
+            .cloned()
+            .collect();
+        lis.sort_by_key(|li| li.ledger_info().epoch());
+        Ok(EpochChangeProof::new(lis, false))
+    }
+
+    fn get_latest_ledger_info_option(&self) -> Result<Option<LedgerInfoWithSignatures>> {
+        Ok(Some(LedgerInfoWithSignatures::new(
+            self.storage_ledger.lock().clone(),
+            AggregateSignature::empty(),
+        )))
+    }
+
+    fn get_latest_version(&self) -> Result<Version> {
+        Ok(self.storage_ledger.lock().version())
+    }
+
+    /// The mock ledger executes no transactions, so it has no events: DAG starts every epoch
+    /// without commit history, as it does right after genesis.
+    fn get_events(
+        &self,
+        _event_key: &EventKey,
+        _start: u64,
+        _order: Order,
+        _limit: u64,
+        _ledger_version: Version,
+    ) -> Result<Vec<EventWithVersion>> {
+        Ok(vec![])
     }
 }
 
@@ -306,7 +391,7 @@ impl PersistentLivenessStorage for EmptyStorage {
         unimplemented!()
     }
 
-    fn consensus_db(&self) -> Arc<crate::consensusdb::ConsensusDB> {
+    fn consensus_db(&self) -> Arc<ConsensusDB> {
         unimplemented!()
     }
 }
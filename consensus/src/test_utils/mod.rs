@@ -27,7 +27,8 @@ use aptos_consensus_types::{block::block_test_utils::gen_test_certificate, commo
 use aptos_types::block_info::BlockInfo;
 pub use mock_payload_manager::MockPayloadManager;
 pub use mock_state_computer::{
-    EmptyStateComputer, MockStateComputer, RandomComputeResultStateComputer,
+    EmptyStateComputer, EpochConfig, MockStateComputer, RandomComputeResultStateComputer,
+    ScriptedReconfig,
 };
 pub use mock_storage::{EmptyStorage, MockSharedStorage, MockStorage};
 
@@ -0,0 +1,436 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Drives nodes through scripted reconfigurations: validator set changes, on-chain consensus
+//! config changes (quorum store toggles and switches between Jolteon and DAG) and consensus key
+//! rotations. Every test checks that each node reaches the last epoch through a valid chain of
+//! epoch changes, and that no committed block is lost or forked across the epoch boundaries.
+
+use crate::{
+    network_tests::NetworkPlayground,
+    persistent_liveness_storage::PersistentLivenessStorage,
+    test_utils::{consensus_runtime, timed_block_on, EpochConfig},
+    twins::twins_node::SMRNode,
+};
+use aptos_consensus_types::common::Round;
+use aptos_crypto::{bls12381, HashValue, Uniform};
+use aptos_types::{
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    on_chain_config::{
+        ConsensusConfigV1, DagConsensusConfigV1, OnChainConsensusConfig,
+        ProposerElectionType::RotatingProposer, ValidatorSet,
+    },
+    validator_info::ValidatorInfo,
+    waypoint::Waypoint,
+};
+use futures::StreamExt;
+use rand::{rngs::StdRng, SeedableRng};
+use std::{
+    collections::HashMap,
+    sync::{Mutex, MutexGuard},
+};
+
+const NUM_NODES: usize = 4;
+const EPOCH_LENGTH: Round = 5;
+/// Timeouts are needed for liveness once messages get dropped
+const ROUND_INITIAL_TIMEOUT_MS: u64 = 1_000;
+
+/// Failpoints are global to the process, so the tests in this module run one at a time: the
+/// failures injected by one of them can't leak into another one.
+static SERIAL_TEST_LOCK: Mutex<()> = Mutex::new(());
+
+fn serial_test_guard() -> MutexGuard<'static, ()> {
+    // A failed test doesn't affect the others
+    SERIAL_TEST_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn consensus_config(quorum_store_enabled: bool) -> OnChainConsensusConfig {
+    let config = ConsensusConfigV1 {
+        proposer_election_type: RotatingProposer(1),
+        ..ConsensusConfigV1::default()
+    };
+    if quorum_store_enabled {
+        OnChainConsensusConfig::V2(config)
+    } else {
+        OnChainConsensusConfig::V1(config)
+    }
+}
+
+fn dag_consensus_config() -> OnChainConsensusConfig {
+    OnChainConsensusConfig::DagV1(DagConsensusConfigV1::default())
+}
+
+/// Rebuilds the validator set, mapping the consensus key and voting power of every validator
+fn map_validators(
+    validator_set: &ValidatorSet,
+    f: impl Fn(usize, &ValidatorInfo) -> Option<(bls12381::PublicKey, u64)>,
+) -> ValidatorSet {
+    ValidatorSet::new(
+        validator_set
+            .payload()
+            .enumerate()
+            .filter_map(|(index, info)| {
+                f(index, info).map(|(public_key, voting_power)| {
+                    ValidatorInfo::new_with_test_network_keys(
+                        *info.account_address(),
+                        public_key,
+                        voting_power,
+                        info.config().validator_index,
+                    )
+                })
+            })
+            .collect(),
+    )
+}
+
+fn with_voting_power(
+    validator_set: &ValidatorSet,
+    index: usize,
+    voting_power: u64,
+) -> ValidatorSet {
+    map_validators(validator_set, |i, info| {
+        let power = if i == index {
+            voting_power
+        } else {
+            info.consensus_voting_power()
+        };
+        Some((info.consensus_public_key().clone(), power))
+    })
+}
+
+fn without_validator(validator_set: &ValidatorSet, index: usize) -> ValidatorSet {
+    map_validators(validator_set, |i, info| {
+        (i != index).then(|| {
+            (
+                info.consensus_public_key().clone(),
+                info.consensus_voting_power(),
+            )
+        })
+    })
+}
+
+/// Rotates the consensus keys of the validators at the given indices
+fn with_rotated_keys(
+    validator_set: &ValidatorSet,
+    consensus_config: OnChainConsensusConfig,
+    indices: &[usize],
+    seed: u8,
+) -> EpochConfig {
+    let mut rng = StdRng::from_seed([seed; 32]);
+    let keys: HashMap<usize, bls12381::PrivateKey> = indices
+        .iter()
+        .map(|index| (*index, bls12381::PrivateKey::generate(&mut rng)))
+        .collect();
+    let validator_set = map_validators(validator_set, |i, info| {
+        let public_key = keys.get(&i).map_or_else(
+            || info.consensus_public_key().clone(),
+            |key| key.public_key(),
+        );
+        Some((public_key, info.consensus_voting_power()))
+    });
+    let rotated_keys = validator_set
+        .payload()
+        .enumerate()
+        .filter_map(|(i, info)| {
+            keys.get(&i)
+                .map(|key| (*info.account_address(), key.clone()))
+        })
+        .collect();
+    EpochConfig {
+        validator_set,
+        consensus_config,
+        rotated_keys,
+    }
+}
+
+/// Runs the nodes through the scripted epochs until all validators of the last epoch commit in
+/// it, then checks the ledger of every one of them
+fn run_epochs(epochs: impl FnOnce(ValidatorSet) -> Vec<EpochConfig>) {
+    let runtime = consensus_runtime();
+    let mut playground = NetworkPlayground::new(runtime.handle().clone());
+    let mut scripted = vec![];
+    let mut nodes = SMRNode::start_num_nodes_with_epochs(
+        NUM_NODES,
+        &mut playground,
+        EPOCH_LENGTH,
+        ROUND_INITIAL_TIMEOUT_MS,
+        |validator_set| {
+            scripted = epochs(validator_set);
+            scripted.clone()
+        },
+    );
+    runtime.spawn(playground.start());
+
+    let last_epoch = scripted.len() as u64;
+    let last_validators = &scripted.last().unwrap().validator_set;
+    // Validators removed along the way keep running, but they are not expected to commit
+    let mut validators: Vec<_> = nodes
+        .iter_mut()
+        .filter(|node| {
+            last_validators
+                .payload()
+                .any(|info| *info.account_address() == node.id.author)
+        })
+        .collect();
+
+    let commits = timed_block_on(&runtime, async {
+        let mut commits = vec![];
+        for node in validators.iter_mut() {
+            commits.push(wait_for_epoch(node, last_epoch).await);
+        }
+        commits
+    });
+
+    let genesis_li = LedgerInfo::mock_genesis(Some(scripted[0].validator_set.clone()));
+    check_ledgers(
+        &validators,
+        &commits,
+        Waypoint::new_epoch_boundary(&genesis_li).unwrap(),
+        last_epoch,
+    );
+}
+
+/// Returns every ledger info the node committed until it committed a block of `epoch`
+async fn wait_for_epoch(node: &mut SMRNode, epoch: u64) -> Vec<LedgerInfoWithSignatures> {
+    let mut commits = vec![];
+    loop {
+        let commit = node.commit_cb_receiver.next().await.unwrap();
+        let reached = commit.ledger_info().epoch() >= epoch;
+        commits.push(commit);
+        if reached {
+            return commits;
+        }
+    }
+}
+
+fn check_ledgers(
+    nodes: &[&mut SMRNode],
+    commits: &[Vec<LedgerInfoWithSignatures>],
+    genesis_waypoint: Waypoint,
+    last_epoch: u64,
+) {
+    let mut epoch_ending_blocks: HashMap<u64, (u64, HashValue)> = HashMap::new();
+    let mut committed_blocks: HashMap<u64, HashValue> = HashMap::new();
+
+    for (node, commits) in nodes.iter().zip(commits) {
+        // The node went through every epoch change, each one verified by the previous epoch
+        let proof = node.storage.retrieve_epoch_change_proof(0).unwrap();
+        let last_li = proof.verify(&genesis_waypoint).unwrap();
+        assert_eq!(last_li.ledger_info().next_block_epoch(), last_epoch);
+        for li in &proof.ledger_info_with_sigs {
+            let li = li.ledger_info();
+            let block = (li.version(), li.consensus_block_id());
+            assert_eq!(
+                *epoch_ending_blocks.entry(li.epoch()).or_insert(block),
+                block,
+                "Nodes ended epoch {} with different blocks",
+                li.epoch()
+            );
+        }
+
+        // Commits only move forward, and no block is committed twice at different versions
+        for pair in commits.windows(2) {
+            let (prev, next) = (pair[0].ledger_info(), pair[1].ledger_info());
+            assert!((prev.epoch(), prev.round()) < (next.epoch(), next.round()));
+            assert!(prev.version() <= next.version());
+        }
+        for commit in commits {
+            let li = commit.ledger_info();
+            assert_eq!(
+                *committed_blocks
+                    .entry(li.version())
+                    .or_insert_with(|| li.consensus_block_id()),
+                li.consensus_block_id(),
+                "Nodes committed different blocks at version {}",
+                li.version()
+            );
+        }
+    }
+
+    // Blocks of a new epoch extend the ledger where the previous epoch ended
+    for commits in commits {
+        for commit in commits {
+            let li = commit.ledger_info();
+            if let Some((version, _)) = epoch_ending_blocks.get(&(li.epoch() - 1)) {
+                assert!(
+                    li.version() > *version,
+                    "Commit {} of epoch {} does not extend the end of the previous epoch",
+                    li,
+                    li.epoch()
+                );
+            }
+        }
+    }
+}
+
+fn validator_set_changes(genesis: ValidatorSet) -> Vec<EpochConfig> {
+    let epoch_2 = with_voting_power(&genesis, 0, 2);
+    let epoch_3 = without_validator(&epoch_2, NUM_NODES - 1);
+    vec![
+        EpochConfig::new(genesis, consensus_config(false)),
+        EpochConfig::new(epoch_2, consensus_config(false)),
+        EpochConfig::new(epoch_3, consensus_config(false)),
+    ]
+}
+
+fn quorum_store_toggles(genesis: ValidatorSet) -> Vec<EpochConfig> {
+    vec![
+        EpochConfig::new(genesis.clone(), consensus_config(false)),
+        EpochConfig::new(genesis.clone(), consensus_config(true)),
+        EpochConfig::new(genesis, consensus_config(false)),
+    ]
+}
+
+/// Switches to DAG and back, each time from and to Jolteon with a different quorum store setting
+fn dag_switches(genesis: ValidatorSet) -> Vec<EpochConfig> {
+    vec![
+        EpochConfig::new(genesis.clone(), consensus_config(false)),
+        EpochConfig::new(genesis.clone(), dag_consensus_config()),
+        EpochConfig::new(genesis.clone(), consensus_config(true)),
+        EpochConfig::new(genesis.clone(), dag_consensus_config()),
+        EpochConfig::new(genesis, consensus_config(false)),
+    ]
+}
+
+fn key_rotations(genesis: ValidatorSet) -> Vec<EpochConfig> {
+    let epoch_2 = with_rotated_keys(&genesis, consensus_config(false), &[0], 2);
+    let epoch_3 = with_rotated_keys(&epoch_2.validator_set, consensus_config(true), &[1, 2], 3);
+    vec![
+        EpochConfig::new(genesis, consensus_config(false)),
+        epoch_2,
+        epoch_3,
+    ]
+}
+
+/// All kinds of reconfigurations, the validator set change last so all nodes stay validators
+/// until then
+fn mixed_reconfigurations(genesis: ValidatorSet) -> Vec<EpochConfig> {
+    let epoch_2 = with_rotated_keys(&genesis, consensus_config(true), &[0], 2);
+    let epoch_3 = with_voting_power(&epoch_2.validator_set, 1, 2);
+    let epoch_4 = with_rotated_keys(&epoch_3, dag_consensus_config(), &[1], 4);
+    let epoch_5 = without_validator(&epoch_4.validator_set, NUM_NODES - 1);
+    vec![
+        EpochConfig::new(genesis, consensus_config(false)),
+        epoch_2,
+        EpochConfig::new(epoch_3, consensus_config(false)),
+        epoch_4,
+        EpochConfig::new(epoch_5, consensus_config(true)),
+    ]
+}
+
+#[test]
+/// Changes the voting power of a validator, then removes another one from the validator set.
+///
+/// Run the test:
+/// cargo xtest -p consensus epoch_change_validator_set_test -- --nocapture
+fn epoch_change_validator_set_test() {
+    let _guard = serial_test_guard();
+    run_epochs(validator_set_changes);
+}
+
+#[test]
+/// Enables quorum store for an epoch, then disables it again.
+///
+/// Run the test:
+/// cargo xtest -p consensus epoch_change_quorum_store_test -- --nocapture
+fn epoch_change_quorum_store_test() {
+    let _guard = serial_test_guard();
+    run_epochs(quorum_store_toggles);
+}
+
+#[test]
+/// Switches from Jolteon to DAG and back, with and without quorum store on the Jolteon side.
+///
+/// Run the test:
+/// cargo xtest -p consensus epoch_change_dag_test -- --nocapture
+fn epoch_change_dag_test() {
+    let _guard = serial_test_guard();
+    run_epochs(dag_switches);
+}
+
+#[test]
+/// Rotates the consensus keys of some validators, in epochs with and without quorum store.
+///
+/// Run the test:
+/// cargo xtest -p consensus epoch_change_key_rotation_test -- --nocapture
+fn epoch_change_key_rotation_test() {
+    let _guard = serial_test_guard();
+    run_epochs(key_rotations);
+}
+
+/// Every failpoint at an await point of consensus, from the commit of the epoch-ending block to
+/// the new epoch processing its first messages.
+#[cfg(feature = "failpoints")]
+const EPOCH_CHANGE_FAILPOINTS: &[&str] = &[
+    // Any message sent, on top of the failpoints of each kind of message below
+    "consensus::send::any",
+    // The buffer manager hands the epoch change proof to the epoch manager over the network
+    "consensus::send::epoch_change",
+    "consensus::send::broadcast_epoch_change",
+    // The execution and commit of the epoch-ending block
+    "consensus::compute",
+    "consensus::send::commit_vote",
+    "consensus::send::commit_decision",
+    // Catching up with the new epoch from peers
+    "consensus::process::any",
+    "consensus::process_sync_info_msg",
+    "consensus::process_block_retrieval",
+    "consensus::send::block_retrieval",
+    "consensus::send::broadcast_sync_info",
+    "consensus::sync_to",
+    // The first rounds of the new epoch
+    "consensus::inject_reconfiguration_error",
+    "consensus::send::proposal",
+    "consensus::send::broadcast_proposal",
+    "consensus::send::vote",
+    "consensus::send::broadcast_timeout_vote",
+    "consensus::process_proposal_msg",
+    "consensus::process_vote_msg",
+    "consensus::pull_payload",
+    // Quorum store, in the epochs that enable it
+    "consensus::send::batch",
+    "consensus::send::batch_request",
+    "consensus::send::broadcast_batch",
+    "consensus::send::signed_batch_info",
+    "consensus::send::proof_of_store",
+    "quorum_store::save",
+];
+
+/// How many times each failpoint of [`EPOCH_CHANGE_FAILPOINTS`] fails.
+#[cfg(feature = "failpoints")]
+const FAILURES_PER_FAILPOINT: usize = 3;
+
+#[test]
+#[cfg(feature = "failpoints")]
+/// Injects failures at every await point of an epoch change, one failpoint at a time, while
+/// going through all kinds of reconfigurations.
+///
+/// Run the test:
+/// cargo xtest -p consensus --features failpoints epoch_change_fault_injection_test -- --nocapture
+fn epoch_change_fault_injection_test() {
+    let _guard = serial_test_guard();
+    let scenario = fail::FailScenario::setup();
+    for failpoint in EPOCH_CHANGE_FAILPOINTS {
+        // Fails the first few times the failpoint is hit, then lets the epoch change go through
+        fail::cfg(*failpoint, &format!("{}*return", FAILURES_PER_FAILPOINT)).unwrap();
+        run_epochs(mixed_reconfigurations);
+        fail::remove(*failpoint);
+    }
+    scenario.teardown();
+}
+
+#[test]
+#[cfg(feature = "failpoints")]
+/// Quorum store is enabled on chain, but disabled locally by every node.
+///
+/// Run the test:
+/// cargo xtest -p consensus --features failpoints epoch_change_disable_quorum_store_test -- --nocapture
+fn epoch_change_disable_quorum_store_test() {
+    let _guard = serial_test_guard();
+    let scenario = fail::FailScenario::setup();
+    fail::cfg("consensus::start_new_epoch::disable_qs", "return").unwrap();
+    run_epochs(quorum_store_toggles);
+    scenario.teardown();
+}
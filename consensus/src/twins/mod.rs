@@ -3,4 +3,5 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod basic_twins_test;
+mod epoch_change_test;
 mod twins_node;
@@ -11,18 +11,18 @@ use crate::{
     network_tests::{NetworkPlayground, TwinId},
     payload_manager::PayloadManager,
     quorum_store::quorum_store_db::MockQuorumStoreDB,
-    test_utils::{MockStateComputer, MockStorage},
+    test_utils::{EpochConfig, MockStateComputer, MockStorage, ScriptedReconfig},
     util::time_service::ClockTimeService,
 };
 use aptos_bounded_executor::BoundedExecutor;
 use aptos_channels::{self, aptos_channel, message_queues::QueueStyle};
 use aptos_config::{
-    config::{NodeConfig, WaypointConfig},
+    config::{NodeConfig, OnDiskStorageConfig, SecureBackend, WaypointConfig},
     generator::{self, ValidatorSwarm},
     network_id::{NetworkId, PeerNetworkId},
 };
 use aptos_consensus_types::common::{Author, Round};
-use aptos_event_notifications::{ReconfigNotification, ReconfigNotificationListener};
+use aptos_event_notifications::ReconfigNotificationListener;
 use aptos_mempool::mocks::MockSharedMempool;
 use aptos_network::{
     application::interface::{NetworkClient, NetworkServiceEvents},
@@ -35,11 +35,12 @@ use aptos_network::{
     transport::ConnectionMetadata,
     ProtocolId,
 };
+use aptos_secure_storage::Storage;
+use aptos_temppath::TempPath;
 use aptos_types::{
     ledger_info::LedgerInfoWithSignatures,
     on_chain_config::{
-        ConsensusConfigV1, InMemoryOnChainConfig, OnChainConfig, OnChainConfigPayload,
-        OnChainConsensusConfig,
+        ConsensusConfigV1, OnChainConsensusConfig,
         ProposerElectionType::{self, RoundProposer},
         ValidatorSet,
     },
@@ -60,6 +61,7 @@ pub struct SMRNode {
     _runtime: Runtime,
     _shared_mempool: MockSharedMempool,
     _state_sync: mpsc::UnboundedReceiver<Vec<SignedTransaction>>,
+    _safety_rules_storage: TempPath,
 }
 
 fn author_from_config(config: &NodeConfig) -> Author {
@@ -69,11 +71,31 @@ fn author_from_config(config: &NodeConfig) -> Author {
 impl SMRNode {
     fn start(
         playground: &mut NetworkPlayground,
-        config: NodeConfig,
-        consensus_config: OnChainConsensusConfig,
-        storage: Arc<MockStorage>,
+        mut config: NodeConfig,
+        epochs: Vec<EpochConfig>,
+        epoch_length: Round,
         twin_id: TwinId,
     ) -> Self {
+        let (_, storage) = MockStorage::start_for_testing(epochs[0].validator_set.clone());
+
+        let waypoint = Waypoint::new_epoch_boundary(&storage.get_ledger_info())
+            .expect("Unable to produce waypoint with the provided LedgerInfo");
+        config
+            .consensus
+            .safety_rules
+            .test
+            .as_mut()
+            .unwrap()
+            .waypoint = Some(waypoint);
+        config.base.waypoint = WaypointConfig::FromConfig(waypoint);
+
+        // Quorum store and key rotation need the keys of safety rules to outlive a single
+        // Storage instance, which rules out the in-memory backend
+        let safety_rules_storage = TempPath::new();
+        let mut backend_config = OnDiskStorageConfig::default();
+        backend_config.path = safety_rules_storage.path().to_path_buf();
+        config.consensus.safety_rules.backend = SecureBackend::OnDiskStorage(backend_config);
+
         // Create a runtime for the twin
         let thread_name = format!("twin-{}", twin_id.id);
         let runtime = aptos_runtimes::spawn_named_runtime(thread_name, None);
@@ -106,34 +128,25 @@ impl SMRNode {
         let (ordered_blocks_tx, mut ordered_blocks_events) = mpsc::unbounded::<OrderedBlocks>();
         let shared_mempool = MockSharedMempool::new();
         let (quorum_store_to_mempool_sender, _) = mpsc::channel(1_024);
-        let state_computer = Arc::new(MockStateComputer::new(
-            state_sync_client,
-            ordered_blocks_tx,
-            Arc::clone(&storage),
-        ));
         let (reconfig_sender, reconfig_events) = aptos_channel::new(QueueStyle::LIFO, 1, None);
         let reconfig_listener = ReconfigNotificationListener {
             notification_receiver: reconfig_events,
         };
-        let _commit_notifier = Arc::from(PayloadManager::DirectMempool);
-        let mut configs = HashMap::new();
-        configs.insert(
-            ValidatorSet::CONFIG_ID,
-            bcs::to_bytes(storage.get_validator_set()).unwrap(),
-        );
-        configs.insert(
-            OnChainConsensusConfig::CONFIG_ID,
-            // Requires double serialization, check deserialize_into_config for more details
-            bcs::to_bytes(&bcs::to_bytes(&consensus_config).unwrap()).unwrap(),
+        let reconfig = ScriptedReconfig::new(
+            twin_id.author,
+            epoch_length,
+            epochs,
+            reconfig_sender,
+            Storage::from(&config.consensus.safety_rules.backend),
         );
-        let payload = OnChainConfigPayload::new(1, InMemoryOnChainConfig::new(configs));
-
-        reconfig_sender
-            .push((), ReconfigNotification {
-                version: 1,
-                on_chain_configs: payload,
-            })
-            .unwrap();
+        reconfig.start();
+        let state_computer = Arc::new(MockStateComputer::new_with_reconfig(
+            state_sync_client,
+            ordered_blocks_tx,
+            Arc::clone(&storage),
+            reconfig,
+        ));
+        let _commit_notifier = Arc::from(PayloadManager::DirectMempool);
 
         let time_service = Arc::new(ClockTimeService::new(runtime.handle().clone()));
 
@@ -186,18 +199,16 @@ impl SMRNode {
             storage,
             _shared_mempool: shared_mempool,
             _state_sync: state_sync,
+            _safety_rules_storage: safety_rules_storage,
         }
     }
 
-    /// Starts a given number of nodes and their twins
-    pub fn start_num_nodes_with_twins(
+    /// Generates the configs of a given number of nodes, sorted by author, and their genesis
+    /// validator set
+    fn generate_configs(
         num_nodes: usize,
-        num_twins: usize,
         playground: &mut NetworkPlayground,
-        proposer_type: ProposerElectionType,
-        round_proposers_idx: Option<HashMap<Round, usize>>,
-    ) -> Vec<Self> {
-        assert!(num_nodes >= num_twins);
+    ) -> (Vec<NodeConfig>, ValidatorSet) {
         let ValidatorSwarm {
             nodes: mut node_configs,
         } = generator::validator_swarm_for_testing(num_nodes);
@@ -216,6 +227,7 @@ impl SMRNode {
                 .unwrap();
         });
 
+        // sort by the peer id
         node_configs.sort_by_key(author_from_config);
         let validator_set = ValidatorSet::new(
             node_configs
@@ -232,7 +244,43 @@ impl SMRNode {
                 })
                 .collect(),
         );
-        // sort by the peer id
+        (node_configs, validator_set)
+    }
+
+    /// Starts a given number of nodes that go through the epochs returned by `epochs` for the
+    /// genesis validator set. Every epoch but the last one ends after `epoch_length` rounds.
+    pub fn start_num_nodes_with_epochs(
+        num_nodes: usize,
+        playground: &mut NetworkPlayground,
+        epoch_length: Round,
+        round_initial_timeout_ms: u64,
+        epochs: impl FnOnce(ValidatorSet) -> Vec<EpochConfig>,
+    ) -> Vec<Self> {
+        let (node_configs, validator_set) = Self::generate_configs(num_nodes, playground);
+        let epochs = epochs(validator_set);
+
+        node_configs
+            .into_iter()
+            .enumerate()
+            .map(|(smr_id, mut config)| {
+                config.consensus.round_initial_timeout_ms = round_initial_timeout_ms;
+                let author = author_from_config(&config);
+                let twin_id = TwinId { id: smr_id, author };
+                Self::start(playground, config, epochs.clone(), epoch_length, twin_id)
+            })
+            .collect()
+    }
+
+    /// Starts a given number of nodes and their twins
+    pub fn start_num_nodes_with_twins(
+        num_nodes: usize,
+        num_twins: usize,
+        playground: &mut NetworkPlayground,
+        proposer_type: ProposerElectionType,
+        round_proposers_idx: Option<HashMap<Round, usize>>,
+    ) -> Vec<Self> {
+        assert!(num_nodes >= num_twins);
+        let (mut node_configs, validator_set) = Self::generate_configs(num_nodes, playground);
 
         let proposer_type = match proposer_type {
             RoundProposer(_) => {
@@ -259,18 +307,6 @@ impl SMRNode {
         let mut smr_nodes = vec![];
 
         for (smr_id, mut config) in node_configs.into_iter().enumerate() {
-            let (_, storage) = MockStorage::start_for_testing(validator_set.clone());
-
-            let waypoint = Waypoint::new_epoch_boundary(&storage.get_ledger_info())
-                .expect("Unable to produce waypoint with the provided LedgerInfo");
-            config
-                .consensus
-                .safety_rules
-                .test
-                .as_mut()
-                .unwrap()
-                .waypoint = Some(waypoint);
-            config.base.waypoint = WaypointConfig::FromConfig(waypoint);
             // Disable timeout in twins test to avoid flakiness
             config.consensus.round_initial_timeout_ms = 2_000_000;
 
@@ -286,8 +322,8 @@ impl SMRNode {
             smr_nodes.push(Self::start(
                 playground,
                 config,
-                consensus_config,
-                storage,
+                vec![EpochConfig::new(validator_set.clone(), consensus_config)],
+                Round::MAX,
                 twin_id,
             ));
         }
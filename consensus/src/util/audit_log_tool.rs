@@ -0,0 +1,60 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::audit_log::{read_log, timelines};
+use anyhow::Result;
+use aptos_consensus_types::common::Round;
+use clap::Parser;
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[clap(about = "Merge consensus audit logs of several validators into per-round timelines.")]
+pub struct Command {
+    /// Audit logs to merge, one per validator
+    #[clap(required = true, value_parser)]
+    pub logs: Vec<PathBuf>,
+
+    // If None, will print all epochs.
+    #[clap(long)]
+    pub epoch: Option<u64>,
+
+    // If None, will print all rounds, otherwise the rounds in [start_round, end_round].
+    #[clap(long)]
+    pub start_round: Option<Round>,
+
+    #[clap(long)]
+    pub end_round: Option<Round>,
+}
+
+impl Command {
+    pub async fn run(self) -> Result<()> {
+        let logs = self
+            .logs
+            .iter()
+            .map(|path| read_log(path))
+            .collect::<Result<Vec<_>>>()?;
+
+        for ((epoch, round), events) in timelines(logs) {
+            if self.epoch.map_or(false, |e| e != epoch)
+                || self.start_round.map_or(false, |r| round < r)
+                || self.end_round.map_or(false, |r| round > r)
+            {
+                continue;
+            }
+            println!("epoch {epoch} round {round}");
+            // Times are relative to the first event of the round, clocks of different validators
+            // are only as close as their NTP sync.
+            let start = events[0].1.timestamp_usecs;
+            for (author, record) in events {
+                println!(
+                    "  +{:>8.3}ms {} {:?}",
+                    (record.timestamp_usecs - start) as f64 / 1000.0,
+                    author.short_str_lossless(),
+                    record.event
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
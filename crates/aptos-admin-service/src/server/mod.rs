@@ -24,6 +24,7 @@ use tokio::runtime::Runtime;
 mod consensus;
 #[cfg(target_os = "linux")]
 mod profiling;
+mod storage;
 #[cfg(target_os = "linux")]
 mod thread_dump;
 mod utils;
@@ -211,6 +212,28 @@ impl AdminService {
                     ))
                 }
             },
+            (hyper::Method::GET, "/debug/storage/pruner") => {
+                let aptos_db = context.aptos_db.read().clone();
+                if let Some(aptos_db) = aptos_db {
+                    storage::handle_pruner_status_request(req, aptos_db).await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "AptosDB is not available.",
+                    ))
+                }
+            },
+            (hyper::Method::POST, "/debug/storage/pruner/prune_window") => {
+                let aptos_db = context.aptos_db.read().clone();
+                if let Some(aptos_db) = aptos_db {
+                    storage::handle_set_prune_window_request(req, aptos_db).await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "AptosDB is not available.",
+                    ))
+                }
+            },
             _ => Ok(reply_with_status(StatusCode::NOT_FOUND, "Not found.")),
         }
     }
@@ -0,0 +1,114 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::server::utils::{reply_with, reply_with_status, spawn_blocking};
+use aptos_config::config::{
+    MIN_EPOCH_SNAPSHOT_PRUNE_WINDOW, MIN_LEDGER_PRUNE_WINDOW, MIN_STATE_MERKLE_PRUNE_WINDOW,
+};
+use aptos_logger::info;
+use aptos_storage_interface::DbReaderWriter;
+use aptos_types::transaction::Version;
+use hyper::{Body, Request, Response, StatusCode};
+use std::{collections::HashMap, sync::Arc};
+
+pub async fn handle_pruner_status_request(
+    req: Request<Body>,
+    aptos_db: Arc<DbReaderWriter>,
+) -> hyper::Result<Response<Body>> {
+    let query = req.uri().query().unwrap_or("");
+    let query_pairs: HashMap<_, _> = url::form_urlencoded::parse(query.as_bytes()).collect();
+
+    let bcs: bool = match query_pairs.get("bcs") {
+        Some(val) => match val.parse() {
+            Ok(val) => val,
+            Err(err) => return Ok(reply_with_status(StatusCode::BAD_REQUEST, err.to_string())),
+        },
+        None => false,
+    };
+
+    match spawn_blocking(move || {
+        let statuses = aptos_db.reader.get_pruner_status()?;
+        if bcs {
+            Ok(bcs::to_bytes(&statuses)?.into())
+        } else {
+            let mut body = String::new();
+            for status in statuses {
+                body.push_str(&format!("{status:?}\n\n"));
+            }
+            Ok(Into::<Body>::into(body))
+        }
+    })
+    .await
+    {
+        Ok(result) => Ok(reply_with(vec![], result)),
+        Err(e) => {
+            info!("Failed to get pruner status: {e:?}");
+            Ok(reply_with_status(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                e.to_string(),
+            ))
+        },
+    }
+}
+
+pub async fn handle_set_prune_window_request(
+    req: Request<Body>,
+    aptos_db: Arc<DbReaderWriter>,
+) -> hyper::Result<Response<Body>> {
+    let query = req.uri().query().unwrap_or("");
+    let query_pairs: HashMap<_, _> = url::form_urlencoded::parse(query.as_bytes()).collect();
+
+    let pruner_name = match query_pairs.get("pruner") {
+        Some(val) => val.to_string(),
+        None => {
+            return Ok(reply_with_status(
+                StatusCode::BAD_REQUEST,
+                "Missing \"pruner\".",
+            ))
+        },
+    };
+    let prune_window: Version = match query_pairs.get("prune_window") {
+        Some(val) => match val.parse() {
+            Ok(val) => val,
+            Err(err) => return Ok(reply_with_status(StatusCode::BAD_REQUEST, err.to_string())),
+        },
+        None => {
+            return Ok(reply_with_status(
+                StatusCode::BAD_REQUEST,
+                "Missing \"prune_window\".",
+            ))
+        },
+    };
+
+    if let Some(min_prune_window) = min_prune_window(&pruner_name) {
+        if prune_window < min_prune_window {
+            return Ok(reply_with_status(
+                StatusCode::BAD_REQUEST,
+                format!("\"prune_window\" of {pruner_name} can't be less than {min_prune_window}."),
+            ));
+        }
+    }
+
+    info!("Setting prune_window of {pruner_name} to {prune_window}.");
+
+    match spawn_blocking(move || aptos_db.writer.set_prune_window(&pruner_name, prune_window)).await
+    {
+        Ok(()) => Ok(reply_with(vec![], "OK")),
+        Err(e) => {
+            info!("Failed to set prune_window: {e:?}");
+            Ok(reply_with_status(StatusCode::BAD_REQUEST, e.to_string()))
+        },
+    }
+}
+
+/// The smallest prune window the config sanitizer accepts for the named pruner, `None` for
+/// unknown pruners, which the DB rejects anyway.
+fn min_prune_window(pruner_name: &str) -> Option<Version> {
+    match pruner_name {
+        // The state kv pruner is configured by the ledger pruner config.
+        "ledger_pruner" | "state_kv_pruner" => Some(MIN_LEDGER_PRUNE_WINDOW),
+        "state_merkle_pruner" => Some(MIN_STATE_MERKLE_PRUNE_WINDOW),
+        "epoch_snapshot_pruner" => Some(MIN_EPOCH_SNAPSHOT_PRUNE_WINDOW),
+        _ => None,
+    }
+}
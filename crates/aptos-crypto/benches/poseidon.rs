@@ -0,0 +1,35 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+#[macro_use]
+extern crate criterion;
+
+use aptos_crypto::poseidon::{self, PoseidonField, MAX_INPUTS};
+use ark_std::test_rng;
+use criterion::{measurement::Measurement, BenchmarkGroup, BenchmarkId, Criterion};
+
+fn benchmark_groups(c: &mut Criterion) {
+    let mut group = c.benchmark_group("poseidon");
+
+    hash::<ark_bls12_381::Fr, _>(&mut group, "bls12381_fr_hash");
+    hash::<ark_bn254::Fr, _>(&mut group, "bn254_fr_hash");
+
+    group.finish();
+}
+
+/// Benchmarks the time to hash every supported number of inputs. The permutation does the same
+/// work for any input, so the cost only depends on the number of inputs. (Used for gas
+/// estimation.)
+fn hash<F: PoseidonField, M: Measurement>(g: &mut BenchmarkGroup<M>, name: &str) {
+    let mut rng = test_rng();
+
+    for num_inputs in 1..=MAX_INPUTS {
+        let inputs: Vec<F> = (0..num_inputs).map(|_| F::rand(&mut rng)).collect();
+        g.bench_with_input(BenchmarkId::new(name, num_inputs), &inputs, |b, inputs| {
+            b.iter(|| poseidon::hash(inputs).unwrap())
+        });
+    }
+}
+
+criterion_group!(poseidon_benches, benchmark_groups);
+criterion_main!(poseidon_benches);
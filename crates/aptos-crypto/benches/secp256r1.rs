@@ -0,0 +1,94 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+#[macro_use]
+extern crate criterion;
+
+use aptos_crypto::{
+    p256_ecdsa::{P256PrivateKey, P256PublicKey, P256Signature},
+    traits::{Signature, SigningKey, Uniform},
+    PrivateKey,
+};
+use aptos_crypto_derive::{BCSCryptoHash, CryptoHasher};
+use criterion::{measurement::Measurement, BenchmarkGroup, Criterion, Throughput};
+use rand::{prelude::ThreadRng, thread_rng, Rng};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, CryptoHasher, BCSCryptoHash, Serialize, Deserialize)]
+pub struct TestAptosCrypto(pub u64);
+
+fn benchmark_groups(c: &mut Criterion) {
+    let mut group = c.benchmark_group("secp256r1");
+
+    group.sample_size(1000);
+
+    pk_deserialize(&mut group);
+    sig_deserialize(&mut group);
+    for msg_len in [0, 65536] {
+        ecdsa_verify(&mut group, msg_len);
+    }
+
+    group.finish();
+}
+
+/// Benchmarks the time to deserialize a P256 public key from its SEC1 encoding. (Used for gas
+/// estimation.)
+fn pk_deserialize<M: Measurement>(g: &mut BenchmarkGroup<M>) {
+    let mut csprng = thread_rng();
+
+    g.throughput(Throughput::Elements(1_u64));
+    g.bench_function("pk_deserialize", move |b| {
+        b.iter_with_setup(
+            || {
+                P256PrivateKey::generate(&mut csprng)
+                    .public_key()
+                    .to_bytes()
+            },
+            |pk_bytes| P256PublicKey::try_from(&pk_bytes[..]),
+        )
+    });
+}
+
+/// Benchmarks the time to deserialize a P256 signature, including the malleability check. (Used
+/// for gas estimation.)
+fn sig_deserialize<M: Measurement>(g: &mut BenchmarkGroup<M>) {
+    let mut csprng = thread_rng();
+
+    g.throughput(Throughput::Elements(1_u64));
+    g.bench_function("sig_deserialize", move |b| {
+        b.iter_with_setup(
+            || {
+                P256PrivateKey::generate(&mut csprng)
+                    .sign(&TestAptosCrypto(csprng.gen()))
+                    .unwrap()
+                    .to_bytes()
+            },
+            |sig_bytes| P256Signature::try_from(&sig_bytes[..]),
+        )
+    });
+}
+
+/// Benchmarks the time to verify a signature on a message of `msg_len` bytes. The difference
+/// between lengths is the cost of hashing the message. (Used for gas estimation.)
+fn ecdsa_verify<M: Measurement>(g: &mut BenchmarkGroup<M>, msg_len: usize) {
+    let mut csprng: ThreadRng = thread_rng();
+
+    let priv_key = P256PrivateKey::generate(&mut csprng);
+    let pub_key: P256PublicKey = (&priv_key).into();
+    let msg = vec![0u8; msg_len];
+
+    g.throughput(Throughput::Bytes(msg_len as u64));
+    g.bench_function(format!("ecdsa_verify/{}", msg_len), move |b| {
+        b.iter_with_setup(
+            || {
+                // Verification does the same work whether or not the signature is on `msg`, so
+                // any fresh signature does.
+                priv_key.sign(&TestAptosCrypto(csprng.gen())).unwrap()
+            },
+            |sig| sig.verify_arbitrary_msg(&msg, &pub_key),
+        )
+    });
+}
+
+criterion_group!(secp256r1_benches, benchmark_groups);
+criterion_main!(secp256r1_benches);
@@ -0,0 +1,80 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Generates the Poseidon parameters of every supported number of inputs as static tables, see
+//! `src/poseidon/mod.rs`.
+
+#[path = "src/poseidon/derivation.rs"]
+mod derivation;
+
+use ark_ff::{
+    fields::{Fp, FpConfig},
+    MontBackend, PrimeField,
+};
+use derivation::{derive_parameters, FULL_ROUNDS, MAX_INPUTS, PARTIAL_ROUNDS};
+use std::{env, fmt::Write, fs, path::Path};
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=src/poseidon/derivation.rs");
+
+    let mut out = String::new();
+    write_parameters::<MontBackend<ark_bls12_381::FrConfig, 4>, 4>(
+        &mut out,
+        "BLS12381_FR_PARAMETERS",
+        "ark_bls12_381::Fr",
+    );
+    write_parameters::<MontBackend<ark_bn254::FrConfig, 4>, 4>(
+        &mut out,
+        "BN254_FR_PARAMETERS",
+        "ark_bn254::Fr",
+    );
+    let out_dir = env::var("OUT_DIR").expect("Cargo sets OUT_DIR for build scripts.");
+    fs::write(Path::new(&out_dir).join("poseidon_parameters.rs"), out)
+        .expect("Writing the Poseidon parameters should succeed.");
+}
+
+/// Writes a `static $name: [PoseidonParameters<$field_type>; MAX_INPUTS]`, with the field
+/// elements in their internal Montgomery form so that they can be built in a constant context.
+fn write_parameters<P: FpConfig<N>, const N: usize>(out: &mut String, name: &str, field_type: &str)
+where
+    Fp<P, N>: PrimeField,
+{
+    let elements = |elements: &[Fp<P, N>]| -> String {
+        elements
+            .iter()
+            .map(|e| {
+                let limbs: Vec<String> = e.0 .0.iter().map(|l| format!("{:#018x}", l)).collect();
+                format!(
+                    "{}::new_unchecked(ark_ff::BigInt([{}])),",
+                    field_type,
+                    limbs.join(", ")
+                )
+            })
+            .collect()
+    };
+
+    writeln!(
+        out,
+        "static {}: [PoseidonParameters<{}>; MAX_INPUTS] = [",
+        name, field_type
+    )
+    .unwrap();
+    for (num_inputs, partial_rounds) in (1..=MAX_INPUTS).zip(PARTIAL_ROUNDS) {
+        let width = num_inputs + 1;
+        let (round_constants, mds) =
+            derive_parameters::<Fp<P, N>>(width, FULL_ROUNDS, partial_rounds);
+        writeln!(
+            out,
+            "PoseidonParameters {{ width: {}, full_rounds: {}, partial_rounds: {}, \
+            round_constants: &[{}], mds: &[{}] }},",
+            width,
+            FULL_ROUNDS,
+            partial_rounds,
+            elements(&round_constants),
+            elements(&mds),
+        )
+        .unwrap();
+    }
+    writeln!(out, "];").unwrap();
+}
@@ -17,6 +17,7 @@ pub mod hkdf;
 pub mod multi_ed25519;
 pub mod noise;
 pub mod p256_ecdsa;
+pub mod poseidon;
 pub mod secp256k1_ecdsa;
 pub mod test_utils;
 pub mod traits;
@@ -0,0 +1,133 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Derives the Poseidon round constants and MDS matrices with the Grain LFSR, as done by
+//! `generate_parameters_grain.sage` in the reference implementation. This is compiled into the
+//! build script, which writes the parameters of every supported arity out as static tables, and
+//! into the tests, which check those tables against it.
+
+use ark_ff::{BigInteger, PrimeField};
+
+/// The maximum number of field elements that can be hashed with a single permutation.
+pub const MAX_INPUTS: usize = 16;
+
+/// The number of full rounds, split evenly before and after the partial rounds.
+pub const FULL_ROUNDS: usize = 8;
+
+/// The number of partial rounds for `1, 2, ..., MAX_INPUTS` inputs.
+pub const PARTIAL_ROUNDS: [usize; MAX_INPUTS] = [
+    56, 57, 56, 60, 60, 63, 64, 63, 60, 66, 60, 65, 70, 60, 64, 68,
+];
+
+/// Returns `(full_rounds + partial_rounds) * width` round constants and a `width x width` Cauchy
+/// matrix, in row-major order.
+pub fn derive_parameters<F: PrimeField>(
+    width: usize,
+    full_rounds: usize,
+    partial_rounds: usize,
+) -> (Vec<F>, Vec<F>) {
+    let field_bits = F::MODULUS_BIT_SIZE as usize;
+    let mut grain = GrainLfsr::new(field_bits, width, full_rounds, partial_rounds);
+
+    // Round constants are sampled by rejection.
+    let round_constants = (0..(full_rounds + partial_rounds) * width)
+        .map(|_| loop {
+            if let Some(c) = F::from_bigint(grain.next_bigint::<F>(field_bits)) {
+                break c;
+            }
+        })
+        .collect();
+
+    // The MDS matrix entries are 1 / (x_i + y_j) for 2 * width distinct, reduced samples.
+    let mds = loop {
+        let samples: Vec<F> = loop {
+            let samples: Vec<F> = (0..2 * width)
+                .map(|_| {
+                    F::from_be_bytes_mod_order(&grain.next_bigint::<F>(field_bits).to_bytes_be())
+                })
+                .collect();
+            let all_distinct = samples
+                .iter()
+                .enumerate()
+                .all(|(i, s)| !samples[..i].contains(s));
+            if all_distinct {
+                break samples;
+            }
+        };
+        let (xs, ys) = samples.split_at(width);
+        let mds: Option<Vec<F>> = xs
+            .iter()
+            .flat_map(|x| ys.iter().map(move |y| (*x + y).inverse()))
+            .collect();
+        if let Some(mds) = mds {
+            break mds;
+        }
+    };
+
+    (round_constants, mds)
+}
+
+/// The self-shrinking Grain LFSR used to derive Poseidon parameters. Its 80-bit state is seeded
+/// with the field type, S-box type, field size, width and round numbers.
+struct GrainLfsr {
+    /// Bit `i` of the register holds the `i`-th oldest bit of the state.
+    state: u128,
+}
+
+impl GrainLfsr {
+    const STATE_BITS: usize = 80;
+    /// The number of initial outputs that are discarded.
+    const WARMUP: usize = 160;
+
+    fn new(field_bits: usize, width: usize, full_rounds: usize, partial_rounds: usize) -> Self {
+        // (value, bit length), most significant bit first: a prime field (1), the x^alpha S-box
+        // (0), followed by the parameters and 30 bits of padding.
+        let seed = [
+            (1u128, 2),
+            (0, 4),
+            (field_bits as u128, 12),
+            (width as u128, 12),
+            (full_rounds as u128, 10),
+            (partial_rounds as u128, 10),
+            ((1 << 30) - 1, 30),
+        ];
+        let mut lfsr = Self { state: 0 };
+        let mut position = 0;
+        for (value, len) in seed {
+            for i in (0..len).rev() {
+                lfsr.state |= ((value >> i) & 1) << position;
+                position += 1;
+            }
+        }
+        debug_assert_eq!(position, Self::STATE_BITS);
+
+        for _ in 0..Self::WARMUP {
+            lfsr.step();
+        }
+        lfsr
+    }
+
+    fn step(&mut self) -> bool {
+        let bit = |i: usize| (self.state >> i) & 1;
+        let new_bit = bit(62) ^ bit(51) ^ bit(38) ^ bit(23) ^ bit(13) ^ bit(0);
+        self.state = (self.state >> 1) | (new_bit << (Self::STATE_BITS - 1));
+        new_bit == 1
+    }
+
+    /// Outputs the second bit of the first pair whose first bit is set.
+    fn next_bit(&mut self) -> bool {
+        loop {
+            let keep = self.step();
+            let bit = self.step();
+            if keep {
+                return bit;
+            }
+        }
+    }
+
+    /// Outputs a `num_bits`-bit integer, most significant bit first.
+    fn next_bigint<F: PrimeField>(&mut self, num_bits: usize) -> F::BigInt {
+        let bits: Vec<bool> = (0..num_bits).map(|_| self.next_bit()).collect();
+        F::BigInt::from_bits_be(&bits)
+    }
+}
@@ -0,0 +1,122 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module implements the [Poseidon](https://eprint.iacr.org/2019/458) hash function over the
+//! scalar fields of BLS12-381 and BN254, for hashing inside zero-knowledge circuits.
+//!
+//! The parameterization follows [circomlib](https://github.com/iden3/circomlib): the S-box is
+//! `x^5`, there are 8 full rounds, the number of partial rounds depends on the number of inputs
+//! and an `n`-input hash uses a width-`(n+1)` permutation whose first cell is a zero capacity
+//! element and whose first output cell is the digest. The round constants and the MDS matrix are
+//! derived with the Grain LFSR procedure of the reference implementation, so the BN254 instance
+//! is compatible with circomlib's `Poseidon(n)` template. The partial round numbers meet or exceed
+//! the 128-bit security recommendation of the paper for both fields.
+//!
+//! Deriving the parameters takes far longer than hashing, so it's done by the build script, which
+//! generates static tables for every supported number of inputs.
+
+// Also compiled into the build script, and only used by the tests here.
+#[cfg_attr(not(test), allow(dead_code))]
+pub(crate) mod derivation;
+
+use anyhow::{ensure, Result};
+use ark_ff::PrimeField;
+pub use derivation::MAX_INPUTS;
+
+/// The S-box exponent.
+const ALPHA: u64 = 5;
+
+/// The parameters of a Poseidon permutation of a fixed width.
+pub struct PoseidonParameters<F: 'static> {
+    width: usize,
+    full_rounds: usize,
+    partial_rounds: usize,
+    /// `width` constants per round, for `full_rounds + partial_rounds` rounds.
+    pub(crate) round_constants: &'static [F],
+    /// A `width x width` Cauchy matrix, in row-major order.
+    pub(crate) mds: &'static [F],
+}
+
+impl<F: PrimeField> PoseidonParameters<F> {
+    /// The number of field elements the permutation operates on.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The number of full and partial rounds of the permutation.
+    pub fn num_rounds(&self) -> usize {
+        self.full_rounds + self.partial_rounds
+    }
+
+    /// Applies the Poseidon permutation to `state` in place.
+    pub fn permute(&self, state: &mut [F]) {
+        assert_eq!(state.len(), self.width);
+
+        let half_full_rounds = self.full_rounds / 2;
+        let mut mixed = vec![F::zero(); self.width];
+        for (round, constants) in self.round_constants.chunks_exact(self.width).enumerate() {
+            for (cell, c) in state.iter_mut().zip(constants) {
+                *cell += c;
+            }
+
+            let is_full_round =
+                round < half_full_rounds || round >= half_full_rounds + self.partial_rounds;
+            if is_full_round {
+                state.iter_mut().for_each(|cell| *cell = cell.pow([ALPHA]));
+            } else {
+                state[0] = state[0].pow([ALPHA]);
+            }
+
+            for (out, row) in mixed.iter_mut().zip(self.mds.chunks_exact(self.width)) {
+                *out = row.iter().zip(state.iter()).map(|(m, s)| *m * s).sum();
+            }
+            state.copy_from_slice(&mixed);
+        }
+    }
+
+    /// Hashes exactly `width - 1` field elements.
+    pub fn hash(&self, inputs: &[F]) -> F {
+        assert_eq!(inputs.len() + 1, self.width);
+
+        let mut state = Vec::with_capacity(self.width);
+        state.push(F::zero());
+        state.extend_from_slice(inputs);
+        self.permute(&mut state);
+        state[0]
+    }
+}
+
+/// A scalar field with a standardized set of Poseidon parameters.
+pub trait PoseidonField: PrimeField {
+    /// Returns the parameters for hashing `num_inputs` elements.
+    ///
+    /// Panics if `num_inputs` is not in `1..=MAX_INPUTS`.
+    fn parameters(num_inputs: usize) -> &'static PoseidonParameters<Self>;
+}
+
+// Defines `BLS12381_FR_PARAMETERS` and `BN254_FR_PARAMETERS`, indexed by the number of inputs
+// minus one.
+include!(concat!(env!("OUT_DIR"), "/poseidon_parameters.rs"));
+
+impl PoseidonField for ark_bls12_381::Fr {
+    fn parameters(num_inputs: usize) -> &'static PoseidonParameters<Self> {
+        &BLS12381_FR_PARAMETERS[num_inputs - 1]
+    }
+}
+
+impl PoseidonField for ark_bn254::Fr {
+    fn parameters(num_inputs: usize) -> &'static PoseidonParameters<Self> {
+        &BN254_FR_PARAMETERS[num_inputs - 1]
+    }
+}
+
+/// Returns the Poseidon hash of between 1 and `MAX_INPUTS` field elements.
+pub fn hash<F: PoseidonField>(inputs: &[F]) -> Result<F> {
+    ensure!(
+        (1..=MAX_INPUTS).contains(&inputs.len()),
+        "Poseidon takes between 1 and {} inputs, got {}",
+        MAX_INPUTS,
+        inputs.len()
+    );
+    Ok(F::parameters(inputs.len()).hash(inputs))
+}
@@ -13,4 +13,5 @@ mod hash_test;
 mod hkdf_test;
 mod multi_ed25519_test;
 mod noise_test;
+mod poseidon_test;
 mod secp256k1_ecdsa_test;
@@ -0,0 +1,66 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::poseidon::{
+    self,
+    derivation::{derive_parameters, FULL_ROUNDS, PARTIAL_ROUNDS},
+    PoseidonField, MAX_INPUTS,
+};
+
+/// The number of inputs of each known-answer test, hashing `1, 2, ..., n`.
+const NUM_INPUTS: [u64; 4] = [1, 2, 4, 16];
+
+fn check_known_answers<F: PoseidonField>(expected: [&str; 4]) {
+    for (n, expected) in NUM_INPUTS.into_iter().zip(expected) {
+        let inputs: Vec<F> = (1..=n).map(F::from).collect();
+        let expected = F::from_be_bytes_mod_order(&hex::decode(expected).unwrap());
+        assert_eq!(poseidon::hash(&inputs).unwrap(), expected);
+    }
+}
+
+#[test]
+fn test_bn254_matches_circomlib() {
+    // Outputs of circomlib's `Poseidon(n)` template.
+    check_known_answers::<ark_bn254::Fr>([
+        "29176100eaa962bdc1fe6c654d6a3c130e96a4d1168b33848b897dc502820133",
+        "115cc0f5e7d690413df64c6b9662e9cf2a3617f2743245519e19607a4417189a",
+        "299c867db6c1fdd79dcefa40e4510b9837e60ebb1ce0663dbaa525df65250465",
+        "16159a551cbb66108281a48099fff949ae08afd7f1f2ec06de2ffb96b919b765",
+    ]);
+}
+
+#[test]
+fn test_bls12381_known_answers() {
+    check_known_answers::<ark_bls12_381::Fr>([
+        "49a66f6b01dbc6440d1a5f920e027b94429916f2c821a920cf6203ad3de56cea",
+        "28ce19420fc246a05553ad1e8c98f5c9d67166be2c18e9e4cb4b4e317dd2a78a",
+        "2a918b9c9f9bd7bb509331c81e297b5707f6fc7393dcee1b13901a0b22202e18",
+        "5b95f4a8d0f1739ace74def29f790fe04ff52b65b688a8801ea9da77edcd603e",
+    ]);
+}
+
+#[test]
+fn test_number_of_inputs() {
+    assert!(poseidon::hash::<ark_bn254::Fr>(&[]).is_err());
+    let too_many = vec![ark_bn254::Fr::from(1u64); MAX_INPUTS + 1];
+    assert!(poseidon::hash(&too_many).is_err());
+
+    let params = ark_bls12_381::Fr::parameters(MAX_INPUTS);
+    assert_eq!(params.width(), MAX_INPUTS + 1);
+}
+
+fn check_generated_parameters<F: PoseidonField>() {
+    for num_inputs in 1..=MAX_INPUTS {
+        let params = F::parameters(num_inputs);
+        let (round_constants, mds) =
+            derive_parameters::<F>(num_inputs + 1, FULL_ROUNDS, PARTIAL_ROUNDS[num_inputs - 1]);
+        assert_eq!(params.round_constants, round_constants.as_slice());
+        assert_eq!(params.mds, mds.as_slice());
+    }
+}
+
+#[test]
+fn test_generated_parameters() {
+    check_generated_parameters::<ark_bls12_381::Fr>();
+    check_generated_parameters::<ark_bn254::Fr>();
+}
@@ -25,8 +25,10 @@ use aptos_config::config::{NodeConfig, PrunerConfig};
 use aptos_db::AptosDB;
 use aptos_executor::{
     block_executor::{BlockExecutor, TransactionBlockExecutor},
+    components::access_hints::AccessHints,
     metrics::{
-        APTOS_EXECUTOR_COMMIT_BLOCKS_SECONDS, APTOS_EXECUTOR_EXECUTE_BLOCK_SECONDS,
+        APTOS_EXECUTOR_ACCESS_HINTS_KEYS, APTOS_EXECUTOR_COMMIT_BLOCKS_SECONDS,
+        APTOS_EXECUTOR_EXECUTE_BLOCK_SECONDS,
         APTOS_EXECUTOR_LEDGER_UPDATE_SECONDS, APTOS_EXECUTOR_OTHER_TIMERS_SECONDS,
         APTOS_EXECUTOR_VM_EXECUTE_BLOCK_SECONDS, APTOS_PROCESSED_TXNS_OUTPUT_SIZE,
     },
@@ -222,6 +224,8 @@ pub fn run_benchmark<V>(
     let start_commit_total = APTOS_EXECUTOR_COMMIT_BLOCKS_SECONDS.get_sample_sum();
 
     let start_vm_time = APTOS_EXECUTOR_VM_EXECUTE_BLOCK_SECONDS.get_sample_sum();
+    let start_prefetch = prefetch_access_hints_seconds();
+    let start_access_hints_keys = access_hints_keys();
     if let Some(transaction_generator_creator) = transaction_generator_creator {
         generator.run_workload(
             block_size,
@@ -304,6 +308,26 @@ pub fn run_benchmark<V>(
         time_in_vm / time_in_execution,
         delta_v / time_in_vm
     );
+    if AccessHints::get().is_some() {
+        let time_in_prefetch = prefetch_access_hints_seconds() - start_prefetch;
+        let (prefetched, accessed, used) = access_hints_keys();
+        let (prefetched, accessed, used) = (
+            prefetched - start_access_hints_keys.0,
+            accessed - start_access_hints_keys.1,
+            used - start_access_hints_keys.2,
+        );
+        info!(
+            "Overall fraction of VM {:.3} in prefetching by access hints",
+            time_in_prefetch / time_in_vm
+        );
+        info!(
+            "Access hints prefetched {} keys, of which {:.3} were used, covering {:.3} of {} keys accessed",
+            prefetched,
+            used as f64 / (prefetched as f64).max(1.0),
+            used as f64 / (accessed as f64).max(1.0),
+            accessed
+        );
+    }
     for (prefix, top_level, other_label) in other_labels {
         let time_in_label = APTOS_EXECUTOR_OTHER_TIMERS_SECONDS
             .with_label_values(&[other_label])
@@ -504,6 +528,18 @@ fn add_accounts_impl<V>(
     );
 }
 
+fn prefetch_access_hints_seconds() -> f64 {
+    APTOS_EXECUTOR_OTHER_TIMERS_SECONDS
+        .with_label_values(&["prefetch_access_hints"])
+        .get_sample_sum()
+}
+
+/// Returns the number of keys prefetched, accessed, and both prefetched and accessed so far.
+fn access_hints_keys() -> (u64, u64, u64) {
+    let get = |kind| APTOS_EXECUTOR_ACCESS_HINTS_KEYS.with_label_values(&[kind]).get();
+    (get("prefetched"), get("accessed"), get("used"))
+}
+
 struct GasMeasurement {
     pub gas: f64,
 
@@ -11,11 +11,16 @@ use aptos_block_partitioner::{
     v2::config::PartitionerV2Config,
 };
 use aptos_config::config::{
-    EpochSnapshotPrunerConfig, LedgerPrunerConfig, PrunerConfig, StateMerklePrunerConfig,
+    EpochSnapshotPrunerConfig, LedgerArchiveConfig, LedgerPrunerConfig, ProcessShardingConfig,
+    PrunerConfig, StateMerklePrunerConfig,
+};
+use aptos_executor::{
+    block_executor::TransactionBlockExecutor, components::access_hints::AccessHints,
 };
-use aptos_executor::block_executor::TransactionBlockExecutor;
 use aptos_executor_benchmark::{native_executor::NativeExecutor, pipeline::PipelineConfig};
-use aptos_executor_service::remote_executor_client;
+use aptos_executor_service::{
+    remote_cross_shard_client::DEFAULT_CROSS_SHARD_CHANNEL_CAPACITY, remote_executor_client,
+};
 use aptos_experimental_ptx_executor::PtxBlockExecutor;
 #[cfg(target_os = "linux")]
 use aptos_experimental_runtimes::thread_manager::{ThreadConfigStrategy, ThreadManagerBuilder};
@@ -90,6 +95,7 @@ impl PrunerOpt {
                 batch_size: self.ledger_pruning_batch_size,
                 user_pruning_window_offset: 0,
             },
+            ledger_archive_config: LedgerArchiveConfig::default(),
         }
     }
 }
@@ -141,6 +147,12 @@ struct ShardingOpt {
     remote_executor_addresses: Option<Vec<SocketAddr>>,
     #[clap(long)]
     coordinator_address: Option<SocketAddr>,
+    /// Gives an option to launch 'num_executor_shards' executor service processes on this machine
+    /// from the given 'aptos-executor-service' binary, and to execute the shards on them.
+    #[clap(long, conflicts_with = "remote_executor_addresses")]
+    executor_service_binary: Option<PathBuf>,
+    #[clap(long, default_value_t = DEFAULT_CROSS_SHARD_CHANNEL_CAPACITY)]
+    cross_shard_channel_capacity: usize,
     #[clap(long, default_value = "4")]
     max_partitioning_rounds: usize,
     #[clap(long, default_value = "0.90")]
@@ -256,6 +268,15 @@ struct Opt {
 
     #[clap(flatten)]
     profiler_opt: ProfilerOpt,
+
+    /// Prefetch the state each block is expected to access before executing it, based on the
+    /// state accessed by earlier transactions calling the same entry functions
+    #[clap(long)]
+    prefetch_access_hints: bool,
+
+    /// File to load the access hints from and persist them to
+    #[clap(long, requires = "prefetch_access_hints")]
+    access_hints_path: Option<PathBuf>,
 }
 
 impl Opt {
@@ -479,10 +500,35 @@ fn main() {
         execution_threads_per_shard = execution_threads;
     }
 
+    if let Some(executor_service_binary) = &opt.pipeline_opt.sharding_opt.executor_service_binary {
+        assert!(
+            execution_shards > 0,
+            "Number of execution shards must be set to launch executor service processes."
+        );
+        remote_executor_client::set_process_sharding_config(ProcessShardingConfig {
+            executor_service_binary: executor_service_binary.clone(),
+            num_shards: execution_shards,
+            num_threads_per_shard: execution_threads_per_shard,
+            coordinator_address: opt
+                .pipeline_opt
+                .sharding_opt
+                .coordinator_address
+                .unwrap_or_else(remote_executor_client::get_coordinator_address),
+            cross_shard_channel_capacity: opt
+                .pipeline_opt
+                .sharding_opt
+                .cross_shard_channel_capacity,
+            ..Default::default()
+        });
+    }
+
     AptosVM::set_num_shards_once(execution_shards);
     AptosVM::set_concurrency_level_once(execution_threads_per_shard);
     NativeExecutor::set_concurrency_level_once(execution_threads_per_shard);
     AptosVM::set_processed_transactions_detailed_counters();
+    if opt.prefetch_access_hints {
+        AccessHints::enable_once(opt.access_hints_path.clone());
+    }
 
     let config = ProfilerConfig::new_with_defaults();
     let handler = ProfilerHandler::new(config);
@@ -13,11 +13,12 @@ pub mod local_executor_helper;
 mod metrics;
 pub mod process_executor_service;
 mod remote_cordinator_client;
-mod remote_cross_shard_client;
+pub mod remote_cross_shard_client;
 pub mod remote_executor_client;
 pub mod remote_executor_service;
 mod remote_state_view;
 mod remote_state_view_service;
+pub mod shard_processes;
 #[cfg(test)]
 mod test_utils;
 #[cfg(test)]
@@ -27,12 +28,14 @@ mod thread_executor_service;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct RemoteExecutionResult {
+    /// The block the result is for, see [`ExecuteBlockCommand::block_id`].
+    pub block_id: u64,
     pub inner: Result<Vec<Vec<TransactionOutput>>, VMStatus>,
 }
 
 impl RemoteExecutionResult {
-    pub fn new(inner: Result<Vec<Vec<TransactionOutput>>, VMStatus>) -> Self {
-        Self { inner }
+    pub fn new(block_id: u64, inner: Result<Vec<Vec<TransactionOutput>>, VMStatus>) -> Self {
+        Self { block_id, inner }
     }
 }
 
@@ -43,6 +46,9 @@ pub enum RemoteExecutionRequest {
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct ExecuteBlockCommand {
+    /// Identifies the block among the blocks sent by the coordinator, so that the results of an
+    /// abandoned block are not mistaken for the results of a later one.
+    pub(crate) block_id: u64,
     pub(crate) sub_blocks: SubBlocksForShard<AnalyzedTransaction>,
     pub(crate) concurrency_level: usize,
     pub(crate) maybe_block_gas_limit: Option<u64>,
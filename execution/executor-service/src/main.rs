@@ -1,7 +1,10 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use aptos_executor_service::process_executor_service::ProcessExecutorService;
+use aptos_executor_service::{
+    process_executor_service::ProcessExecutorService,
+    remote_cross_shard_client::DEFAULT_CROSS_SHARD_CHANNEL_CAPACITY,
+};
 use aptos_logger::info;
 use clap::Parser;
 use std::net::SocketAddr;
@@ -22,6 +25,9 @@ struct Args {
 
     #[clap(long)]
     pub coordinator_address: SocketAddr,
+
+    #[clap(long, default_value_t = DEFAULT_CROSS_SHARD_CHANNEL_CAPACITY)]
+    pub cross_shard_channel_capacity: usize,
 }
 
 fn main() {
@@ -40,6 +46,7 @@ fn main() {
         args.num_executor_threads,
         args.coordinator_address,
         args.remote_executor_addresses,
+        args.cross_shard_channel_capacity,
     );
 
     rx.recv()
@@ -2,8 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use aptos_metrics_core::{
-    exponential_buckets, register_histogram_vec, register_int_counter_vec, HistogramVec,
-    IntCounterVec,
+    exponential_buckets, register_histogram_vec, register_int_counter, register_int_counter_vec,
+    HistogramVec, IntCounter, IntCounterVec,
 };
 use once_cell::sync::Lazy;
 
@@ -44,3 +44,13 @@ pub static REMOTE_EXECUTOR_REMOTE_KV_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+pub static REMOTE_EXECUTOR_LOCAL_FALLBACK_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        // metric name
+        "remote_executor_local_fallback_count",
+        // metric description
+        "The number of blocks re-executed locally by the coordinator because the remote shards failed"
+    )
+    .unwrap()
+});
@@ -19,6 +19,7 @@ impl ProcessExecutorService {
         num_threads: usize,
         coordinator_address: SocketAddr,
         remote_shard_addresses: Vec<SocketAddr>,
+        cross_shard_channel_capacity: usize,
     ) -> Self {
         let self_address = remote_shard_addresses[shard_id];
         info!(
@@ -38,6 +39,7 @@ impl ProcessExecutorService {
             self_address,
             coordinator_address,
             remote_shard_addresses,
+            cross_shard_channel_capacity,
         );
         executor_service.start();
         Self { executor_service }
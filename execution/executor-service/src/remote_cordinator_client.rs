@@ -14,13 +14,21 @@ use aptos_vm::sharded_block_executor::{
 };
 use crossbeam_channel::{Receiver, Sender};
 use rayon::prelude::*;
-use std::{net::SocketAddr, sync::Arc};
+use std::{
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
 
 pub struct RemoteCoordinatorClient {
     state_view_client: Arc<RemoteStateViewClient>,
     command_rx: Receiver<Message>,
     result_tx: Sender<Message>,
     shard_id: ShardId,
+    // The block being executed, which the result is tagged with.
+    block_id: AtomicU64,
 }
 
 impl RemoteCoordinatorClient {
@@ -43,6 +51,7 @@ impl RemoteCoordinatorClient {
             command_rx,
             result_tx,
             shard_id,
+            block_id: AtomicU64::new(0),
         }
     }
 
@@ -98,6 +107,7 @@ impl CoordinatorClient<RemoteStateViewClient> for RemoteCoordinatorClient {
                         self.state_view_client.init_for_block(state_keys);
                         drop(init_prefetch_timer);
 
+                        self.block_id.store(command.block_id, Ordering::Relaxed);
                         let (sub_blocks, concurrency, gas_limit) = command.into();
                         ExecutorShardCommand::ExecuteSubBlocks(
                             self.state_view_client.clone(),
@@ -113,7 +123,8 @@ impl CoordinatorClient<RemoteStateViewClient> for RemoteCoordinatorClient {
     }
 
     fn send_execution_result(&self, result: Result<Vec<Vec<TransactionOutput>>, VMStatus>) {
-        let remote_execution_result = RemoteExecutionResult::new(result);
+        let remote_execution_result =
+            RemoteExecutionResult::new(self.block_id.load(Ordering::Relaxed), result);
         let output_message = bcs::to_bytes(&remote_execution_result).unwrap();
         self.result_tx.send(Message::new(output_message)).unwrap();
     }
@@ -11,15 +11,25 @@ use std::{
     sync::{Arc, Mutex},
 };
 
+/// The default maximum number of cross-shard messages that are queued for sending per destination
+/// shard and round.
+pub const DEFAULT_CROSS_SHARD_CHANNEL_CAPACITY: usize = 1024;
+
 pub struct RemoteCrossShardClient {
-    // The senders of cross-shard messages to other shards per round.
+    // The senders of cross-shard messages to other shards per round. The senders are bounded, so
+    // a shard that produces cross-shard messages faster than they can be sent over the network
+    // blocks instead of buffering them without limit.
     message_txs: Arc<Vec<Vec<Mutex<Sender<Message>>>>>,
     // The receivers of cross shard messages from other shards per round.
     message_rxs: Arc<Vec<Mutex<Receiver<Message>>>>,
 }
 
 impl RemoteCrossShardClient {
-    pub fn new(controller: &mut NetworkController, shard_addresses: Vec<SocketAddr>) -> Self {
+    pub fn new(
+        controller: &mut NetworkController,
+        shard_addresses: Vec<SocketAddr>,
+        channel_capacity: usize,
+    ) -> Self {
         let mut message_txs = vec![];
         let mut message_rxs = vec![];
         // Create outbound channels for each shard per round.
@@ -27,7 +37,11 @@ impl RemoteCrossShardClient {
             let mut txs = vec![];
             for round in 0..MAX_ALLOWED_PARTITIONING_ROUNDS {
                 let message_type = format!("cross_shard_{}", round);
-                let tx = controller.create_outbound_channel(*remote_address, message_type);
+                let tx = controller.create_bounded_outbound_channel(
+                    *remote_address,
+                    message_type,
+                    channel_capacity,
+                );
                 txs.push(Mutex::new(tx));
             }
             message_txs.push(txs);
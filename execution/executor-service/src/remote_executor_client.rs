@@ -2,33 +2,43 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
-    remote_state_view_service::RemoteStateViewService, ExecuteBlockCommand, RemoteExecutionRequest,
-    RemoteExecutionResult,
+    metrics::REMOTE_EXECUTOR_LOCAL_FALLBACK_COUNT,
+    remote_state_view_service::RemoteStateViewService, shard_processes::ShardProcesses,
+    ExecuteBlockCommand, RemoteExecutionRequest, RemoteExecutionResult,
 };
-use aptos_logger::{info, trace};
+use anyhow::{bail, ensure, Context};
+use aptos_config::{config::ProcessShardingConfig, utils};
+use aptos_logger::{error, info, trace, warn};
 use aptos_secure_net::network_controller::{Message, NetworkController};
 use aptos_state_view::StateView;
 use aptos_storage_interface::cached_state_view::CachedStateView;
-use aptos_types::{
-    block_executor::partitioner::PartitionedTransactions, transaction::TransactionOutput,
-    vm_status::VMStatus,
-};
+use aptos_types::{block_executor::partitioner::PartitionedTransactions, vm_status::VMStatus};
 use aptos_vm::sharded_block_executor::{
     executor_client::{ExecutorClient, ShardedExecutionOutput},
+    local_executor_shard::{LocalExecutorClient, LocalExecutorService},
     ShardedBlockExecutor,
 };
-use crossbeam_channel::{Receiver, Sender};
+use crossbeam_channel::{Receiver, RecvTimeoutError, Sender};
 use once_cell::sync::{Lazy, OnceCell};
 use std::{
     net::{IpAddr, Ipv4Addr, SocketAddr},
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
     thread,
+    time::{Duration, Instant},
 };
 
 pub static COORDINATOR_PORT: u16 = 52200;
 
+const DEFAULT_SHARD_RESULT_TIMEOUT: Duration = Duration::from_secs(60);
+// How often the liveness of launched shard processes is checked while waiting for results.
+const SHARD_LIVENESS_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
 static REMOTE_ADDRESSES: OnceCell<Vec<SocketAddr>> = OnceCell::new();
 static COORDINATOR_ADDRESS: OnceCell<SocketAddr> = OnceCell::new();
+static PROCESS_SHARDING_CONFIG: OnceCell<ProcessShardingConfig> = OnceCell::new();
 
 pub fn set_remote_addresses(addresses: Vec<SocketAddr>) {
     REMOTE_ADDRESSES.set(addresses).ok();
@@ -52,6 +62,17 @@ pub fn get_coordinator_address() -> SocketAddr {
     }
 }
 
+/// Selects executing sharded blocks in executor shard processes that are launched on this machine,
+/// listening on available local ports.
+pub fn set_process_sharding_config(config: ProcessShardingConfig) {
+    let shard_addresses = (0..config.num_shards)
+        .map(|_| SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), utils::get_available_port()))
+        .collect();
+    set_remote_addresses(shard_addresses);
+    set_coordinator_address(config.coordinator_address);
+    PROCESS_SHARDING_CONFIG.set(config).ok();
+}
+
 pub static REMOTE_SHARDED_BLOCK_EXECUTOR: Lazy<
     Arc<
         aptos_infallible::Mutex<
@@ -60,13 +81,19 @@ pub static REMOTE_SHARDED_BLOCK_EXECUTOR: Lazy<
     >,
 > = Lazy::new(|| {
     info!("REMOTE_SHARDED_BLOCK_EXECUTOR created");
-    Arc::new(aptos_infallible::Mutex::new(
-        RemoteExecutorClient::create_remote_sharded_block_executor(
+    let sharded_block_executor = match PROCESS_SHARDING_CONFIG.get() {
+        Some(config) => RemoteExecutorClient::create_process_sharded_block_executor(
+            config.clone(),
+            get_remote_addresses(),
+        )
+        .expect("Failed to launch executor shard processes"),
+        None => RemoteExecutorClient::create_remote_sharded_block_executor(
             get_coordinator_address(),
             get_remote_addresses(),
             None,
         ),
-    ))
+    };
+    Arc::new(aptos_infallible::Mutex::new(sharded_block_executor))
 });
 
 #[allow(dead_code)]
@@ -82,6 +109,17 @@ pub struct RemoteExecutorClient<S: StateView + Sync + Send + 'static> {
     result_rxs: Vec<Receiver<Message>>,
     // Thread pool used to pre-fetch the state values for the block in parallel and create an in-memory state view.
     thread_pool: Arc<rayon::ThreadPool>,
+    // The executor shard processes, if they were launched by this client.
+    shard_processes: Option<Mutex<ShardProcesses>>,
+    // Time to wait for the results of all shards before the block is re-executed locally.
+    result_timeout: Duration,
+    // Executes blocks in this process if the remote shards fail. Created on first use.
+    local_fallback: OnceCell<LocalExecutorClient<S>>,
+    // Set if the remote shards failed and cannot be restarted, in which case all subsequent blocks
+    // are executed locally.
+    remote_shards_failed: AtomicBool,
+    // The id of the next block sent to the shards, which their results are tagged with.
+    next_block_id: AtomicU64,
 
     phantom: std::marker::PhantomData<S>,
     _join_handle: Option<thread::JoinHandle<()>>,
@@ -138,10 +176,27 @@ impl<S: StateView + Sync + Send + 'static> RemoteExecutorClient<S> {
             command_txs: Arc::new(command_txs),
             result_rxs,
             thread_pool,
+            shard_processes: None,
+            result_timeout: DEFAULT_SHARD_RESULT_TIMEOUT,
+            local_fallback: OnceCell::new(),
+            remote_shards_failed: AtomicBool::new(false),
+            next_block_id: AtomicU64::new(0),
             phantom: std::marker::PhantomData,
         }
     }
 
+    /// Makes the client responsible for the given shard processes: their exit is detected while
+    /// waiting for results, and they are restarted after a failure.
+    pub fn with_shard_processes(mut self, shard_processes: ShardProcesses) -> Self {
+        self.shard_processes = Some(Mutex::new(shard_processes));
+        self
+    }
+
+    pub fn with_result_timeout(mut self, result_timeout: Duration) -> Self {
+        self.result_timeout = result_timeout;
+        self
+    }
+
     pub fn create_remote_sharded_block_executor(
         coordinator_address: SocketAddr,
         remote_shard_addresses: Vec<SocketAddr>,
@@ -158,16 +213,121 @@ impl<S: StateView + Sync + Send + 'static> RemoteExecutorClient<S> {
         ))
     }
 
-    fn get_output_from_shards(&self) -> Result<Vec<Vec<Vec<TransactionOutput>>>, VMStatus> {
+    /// Launches the executor shard processes on this machine and creates a sharded block executor
+    /// that executes blocks on them.
+    pub fn create_process_sharded_block_executor(
+        config: ProcessShardingConfig,
+        remote_shard_addresses: Vec<SocketAddr>,
+    ) -> anyhow::Result<ShardedBlockExecutor<S, RemoteExecutorClient<S>>> {
+        let shard_processes = ShardProcesses::launch(config.clone(), remote_shard_addresses)?;
+        let executor_client = RemoteExecutorClient::new(
+            shard_processes.shard_addresses().to_vec(),
+            NetworkController::new(
+                "remote-executor-coordinator".to_string(),
+                config.coordinator_address,
+                5000,
+            ),
+            None,
+        )
+        .with_shard_processes(shard_processes)
+        .with_result_timeout(Duration::from_millis(config.shard_result_timeout_ms));
+        Ok(ShardedBlockExecutor::new(executor_client))
+    }
+
+    fn get_output_from_shards(&self, block_id: u64) -> anyhow::Result<Vec<RemoteExecutionResult>> {
         trace!("RemoteExecutorClient Waiting for results");
+        let deadline = Instant::now() + self.result_timeout;
         let mut results = vec![];
-        for rx in self.result_rxs.iter() {
-            let received_bytes = rx.recv().unwrap().to_bytes();
-            let result: RemoteExecutionResult = bcs::from_bytes(&received_bytes).unwrap();
-            results.push(result.inner?);
+        for (shard_id, rx) in self.result_rxs.iter().enumerate() {
+            let result = loop {
+                let received_bytes = match rx.recv_timeout(SHARD_LIVENESS_CHECK_INTERVAL) {
+                    Ok(message) => message.to_bytes(),
+                    Err(RecvTimeoutError::Timeout) => {
+                        self.ensure_shard_processes_alive()?;
+                        ensure!(
+                            Instant::now() < deadline,
+                            "Timed out waiting for the result of shard {}",
+                            shard_id
+                        );
+                        continue;
+                    },
+                    Err(RecvTimeoutError::Disconnected) => {
+                        bail!("Result channel of shard {} disconnected", shard_id)
+                    },
+                };
+                let result: RemoteExecutionResult =
+                    bcs::from_bytes(&received_bytes).with_context(|| {
+                        format!("Failed to deserialize the result of shard {}", shard_id)
+                    })?;
+                if result.block_id == block_id {
+                    break result;
+                }
+                // Results of a block that was abandoned because of a shard failure can arrive
+                // later, and must not be mistaken for the results of this block.
+                warn!(
+                    "Discarding a stale execution result of shard {} for block {}",
+                    shard_id, result.block_id
+                );
+            };
+            results.push(result);
         }
         Ok(results)
     }
+
+    fn ensure_shard_processes_alive(&self) -> anyhow::Result<()> {
+        if let Some(shard_processes) = &self.shard_processes {
+            let exited_shards = shard_processes.lock().unwrap().exited_shards();
+            ensure!(
+                exited_shards.is_empty(),
+                "Executor shards {:?} exited",
+                exited_shards
+            );
+        }
+        Ok(())
+    }
+
+    fn recover_from_shard_failure(&self) {
+        match &self.shard_processes {
+            Some(shard_processes) => {
+                // The remaining shards might be stuck waiting for cross-shard messages of the
+                // failed one, so all of them are restarted.
+                if let Err(e) = shard_processes.lock().unwrap().restart() {
+                    error!(
+                        "Failed to restart executor shards, executing subsequent blocks locally: {:?}",
+                        e
+                    );
+                    self.remote_shards_failed.store(true, Ordering::Relaxed);
+                }
+            },
+            None => {
+                error!(
+                    "Executor shards not launched by the coordinator cannot be restarted, \
+                     executing subsequent blocks locally"
+                );
+                self.remote_shards_failed.store(true, Ordering::Relaxed);
+            },
+        }
+    }
+
+    fn execute_block_locally(
+        &self,
+        state_view: Arc<S>,
+        transactions: PartitionedTransactions,
+        concurrency_level_per_shard: usize,
+        maybe_block_gas_limit: Option<u64>,
+    ) -> Result<ShardedExecutionOutput, VMStatus> {
+        REMOTE_EXECUTOR_LOCAL_FALLBACK_COUNT.inc();
+        self.local_fallback
+            .get_or_init(|| {
+                LocalExecutorService::setup_local_executor_shards(self.num_shards(), None)
+            })
+            .execute_block(
+                state_view,
+                transactions,
+                concurrency_level_per_shard,
+                maybe_block_gas_limit,
+            )
+    }
 }
 
 impl<S: StateView + Sync + Send + 'static> ExecutorClient<S> for RemoteExecutorClient<S> {
@@ -182,31 +342,71 @@ impl<S: StateView + Sync + Send + 'static> ExecutorClient<S> for RemoteExecutorC
         concurrency_level_per_shard: usize,
         maybe_block_gas_limit: Option<u64>,
     ) -> Result<ShardedExecutionOutput, VMStatus> {
+        if self.remote_shards_failed.load(Ordering::Relaxed) {
+            return self.execute_block_locally(
+                state_view,
+                transactions,
+                concurrency_level_per_shard,
+                maybe_block_gas_limit,
+            );
+        }
+
         trace!("RemoteExecutorClient Sending block to shards");
-        self.state_view_service.set_state_view(state_view);
+        let block_id = self.next_block_id.fetch_add(1, Ordering::Relaxed);
+        self.state_view_service.set_state_view(state_view.clone());
         let (sub_blocks, global_txns) = transactions.into();
         if !global_txns.is_empty() {
             panic!("Global transactions are not supported yet");
         }
-        for (shard_id, sub_blocks) in sub_blocks.into_iter().enumerate() {
-            let senders = self.command_txs.clone();
-            let execution_request = RemoteExecutionRequest::ExecuteBlock(ExecuteBlockCommand {
-                sub_blocks,
-                concurrency_level: concurrency_level_per_shard,
-                maybe_block_gas_limit,
-            });
+        // The sub-blocks are moved back out of the requests once they are serialized, and kept to
+        // re-execute the block locally if a shard fails.
+        let sub_blocks: Vec<_> = sub_blocks
+            .into_iter()
+            .enumerate()
+            .map(|(shard_id, sub_blocks)| {
+                let execution_request = RemoteExecutionRequest::ExecuteBlock(ExecuteBlockCommand {
+                    block_id,
+                    sub_blocks,
+                    concurrency_level: concurrency_level_per_shard,
+                    maybe_block_gas_limit,
+                });
 
-            senders[shard_id]
-                .lock()
-                .unwrap()
-                .send(Message::new(bcs::to_bytes(&execution_request).unwrap()))
-                .unwrap();
-        }
+                self.command_txs[shard_id]
+                    .lock()
+                    .unwrap()
+                    .send(Message::new(bcs::to_bytes(&execution_request).unwrap()))
+                    .unwrap();
 
-        let execution_results = self.get_output_from_shards()?;
+                let RemoteExecutionRequest::ExecuteBlock(command) = execution_request;
+                command.sub_blocks
+            })
+            .collect();
 
+        let shard_results = self.get_output_from_shards(block_id);
         self.state_view_service.drop_state_view();
-        Ok(ShardedExecutionOutput::new(execution_results, vec![]))
+
+        match shard_results {
+            Ok(shard_results) => {
+                let execution_results = shard_results
+                    .into_iter()
+                    .map(|result| result.inner)
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(ShardedExecutionOutput::new(execution_results, vec![]))
+            },
+            Err(e) => {
+                error!(
+                    "Remote sharded execution failed, re-executing the block locally: {:?}",
+                    e
+                );
+                self.recover_from_shard_failure();
+                self.execute_block_locally(
+                    state_view,
+                    PartitionedTransactions::new(sub_blocks, global_txns),
+                    concurrency_level_per_shard,
+                    maybe_block_gas_limit,
+                )
+            },
+        }
     }
 
     fn shutdown(&mut self) {
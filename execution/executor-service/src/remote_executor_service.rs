@@ -26,6 +26,7 @@ impl ExecutorService {
         self_address: SocketAddr,
         coordinator_address: SocketAddr,
         remote_shard_addresses: Vec<SocketAddr>,
+        cross_shard_channel_capacity: usize,
     ) -> Self {
         let service_name = format!("executor_service-{}", shard_id);
         let mut controller = NetworkController::new(service_name, self_address, 5000);
@@ -37,6 +38,7 @@ impl ExecutorService {
         let cross_shard_client = Arc::new(RemoteCrossShardClient::new(
             &mut controller,
             remote_shard_addresses,
+            cross_shard_channel_capacity,
         ));
 
         let executor_service = Arc::new(ShardedExecutorService::new(
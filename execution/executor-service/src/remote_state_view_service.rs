@@ -10,7 +10,7 @@ use std::{
 
 extern crate itertools;
 use crate::metrics::REMOTE_EXECUTOR_TIMER;
-use aptos_logger::trace;
+use aptos_logger::{trace, warn};
 use aptos_state_view::{StateView, TStateView};
 use itertools::Itertools;
 
@@ -92,19 +92,27 @@ impl<S: StateView + Sync + Send + 'static> RemoteStateViewService<S> {
             shard_id,
             state_keys.len()
         );
+        let state_view_lock = state_view.read().unwrap();
+        let state_view = match state_view_lock.as_ref() {
+            Some(state_view) => state_view,
+            None => {
+                // The block was abandoned (e.g. re-executed locally after a shard failure) while
+                // the request was in flight.
+                warn!(
+                    "remote state view service - dropping request for shard {} without a block in execution",
+                    shard_id
+                );
+                return;
+            },
+        };
         let resp = state_keys
             .into_iter()
             .map(|state_key| {
-                let state_value = state_view
-                    .read()
-                    .unwrap()
-                    .as_ref()
-                    .unwrap()
-                    .get_state_value(&state_key)
-                    .unwrap();
+                let state_value = state_view.get_state_value(&state_key).unwrap();
                 (state_key, state_value)
             })
             .collect_vec();
+        drop(state_view_lock);
         let len = resp.len();
         let resp = RemoteKVResponse::new(resp);
         let bcs_ser_timer = REMOTE_EXECUTOR_TIMER
@@ -0,0 +1,127 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{Context, Result};
+use aptos_config::config::ProcessShardingConfig;
+use aptos_logger::{error, info, warn};
+use aptos_types::block_executor::partitioner::ShardId;
+use std::{
+    net::SocketAddr,
+    process::{Child, Command},
+};
+
+/// The executor shard processes launched by a coordinator on the local machine. Each process runs
+/// the `aptos-executor-service` binary for one shard. The processes are killed when this is
+/// dropped.
+pub struct ShardProcesses {
+    config: ProcessShardingConfig,
+    shard_addresses: Vec<SocketAddr>,
+    children: Vec<Child>,
+}
+
+impl ShardProcesses {
+    pub fn launch(config: ProcessShardingConfig, shard_addresses: Vec<SocketAddr>) -> Result<Self> {
+        assert_eq!(
+            config.num_shards,
+            shard_addresses.len(),
+            "Number of shards ({}) must be equal to the number of shard addresses ({}).",
+            config.num_shards,
+            shard_addresses.len()
+        );
+        let mut shard_processes = Self {
+            config,
+            shard_addresses,
+            children: vec![],
+        };
+        shard_processes.spawn_all()?;
+        Ok(shard_processes)
+    }
+
+    pub fn shard_addresses(&self) -> &[SocketAddr] {
+        &self.shard_addresses
+    }
+
+    /// Returns the shards whose process has exited.
+    pub fn exited_shards(&mut self) -> Vec<ShardId> {
+        self.children
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(shard_id, child)| match child.try_wait() {
+                Ok(None) => None,
+                Ok(Some(status)) => {
+                    warn!("Executor shard {} exited with {}", shard_id, status);
+                    Some(shard_id)
+                },
+                Err(e) => {
+                    warn!("Failed to poll executor shard {}: {}", shard_id, e);
+                    Some(shard_id)
+                },
+            })
+            .collect()
+    }
+
+    /// Kills all shard processes and launches them again, e.g. after a shard failed and the
+    /// remaining shards might be stuck waiting for its cross-shard messages.
+    pub fn restart(&mut self) -> Result<()> {
+        info!("Restarting {} executor shards", self.children.len());
+        self.kill_all();
+        self.spawn_all()
+    }
+
+    fn spawn_all(&mut self) -> Result<()> {
+        self.children = (0..self.config.num_shards)
+            .map(|shard_id| self.spawn(shard_id))
+            .collect::<Result<_>>()?;
+        Ok(())
+    }
+
+    fn spawn(&self, shard_id: ShardId) -> Result<Child> {
+        let child = Command::new(&self.config.executor_service_binary)
+            .arg("--shard-id")
+            .arg(shard_id.to_string())
+            .arg("--num-shards")
+            .arg(self.config.num_shards.to_string())
+            .arg("--num-executor-threads")
+            .arg(self.config.num_threads_per_shard.to_string())
+            .arg("--coordinator-address")
+            .arg(self.config.coordinator_address.to_string())
+            .arg("--cross-shard-channel-capacity")
+            .arg(self.config.cross_shard_channel_capacity.to_string())
+            .arg("--remote-executor-addresses")
+            .args(
+                self.shard_addresses
+                    .iter()
+                    .map(|address| address.to_string()),
+            )
+            .spawn()
+            .with_context(|| {
+                format!(
+                    "Failed to launch executor shard {} from {:?}",
+                    shard_id, self.config.executor_service_binary
+                )
+            })?;
+        info!(
+            "Launched executor shard {} (pid {}) on {}",
+            shard_id,
+            child.id(),
+            self.shard_addresses[shard_id]
+        );
+        Ok(child)
+    }
+
+    fn kill_all(&mut self) {
+        for (shard_id, mut child) in self.children.drain(..).enumerate() {
+            // Killing a process that already exited fails, which is fine.
+            let _ = child.kill();
+            if let Err(e) = child.wait() {
+                error!("Failed to wait for executor shard {}: {}", shard_id, e);
+            }
+        }
+    }
+}
+
+impl Drop for ShardProcesses {
+    fn drop(&mut self) {
+        self.kill_all();
+    }
+}
@@ -92,3 +92,26 @@ fn test_sharded_block_executor_with_conflict() {
         executor_service.shutdown();
     });
 }
+
+#[test]
+fn test_sharded_block_executor_recovers_from_shard_failure() {
+    use std::{thread, time::Duration};
+
+    let num_shards = 4;
+    let (executor_client, mut executor_services) =
+        create_thread_remote_executor_shards(num_shards, Some(2));
+    let sharded_block_executor =
+        ShardedBlockExecutor::new(executor_client.with_result_timeout(Duration::from_secs(2)));
+
+    // wait for the servers to be ready before sending messages
+    thread::sleep(std::time::Duration::from_millis(10));
+
+    // The failed shard never returns its result, so the block is re-executed locally, which must
+    // produce the same output as unsharded execution.
+    executor_services[0].shutdown();
+    test_utils::test_sharded_block_executor_no_conflict(sharded_block_executor);
+
+    executor_services.iter_mut().for_each(|executor_service| {
+        executor_service.shutdown();
+    });
+}
@@ -1,6 +1,9 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
-use crate::remote_executor_service::ExecutorService;
+use crate::{
+    remote_cross_shard_client::DEFAULT_CROSS_SHARD_CHANNEL_CAPACITY,
+    remote_executor_service::ExecutorService,
+};
 use aptos_types::block_executor::partitioner::ShardId;
 use std::net::SocketAddr;
 
@@ -27,6 +30,7 @@ impl ThreadExecutorService {
             self_address,
             coordinator_address,
             remote_shard_addresses,
+            DEFAULT_CROSS_SHARD_CHANNEL_CAPACITY,
         );
         executor_service.start();
         Self {
@@ -0,0 +1,354 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Hints of the state accessed by the transactions calling each entry function. They are learnt
+//! from the access lists of executed transactions and used to prefetch the state a block is
+//! likely to access before it is executed, so that the VM does not wait on cold reads.
+//!
+//! Keys under the account of the sender are remembered relative to the sender, so that what is
+//! learnt from one sender applies to all senders calling the same entry function.
+//!
+//! The memory used is bounded: only the most recently called entry functions are remembered, and
+//! an entry function that accesses too many distinct keys is not hinted at all, as what it
+//! accesses depends on more than its sender.
+
+use crate::metrics::APTOS_EXECUTOR_ACCESS_HINTS_KEYS;
+use anyhow::Result;
+use aptos_infallible::Mutex;
+use aptos_logger::{info, warn};
+use aptos_storage_interface::cached_state_view::CachedStateView;
+use aptos_types::{
+    access_path::AccessPath,
+    account_address::AccountAddress,
+    state_store::state_key::{StateKey, StateKeyInner},
+    transaction::{
+        signature_verified_transaction::SignatureVerifiedTransaction, Transaction,
+        TransactionOutput, TransactionPayload, TransactionStatus,
+    },
+};
+use aptos_vm::AptosVM;
+use lru::LruCache;
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeSet, HashSet},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// The least recently called entry functions are forgotten beyond this many.
+const MAX_ENTRY_FUNCTIONS: usize = 4096;
+/// An entry function that has accessed more keys than this is not hinted.
+const MAX_KEYS_PER_ENTRY_FUNCTION: usize = 256;
+/// The hints are persisted after this many blocks have been learnt from.
+const PERSIST_INTERVAL_BLOCKS: usize = 100;
+
+static ACCESS_HINTS: OnceCell<AccessHints> = OnceCell::new();
+
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd, Serialize, Deserialize)]
+enum HintKey {
+    /// The path of a key under the account of the sender.
+    Sender(Vec<u8>),
+    /// Any other key.
+    Absolute(StateKey),
+}
+
+impl HintKey {
+    fn new(key: &StateKey, sender: AccountAddress) -> Self {
+        match key.inner() {
+            StateKeyInner::AccessPath(access_path) if access_path.address == sender => {
+                Self::Sender(access_path.path.clone())
+            },
+            _ => Self::Absolute(key.clone()),
+        }
+    }
+
+    fn resolve(&self, sender: AccountAddress) -> StateKey {
+        match self {
+            Self::Sender(path) => StateKey::access_path(AccessPath::new(sender, path.clone())),
+            Self::Absolute(key) => key.clone(),
+        }
+    }
+}
+
+/// The keys learnt for an entry function, or `None` if it has accessed too many keys to be
+/// hinted.
+type EntryFunctionHints = Option<BTreeSet<HintKey>>;
+
+/// Returns the entry function called by a user transaction, e.g. `0x1::coin::transfer`, and the
+/// sender of the transaction.
+fn entry_function_and_sender(
+    txn: &SignatureVerifiedTransaction,
+) -> Option<(String, AccountAddress)> {
+    if !txn.is_valid() {
+        return None;
+    }
+    match txn.expect_valid() {
+        Transaction::UserTransaction(signed_txn) => match signed_txn.payload() {
+            TransactionPayload::EntryFunction(entry_function) => Some((
+                format!(
+                    "{}::{}",
+                    entry_function.module().short_str_lossless(),
+                    entry_function.function()
+                ),
+                signed_txn.sender(),
+            )),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The state keys accessed by the transactions calling each entry function.
+pub struct AccessHints {
+    hints: Mutex<LruCache<String, EntryFunctionHints>>,
+    path: Option<PathBuf>,
+    blocks_since_persist: AtomicUsize,
+}
+
+impl AccessHints {
+    /// Creates the hints, loading them from `path` if it exists. The hints are persisted to
+    /// `path` periodically if it is set.
+    pub fn new(path: Option<PathBuf>) -> Self {
+        let mut hints = LruCache::new(MAX_ENTRY_FUNCTIONS);
+        if let Some(path) = path.as_ref().filter(|path| path.exists()) {
+            match Self::load(path) {
+                Ok(loaded) => {
+                    info!(
+                        "Loaded access hints of {} entry functions from {:?}",
+                        loaded.len(),
+                        path
+                    );
+                    for (entry_function, entry_function_hints) in loaded {
+                        hints.put(entry_function, entry_function_hints);
+                    }
+                },
+                Err(e) => warn!("Failed to load access hints from {:?}: {}", path, e),
+            }
+        }
+        Self {
+            hints: Mutex::new(hints),
+            path,
+            blocks_since_persist: AtomicUsize::new(0),
+        }
+    }
+
+    /// Enables prefetching by access hints for all blocks executed by this process, along with
+    /// the recording of the read sets the hints are learned from. Only the first call has an
+    /// effect.
+    pub fn enable_once(path: Option<PathBuf>) {
+        AptosVM::set_record_read_sets_once();
+        ACCESS_HINTS.get_or_init(|| Self::new(path));
+    }
+
+    /// Returns the hints, if prefetching by access hints is enabled.
+    pub fn get() -> Option<&'static Self> {
+        ACCESS_HINTS.get()
+    }
+
+    /// Loads the hints persisted by `persist`, from the least to the most recently used.
+    fn load(path: &Path) -> Result<Vec<(String, EntryFunctionHints)>> {
+        Ok(bcs::from_bytes(&std::fs::read(path)?)?)
+    }
+
+    /// Writes the hints to the configured path, if any.
+    pub fn persist(&self) -> Result<()> {
+        if let Some(path) = &self.path {
+            let bytes = bcs::to_bytes(&self.hints.lock().iter().rev().collect::<Vec<_>>())?;
+            std::fs::write(path, bytes)?;
+        }
+        Ok(())
+    }
+
+    fn keys(&self, entry_function: &str, sender: AccountAddress) -> Vec<StateKey> {
+        match self.hints.lock().get(entry_function) {
+            Some(Some(hint_keys)) => hint_keys
+                .iter()
+                .map(|hint_key| hint_key.resolve(sender))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn record<'a>(
+        &self,
+        entry_function: String,
+        sender: AccountAddress,
+        keys: impl Iterator<Item = &'a StateKey>,
+    ) {
+        let mut hints = self.hints.lock();
+        if hints.get_mut(&entry_function).is_none() {
+            hints.put(entry_function.clone(), Some(BTreeSet::new()));
+        }
+        let entry_function_hints = hints
+            .get_mut(&entry_function)
+            .expect("The entry function was inserted above.");
+        let overflowed = match entry_function_hints {
+            Some(hint_keys) => {
+                hint_keys.extend(keys.map(|key| HintKey::new(key, sender)));
+                hint_keys.len() > MAX_KEYS_PER_ENTRY_FUNCTION
+            },
+            None => false,
+        };
+        if overflowed {
+            *entry_function_hints = None;
+        }
+    }
+
+    /// Fetches the state the transactions are expected to access into the cache of the state
+    /// view, and returns the keys fetched.
+    pub fn prefetch(
+        &self,
+        transactions: &[SignatureVerifiedTransaction],
+        state_view: &CachedStateView,
+    ) -> Result<HashSet<StateKey>> {
+        let keys: HashSet<_> = transactions
+            .iter()
+            .filter_map(entry_function_and_sender)
+            .flat_map(|(entry_function, sender)| self.keys(&entry_function, sender))
+            .collect();
+        APTOS_EXECUTOR_ACCESS_HINTS_KEYS
+            .with_label_values(&["prefetched"])
+            .inc_by(keys.len() as u64);
+        state_view.prime_cache_by_keys(&keys)?;
+        Ok(keys)
+    }
+
+    /// Learns the keys accessed by the executed transactions from their access lists, and
+    /// measures how many of the prefetched keys were used.
+    pub fn learn(
+        &self,
+        transactions: &[SignatureVerifiedTransaction],
+        transaction_outputs: &[TransactionOutput],
+        prefetched_keys: &HashSet<StateKey>,
+    ) {
+        let mut accessed_keys = HashSet::new();
+        for (txn, output) in transactions.iter().zip(transaction_outputs) {
+            // Discarded and retried transactions did not run the entry function.
+            if !matches!(output.status(), TransactionStatus::Keep(_)) {
+                continue;
+            }
+            let access_list = output.access_list();
+            accessed_keys.extend(access_list.keys().cloned());
+            if let Some((entry_function, sender)) = entry_function_and_sender(txn) {
+                self.record(entry_function, sender, access_list.keys());
+            }
+        }
+
+        let num_used = accessed_keys
+            .iter()
+            .filter(|key| prefetched_keys.contains(*key))
+            .count();
+        APTOS_EXECUTOR_ACCESS_HINTS_KEYS
+            .with_label_values(&["accessed"])
+            .inc_by(accessed_keys.len() as u64);
+        APTOS_EXECUTOR_ACCESS_HINTS_KEYS
+            .with_label_values(&["used"])
+            .inc_by(num_used as u64);
+
+        if self.blocks_since_persist.fetch_add(1, Ordering::Relaxed) + 1 >= PERSIST_INTERVAL_BLOCKS
+        {
+            self.blocks_since_persist.store(0, Ordering::Relaxed);
+            if let Err(e) = self.persist() {
+                warn!("Failed to persist access hints to {:?}: {}", self.path, e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_temppath::TempPath;
+
+    fn resource_key(address: AccountAddress, path: &[u8]) -> StateKey {
+        StateKey::access_path(AccessPath::new(address, path.to_vec()))
+    }
+
+    #[test]
+    fn test_sender_keys_are_relative() {
+        let alice = AccountAddress::random();
+        let bob = AccountAddress::random();
+        let hints = AccessHints::new(None);
+        hints.record(
+            "0x1::coin::transfer".to_string(),
+            alice,
+            [
+                resource_key(alice, b"coin_store"),
+                resource_key(AccountAddress::ONE, b"coin_info"),
+            ]
+            .iter(),
+        );
+
+        let keys: HashSet<_> = hints.keys("0x1::coin::transfer", bob).into_iter().collect();
+        assert_eq!(
+            keys,
+            HashSet::from([
+                resource_key(bob, b"coin_store"),
+                resource_key(AccountAddress::ONE, b"coin_info"),
+            ])
+        );
+        assert!(hints.keys("0x1::coin::mint", bob).is_empty());
+    }
+
+    #[test]
+    fn test_keys_are_bounded() {
+        let sender = AccountAddress::random();
+        let hints = AccessHints::new(None);
+        let keys: Vec<_> = (0..=MAX_KEYS_PER_ENTRY_FUNCTION)
+            .map(|i| StateKey::raw(i.to_le_bytes().to_vec()))
+            .collect();
+        hints.record("0x1::m::f".to_string(), sender, keys[1..].iter());
+        assert_eq!(
+            hints.keys("0x1::m::f", sender).len(),
+            MAX_KEYS_PER_ENTRY_FUNCTION
+        );
+
+        // One key too many drops the hint for good.
+        hints.record("0x1::m::f".to_string(), sender, keys[..1].iter());
+        assert!(hints.keys("0x1::m::f", sender).is_empty());
+        hints.record("0x1::m::f".to_string(), sender, keys[..1].iter());
+        assert!(hints.keys("0x1::m::f", sender).is_empty());
+    }
+
+    #[test]
+    fn test_least_recently_used_entry_functions_are_evicted() {
+        let sender = AccountAddress::random();
+        let hints = AccessHints::new(None);
+        let key = resource_key(AccountAddress::ONE, b"config");
+        let entry_function = |i: usize| format!("0x1::m::f{}", i);
+        for i in 0..MAX_ENTRY_FUNCTIONS {
+            hints.record(entry_function(i), sender, [key.clone()].iter());
+        }
+        // Using the hints of the first entry function makes the second the least recently used.
+        assert_eq!(hints.keys(&entry_function(0), sender), vec![key.clone()]);
+
+        hints.record(
+            entry_function(MAX_ENTRY_FUNCTIONS),
+            sender,
+            [key.clone()].iter(),
+        );
+        assert!(hints.keys(&entry_function(1), sender).is_empty());
+        for i in [0, 2, MAX_ENTRY_FUNCTIONS] {
+            assert_eq!(hints.keys(&entry_function(i), sender), vec![key.clone()]);
+        }
+    }
+
+    #[test]
+    fn test_persist_and_load() {
+        let path = TempPath::new();
+        let sender = AccountAddress::random();
+        let hints = AccessHints::new(Some(path.path().to_path_buf()));
+        hints.record(
+            "0x1::coin::transfer".to_string(),
+            sender,
+            [resource_key(sender, b"coin_store")].iter(),
+        );
+        hints.persist().unwrap();
+
+        let loaded = AccessHints::new(Some(path.path().to_path_buf()));
+        assert_eq!(loaded.keys("0x1::coin::transfer", sender), vec![
+            resource_key(sender, b"coin_store")
+        ]);
+    }
+}
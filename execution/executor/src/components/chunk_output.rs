@@ -4,7 +4,10 @@
 
 #![forbid(unsafe_code)]
 
-use crate::{components::apply_chunk_output::ApplyChunkOutput, metrics};
+use crate::{
+    components::{access_hints::AccessHints, apply_chunk_output::ApplyChunkOutput},
+    metrics::{self, APTOS_EXECUTOR_OTHER_TIMERS_SECONDS},
+};
 use anyhow::Result;
 use aptos_crypto::HashValue;
 use aptos_executor_service::{
@@ -32,7 +35,7 @@ use aptos_types::{
 use aptos_vm::{AptosVM, VMExecutor};
 use fail::fail_point;
 use move_core_types::vm_status::StatusCode;
-use std::{ops::Deref, sync::Arc, time::Duration};
+use std::{collections::HashSet, ops::Deref, sync::Arc, time::Duration};
 
 pub struct ChunkOutput {
     /// Input transactions.
@@ -70,9 +73,24 @@ impl ChunkOutput {
         state_view: CachedStateView,
         maybe_block_gas_limit: Option<u64>,
     ) -> Result<Self> {
+        let access_hints = AccessHints::get();
+        let prefetched_keys = match access_hints {
+            Some(access_hints) => {
+                let _timer = APTOS_EXECUTOR_OTHER_TIMERS_SECONDS
+                    .with_label_values(&["prefetch_access_hints"])
+                    .start_timer();
+                access_hints.prefetch(&transactions, &state_view)?
+            },
+            None => HashSet::new(),
+        };
+
         let transaction_outputs =
             Self::execute_block::<V>(&transactions, &state_view, maybe_block_gas_limit)?;
 
+        if let Some(access_hints) = access_hints {
+            access_hints.learn(&transactions, &transaction_outputs, &prefetched_keys);
+        }
+
         Ok(Self {
             transactions: transactions.into_iter().map(|t| t.into_inner()).collect(),
             transaction_outputs,
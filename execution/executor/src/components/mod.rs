@@ -4,6 +4,7 @@
 
 #![forbid(unsafe_code)]
 
+pub mod access_hints;
 pub mod apply_chunk_output;
 pub mod block_tree;
 pub mod chunk_commit_queue;
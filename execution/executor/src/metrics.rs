@@ -218,3 +218,15 @@ pub static APTOS_PROCESSED_TXNS_OUTPUT_SIZE: Lazy<IntCounter> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+/// Counter of state keys by how they were used in prefetching by access hints: "prefetched" keys
+/// were prefetched before executing a block, "accessed" keys were accessed by the block, and
+/// "used" keys were both.
+pub static APTOS_EXECUTOR_ACCESS_HINTS_KEYS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_executor_access_hints_keys",
+        "Counter of state keys prefetched and accessed when prefetching by access hints",
+        &["kind"]
+    )
+    .unwrap()
+});
@@ -363,6 +363,7 @@ impl serde::Serialize for any_public_key::Type {
             Self::Unspecified => "TYPE_UNSPECIFIED",
             Self::Ed25519 => "TYPE_ED25519",
             Self::Secp256k1Ecdsa => "TYPE_SECP256K1_ECDSA",
+            Self::Secp256r1Ecdsa => "TYPE_SECP256R1_ECDSA",
         };
         serializer.serialize_str(variant)
     }
@@ -377,6 +378,7 @@ impl<'de> serde::Deserialize<'de> for any_public_key::Type {
             "TYPE_UNSPECIFIED",
             "TYPE_ED25519",
             "TYPE_SECP256K1_ECDSA",
+            "TYPE_SECP256R1_ECDSA",
         ];
 
         struct GeneratedVisitor;
@@ -422,6 +424,7 @@ impl<'de> serde::Deserialize<'de> for any_public_key::Type {
                     "TYPE_UNSPECIFIED" => Ok(any_public_key::Type::Unspecified),
                     "TYPE_ED25519" => Ok(any_public_key::Type::Ed25519),
                     "TYPE_SECP256K1_ECDSA" => Ok(any_public_key::Type::Secp256k1Ecdsa),
+                    "TYPE_SECP256R1_ECDSA" => Ok(any_public_key::Type::Secp256r1Ecdsa),
                     _ => Err(serde::de::Error::unknown_variant(value, FIELDS)),
                 }
             }
@@ -551,6 +554,7 @@ impl serde::Serialize for any_signature::Type {
             Self::Unspecified => "TYPE_UNSPECIFIED",
             Self::Ed25519 => "TYPE_ED25519",
             Self::Secp256k1Ecdsa => "TYPE_SECP256K1_ECDSA",
+            Self::Webauthn => "TYPE_WEBAUTHN",
         };
         serializer.serialize_str(variant)
     }
@@ -565,6 +569,7 @@ impl<'de> serde::Deserialize<'de> for any_signature::Type {
             "TYPE_UNSPECIFIED",
             "TYPE_ED25519",
             "TYPE_SECP256K1_ECDSA",
+            "TYPE_WEBAUTHN",
         ];
 
         struct GeneratedVisitor;
@@ -610,6 +615,7 @@ impl<'de> serde::Deserialize<'de> for any_signature::Type {
                     "TYPE_UNSPECIFIED" => Ok(any_signature::Type::Unspecified),
                     "TYPE_ED25519" => Ok(any_signature::Type::Ed25519),
                     "TYPE_SECP256K1_ECDSA" => Ok(any_signature::Type::Secp256k1Ecdsa),
+                    "TYPE_WEBAUTHN" => Ok(any_signature::Type::Webauthn),
                     _ => Err(serde::de::Error::unknown_variant(value, FIELDS)),
                 }
             }
@@ -0,0 +1,69 @@
+#!/usr/bin/env python3
+
+'''
+This module automated the steps to
+calculate gas parameters for `bn254_algebra.move` natives from benchmarking results,
+then update the gas parameter definitions in rust.
+'''
+
+import argparse
+import fit_linear_model
+import load_bench_ns
+import load_bench_datapoints
+from math import ceil
+from pathlib import Path
+from time import time
+
+# Typically you are making a new version of gas schedule,
+# so this should be larger than `LATEST_GAS_FEATURE_VERSION` in `aptos-move/aptos-gas-schedule/src/ver.rs`.
+TARGET_GAS_VERSION = 13
+
+# Parameters whose natives do (almost) no work, so they are not benched.
+TRIVIAL_OPS = ['fq_one', 'fq_zero', 'fq12_one', 'fr_one', 'fr_zero', 'g1_proj_generator', 'g1_proj_infinity', 'g1_proj_neg', 'g2_proj_generator', 'g2_proj_infinity', 'g2_proj_neg']
+
+# Parameters read from a single-datapoint bench of the same name in `target/criterion/ark_bn254`.
+BENCHED_OPS = [
+    'fq_add', 'fq_deser', 'fq_div', 'fq_eq', 'fq_from_u64', 'fq_inv', 'fq_mul', 'fq_neg', 'fq_serialize', 'fq_square', 'fq_sub',
+    'fq12_add', 'fq12_clone', 'fq12_deser', 'fq12_div', 'fq12_eq', 'fq12_from_u64', 'fq12_inv', 'fq12_mul', 'fq12_neg', 'fq12_pow_u256', 'fq12_serialize', 'fq12_square', 'fq12_sub', 'fq12_zero',
+    'fr_add', 'fr_deser', 'fr_div', 'fr_eq', 'fr_from_u64', 'fr_inv', 'fr_mul', 'fr_neg', 'fr_serialize', 'fr_square', 'fr_sub',
+    'g1_affine_deser_comp', 'g1_affine_deser_uncomp', 'g1_affine_serialize_comp', 'g1_affine_serialize_uncomp',
+    'g1_proj_add', 'g1_proj_double', 'g1_proj_eq', 'g1_proj_scalar_mul', 'g1_proj_sub', 'g1_proj_to_affine',
+    'g2_affine_deser_comp', 'g2_affine_deser_uncomp', 'g2_affine_serialize_comp', 'g2_affine_serialize_uncomp',
+    'g2_proj_add', 'g2_proj_double', 'g2_proj_eq', 'g2_proj_scalar_mul', 'g2_proj_sub', 'g2_proj_to_affine',
+    'pairing',
+]
+
+def get_bench_ns_linear(bench_path):
+    datapoints = load_bench_datapoints.main(bench_path)
+    X,Y,k,b = fit_linear_model.main(datapoints)
+    return X,Y,k,b
+
+def get_algebra_lines(gas_per_ns):
+    nanoseconds = {}
+    for op in TRIVIAL_OPS:
+        nanoseconds[f'ark_bn254_{op}'] = 1
+    for op in BENCHED_OPS:
+        nanoseconds[f'ark_bn254_{op}'] = load_bench_ns.main(f'target/criterion/ark_bn254/{op}')
+    _,_,nanoseconds['ark_bn254_multi_pairing_per_pair'],nanoseconds['ark_bn254_multi_pairing_base'] = get_bench_ns_linear('target/criterion/ark_bn254/pairing_product')
+    gas_units = {k:gas_per_ns*v for k,v in nanoseconds.items()}
+    lines = []
+    for k,v in sorted(gas_units.items()):
+        gas_type = 'InternalGasPerArg' if k.endswith('_per_pair') else 'InternalGas'
+        lines.append(f'        [algebra_{k}: {gas_type}, {{ {TARGET_GAS_VERSION}.. => "algebra.{k}" }}, {ceil(v)}],')
+    return lines
+
+def main(gas_per_ns):
+    path = Path('aptos-move/aptos-gas-schedule/src/gas_schedule/aptos_framework.rs')
+    lines = path.read_text().split('\n')
+    line_id_begin = lines.index('        // BN254 algebra gas parameters begin.')
+    line_id_end = lines.index('        // BN254 algebra gas parameters end.')
+    generator_note_line = f'        // Generated at time {time()} by `scripts/algebra-gas/update_bn254_algebra_gas_params.py` with gas_per_ns={gas_per_ns}.'
+    new_lines = lines[:line_id_begin+1] + [generator_note_line] + get_algebra_lines(gas_per_ns) + lines[line_id_end:]
+    path.write_text('\n'.join(new_lines))
+
+if __name__=='__main__':
+    parser = argparse.ArgumentParser(
+        description='Generate gas parameters for the BN254 algebra module in `aptos-move/aptos-gas-schedule/src/gas_schedule/aptos_framework.rs`.')
+    parser.add_argument('--gas_per_ns', required=True, type=float)
+    args = parser.parse_args()
+    main(args.gas_per_ns)
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::network_controller::{metrics::NETWORK_HANDLER_TIMER, Message, MessageType};
-use aptos_logger::{error, info};
+use aptos_logger::{error, info, warn};
 use aptos_protos::remote_executor::v1::{
     network_message_service_client::NetworkMessageServiceClient,
     network_message_service_server::{NetworkMessageService, NetworkMessageServiceServer},
@@ -13,6 +13,7 @@ use std::{
     collections::HashMap,
     net::SocketAddr,
     sync::{Arc, Mutex},
+    time::Duration,
 };
 use tokio::{runtime::Runtime, sync::oneshot};
 use tonic::{
@@ -21,6 +22,10 @@ use tonic::{
 };
 
 const MAX_MESSAGE_SIZE: usize = 1024 * 1024 * 80;
+// Number of times sending a message is retried before the message is dropped, with the backoff
+// doubling after every attempt. The remote node is given ~10 seconds to (re)start.
+const MAX_SEND_RETRIES: u32 = 10;
+const INITIAL_SEND_BACKOFF_MS: u64 = 10;
 
 pub struct GRPCNetworkMessageServiceServerWrapper {
     inbound_handlers: Arc<Mutex<HashMap<MessageType, Sender<Message>>>>,
@@ -137,26 +142,50 @@ impl GRPCNetworkMessageServiceClientWrapper {
         NetworkMessageServiceClient::new(conn).max_decoding_message_size(MAX_MESSAGE_SIZE)
     }
 
+    /// Sends the message to the remote node, retrying with exponential backoff while the remote
+    /// node is unreachable (e.g. while it is (re)starting). Returns the error of the last attempt
+    /// if the message could not be delivered.
     pub async fn send_message(
         &mut self,
         sender_addr: SocketAddr,
         message: Message,
         mt: &MessageType,
-    ) {
+    ) -> Result<(), Status> {
+        let data = message.data;
+        let mut backoff = Duration::from_millis(INITIAL_SEND_BACKOFF_MS);
+        for attempt in 0..MAX_SEND_RETRIES {
+            // The request takes ownership of the data, so it's copied as long as the message may
+            // have to be resent.
+            let request = tonic::Request::new(NetworkMessage {
+                message: data.clone(),
+                message_type: mt.get_type(),
+            });
+            match self.remote_channel.simple_msg_exchange(request).await {
+                Ok(_) => return Ok(()),
+                Err(e) => {
+                    warn!(
+                        "Error '{}' sending message to {} on node {:?} (attempt {}), retrying in {:?}",
+                        e,
+                        self.remote_addr,
+                        sender_addr,
+                        attempt + 1,
+                        backoff
+                    );
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                },
+            }
+        }
+
+        // Last attempt, the data is moved into the request.
         let request = tonic::Request::new(NetworkMessage {
-            message: message.data,
+            message: data,
             message_type: mt.get_type(),
         });
-        // TODO: Retry with exponential backoff on failures
-        match self.remote_channel.simple_msg_exchange(request).await {
-            Ok(_) => {},
-            Err(e) => {
-                panic!(
-                    "Error '{}' sending message to {} on node {:?}",
-                    e, self.remote_addr, sender_addr
-                );
-            },
-        }
+        self.remote_channel
+            .simple_msg_exchange(request)
+            .await
+            .map(|_| ())
     }
 }
 
@@ -208,7 +237,8 @@ fn basic_test() {
                     Message::new(test_message_content.clone()),
                     &MessageType::new(message_type.clone()),
                 )
-                .await;
+                .await
+                .unwrap();
         });
     }
 
@@ -18,7 +18,11 @@
 
 pub mod grpc_network_service;
 pub mod network_controller;
+mod noise;
 
+pub use crate::noise::NoiseKeys;
+
+use aptos_crypto::noise::NoiseSession;
 use aptos_logger::{info, trace, warn, Schema};
 use aptos_metrics_core::{register_int_counter_vec, IntCounterVec};
 use once_cell::sync::Lazy;
@@ -59,6 +63,7 @@ enum LogEvent {
     ConnectionAttempt,
     ConnectionSuccessful,
     ConnectionFailed,
+    HandshakeFailed,
     DisconnectedPeerOnRead,
     DisconnectedPeerOnWrite,
     Shutdown,
@@ -93,6 +98,7 @@ static EVENT_COUNTER: Lazy<IntCounterVec> = Lazy::new(|| {
 #[serde(rename_all = "snake_case")]
 enum Method {
     Connect,
+    Handshake,
     Read,
     Write,
 }
@@ -101,6 +107,7 @@ impl Method {
     fn as_str(&self) -> &'static str {
         match self {
             Method::Connect => "connect",
+            Method::Handshake => "handshake",
             Method::Read => "read",
             Method::Write => "write",
         }
@@ -141,10 +148,14 @@ pub enum Error {
     NetworkError(#[from] std::io::Error),
     #[error("No active stream")]
     NoActiveStream,
+    #[error("Noise error: {0}")]
+    NoiseError(String),
     #[error("Overflow error: {0}")]
     OverflowError(String),
     #[error("Remote stream cleanly closed")]
     RemoteStreamClosed,
+    #[error("Remote peer presented an unexpected static key: {0}")]
+    UnauthenticatedPeer(String),
 }
 
 pub struct NetworkClient {
@@ -153,6 +164,10 @@ pub struct NetworkClient {
     stream: Option<NetworkStream>,
     /// Read, Write, Connect timeout in milliseconds.
     timeout_ms: u64,
+    /// If set, every connection is authenticated and encrypted with Noise
+    noise_keys: Option<NoiseKeys>,
+    /// Number of connections successfully established, used to detect reconnects
+    num_connections: u64,
 }
 
 impl NetworkClient {
@@ -162,9 +177,34 @@ impl NetworkClient {
             server,
             stream: None,
             timeout_ms,
+            noise_keys: None,
+            num_connections: 0,
+        }
+    }
+
+    /// Creates a client that only talks to a server holding the pinned Noise static key
+    pub fn new_with_noise(
+        service: String,
+        server: SocketAddr,
+        timeout_ms: u64,
+        noise_keys: NoiseKeys,
+    ) -> Self {
+        Self {
+            noise_keys: Some(noise_keys),
+            ..Self::new(service, server, timeout_ms)
         }
     }
 
+    /// Returns true iff there is a live (and, if configured, authenticated) stream to the server
+    pub fn is_connected(&self) -> bool {
+        self.stream.is_some()
+    }
+
+    /// Returns the number of connections established so far, this increases on every reconnect
+    pub fn num_connections(&self) -> u64 {
+        self.num_connections
+    }
+
     fn increment_counter(&self, method: Method, result: MethodResult) {
         increment_counter(&self.service, NetworkMode::Client, method, result)
     }
@@ -236,28 +276,60 @@ impl NetworkClient {
             )
             .remote_peer(&self.server));
 
+            // Keep retrying until the server is reachable, but give up once the connect timeout
+            // has elapsed so that callers can apply their own deadlines.
             let timeout = std::time::Duration::from_millis(self.timeout_ms);
-            let mut stream = TcpStream::connect_timeout(&self.server, timeout);
-
+            let deadline = time::Instant::now() + timeout;
             let sleeptime = time::Duration::from_millis(100);
-            while let Err(err) = stream {
-                self.increment_counter(Method::Connect, MethodResult::Failure);
-                warn!(SecureNetLogSchema::new(
-                    &self.service,
-                    NetworkMode::Client,
-                    LogEvent::ConnectionFailed,
-                )
-                .error(&err.into())
-                .remote_peer(&self.server));
-
-                thread::sleep(sleeptime);
-                stream = TcpStream::connect_timeout(&self.server, timeout);
-            }
+            let stream = loop {
+                match TcpStream::connect_timeout(&self.server, timeout) {
+                    Ok(stream) => break stream,
+                    Err(err) => {
+                        self.increment_counter(Method::Connect, MethodResult::Failure);
+                        let err = err.into();
+                        warn!(SecureNetLogSchema::new(
+                            &self.service,
+                            NetworkMode::Client,
+                            LogEvent::ConnectionFailed,
+                        )
+                        .error(&err)
+                        .remote_peer(&self.server));
+
+                        if time::Instant::now() >= deadline {
+                            return Err(err);
+                        }
+                        thread::sleep(sleeptime);
+                    },
+                }
+            };
 
-            let stream = stream?;
             stream.set_nodelay(true)?;
-            self.stream = Some(NetworkStream::new(stream, self.server, self.timeout_ms));
+            let mut stream = NetworkStream::new(stream, self.server, self.timeout_ms);
             self.increment_counter(Method::Connect, MethodResult::Success);
+
+            if let Some(noise_keys) = &self.noise_keys {
+                self.increment_counter(Method::Handshake, MethodResult::Query);
+                match noise::initiate(&mut stream, &self.service, noise_keys) {
+                    Ok(session) => {
+                        self.increment_counter(Method::Handshake, MethodResult::Success);
+                        stream.session = Some(session);
+                    },
+                    Err(err) => {
+                        self.increment_counter(Method::Handshake, MethodResult::Failure);
+                        warn!(SecureNetLogSchema::new(
+                            &self.service,
+                            NetworkMode::Client,
+                            LogEvent::HandshakeFailed,
+                        )
+                        .error(&err)
+                        .remote_peer(&self.server));
+                        return Err(err);
+                    },
+                }
+            }
+
+            self.stream = Some(stream);
+            self.num_connections += 1;
             info!(SecureNetLogSchema::new(
                 &self.service,
                 NetworkMode::Client,
@@ -276,6 +348,8 @@ pub struct NetworkServer {
     stream: Option<NetworkStream>,
     /// Read, Write, Connect timeout in milliseconds.
     timeout_ms: u64,
+    /// If set, only clients holding the pinned Noise static key are accepted
+    noise_keys: Option<NoiseKeys>,
 }
 
 impl NetworkServer {
@@ -286,6 +360,20 @@ impl NetworkServer {
             listener: Some(listener.unwrap()),
             stream: None,
             timeout_ms,
+            noise_keys: None,
+        }
+    }
+
+    /// Creates a server that only accepts clients holding the pinned Noise static key
+    pub fn new_with_noise(
+        service: String,
+        listen: SocketAddr,
+        timeout_ms: u64,
+        noise_keys: NoiseKeys,
+    ) -> Self {
+        Self {
+            noise_keys: Some(noise_keys),
+            ..Self::new(service, listen, timeout_ms)
         }
     }
 
@@ -390,15 +478,39 @@ impl NetworkServer {
             };
 
             self.increment_counter(Method::Connect, MethodResult::Success);
+            stream.set_nodelay(true)?;
+            let mut stream = NetworkStream::new(stream, stream_addr, self.timeout_ms);
+
+            if let Some(noise_keys) = &self.noise_keys {
+                self.increment_counter(Method::Handshake, MethodResult::Query);
+                match noise::respond(&mut stream, &self.service, noise_keys) {
+                    Ok(session) => {
+                        self.increment_counter(Method::Handshake, MethodResult::Success);
+                        stream.session = Some(session);
+                    },
+                    Err(err) => {
+                        self.increment_counter(Method::Handshake, MethodResult::Failure);
+                        warn!(SecureNetLogSchema::new(
+                            &self.service,
+                            NetworkMode::Server,
+                            LogEvent::HandshakeFailed,
+                        )
+                        .error(&err)
+                        .remote_peer(&stream_addr));
+                        // Best effort, the connection is dropped regardless
+                        let _ = stream.shutdown();
+                        return Err(err);
+                    },
+                }
+            }
+
             info!(SecureNetLogSchema::new(
                 &self.service,
                 NetworkMode::Server,
                 LogEvent::ConnectionSuccessful,
             )
             .remote_peer(&stream_addr));
-
-            stream.set_nodelay(true)?;
-            self.stream = Some(NetworkStream::new(stream, stream_addr, self.timeout_ms));
+            self.stream = Some(stream);
         }
 
         self.stream.as_mut().ok_or(Error::NoActiveStream)
@@ -410,6 +522,8 @@ struct NetworkStream {
     remote: SocketAddr,
     buffer: Vec<u8>,
     temp_buffer: [u8; 1024],
+    /// Set once a Noise handshake completed, all subsequent messages are encrypted
+    session: Option<NoiseSession>,
 }
 
 impl NetworkStream {
@@ -424,11 +538,34 @@ impl NetworkStream {
             remote,
             buffer: Vec::new(),
             temp_buffer: [0; 1024],
+            session: None,
         }
     }
 
-    /// Blocking read until able to successfully read an entire message
+    /// Blocking read until able to successfully read an entire message, decrypting it if a Noise
+    /// session has been established
     pub fn read(&mut self) -> Result<Vec<u8>, Error> {
+        let frame = self.read_frame()?;
+        match self.session.as_mut() {
+            Some(session) => noise::decrypt(session, frame),
+            None => Ok(frame),
+        }
+    }
+
+    /// Blocking write until able to successfully send an entire message, encrypting it if a Noise
+    /// session has been established
+    pub fn write(&mut self, data: &[u8]) -> Result<(), Error> {
+        match self.session.as_mut() {
+            Some(session) => {
+                let frame = noise::encrypt(session, data)?;
+                self.write_frame(&frame)
+            },
+            None => self.write_frame(data),
+        }
+    }
+
+    /// Blocking read until able to successfully read an entire frame
+    fn read_frame(&mut self) -> Result<Vec<u8>, Error> {
         let result = self.read_buffer();
         if !result.is_empty() {
             return Ok(result);
@@ -456,8 +593,8 @@ impl NetworkStream {
         Ok(self.stream.shutdown(Shutdown::Both)?)
     }
 
-    /// Blocking write until able to successfully send an entire message
-    pub fn write(&mut self, data: &[u8]) -> Result<(), Error> {
+    /// Blocking write until able to successfully send an entire frame
+    fn write_frame(&mut self, data: &[u8]) -> Result<(), Error> {
         let u32_max = u32::max_value() as usize;
         if u32_max <= data.len() {
             return Err(Error::DataTooLarge(data.len()));
@@ -517,6 +654,8 @@ impl NetworkStream {
 mod test {
     use super::*;
     use aptos_config::utils;
+    use aptos_crypto::{x25519, Uniform};
+    use rand::{rngs::StdRng, SeedableRng};
     use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 
     /// Read, Write, Connect timeout in milliseconds.
@@ -655,4 +794,92 @@ mod test {
         let result2 = server2.read().unwrap();
         assert_eq!(data2, result2);
     }
+
+    fn noise_keys(rng: &mut StdRng) -> (NoiseKeys, NoiseKeys) {
+        let client_key = x25519::PrivateKey::generate(rng);
+        let server_key = x25519::PrivateKey::generate(rng);
+        let client_public_key = client_key.public_key();
+        let server_public_key = server_key.public_key();
+        (
+            NoiseKeys::new(client_key, server_public_key),
+            NoiseKeys::new(server_key, client_public_key),
+        )
+    }
+
+    #[test]
+    fn test_noise_ping() {
+        let mut rng = StdRng::from_seed([0u8; 32]);
+        let (client_keys, server_keys) = noise_keys(&mut rng);
+
+        let server_port = utils::get_available_port();
+        let server_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), server_port);
+        let mut server =
+            NetworkServer::new_with_noise("test".to_string(), server_addr, TIMEOUT, server_keys);
+        let mut client =
+            NetworkClient::new_with_noise("test".to_string(), server_addr, TIMEOUT, client_keys);
+
+        // The client performs the handshake on its first write, while the server performs it
+        // when accepting on its first read, so they have to run concurrently.
+        let data = vec![0, 1, 2, 3];
+        let server_thread = thread::spawn(move || {
+            let result = server.read().unwrap();
+            server.write(&result).unwrap();
+            server
+        });
+        client.write(&data).unwrap();
+        assert_eq!(data, client.read().unwrap());
+        assert_eq!(client.num_connections(), 1);
+        let mut server = server_thread.join().unwrap();
+
+        // Messages larger than a single noise frame, and empty messages, are supported
+        for data in [
+            vec![7u8; 3 * aptos_crypto::noise::MAX_SIZE_NOISE_MSG + 5],
+            vec![],
+        ] {
+            client.write(&data).unwrap();
+            assert_eq!(data, server.read().unwrap());
+            server.write(&data).unwrap();
+            assert_eq!(data, client.read().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_noise_rejects_unpinned_client() {
+        let mut rng = StdRng::from_seed([1u8; 32]);
+        let (_, server_keys) = noise_keys(&mut rng);
+        // The rogue client knows the server key but the server pinned a different client key
+        let rogue_keys = NoiseKeys::new(
+            x25519::PrivateKey::generate(&mut rng),
+            server_keys.public_key(),
+        );
+
+        let server_port = utils::get_available_port();
+        let server_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), server_port);
+        let mut server =
+            NetworkServer::new_with_noise("test".to_string(), server_addr, TIMEOUT, server_keys);
+        let mut client =
+            NetworkClient::new_with_noise("test".to_string(), server_addr, TIMEOUT, rogue_keys);
+
+        let client_thread = thread::spawn(move || client.write(&[0, 1, 2, 3]));
+        assert!(matches!(
+            server.read().unwrap_err(),
+            Error::UnauthenticatedPeer(_)
+        ));
+        assert!(client_thread.join().unwrap().is_err());
+    }
+
+    #[test]
+    fn test_noise_rejects_plaintext_client() {
+        let mut rng = StdRng::from_seed([2u8; 32]);
+        let (_, server_keys) = noise_keys(&mut rng);
+
+        let server_port = utils::get_available_port();
+        let server_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), server_port);
+        let mut server =
+            NetworkServer::new_with_noise("test".to_string(), server_addr, TIMEOUT, server_keys);
+        let mut client = NetworkClient::new("test".to_string(), server_addr, TIMEOUT);
+
+        client.write(&[0, 1, 2, 3]).unwrap();
+        assert!(matches!(server.read().unwrap_err(), Error::NoiseError(_)));
+    }
 }
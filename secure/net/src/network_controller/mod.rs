@@ -4,7 +4,7 @@ use crate::network_controller::{
     inbound_handler::InboundHandler, outbound_handler::OutboundHandler,
 };
 use aptos_logger::{info, warn};
-use crossbeam_channel::{unbounded, Receiver, Sender};
+use crossbeam_channel::{bounded, unbounded, Receiver, Sender};
 use serde::{Deserialize, Serialize};
 use std::{
     net::SocketAddr,
@@ -123,6 +123,22 @@ impl NetworkController {
         outbound_sender
     }
 
+    /// Like [`Self::create_outbound_channel`], but at most `capacity` messages can be queued for
+    /// sending, after which sending blocks until the outbound handler has caught up.
+    pub fn create_bounded_outbound_channel(
+        &mut self,
+        remote_peer_addr: SocketAddr,
+        message_type: String,
+        capacity: usize,
+    ) -> Sender<Message> {
+        let (outbound_sender, outbound_receiver) = bounded(capacity);
+
+        self.outbound_handler
+            .register_handler(message_type, remote_peer_addr, outbound_receiver);
+
+        outbound_sender
+    }
+
     pub fn create_inbound_channel(&mut self, message_type: String) -> Receiver<Message> {
         let (inbound_sender, inbound_receiver) = unbounded();
 
@@ -171,6 +187,7 @@ mod tests {
     use std::{
         net::{IpAddr, Ipv4Addr, SocketAddr},
         thread,
+        time::Duration,
     };
 
     #[test]
@@ -221,4 +238,49 @@ mod tests {
         network_controller2.shutdown();
         thread::sleep(std::time::Duration::from_millis(100));
     }
+
+    #[test]
+    fn test_send_to_healthy_node_while_another_is_down() {
+        let server_addr =
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), utils::get_available_port());
+        let healthy_addr =
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), utils::get_available_port());
+        // Nothing listens on this address.
+        let down_addr =
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), utils::get_available_port());
+
+        let mut network_controller =
+            NetworkController::new("sender".to_string(), server_addr, 1000);
+        let mut healthy_network_controller =
+            NetworkController::new("healthy".to_string(), healthy_addr, 1000);
+
+        let down_sender = network_controller.create_outbound_channel(down_addr, "test".to_string());
+        let healthy_sender =
+            network_controller.create_outbound_channel(healthy_addr, "test".to_string());
+        let healthy_receiver =
+            healthy_network_controller.create_inbound_channel("test".to_string());
+
+        network_controller.start();
+        healthy_network_controller.start();
+
+        // wait for the server to be ready to serve
+        thread::sleep(Duration::from_millis(100));
+
+        // The message to the node that is down is retried for seconds, which must not delay the
+        // messages to the healthy node.
+        down_sender.send(Message::new(b"lost".to_vec())).unwrap();
+        for i in 0..3u8 {
+            healthy_sender.send(Message::new(vec![i])).unwrap();
+        }
+        for i in 0..3u8 {
+            let received_message = healthy_receiver
+                .recv_timeout(Duration::from_secs(2))
+                .unwrap();
+            assert_eq!(received_message.data, vec![i]);
+        }
+
+        network_controller.shutdown();
+        healthy_network_controller.shutdown();
+        thread::sleep(Duration::from_millis(100));
+    }
 }
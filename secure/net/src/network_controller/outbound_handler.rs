@@ -6,15 +6,19 @@ use crate::{
         inbound_handler::InboundHandler, metrics::NETWORK_HANDLER_TIMER, Message, MessageType,
     },
 };
-use aptos_logger::{info, warn};
+use aptos_logger::{error, info, warn};
 use crossbeam_channel::{unbounded, Receiver, Select, Sender};
 use std::{
     collections::{HashMap, HashSet},
     mem,
     net::SocketAddr,
     sync::{Arc, Mutex},
+    thread,
+};
+use tokio::{
+    runtime::Runtime,
+    sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
 };
-use tokio::runtime::Runtime;
 
 pub struct OutboundHandler {
     _service: String,
@@ -64,19 +68,29 @@ impl OutboundHandler {
             MessageType::new("stop_task".to_string()),
         ));
 
-        // Create a grpc client for each remote address
-        let mut grpc_clients: HashMap<SocketAddr, GRPCNetworkMessageServiceClientWrapper> =
-            HashMap::new();
-        self.remote_addresses.iter().for_each(|remote_addr| {
-            grpc_clients.insert(
-                *remote_addr,
-                GRPCNetworkMessageServiceClientWrapper::new(rt, *remote_addr),
-            );
-        });
-
-        // Prepare for objects to be moved into the async block (&mut self cannot be moved into the
-        // async block)
+        // Create a task with its own grpc client for each remote address, which sends the messages
+        // to that address in order. Retrying the messages to a node that is down then does not
+        // hold up the messages to the other nodes.
         let address = self.address;
+        let peer_txs: HashMap<SocketAddr, UnboundedSender<(Message, MessageType)>> = self
+            .remote_addresses
+            .iter()
+            .filter(|remote_addr| **remote_addr != address)
+            .map(|remote_addr| {
+                let (peer_tx, peer_rx) = unbounded_channel();
+                let grpc_client = GRPCNetworkMessageServiceClientWrapper::new(rt, *remote_addr);
+                rt.spawn(Self::send_to_peer(
+                    address,
+                    *remote_addr,
+                    grpc_client,
+                    peer_rx,
+                ));
+                (*remote_addr, peer_tx)
+            })
+            .collect();
+
+        // Prepare for objects to be moved into the thread (&mut self cannot be moved into the
+        // thread)
         let inbound_handler = self.inbound_handler.clone();
         // Moving the handlers out of self is fine because once 'start()' is called we do not intend
         // to register any more handlers. A reference count like Arc<Mutex> has issues of being
@@ -84,26 +98,54 @@ impl OutboundHandler {
         // the cost of the mutex when there is no contention
         let outbound_handlers = mem::take(self.handlers.as_mut());
 
-        // TODO: Consider using multiple tasks for outbound handlers
-        rt.spawn(async move {
-            info!("Starting outbound handler at {}", address.to_string());
-            Self::process_one_outgoing_message(
-                outbound_handlers,
-                &address,
-                inbound_handler.clone(),
-                &mut grpc_clients,
-            )
-            .await;
-            info!("Stopping outbound handler at {}", address.to_string());
-        });
+        // Waiting for outgoing messages blocks, so it's done on a thread of its own rather than on
+        // the runtime shared with the tasks sending the messages.
+        thread::Builder::new()
+            .name(format!("outbound-handler-{}", address))
+            .spawn(move || {
+                info!("Starting outbound handler at {}", address.to_string());
+                Self::process_one_outgoing_message(
+                    outbound_handlers,
+                    &address,
+                    inbound_handler,
+                    &peer_txs,
+                );
+                info!("Stopping outbound handler at {}", address.to_string());
+            })
+            .unwrap();
         Some(stop_signal_tx)
     }
 
-    async fn process_one_outgoing_message(
+    async fn send_to_peer(
+        socket_addr: SocketAddr,
+        remote_addr: SocketAddr,
+        mut grpc_client: GRPCNetworkMessageServiceClientWrapper,
+        mut peer_rx: UnboundedReceiver<(Message, MessageType)>,
+    ) {
+        while let Some((msg, message_type)) = peer_rx.recv().await {
+            if let Err(e) = grpc_client
+                .send_message(socket_addr, msg, &message_type)
+                .await
+            {
+                // The remote node is considered down. Its peers detect the failure on their own
+                // (e.g. by timing out), so the message is dropped instead of bringing down the
+                // task sending to this node.
+                error!(
+                    "Error '{}' sending message to {} on node {:?}, dropping message of type {:?}",
+                    e,
+                    remote_addr,
+                    socket_addr,
+                    message_type.get_type()
+                );
+            }
+        }
+    }
+
+    fn process_one_outgoing_message(
         outbound_handlers: Vec<(Receiver<Message>, SocketAddr, MessageType)>,
         socket_addr: &SocketAddr,
         inbound_handler: Arc<Mutex<InboundHandler>>,
-        grpc_clients: &mut HashMap<SocketAddr, GRPCNetworkMessageServiceClientWrapper>,
+        peer_txs: &HashMap<SocketAddr, UnboundedSender<(Message, MessageType)>>,
     ) {
         loop {
             let mut select = Select::new();
@@ -150,12 +192,16 @@ impl OutboundHandler {
                     .lock()
                     .unwrap()
                     .send_incoming_message_to_handler(message_type, msg);
-            } else {
-                grpc_clients
-                    .get_mut(remote_addr)
-                    .unwrap()
-                    .send_message(*socket_addr, msg, message_type)
-                    .await;
+            } else if peer_txs[remote_addr]
+                .send((msg, message_type.clone()))
+                .is_err()
+            {
+                warn!(
+                    "Task sending to {} on node {:?} stopped, dropping message of type {:?}",
+                    remote_addr,
+                    socket_addr,
+                    message_type.get_type()
+                );
             }
         }
     }
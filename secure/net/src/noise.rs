@@ -0,0 +1,149 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Optional authentication and encryption of the blocking network streams. Both ends pin the
+//! static x25519 public key of the other end and perform a Noise IK handshake as the very first
+//! exchange on every new connection. A connection whose handshake fails, or whose remote static
+//! key does not match the pinned key, is dropped before any application data is exchanged.
+//!
+//! After the handshake, every message is encrypted with the resulting `NoiseSession`. A Noise
+//! message is limited to `MAX_SIZE_NOISE_MSG` bytes, so larger messages are split into chunks.
+//! Every chunk but the last one is exactly `MAX_SIZE_NOISE_MSG` bytes once encrypted, which lets
+//! the receiver split a frame back into chunks without any additional framing.
+
+use crate::{Error, NetworkStream};
+use aptos_crypto::{noise, x25519};
+
+/// Prefix of the Noise prologue, the service name is appended so that a session established for
+/// one service cannot be used to talk to another.
+const PROLOGUE_PREFIX: &[u8] = b"aptos-secure-net:";
+
+/// The largest plaintext that fits into a single Noise message.
+const MAX_PLAINTEXT_CHUNK_SIZE: usize = noise::MAX_SIZE_NOISE_MSG - noise::AES_GCM_TAGLEN;
+
+/// The static keys used to authenticate a connection: our own private key and the pinned public
+/// key of the only peer we are willing to talk to.
+pub struct NoiseKeys {
+    config: noise::NoiseConfig,
+    remote_public_key: x25519::PublicKey,
+}
+
+impl NoiseKeys {
+    pub fn new(private_key: x25519::PrivateKey, remote_public_key: x25519::PublicKey) -> Self {
+        Self {
+            config: noise::NoiseConfig::new(private_key),
+            remote_public_key,
+        }
+    }
+
+    /// Our static public key, this is what the remote end has to pin.
+    pub fn public_key(&self) -> x25519::PublicKey {
+        self.config.public_key()
+    }
+
+    /// The pinned static public key of the remote end.
+    pub fn remote_public_key(&self) -> x25519::PublicKey {
+        self.remote_public_key
+    }
+}
+
+impl From<noise::NoiseError> for Error {
+    fn from(error: noise::NoiseError) -> Self {
+        Error::NoiseError(error.to_string())
+    }
+}
+
+fn prologue(service: &str) -> Vec<u8> {
+    let mut prologue = PROLOGUE_PREFIX.to_vec();
+    prologue.extend_from_slice(service.as_bytes());
+    prologue
+}
+
+/// Performs the initiator (client) side of the handshake on a freshly connected stream.
+pub(crate) fn initiate(
+    stream: &mut NetworkStream,
+    service: &str,
+    keys: &NoiseKeys,
+) -> Result<noise::NoiseSession, Error> {
+    let mut rng = rand::rngs::OsRng;
+    let mut client_init_message = vec![0u8; noise::handshake_init_msg_len(0)];
+    let handshake_state = keys.config.initiate_connection(
+        &mut rng,
+        &prologue(service),
+        keys.remote_public_key,
+        None,
+        &mut client_init_message,
+    )?;
+    stream.write(&client_init_message)?;
+
+    let server_response = stream.read()?;
+    let (_, session) = keys
+        .config
+        .finalize_connection(handshake_state, &server_response)?;
+    Ok(session)
+}
+
+/// Performs the responder (server) side of the handshake on a freshly accepted stream and rejects
+/// any initiator that does not present the pinned static key.
+pub(crate) fn respond(
+    stream: &mut NetworkStream,
+    service: &str,
+    keys: &NoiseKeys,
+) -> Result<noise::NoiseSession, Error> {
+    let client_init_message = stream.read()?;
+    let (remote_public_key, handshake_state, _) = keys
+        .config
+        .parse_client_init_message(&prologue(service), &client_init_message)?;
+    if remote_public_key != keys.remote_public_key {
+        return Err(Error::UnauthenticatedPeer(remote_public_key.to_string()));
+    }
+
+    let mut rng = rand::rngs::OsRng;
+    let mut server_response = vec![0u8; noise::handshake_resp_msg_len(0)];
+    let session =
+        keys.config
+            .respond_to_client(&mut rng, handshake_state, None, &mut server_response)?;
+    stream.write(&server_response)?;
+    Ok(session)
+}
+
+/// Encrypts a message into a single frame. An empty message still produces one (empty) chunk so
+/// that every frame carries at least one authentication tag.
+pub(crate) fn encrypt(session: &mut noise::NoiseSession, data: &[u8]) -> Result<Vec<u8>, Error> {
+    let num_chunks = std::cmp::max(
+        1,
+        (data.len() + MAX_PLAINTEXT_CHUNK_SIZE - 1) / MAX_PLAINTEXT_CHUNK_SIZE,
+    );
+    let mut frame = Vec::with_capacity(data.len() + num_chunks * noise::AES_GCM_TAGLEN);
+
+    let mut offset = 0;
+    loop {
+        let end = std::cmp::min(offset + MAX_PLAINTEXT_CHUNK_SIZE, data.len());
+        let mut chunk = data[offset..end].to_vec();
+        let auth_tag = session.write_message_in_place(&mut chunk)?;
+        frame.extend_from_slice(&chunk);
+        frame.extend_from_slice(&auth_tag);
+
+        offset = end;
+        if offset == data.len() {
+            return Ok(frame);
+        }
+    }
+}
+
+/// Decrypts a frame produced by `encrypt`. Any tampering invalidates the session, after which the
+/// connection has to be re-established.
+pub(crate) fn decrypt(
+    session: &mut noise::NoiseSession,
+    mut frame: Vec<u8>,
+) -> Result<Vec<u8>, Error> {
+    if frame.is_empty() {
+        return Err(Error::NoiseError("received an empty frame".into()));
+    }
+
+    let mut data = Vec::with_capacity(frame.len());
+    for chunk in frame.chunks_mut(noise::MAX_SIZE_NOISE_MSG) {
+        data.extend_from_slice(session.read_message_in_place(chunk)?);
+    }
+    Ok(data)
+}
@@ -10,7 +10,10 @@ use aptos_storage_service_types::responses::{
 };
 use aptos_types::{
     epoch_change::EpochChangeProof,
-    state_store::state_value::StateValueChunkWithProof,
+    state_store::{
+        state_key::StateKey,
+        state_value::{StateValueChunkWithProof, StateValuesWithMultiProof},
+    },
     transaction::{TransactionListWithProof, TransactionOutputListWithProof, Version},
 };
 use serde::Serialize;
@@ -88,6 +91,16 @@ pub trait StorageReaderInterface: Clone + Send + 'static {
         start_index: u64,
         end_index: u64,
     ) -> aptos_storage_service_types::Result<StateValueChunkWithProof, Error>;
+
+    /// Returns the state values of the given `state_keys` at the specified
+    /// `version`, with a single proof for all of them. Unlike chunks, this
+    /// can't be served partially, so requests for more keys than the state
+    /// chunk limit, or that don't fit into a network frame, are rejected.
+    fn get_state_values_with_multi_proof(
+        &self,
+        version: u64,
+        state_keys: Vec<StateKey>,
+    ) -> aptos_storage_service_types::Result<StateValuesWithMultiProof, Error>;
 }
 
 /// The underlying implementation of the StorageReaderInterface, used by the
@@ -494,6 +507,46 @@ impl StorageReaderInterface for StorageReader {
             version, start_index, end_index
         )))
     }
+
+    fn get_state_values_with_multi_proof(
+        &self,
+        version: u64,
+        state_keys: Vec<StateKey>,
+    ) -> aptos_storage_service_types::Result<StateValuesWithMultiProof, Error> {
+        let max_num_state_values = self.config.max_state_chunk_size;
+        if state_keys.len() as u64 > max_num_state_values {
+            return Err(Error::InvalidRequest(format!(
+                "Too many state keys requested: {:?}, max: {:?}",
+                state_keys.len(),
+                max_num_state_values
+            )));
+        }
+
+        let (values, proof) = self
+            .storage
+            .get_state_proof_with_multiple_keys(&state_keys, version)
+            .map_err(|error| Error::StorageErrorEncountered(error.to_string()))?;
+        let state_values_with_proof = StateValuesWithMultiProof {
+            version,
+            raw_values: state_keys.into_iter().zip(values).collect(),
+            proof,
+        };
+
+        let (overflow_frame, num_bytes) = check_overflow_network_frame(
+            &state_values_with_proof,
+            self.config.max_network_chunk_bytes,
+        )?;
+        if overflow_frame {
+            return Err(Error::UnexpectedErrorEncountered(format!(
+                "Unable to serve the get_state_values_with_multi_proof request! Version: {:?}, \
+                number of keys: {:?}, num bytes: {:?}. The data cannot fit into a single network frame!",
+                version,
+                state_values_with_proof.raw_values.len(),
+                num_bytes
+            )));
+        }
+        Ok(state_values_with_proof)
+    }
 }
 
 /// Calculate `(start..=end).len()`. Returns an error if `end < start` or
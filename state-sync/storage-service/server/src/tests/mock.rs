@@ -34,7 +34,10 @@ use aptos_types::{
     epoch_change::EpochChangeProof,
     event::EventKey,
     ledger_info::LedgerInfoWithSignatures,
-    proof::{AccumulatorConsistencyProof, SparseMerkleProof, TransactionAccumulatorSummary},
+    proof::{
+        AccumulatorConsistencyProof, SparseMerkleMultiProof, SparseMerkleProof,
+        TransactionAccumulatorSummary,
+    },
     state_proof::StateProof,
     state_store::{
         state_key::StateKey,
@@ -322,6 +325,12 @@ mock! {
             version: Version,
         ) -> Result<(Option<StateValue>, SparseMerkleProof)>;
 
+        fn get_state_proof_with_multiple_keys(
+            &self,
+            state_keys: &[StateKey],
+            version: Version,
+        ) -> Result<(Vec<Option<StateValue>>, SparseMerkleMultiProof)>;
+
         fn get_latest_executed_trees(&self) -> Result<ExecutedTrees>;
 
         fn get_epoch_ending_ledger_info(&self, known_version: u64) -> Result<LedgerInfoWithSignatures>;
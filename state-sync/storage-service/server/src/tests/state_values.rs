@@ -1,10 +1,14 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::tests::{
-    mock,
-    mock::{MockClient, MockDatabaseReader},
-    utils,
+use crate::{
+    error::Error,
+    storage::{StorageReader, StorageReaderInterface},
+    tests::{
+        mock,
+        mock::{MockClient, MockDatabaseReader},
+        utils,
+    },
 };
 use aptos_config::config::StorageServiceConfig;
 use aptos_crypto::hash::HashValue;
@@ -14,7 +18,7 @@ use aptos_storage_service_types::{
     StorageServiceError,
 };
 use aptos_types::{
-    proof::definition::SparseMerkleRangeProof,
+    proof::{definition::SparseMerkleRangeProof, SparseMerkleMultiProof},
     state_store::{
         state_key::StateKey,
         state_value::{StateValue, StateValueChunkWithProof},
@@ -24,6 +28,7 @@ use bytes::Bytes;
 use claims::assert_matches;
 use mockall::{predicate::eq, Sequence};
 use rand::Rng;
+use std::sync::Arc;
 
 #[tokio::test]
 async fn test_get_states_with_proof() {
@@ -176,6 +181,62 @@ async fn test_get_states_with_proof_not_serviceable() {
     }
 }
 
+#[test]
+fn test_get_state_values_with_multi_proof() {
+    // Create test data
+    let version = 101;
+    let state_keys: Vec<_> = (0..3)
+        .map(|i| StateKey::raw(format!("key{}", i).into_bytes()))
+        .collect();
+    let values = vec![
+        Some(StateValue::from(vec![1])),
+        None,
+        Some(StateValue::from(vec![2])),
+    ];
+    let proof = SparseMerkleMultiProof::new(vec![None; 3], vec![0; 3], vec![]);
+
+    // Create the mock db reader
+    let mut db_reader = mock::create_mock_db_reader();
+    let (expected_values, expected_proof) = (values.clone(), proof.clone());
+    db_reader
+        .expect_get_state_proof_with_multiple_keys()
+        .times(1)
+        .withf(move |keys, v| keys.len() == 3 && *v == version)
+        .returning(move |_, _| Ok((expected_values.clone(), expected_proof.clone())));
+
+    // Fetch the values and verify they are returned in the requested order
+    let storage_reader = StorageReader::new(StorageServiceConfig::default(), Arc::new(db_reader));
+    let state_values_with_proof = storage_reader
+        .get_state_values_with_multi_proof(version, state_keys.clone())
+        .unwrap();
+    assert_eq!(state_values_with_proof.version, version);
+    assert_eq!(
+        state_values_with_proof.raw_values,
+        state_keys.into_iter().zip(values).collect::<Vec<_>>()
+    );
+    assert_eq!(state_values_with_proof.proof, proof);
+}
+
+#[test]
+fn test_get_state_values_with_multi_proof_chunk_limit() {
+    // Create a request for more keys than the state chunk limit
+    let storage_service_config = StorageServiceConfig {
+        max_state_chunk_size: 2,
+        ..Default::default()
+    };
+    let state_keys = vec![StateKey::raw(vec![]); 3];
+
+    // Verify the request is rejected without touching storage
+    let storage_reader = StorageReader::new(
+        storage_service_config,
+        Arc::new(mock::create_mock_db_reader()),
+    );
+    let error = storage_reader
+        .get_state_values_with_multi_proof(101, state_keys)
+        .unwrap_err();
+    assert_matches!(error, Error::InvalidRequest(_));
+}
+
 /// Creates a set of state keys and values using the specified number and size
 fn create_state_keys_and_values(
     num_keys_and_values: u64,
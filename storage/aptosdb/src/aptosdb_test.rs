@@ -4,14 +4,16 @@
 use crate::{
     get_first_seq_num_and_limit,
     pruner::{LedgerPrunerManager, StateMerklePrunerManager},
+    schema::transaction::TransactionSchema,
     test_helper,
     test_helper::{arb_blocks_to_commit, put_as_state_root, put_transaction_info},
     AptosDB, PrunerManager, StaleNodeIndexSchema,
 };
+use anyhow::Result;
 use aptos_config::config::{
-    EpochSnapshotPrunerConfig, LedgerPrunerConfig, PrunerConfig, RocksdbConfigs,
-    StateMerklePrunerConfig, StorageDirPaths, BUFFERED_STATE_TARGET_ITEMS,
-    DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+    EpochSnapshotPrunerConfig, LedgerArchiveConfig, LedgerPrunerConfig, PrunerConfig,
+    RocksdbConfigs, StateMerklePrunerConfig, StorageDirPaths, BUFFERED_STATE_TARGET_ITEMS,
+    DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD, NO_OP_STORAGE_PRUNER_CONFIG,
 };
 use aptos_crypto::{hash::CryptoHash, HashValue};
 use aptos_storage_interface::{DbReader, ExecutedTrees, Order};
@@ -198,6 +200,7 @@ pub fn test_state_merkle_pruning_impl(
                 prune_window: 10,
                 batch_size: 1,
             },
+            ledger_archive_config: LedgerArchiveConfig::default(),
         },
         RocksdbConfigs::default(),
         false, /* enable_indexer */
@@ -289,3 +292,146 @@ proptest! {
         test_state_merkle_pruning_impl(input);
     }
 }
+
+fn open_archiving_db(tmp_dir: &TempPath) -> AptosDB {
+    AptosDB::open(
+        StorageDirPaths::from_path(tmp_dir),
+        /*readonly=*/ false,
+        PrunerConfig {
+            ledger_archive_config: LedgerArchiveConfig {
+                enable: true,
+                hot_window: 5,
+                batch_size: 3,
+            },
+            ..NO_OP_STORAGE_PRUNER_CONFIG
+        },
+        RocksdbConfigs::default(),
+        false, /* enable_indexer */
+        BUFFERED_STATE_TARGET_ITEMS,
+        DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+    )
+    .unwrap()
+}
+
+fn wait_until(condition: impl Fn() -> bool) {
+    while !condition() {
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+}
+
+pub fn test_ledger_archive_impl(input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>) {
+    let tmp_dir = TempPath::new();
+    let db = open_archiving_db(&tmp_dir);
+
+    let mut in_memory_state = db
+        .state_store
+        .buffered_state()
+        .lock()
+        .current_state()
+        .clone();
+    let mut next_ver: Version = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in input.iter() {
+        test_helper::update_in_memory_state(&mut in_memory_state, txns_to_commit.as_slice());
+        db.save_transactions_for_test(
+            txns_to_commit,
+            next_ver,                /* first_version */
+            next_ver.checked_sub(1), /* base_state_version */
+            Some(ledger_info_with_sigs),
+            true, /* sync_commit */
+            in_memory_state.clone(),
+        )
+        .unwrap();
+        next_ver += txns_to_commit.len() as u64;
+    }
+
+    let target_version = (next_ver - 1).saturating_sub(5);
+    let cold_db = db.ledger_db.cold_db().unwrap();
+    wait_until(|| cold_db.archived_version() >= target_version);
+    assert_eq!(cold_db.archived_version(), target_version);
+
+    // Everything reads the same, whichever tier it's in.
+    let txns_to_commit: Vec<_> = input.iter().flat_map(|(txns, _)| txns).collect();
+    for (version, txn_to_commit) in txns_to_commit.iter().enumerate() {
+        let version = version as Version;
+        assert_eq!(
+            &db.transaction_store.get_transaction(version).unwrap(),
+            txn_to_commit.transaction()
+        );
+        assert_eq!(
+            &db.transaction_store.get_write_set(version).unwrap(),
+            txn_to_commit.write_set()
+        );
+        assert_eq!(
+            &db.ledger_store.get_transaction_info(version).unwrap(),
+            txn_to_commit.transaction_info()
+        );
+        assert_eq!(
+            db.event_store.get_events_by_version(version).unwrap(),
+            txn_to_commit.events()
+        );
+    }
+    let transactions: Vec<_> = db
+        .transaction_store
+        .get_transaction_iter(0, txns_to_commit.len())
+        .unwrap()
+        .collect::<Result<_>>()
+        .unwrap();
+    assert_eq!(
+        transactions,
+        txns_to_commit
+            .iter()
+            .map(|txn| txn.transaction().clone())
+            .collect::<Vec<_>>()
+    );
+    let events: Vec<_> = db
+        .event_store
+        .get_events_by_version_iter(0, txns_to_commit.len())
+        .unwrap()
+        .collect::<Result<_>>()
+        .unwrap();
+    assert_eq!(
+        events,
+        txns_to_commit
+            .iter()
+            .map(|txn| txn.events().to_vec())
+            .collect::<Vec<_>>()
+    );
+
+    // Archived data is gone from the ledger db, right after it's moved, or after a restart if
+    // the process stopped in between.
+    if target_version > 0 {
+        let in_ledger_db = |db: &AptosDB| {
+            db.ledger_db
+                .transaction_db()
+                .get::<TransactionSchema>(&(target_version - 1))
+                .unwrap()
+                .is_some()
+        };
+        wait_until(|| !in_ledger_db(&db));
+        db.ledger_db
+            .transaction_db()
+            .put::<TransactionSchema>(
+                &(target_version - 1),
+                txns_to_commit[target_version as usize - 1].transaction(),
+            )
+            .unwrap();
+        drop(db);
+        let db = open_archiving_db(&tmp_dir);
+        wait_until(|| !in_ledger_db(&db));
+        assert_eq!(
+            &db.transaction_store
+                .get_transaction(target_version - 1)
+                .unwrap(),
+            txns_to_commit[target_version as usize - 1].transaction()
+        );
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(10))]
+
+    #[test]
+    fn test_ledger_archive(input in arb_blocks_to_commit()) {
+        test_ledger_archive_impl(input);
+    }
+}
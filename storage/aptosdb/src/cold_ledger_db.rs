@@ -0,0 +1,248 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! The archive tier of the ledger: transactions, transaction infos, write sets and events older
+//! than the configured window, moved out of the ledger db by the
+//! [`LedgerArchiver`](crate::ledger_archiver::LedgerArchiver). Reads of these go through
+//! [`get_tiered`] and [`TieredIter`], which fall through to the cold db transparently.
+//!
+//! Data below [`ColdLedgerDb::archived_version`] lives in the cold db, data at or above it in the
+//! ledger db. The archiver writes to the cold db, then moves the boundary, and only then deletes
+//! from the ledger db, so a reader that loads the boundary after taking its view of the ledger db
+//! always finds what it's looking for in one of the two.
+
+use crate::{
+    db_options::{cold_ledger_db_column_families, gen_cold_ledger_cfds},
+    metrics::{LEDGER_ARCHIVED_VERSION, LEDGER_TIER_READS},
+    schema::db_metadata::{DbMetadataKey, DbMetadataSchema, DbMetadataValue},
+    utils::get_progress,
+};
+use anyhow::Result;
+use aptos_config::config::RocksdbConfig;
+use aptos_logger::prelude::info;
+use aptos_rocksdb_options::gen_rocksdb_options;
+use aptos_schemadb::{iterator::SchemaIterator, schema::Schema, ReadOptions, SchemaBatch, DB};
+use aptos_types::transaction::{AtomicVersion, Version};
+use std::{
+    path::{Path, PathBuf},
+    sync::{atomic::Ordering, Arc},
+};
+
+pub const COLD_LEDGER_DB_FOLDER_NAME: &str = "cold_ledger_db";
+pub const COLD_LEDGER_DB_NAME: &str = "cold_ledger_db";
+
+/// Keys of the tiered schemas, all ordered by version first.
+pub(crate) trait KeyVersion {
+    fn version(&self) -> Version;
+}
+
+impl KeyVersion for Version {
+    fn version(&self) -> Version {
+        *self
+    }
+}
+
+impl KeyVersion for (Version, u64) {
+    fn version(&self) -> Version {
+        self.0
+    }
+}
+
+#[derive(Debug)]
+pub struct ColdLedgerDb {
+    db: Arc<DB>,
+    archived_version: AtomicVersion,
+}
+
+impl ColdLedgerDb {
+    pub(crate) fn new<P: AsRef<Path>>(
+        db_root_path: P,
+        rocksdb_config: &RocksdbConfig,
+        readonly: bool,
+    ) -> Result<Self> {
+        let path = Self::db_path(db_root_path);
+        let db = if readonly {
+            DB::open_cf_readonly(
+                &gen_rocksdb_options(rocksdb_config, true),
+                path.clone(),
+                COLD_LEDGER_DB_NAME,
+                cold_ledger_db_column_families(),
+            )?
+        } else {
+            DB::open_cf(
+                &gen_rocksdb_options(rocksdb_config, false),
+                path.clone(),
+                COLD_LEDGER_DB_NAME,
+                gen_cold_ledger_cfds(rocksdb_config),
+            )?
+        };
+        let archived_version =
+            get_progress(&db, &DbMetadataKey::LedgerArchiveProgress)?.unwrap_or(0);
+        LEDGER_ARCHIVED_VERSION.set(archived_version as i64);
+
+        info!(
+            archived_version = archived_version,
+            "Opened {COLD_LEDGER_DB_NAME} at {path:?}!"
+        );
+
+        Ok(Self {
+            db: Arc::new(db),
+            archived_version: AtomicVersion::new(archived_version),
+        })
+    }
+
+    /// Whether a cold db has been created under `db_root_path` before.
+    pub(crate) fn exists<P: AsRef<Path>>(db_root_path: P) -> bool {
+        Self::db_path(db_root_path).exists()
+    }
+
+    fn db_path<P: AsRef<Path>>(db_root_path: P) -> PathBuf {
+        db_root_path.as_ref().join(COLD_LEDGER_DB_FOLDER_NAME)
+    }
+
+    pub(crate) fn db(&self) -> &DB {
+        &self.db
+    }
+
+    /// Ledger data below this version is in the cold db.
+    pub(crate) fn archived_version(&self) -> Version {
+        self.archived_version.load(Ordering::SeqCst)
+    }
+
+    /// Writes a batch of archived data together with the new boundary, then moves the boundary
+    /// for readers.
+    pub(crate) fn write_archived(
+        &self,
+        batch: SchemaBatch,
+        archived_version: Version,
+    ) -> Result<()> {
+        batch.put::<DbMetadataSchema>(
+            &DbMetadataKey::LedgerArchiveProgress,
+            &DbMetadataValue::Version(archived_version),
+        )?;
+        self.db.write_schemas(batch)?;
+        self.archived_version
+            .store(archived_version, Ordering::SeqCst);
+        LEDGER_ARCHIVED_VERSION.set(archived_version as i64);
+        Ok(())
+    }
+}
+
+/// Point read of a tiered schema.
+pub(crate) fn get_tiered<S>(
+    hot_db: &DB,
+    cold_db: Option<&ColdLedgerDb>,
+    key: &S::Key,
+) -> Result<Option<S::Value>>
+where
+    S: Schema,
+    S::Key: KeyVersion,
+{
+    if let Some(cold_db) = cold_db {
+        if key.version() < cold_db.archived_version() {
+            return get_cold::<S>(cold_db, key);
+        }
+    }
+    let value = hot_db.get::<S>(key)?;
+    if value.is_some() {
+        LEDGER_TIER_READS
+            .with_label_values(&[S::COLUMN_FAMILY_NAME, "hot"])
+            .inc();
+        return Ok(value);
+    }
+    match cold_db {
+        // Archived and deleted from the ledger db since the boundary was checked.
+        Some(cold_db) if key.version() < cold_db.archived_version() => get_cold::<S>(cold_db, key),
+        _ => Ok(None),
+    }
+}
+
+fn get_cold<S>(cold_db: &ColdLedgerDb, key: &S::Key) -> Result<Option<S::Value>>
+where
+    S: Schema,
+{
+    LEDGER_TIER_READS
+        .with_label_values(&[S::COLUMN_FAMILY_NAME, "cold"])
+        .inc();
+    cold_db.db.get::<S>(key)
+}
+
+/// Iterates a tiered schema from a key on, yielding what's in the cold db below the archived
+/// version, followed by what's in the ledger db from there.
+pub(crate) struct TieredIter<'a, S: Schema> {
+    cold_iter: Option<SchemaIterator<'a, S>>,
+    hot_iter: SchemaIterator<'a, S>,
+    archived_version: Version,
+}
+
+impl<'a, S> TieredIter<'a, S>
+where
+    S: Schema,
+    S::Key: KeyVersion,
+{
+    pub(crate) fn new(
+        hot_db: &'a DB,
+        cold_db: Option<&'a ColdLedgerDb>,
+        seek_key: &S::Key,
+    ) -> Result<Self> {
+        // The ledger db iterator reads from an implicit snapshot taken on creation, the boundary
+        // must be loaded after that.
+        let mut hot_iter = hot_db.iter::<S>(ReadOptions::default())?;
+        hot_iter.seek(seek_key)?;
+        let (cold_iter, archived_version) = match cold_db {
+            Some(cold_db) => {
+                let archived_version = cold_db.archived_version();
+                let cold_iter = if seek_key.version() < archived_version {
+                    let mut iter = cold_db.db.iter::<S>(ReadOptions::default())?;
+                    iter.seek(seek_key)?;
+                    Some(iter)
+                } else {
+                    None
+                };
+                (cold_iter, archived_version)
+            },
+            None => (None, 0),
+        };
+        Ok(Self {
+            cold_iter,
+            hot_iter,
+            archived_version,
+        })
+    }
+
+    fn next_impl(&mut self) -> Result<Option<(S::Key, S::Value)>> {
+        if let Some(cold_iter) = self.cold_iter.as_mut() {
+            match cold_iter.next().transpose()? {
+                Some((key, value)) if key.version() < self.archived_version => {
+                    LEDGER_TIER_READS
+                        .with_label_values(&[S::COLUMN_FAMILY_NAME, "cold"])
+                        .inc();
+                    return Ok(Some((key, value)));
+                },
+                _ => self.cold_iter = None,
+            }
+        }
+        // Skips what's archived but not yet deleted from the ledger db.
+        while let Some((key, value)) = self.hot_iter.next().transpose()? {
+            if key.version() >= self.archived_version {
+                LEDGER_TIER_READS
+                    .with_label_values(&[S::COLUMN_FAMILY_NAME, "hot"])
+                    .inc();
+                return Ok(Some((key, value)));
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl<'a, S> Iterator for TieredIter<'a, S>
+where
+    S: Schema,
+    S::Key: KeyVersion,
+{
+    type Item = Result<(S::Key, S::Value)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_impl().transpose()
+    }
+}
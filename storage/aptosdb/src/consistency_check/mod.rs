@@ -0,0 +1,664 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Cross checks the ledger, its indices and the sharded state DBs against each other, collecting
+//! every inconsistency found instead of stopping at the first one. Each check covers a version
+//! range, an index or a state shard, so callers can run them in parallel and resume.
+
+#[cfg(test)]
+mod test;
+
+use crate::{
+    schema::{
+        db_metadata::DbMetadataKey, event_by_key::EventByKeySchema,
+        event_by_version::EventByVersionSchema, jellyfish_merkle_node::JellyfishMerkleNodeSchema,
+        state_value::StateValueSchema, transaction_accumulator::TransactionAccumulatorSchema,
+        transaction_by_account::TransactionByAccountSchema,
+        transaction_by_hash::TransactionByHashSchema,
+    },
+    utils::get_progress,
+    AptosDB,
+};
+use anyhow::{ensure, Result};
+use aptos_crypto::{hash::CryptoHash, HashValue};
+use aptos_jellyfish_merkle::{
+    node_type::{LeafNode, Node, NodeKey},
+    TreeReader,
+};
+use aptos_schemadb::{schema::Schema, ReadOptions, DB};
+use aptos_types::{
+    contract_event::ContractEvent,
+    event::EventKey,
+    nibble::Nibble,
+    proof::{accumulator::InMemoryTransactionAccumulator, position::Position},
+    state_store::state_key::StateKey,
+    transaction::{Transaction, TransactionInfo, Version},
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConsistencyCheck {
+    /// Transaction infos vs. the accumulator leaves and roots, and the latest ledger info.
+    TransactionAccumulator,
+    /// Transactions vs. the transaction hashes in their infos.
+    TransactionHash,
+    /// State checkpoint hashes in transaction infos vs. the JMT roots.
+    StateCheckpointHash,
+    TransactionByHash,
+    TransactionByAccount,
+    EventByKey,
+    EventByVersion,
+    /// State KV shard progress and key placement, and the values the JMT leaves point to.
+    StateKvShard,
+    /// State Merkle shard progress and the JMT nodes under the shard.
+    StateMerkleShard,
+}
+
+/// An index that can be scanned for entries pointing to data that doesn't match them.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LedgerIndex {
+    TransactionByHash,
+    TransactionByAccount,
+    EventByKey,
+    EventByVersion,
+}
+
+impl LedgerIndex {
+    pub const ALL: [LedgerIndex; 4] = [
+        LedgerIndex::TransactionByHash,
+        LedgerIndex::TransactionByAccount,
+        LedgerIndex::EventByKey,
+        LedgerIndex::EventByVersion,
+    ];
+
+    fn check(&self) -> ConsistencyCheck {
+        match self {
+            LedgerIndex::TransactionByHash => ConsistencyCheck::TransactionByHash,
+            LedgerIndex::TransactionByAccount => ConsistencyCheck::TransactionByAccount,
+            LedgerIndex::EventByKey => ConsistencyCheck::EventByKey,
+            LedgerIndex::EventByVersion => ConsistencyCheck::EventByVersion,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Mismatch {
+    pub check: ConsistencyCheck,
+    pub version: Option<Version>,
+    pub shard_id: Option<u8>,
+    pub detail: String,
+}
+
+impl Mismatch {
+    fn at_version(check: ConsistencyCheck, version: Version, detail: String) -> Self {
+        Self {
+            check,
+            version: Some(version),
+            shard_id: None,
+            detail,
+        }
+    }
+
+    fn in_shard(check: ConsistencyCheck, shard_id: u8, detail: String) -> Self {
+        Self {
+            check,
+            version: None,
+            shard_id: Some(shard_id),
+            detail,
+        }
+    }
+}
+
+impl AptosDB {
+    /// Checks transactions, transaction infos and events in `[start_version, end_version)`
+    /// against the transaction accumulator, the JMT roots and the ledger indices.
+    pub fn check_ledger_consistency(
+        &self,
+        start_version: Version,
+        end_version: Version,
+    ) -> Result<Vec<Mismatch>> {
+        ensure!(
+            start_version < end_version,
+            "Empty version range [{}, {}).",
+            start_version,
+            end_version,
+        );
+        let num_versions = (end_version - start_version) as usize;
+        let mut mismatches = Vec::new();
+
+        let txn_infos = self
+            .ledger_store
+            .get_transaction_info_iter(start_version, num_versions)?
+            .collect::<Result<Vec<_>>>()?;
+        self.check_transaction_accumulator(start_version, &txn_infos, &mut mismatches)?;
+
+        let txn_iter = self
+            .transaction_store
+            .get_transaction_iter(start_version, num_versions)?;
+        let events_iter = self
+            .event_store
+            .get_events_by_version_iter(start_version, num_versions)?;
+        for (((version, txn_info), txn), events) in (start_version..)
+            .zip(&txn_infos)
+            .zip(txn_iter)
+            .zip(events_iter)
+        {
+            self.check_transaction(version, txn_info, &txn?, &mut mismatches)?;
+            self.check_events(version, &events?, &mut mismatches)?;
+            self.check_state_checkpoint(version, txn_info, &mut mismatches)?;
+        }
+
+        Ok(mismatches)
+    }
+
+    /// Scans the whole `index`, checking that each entry pointing into
+    /// `[start_version, end_version)` matches the data it points to.
+    pub fn check_index_consistency(
+        &self,
+        index: LedgerIndex,
+        start_version: Version,
+        end_version: Version,
+    ) -> Result<Vec<Mismatch>> {
+        let in_range = |version: Version| (start_version..end_version).contains(&version);
+        let check = index.check();
+        let mut mismatches = Vec::new();
+
+        match index {
+            LedgerIndex::TransactionByHash => {
+                for res in scan::<TransactionByHashSchema>(self.ledger_db.transaction_db())? {
+                    let (hash, version) = res?;
+                    if !in_range(version) {
+                        continue;
+                    }
+                    let detail = match self.transaction_store.get_transaction(version) {
+                        Ok(txn) if txn.hash() == hash => continue,
+                        Ok(txn) => format!("{} indexed, found {}.", hash, txn.hash()),
+                        Err(err) => format!("{} indexed, found {}.", hash, err),
+                    };
+                    mismatches.push(Mismatch::at_version(check, version, detail));
+                }
+            },
+            LedgerIndex::TransactionByAccount => {
+                for res in scan::<TransactionByAccountSchema>(self.ledger_db.transaction_db())? {
+                    let ((sender, seq_num), version) = res?;
+                    if !in_range(version) {
+                        continue;
+                    }
+                    let found = self.transaction_store.get_transaction(version).map(|txn| {
+                        txn.try_as_signed_user_txn()
+                            .map(|txn| (txn.sender(), txn.sequence_number()))
+                    });
+                    let detail = match found {
+                        Ok(Some(found)) if found == (sender, seq_num) => continue,
+                        Ok(found) => format!("{:?} indexed, found {:?}.", (sender, seq_num), found),
+                        Err(err) => format!("{:?} indexed, found {}.", (sender, seq_num), err),
+                    };
+                    mismatches.push(Mismatch::at_version(check, version, detail));
+                }
+            },
+            LedgerIndex::EventByKey => {
+                for res in scan::<EventByKeySchema>(self.ledger_db.event_db())? {
+                    let ((key, seq_num), (version, idx)) = res?;
+                    if !in_range(version) {
+                        continue;
+                    }
+                    if let Some(detail) = self.mismatched_event(version, idx, &key, seq_num) {
+                        mismatches.push(Mismatch::at_version(check, version, detail));
+                    }
+                }
+            },
+            LedgerIndex::EventByVersion => {
+                for res in scan::<EventByVersionSchema>(self.ledger_db.event_db())? {
+                    let ((key, version, seq_num), idx) = res?;
+                    if !in_range(version) {
+                        continue;
+                    }
+                    if let Some(detail) = self.mismatched_event(version, idx, &key, seq_num) {
+                        mismatches.push(Mismatch::at_version(check, version, detail));
+                    }
+                }
+            },
+        }
+
+        Ok(mismatches)
+    }
+
+    /// Checks that state KV shard `shard_id` and state Merkle shard `shard_id` agree with the
+    /// metadata DBs and each other. If there's a state snapshot, walks the part of the JMT under
+    /// the shard, verifying every node hash, and that the value of every leaf is in state KV.
+    pub fn check_state_shard_consistency(
+        &self,
+        shard_id: u8,
+        snapshot_version: Option<Version>,
+    ) -> Result<Vec<Mismatch>> {
+        let state_merkle_db = &self.state_store.state_db.state_merkle_db;
+        ensure!(
+            shard_id < self.state_kv_db.num_shards(),
+            "Shard {} doesn't exist.",
+            shard_id,
+        );
+        let mut mismatches = Vec::new();
+
+        if self.state_kv_db.enabled_sharding() {
+            check_shard_progress(
+                ConsistencyCheck::StateKvShard,
+                shard_id,
+                self.state_kv_db.metadata_db(),
+                &DbMetadataKey::StateKvCommitProgress,
+                self.state_kv_db.db_shard(shard_id),
+                &DbMetadataKey::StateKvShardCommitProgress(shard_id as usize),
+                &mut mismatches,
+            )?;
+            for res in scan::<StateValueSchema>(self.state_kv_db.db_shard(shard_id))? {
+                let ((state_key, version), _value) = res?;
+                if state_key.get_shard_id() != shard_id {
+                    mismatches.push(Mismatch::in_shard(
+                        ConsistencyCheck::StateKvShard,
+                        shard_id,
+                        format!(
+                            "{:?} at version {} belongs to shard {}.",
+                            state_key,
+                            version,
+                            state_key.get_shard_id()
+                        ),
+                    ));
+                }
+            }
+        }
+        if state_merkle_db.sharding_enabled() {
+            check_shard_progress(
+                ConsistencyCheck::StateMerkleShard,
+                shard_id,
+                state_merkle_db.metadata_db(),
+                &DbMetadataKey::StateMerkleCommitProgress,
+                state_merkle_db.db_shard(shard_id),
+                &DbMetadataKey::StateMerkleShardCommitProgress(shard_id as usize),
+                &mut mismatches,
+            )?;
+        }
+
+        if let Some(version) = snapshot_version {
+            self.check_state_merkle_shard(shard_id, version, &mut mismatches)?;
+        }
+
+        Ok(mismatches)
+    }
+
+    fn check_transaction_accumulator(
+        &self,
+        start_version: Version,
+        txn_infos: &[TransactionInfo],
+        mismatches: &mut Vec<Mismatch>,
+    ) -> Result<()> {
+        let check = ConsistencyCheck::TransactionAccumulator;
+        let leaves: Vec<HashValue> = txn_infos.iter().map(CryptoHash::hash).collect();
+        for (version, leaf) in (start_version..).zip(&leaves) {
+            let stored = self
+                .ledger_db
+                .transaction_accumulator_db()
+                .get::<TransactionAccumulatorSchema>(&Position::from_leaf_index(version))?;
+            if stored.as_ref() != Some(leaf) {
+                mismatches.push(Mismatch::at_version(
+                    check,
+                    version,
+                    format!("Leaf is {:?}, transaction info hash is {}.", stored, leaf),
+                ));
+            }
+        }
+
+        let accumulator = InMemoryTransactionAccumulator::new(
+            self.ledger_store.get_frozen_subtree_hashes(start_version)?,
+            start_version,
+        )?;
+        let last_version = start_version + leaves.len() as u64 - 1;
+        let root_hash = accumulator.append(&leaves).root_hash();
+        match self.ledger_store.get_root_hash(last_version) {
+            Ok(stored) if stored == root_hash => (),
+            res => mismatches.push(Mismatch::at_version(
+                check,
+                last_version,
+                format!("Stored root is {:?}, recomputed {}.", res, root_hash),
+            )),
+        }
+
+        if let Some(ledger_info_with_sigs) = self.ledger_store.get_latest_ledger_info_option() {
+            let ledger_info = ledger_info_with_sigs.ledger_info();
+            if (start_version..=last_version).contains(&ledger_info.version()) {
+                let num_leaves = (ledger_info.version() - start_version + 1) as usize;
+                let root_hash = accumulator.append(&leaves[..num_leaves]).root_hash();
+                if root_hash != ledger_info.transaction_accumulator_hash() {
+                    mismatches.push(Mismatch::at_version(
+                        check,
+                        ledger_info.version(),
+                        format!(
+                            "Latest ledger info has root {}, recomputed {}.",
+                            ledger_info.transaction_accumulator_hash(),
+                            root_hash,
+                        ),
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_transaction(
+        &self,
+        version: Version,
+        txn_info: &TransactionInfo,
+        txn: &Transaction,
+        mismatches: &mut Vec<Mismatch>,
+    ) -> Result<()> {
+        let hash = txn.hash();
+        if hash != txn_info.transaction_hash() {
+            mismatches.push(Mismatch::at_version(
+                ConsistencyCheck::TransactionHash,
+                version,
+                format!(
+                    "Transaction hash is {}, transaction info has {}.",
+                    hash,
+                    txn_info.transaction_hash()
+                ),
+            ));
+        }
+
+        let transaction_db = self.ledger_db.transaction_db();
+        let indexed = transaction_db.get::<TransactionByHashSchema>(&hash)?;
+        if indexed != Some(version) {
+            mismatches.push(Mismatch::at_version(
+                ConsistencyCheck::TransactionByHash,
+                version,
+                format!("{} is indexed at {:?}.", hash, indexed),
+            ));
+        }
+
+        if let Some(user_txn) = txn.try_as_signed_user_txn() {
+            let key = (user_txn.sender(), user_txn.sequence_number());
+            let indexed = transaction_db.get::<TransactionByAccountSchema>(&key)?;
+            if indexed != Some(version) {
+                mismatches.push(Mismatch::at_version(
+                    ConsistencyCheck::TransactionByAccount,
+                    version,
+                    format!("{:?} is indexed at {:?}.", key, indexed),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_events(
+        &self,
+        version: Version,
+        events: &[ContractEvent],
+        mismatches: &mut Vec<Mismatch>,
+    ) -> Result<()> {
+        let event_db = self.ledger_db.event_db();
+        for (idx, event) in events.iter().enumerate() {
+            let idx = idx as u64;
+            if let ContractEvent::V1(v1) = event {
+                let indexed =
+                    event_db.get::<EventByKeySchema>(&(*v1.key(), v1.sequence_number()))?;
+                if indexed != Some((version, idx)) {
+                    mismatches.push(Mismatch::at_version(
+                        ConsistencyCheck::EventByKey,
+                        version,
+                        format!(
+                            "Event {} ({:?}, {}) is indexed at {:?}.",
+                            idx,
+                            v1.key(),
+                            v1.sequence_number(),
+                            indexed
+                        ),
+                    ));
+                }
+                let indexed = event_db.get::<EventByVersionSchema>(&(
+                    *v1.key(),
+                    version,
+                    v1.sequence_number(),
+                ))?;
+                if indexed != Some(idx) {
+                    mismatches.push(Mismatch::at_version(
+                        ConsistencyCheck::EventByVersion,
+                        version,
+                        format!(
+                            "Event {} ({:?}, {}) is indexed as {:?}.",
+                            idx,
+                            v1.key(),
+                            v1.sequence_number(),
+                            indexed
+                        ),
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Only checkpoints whose JMT root is persisted can be checked, i.e. the snapshots.
+    fn check_state_checkpoint(
+        &self,
+        version: Version,
+        txn_info: &TransactionInfo,
+        mismatches: &mut Vec<Mismatch>,
+    ) -> Result<()> {
+        if let Some(expected) = txn_info.state_checkpoint_hash() {
+            let root_key = NodeKey::new_empty_path(version);
+            if let Some(root) = self
+                .state_store
+                .state_db
+                .state_merkle_db
+                .metadata_db()
+                .get::<JellyfishMerkleNodeSchema>(&root_key)?
+            {
+                if root.hash() != expected {
+                    mismatches.push(Mismatch::at_version(
+                        ConsistencyCheck::StateCheckpointHash,
+                        version,
+                        format!(
+                            "State checkpoint hash is {}, JMT root is {}.",
+                            expected,
+                            root.hash()
+                        ),
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns what's wrong with the event indexed as `(key, seq_num)` at `(version, idx)`, if
+    /// anything.
+    fn mismatched_event(
+        &self,
+        version: Version,
+        idx: u64,
+        key: &EventKey,
+        seq_num: u64,
+    ) -> Option<String> {
+        let found = self
+            .event_store
+            .get_event_by_version_and_index(version, idx)
+            .map(|event| match event {
+                ContractEvent::V1(v1) => Some((*v1.key(), v1.sequence_number())),
+                ContractEvent::V2(_) => None,
+            });
+        match found {
+            Ok(Some(found)) if found == (*key, seq_num) => None,
+            Ok(found) => Some(format!(
+                "Event {} ({:?}, {}) indexed, found {:?}.",
+                idx, key, seq_num, found
+            )),
+            Err(err) => Some(format!(
+                "Event {} ({:?}, {}) indexed, found {}.",
+                idx, key, seq_num, err
+            )),
+        }
+    }
+
+    fn check_state_merkle_shard(
+        &self,
+        shard_id: u8,
+        version: Version,
+        mismatches: &mut Vec<Mismatch>,
+    ) -> Result<()> {
+        let state_merkle_db = &self.state_store.state_db.state_merkle_db;
+        let root_key = NodeKey::new_empty_path(version);
+        let root: Node<StateKey> = match state_merkle_db.get_node_option(&root_key, "verify")? {
+            Some(root) => root,
+            None => {
+                mismatches.push(Mismatch::in_shard(
+                    ConsistencyCheck::StateMerkleShard,
+                    shard_id,
+                    format!("Root of the snapshot at version {} is missing.", version),
+                ));
+                return Ok(());
+            },
+        };
+
+        // Nodes still to visit, along with the child entries in their parents pointing to them.
+        let mut pending = Vec::new();
+        match root {
+            Node::Internal(internal) => {
+                let nibble = Nibble::from(shard_id);
+                if let Some(child) = internal.child(nibble) {
+                    pending.push((
+                        root_key.gen_child_node_key(child.version, nibble),
+                        child.clone(),
+                    ));
+                }
+            },
+            Node::Leaf(leaf) => {
+                if leaf.account_key().nibble(0) == shard_id {
+                    self.check_state_leaf(shard_id, &leaf, mismatches)?;
+                }
+            },
+            Node::Null => (),
+        }
+
+        while let Some((node_key, child)) = pending.pop() {
+            let node: Node<StateKey> = match state_merkle_db.get_node_option(&node_key, "verify")? {
+                Some(node) => node,
+                None => {
+                    mismatches.push(Mismatch::in_shard(
+                        ConsistencyCheck::StateMerkleShard,
+                        shard_id,
+                        format!("{:?} is missing.", node_key),
+                    ));
+                    continue;
+                },
+            };
+            if node.hash() != child.hash || node.node_type() != child.node_type {
+                mismatches.push(Mismatch::in_shard(
+                    ConsistencyCheck::StateMerkleShard,
+                    shard_id,
+                    format!(
+                        "{:?} is {:?} with hash {}, its parent expects {:?} with hash {}.",
+                        node_key,
+                        node.node_type(),
+                        node.hash(),
+                        child.node_type,
+                        child.hash,
+                    ),
+                ));
+            }
+            match node {
+                Node::Internal(internal) => {
+                    for (nibble, child) in internal.children_sorted() {
+                        pending.push((
+                            node_key.gen_child_node_key(child.version, *nibble),
+                            child.clone(),
+                        ));
+                    }
+                },
+                Node::Leaf(leaf) => self.check_state_leaf(shard_id, &leaf, mismatches)?,
+                Node::Null => mismatches.push(Mismatch::in_shard(
+                    ConsistencyCheck::StateMerkleShard,
+                    shard_id,
+                    format!("{:?} is a null node.", node_key),
+                )),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_state_leaf(
+        &self,
+        shard_id: u8,
+        leaf: &LeafNode<StateKey>,
+        mismatches: &mut Vec<Mismatch>,
+    ) -> Result<()> {
+        let (state_key, version) = leaf.value_index();
+        if state_key.hash() != leaf.account_key() {
+            mismatches.push(Mismatch::in_shard(
+                ConsistencyCheck::StateMerkleShard,
+                shard_id,
+                format!(
+                    "Leaf {} points to {:?}, which hashes to {}.",
+                    leaf.account_key(),
+                    state_key,
+                    state_key.hash()
+                ),
+            ));
+        }
+
+        let value = self
+            .state_kv_db
+            .db_shard(state_key.get_shard_id())
+            .get::<StateValueSchema>(&(state_key.clone(), *version))?;
+        let value_hash = value
+            .as_ref()
+            .map(|value| value.as_ref().map(CryptoHash::hash));
+        if value_hash != Some(Some(leaf.value_hash())) {
+            mismatches.push(Mismatch {
+                check: ConsistencyCheck::StateKvShard,
+                version: Some(*version),
+                shard_id: Some(shard_id),
+                detail: format!(
+                    "Leaf of {:?} has value hash {}, state KV has {:?}.",
+                    state_key,
+                    leaf.value_hash(),
+                    value_hash,
+                ),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+fn scan<S: Schema>(db: &DB) -> Result<impl Iterator<Item = Result<(S::Key, S::Value)>> + '_> {
+    let mut iter = db.iter::<S>(ReadOptions::default())?;
+    iter.seek_to_first();
+    Ok(iter)
+}
+
+fn check_shard_progress(
+    check: ConsistencyCheck,
+    shard_id: u8,
+    metadata_db: &DB,
+    progress_key: &DbMetadataKey,
+    shard_db: &DB,
+    shard_progress_key: &DbMetadataKey,
+    mismatches: &mut Vec<Mismatch>,
+) -> Result<()> {
+    let progress = get_progress(metadata_db, progress_key)?;
+    let shard_progress = get_progress(shard_db, shard_progress_key)?;
+    if shard_progress != progress {
+        mismatches.push(Mismatch::in_shard(
+            check,
+            shard_id,
+            format!(
+                "Shard is at version {:?}, the metadata DB at {:?}.",
+                shard_progress, progress
+            ),
+        ));
+    }
+    Ok(())
+}
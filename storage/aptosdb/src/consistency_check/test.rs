@@ -0,0 +1,97 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    consistency_check::{ConsistencyCheck, LedgerIndex, Mismatch},
+    schema::transaction_by_hash::TransactionByHashSchema,
+    test_helper::{arb_blocks_to_commit, update_in_memory_state},
+    AptosDB, NUM_STATE_SHARDS,
+};
+use aptos_crypto::{hash::CryptoHash, HashValue};
+use aptos_storage_interface::DbReader;
+use aptos_temppath::TempPath;
+use aptos_types::{
+    ledger_info::LedgerInfoWithSignatures,
+    transaction::{TransactionToCommit, Version},
+};
+use proptest::prelude::*;
+
+fn check_all(db: &AptosDB, end_version: Version) -> Vec<Mismatch> {
+    let mut mismatches = db.check_ledger_consistency(0, end_version).unwrap();
+    for index in LedgerIndex::ALL {
+        mismatches.extend(db.check_index_consistency(index, 0, end_version).unwrap());
+    }
+    let snapshot_version = db
+        .get_state_snapshot_before(Version::MAX)
+        .unwrap()
+        .map(|(version, _)| version);
+    for shard_id in 0..NUM_STATE_SHARDS as u8 {
+        mismatches.extend(
+            db.check_state_shard_consistency(shard_id, snapshot_version)
+                .unwrap(),
+        );
+    }
+    mismatches
+}
+
+fn test_consistency_check_impl(input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>) {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+    let mut in_memory_state = db
+        .state_store
+        .buffered_state()
+        .lock()
+        .current_state()
+        .clone();
+    let mut next_ver: Version = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in input.iter() {
+        update_in_memory_state(&mut in_memory_state, txns_to_commit.as_slice());
+        db.save_transactions_for_test(
+            txns_to_commit,
+            next_ver,                /* first_version */
+            next_ver.checked_sub(1), /* base_state_version */
+            Some(ledger_info_with_sigs),
+            true, /* sync_commit */
+            in_memory_state.clone(),
+        )
+        .unwrap();
+        next_ver += txns_to_commit.len() as u64;
+    }
+
+    assert_eq!(check_all(&db, next_ver), vec![]);
+    // Chunks are checked independently.
+    for version in 0..next_ver {
+        assert_eq!(
+            db.check_ledger_consistency(version, version + 1).unwrap(),
+            vec![]
+        );
+    }
+
+    // Move the first transaction to a version it isn't at in the hash index.
+    let txn_hash = db.transaction_store.get_transaction(0).unwrap().hash();
+    db.ledger_db
+        .transaction_db()
+        .put::<TransactionByHashSchema>(&txn_hash, &next_ver)
+        .unwrap();
+    // And make up an entry in range pointing to it.
+    db.ledger_db
+        .transaction_db()
+        .put::<TransactionByHashSchema>(&HashValue::random(), &0)
+        .unwrap();
+
+    let mismatches = check_all(&db, next_ver);
+    assert_eq!(mismatches.len(), 2);
+    for mismatch in mismatches {
+        assert_eq!(mismatch.check, ConsistencyCheck::TransactionByHash);
+        assert_eq!(mismatch.version, Some(0));
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(10))]
+
+    #[test]
+    fn test_consistency_check(input in arb_blocks_to_commit()) {
+        test_consistency_check_impl(input);
+    }
+}
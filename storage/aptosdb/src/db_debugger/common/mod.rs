@@ -1,9 +1,14 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{db_debugger::ShardingConfig, ledger_db::LedgerDb, state_merkle_db::StateMerkleDb};
+use crate::{
+    db_debugger::ShardingConfig, ledger_db::LedgerDb, state_merkle_db::StateMerkleDb, AptosDB,
+};
 use anyhow::Result;
-use aptos_config::config::{RocksdbConfigs, StorageDirPaths};
+use aptos_config::config::{
+    RocksdbConfigs, StorageDirPaths, BUFFERED_STATE_TARGET_ITEMS,
+    DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD, NO_OP_STORAGE_PRUNER_CONFIG,
+};
 use aptos_types::nibble::{nibble_path::NibblePath, Nibble};
 use clap::Parser;
 use core::default::Default;
@@ -44,6 +49,22 @@ impl DbDir {
             true,
         )
     }
+
+    /// Opens the whole DB readonly, with the pruners not running.
+    pub fn open_aptos_db(&self) -> Result<AptosDB> {
+        AptosDB::open(
+            StorageDirPaths::from_path(&self.db_dir),
+            /*readonly=*/ true,
+            NO_OP_STORAGE_PRUNER_CONFIG,
+            RocksdbConfigs {
+                enable_storage_sharding: self.sharding_config.enable_storage_sharding,
+                ..Default::default()
+            },
+            /*enable_indexer=*/ false,
+            BUFFERED_STATE_TARGET_ITEMS,
+            DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+        )
+    }
 }
 
 impl AsRef<Path> for DbDir {
@@ -0,0 +1,33 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::db_debugger::common::DbDir;
+use anyhow::Result;
+use clap::Parser;
+
+#[derive(Parser)]
+#[clap(
+    about = "Sample the oldest state snapshots the pruners are supposed to keep, and the latest, \
+    checking that no JMT node or state value still reachable from them has been pruned."
+)]
+pub struct Cmd {
+    #[clap(flatten)]
+    db_dir: DbDir,
+
+    #[clap(long, default_value_t = 1000)]
+    num_samples: usize,
+}
+
+impl Cmd {
+    pub fn run(self) -> Result<()> {
+        let db = self.db_dir.open_aptos_db()?;
+
+        let versions = db.check_pruned_state(self.num_samples)?;
+        println!(
+            "Checked {} samples at each of the state snapshots at versions {:?}.",
+            self.num_samples, versions
+        );
+
+        Ok(())
+    }
+}
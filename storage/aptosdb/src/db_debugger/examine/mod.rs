@@ -1,7 +1,9 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+mod check_pruned_state;
 mod print_db_versions;
+mod print_pruner_status;
 
 use anyhow::Result;
 
@@ -9,12 +11,16 @@ use anyhow::Result;
 #[clap(about = "Examine databases.")]
 pub enum Cmd {
     PrintDbVersions(print_db_versions::Cmd),
+    PrintPrunerStatus(print_pruner_status::Cmd),
+    CheckPrunedState(check_pruned_state::Cmd),
 }
 
 impl Cmd {
     pub fn run(self) -> Result<()> {
         match self {
             Self::PrintDbVersions(cmd) => cmd.run(),
+            Self::PrintPrunerStatus(cmd) => cmd.run(),
+            Self::CheckPrunedState(cmd) => cmd.run(),
         }
     }
 }
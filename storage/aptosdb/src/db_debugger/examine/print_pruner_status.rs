@@ -0,0 +1,42 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::db_debugger::common::DbDir;
+use anyhow::Result;
+use aptos_storage_interface::DbReader;
+use clap::Parser;
+
+#[derive(Parser)]
+#[clap(
+    about = "Print how far each pruner has progressed, per shard if sharded, and an estimate of the \
+    space to be reclaimed."
+)]
+pub struct Cmd {
+    #[clap(flatten)]
+    db_dir: DbDir,
+}
+
+impl Cmd {
+    pub fn run(self) -> Result<()> {
+        // Opened with the pruners disabled, so the target version shown is the progress.
+        let db = self.db_dir.open_aptos_db()?;
+
+        for status in db.get_pruner_status()? {
+            println!(
+                "{}: min_readable_version: {}, progress: {}, estimated_reclaimable_bytes: {}",
+                status.name,
+                status.min_readable_version,
+                status.progress,
+                status.estimated_reclaimable_bytes,
+            );
+            for shard in status.shards {
+                println!(
+                    "    shard {}: progress: {}, estimated_reclaimable_bytes: {}",
+                    shard.shard_id, shard.progress, shard.estimated_reclaimable_bytes,
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
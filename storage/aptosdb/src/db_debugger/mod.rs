@@ -5,8 +5,10 @@ pub mod checkpoint;
 mod common;
 mod examine;
 pub mod ledger;
+pub mod snapshot_bundle;
 pub mod state_tree;
 pub mod truncate;
+pub mod verify_all;
 
 use anyhow::Result;
 use clap::Parser;
@@ -31,6 +33,11 @@ pub enum Cmd {
 
     #[clap(subcommand)]
     Examine(examine::Cmd),
+
+    VerifyAll(verify_all::Cmd),
+
+    #[clap(subcommand)]
+    SnapshotBundle(snapshot_bundle::Cmd),
 }
 
 impl Cmd {
@@ -41,6 +48,8 @@ impl Cmd {
             Cmd::Ledger(cmd) => cmd.run(),
             Cmd::Truncate(cmd) => cmd.run(),
             Cmd::Examine(cmd) => cmd.run(),
+            Cmd::VerifyAll(cmd) => cmd.run(),
+            Cmd::SnapshotBundle(cmd) => cmd.run(),
         }
     }
 }
@@ -0,0 +1,84 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{db_debugger::common::DbDir, snapshot_bundle::SnapshotBundle};
+use anyhow::Result;
+use aptos_types::waypoint::Waypoint;
+use clap::Parser;
+use std::path::PathBuf;
+
+#[derive(clap::Subcommand)]
+#[clap(about = "Make and verify snapshot bundles to bootstrap nodes from.")]
+pub enum Cmd {
+    Create(CreateCmd),
+    Verify(VerifyCmd),
+}
+
+impl Cmd {
+    pub fn run(self) -> Result<()> {
+        match self {
+            Self::Create(cmd) => cmd.run(),
+            Self::Verify(cmd) => cmd.run(),
+        }
+    }
+}
+
+#[derive(Parser)]
+#[clap(
+    about = "Make a snapshot bundle: a DB checkpoint, the latest ledger info with its epoch change \
+    proof and a manifest of the root hashes."
+)]
+pub struct CreateCmd {
+    #[clap(flatten)]
+    db_dir: DbDir,
+
+    #[clap(long, value_parser)]
+    output_dir: PathBuf,
+
+    /// The epoch change proof starts here, which has to be at or before the epoch of the waypoint
+    /// the bundle is verified against.
+    #[clap(long, default_value_t = 0)]
+    start_epoch: u64,
+}
+
+impl CreateCmd {
+    pub fn run(self) -> Result<()> {
+        let enable_storage_sharding = self.db_dir.sharding_config.enable_storage_sharding;
+        let bundle = SnapshotBundle::create(
+            self.db_dir,
+            self.output_dir,
+            enable_storage_sharding,
+            self.start_epoch,
+        )?;
+        println!("{}", serde_json::to_string_pretty(bundle.manifest())?);
+
+        Ok(())
+    }
+}
+
+#[derive(Parser)]
+#[clap(about = "Verify a snapshot bundle against a waypoint.")]
+pub struct VerifyCmd {
+    #[clap(long, value_parser)]
+    bundle_dir: PathBuf,
+
+    #[clap(long)]
+    waypoint: Waypoint,
+
+    /// Only verify the proofs and the root hashes, skipping the scan of the whole DB.
+    #[clap(long)]
+    skip_full_verification: bool,
+}
+
+impl VerifyCmd {
+    pub fn run(self) -> Result<()> {
+        let bundle = SnapshotBundle::open(&self.bundle_dir)?;
+        bundle.verify(self.waypoint, !self.skip_full_verification)?;
+        println!(
+            "Snapshot bundle at version {} verified.",
+            bundle.manifest().version
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,277 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    consistency_check::{LedgerIndex, Mismatch},
+    db_debugger::common::DbDir,
+    AptosDB, NUM_STATE_SHARDS,
+};
+use anyhow::{ensure, Result};
+use aptos_storage_interface::DbReader;
+use aptos_types::transaction::Version;
+use clap::Parser;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::{max, min},
+    collections::HashMap,
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+};
+
+#[derive(Parser)]
+#[clap(
+    about = "Verify the whole DB: recompute the transaction accumulator, check state checkpoint \
+    hashes against the JMT roots, cross check the ledger indices against the primary data and \
+    check the state KV and state Merkle shards. Writes a JSON report of every mismatch found."
+)]
+pub struct Cmd {
+    #[clap(flatten)]
+    db_dir: DbDir,
+
+    /// Defaults to the one in the progress file, or to the first version not pruned.
+    #[clap(long)]
+    start_version: Option<Version>,
+
+    /// Exclusive, defaults to the one in the progress file, or to the version after the latest
+    /// one in the DB.
+    #[clap(long)]
+    end_version: Option<Version>,
+
+    #[clap(long, default_value_t = 100_000)]
+    versions_per_chunk: u64,
+
+    /// Number of checks run in parallel, defaults to the number of CPUs.
+    #[clap(long)]
+    concurrency: Option<usize>,
+
+    /// Records the version range and the checks done, so that running the same command again
+    /// resumes from there, even if the DB has moved on since.
+    #[clap(long, value_parser)]
+    progress_file: Option<PathBuf>,
+
+    #[clap(long, value_parser)]
+    report: PathBuf,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Task {
+    Ledger {
+        start_version: Version,
+        end_version: Version,
+    },
+    Index {
+        index: LedgerIndex,
+        start_version: Version,
+        end_version: Version,
+    },
+    StateShard {
+        shard_id: u8,
+        snapshot_version: Option<Version>,
+    },
+}
+
+/// The range the checks are cut from, recorded so that a resumed run cuts them the same way.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+struct VersionRange {
+    start_version: Version,
+    end_version: Version,
+}
+
+#[derive(Deserialize, Serialize)]
+struct TaskResult {
+    task: Task,
+    mismatches: Vec<Mismatch>,
+}
+
+#[derive(Serialize)]
+struct Report {
+    start_version: Version,
+    end_version: Version,
+    state_snapshot_version: Option<Version>,
+    num_checks: usize,
+    mismatches: Vec<Mismatch>,
+}
+
+impl Cmd {
+    pub fn run(self) -> Result<()> {
+        ensure!(
+            self.versions_per_chunk > 0,
+            "--versions-per-chunk must be positive."
+        );
+        let db = self.db_dir.open_aptos_db()?;
+
+        let (recorded_range, mut done) = self.load_progress()?;
+        let first_version = db.get_first_txn_version()?.unwrap_or(0);
+        let start_version = self
+            .start_version
+            .or_else(|| recorded_range.map(|range| range.start_version))
+            .unwrap_or(first_version);
+        let start_version = max(start_version, first_version);
+        let end_version = match self
+            .end_version
+            .or_else(|| recorded_range.map(|range| range.end_version))
+        {
+            Some(end_version) => end_version,
+            None => db.get_latest_version()? + 1,
+        };
+        let range = VersionRange {
+            start_version,
+            end_version,
+        };
+        ensure!(
+            start_version < end_version,
+            "Nothing to verify between {} and {}.",
+            start_version,
+            end_version,
+        );
+        let state_snapshot_version = db
+            .get_state_snapshot_before(Version::MAX)?
+            .map(|(version, _)| version);
+
+        let mut tasks = Vec::new();
+        for chunk_start in (start_version..end_version).step_by(self.versions_per_chunk as usize) {
+            let chunk_end = min(chunk_start + self.versions_per_chunk, end_version);
+            tasks.push(Task::Ledger {
+                start_version: chunk_start,
+                end_version: chunk_end,
+            });
+            tasks.extend(LedgerIndex::ALL.into_iter().map(|index| Task::Index {
+                index,
+                start_version: chunk_start,
+                end_version: chunk_end,
+            }));
+        }
+        tasks.extend(
+            (0..NUM_STATE_SHARDS as u8).map(|shard_id| Task::StateShard {
+                shard_id,
+                snapshot_version: state_snapshot_version,
+            }),
+        );
+
+        done.retain(|task, _| tasks.contains(task));
+        let pending: Vec<_> = tasks
+            .iter()
+            .filter(|task| !done.contains_key(task))
+            .collect();
+        println!(
+            "Verifying versions [{}, {}) and the state snapshot at {:?}, {} of {} checks to go.",
+            start_version,
+            end_version,
+            state_snapshot_version,
+            pending.len(),
+            tasks.len(),
+        );
+
+        let progress_file = match &self.progress_file {
+            Some(path) => {
+                let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+                if recorded_range != Some(range) {
+                    writeln!(file, "{}", serde_json::to_string(&range)?)?;
+                }
+                Some(Mutex::new(file))
+            },
+            None => None,
+        };
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(self.concurrency.unwrap_or_else(num_cpus::get))
+            .thread_name(|index| format!("db_verify_{}", index))
+            .build()?;
+        let num_done = AtomicUsize::new(0);
+        let results = pool.install(|| {
+            pending
+                .par_iter()
+                .map(|task| {
+                    let result = TaskResult {
+                        task: (*task).clone(),
+                        mismatches: task.run(&db)?,
+                    };
+                    if let Some(file) = &progress_file {
+                        writeln!(file.lock().unwrap(), "{}", serde_json::to_string(&result)?)?;
+                    }
+                    println!(
+                        "Checked {:?}, {} mismatches, {}/{} done.",
+                        task,
+                        result.mismatches.len(),
+                        num_done.fetch_add(1, Ordering::Relaxed) + 1,
+                        pending.len(),
+                    );
+                    Ok(result)
+                })
+                .collect::<Result<Vec<_>>>()
+        })?;
+        done.extend(
+            results
+                .into_iter()
+                .map(|result| (result.task, result.mismatches)),
+        );
+
+        let mismatches: Vec<_> = tasks
+            .iter()
+            .flat_map(|task| done.remove(task).unwrap_or_default())
+            .collect();
+        let report = Report {
+            start_version,
+            end_version,
+            state_snapshot_version,
+            num_checks: tasks.len(),
+            mismatches,
+        };
+        serde_json::to_writer_pretty(File::create(&self.report)?, &report)?;
+        ensure!(
+            report.mismatches.is_empty(),
+            "Found {} mismatches, see {:?}.",
+            report.mismatches.len(),
+            self.report,
+        );
+        println!("No mismatch found.");
+
+        Ok(())
+    }
+
+    /// Returns the last version range recorded and the checks done. A line not fully written
+    /// before the previous run was killed is ignored, as if that check was never done.
+    fn load_progress(&self) -> Result<(Option<VersionRange>, HashMap<Task, Vec<Mismatch>>)> {
+        let mut range = None;
+        let mut done = HashMap::new();
+        if let Some(path) = &self.progress_file {
+            if path.exists() {
+                for line in BufReader::new(File::open(path)?).lines() {
+                    let line = line?;
+                    if let Ok(result) = serde_json::from_str::<TaskResult>(&line) {
+                        done.insert(result.task, result.mismatches);
+                    } else if let Ok(recorded) = serde_json::from_str::<VersionRange>(&line) {
+                        range = Some(recorded);
+                    }
+                }
+            }
+        }
+        Ok((range, done))
+    }
+}
+
+impl Task {
+    fn run(&self, db: &AptosDB) -> Result<Vec<Mismatch>> {
+        match self {
+            Task::Ledger {
+                start_version,
+                end_version,
+            } => db.check_ledger_consistency(*start_version, *end_version),
+            Task::Index {
+                index,
+                start_version,
+                end_version,
+            } => db.check_index_consistency(*index, *start_version, *end_version),
+            Task::StateShard {
+                shard_id,
+                snapshot_version,
+            } => db.check_state_shard_consistency(*shard_id, *snapshot_version),
+        }
+    }
+}
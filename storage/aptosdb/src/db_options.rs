@@ -89,6 +89,17 @@ pub(super) fn ledger_metadata_db_column_families() -> Vec<ColumnFamilyName> {
     ]
 }
 
+pub(super) fn cold_ledger_db_column_families() -> Vec<ColumnFamilyName> {
+    vec![
+        /* empty cf */ DEFAULT_COLUMN_FAMILY_NAME,
+        DB_METADATA_CF_NAME,
+        EVENT_CF_NAME,
+        TRANSACTION_CF_NAME,
+        TRANSACTION_INFO_CF_NAME,
+        WRITE_SET_CF_NAME,
+    ]
+}
+
 pub(super) fn state_merkle_db_column_families() -> Vec<ColumnFamilyName> {
     vec![
         /* empty cf */ DEFAULT_COLUMN_FAMILY_NAME,
@@ -182,6 +193,11 @@ pub(super) fn gen_ledger_cfds(rocksdb_config: &RocksdbConfig) -> Vec<ColumnFamil
     gen_cfds(rocksdb_config, cfs, with_state_key_extractor_processor)
 }
 
+pub(super) fn gen_cold_ledger_cfds(rocksdb_config: &RocksdbConfig) -> Vec<ColumnFamilyDescriptor> {
+    let cfs = cold_ledger_db_column_families();
+    gen_cfds(rocksdb_config, cfs, |_, _| {})
+}
+
 pub(super) fn gen_state_merkle_cfds(rocksdb_config: &RocksdbConfig) -> Vec<ColumnFamilyDescriptor> {
     let cfs = state_merkle_db_column_families();
     gen_cfds(rocksdb_config, cfs, |_, _| {})
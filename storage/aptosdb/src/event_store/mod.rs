@@ -8,6 +8,7 @@
 
 use super::AptosDB;
 use crate::{
+    cold_ledger_db::{get_tiered, ColdLedgerDb, TieredIter},
     errors::AptosDbError,
     schema::{
         event::EventSchema, event_accumulator::EventAccumulatorSchema,
@@ -40,11 +41,14 @@ use std::{
 #[derive(Debug)]
 pub struct EventStore {
     event_db: Arc<DB>,
+    /// Where old events are moved to if the archive tier is in use. Indices and the event
+    /// accumulator stay in `event_db`.
+    cold_db: Option<Arc<ColdLedgerDb>>,
 }
 
 impl EventStore {
-    pub fn new(event_db: Arc<DB>) -> Self {
-        Self { event_db }
+    pub fn new(event_db: Arc<DB>, cold_db: Option<Arc<ColdLedgerDb>>) -> Self {
+        Self { event_db, cold_db }
     }
 
     /// Get all of the events given a transaction version.
@@ -53,9 +57,9 @@ impl EventStore {
     pub fn get_events_by_version(&self, version: Version) -> Result<Vec<ContractEvent>> {
         let mut events = vec![];
 
-        let mut iter = self.event_db.iter::<EventSchema>(ReadOptions::default())?;
         // Grab the first event and then iterate until we get all events for this version.
-        iter.seek(&version)?;
+        let mut iter =
+            TieredIter::<EventSchema>::new(&self.event_db, self.cold_db.as_deref(), &(version, 0))?;
         while let Some(((ver, index), event)) = iter.next().transpose()? {
             if ver != version {
                 break;
@@ -71,8 +75,11 @@ impl EventStore {
         start_version: Version,
         num_versions: usize,
     ) -> Result<EventsByVersionIter> {
-        let mut iter = self.event_db.iter::<EventSchema>(Default::default())?;
-        iter.seek(&start_version)?;
+        let iter = TieredIter::<EventSchema>::new(
+            &self.event_db,
+            self.cold_db.as_deref(),
+            &(start_version, 0),
+        )?;
 
         Ok(EventsByVersionIter::new(
             iter,
@@ -88,8 +95,7 @@ impl EventStore {
         version: Version,
         index: u64,
     ) -> Result<ContractEvent> {
-        self.event_db
-            .get::<EventSchema>(&(version, index))?
+        get_tiered::<EventSchema>(&self.event_db, self.cold_db.as_deref(), &(version, index))?
             .ok_or_else(|| {
                 AptosDbError::NotFound(format!("Event {} of Txn {}", index, version)).into()
             })
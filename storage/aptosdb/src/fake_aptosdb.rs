@@ -15,8 +15,8 @@ use aptos_crypto::{
 };
 use aptos_infallible::Mutex;
 use aptos_storage_interface::{
-    cached_state_view::ShardedStateCache, state_delta::StateDelta, DbReader, DbWriter,
-    ExecutedTrees, MAX_REQUEST_LIMIT,
+    cached_state_view::ShardedStateCache, pruner_status::PrunerStatus, state_delta::StateDelta,
+    DbReader, DbWriter, ExecutedTrees, MAX_REQUEST_LIMIT,
 };
 use aptos_types::{
     access_path::AccessPath,
@@ -28,9 +28,9 @@ use aptos_types::{
     ledger_info::LedgerInfoWithSignatures,
     proof::{
         accumulator::InMemoryAccumulator, position::Position, AccumulatorConsistencyProof,
-        AccumulatorRangeProof, SparseMerkleProofExt, TransactionAccumulatorProof,
-        TransactionAccumulatorRangeProof, TransactionAccumulatorSummary,
-        TransactionInfoListWithProof, TransactionInfoWithProof,
+        AccumulatorRangeProof, SparseMerkleMultiProof, SparseMerkleProofExt,
+        TransactionAccumulatorProof, TransactionAccumulatorRangeProof,
+        TransactionAccumulatorSummary, TransactionInfoListWithProof, TransactionInfoWithProof,
     },
     state_proof::StateProof,
     state_store::{
@@ -399,6 +399,10 @@ impl DbWriter for FakeAptosDB {
             latest_in_memory_state,
         )
     }
+
+    fn set_prune_window(&self, pruner_name: &str, prune_window: Version) -> Result<()> {
+        self.inner.set_prune_window(pruner_name, prune_window)
+    }
 }
 
 impl DbReader for FakeAptosDB {
@@ -750,6 +754,15 @@ impl DbReader for FakeAptosDB {
             .get_state_value_with_proof_by_version_ext(state_key, version)
     }
 
+    fn get_state_proof_with_multiple_keys(
+        &self,
+        state_keys: &[StateKey],
+        version: Version,
+    ) -> Result<(Vec<Option<StateValue>>, SparseMerkleMultiProof)> {
+        self.inner
+            .get_state_proof_with_multiple_keys(state_keys, version)
+    }
+
     fn get_latest_executed_trees(&self) -> Result<ExecutedTrees> {
         // If the genesis is not executed yet, we need to get the executed trees from the inner AptosDB
         // This is because when we call save_transactions for the genesis block, we call [AptosDB::save_transactions]
@@ -837,6 +850,10 @@ impl DbReader for FakeAptosDB {
         self.inner.get_ledger_prune_window()
     }
 
+    fn get_pruner_status(&self) -> Result<Vec<PrunerStatus>> {
+        self.inner.get_pruner_status()
+    }
+
     fn get_table_info(&self, handle: table::TableHandle) -> Result<table::TableInfo> {
         self.inner.get_table_info(handle)
     }
@@ -176,6 +176,11 @@ impl DbWriter for FastSyncStorageWrapper {
             sharded_state_cache,
         )
     }
+
+    fn set_prune_window(&self, pruner_name: &str, prune_window: Version) -> Result<()> {
+        self.get_aptos_db_write_ref()
+            .set_prune_window(pruner_name, prune_window)
+    }
 }
 
 impl DbReader for FastSyncStorageWrapper {
@@ -0,0 +1,257 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Moves transactions, transaction infos, write sets and events older than the configured window
+//! from the ledger db to the cold db of the archive tier, see [`crate::cold_ledger_db`].
+
+use crate::{
+    cold_ledger_db::{ColdLedgerDb, KeyVersion},
+    ledger_db::LedgerDb,
+    metrics::OTHER_TIMERS_SECONDS,
+    schema::{
+        event::EventSchema, transaction::TransactionSchema,
+        transaction_info::TransactionInfoSchema, write_set::WriteSetSchema,
+    },
+};
+use anyhow::Result;
+use aptos_config::config::LedgerArchiveConfig;
+use aptos_logger::{
+    error, info,
+    prelude::{sample, SampleRate},
+};
+use aptos_metrics_core::TimerHelper;
+use aptos_schemadb::{schema::Schema, ReadOptions, SchemaBatch, DB};
+use aptos_types::transaction::{AtomicVersion, Version};
+use std::{
+    cmp::min,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{sleep, JoinHandle},
+    time::Duration,
+};
+
+/// Maintains the thread moving data to the cold db, the way `PrunerWorker` does for pruners.
+pub(crate) struct LedgerArchiver {
+    hot_window: Version,
+    worker_thread: Option<JoinHandle<()>>,
+    inner: Arc<LedgerArchiverInner>,
+}
+
+struct LedgerArchiverInner {
+    /// The worker will sleep for this period of time when there's nothing to archive.
+    archive_time_interval_in_ms: u64,
+    ledger_db: Arc<LedgerDb>,
+    batch_size: usize,
+    target_version: AtomicVersion,
+    quit_worker: AtomicBool,
+}
+
+impl LedgerArchiverInner {
+    fn cold_db(&self) -> &ColdLedgerDb {
+        self.ledger_db
+            .cold_db()
+            .expect("Cold ledger db must be open for the archiver.")
+    }
+
+    fn work(&self) {
+        let mut leftovers_deleted = false;
+        while !self.quit_worker.load(Ordering::SeqCst) {
+            if !leftovers_deleted {
+                match self.delete_leftovers() {
+                    Ok(()) => leftovers_deleted = true,
+                    Err(e) => {
+                        sample!(
+                            SampleRate::Duration(Duration::from_secs(1)),
+                            error!(error = ?e, "Ledger archiver failed to delete leftovers.")
+                        );
+                        sleep(Duration::from_millis(self.archive_time_interval_in_ms));
+                        continue;
+                    },
+                }
+            }
+            let progress = self.cold_db().archived_version();
+            let target_version = self.target_version.load(Ordering::SeqCst);
+            if progress >= target_version {
+                sleep(Duration::from_millis(self.archive_time_interval_in_ms));
+                continue;
+            }
+            let end_version = min(progress + self.batch_size as Version, target_version);
+            if let Err(e) = self.archive(progress, end_version) {
+                sample!(
+                    SampleRate::Duration(Duration::from_secs(1)),
+                    error!(error = ?e, "Ledger archiver has error.")
+                );
+                sleep(Duration::from_millis(self.archive_time_interval_in_ms));
+            }
+        }
+    }
+
+    /// Moves data in `[begin, end)` to the cold db.
+    fn archive(&self, begin: Version, end: Version) -> Result<()> {
+        let _timer = OTHER_TIMERS_SECONDS.timer_with(&["ledger_archiver__archive"]);
+
+        let cold_batch = SchemaBatch::new();
+        let transaction_batch = SchemaBatch::new();
+        let transaction_info_batch = SchemaBatch::new();
+        let write_set_batch = SchemaBatch::new();
+        let event_batch = SchemaBatch::new();
+        move_range::<TransactionSchema>(
+            self.ledger_db.transaction_db(),
+            &begin,
+            end,
+            &cold_batch,
+            &transaction_batch,
+        )?;
+        move_range::<TransactionInfoSchema>(
+            self.ledger_db.transaction_info_db(),
+            &begin,
+            end,
+            &cold_batch,
+            &transaction_info_batch,
+        )?;
+        move_range::<WriteSetSchema>(
+            self.ledger_db.write_set_db(),
+            &begin,
+            end,
+            &cold_batch,
+            &write_set_batch,
+        )?;
+        move_range::<EventSchema>(
+            self.ledger_db.event_db(),
+            &(begin, 0),
+            end,
+            &cold_batch,
+            &event_batch,
+        )?;
+
+        // Readers go to the cold db for these from now on, so they can be deleted. A crash in
+        // between leaves copies in the ledger db, which readers skip and `delete_leftovers`
+        // deletes after a restart.
+        self.cold_db().write_archived(cold_batch, end)?;
+        self.ledger_db
+            .transaction_db()
+            .write_schemas(transaction_batch)?;
+        self.ledger_db
+            .transaction_info_db()
+            .write_schemas(transaction_info_batch)?;
+        self.ledger_db
+            .write_set_db()
+            .write_schemas(write_set_batch)?;
+        self.ledger_db.event_db().write_schemas(event_batch)
+    }
+
+    /// Deletes whatever is left in the ledger db below the archived version, which can only be
+    /// there if the process stopped between moving a batch to the cold db and deleting it from the
+    /// ledger db.
+    fn delete_leftovers(&self) -> Result<()> {
+        let _timer = OTHER_TIMERS_SECONDS.timer_with(&["ledger_archiver__delete_leftovers"]);
+
+        let archived_version = self.cold_db().archived_version();
+        delete_below::<TransactionSchema>(self.ledger_db.transaction_db(), archived_version)?;
+        delete_below::<TransactionInfoSchema>(
+            self.ledger_db.transaction_info_db(),
+            archived_version,
+        )?;
+        delete_below::<WriteSetSchema>(self.ledger_db.write_set_db(), archived_version)?;
+        delete_below::<EventSchema>(self.ledger_db.event_db(), archived_version)
+    }
+}
+
+/// Copies the items of a version range to `cold_batch` and deletes them via `hot_batch`.
+fn move_range<S>(
+    hot_db: &DB,
+    seek_key: &S::Key,
+    end: Version,
+    cold_batch: &SchemaBatch,
+    hot_batch: &SchemaBatch,
+) -> Result<()>
+where
+    S: Schema,
+    S::Key: KeyVersion,
+{
+    let mut iter = hot_db.iter::<S>(ReadOptions::default())?;
+    iter.seek(seek_key)?;
+    for item in iter {
+        let (key, value) = item?;
+        if key.version() >= end {
+            break;
+        }
+        cold_batch.put::<S>(&key, &value)?;
+        hot_batch.delete::<S>(&key)?;
+    }
+    Ok(())
+}
+
+/// Deletes the items below `end` from the ledger db.
+fn delete_below<S>(hot_db: &DB, end: Version) -> Result<()>
+where
+    S: Schema,
+    S::Key: KeyVersion,
+{
+    let batch = SchemaBatch::new();
+    let mut iter = hot_db.iter::<S>(ReadOptions::default())?;
+    iter.seek_to_first();
+    for item in iter {
+        let (key, _value) = item?;
+        if key.version() >= end {
+            break;
+        }
+        batch.delete::<S>(&key)?;
+    }
+    hot_db.write_schemas(batch)
+}
+
+impl LedgerArchiver {
+    pub(crate) fn new(ledger_db: Arc<LedgerDb>, config: LedgerArchiveConfig) -> Self {
+        let archived_version = ledger_db
+            .cold_db()
+            .expect("Cold ledger db must be open for the archiver.")
+            .archived_version();
+        info!(
+            archived_version = archived_version,
+            hot_window = config.hot_window,
+            "Starting ledger archiver."
+        );
+
+        let inner = Arc::new(LedgerArchiverInner {
+            archive_time_interval_in_ms: if cfg!(test) { 10 } else { 1000 },
+            ledger_db,
+            batch_size: config.batch_size,
+            target_version: AtomicVersion::new(archived_version),
+            quit_worker: AtomicBool::new(false),
+        });
+        let inner_cloned = Arc::clone(&inner);
+        let worker_thread = std::thread::Builder::new()
+            .name("ledger_archiver".into())
+            .spawn(move || inner_cloned.work())
+            .expect("Creating ledger archiver thread should succeed.");
+
+        Self {
+            hot_window: config.hot_window,
+            worker_thread: Some(worker_thread),
+            inner,
+        }
+    }
+
+    /// Called on every commit, moves the target forward to keep `hot_window` versions in the
+    /// ledger db.
+    pub(crate) fn maybe_set_target_version(&self, latest_version: Version) {
+        let target_version = latest_version.saturating_sub(self.hot_window);
+        self.inner
+            .target_version
+            .fetch_max(target_version, Ordering::SeqCst);
+    }
+}
+
+impl Drop for LedgerArchiver {
+    fn drop(&mut self) {
+        self.inner.quit_worker.store(true, Ordering::SeqCst);
+        self.worker_thread
+            .take()
+            .expect("Ledger archiver thread must exist.")
+            .join()
+            .expect("Ledger archiver thread should join peacefully.");
+    }
+}
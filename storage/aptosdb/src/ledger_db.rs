@@ -5,6 +5,7 @@
 #![allow(dead_code)]
 
 use crate::{
+    cold_ledger_db::ColdLedgerDb,
     db_options::{
         event_db_column_families, gen_event_cfds, gen_ledger_cfds, gen_ledger_metadata_cfds,
         gen_transaction_accumulator_cfds, gen_transaction_cfds, gen_transaction_info_cfds,
@@ -71,6 +72,9 @@ pub struct LedgerDb {
     transaction_db: Arc<DB>,
     transaction_info_db: Arc<DB>,
     write_set_db: Arc<DB>,
+    /// Where old transactions, transaction infos, write sets and events are moved to, if the
+    /// archive tier is in use.
+    cold_db: Option<Arc<ColdLedgerDb>>,
 }
 
 impl LedgerDb {
@@ -107,6 +111,7 @@ impl LedgerDb {
                 transaction_db: Arc::clone(&ledger_metadata_db),
                 transaction_info_db: Arc::clone(&ledger_metadata_db),
                 write_set_db: Arc::clone(&ledger_metadata_db),
+                cold_db: None,
             });
         }
 
@@ -156,9 +161,25 @@ impl LedgerDb {
             transaction_db,
             transaction_info_db,
             write_set_db,
+            cold_db: None,
         })
     }
 
+    /// Opens (or creates) the cold db of the archive tier under `db_root_path`.
+    pub(crate) fn open_cold_db<P: AsRef<Path>>(
+        &mut self,
+        db_root_path: P,
+        rocksdb_config: &RocksdbConfig,
+        readonly: bool,
+    ) -> Result<()> {
+        self.cold_db = Some(Arc::new(ColdLedgerDb::new(
+            db_root_path,
+            rocksdb_config,
+            readonly,
+        )?));
+        Ok(())
+    }
+
     pub(crate) fn create_checkpoint(
         db_root_path: impl AsRef<Path>,
         cp_root_path: impl AsRef<Path>,
@@ -283,6 +304,14 @@ impl LedgerDb {
         Arc::clone(&self.write_set_db)
     }
 
+    pub(crate) fn cold_db(&self) -> Option<&ColdLedgerDb> {
+        self.cold_db.as_deref()
+    }
+
+    pub(crate) fn cold_db_arc(&self) -> Option<Arc<ColdLedgerDb>> {
+        self.cold_db.clone()
+    }
+
     fn open_rocksdb(
         path: PathBuf,
         name: &str,
@@ -6,6 +6,7 @@
 //! root(LedgerInfo) to leaf(TransactionInfo).
 
 use crate::{
+    cold_ledger_db::{get_tiered, TieredIter},
     errors::AptosDbError,
     ledger_db::LedgerDb,
     schema::{
@@ -174,10 +175,12 @@ impl LedgerStore {
 
     /// Get transaction info given `version`
     pub fn get_transaction_info(&self, version: Version) -> Result<TransactionInfo> {
-        self.ledger_db
-            .transaction_info_db()
-            .get::<TransactionInfoSchema>(&version)?
-            .ok_or_else(|| format_err!("No TransactionInfo at version {}", version))
+        get_tiered::<TransactionInfoSchema>(
+            self.ledger_db.transaction_info_db(),
+            self.ledger_db.cold_db(),
+            &version,
+        )?
+        .ok_or_else(|| format_err!("No TransactionInfo at version {}", version))
     }
 
     pub fn get_latest_version(&self) -> Result<Version> {
@@ -195,12 +198,12 @@ impl LedgerStore {
         start_version: Version,
         num_transaction_infos: usize,
     ) -> Result<impl Iterator<Item = Result<TransactionInfo>> + '_> {
-        let mut iter = self
-            .ledger_db
-            .transaction_info_db()
-            .iter::<TransactionInfoSchema>(ReadOptions::default())?;
-        iter.seek(&start_version)?;
-        iter.expect_continuous_versions(start_version, num_transaction_infos)
+        TieredIter::<TransactionInfoSchema>::new(
+            self.ledger_db.transaction_info_db(),
+            self.ledger_db.cold_db(),
+            &start_version,
+        )?
+        .expect_continuous_versions(start_version, num_transaction_infos)
     }
 
     /// Gets an iterator that yields epoch ending ledger infos, starting
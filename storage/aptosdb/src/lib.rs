@@ -20,11 +20,15 @@ pub mod backup;
 pub mod errors;
 pub mod metrics;
 pub mod schema;
+pub mod snapshot_bundle;
 pub mod state_restore;
 pub mod utils;
 
+mod cold_ledger_db;
+mod consistency_check;
 mod db_options;
 mod event_store;
+mod ledger_archiver;
 mod ledger_db;
 mod ledger_store;
 mod lru_node_cache;
@@ -47,22 +51,28 @@ use crate::state_store::buffered_state::BufferedState;
 use crate::{
     backup::{backup_handler::BackupHandler, restore_handler::RestoreHandler, restore_utils},
     block_index::BlockIndexSchema,
+    cold_ledger_db::ColdLedgerDb,
     db_metadata::{DbMetadataKey, DbMetadataSchema, DbMetadataValue},
     db_options::{
-        event_db_column_families, ledger_db_column_families, ledger_metadata_db_column_families,
-        state_kv_db_column_families, state_merkle_db_column_families,
-        transaction_accumulator_db_column_families, transaction_db_column_families,
-        transaction_info_db_column_families, write_set_db_column_families,
+        cold_ledger_db_column_families, event_db_column_families, ledger_db_column_families,
+        ledger_metadata_db_column_families, state_kv_db_column_families,
+        state_merkle_db_column_families, transaction_accumulator_db_column_families,
+        transaction_db_column_families, transaction_info_db_column_families,
+        write_set_db_column_families,
     },
     errors::AptosDbError,
     event_store::EventStore,
+    ledger_archiver::LedgerArchiver,
     ledger_db::{LedgerDb, LedgerDbSchemaBatches},
     ledger_store::LedgerStore,
     metrics::{
         API_LATENCY_SECONDS, COMMITTED_TXNS, LATEST_TXN_VERSION, LEDGER_VERSION, NEXT_BLOCK_EPOCH,
         OTHER_TIMERS_SECONDS, ROCKSDB_PROPERTIES,
     },
-    pruner::{LedgerPrunerManager, PrunerManager, StateKvPrunerManager, StateMerklePrunerManager},
+    pruner::{
+        LedgerPrunerManager, PrunerManager, StaleNodeIndexSchemaTrait, StateKvPrunerManager,
+        StateMerklePrunerManager, LEDGER_PRUNER_NAME, STATE_KV_PRUNER_NAME,
+    },
     schema::*,
     stale_node_index::StaleNodeIndexSchema,
     stale_node_index_cross_epoch::StaleNodeIndexCrossEpochSchema,
@@ -70,6 +80,7 @@ use crate::{
     state_merkle_db::StateMerkleDb,
     state_store::StateStore,
     transaction_store::TransactionStore,
+    utils::get_progress,
 };
 use anyhow::{anyhow, bail, ensure, Result};
 use aptos_config::config::{
@@ -88,8 +99,9 @@ use aptos_metrics_core::TimerHelper;
 use aptos_schemadb::{ReadOptions, SchemaBatch, DB};
 use aptos_scratchpad::SparseMerkleTree;
 use aptos_storage_interface::{
-    cached_state_view::ShardedStateCache, state_delta::StateDelta, state_view::DbStateView,
-    DbReader, DbWriter, ExecutedTrees, Order, StateSnapshotReceiver, MAX_REQUEST_LIMIT,
+    cached_state_view::ShardedStateCache, pruner_status::PrunerStatus, state_delta::StateDelta,
+    state_view::DbStateView, DbReader, DbWriter, ExecutedTrees, Order, StateSnapshotReceiver,
+    MAX_REQUEST_LIMIT,
 };
 use aptos_types::{
     account_address::AccountAddress,
@@ -101,8 +113,8 @@ use aptos_types::{
     ledger_info::LedgerInfoWithSignatures,
     on_chain_config::{CurrentTimeMicroseconds, OnChainConfig},
     proof::{
-        accumulator::InMemoryAccumulator, AccumulatorConsistencyProof, SparseMerkleProofExt,
-        TransactionAccumulatorRangeProof, TransactionAccumulatorSummary,
+        accumulator::InMemoryAccumulator, AccumulatorConsistencyProof, SparseMerkleMultiProof,
+        SparseMerkleProofExt, TransactionAccumulatorRangeProof, TransactionAccumulatorSummary,
         TransactionInfoListWithProof,
     },
     state_proof::StateProof,
@@ -220,6 +232,16 @@ fn set_property_sharded(cf_name: &str, db: &DB, db_shard_id: usize) -> Result<()
     Ok(())
 }
 
+fn set_property_cold(cf_name: &str, db: &DB) -> Result<()> {
+    for (rockdb_property_name, aptos_rocksdb_property_name) in &*ROCKSDB_PROPERTY_MAP {
+        let cf_label = format!("cold_{}", cf_name);
+        ROCKSDB_PROPERTIES
+            .with_label_values(&[&cf_label, aptos_rocksdb_property_name])
+            .set(db.get_property(cf_name, rockdb_property_name)? as i64);
+    }
+    Ok(())
+}
+
 fn update_rocksdb_properties(
     ledger_db: &LedgerDb,
     state_merkle_db: &StateMerkleDb,
@@ -270,6 +292,12 @@ fn update_rocksdb_properties(
         }
     }
 
+    if let Some(cold_db) = ledger_db.cold_db() {
+        for cf in cold_ledger_db_column_families() {
+            set_property_cold(cf, cold_db.db())?;
+        }
+    }
+
     for cf_name in state_merkle_db_column_families() {
         set_property(cf_name, state_merkle_db.metadata_db())?;
         if state_merkle_db.sharding_enabled() {
@@ -339,6 +367,7 @@ pub struct AptosDB {
     pub(crate) state_store: Arc<StateStore>,
     pub(crate) transaction_store: Arc<TransactionStore>,
     ledger_pruner: LedgerPrunerManager,
+    ledger_archiver: Option<LedgerArchiver>,
     _rocksdb_property_reporter: RocksdbPropertyReporter,
     ledger_commit_lock: std::sync::Mutex<()>,
     indexer: Option<Indexer>,
@@ -388,11 +417,15 @@ impl AptosDB {
         AptosDB {
             ledger_db: Arc::clone(&ledger_db),
             state_kv_db: Arc::clone(&state_kv_db),
-            event_store: Arc::new(EventStore::new(ledger_db.event_db_arc())),
+            event_store: Arc::new(EventStore::new(
+                ledger_db.event_db_arc(),
+                ledger_db.cold_db_arc(),
+            )),
             ledger_store: Arc::new(LedgerStore::new(Arc::clone(&ledger_db))),
             state_store,
             transaction_store: Arc::new(TransactionStore::new(Arc::clone(&ledger_db))),
             ledger_pruner,
+            ledger_archiver: None,
             _rocksdb_property_reporter: RocksdbPropertyReporter::new(
                 ledger_db,
                 state_merkle_db,
@@ -419,12 +452,20 @@ impl AptosDB {
             "Do not set prune_window when opening readonly.",
         );
 
-        let (ledger_db, state_merkle_db, state_kv_db) = Self::open_dbs(
+        let (mut ledger_db, state_merkle_db, state_kv_db) = Self::open_dbs(
             db_paths,
             rocksdb_configs,
             readonly,
             max_num_nodes_per_lru_cache_shard,
         )?;
+        let ledger_archive_config = pruner_config.ledger_archive_config;
+        if ledger_archive_config.enable && ledger_db.cold_db().is_none() {
+            ledger_db.open_cold_db(
+                db_paths.cold_ledger_db_root_path(),
+                &rocksdb_configs.cold_ledger_db_config,
+                readonly,
+            )?;
+        }
 
         let mut myself = Self::new_with_dbs(
             ledger_db,
@@ -437,6 +478,13 @@ impl AptosDB {
             rocksdb_configs.enable_storage_sharding,
         );
 
+        if ledger_archive_config.enable {
+            myself.ledger_archiver = Some(LedgerArchiver::new(
+                Arc::clone(&myself.ledger_db),
+                ledger_archive_config,
+            ));
+        }
+
         if !readonly && enable_indexer {
             myself.open_indexer(
                 db_paths.default_root_path(),
@@ -495,7 +543,16 @@ impl AptosDB {
         readonly: bool,
         max_num_nodes_per_lru_cache_shard: usize,
     ) -> Result<(LedgerDb, StateMerkleDb, StateKvDb)> {
-        let ledger_db = LedgerDb::new(db_paths.ledger_db_root_path(), rocksdb_configs, readonly)?;
+        let mut ledger_db =
+            LedgerDb::new(db_paths.ledger_db_root_path(), rocksdb_configs, readonly)?;
+        // Data already moved to the archive tier stays readable even if archiving is turned off.
+        if ColdLedgerDb::exists(db_paths.cold_ledger_db_root_path()) {
+            ledger_db.open_cold_db(
+                db_paths.cold_ledger_db_root_path(),
+                &rocksdb_configs.cold_ledger_db_config,
+                readonly,
+            )?;
+        }
         let state_kv_db = StateKvDb::new(
             db_paths,
             rocksdb_configs,
@@ -1318,6 +1375,9 @@ impl AptosDB {
             self.state_store
                 .state_kv_pruner
                 .maybe_set_pruner_target_db_version(last_version);
+            if let Some(ledger_archiver) = &self.ledger_archiver {
+                ledger_archiver.maybe_set_target_version(last_version);
+            }
         }
 
         // Note: this must happen after txns have been saved to db because types can be newly
@@ -1836,6 +1896,20 @@ impl DbReader for AptosDB {
         })
     }
 
+    fn get_state_proof_with_multiple_keys(
+        &self,
+        state_keys: &[StateKey],
+        version: Version,
+    ) -> Result<(Vec<Option<StateValue>>, SparseMerkleMultiProof)> {
+        gauged_api("get_state_proof_with_multiple_keys", || {
+            error_if_too_many_requested(state_keys.len() as u64, MAX_REQUEST_LIMIT)?;
+            self.error_if_state_merkle_pruned("State merkle", version)?;
+
+            self.state_store
+                .get_state_proof_with_multiple_keys(state_keys, version)
+        })
+    }
+
     fn get_latest_epoch_state(&self) -> Result<EpochState> {
         gauged_api("get_latest_epoch_state", || {
             let latest_ledger_info = self.ledger_store.get_latest_ledger_info()?;
@@ -2122,6 +2196,23 @@ impl DbReader for AptosDB {
         })
     }
 
+    fn get_pruner_status(&self) -> Result<Vec<PrunerStatus>> {
+        gauged_api("get_pruner_status", || {
+            let latest_version = get_progress(
+                self.ledger_db.metadata_db(),
+                &DbMetadataKey::OverallCommitProgress,
+            )?
+            .unwrap_or(0);
+            let state_db = &self.state_store.state_db;
+            Ok(vec![
+                self.ledger_pruner.get_status(latest_version)?,
+                state_db.state_kv_pruner.get_status(latest_version)?,
+                state_db.state_merkle_pruner.get_status(latest_version)?,
+                state_db.epoch_snapshot_pruner.get_status(latest_version)?,
+            ])
+        })
+    }
+
     fn get_table_info(&self, handle: TableHandle) -> Result<TableInfo> {
         gauged_api("get_table_info", || {
             self.get_table_info_option(handle)?
@@ -2344,6 +2435,24 @@ impl DbWriter for AptosDB {
             Ok(())
         })
     }
+    fn set_prune_window(&self, pruner_name: &str, prune_window: Version) -> Result<()> {
+        gauged_api("set_prune_window", || {
+            let state_db = &self.state_store.state_db;
+            if pruner_name == LEDGER_PRUNER_NAME {
+                self.ledger_pruner.set_prune_window(prune_window)
+            } else if pruner_name == STATE_KV_PRUNER_NAME {
+                state_db.state_kv_pruner.set_prune_window(prune_window)
+            } else if pruner_name == StaleNodeIndexSchema::name() {
+                state_db.state_merkle_pruner.set_prune_window(prune_window)
+            } else if pruner_name == StaleNodeIndexCrossEpochSchema::name() {
+                state_db
+                    .epoch_snapshot_pruner
+                    .set_prune_window(prune_window)
+            } else {
+                bail!("Unknown pruner: {}", pruner_name)
+            }
+        })
+    }
 }
 
 // Convert requested range and order to a range in ascending order.
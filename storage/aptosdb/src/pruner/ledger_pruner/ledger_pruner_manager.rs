@@ -5,13 +5,21 @@ use crate::{
     ledger_db::LedgerDb,
     metrics::{PRUNER_BATCH_SIZE, PRUNER_VERSIONS, PRUNER_WINDOW},
     pruner::{
-        ledger_pruner::LedgerPruner, pruner_manager::PrunerManager, pruner_utils,
+        ledger_pruner::{LedgerPruner, LEDGER_PRUNER_NAME},
+        pruner_manager::PrunerManager,
+        pruner_utils,
         pruner_worker::PrunerWorker,
     },
+    schema::{
+        EVENT_CF_NAME, TRANSACTION_ACCUMULATOR_CF_NAME, TRANSACTION_BY_HASH_CF_NAME,
+        TRANSACTION_CF_NAME, TRANSACTION_INFO_CF_NAME, WRITE_SET_CF_NAME,
+    },
 };
-use anyhow::Result;
+use anyhow::{ensure, Result};
 use aptos_config::config::LedgerPrunerConfig;
 use aptos_infallible::Mutex;
+use aptos_logger::info;
+use aptos_storage_interface::pruner_status::PrunerStatus;
 use aptos_types::transaction::{AtomicVersion, Version};
 use std::sync::{atomic::Ordering, Arc};
 
@@ -20,7 +28,7 @@ pub(crate) struct LedgerPrunerManager {
     ledger_db: Arc<LedgerDb>,
     /// DB version window, which dictates how many version of other stores like transaction, ledger
     /// info, events etc to keep.
-    prune_window: Version,
+    prune_window: AtomicVersion,
     /// It is None iff the pruner is not enabled.
     pruner_worker: Option<PrunerWorker>,
     /// Ideal batch size of the versions to be sent to the ledger pruner
@@ -41,7 +49,7 @@ impl PrunerManager for LedgerPrunerManager {
     }
 
     fn get_prune_window(&self) -> Version {
-        self.prune_window
+        self.prune_window.load(Ordering::SeqCst)
     }
 
     fn get_min_readable_version(&self) -> Version {
@@ -52,7 +60,7 @@ impl PrunerManager for LedgerPrunerManager {
         let min_version = self.get_min_readable_version();
         if self.is_pruner_enabled() {
             let adjusted_window = self
-                .prune_window
+                .get_prune_window()
                 .saturating_sub(self.user_pruning_window_offset);
             let adjusted_cutoff = self.latest_version.lock().saturating_sub(adjusted_window);
             std::cmp::max(min_version, adjusted_cutoff)
@@ -70,7 +78,7 @@ impl PrunerManager for LedgerPrunerManager {
         // versions.
         if self.is_pruner_enabled()
             && latest_version
-                >= min_readable_version + self.pruning_batch_size as u64 + self.prune_window
+                >= min_readable_version + self.pruning_batch_size as u64 + self.get_prune_window()
         {
             self.set_pruner_target_db_version(latest_version);
         }
@@ -93,6 +101,71 @@ impl PrunerManager for LedgerPrunerManager {
             .map_or(false, |w| w.is_pruning_pending())
     }
 
+    fn set_prune_window(&self, prune_window: Version) -> Result<()> {
+        ensure!(
+            prune_window >= self.user_pruning_window_offset,
+            "Ledger prune_window {} is smaller than user_pruning_window_offset {}.",
+            prune_window,
+            self.user_pruning_window_offset,
+        );
+        self.prune_window.store(prune_window, Ordering::SeqCst);
+        PRUNER_WINDOW
+            .with_label_values(&["ledger_pruner"])
+            .set(prune_window as i64);
+        info!(
+            prune_window = prune_window,
+            "Ledger pruner prune_window changed."
+        );
+        Ok(())
+    }
+
+    fn get_status(&self, latest_version: Version) -> Result<PrunerStatus> {
+        let min_readable_version = self.get_min_readable_version();
+        let (progress, target_version) = self
+            .pruner_worker
+            .as_ref()
+            .map_or((min_readable_version, min_readable_version), |w| {
+                (w.progress(), w.target_version())
+            });
+
+        let mut estimated_reclaimable_bytes = 0;
+        for (db, cf_names) in [
+            (
+                self.ledger_db.transaction_db(),
+                &[TRANSACTION_CF_NAME, TRANSACTION_BY_HASH_CF_NAME][..],
+            ),
+            (
+                self.ledger_db.transaction_info_db(),
+                &[TRANSACTION_INFO_CF_NAME],
+            ),
+            (
+                self.ledger_db.transaction_accumulator_db(),
+                &[TRANSACTION_ACCUMULATOR_CF_NAME],
+            ),
+            (self.ledger_db.write_set_db(), &[WRITE_SET_CF_NAME]),
+            (self.ledger_db.event_db(), &[EVENT_CF_NAME]),
+        ] {
+            estimated_reclaimable_bytes += pruner_utils::estimate_reclaimable_bytes(
+                db,
+                cf_names,
+                progress,
+                target_version,
+                latest_version,
+            )?;
+        }
+
+        Ok(PrunerStatus {
+            name: LEDGER_PRUNER_NAME.to_string(),
+            enabled: self.is_pruner_enabled(),
+            prune_window: self.get_prune_window(),
+            min_readable_version,
+            progress,
+            target_version,
+            shards: Vec::new(),
+            estimated_reclaimable_bytes,
+        })
+    }
+
     #[cfg(test)]
     fn set_worker_target_version(&self, target_version: Version) {
         self.pruner_worker
@@ -123,7 +196,7 @@ impl LedgerPrunerManager {
 
         Self {
             ledger_db,
-            prune_window: ledger_pruner_config.prune_window,
+            prune_window: AtomicVersion::new(ledger_pruner_config.prune_window),
             pruner_worker,
             pruning_batch_size: ledger_pruner_config.batch_size,
             latest_version: Arc::new(Mutex::new(min_readable_version)),
@@ -152,7 +225,7 @@ impl LedgerPrunerManager {
 
     fn set_pruner_target_db_version(&self, latest_version: Version) {
         assert!(self.pruner_worker.is_some());
-        let min_readable_version = latest_version.saturating_sub(self.prune_window);
+        let min_readable_version = latest_version.saturating_sub(self.get_prune_window());
         self.min_readable_version
             .store(min_readable_version, Ordering::SeqCst);
 
@@ -127,7 +127,10 @@ impl LedgerPruner {
         let transaction_store = Arc::new(TransactionStore::new(Arc::clone(&ledger_db)));
 
         let event_store_pruner = Box::new(EventStorePruner::new(
-            Arc::new(EventStore::new(ledger_db.event_db_arc())),
+            Arc::new(EventStore::new(
+                ledger_db.event_db_arc(),
+                ledger_db.cold_db_arc(),
+            )),
             ledger_db.event_db_arc(),
             metadata_progress,
         )?);
@@ -5,13 +5,18 @@
 mod db_pruner;
 mod db_sub_pruner;
 mod ledger_pruner;
+mod pruned_state_check;
 mod pruner_manager;
 mod pruner_utils;
 mod pruner_worker;
 mod state_kv_pruner;
 mod state_merkle_pruner;
 
-pub(crate) use ledger_pruner::ledger_pruner_manager::LedgerPrunerManager;
+pub(crate) use ledger_pruner::{ledger_pruner_manager::LedgerPrunerManager, LEDGER_PRUNER_NAME};
 pub(crate) use pruner_manager::PrunerManager;
-pub(crate) use state_kv_pruner::state_kv_pruner_manager::StateKvPrunerManager;
-pub(crate) use state_merkle_pruner::state_merkle_pruner_manager::StateMerklePrunerManager;
+pub(crate) use state_kv_pruner::{
+    state_kv_pruner_manager::StateKvPrunerManager, STATE_KV_PRUNER_NAME,
+};
+pub(crate) use state_merkle_pruner::{
+    generics::StaleNodeIndexSchemaTrait, state_merkle_pruner_manager::StateMerklePrunerManager,
+};
@@ -0,0 +1,124 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Checks that the pruners didn't delete anything still reachable from a state snapshot they are
+//! supposed to keep.
+
+use crate::{
+    pruner::PrunerManager,
+    schema::{
+        epoch_by_version::EpochByVersionSchema, jellyfish_merkle_node::JellyfishMerkleNodeSchema,
+    },
+    AptosDB,
+};
+use anyhow::{ensure, Context, Result};
+use aptos_crypto::{hash::CryptoHash, HashValue};
+use aptos_jellyfish_merkle::node_type::NodeKey;
+use aptos_schemadb::ReadOptions;
+use aptos_storage_interface::DbReader;
+use aptos_types::transaction::Version;
+use std::cmp::max;
+
+impl AptosDB {
+    /// Reads `num_samples` random state values with proofs at each of the oldest epoch ending
+    /// snapshot kept by the epoch snapshot pruner, the oldest snapshot kept by the state merkle
+    /// pruner and the latest snapshot. Fails if a JMT node or a state value on the way has been
+    /// pruned, or a proof doesn't verify. Returns the versions checked.
+    pub fn check_pruned_state(&self, num_samples: usize) -> Result<Vec<Version>> {
+        let state_db = &self.state_store.state_db;
+        // A snapshot is readable only if the state values live at it are not pruned either.
+        let kv_min_readable_version = state_db.state_kv_pruner.get_min_readable_version();
+
+        let latest_snapshot_version = match state_db
+            .state_merkle_db
+            .get_state_snapshot_version_before(Version::MAX)?
+        {
+            Some(version) => version,
+            None => return Ok(Vec::new()),
+        };
+        let mut versions = vec![latest_snapshot_version];
+        if let Some(version) = self.get_epoch_ending_snapshot_version_after(max(
+            state_db.epoch_snapshot_pruner.get_min_readable_version(),
+            kv_min_readable_version,
+        ))? {
+            versions.push(version);
+        }
+        if let Some(version) = state_db
+            .state_merkle_db
+            .get_state_snapshot_version_after(max(
+                state_db.state_merkle_pruner.get_min_readable_version(),
+                kv_min_readable_version,
+            ))?
+        {
+            versions.push(version);
+        }
+        versions.retain(|version| *version <= latest_snapshot_version);
+        versions.sort_unstable();
+        versions.dedup();
+
+        for version in &versions {
+            self.check_state_snapshot(*version, num_samples)
+                .with_context(|| format!("Checking state snapshot at version {}.", version))?;
+        }
+        Ok(versions)
+    }
+
+    fn get_epoch_ending_snapshot_version_after(&self, version: Version) -> Result<Option<Version>> {
+        let mut iter = self
+            .ledger_db
+            .metadata_db()
+            .iter::<EpochByVersionSchema>(ReadOptions::default())?;
+        iter.seek(&version)?;
+        for res in iter {
+            let (epoch_ending_version, _epoch) = res?;
+            // Epochs ended before the DB was state synced or restored have no snapshot at all.
+            if self
+                .state_store
+                .state_db
+                .state_merkle_db
+                .metadata_db()
+                .get::<JellyfishMerkleNodeSchema>(&NodeKey::new_empty_path(epoch_ending_version))?
+                .is_some()
+            {
+                return Ok(Some(epoch_ending_version));
+            }
+        }
+        Ok(None)
+    }
+
+    fn check_state_snapshot(&self, version: Version, num_samples: usize) -> Result<()> {
+        let root_hash = self.state_store.get_root_hash(version)?;
+        for _ in 0..num_samples {
+            // Takes the first leaf at or after a random position, wrapping around past the
+            // rightmost leaf.
+            let item = match self
+                .state_store
+                .get_state_key_and_value_iter(version, HashValue::random())?
+                .next()
+            {
+                Some(item) => item,
+                None => match self
+                    .state_store
+                    .get_state_key_and_value_iter(version, HashValue::zero())?
+                    .next()
+                {
+                    Some(item) => item,
+                    // Empty tree.
+                    None => return Ok(()),
+                },
+            };
+            let (state_key, state_value) = item?;
+
+            let (value, proof) = self
+                .state_store
+                .get_state_value_with_proof_by_version_ext(&state_key, version)?;
+            ensure!(
+                value.as_ref() == Some(&state_value),
+                "Value of {:?} read with proof differs from the one iterated.",
+                state_key,
+            );
+            proof.verify(root_hash, state_key.hash(), Some(&state_value))?;
+        }
+        Ok(())
+    }
+}
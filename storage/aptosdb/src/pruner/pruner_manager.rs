@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::pruner::db_pruner::DBPruner;
+use aptos_storage_interface::pruner_status::PrunerStatus;
 use aptos_types::transaction::Version;
 
 /// This module provides `Pruner` which manages a thread pruning old data in the background and is
@@ -35,6 +36,13 @@ pub trait PrunerManager: Sync {
 
     fn is_pruning_pending(&self) -> bool;
 
+    /// Changes the prune window, which takes effect the next time the target version is set. The
+    /// min readable version never goes backwards, data already pruned stays pruned.
+    fn set_prune_window(&self, prune_window: Version) -> anyhow::Result<()>;
+
+    /// Reports the progress of the pruner, `latest_version` being the latest committed version.
+    fn get_status(&self, latest_version: Version) -> anyhow::Result<PrunerStatus>;
+
     /// (For tests only.) Notifies the worker thread and waits for it to finish its job by polling
     /// an internal counter.
     #[cfg(test)]
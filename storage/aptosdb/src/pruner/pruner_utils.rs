@@ -16,7 +16,7 @@ use crate::{
 };
 use anyhow::Result;
 use aptos_jellyfish_merkle::StaleNodeIndex;
-use aptos_schemadb::{schema::KeyCodec, ReadOptions, DB};
+use aptos_schemadb::{schema::KeyCodec, ColumnFamilyName, ReadOptions, DB};
 use aptos_types::transaction::Version;
 
 pub(crate) fn get_ledger_pruner_progress(ledger_db: &LedgerDb) -> Result<Version> {
@@ -27,18 +27,26 @@ pub(crate) fn get_ledger_pruner_progress(ledger_db: &LedgerDb) -> Result<Version
         )? {
             version
         } else {
-            let mut iter = ledger_db
-                .transaction_info_db()
-                .iter::<TransactionInfoSchema>(ReadOptions::default())?;
-            iter.seek_to_first();
-            match iter.next().transpose()? {
-                Some((version, _)) => version,
-                None => 0,
+            // Data moved to the archive tier is not pruned.
+            let mut first_version = None;
+            if let Some(cold_db) = ledger_db.cold_db() {
+                first_version = get_first_transaction_info_version(cold_db.db())?;
             }
+            if first_version.is_none() {
+                first_version =
+                    get_first_transaction_info_version(ledger_db.transaction_info_db())?;
+            }
+            first_version.unwrap_or(0)
         },
     )
 }
 
+fn get_first_transaction_info_version(db: &DB) -> Result<Option<Version>> {
+    let mut iter = db.iter::<TransactionInfoSchema>(ReadOptions::default())?;
+    iter.seek_to_first();
+    Ok(iter.next().transpose()?.map(|(version, _)| version))
+}
+
 pub(crate) fn get_state_kv_pruner_progress(state_kv_db: &StateKvDb) -> Result<Version> {
     Ok(get_progress(
         state_kv_db.metadata_db(),
@@ -77,3 +85,25 @@ pub(crate) fn get_or_initialize_subpruner_progress(
         },
     )
 }
+
+/// Estimates the bytes freed by pruning from `progress` to `target_version`, assuming the live data
+/// in the column families is evenly spread across `progress..=latest_version`.
+pub(crate) fn estimate_reclaimable_bytes(
+    db: &DB,
+    cf_names: &[ColumnFamilyName],
+    progress: Version,
+    target_version: Version,
+    latest_version: Version,
+) -> Result<u64> {
+    if target_version <= progress || latest_version < progress {
+        return Ok(0);
+    }
+    let mut live_data_size = 0u128;
+    for cf_name in cf_names {
+        live_data_size += db.get_property(cf_name, "rocksdb.estimate-live-data-size")? as u128;
+    }
+    let num_versions = (latest_version - progress + 1) as u128;
+    let num_versions_to_prune =
+        (std::cmp::min(target_version, latest_version + 1) - progress) as u128;
+    Ok((live_data_size * num_versions_to_prune / num_versions) as u64)
+}
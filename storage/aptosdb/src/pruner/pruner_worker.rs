@@ -99,6 +99,14 @@ impl PrunerWorker {
     pub fn is_pruning_pending(&self) -> bool {
         self.inner.pruner.is_pruning_pending()
     }
+
+    pub fn progress(&self) -> Version {
+        self.inner.pruner.progress()
+    }
+
+    pub fn target_version(&self) -> Version {
+        self.inner.pruner.target_version()
+    }
 }
 
 impl Drop for PrunerWorker {
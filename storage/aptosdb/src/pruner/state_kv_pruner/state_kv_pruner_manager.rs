@@ -4,13 +4,19 @@
 use crate::{
     metrics::{PRUNER_BATCH_SIZE, PRUNER_VERSIONS, PRUNER_WINDOW},
     pruner::{
-        pruner_manager::PrunerManager, pruner_utils, pruner_worker::PrunerWorker,
-        state_kv_pruner::StateKvPruner,
+        pruner_manager::PrunerManager,
+        pruner_utils,
+        pruner_worker::PrunerWorker,
+        state_kv_pruner::{StateKvPruner, STATE_KV_PRUNER_NAME},
     },
+    schema::{db_metadata::DbMetadataKey, STALE_STATE_VALUE_INDEX_CF_NAME, STATE_VALUE_CF_NAME},
     state_kv_db::StateKvDb,
+    utils::get_progress,
 };
 use anyhow::Result;
 use aptos_config::config::LedgerPrunerConfig;
+use aptos_logger::info;
+use aptos_storage_interface::pruner_status::{PrunerShardStatus, PrunerStatus};
 use aptos_types::transaction::{AtomicVersion, Version};
 use std::sync::{atomic::Ordering, Arc};
 
@@ -18,7 +24,7 @@ use std::sync::{atomic::Ordering, Arc};
 pub(crate) struct StateKvPrunerManager {
     state_kv_db: Arc<StateKvDb>,
     /// DB version window, which dictates how many version of state values to keep.
-    prune_window: Version,
+    prune_window: AtomicVersion,
     /// It is None iff the pruner is not enabled.
     pruner_worker: Option<PrunerWorker>,
     /// Ideal batch size of the versions to be sent to the state kv pruner.
@@ -35,7 +41,7 @@ impl PrunerManager for StateKvPrunerManager {
     }
 
     fn get_prune_window(&self) -> Version {
-        self.prune_window
+        self.prune_window.load(Ordering::SeqCst)
     }
 
     fn get_min_readable_version(&self) -> Version {
@@ -48,7 +54,7 @@ impl PrunerManager for StateKvPrunerManager {
         // Only wake up the state kv pruner if there are `ledger_pruner_pruning_batch_size` pending
         if self.is_pruner_enabled()
             && latest_version
-                >= min_readable_version + self.pruning_batch_size as u64 + self.prune_window
+                >= min_readable_version + self.pruning_batch_size as u64 + self.get_prune_window()
         {
             self.set_pruner_target_db_version(latest_version);
         }
@@ -71,6 +77,73 @@ impl PrunerManager for StateKvPrunerManager {
             .map_or(false, |w| w.is_pruning_pending())
     }
 
+    fn set_prune_window(&self, prune_window: Version) -> Result<()> {
+        self.prune_window.store(prune_window, Ordering::SeqCst);
+        PRUNER_WINDOW
+            .with_label_values(&["state_kv_pruner"])
+            .set(prune_window as i64);
+        info!(
+            prune_window = prune_window,
+            "State KV pruner prune_window changed."
+        );
+        Ok(())
+    }
+
+    fn get_status(&self, latest_version: Version) -> Result<PrunerStatus> {
+        const CF_NAMES: &[&str] = &[STATE_VALUE_CF_NAME, STALE_STATE_VALUE_INDEX_CF_NAME];
+
+        let min_readable_version = self.get_min_readable_version();
+        let (progress, target_version) = self
+            .pruner_worker
+            .as_ref()
+            .map_or((min_readable_version, min_readable_version), |w| {
+                (w.progress(), w.target_version())
+            });
+
+        let mut shards = Vec::new();
+        let estimated_reclaimable_bytes = if self.state_kv_db.enabled_sharding() {
+            for shard_id in 0..self.state_kv_db.num_shards() {
+                let db_shard = self.state_kv_db.db_shard(shard_id);
+                let shard_progress = get_progress(
+                    db_shard,
+                    &DbMetadataKey::StateKvShardPrunerProgress(shard_id as usize),
+                )?
+                .unwrap_or(progress);
+                shards.push(PrunerShardStatus {
+                    shard_id,
+                    progress: shard_progress,
+                    estimated_reclaimable_bytes: pruner_utils::estimate_reclaimable_bytes(
+                        db_shard,
+                        CF_NAMES,
+                        shard_progress,
+                        target_version,
+                        latest_version,
+                    )?,
+                });
+            }
+            shards.iter().map(|s| s.estimated_reclaimable_bytes).sum()
+        } else {
+            pruner_utils::estimate_reclaimable_bytes(
+                self.state_kv_db.metadata_db(),
+                CF_NAMES,
+                progress,
+                target_version,
+                latest_version,
+            )?
+        };
+
+        Ok(PrunerStatus {
+            name: STATE_KV_PRUNER_NAME.to_string(),
+            enabled: self.is_pruner_enabled(),
+            prune_window: self.get_prune_window(),
+            min_readable_version,
+            progress,
+            target_version,
+            shards,
+            estimated_reclaimable_bytes,
+        })
+    }
+
     #[cfg(test)]
     fn set_worker_target_version(&self, target_version: Version) {
         self.pruner_worker
@@ -100,7 +173,7 @@ impl StateKvPrunerManager {
 
         Self {
             state_kv_db,
-            prune_window: state_kv_pruner_config.prune_window,
+            prune_window: AtomicVersion::new(state_kv_pruner_config.prune_window),
             pruner_worker,
             pruning_batch_size: state_kv_pruner_config.batch_size,
             min_readable_version: AtomicVersion::new(min_readable_version),
@@ -127,7 +200,7 @@ impl StateKvPrunerManager {
 
     fn set_pruner_target_db_version(&self, latest_version: Version) {
         assert!(self.pruner_worker.is_some());
-        let min_readable_version = latest_version.saturating_sub(self.prune_window);
+        let min_readable_version = latest_version.saturating_sub(self.get_prune_window());
         self.min_readable_version
             .store(min_readable_version, Ordering::SeqCst);
 
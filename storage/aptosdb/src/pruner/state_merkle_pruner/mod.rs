@@ -1,7 +1,7 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-pub(in crate::pruner) mod generics;
+pub(crate) mod generics;
 mod state_merkle_metadata_pruner;
 pub(crate) mod state_merkle_pruner_manager;
 mod state_merkle_shard_pruner;
@@ -12,12 +12,16 @@ use crate::{
         pruner_worker::PrunerWorker,
         state_merkle_pruner::{generics::StaleNodeIndexSchemaTrait, StateMerklePruner},
     },
+    schema::JELLYFISH_MERKLE_NODE_CF_NAME,
     state_merkle_db::StateMerkleDb,
+    utils::get_progress,
 };
 use anyhow::Result;
 use aptos_config::config::StateMerklePrunerConfig;
 use aptos_jellyfish_merkle::StaleNodeIndex;
-use aptos_schemadb::schema::KeyCodec;
+use aptos_logger::info;
+use aptos_schemadb::schema::{KeyCodec, Schema};
+use aptos_storage_interface::pruner_status::{PrunerShardStatus, PrunerStatus};
 use aptos_types::transaction::{AtomicVersion, Version};
 use std::{
     marker::PhantomData,
@@ -36,7 +40,7 @@ where
 {
     state_merkle_db: Arc<StateMerkleDb>,
     /// DB version window, which dictates how many versions of state merkle data to keep.
-    prune_window: Version,
+    prune_window: AtomicVersion,
     /// It is None iff the pruner is not enabled.
     pruner_worker: Option<PrunerWorker>,
     /// The minimal readable version for the state merkle data.
@@ -56,7 +60,7 @@ where
     }
 
     fn get_prune_window(&self) -> Version {
-        self.prune_window
+        self.prune_window.load(Ordering::SeqCst)
     }
 
     fn get_min_readable_version(&self) -> Version {
@@ -66,7 +70,9 @@ where
     /// Sets pruner target version when necessary.
     fn maybe_set_pruner_target_db_version(&self, latest_version: Version) {
         let min_readable_version = self.get_min_readable_version();
-        if self.is_pruner_enabled() && latest_version >= min_readable_version + self.prune_window {
+        if self.is_pruner_enabled()
+            && latest_version >= min_readable_version + self.get_prune_window()
+        {
             self.set_pruner_target_db_version(latest_version);
         }
     }
@@ -89,6 +95,73 @@ where
             .map_or(false, |w| w.is_pruning_pending())
     }
 
+    fn set_prune_window(&self, prune_window: Version) -> Result<()> {
+        self.prune_window.store(prune_window, Ordering::SeqCst);
+        PRUNER_WINDOW
+            .with_label_values(&[S::name()])
+            .set(prune_window as i64);
+        info!(
+            name = S::name(),
+            prune_window = prune_window,
+            "Pruner prune_window changed."
+        );
+        Ok(())
+    }
+
+    fn get_status(&self, latest_version: Version) -> Result<PrunerStatus> {
+        let cf_names = &[JELLYFISH_MERKLE_NODE_CF_NAME, S::COLUMN_FAMILY_NAME];
+
+        let min_readable_version = self.get_min_readable_version();
+        let (progress, target_version) = self
+            .pruner_worker
+            .as_ref()
+            .map_or((min_readable_version, min_readable_version), |w| {
+                (w.progress(), w.target_version())
+            });
+
+        // The top levels of the tree stay in the metadata DB even if sharded.
+        let mut estimated_reclaimable_bytes = pruner_utils::estimate_reclaimable_bytes(
+            self.state_merkle_db.metadata_db(),
+            cf_names,
+            progress,
+            target_version,
+            latest_version,
+        )?;
+        let mut shards = Vec::new();
+        if self.state_merkle_db.sharding_enabled() {
+            for shard_id in 0..self.state_merkle_db.num_shards() {
+                let db_shard = self.state_merkle_db.db_shard(shard_id);
+                let shard_progress =
+                    get_progress(db_shard, &S::progress_metadata_key(Some(shard_id)))?
+                        .unwrap_or(progress);
+                let shard_estimated_reclaimable_bytes = pruner_utils::estimate_reclaimable_bytes(
+                    db_shard,
+                    cf_names,
+                    shard_progress,
+                    target_version,
+                    latest_version,
+                )?;
+                estimated_reclaimable_bytes += shard_estimated_reclaimable_bytes;
+                shards.push(PrunerShardStatus {
+                    shard_id,
+                    progress: shard_progress,
+                    estimated_reclaimable_bytes: shard_estimated_reclaimable_bytes,
+                });
+            }
+        }
+
+        Ok(PrunerStatus {
+            name: S::name().to_string(),
+            enabled: self.is_pruner_enabled(),
+            prune_window: self.get_prune_window(),
+            min_readable_version,
+            progress,
+            target_version,
+            shards,
+            estimated_reclaimable_bytes,
+        })
+    }
+
     #[cfg(test)]
     fn set_worker_target_version(&self, target_version: Version) {
         self.pruner_worker
@@ -125,7 +198,7 @@ where
 
         Self {
             state_merkle_db,
-            prune_window: state_merkle_pruner_config.prune_window,
+            prune_window: AtomicVersion::new(state_merkle_pruner_config.prune_window),
             pruner_worker,
             min_readable_version: AtomicVersion::new(min_readable_version),
             _phantom: PhantomData,
@@ -159,7 +232,7 @@ where
     fn set_pruner_target_db_version(&self, latest_version: Version) {
         assert!(self.pruner_worker.is_some());
 
-        let min_readable_version = latest_version.saturating_sub(self.prune_window);
+        let min_readable_version = latest_version.saturating_sub(self.get_prune_window());
         self.min_readable_version
             .store(min_readable_version, Ordering::SeqCst);
 
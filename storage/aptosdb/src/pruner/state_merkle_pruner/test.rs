@@ -343,6 +343,57 @@ fn test_state_store_pruner_disabled() {
     }
 }
 
+#[test]
+fn test_state_store_pruner_status_and_prune_window() {
+    let key = StateKey::raw(String::from("test_key1").into_bytes());
+
+    let prune_batch_size = 10;
+    let num_versions = 25;
+    let tmp_dir = TempPath::new();
+    let aptos_db = AptosDB::new_for_test_no_cache(&tmp_dir);
+    let state_store = &aptos_db.state_store;
+
+    for i in 0..num_versions {
+        put_value_set(
+            state_store,
+            vec![(key.clone(), StateValue::from(vec![i as u8]))],
+            i, /* version */
+        );
+    }
+
+    let pruner = create_state_merkle_pruner_manager(&aptos_db.state_merkle_db(), prune_batch_size);
+    let status = pruner.get_status(num_versions - 1).unwrap();
+    assert!(status.enabled);
+    assert_eq!(status.prune_window, 0);
+    assert_eq!(status.min_readable_version, 0);
+    assert_eq!(status.progress, 0);
+    assert_eq!(status.target_version, 0);
+    assert_eq!(status.estimated_reclaimable_bytes, 0);
+    assert!(status.shards.is_empty());
+
+    // Takes effect without recreating the pruner.
+    pruner.set_prune_window(5).unwrap();
+    pruner
+        .wake_and_wait_pruner(20 /* latest_version */)
+        .unwrap();
+    let status = pruner.get_status(num_versions - 1).unwrap();
+    assert_eq!(status.prune_window, 5);
+    assert_eq!(status.min_readable_version, 15);
+    assert_eq!(status.progress, 15);
+    assert_eq!(status.target_version, 15);
+    assert!(state_store
+        .get_state_value_with_proof_by_version(&key, 14)
+        .is_err());
+    verify_state_in_store(state_store, key, Some(&StateValue::from(vec![15])), 15);
+
+    // What the DB's own pruner would have recorded had it done the pruning.
+    state_store
+        .state_merkle_pruner
+        .save_min_readable_version(15)
+        .unwrap();
+    assert_eq!(aptos_db.check_pruned_state(10).unwrap(), vec![15, 24]);
+}
+
 proptest! {
     #![proptest_config(ProptestConfig::with_cases(10))]
 
@@ -0,0 +1,333 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! A snapshot bundle is a DB checkpoint along with the latest ledger info in it and an epoch change
+//! proof leading to that ledger info, so a node can bootstrap from it after verifying it against
+//! its waypoint, without trusting whoever made the bundle. The root hashes are listed in a manifest
+//! for operators to compare bundles at a glance.
+//!
+//! Layout:
+//!   <bundle>/db/          the checkpoint
+//!   <bundle>/proof.bcs    the ledger info and the epoch change proof
+//!   <bundle>/manifest.json
+
+#[cfg(test)]
+mod test;
+
+use crate::{
+    consistency_check::Mismatch, ledger_db::LEDGER_DB_FOLDER_NAME, AptosDB, NUM_STATE_SHARDS,
+};
+use anyhow::{anyhow, bail, ensure, Result};
+use aptos_config::config::{
+    RocksdbConfigs, StorageDirPaths, BUFFERED_STATE_TARGET_ITEMS,
+    DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD, NO_OP_STORAGE_PRUNER_CONFIG,
+};
+use aptos_crypto::HashValue;
+use aptos_logger::info;
+use aptos_storage_interface::DbReader;
+use aptos_types::{
+    epoch_change::EpochChangeProof, ledger_info::LedgerInfoWithSignatures, transaction::Version,
+    trusted_state::TrustedState, waypoint::Waypoint,
+};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::min,
+    fs,
+    path::{Path, PathBuf},
+};
+
+pub const SNAPSHOT_BUNDLE_DB_DIR_NAME: &str = "db";
+pub const SNAPSHOT_BUNDLE_PROOF_FILE_NAME: &str = "proof.bcs";
+pub const SNAPSHOT_BUNDLE_MANIFEST_FILE_NAME: &str = "manifest.json";
+
+/// Number of versions checked together by the full verification.
+const VERIFICATION_CHUNK_SIZE: u64 = 100_000;
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SnapshotBundleManifest {
+    pub version: Version,
+    pub epoch: u64,
+    pub transaction_accumulator_root_hash: HashValue,
+    pub state_snapshot_version: Version,
+    pub state_root_hash: HashValue,
+    pub enable_storage_sharding: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct SnapshotBundleProof {
+    ledger_info_with_sigs: LedgerInfoWithSignatures,
+    epoch_change_proof: EpochChangeProof,
+}
+
+pub struct SnapshotBundle {
+    path: PathBuf,
+    manifest: SnapshotBundleManifest,
+    proof: SnapshotBundleProof,
+}
+
+impl SnapshotBundle {
+    /// Makes a bundle of the DB at `db_path` in `bundle_path`, with an epoch change proof starting
+    /// from `start_epoch`. The proof has to start at or before the epoch of the waypoint the
+    /// bundle will be verified against, so 0 works for everyone.
+    pub fn create(
+        db_path: impl AsRef<Path>,
+        bundle_path: impl AsRef<Path>,
+        enable_storage_sharding: bool,
+        start_epoch: u64,
+    ) -> Result<Self> {
+        let bundle_path = bundle_path.as_ref();
+        ensure!(!bundle_path.exists(), "{:?} already exists.", bundle_path);
+        let checkpoint_path = bundle_path.join(SNAPSHOT_BUNDLE_DB_DIR_NAME);
+        fs::create_dir_all(&checkpoint_path)?;
+        // Everything else is read from the checkpoint, which can't move on like the DB can.
+        AptosDB::create_checkpoint(db_path, &checkpoint_path, enable_storage_sharding)?;
+        let db = open_readonly(&checkpoint_path, enable_storage_sharding)?;
+
+        let ledger_info_with_sigs = db.get_latest_ledger_info()?;
+        let ledger_info = ledger_info_with_sigs.ledger_info();
+        // Include the ledger info itself if it ends an epoch.
+        let end_epoch = ledger_info.next_block_epoch();
+        ensure!(
+            start_epoch <= ledger_info.epoch(),
+            "Start epoch {} is after the epoch of the latest ledger info, {}.",
+            start_epoch,
+            ledger_info.epoch(),
+        );
+        let mut epoch_ending_ledger_infos = Vec::new();
+        let mut epoch = start_epoch;
+        while epoch < end_epoch {
+            let chunk = DbReader::get_epoch_ending_ledger_infos(&db, epoch, end_epoch)?
+                .ledger_info_with_sigs;
+            match chunk.last() {
+                Some(last) => epoch = last.ledger_info().next_block_epoch(),
+                None => bail!("No epoch ending ledger info for epoch {}.", epoch),
+            }
+            epoch_ending_ledger_infos.extend(chunk);
+        }
+
+        let (state_snapshot_version, state_root_hash) = db
+            .get_state_snapshot_before(ledger_info.version() + 1)?
+            .ok_or_else(|| anyhow!("No state snapshot in the DB."))?;
+        let manifest = SnapshotBundleManifest {
+            version: ledger_info.version(),
+            epoch: ledger_info.epoch(),
+            transaction_accumulator_root_hash: ledger_info.transaction_accumulator_hash(),
+            state_snapshot_version,
+            state_root_hash,
+            enable_storage_sharding,
+        };
+        let proof = SnapshotBundleProof {
+            ledger_info_with_sigs: ledger_info_with_sigs.clone(),
+            epoch_change_proof: EpochChangeProof::new(epoch_ending_ledger_infos, false),
+        };
+        fs::write(
+            bundle_path.join(SNAPSHOT_BUNDLE_PROOF_FILE_NAME),
+            bcs::to_bytes(&proof)?,
+        )?;
+        // Written last, a bundle without a manifest is incomplete.
+        fs::write(
+            bundle_path.join(SNAPSHOT_BUNDLE_MANIFEST_FILE_NAME),
+            serde_json::to_vec_pretty(&manifest)?,
+        )?;
+        info!(
+            bundle_path = bundle_path,
+            version = manifest.version,
+            state_snapshot_version = manifest.state_snapshot_version,
+            "Made snapshot bundle."
+        );
+
+        Ok(Self {
+            path: bundle_path.to_path_buf(),
+            manifest,
+            proof,
+        })
+    }
+
+    pub fn open(bundle_path: impl AsRef<Path>) -> Result<Self> {
+        let path = bundle_path.as_ref().to_path_buf();
+        let manifest =
+            serde_json::from_slice(&fs::read(path.join(SNAPSHOT_BUNDLE_MANIFEST_FILE_NAME))?)?;
+        let proof = bcs::from_bytes(&fs::read(path.join(SNAPSHOT_BUNDLE_PROOF_FILE_NAME))?)?;
+        Ok(Self {
+            path,
+            manifest,
+            proof,
+        })
+    }
+
+    pub fn manifest(&self) -> &SnapshotBundleManifest {
+        &self.manifest
+    }
+
+    pub fn ledger_info_with_sigs(&self) -> &LedgerInfoWithSignatures {
+        &self.proof.ledger_info_with_sigs
+    }
+
+    /// Verifies the ledger info against `waypoint` with the epoch change proof, then the
+    /// transaction accumulator and the state snapshot in the checkpoint against the ledger info,
+    /// and that the manifest agrees. With `full_verification`, also checks every transaction,
+    /// index and JMT node in the checkpoint, see `AptosDB::check_ledger_consistency()` and
+    /// `AptosDB::check_state_shard_consistency()`.
+    pub fn verify(&self, waypoint: Waypoint, full_verification: bool) -> Result<()> {
+        let ledger_info_with_sigs = &self.proof.ledger_info_with_sigs;
+        let ledger_info = ledger_info_with_sigs.ledger_info();
+        let change = TrustedState::from_epoch_waypoint(waypoint)
+            .verify_and_ratchet_inner(ledger_info_with_sigs, &self.proof.epoch_change_proof)?;
+        // If the ledger info is in a later epoch than the proof gets to, the trusted state only
+        // ratchets to the end of the proof.
+        if let Some(new_state) = change.new_state() {
+            ensure!(
+                new_state.waypoint() == Waypoint::new_any(ledger_info),
+                "The epoch change proof doesn't get to the epoch of the ledger info, {}.",
+                ledger_info.epoch(),
+            );
+        }
+
+        let manifest = &self.manifest;
+        ensure!(
+            manifest.version == ledger_info.version()
+                && manifest.epoch == ledger_info.epoch()
+                && manifest.transaction_accumulator_root_hash
+                    == ledger_info.transaction_accumulator_hash(),
+            "Manifest {:?} doesn't match the ledger info {}.",
+            manifest,
+            ledger_info,
+        );
+        ensure!(
+            manifest.state_snapshot_version <= manifest.version,
+            "State snapshot at version {} is after the ledger info at {}.",
+            manifest.state_snapshot_version,
+            manifest.version,
+        );
+
+        let db = open_readonly(self.db_path(), manifest.enable_storage_sharding)?;
+        ensure!(
+            &db.get_latest_ledger_info()? == ledger_info_with_sigs,
+            "The latest ledger info in the checkpoint is not the one in the proof.",
+        );
+        db.ledger_store
+            .get_transaction_info_with_proof(manifest.state_snapshot_version, manifest.version)?
+            .verify(ledger_info, manifest.state_snapshot_version)?;
+        let txn_info = db
+            .ledger_store
+            .get_transaction_info(manifest.state_snapshot_version)?;
+        ensure!(
+            txn_info.state_checkpoint_hash() == Some(manifest.state_root_hash),
+            "State root hash in the manifest is {}, the verified transaction info has {:?}.",
+            manifest.state_root_hash,
+            txn_info.state_checkpoint_hash(),
+        );
+        let state_root_hash = db
+            .state_store
+            .state_db
+            .state_merkle_db
+            .get_root_hash(manifest.state_snapshot_version)?;
+        ensure!(
+            state_root_hash == manifest.state_root_hash,
+            "JMT root at version {} is {}, expecting {}.",
+            manifest.state_snapshot_version,
+            state_root_hash,
+            manifest.state_root_hash,
+        );
+
+        if full_verification {
+            let mismatches = db.check_snapshot_bundle(manifest)?;
+            if let Some(first) = mismatches.first() {
+                bail!(
+                    "Found {} inconsistencies in the checkpoint, the first: {:?}",
+                    mismatches.len(),
+                    first,
+                );
+            }
+        }
+        info!(
+            bundle_path = &self.path,
+            version = manifest.version,
+            waypoint = %waypoint,
+            full_verification = full_verification,
+            "Verified snapshot bundle."
+        );
+
+        Ok(())
+    }
+
+    /// Makes a checkpoint of the bundled DB at `db_path`. The bundle should be verified first.
+    pub fn install(&self, db_path: impl AsRef<Path>) -> Result<()> {
+        let db_path = db_path.as_ref();
+        ensure!(
+            !db_exists(db_path),
+            "There is already a DB at {:?}.",
+            db_path
+        );
+        fs::create_dir_all(db_path)?;
+        AptosDB::create_checkpoint(
+            self.db_path(),
+            db_path,
+            self.manifest.enable_storage_sharding,
+        )?;
+        info!(
+            bundle_path = &self.path,
+            db_path = db_path,
+            version = self.manifest.version,
+            "Installed snapshot bundle."
+        );
+        Ok(())
+    }
+
+    fn db_path(&self) -> PathBuf {
+        self.path.join(SNAPSHOT_BUNDLE_DB_DIR_NAME)
+    }
+}
+
+/// Whether there's an AptosDB at `db_path`.
+pub fn db_exists(db_path: impl AsRef<Path>) -> bool {
+    db_path.as_ref().join(LEDGER_DB_FOLDER_NAME).exists()
+}
+
+impl AptosDB {
+    fn check_snapshot_bundle(&self, manifest: &SnapshotBundleManifest) -> Result<Vec<Mismatch>> {
+        let start_version = self.get_first_txn_version()?.unwrap_or(0);
+        let end_version = manifest.version + 1;
+        let ledger_chunks: Vec<_> = (start_version..end_version)
+            .step_by(VERIFICATION_CHUNK_SIZE as usize)
+            .map(|chunk_start| {
+                (
+                    chunk_start,
+                    min(chunk_start + VERIFICATION_CHUNK_SIZE, end_version),
+                )
+            })
+            .collect();
+        let mut mismatches = ledger_chunks
+            .par_iter()
+            .map(|(chunk_start, chunk_end)| self.check_ledger_consistency(*chunk_start, *chunk_end))
+            .collect::<Result<Vec<_>>>()?;
+        mismatches.extend(
+            (0..NUM_STATE_SHARDS as u8)
+                .into_par_iter()
+                .map(|shard_id| {
+                    self.check_state_shard_consistency(
+                        shard_id,
+                        Some(manifest.state_snapshot_version),
+                    )
+                })
+                .collect::<Result<Vec<_>>>()?,
+        );
+        Ok(mismatches.into_iter().flatten().collect())
+    }
+}
+
+fn open_readonly(db_path: &Path, enable_storage_sharding: bool) -> Result<AptosDB> {
+    AptosDB::open(
+        StorageDirPaths::from_path(db_path),
+        /*readonly=*/ true,
+        NO_OP_STORAGE_PRUNER_CONFIG,
+        RocksdbConfigs {
+            enable_storage_sharding,
+            ..Default::default()
+        },
+        /*enable_indexer=*/ false,
+        BUFFERED_STATE_TARGET_ITEMS,
+        DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+    )
+}
@@ -0,0 +1,103 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    snapshot_bundle::{db_exists, SnapshotBundle, SNAPSHOT_BUNDLE_MANIFEST_FILE_NAME},
+    test_helper::{arb_blocks_to_commit, update_in_memory_state},
+    AptosDB,
+};
+use aptos_crypto::HashValue;
+use aptos_storage_interface::DbReader;
+use aptos_temppath::TempPath;
+use aptos_types::{
+    ledger_info::LedgerInfoWithSignatures,
+    transaction::{TransactionToCommit, Version},
+    waypoint::Waypoint,
+};
+use proptest::prelude::*;
+use std::{fs, str::FromStr};
+
+fn test_snapshot_bundle_impl(input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>) {
+    let tmp_dir = TempPath::new();
+    {
+        let db = AptosDB::new_for_test(&tmp_dir);
+        let mut in_memory_state = db
+            .state_store
+            .buffered_state()
+            .lock()
+            .current_state()
+            .clone();
+        let mut next_ver: Version = 0;
+        for (txns_to_commit, ledger_info_with_sigs) in input.iter() {
+            update_in_memory_state(&mut in_memory_state, txns_to_commit.as_slice());
+            db.save_transactions_for_test(
+                txns_to_commit,
+                next_ver,                /* first_version */
+                next_ver.checked_sub(1), /* base_state_version */
+                Some(ledger_info_with_sigs),
+                true, /* sync_commit */
+                in_memory_state.clone(),
+            )
+            .unwrap();
+            next_ver += txns_to_commit.len() as u64;
+        }
+    }
+    // The first ledger info always ends epoch 0.
+    let waypoint = Waypoint::new_epoch_boundary(input[0].1.ledger_info()).unwrap();
+    let latest_ledger_info = &input.last().unwrap().1;
+
+    let bundle_dir = TempPath::new();
+    let bundle = SnapshotBundle::create(
+        &tmp_dir,
+        &bundle_dir,
+        false, /* enable_storage_sharding */
+        0,     /* start_epoch */
+    )
+    .unwrap();
+    assert_eq!(bundle.ledger_info_with_sigs(), latest_ledger_info);
+    assert_eq!(
+        bundle.manifest().version,
+        latest_ledger_info.ledger_info().version()
+    );
+
+    let bundle = SnapshotBundle::open(&bundle_dir).unwrap();
+    bundle
+        .verify(waypoint, true /* full_verification */)
+        .unwrap();
+    let other_waypoint = Waypoint::from_str(&format!(
+        "{}:{}",
+        waypoint.version(),
+        HashValue::random().to_hex()
+    ))
+    .unwrap();
+    assert!(bundle.verify(other_waypoint, false).is_err());
+
+    let db_dir = TempPath::new();
+    assert!(!db_exists(&db_dir));
+    bundle.install(&db_dir).unwrap();
+    assert!(db_exists(&db_dir));
+    assert!(bundle.install(&db_dir).is_err());
+    let db = AptosDB::new_for_test(&db_dir);
+    assert_eq!(&db.get_latest_ledger_info().unwrap(), latest_ledger_info);
+    drop(db);
+
+    // The manifest is checked too.
+    let mut manifest = bundle.manifest().clone();
+    manifest.state_root_hash = HashValue::random();
+    fs::write(
+        bundle_dir.path().join(SNAPSHOT_BUNDLE_MANIFEST_FILE_NAME),
+        serde_json::to_vec(&manifest).unwrap(),
+    )
+    .unwrap();
+    let bundle = SnapshotBundle::open(&bundle_dir).unwrap();
+    assert!(bundle.verify(waypoint, false).is_err());
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(5))]
+
+    #[test]
+    fn test_snapshot_bundle(input in arb_blocks_to_commit()) {
+        test_snapshot_bundle_impl(input);
+    }
+}
@@ -28,7 +28,7 @@ use aptos_schemadb::{SchemaBatch, DB};
 use aptos_scratchpad::get_state_shard_id;
 use aptos_types::{
     nibble::{nibble_path::NibblePath, ROOT_NIBBLE_HEIGHT},
-    proof::{SparseMerkleProofExt, SparseMerkleRangeProof},
+    proof::{SparseMerkleMultiProof, SparseMerkleProofExt, SparseMerkleRangeProof},
     state_store::state_key::StateKey,
     transaction::Version,
 };
@@ -248,6 +248,17 @@ impl StateMerkleDb {
         JellyfishMerkleTree::new(self).get_with_proof_ext(state_key.hash(), version)
     }
 
+    pub fn get_with_multi_proof(
+        &self,
+        key_hashes: &[HashValue],
+        version: Version,
+    ) -> Result<(
+        Vec<Option<(HashValue, (StateKey, Version))>>,
+        SparseMerkleMultiProof,
+    )> {
+        JellyfishMerkleTree::new(self).get_with_multi_proof(key_hashes, version)
+    }
+
     pub fn get_range_proof(
         &self,
         rightmost_key: HashValue,
@@ -310,6 +321,22 @@ impl StateMerkleDb {
         Ok(None)
     }
 
+    /// Returns the first version at or after `version` with a state snapshot.
+    pub fn get_state_snapshot_version_after(&self, version: Version) -> Result<Option<Version>> {
+        let mut iter = self
+            .metadata_db()
+            .iter::<JellyfishMerkleNodeSchema>(Default::default())?;
+        iter.seek(&NodeKey::new_empty_path(version))?;
+        while let Some((key, _node)) = iter.next().transpose()? {
+            if key.nibble_path().is_empty() {
+                return Ok(Some(key.version()));
+            }
+            // No root at this version, the first key of the next version is its root if any.
+            iter.seek(&NodeKey::new_empty_path(key.version() + 1))?;
+        }
+        Ok(None)
+    }
+
     fn create_jmt_commit_batch_for_shard(
         &self,
         shard_id: Option<u8>,
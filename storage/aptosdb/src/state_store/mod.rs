@@ -47,7 +47,9 @@ use aptos_storage_interface::{
     DbReader, StateSnapshotReceiver,
 };
 use aptos_types::{
-    proof::{definition::LeafCount, SparseMerkleProofExt, SparseMerkleRangeProof},
+    proof::{
+        definition::LeafCount, SparseMerkleMultiProof, SparseMerkleProofExt, SparseMerkleRangeProof,
+    },
     state_store::{
         create_empty_sharded_state_updates,
         state_key::StateKey,
@@ -61,7 +63,11 @@ use aptos_types::{
 };
 use claims::{assert_ge, assert_le};
 use rayon::prelude::*;
-use std::{collections::HashSet, ops::Deref, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    ops::Deref,
+    sync::Arc,
+};
 
 pub(crate) mod buffered_state;
 mod state_merkle_batch_committer;
@@ -187,6 +193,37 @@ impl DbReader for StateDb {
         ))
     }
 
+    /// Get the state values of multiple state keys with a single proof given the version
+    fn get_state_proof_with_multiple_keys(
+        &self,
+        state_keys: &[StateKey],
+        version: Version,
+    ) -> Result<(Vec<Option<StateValue>>, SparseMerkleMultiProof)> {
+        let key_hashes: Vec<_> = state_keys.iter().map(|key| key.hash()).collect();
+        let mut sorted_key_hashes = key_hashes.clone();
+        sorted_key_hashes.sort();
+        sorted_key_hashes.dedup();
+
+        let (leaf_data, proof) = self
+            .state_merkle_db
+            .get_with_multi_proof(&sorted_key_hashes, version)?;
+        let mut values = HashMap::new();
+        for (key_hash, leaf) in sorted_key_hashes.into_iter().zip(leaf_data) {
+            let value = match leaf {
+                Some((_, (key, version))) => Some(self.expect_value_by_version(&key, version)?),
+                None => None,
+            };
+            values.insert(key_hash, value);
+        }
+        Ok((
+            key_hashes
+                .iter()
+                .map(|key_hash| values[key_hash].clone())
+                .collect(),
+            proof,
+        ))
+    }
+
     fn get_state_storage_usage(&self, version: Option<Version>) -> Result<StateStorageUsage> {
         version.map_or(Ok(StateStorageUsage::zero()), |version| {
             Ok(
@@ -279,6 +316,16 @@ impl DbReader for StateStore {
         self.deref()
             .get_state_value_with_proof_by_version_ext(state_key, version)
     }
+
+    /// Get the state values of multiple state keys with a single proof given the version
+    fn get_state_proof_with_multiple_keys(
+        &self,
+        state_keys: &[StateKey],
+        version: Version,
+    ) -> Result<(Vec<Option<StateValue>>, SparseMerkleMultiProof)> {
+        self.deref()
+            .get_state_proof_with_multiple_keys(state_keys, version)
+    }
 }
 
 impl StateDb {
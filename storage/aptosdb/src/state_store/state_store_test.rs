@@ -165,6 +165,52 @@ fn test_state_store_reader_writer() {
     verify_value_and_proof(store, key3, Some(&value3), 1, root);
 }
 
+#[test]
+fn test_get_state_proof_with_multiple_keys() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+    let store = &db.state_store;
+    let keys: Vec<_> = (0..10)
+        .map(|i| StateKey::raw(format!("test_key{}", i).into_bytes()))
+        .collect();
+    let value_set: Vec<_> = keys[..6]
+        .iter()
+        .map(|key| (key.clone(), StateValue::from(key.hash().to_vec())))
+        .collect();
+    let root = put_value_set(store, value_set.clone(), 0 /* version */, None);
+
+    // Unordered, with a duplicate and keys that don't exist.
+    let requested_keys = vec![
+        keys[7].clone(),
+        keys[2].clone(),
+        keys[0].clone(),
+        keys[9].clone(),
+        keys[2].clone(),
+        keys[5].clone(),
+    ];
+    let (values, proof) = store
+        .get_state_proof_with_multiple_keys(&requested_keys, 0)
+        .unwrap();
+    assert_eq!(values, vec![
+        None,
+        Some(value_set[2].1.clone()),
+        Some(value_set[0].1.clone()),
+        None,
+        Some(value_set[2].1.clone()),
+        Some(value_set[5].1.clone()),
+    ]);
+
+    let mut proved: Vec<_> = requested_keys
+        .iter()
+        .zip(&values)
+        .map(|(key, value)| (key.hash(), value.as_ref()))
+        .collect();
+    proved.sort_by_key(|(key_hash, _)| *key_hash);
+    proved.dedup_by_key(|(key_hash, _)| *key_hash);
+    let (key_hashes, values): (Vec<_>, Vec<_>) = proved.into_iter().unzip();
+    proof.verify(root, &key_hashes, &values).unwrap();
+}
+
 fn traverse_values(
     store: &StateStore,
     prefix: &StateKeyPrefix,
@@ -5,6 +5,7 @@
 //! This file defines transaction store APIs that are related to committed signed transactions.
 
 use crate::{
+    cold_ledger_db::{get_tiered, TieredIter},
     errors::AptosDbError,
     ledger_db::LedgerDb,
     schema::{
@@ -107,10 +108,12 @@ impl TransactionStore {
 
     /// Get signed transaction given `version`
     pub fn get_transaction(&self, version: Version) -> Result<Transaction> {
-        self.ledger_db
-            .transaction_db()
-            .get::<TransactionSchema>(&version)?
-            .ok_or_else(|| AptosDbError::NotFound(format!("Txn {}", version)).into())
+        get_tiered::<TransactionSchema>(
+            self.ledger_db.transaction_db(),
+            self.ledger_db.cold_db(),
+            &version,
+        )?
+        .ok_or_else(|| AptosDbError::NotFound(format!("Txn {}", version)).into())
     }
 
     /// Gets an iterator that yields at most `num_transactions` transactions starting from `start_version`.
@@ -119,12 +122,12 @@ impl TransactionStore {
         start_version: Version,
         num_transactions: usize,
     ) -> Result<impl Iterator<Item = Result<Transaction>> + '_> {
-        let mut iter = self
-            .ledger_db
-            .transaction_db()
-            .iter::<TransactionSchema>(ReadOptions::default())?;
-        iter.seek(&start_version)?;
-        iter.expect_continuous_versions(start_version, num_transactions)
+        TieredIter::<TransactionSchema>::new(
+            self.ledger_db.transaction_db(),
+            self.ledger_db.cold_db(),
+            &start_version,
+        )?
+        .expect_continuous_versions(start_version, num_transactions)
     }
 
     /// Gets an iterator that yields `num_transactions` write sets starting from `start_version`.
@@ -133,12 +136,12 @@ impl TransactionStore {
         start_version: Version,
         num_transactions: usize,
     ) -> Result<impl Iterator<Item = Result<WriteSet>> + '_> {
-        let mut iter = self
-            .ledger_db
-            .write_set_db()
-            .iter::<WriteSetSchema>(ReadOptions::default())?;
-        iter.seek(&start_version)?;
-        iter.expect_continuous_versions(start_version, num_transactions)
+        TieredIter::<WriteSetSchema>::new(
+            self.ledger_db.write_set_db(),
+            self.ledger_db.cold_db(),
+            &start_version,
+        )?
+        .expect_continuous_versions(start_version, num_transactions)
     }
 
     /// Save signed transaction at `version`
@@ -165,12 +168,12 @@ impl TransactionStore {
 
     /// Get executed transaction vm output given `version`
     pub fn get_write_set(&self, version: Version) -> Result<WriteSet> {
-        self.ledger_db
-            .write_set_db()
-            .get::<WriteSetSchema>(&version)?
-            .ok_or_else(|| {
-                AptosDbError::NotFound(format!("WriteSet at version {}", version)).into()
-            })
+        get_tiered::<WriteSetSchema>(
+            self.ledger_db.write_set_db(),
+            self.ledger_db.cold_db(),
+            &version,
+        )?
+        .ok_or_else(|| AptosDbError::NotFound(format!("WriteSet at version {}", version)).into())
     }
 
     /// Get write sets in `[begin_version, end_version)` half-open range.
@@ -191,11 +194,11 @@ impl TransactionStore {
             end_version
         );
 
-        let mut iter = self
-            .ledger_db
-            .write_set_db()
-            .iter::<WriteSetSchema>(Default::default())?;
-        iter.seek(&begin_version)?;
+        let mut iter = TieredIter::<WriteSetSchema>::new(
+            self.ledger_db.write_set_db(),
+            self.ledger_db.cold_db(),
+            &begin_version,
+        )?;
 
         let mut ret = Vec::with_capacity((end_version - begin_version) as usize);
         for current_version in begin_version..end_version {
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    cold_ledger_db::TieredIter,
     schema::{
         event::EventSchema, ledger_info::LedgerInfoSchema, state_value::StateValueSchema,
         state_value_index::StateValueIndexSchema,
@@ -380,14 +381,14 @@ impl<'a> Iterator for EpochEndingLedgerInfoIter<'a> {
 }
 
 pub struct EventsByVersionIter<'a> {
-    inner: Peekable<SchemaIterator<'a, EventSchema>>,
+    inner: Peekable<TieredIter<'a, EventSchema>>,
     expected_next_version: Version,
     end_version: Version,
 }
 
 impl<'a> EventsByVersionIter<'a> {
     pub(crate) fn new(
-        inner: SchemaIterator<'a, EventSchema>,
+        inner: TieredIter<'a, EventSchema>,
         expected_next_version: Version,
         end_version: Version,
     ) -> Self {
@@ -65,7 +65,7 @@ pub(crate) fn get_state_merkle_commit_progress(
 }
 
 pub(crate) fn truncate_ledger_db(ledger_db: Arc<LedgerDb>, target_version: Version) -> Result<()> {
-    let event_store = EventStore::new(ledger_db.event_db_arc());
+    let event_store = EventStore::new(ledger_db.event_db_arc(), ledger_db.cold_db_arc());
     let transaction_store = TransactionStore::new(Arc::clone(&ledger_db));
 
     let start_version = target_version + 1;
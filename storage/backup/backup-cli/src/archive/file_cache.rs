@@ -0,0 +1,95 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    storage::{
+        BackupHandle, BackupHandleRef, BackupStorage, FileHandle, FileHandleRef, ShellSafeName,
+        TextLine,
+    },
+    utils::storage_ext::BackupStorageExt,
+};
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use sha2::{Digest, Sha256};
+use std::{
+    io::Cursor,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tokio::io::{AsyncRead, AsyncWrite};
+
+/// Read only view of another storage, keeping a copy of every file read in a local directory so
+/// it's downloaded only once.
+///
+/// What's in the directory is not trusted: the archive verifies every file against the proofs
+/// each time it's loaded, same as the ones downloaded.
+pub struct FileCacheStorage {
+    inner: Arc<dyn BackupStorage>,
+    dir: PathBuf,
+}
+
+impl FileCacheStorage {
+    pub fn new(inner: Arc<dyn BackupStorage>, dir: &Path) -> Result<Self> {
+        std::fs::create_dir_all(dir)?;
+        Ok(Self {
+            inner,
+            dir: dir.to_path_buf(),
+        })
+    }
+
+    fn cached_path(&self, file_handle: &FileHandleRef) -> PathBuf {
+        self.dir
+            .join(hex::encode(Sha256::digest(file_handle.as_bytes())))
+    }
+}
+
+#[async_trait]
+impl BackupStorage for FileCacheStorage {
+    async fn create_backup(&self, _name: &ShellSafeName) -> Result<BackupHandle> {
+        bail!("Archive storage is read only.")
+    }
+
+    async fn create_for_write(
+        &self,
+        _backup_handle: &BackupHandleRef,
+        _name: &ShellSafeName,
+    ) -> Result<(FileHandle, Box<dyn AsyncWrite + Send + Unpin>)> {
+        bail!("Archive storage is read only.")
+    }
+
+    async fn open_for_read(
+        &self,
+        file_handle: &FileHandleRef,
+    ) -> Result<Box<dyn AsyncRead + Send + Unpin>> {
+        let path = self.cached_path(file_handle);
+        let bytes = match tokio::fs::read(&path).await {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                let bytes = self.inner.read_all(file_handle).await?;
+                // Written aside and renamed, so a partially written file is never read.
+                let tmp_path = path.with_extension(format!("tmp.{}", rand::random::<u32>()));
+                tokio::fs::write(&tmp_path, &bytes).await?;
+                tokio::fs::rename(&tmp_path, &path).await?;
+                bytes
+            },
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Box::new(Cursor::new(bytes)))
+    }
+
+    async fn list_metadata_files(&self) -> Result<Vec<FileHandle>> {
+        self.inner.list_metadata_files().await
+    }
+
+    async fn backup_metadata_file(&self, _file_handle: &FileHandleRef) -> Result<()> {
+        bail!("Archive storage is read only.")
+    }
+
+    async fn save_metadata_lines(
+        &self,
+        _name: &ShellSafeName,
+        _lines: &[TextLine],
+    ) -> Result<FileHandle> {
+        bail!("Archive storage is read only.")
+    }
+}
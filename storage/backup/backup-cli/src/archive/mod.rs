@@ -0,0 +1,189 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Serves historical transactions and state straight from a backup storage, without restoring it
+//! into a DB. Only the chunks needed to answer a query are downloaded, verified and cached.
+
+mod file_cache;
+pub mod reader;
+
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    archive::reader::Archive,
+    metadata::cache::MetadataCacheOpt,
+    storage::BackupStorage,
+    utils::{ConcurrentDownloadsOpt, TrustedWaypointOpt},
+};
+use anyhow::{bail, ensure, Result};
+use aptos_storage_interface::DbReader;
+use aptos_types::{
+    account_config::NewBlockEvent,
+    epoch_change::EpochChangeProof,
+    ledger_info::LedgerInfoWithSignatures,
+    state_store::{state_key::StateKey, state_value::StateValue},
+    transaction::{TransactionWithProof, Version},
+};
+use clap::Parser;
+use std::{future::Future, path::PathBuf, sync::Arc};
+use tokio::runtime::Runtime;
+
+#[derive(Parser)]
+pub struct ArchiveOpt {
+    #[clap(flatten)]
+    pub metadata_cache_opt: MetadataCacheOpt,
+    #[clap(flatten)]
+    pub trusted_waypoints_opt: TrustedWaypointOpt,
+    #[clap(flatten)]
+    pub concurrent_downloads: ConcurrentDownloadsOpt,
+    #[clap(
+        long = "archive-cache-dir",
+        value_parser,
+        help = "Where the backup files fetched to answer queries are kept, so they are downloaded \
+        only once. Can be shared across runs, the files are verified every time they are loaded."
+    )]
+    pub cache_dir: PathBuf,
+    #[clap(
+        long,
+        default_value_t = 64,
+        help = "Max number of verified transaction chunks kept in memory, and of verified \
+        chunks of each state snapshot."
+    )]
+    pub max_cached_chunks: usize,
+}
+
+/// Exposes an [`Archive`] as a `DbReader`, so that what reads from a DB, the REST API for
+/// example, can serve queries from a backup.
+///
+/// Only the transaction and state queries are served. Transaction proofs are against the
+/// LedgerInfo ending the epoch the transaction is in rather than against `ledger_version`.
+pub struct ArchiveDb {
+    // Only taken on drop.
+    runtime: Option<Runtime>,
+    archive: Arc<Archive>,
+}
+
+impl ArchiveDb {
+    pub fn open(opt: ArchiveOpt, storage: Arc<dyn BackupStorage>) -> Result<Self> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .thread_name("backup-archive")
+            .enable_all()
+            .build()?;
+        let archive = Self::block_on_runtime(&runtime, Archive::open(opt, storage))?;
+        Ok(Self {
+            runtime: Some(runtime),
+            archive: Arc::new(archive),
+        })
+    }
+
+    pub fn archive(&self) -> &Arc<Archive> {
+        &self.archive
+    }
+
+    /// Spawns the future on the archive's own runtime, so the caller doesn't need to be in one,
+    /// or can be in another.
+    fn block_on_runtime<T: Send + 'static>(
+        runtime: &Runtime,
+        fut: impl Future<Output = Result<T>> + Send + 'static,
+    ) -> Result<T> {
+        futures::executor::block_on(runtime.spawn(fut))?
+    }
+
+    fn block_on<T: Send + 'static>(
+        &self,
+        fut: impl Future<Output = Result<T>> + Send + 'static,
+    ) -> Result<T> {
+        Self::block_on_runtime(self.runtime.as_ref().expect("Not dropped."), fut)
+    }
+}
+
+impl Drop for ArchiveDb {
+    fn drop(&mut self) {
+        // Dropping a runtime blocks, which is not allowed in an async context.
+        if let Some(runtime) = self.runtime.take() {
+            runtime.shutdown_background();
+        }
+    }
+}
+
+impl DbReader for ArchiveDb {
+    fn get_epoch_ending_ledger_infos(
+        &self,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<EpochChangeProof> {
+        let archive = Arc::clone(&self.archive);
+        self.block_on(async move {
+            archive
+                .get_epoch_ending_ledger_infos(start_epoch, end_epoch)
+                .await
+        })
+    }
+
+    fn get_transaction_by_version(
+        &self,
+        version: Version,
+        ledger_version: Version,
+        fetch_events: bool,
+    ) -> Result<TransactionWithProof> {
+        ensure!(
+            version <= ledger_version && ledger_version <= self.archive.latest_version(),
+            "Version {} with ledger version {} not served, latest version in archive: {}",
+            version,
+            ledger_version,
+            self.archive.latest_version(),
+        );
+        let archive = Arc::clone(&self.archive);
+        self.block_on(async move {
+            archive
+                .get_transaction_by_version(version, fetch_events)
+                .await
+        })
+    }
+
+    fn get_first_txn_version(&self) -> Result<Option<Version>> {
+        Ok(Some(0))
+    }
+
+    fn get_first_viable_txn_version(&self) -> Result<Version> {
+        Ok(0)
+    }
+
+    fn get_block_timestamp(&self, _version: Version) -> Result<u64> {
+        bail!("Block info is not served from a backup archive.")
+    }
+
+    fn get_next_block_event(&self, _version: Version) -> Result<(Version, NewBlockEvent)> {
+        bail!("Block info is not served from a backup archive.")
+    }
+
+    fn get_block_info_by_version(
+        &self,
+        _version: Version,
+    ) -> Result<(Version, Version, NewBlockEvent)> {
+        bail!("Block info is not served from a backup archive.")
+    }
+
+    fn get_latest_ledger_info_option(&self) -> Result<Option<LedgerInfoWithSignatures>> {
+        Ok(Some(self.archive.latest_ledger_info().clone()))
+    }
+
+    fn get_latest_version(&self) -> Result<Version> {
+        Ok(self.archive.latest_version())
+    }
+
+    fn get_state_value_by_version(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+    ) -> Result<Option<StateValue>> {
+        let archive = Arc::clone(&self.archive);
+        let state_key = state_key.clone();
+        self.block_on(async move {
+            archive
+                .get_state_value_by_version(&state_key, version)
+                .await
+        })
+    }
+}
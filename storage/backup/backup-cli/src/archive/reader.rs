@@ -0,0 +1,559 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    archive::{file_cache::FileCacheStorage, ArchiveOpt},
+    backup_types::{
+        epoch_ending::{
+            manifest::EpochEndingBackup,
+            restore::{EpochHistory, EpochHistoryRestoreController},
+        },
+        state_snapshot::manifest::StateSnapshotBackup,
+        transaction::{manifest::TransactionBackup, restore::LoadedChunk},
+    },
+    metadata::{cache, view::MetadataView, EpochEndingBackupMeta, TransactionBackupMeta},
+    storage::{BackupStorage, FileHandle, FileHandleRef},
+    utils::{
+        read_record_bytes::ReadRecordBytes, storage_ext::BackupStorageExt, GlobalRestoreOptions,
+        RestoreRunMode,
+    },
+};
+use anyhow::{anyhow, ensure, Result};
+use aptos_crypto::{
+    hash::{CryptoHash, TransactionAccumulatorHasher},
+    HashValue,
+};
+use aptos_db::state_restore::{StateSnapshotRestore, StateSnapshotRestoreMode};
+use aptos_infallible::Mutex;
+use aptos_logger::prelude::*;
+use aptos_storage_interface::StateSnapshotReceiver;
+use aptos_types::{
+    epoch_change::EpochChangeProof,
+    ledger_info::LedgerInfoWithSignatures,
+    proof::{MerkleTreeInternalNode, TransactionAccumulatorProof, TransactionInfoWithProof},
+    state_store::{state_key::StateKey, state_value::StateValue},
+    transaction::{TransactionWithProof, Version},
+    write_set::TransactionWrite,
+};
+use lru::LruCache;
+use std::{
+    cmp::{max, min},
+    collections::HashMap,
+    sync::Arc,
+};
+
+/// Same as the limit on the number of LedgerInfos AptosDB returns in an `EpochChangeProof`.
+const MAX_NUM_EPOCH_ENDING_LEDGER_INFO: usize = 100;
+
+/// Answers queries on historical transactions and state from a backup storage, fetching only the
+/// chunks needed and verifying each of them against the ledger infos in the epoch history before
+/// it's used.
+pub struct Archive {
+    storage: Arc<dyn BackupStorage>,
+    metadata_view: MetadataView,
+    epoch_history: Arc<EpochHistory>,
+    epoch_ending_backups: Vec<EpochEndingBackupMeta>,
+    transaction_backups: Vec<TransactionBackupMeta>,
+    latest_ledger_info: LedgerInfoWithSignatures,
+    transaction_manifests: Mutex<HashMap<FileHandle, Arc<TransactionBackup>>>,
+    // Keyed by the first version in the chunk.
+    transaction_chunks: Mutex<LruCache<Version, Arc<LoadedChunk>>>,
+    max_cached_chunks: usize,
+    // Keyed by the snapshot version.
+    snapshot_verifiers: Mutex<HashMap<Version, Arc<tokio::sync::Mutex<Option<SnapshotVerifier>>>>>,
+}
+
+impl Archive {
+    pub async fn open(opt: ArchiveOpt, storage: Arc<dyn BackupStorage>) -> Result<Self> {
+        let concurrent_downloads = opt.concurrent_downloads.get();
+        let metadata_view = cache::sync_and_load(
+            &opt.metadata_cache_opt,
+            Arc::clone(&storage),
+            concurrent_downloads,
+        )
+        .await?;
+        let storage: Arc<dyn BackupStorage> =
+            Arc::new(FileCacheStorage::new(storage, &opt.cache_dir)?);
+
+        let transaction_backups = metadata_view.select_transaction_backups(0, Version::MAX)?;
+        let max_txn_version = transaction_backups
+            .last()
+            .map(|backup| backup.last_version)
+            .ok_or_else(|| anyhow!("No transaction backups found."))?;
+        let epoch_ending_backups = metadata_view.select_epoch_ending_backups(max_txn_version)?;
+        let global_opt = GlobalRestoreOptions {
+            target_version: max_txn_version,
+            trusted_waypoints: Arc::new(opt.trusted_waypoints_opt.verify()?),
+            run_mode: Arc::new(RestoreRunMode::Verify),
+            concurrent_downloads,
+            replay_concurrency_level: 0, // won't replay, doesn't matter
+        };
+        let epoch_history = Arc::new(
+            EpochHistoryRestoreController::new(
+                epoch_ending_backups
+                    .iter()
+                    .map(|backup| backup.manifest.clone())
+                    .collect(),
+                global_opt,
+                Arc::clone(&storage),
+            )
+            .run()
+            .await?,
+        );
+
+        // Only what's proven by an epoch ending LedgerInfo is served, the transactions after it
+        // are in an epoch that's not known to have ended.
+        let latest_epoch = epoch_history
+            .epoch_endings
+            .iter()
+            .rev()
+            .find(|li| li.version() <= max_txn_version)
+            .ok_or_else(|| anyhow!("No epoch ending covered by the transaction backups."))?
+            .epoch();
+
+        let latest_ledger_info = Self::load_epoch_ending_ledger_infos(
+            &storage,
+            &epoch_ending_backups,
+            &epoch_history,
+            latest_epoch,
+            latest_epoch + 1,
+        )
+        .await?
+        .pop()
+        .expect("One LedgerInfo loaded.");
+        info!(
+            latest_epoch = latest_epoch,
+            latest_version = latest_ledger_info.ledger_info().version(),
+            "Backup archive opened."
+        );
+
+        Ok(Self {
+            storage,
+            metadata_view,
+            epoch_history,
+            epoch_ending_backups,
+            transaction_backups,
+            latest_ledger_info,
+            transaction_manifests: Mutex::new(HashMap::new()),
+            transaction_chunks: Mutex::new(LruCache::new(opt.max_cached_chunks)),
+            max_cached_chunks: opt.max_cached_chunks,
+            snapshot_verifiers: Mutex::new(HashMap::new()),
+        })
+    }
+
+    pub fn latest_ledger_info(&self) -> &LedgerInfoWithSignatures {
+        &self.latest_ledger_info
+    }
+
+    pub fn latest_version(&self) -> Version {
+        self.latest_ledger_info.ledger_info().version()
+    }
+
+    /// Returns the epoch ending LedgerInfos in [`start_epoch`, `end_epoch`), at most
+    /// `MAX_NUM_EPOCH_ENDING_LEDGER_INFO` of them.
+    pub async fn get_epoch_ending_ledger_infos(
+        &self,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<EpochChangeProof> {
+        let latest_epoch = self.latest_ledger_info.ledger_info().epoch();
+        ensure!(
+            start_epoch <= end_epoch && end_epoch <= latest_epoch + 1,
+            "Bad epoch range [{}, {}), latest epoch in archive: {}",
+            start_epoch,
+            end_epoch,
+            latest_epoch,
+        );
+        let limited_end_epoch = min(
+            end_epoch,
+            start_epoch.saturating_add(MAX_NUM_EPOCH_ENDING_LEDGER_INFO as u64),
+        );
+        Ok(EpochChangeProof::new(
+            Self::load_epoch_ending_ledger_infos(
+                &self.storage,
+                &self.epoch_ending_backups,
+                &self.epoch_history,
+                start_epoch,
+                limited_end_epoch,
+            )
+            .await?,
+            limited_end_epoch < end_epoch,
+        ))
+    }
+
+    /// Returns the transaction at `version` with a proof against the LedgerInfo that the backup
+    /// carries for it, which is the one ending the epoch the transaction is in.
+    pub async fn get_transaction_by_version(
+        &self,
+        version: Version,
+        fetch_events: bool,
+    ) -> Result<TransactionWithProof> {
+        let chunk = self.load_transaction_chunk(version).await?;
+        let idx = (version - chunk.manifest.first_version) as usize;
+        let proof = TransactionInfoWithProof::new(
+            Self::transaction_accumulator_proof(&chunk, version)?,
+            chunk.txn_infos[idx].clone(),
+        );
+        proof.verify(chunk.ledger_info.ledger_info(), version)?;
+
+        Ok(TransactionWithProof::new(
+            version,
+            chunk.txns[idx].clone(),
+            fetch_events.then(|| chunk.event_vecs[idx].clone()),
+            proof,
+        ))
+    }
+
+    /// Looks for the latest write to the key in the transactions between the latest state snapshot
+    /// at or before `version` and `version`, and in the snapshot if there is none.
+    pub async fn get_state_value_by_version(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+    ) -> Result<Option<StateValue>> {
+        ensure!(
+            version <= self.latest_version(),
+            "Version {} is beyond the latest version in archive {}.",
+            version,
+            self.latest_version(),
+        );
+        let snapshot = self
+            .metadata_view
+            .select_state_snapshot(version)?
+            .ok_or_else(|| anyhow!("No state snapshot at or before version {}.", version))?;
+
+        let mut last_version = version;
+        while last_version > snapshot.version {
+            let chunk = self.load_transaction_chunk(last_version).await?;
+            let first_version = max(chunk.manifest.first_version, snapshot.version + 1);
+            for v in (first_version..=last_version).rev() {
+                let write_set = &chunk.write_sets[(v - chunk.manifest.first_version) as usize];
+                if let Some(write_op) = write_set.get(state_key) {
+                    return Ok(write_op.as_state_value());
+                }
+            }
+            last_version = first_version - 1;
+        }
+
+        let verifier = self
+            .snapshot_verifiers
+            .lock()
+            .entry(snapshot.version)
+            .or_default()
+            .clone();
+        let mut verifier = verifier.lock().await;
+        if verifier.is_none() {
+            *verifier = Some(
+                SnapshotVerifier::new(
+                    &self.storage,
+                    &snapshot.manifest,
+                    &self.epoch_history,
+                    self.max_cached_chunks,
+                )
+                .await?,
+            );
+        }
+        verifier
+            .as_mut()
+            .expect("Set above.")
+            .get(&self.storage, state_key)
+            .await
+    }
+
+    async fn load_epoch_ending_ledger_infos(
+        storage: &Arc<dyn BackupStorage>,
+        epoch_ending_backups: &[EpochEndingBackupMeta],
+        epoch_history: &EpochHistory,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<Vec<LedgerInfoWithSignatures>> {
+        let mut lis = Vec::new();
+        for backup in epoch_ending_backups
+            .iter()
+            .filter(|b| b.last_epoch >= start_epoch && b.first_epoch < end_epoch)
+        {
+            let manifest: EpochEndingBackup = storage.load_json_file(&backup.manifest).await?;
+            for chunk in manifest
+                .chunks
+                .iter()
+                .filter(|c| c.last_epoch >= start_epoch && c.first_epoch < end_epoch)
+            {
+                let mut file = storage.open_for_read(&chunk.ledger_infos).await?;
+                let mut epoch = chunk.first_epoch;
+                while let Some(record_bytes) = file.read_record_bytes().await? {
+                    if epoch >= start_epoch && epoch < end_epoch {
+                        lis.push(bcs::from_bytes::<LedgerInfoWithSignatures>(&record_bytes)?);
+                    }
+                    epoch += 1;
+                }
+            }
+        }
+
+        ensure!(
+            lis.len() as u64 == end_epoch - start_epoch,
+            "Epoch ending LedgerInfos missing in [{}, {}), found {}.",
+            start_epoch,
+            end_epoch,
+            lis.len(),
+        );
+        // Signatures are not part of the epoch history, the rest of each LedgerInfo is verified.
+        for (epoch, li) in (start_epoch..end_epoch).zip(lis.iter()) {
+            ensure!(
+                li.ledger_info() == &epoch_history.epoch_endings[epoch as usize],
+                "LedgerInfo of epoch {} doesn't match the epoch history.",
+                epoch,
+            );
+        }
+        Ok(lis)
+    }
+
+    async fn load_transaction_manifest(
+        &self,
+        file_handle: &FileHandleRef,
+    ) -> Result<Arc<TransactionBackup>> {
+        if let Some(manifest) = self.transaction_manifests.lock().get(file_handle) {
+            return Ok(Arc::clone(manifest));
+        }
+        let manifest: Arc<TransactionBackup> =
+            Arc::new(self.storage.load_json_file(file_handle).await?);
+        self.transaction_manifests
+            .lock()
+            .insert(file_handle.to_string(), Arc::clone(&manifest));
+        Ok(manifest)
+    }
+
+    async fn load_transaction_chunk(&self, version: Version) -> Result<Arc<LoadedChunk>> {
+        ensure!(
+            version <= self.latest_version(),
+            "Version {} is beyond the latest version in archive {}.",
+            version,
+            self.latest_version(),
+        );
+        let backup = &self.transaction_backups[self
+            .transaction_backups
+            .partition_point(|b| b.last_version < version)];
+        let manifest = self.load_transaction_manifest(&backup.manifest).await?;
+        let chunk = manifest
+            .chunks
+            .get(
+                manifest
+                    .chunks
+                    .partition_point(|c| c.last_version < version),
+            )
+            .filter(|c| c.first_version <= version)
+            .ok_or_else(|| anyhow!("Version {} not found in {}.", version, backup.manifest))?
+            .clone();
+
+        if let Some(loaded) = self.transaction_chunks.lock().get(&chunk.first_version) {
+            return Ok(Arc::clone(loaded));
+        }
+        let loaded = LoadedChunk::load(chunk, &self.storage, Some(&self.epoch_history)).await?;
+        // The epoch history only warns about LedgerInfos too new for it to verify.
+        let epoch = loaded.ledger_info.ledger_info().epoch();
+        ensure!(
+            epoch <= self.epoch_history.epoch_endings.len() as u64,
+            "LedgerInfo of epoch {} can't be verified with the epoch history.",
+            epoch,
+        );
+        // Loading verifies the transactions and events, but not the write sets.
+        for (i, (txn_info, write_set)) in loaded
+            .txn_infos
+            .iter()
+            .zip(loaded.write_sets.iter())
+            .enumerate()
+        {
+            ensure!(
+                txn_info.state_change_hash() == CryptoHash::hash(write_set),
+                "Write set hash mismatch at version {}.",
+                loaded.manifest.first_version + i as Version,
+            );
+        }
+
+        let loaded = Arc::new(loaded);
+        self.transaction_chunks
+            .lock()
+            .put(loaded.manifest.first_version, Arc::clone(&loaded));
+        Ok(loaded)
+    }
+
+    /// Derives the proof of a single transaction from the range proof of its chunk, by going up
+    /// the accumulator the way the range proof is verified and taking the sibling of the
+    /// transaction's ancestor on each level.
+    fn transaction_accumulator_proof(
+        chunk: &LoadedChunk,
+        version: Version,
+    ) -> Result<TransactionAccumulatorProof> {
+        let mut left_siblings = chunk.range_proof.left_siblings().iter();
+        let mut right_siblings = chunk.range_proof.right_siblings().iter();
+        let mut hashes: Vec<HashValue> = chunk.txn_infos.iter().map(CryptoHash::hash).collect();
+        let mut first_index = chunk.manifest.first_version;
+        let mut index = version;
+        let mut siblings = Vec::new();
+
+        while hashes.len() > 1
+            || !left_siblings.as_slice().is_empty()
+            || !right_siblings.as_slice().is_empty()
+        {
+            // Pads the level to whole pairs with the siblings in the range proof.
+            if first_index % 2 == 1 {
+                let left = left_siblings
+                    .next()
+                    .ok_or_else(|| anyhow!("Missing sibling on the left."))?;
+                hashes.insert(0, *left);
+                first_index -= 1;
+            }
+            if hashes.len() % 2 == 1 {
+                let right = right_siblings
+                    .next()
+                    .ok_or_else(|| anyhow!("Missing sibling on the right."))?;
+                hashes.push(*right);
+            }
+
+            siblings.push(hashes[((index ^ 1) - first_index) as usize]);
+            hashes = hashes
+                .chunks_exact(2)
+                .map(|pair| {
+                    MerkleTreeInternalNode::<TransactionAccumulatorHasher>::new(pair[0], pair[1])
+                        .hash()
+                })
+                .collect();
+            first_index /= 2;
+            index /= 2;
+        }
+
+        Ok(TransactionAccumulatorProof::new(siblings))
+    }
+}
+
+/// Verifies the chunks of a state snapshot on demand, each of them once, and caches the content
+/// of the chunks verified. The range proof of a chunk only has the siblings on the right of it,
+/// the ones on the left are derived from the chunks before it, so the first lookup of a key in a
+/// chunk not verified yet verifies the chunks before it too.
+struct SnapshotVerifier {
+    manifest: StateSnapshotBackup,
+    receiver: StateSnapshotRestore<StateKey, StateValue>,
+    // Hashes of the chunks verified, to check later reads of the chunks against.
+    verified_chunk_hashes: Vec<HashValue>,
+    // Keyed by the index of the chunk.
+    verified_chunks: LruCache<usize, Arc<HashMap<StateKey, StateValue>>>,
+}
+
+impl SnapshotVerifier {
+    async fn new(
+        storage: &Arc<dyn BackupStorage>,
+        manifest_handle: &FileHandleRef,
+        epoch_history: &EpochHistory,
+        max_cached_chunks: usize,
+    ) -> Result<Self> {
+        let manifest: StateSnapshotBackup = storage.load_json_file(manifest_handle).await?;
+        let (txn_info_with_proof, li): (TransactionInfoWithProof, LedgerInfoWithSignatures) =
+            storage.load_bcs_file(&manifest.proof).await?;
+        txn_info_with_proof.verify(li.ledger_info(), manifest.version)?;
+        let state_root_hash = txn_info_with_proof
+            .transaction_info()
+            .ensure_state_checkpoint_hash()?;
+        ensure!(
+            state_root_hash == manifest.root_hash,
+            "Root hash mismatch with that in proof. root hash: {}, expected: {}",
+            manifest.root_hash,
+            state_root_hash,
+        );
+        ensure!(
+            li.ledger_info().epoch() <= epoch_history.epoch_endings.len() as u64,
+            "LedgerInfo of epoch {} can't be verified with the epoch history.",
+            li.ledger_info().epoch(),
+        );
+        epoch_history.verify_ledger_info(&li)?;
+
+        let receiver = RestoreRunMode::Verify.get_state_restore_receiver(
+            manifest.version,
+            manifest.root_hash,
+            StateSnapshotRestoreMode::TreeOnly,
+        )?;
+        Ok(Self {
+            manifest,
+            receiver,
+            verified_chunk_hashes: Vec::new(),
+            verified_chunks: LruCache::new(max_cached_chunks),
+        })
+    }
+
+    async fn read_chunk(
+        storage: &Arc<dyn BackupStorage>,
+        file_handle: &FileHandleRef,
+    ) -> Result<(HashValue, Vec<(StateKey, StateValue)>)> {
+        let bytes = storage.read_all(file_handle).await?;
+        let mut records = bytes.as_slice();
+        let mut blobs = Vec::new();
+        while let Some(record_bytes) = records.read_record_bytes().await? {
+            blobs.push(bcs::from_bytes(&record_bytes)?);
+        }
+        Ok((HashValue::sha3_256_of(&bytes), blobs))
+    }
+
+    async fn get(
+        &mut self,
+        storage: &Arc<dyn BackupStorage>,
+        state_key: &StateKey,
+    ) -> Result<Option<StateValue>> {
+        let key_hash = state_key.hash();
+        let num_chunks = self.manifest.chunks.len();
+        let chunk_idx = self
+            .manifest
+            .chunks
+            .partition_point(|chunk| chunk.last_key < key_hash);
+        if chunk_idx < num_chunks {
+            let chunk = self.verified_chunk(storage, chunk_idx).await?;
+            return Ok(chunk.get(state_key).cloned());
+        }
+
+        // The key is after the last chunk, verifying the last chunk proves it's not there.
+        if let Some(last_chunk_idx) = num_chunks.checked_sub(1) {
+            self.verified_chunk(storage, last_chunk_idx).await?;
+        }
+        Ok(None)
+    }
+
+    async fn verified_chunk(
+        &mut self,
+        storage: &Arc<dyn BackupStorage>,
+        chunk_idx: usize,
+    ) -> Result<Arc<HashMap<StateKey, StateValue>>> {
+        if let Some(chunk) = self.verified_chunks.get(&chunk_idx) {
+            return Ok(Arc::clone(chunk));
+        }
+
+        while self.verified_chunk_hashes.len() <= chunk_idx {
+            let idx = self.verified_chunk_hashes.len();
+            let chunk = &self.manifest.chunks[idx];
+            let (hash, blobs) = Self::read_chunk(storage, &chunk.blobs).await?;
+            let proof = storage.load_bcs_file(&chunk.proof).await?;
+            let target_blobs = (idx == chunk_idx).then(|| blobs.clone());
+            self.receiver.add_chunk(blobs, proof)?;
+            self.verified_chunk_hashes.push(hash);
+            if let Some(blobs) = target_blobs {
+                return Ok(self.cache_chunk(chunk_idx, blobs));
+            }
+        }
+
+        // Verified before, but no longer cached.
+        let chunk = &self.manifest.chunks[chunk_idx];
+        let (hash, blobs) = Self::read_chunk(storage, &chunk.blobs).await?;
+        ensure!(
+            hash == self.verified_chunk_hashes[chunk_idx],
+            "{} changed since verified.",
+            chunk.blobs,
+        );
+        Ok(self.cache_chunk(chunk_idx, blobs))
+    }
+
+    fn cache_chunk(
+        &mut self,
+        chunk_idx: usize,
+        blobs: Vec<(StateKey, StateValue)>,
+    ) -> Arc<HashMap<StateKey, StateValue>> {
+        let chunk = Arc::new(blobs.into_iter().collect::<HashMap<_, _>>());
+        self.verified_chunks.put(chunk_idx, Arc::clone(&chunk));
+        chunk
+    }
+}
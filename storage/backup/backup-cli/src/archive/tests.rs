@@ -0,0 +1,164 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    archive::{ArchiveDb, ArchiveOpt},
+    backup_types::{
+        epoch_ending::backup::{EpochEndingBackupController, EpochEndingBackupOpt},
+        state_snapshot::backup::{StateSnapshotBackupController, StateSnapshotBackupOpt},
+        transaction::backup::{TransactionBackupController, TransactionBackupOpt},
+    },
+    metadata::cache::MetadataCacheOpt,
+    storage::{local_fs::LocalFs, BackupStorage},
+    utils::{
+        backup_service_client::BackupServiceClient, test_utils::start_local_backup_service,
+        ConcurrentDownloadsOpt, GlobalBackupOpt, TrustedWaypointOpt,
+    },
+};
+use aptos_executor_test_helpers::integration_test_impl::test_execution_with_storage_impl;
+use aptos_storage_interface::DbReader;
+use aptos_temppath::TempPath;
+use aptos_types::state_store::state_key::StateKey;
+use std::sync::Arc;
+use tokio::time::Duration;
+
+#[test]
+fn end_to_end() {
+    let src_db = test_execution_with_storage_impl();
+    let backup_dir = TempPath::new();
+    backup_dir.create_as_dir().unwrap();
+    let metadata_cache_dir = TempPath::new();
+    let archive_cache_dir = TempPath::new();
+    let store: Arc<dyn BackupStorage> = Arc::new(LocalFs::new(backup_dir.path().to_path_buf()));
+
+    let latest_epoch = src_db.get_latest_epoch_state().unwrap().epoch;
+    let latest_version = src_db.get_latest_version().unwrap();
+    let epoch_ending_lis = src_db
+        .get_epoch_ending_ledger_infos(0, latest_epoch)
+        .unwrap()
+        .ledger_info_with_sigs;
+    assert!(epoch_ending_lis.len() > 1);
+    let snapshot_version = epoch_ending_lis[0].ledger_info().version();
+    let archive_version = epoch_ending_lis.last().unwrap().ledger_info().version();
+
+    let (rt, port) = start_local_backup_service(Arc::clone(&src_db));
+    let client = Arc::new(BackupServiceClient::new(format!(
+        "http://localhost:{}",
+        port
+    )));
+    let global_backup_opt = GlobalBackupOpt {
+        max_chunk_size: 500,
+    };
+    rt.block_on(
+        EpochEndingBackupController::new(
+            EpochEndingBackupOpt {
+                start_epoch: 0,
+                end_epoch: latest_epoch,
+            },
+            global_backup_opt.clone(),
+            Arc::clone(&client),
+            Arc::clone(&store),
+        )
+        .run(),
+    )
+    .unwrap();
+    rt.block_on(
+        StateSnapshotBackupController::new(
+            StateSnapshotBackupOpt {
+                epoch: epoch_ending_lis[0].ledger_info().epoch(),
+            },
+            global_backup_opt.clone(),
+            Arc::clone(&client),
+            Arc::clone(&store),
+        )
+        .run(),
+    )
+    .unwrap();
+    rt.block_on(
+        TransactionBackupController::new(
+            TransactionBackupOpt {
+                start_version: 0,
+                num_transactions: latest_version as usize + 1,
+            },
+            global_backup_opt,
+            client,
+            Arc::clone(&store),
+        )
+        .run(),
+    )
+    .unwrap();
+    rt.shutdown_timeout(Duration::from_secs(1));
+
+    let archive_db = ArchiveDb::open(
+        ArchiveOpt {
+            metadata_cache_opt: MetadataCacheOpt::new(Some(metadata_cache_dir.path())),
+            trusted_waypoints_opt: TrustedWaypointOpt::default(),
+            concurrent_downloads: ConcurrentDownloadsOpt::default(),
+            cache_dir: archive_cache_dir.path().to_path_buf(),
+            max_cached_chunks: 2,
+        },
+        store,
+    )
+    .unwrap();
+    assert_eq!(archive_db.get_latest_version().unwrap(), archive_version);
+    assert_eq!(
+        archive_db.get_latest_ledger_info().unwrap(),
+        *epoch_ending_lis.last().unwrap()
+    );
+    assert_eq!(
+        archive_db
+            .get_epoch_ending_ledger_infos(0, latest_epoch)
+            .unwrap()
+            .ledger_info_with_sigs,
+        epoch_ending_lis
+    );
+
+    // Transactions, with proofs against the LedgerInfo ending their epochs.
+    for version in 0..=archive_version {
+        let txn = archive_db
+            .get_transaction_by_version(version, archive_version, true)
+            .unwrap();
+        let expected = src_db
+            .get_transaction_by_version(version, archive_version, true)
+            .unwrap();
+        assert_eq!(txn.transaction, expected.transaction);
+        assert_eq!(txn.events, expected.events);
+        let epoch_ending_li = epoch_ending_lis
+            .iter()
+            .find(|li| li.ledger_info().version() >= version)
+            .unwrap();
+        txn.proof
+            .verify(epoch_ending_li.ledger_info(), version)
+            .unwrap();
+    }
+    assert!(archive_db
+        .get_transaction_by_version(archive_version + 1, archive_version + 1, false)
+        .is_err());
+
+    // State, both from the snapshot and from the transactions after it.
+    let mut state_keys: Vec<StateKey> = src_db
+        .get_state_value_chunk_with_proof(archive_version, 0, 10000)
+        .unwrap()
+        .raw_values
+        .into_iter()
+        .map(|(key, _value)| key)
+        .collect();
+    state_keys.push(StateKey::raw(b"not_there".to_vec()));
+    for version in [
+        snapshot_version,
+        snapshot_version + 1,
+        (snapshot_version + archive_version) / 2,
+        archive_version,
+    ] {
+        for state_key in &state_keys {
+            assert_eq!(
+                archive_db
+                    .get_state_value_by_version(state_key, version)
+                    .unwrap(),
+                src_db
+                    .get_state_value_by_version(state_key, version)
+                    .unwrap(),
+            );
+        }
+    }
+}
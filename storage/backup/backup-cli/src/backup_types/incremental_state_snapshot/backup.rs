@@ -0,0 +1,408 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    backup_types::{
+        incremental_state_snapshot::manifest::{
+            IncrementalStateSnapshotBackup, IncrementalStateSnapshotChunk,
+        },
+        state_snapshot::manifest::StateSnapshotBackup,
+    },
+    metadata::Metadata,
+    storage::{BackupHandleRef, BackupStorage, FileHandle, ShellSafeName},
+    utils::{
+        backup_service_client::BackupServiceClient, read_record_bytes::ReadRecordBytes,
+        should_cut_chunk, storage_ext::BackupStorageExt, GlobalBackupOpt,
+    },
+};
+use anyhow::{anyhow, ensure, Result};
+use aptos_crypto::{hash::CryptoHash, HashValue};
+use aptos_logger::prelude::*;
+use aptos_types::{
+    contract_event::ContractEvent,
+    ledger_info::LedgerInfoWithSignatures,
+    proof::{SparseMerkleRangeProof, TransactionInfoWithProof},
+    state_store::{state_key::StateKey, state_value::StateValue},
+    transaction::{Transaction, TransactionInfo, Version},
+    write_set::{TransactionWrite, WriteSet},
+};
+use clap::Parser;
+use futures::{stream, StreamExt, TryStreamExt};
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    convert::TryInto,
+    str::FromStr,
+    sync::Arc,
+};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+#[derive(Parser)]
+pub struct IncrementalStateSnapshotBackupOpt {
+    #[clap(
+        long = "incremental-state-snapshot-epoch",
+        help = "Epoch at the end of which an incremental state snapshot is to be taken."
+    )]
+    pub epoch: u64,
+    #[clap(
+        long = "base-state-manifest",
+        help = "Manifest of the full or incremental state snapshot to take this one on top of. \
+        The transactions since it must not have been pruned on the node."
+    )]
+    pub base_manifest_handle: FileHandle,
+}
+
+/// Either kind of state snapshot an incremental one can be taken on top of.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum BaseStateSnapshot {
+    Incremental(IncrementalStateSnapshotBackup),
+    Full(StateSnapshotBackup),
+}
+
+/// Takes an incremental state snapshot, with the state changes derived from the write sets of
+/// the transactions since the base snapshot.
+///
+/// The changes are accumulated in memory before being written out, so the base snapshot is
+/// expected to be recent enough for them to fit.
+pub struct IncrementalStateSnapshotBackupController {
+    epoch: u64,
+    base_manifest_handle: FileHandle,
+    max_chunk_size: usize,
+    client: Arc<BackupServiceClient>,
+    storage: Arc<dyn BackupStorage>,
+}
+
+impl IncrementalStateSnapshotBackupController {
+    const CONCURRENT_PROOF_REQUESTS: usize = 16;
+
+    pub fn new(
+        opt: IncrementalStateSnapshotBackupOpt,
+        global_opt: GlobalBackupOpt,
+        client: Arc<BackupServiceClient>,
+        storage: Arc<dyn BackupStorage>,
+    ) -> Self {
+        Self {
+            epoch: opt.epoch,
+            base_manifest_handle: opt.base_manifest_handle,
+            max_chunk_size: global_opt.max_chunk_size,
+            client,
+            storage,
+        }
+    }
+
+    pub async fn run(self) -> Result<FileHandle> {
+        info!(
+            "Incremental state snapshot backup started, for epoch {}, on top of {}.",
+            self.epoch, self.base_manifest_handle,
+        );
+        let ret = self
+            .run_impl()
+            .await
+            .map_err(|e| anyhow!("Incremental state snapshot backup failed: {}", e))?;
+        info!(
+            "Incremental state snapshot backup succeeded. Manifest: {}",
+            ret
+        );
+        Ok(ret)
+    }
+
+    async fn run_impl(self) -> Result<FileHandle> {
+        let version = self.get_version_for_epoch_ending(self.epoch).await?;
+        let (base_version, base_boundaries) = self.load_base().await?;
+        ensure!(
+            base_version < version,
+            "Base state snapshot at version {} is not older than version {}.",
+            base_version,
+            version,
+        );
+        let changes = self.read_changes(base_version, version).await?;
+        info!(
+            base_version = base_version,
+            version = version,
+            num_changes = changes.len(),
+            "State changes collected."
+        );
+
+        let backup_handle = self
+            .storage
+            .create_backup_with_random_suffix(&format!(
+                "incremental_state_epoch_{}_ver_{}",
+                self.epoch, version
+            ))
+            .await?;
+
+        // Base boundaries are known to exist at `base_version`, so they still exist at `version`
+        // unless deleted since.
+        let mut boundaries = base_boundaries
+            .into_iter()
+            .filter(|key| changes.get(key).map_or(true, |(_, value)| value.is_some()))
+            .collect::<BTreeSet<_>>();
+
+        let mut chunks = vec![];
+        let mut chunk_bytes = vec![];
+        let mut chunk_first_idx = 0;
+        let mut chunk_keys: Option<(HashValue, HashValue)> = None;
+        let mut chunk_last_existing_key = None;
+        for (idx, (key_hash, (key, value))) in changes.iter().enumerate() {
+            let record_bytes = bcs::to_bytes(&(key, value))?;
+            if should_cut_chunk(&chunk_bytes, &record_bytes, self.max_chunk_size) {
+                let (first_key, last_key) = chunk_keys.expect("Chunk is not empty.");
+                chunks.push(
+                    self.write_chunk(
+                        &backup_handle,
+                        &chunk_bytes,
+                        chunk_first_idx,
+                        idx - 1,
+                        first_key,
+                        last_key,
+                    )
+                    .await?,
+                );
+                boundaries.extend(chunk_last_existing_key.take());
+                chunk_bytes = vec![];
+                chunk_first_idx = idx;
+                chunk_keys = None;
+            }
+            chunk_bytes.extend((record_bytes.len() as u32).to_be_bytes());
+            chunk_bytes.extend(&record_bytes);
+            chunk_keys = Some((chunk_keys.map_or(*key_hash, |(first, _)| first), *key_hash));
+            if value.is_some() {
+                chunk_last_existing_key = Some(*key_hash);
+            }
+        }
+        if let Some((first_key, last_key)) = chunk_keys {
+            chunks.push(
+                self.write_chunk(
+                    &backup_handle,
+                    &chunk_bytes,
+                    chunk_first_idx,
+                    changes.len() - 1,
+                    first_key,
+                    last_key,
+                )
+                .await?,
+            );
+            boundaries.extend(chunk_last_existing_key.take());
+        }
+
+        let range_proofs = self
+            .write_range_proofs(&backup_handle, version, boundaries)
+            .await?;
+        self.write_manifest(&backup_handle, base_version, version, chunks, range_proofs)
+            .await
+    }
+}
+
+impl IncrementalStateSnapshotBackupController {
+    fn manifest_name() -> &'static ShellSafeName {
+        static NAME: Lazy<ShellSafeName> =
+            Lazy::new(|| ShellSafeName::from_str("incremental_state.manifest").unwrap());
+        &NAME
+    }
+
+    fn proof_name() -> &'static ShellSafeName {
+        static NAME: Lazy<ShellSafeName> =
+            Lazy::new(|| ShellSafeName::from_str("state.proof").unwrap());
+        &NAME
+    }
+
+    fn range_proofs_name() -> &'static ShellSafeName {
+        static NAME: Lazy<ShellSafeName> =
+            Lazy::new(|| ShellSafeName::from_str("range.proofs").unwrap());
+        &NAME
+    }
+
+    fn chunk_name(first_idx: usize) -> ShellSafeName {
+        format!("{}-.chunk", first_idx).try_into().unwrap()
+    }
+
+    async fn get_version_for_epoch_ending(&self, epoch: u64) -> Result<u64> {
+        let ledger_info: LedgerInfoWithSignatures = bcs::from_bytes(
+            self.client
+                .get_epoch_ending_ledger_infos(epoch, epoch + 1)
+                .await?
+                .read_record_bytes()
+                .await?
+                .ok_or_else(|| {
+                    anyhow!("Failed to get epoch ending ledger info for epoch {}", epoch)
+                })?
+                .as_ref(),
+        )?;
+        Ok(ledger_info.ledger_info().version())
+    }
+
+    /// Returns the version of the base snapshot and the keys a restore into it cuts chunks at.
+    async fn load_base(&self) -> Result<(Version, Vec<HashValue>)> {
+        Ok(
+            match self
+                .storage
+                .load_json_file(&self.base_manifest_handle)
+                .await?
+            {
+                BaseStateSnapshot::Full(manifest) => (
+                    manifest.version,
+                    manifest.chunks.iter().map(|chunk| chunk.last_key).collect(),
+                ),
+                BaseStateSnapshot::Incremental(manifest) => {
+                    let range_proofs: Vec<(HashValue, SparseMerkleRangeProof)> =
+                        self.storage.load_bcs_file(&manifest.range_proofs).await?;
+                    (
+                        manifest.version,
+                        range_proofs.into_iter().map(|(key, _)| key).collect(),
+                    )
+                },
+            },
+        )
+    }
+
+    /// Folds the write sets in (`base_version`, `version`] into the latest value of each key
+    /// written, `None` for deleted ones.
+    async fn read_changes(
+        &self,
+        base_version: Version,
+        version: Version,
+    ) -> Result<BTreeMap<HashValue, (StateKey, Option<StateValue>)>> {
+        let num_transactions = (version - base_version) as usize;
+        let mut transactions = self
+            .client
+            .get_transactions(base_version + 1, num_transactions)
+            .await?;
+
+        let mut changes = BTreeMap::new();
+        let mut num_read = 0;
+        while let Some(record_bytes) = transactions.read_record_bytes().await? {
+            let (_txn, _txn_info, _events, write_set): (
+                Transaction,
+                TransactionInfo,
+                Vec<ContractEvent>,
+                WriteSet,
+            ) = bcs::from_bytes(&record_bytes)?;
+            for (key, write_op) in write_set {
+                changes.insert(key.hash(), (key, write_op.as_state_value()));
+            }
+            num_read += 1;
+        }
+        ensure!(
+            num_read == num_transactions,
+            "Expecting {} transactions since version {}, got {}. Pruned?",
+            num_transactions,
+            base_version + 1,
+            num_read,
+        );
+        Ok(changes)
+    }
+
+    async fn write_chunk(
+        &self,
+        backup_handle: &BackupHandleRef,
+        chunk_bytes: &[u8],
+        first_idx: usize,
+        last_idx: usize,
+        first_key: HashValue,
+        last_key: HashValue,
+    ) -> Result<IncrementalStateSnapshotChunk> {
+        let (chunk_handle, mut chunk_file) = self
+            .storage
+            .create_for_write(backup_handle, &Self::chunk_name(first_idx))
+            .await?;
+        chunk_file.write_all(chunk_bytes).await?;
+        chunk_file.shutdown().await?;
+        info!(last_idx = last_idx, "Chunk written.");
+
+        Ok(IncrementalStateSnapshotChunk {
+            first_idx,
+            last_idx,
+            first_key,
+            last_key,
+            blobs: chunk_handle,
+        })
+    }
+
+    async fn write_range_proofs(
+        &self,
+        backup_handle: &BackupHandleRef,
+        version: Version,
+        boundaries: BTreeSet<HashValue>,
+    ) -> Result<FileHandle> {
+        let client = self.client.clone();
+        let range_proofs: Vec<(HashValue, SparseMerkleRangeProof)> = stream::iter(boundaries)
+            .map(|key| {
+                let client = client.clone();
+                async move {
+                    let mut proof_bytes = vec![];
+                    client
+                        .get_account_range_proof(key, version)
+                        .await?
+                        .read_to_end(&mut proof_bytes)
+                        .await?;
+                    Result::<_>::Ok((key, bcs::from_bytes(&proof_bytes)?))
+                }
+            })
+            .buffered(Self::CONCURRENT_PROOF_REQUESTS)
+            .try_collect()
+            .await?;
+
+        let (range_proofs_handle, mut range_proofs_file) = self
+            .storage
+            .create_for_write(backup_handle, Self::range_proofs_name())
+            .await?;
+        range_proofs_file
+            .write_all(&bcs::to_bytes(&range_proofs)?)
+            .await?;
+        range_proofs_file.shutdown().await?;
+        Ok(range_proofs_handle)
+    }
+
+    async fn write_manifest(
+        &self,
+        backup_handle: &BackupHandleRef,
+        base_version: Version,
+        version: Version,
+        chunks: Vec<IncrementalStateSnapshotChunk>,
+        range_proofs: FileHandle,
+    ) -> Result<FileHandle> {
+        let proof_bytes = self.client.get_state_root_proof(version).await?;
+        let (txn_info, _): (TransactionInfoWithProof, LedgerInfoWithSignatures) =
+            bcs::from_bytes(&proof_bytes)?;
+
+        let (proof_handle, mut proof_file) = self
+            .storage
+            .create_for_write(backup_handle, Self::proof_name())
+            .await?;
+        proof_file.write_all(&proof_bytes).await?;
+        proof_file.shutdown().await?;
+
+        let manifest = IncrementalStateSnapshotBackup {
+            base_version,
+            version,
+            epoch: self.epoch,
+            root_hash: txn_info.transaction_info().ensure_state_checkpoint_hash()?,
+            chunks,
+            range_proofs,
+            proof: proof_handle,
+        };
+
+        let (manifest_handle, mut manifest_file) = self
+            .storage
+            .create_for_write(backup_handle, Self::manifest_name())
+            .await?;
+        manifest_file
+            .write_all(&serde_json::to_vec(&manifest)?)
+            .await?;
+        manifest_file.shutdown().await?;
+
+        let metadata = Metadata::new_incremental_state_snapshot_backup(
+            base_version,
+            self.epoch,
+            version,
+            manifest_handle.clone(),
+        );
+        self.storage
+            .save_metadata_line(&metadata.name(), &metadata.to_text_line()?)
+            .await?;
+
+        Ok(manifest_handle)
+    }
+}
@@ -0,0 +1,54 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::storage::FileHandle;
+use aptos_crypto::HashValue;
+use aptos_types::transaction::Version;
+use serde::{Deserialize, Serialize};
+
+/// A chunk of an incremental state snapshot manifest, representing the state keys changed in the
+/// key range [`first_key`, `last_key`] (right side inclusive).
+#[derive(Deserialize, Serialize)]
+pub struct IncrementalStateSnapshotChunk {
+    /// index of the first change in this chunk over all changes.
+    pub first_idx: usize,
+    /// index of the last change in this chunk over all changes.
+    pub last_idx: usize,
+    /// key of the first change in this chunk.
+    pub first_key: HashValue,
+    /// key of the last change in this chunk.
+    pub last_key: HashValue,
+    /// Repeated `len(record) + record` where `record` is BCS serialized tuple
+    /// `(key, Option<state_value>)`, `None` meaning the key is deleted.
+    pub blobs: FileHandle,
+}
+
+/// Incremental state snapshot backup manifest, representing the changes to the state between the
+/// (full or incremental) state snapshot at `base_version` and `version`.
+///
+/// It can't be restored on its own: a restore composes a full state snapshot with a chain of
+/// incremental ones, each on top of the previous one, and verifies the composed state against the
+/// root hash of the last one.
+#[derive(Deserialize, Serialize)]
+pub struct IncrementalStateSnapshotBackup {
+    /// Version of the state snapshot this one is taken on top of.
+    pub base_version: Version,
+    /// Version at which this state snapshot is taken.
+    pub version: Version,
+    /// Epoch in which this state snapshot is taken.
+    pub epoch: u64,
+    /// Hash of the state tree root.
+    pub root_hash: HashValue,
+    /// All state keys created, modified or deleted in (`base_version`, `version`], in chunks.
+    pub chunks: Vec<IncrementalStateSnapshotChunk>,
+    /// BCS serialized `Vec<(HashValue, SparseMerkleRangeProof)>`, sorted by key.
+    /// A restore into `version` cuts the composed state into chunks right after each of these
+    /// keys, and the `SparseMerkleRangeProof` at `version` proves such a chunk adds up to
+    /// `root_hash`. The keys are the chunk boundaries of the base snapshot that still exist at
+    /// `version`, plus the last existing key in each chunk of changes above.
+    pub range_proofs: FileHandle,
+    /// BCS serialized
+    /// `Tuple(TransactionInfoWithProof, LedgerInfoWithSignatures)`, like
+    /// `StateSnapshotBackup::proof`.
+    pub proof: FileHandle,
+}
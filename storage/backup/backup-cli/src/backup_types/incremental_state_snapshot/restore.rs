@@ -0,0 +1,338 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    backup_types::{
+        epoch_ending::restore::EpochHistory,
+        incremental_state_snapshot::manifest::IncrementalStateSnapshotBackup,
+        state_snapshot::manifest::StateSnapshotBackup,
+    },
+    metrics::{
+        restore::{STATE_SNAPSHOT_LEAF_INDEX, STATE_SNAPSHOT_VERSION},
+        verify::{VERIFY_STATE_SNAPSHOT_LEAF_INDEX, VERIFY_STATE_SNAPSHOT_VERSION},
+        OTHER_TIMERS_SECONDS,
+    },
+    storage::{BackupStorage, FileHandle},
+    utils::{
+        read_record_bytes::ReadRecordBytes, storage_ext::BackupStorageExt, stream::StreamX,
+        GlobalRestoreOptions, RestoreRunMode,
+    },
+};
+use anyhow::{anyhow, ensure, Result};
+use aptos_crypto::{hash::CryptoHash, HashValue};
+use aptos_db::state_restore::StateSnapshotRestoreMode;
+use aptos_infallible::Mutex;
+use aptos_logger::prelude::*;
+use aptos_storage_interface::StateSnapshotReceiver;
+use aptos_types::{
+    ledger_info::LedgerInfoWithSignatures,
+    proof::{SparseMerkleRangeProof, TransactionInfoWithProof},
+    state_store::{state_key::StateKey, state_value::StateValue},
+    transaction::Version,
+};
+use clap::Parser;
+use futures::{
+    stream::{self, BoxStream},
+    StreamExt, TryStreamExt,
+};
+use std::{iter::Peekable, sync::Arc, vec::IntoIter};
+use tokio::time::Instant;
+
+#[derive(Parser)]
+pub struct IncrementalStateSnapshotRestoreOpt {
+    #[clap(
+        long = "state-manifest",
+        help = "Manifest of the full state snapshot the incremental ones are on top of."
+    )]
+    pub base_manifest_handle: FileHandle,
+    #[clap(
+        long = "incremental-state-manifest",
+        required = true,
+        help = "Manifests of the incremental state snapshots, in version order, each on top of \
+        the previous one. State is restored into the version of the last one."
+    )]
+    pub manifest_handles: Vec<FileHandle>,
+    #[clap(long)]
+    pub restore_mode: StateSnapshotRestoreMode,
+}
+
+pub struct IncrementalStateSnapshotRestoreController {
+    storage: Arc<dyn BackupStorage>,
+    run_mode: Arc<RestoreRunMode>,
+    base_manifest_handle: FileHandle,
+    manifest_handles: Vec<FileHandle>,
+    /// Global "target_version" for the entire restore process, if the version of the last
+    /// incremental snapshot is newer than this, nothing will be done.
+    target_version: Version,
+    epoch_history: Option<Arc<EpochHistory>>,
+    concurrent_downloads: usize,
+    restore_mode: StateSnapshotRestoreMode,
+}
+
+type Record = (HashValue, StateKey, Option<StateValue>);
+
+/// The records of one snapshot in the chain, in key order, downloaded ahead a few chunks at a
+/// time.
+struct Layer {
+    chunks: BoxStream<'static, Result<Vec<Record>>>,
+    records: Peekable<IntoIter<Record>>,
+}
+
+impl Layer {
+    fn new(
+        storage: Arc<dyn BackupStorage>,
+        chunks: Vec<FileHandle>,
+        is_full_snapshot: bool,
+        concurrent_downloads: usize,
+    ) -> Self {
+        let chunks = stream::iter(chunks)
+            .map(move |chunk| {
+                let storage = storage.clone();
+                async move {
+                    tokio::spawn(async move {
+                        let mut file = storage.open_for_read(&chunk).await?;
+                        let mut records = vec![];
+                        while let Some(record_bytes) = file.read_record_bytes().await? {
+                            let (key, value): (StateKey, Option<StateValue>) = if is_full_snapshot {
+                                let (key, value): (StateKey, StateValue) =
+                                    bcs::from_bytes(&record_bytes)?;
+                                (key, Some(value))
+                            } else {
+                                bcs::from_bytes(&record_bytes)?
+                            };
+                            records.push((key.hash(), key, value));
+                        }
+                        Result::<_>::Ok(records)
+                    })
+                    .await?
+                }
+            })
+            .buffered_x(concurrent_downloads * 2, concurrent_downloads)
+            .boxed();
+
+        Self {
+            chunks,
+            records: vec![].into_iter().peekable(),
+        }
+    }
+
+    async fn peek_key(&mut self) -> Result<Option<HashValue>> {
+        while self.records.peek().is_none() {
+            match self.chunks.try_next().await? {
+                Some(records) => self.records = records.into_iter().peekable(),
+                None => return Ok(None),
+            }
+        }
+        Ok(self.records.peek().map(|(key_hash, _, _)| *key_hash))
+    }
+
+    fn next(&mut self) -> Record {
+        self.records.next().expect("Must have been peeked.")
+    }
+}
+
+impl IncrementalStateSnapshotRestoreController {
+    pub fn new(
+        opt: IncrementalStateSnapshotRestoreOpt,
+        global_opt: GlobalRestoreOptions,
+        storage: Arc<dyn BackupStorage>,
+        epoch_history: Option<Arc<EpochHistory>>,
+    ) -> Self {
+        Self {
+            storage,
+            run_mode: global_opt.run_mode,
+            base_manifest_handle: opt.base_manifest_handle,
+            manifest_handles: opt.manifest_handles,
+            target_version: global_opt.target_version,
+            epoch_history,
+            concurrent_downloads: global_opt.concurrent_downloads,
+            restore_mode: opt.restore_mode,
+        }
+    }
+
+    pub async fn run(self) -> Result<()> {
+        let name = self.name();
+        let start = Instant::now();
+        info!(
+            "{} started. Base manifest: {}, incremental manifests: {:?}",
+            name, self.base_manifest_handle, self.manifest_handles,
+        );
+        self.run_impl()
+            .await
+            .map_err(|e| anyhow!("{} failed: {}", name, e))?;
+        info!(time = start.elapsed().as_secs(), "{} succeeded.", name);
+        Ok(())
+    }
+}
+
+impl IncrementalStateSnapshotRestoreController {
+    fn name(&self) -> String {
+        format!("incremental state snapshot {}", self.run_mode.name())
+    }
+
+    async fn run_impl(self) -> Result<()> {
+        let base: StateSnapshotBackup = self
+            .storage
+            .load_json_file(&self.base_manifest_handle)
+            .await?;
+        let mut manifests: Vec<IncrementalStateSnapshotBackup> = vec![];
+        for handle in &self.manifest_handles {
+            manifests.push(self.storage.load_json_file(handle).await?);
+        }
+        let mut prev_version = base.version;
+        for manifest in &manifests {
+            ensure!(
+                manifest.base_version == prev_version,
+                "Incremental state snapshot at version {} is on top of version {}, expecting {}.",
+                manifest.version,
+                manifest.base_version,
+                prev_version,
+            );
+            prev_version = manifest.version;
+        }
+        let last = manifests
+            .last()
+            .ok_or_else(|| anyhow!("No incremental state snapshot to restore."))?;
+        let version = last.version;
+        if version > self.target_version {
+            warn!(
+                "Trying to restore state snapshot to version {}, which is newer than the target version {}, skipping.",
+                version,
+                self.target_version,
+            );
+            return Ok(());
+        }
+
+        // Only the last snapshot in the chain is verified against the ledger: the composed state
+        // is verified against its root hash chunk by chunk.
+        let (txn_info_with_proof, li): (TransactionInfoWithProof, LedgerInfoWithSignatures) =
+            self.storage.load_bcs_file(&last.proof).await?;
+        txn_info_with_proof.verify(li.ledger_info(), version)?;
+        let state_root_hash = txn_info_with_proof
+            .transaction_info()
+            .ensure_state_checkpoint_hash()?;
+        ensure!(
+            state_root_hash == last.root_hash,
+            "Root hash mismatch with that in proof. root hash: {}, expected: {}",
+            last.root_hash,
+            state_root_hash,
+        );
+        if let Some(epoch_history) = self.epoch_history.as_ref() {
+            epoch_history.verify_ledger_info(&li)?;
+        }
+        let range_proofs: Vec<(HashValue, SparseMerkleRangeProof)> =
+            self.storage.load_bcs_file(&last.range_proofs).await?;
+
+        let receiver = Arc::new(Mutex::new(Some(self.run_mode.get_state_restore_receiver(
+            version,
+            state_root_hash,
+            self.restore_mode,
+        )?)));
+
+        let (ver_gauge, leaf_idx) = if self.run_mode.is_verify() {
+            (
+                &VERIFY_STATE_SNAPSHOT_VERSION,
+                &VERIFY_STATE_SNAPSHOT_LEAF_INDEX,
+            )
+        } else {
+            (&STATE_SNAPSHOT_VERSION, &STATE_SNAPSHOT_LEAF_INDEX)
+        };
+        ver_gauge.set(version as i64);
+
+        // Later layers override earlier ones.
+        let mut layers = vec![Layer::new(
+            self.storage.clone(),
+            base.chunks.into_iter().map(|chunk| chunk.blobs).collect(),
+            true, /* is_full_snapshot */
+            self.concurrent_downloads,
+        )];
+        layers.extend(manifests.into_iter().map(|manifest| {
+            Layer::new(
+                self.storage.clone(),
+                manifest
+                    .chunks
+                    .into_iter()
+                    .map(|chunk| chunk.blobs)
+                    .collect(),
+                false, /* is_full_snapshot */
+                self.concurrent_downloads,
+            )
+        }));
+
+        let mut boundaries = range_proofs.into_iter().peekable();
+        let mut chunk = vec![];
+        let mut num_leaves = 0;
+        let start = Instant::now();
+        loop {
+            let mut next_key = None;
+            for layer in layers.iter_mut() {
+                if let Some(key) = layer.peek_key().await? {
+                    next_key = Some(next_key.map_or(key, |k: HashValue| k.min(key)));
+                }
+            }
+            let key_hash = match next_key {
+                Some(key_hash) => key_hash,
+                None => break,
+            };
+            if let Some((boundary, _)) = boundaries.peek() {
+                ensure!(
+                    *boundary >= key_hash,
+                    "Chunk boundary {:x} doesn't exist in the composed state.",
+                    boundary,
+                );
+            }
+
+            let mut latest = None;
+            for layer in layers.iter_mut() {
+                if layer.peek_key().await? == Some(key_hash) {
+                    latest = Some(layer.next());
+                }
+            }
+            let (_, key, value) = latest.expect("At least one layer has the key.");
+            if let Some(value) = value {
+                chunk.push((key, value));
+                if boundaries.peek().map(|(boundary, _)| *boundary) == Some(key_hash) {
+                    let (_, proof) = boundaries.next().expect("Peeked.");
+                    num_leaves += chunk.len();
+                    Self::add_chunk(&receiver, std::mem::take(&mut chunk), proof).await?;
+                    leaf_idx.set(num_leaves as i64 - 1);
+                    info!(
+                        num_leaves = num_leaves,
+                        values_per_second =
+                            (num_leaves as f64 / start.elapsed().as_secs_f64()) as u64,
+                        "State chunk added.",
+                    );
+                }
+            }
+        }
+        ensure!(
+            boundaries.peek().is_none(),
+            "Chunk boundaries beyond the composed state.",
+        );
+        // Nothing exists to the right of the last chunk, so no right siblings to prove it with.
+        if !chunk.is_empty() {
+            num_leaves += chunk.len();
+            Self::add_chunk(&receiver, chunk, SparseMerkleRangeProof::new(vec![])).await?;
+            leaf_idx.set(num_leaves as i64 - 1);
+        }
+
+        tokio::task::spawn_blocking(move || receiver.lock().take().unwrap().finish()).await??;
+        self.run_mode.finish();
+        Ok(())
+    }
+
+    async fn add_chunk<R: StateSnapshotReceiver<StateKey, StateValue> + 'static>(
+        receiver: &Arc<Mutex<Option<R>>>,
+        chunk: Vec<(StateKey, StateValue)>,
+        proof: SparseMerkleRangeProof,
+    ) -> Result<()> {
+        let _timer = OTHER_TIMERS_SECONDS
+            .with_label_values(&["add_state_chunk"])
+            .start_timer();
+        let receiver = receiver.clone();
+        tokio::task::spawn_blocking(move || {
+            receiver.lock().as_mut().unwrap().add_chunk(chunk, proof)
+        })
+        .await?
+    }
+}
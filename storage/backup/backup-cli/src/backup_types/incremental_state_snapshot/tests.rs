@@ -0,0 +1,161 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    backup_types::{
+        incremental_state_snapshot::{
+            backup::{IncrementalStateSnapshotBackupController, IncrementalStateSnapshotBackupOpt},
+            restore::{
+                IncrementalStateSnapshotRestoreController, IncrementalStateSnapshotRestoreOpt,
+            },
+        },
+        state_snapshot::backup::{StateSnapshotBackupController, StateSnapshotBackupOpt},
+    },
+    metadata::{cache, cache::MetadataCacheOpt},
+    storage::{local_fs::LocalFs, BackupStorage},
+    utils::{
+        backup_service_client::BackupServiceClient, test_utils::start_local_backup_service,
+        ConcurrentDownloadsOpt, GlobalBackupOpt, GlobalRestoreOpt, ReplayConcurrencyLevelOpt,
+        RocksdbOpt, TrustedWaypointOpt,
+    },
+};
+use aptos_db::{state_restore::StateSnapshotRestoreMode, AptosDB};
+use aptos_executor_test_helpers::integration_test_impl::test_execution_with_storage_impl;
+use aptos_storage_interface::DbReader;
+use aptos_temppath::TempPath;
+use aptos_types::transaction::Version;
+use std::{convert::TryInto, sync::Arc};
+use tokio::time::Duration;
+
+#[test]
+fn end_to_end() {
+    let src_db = test_execution_with_storage_impl();
+    let tgt_db_dir = TempPath::new();
+    tgt_db_dir.create_as_dir().unwrap();
+    let backup_dir = TempPath::new();
+    backup_dir.create_as_dir().unwrap();
+    let metadata_cache_dir = TempPath::new();
+    let store: Arc<dyn BackupStorage> = Arc::new(LocalFs::new(backup_dir.path().to_path_buf()));
+
+    let latest_epoch = src_db.get_latest_epoch_state().unwrap().epoch;
+    let epoch_ending_lis = src_db
+        .get_epoch_ending_ledger_infos(0, latest_epoch)
+        .unwrap()
+        .ledger_info_with_sigs;
+    assert!(epoch_ending_lis.len() > 1);
+    let last_li = epoch_ending_lis.last().unwrap().ledger_info();
+    let version = last_li.version();
+    let state_root_hash = src_db
+        .get_transactions(version, 1, version, false)
+        .unwrap()
+        .proof
+        .transaction_infos
+        .pop()
+        .unwrap()
+        .state_checkpoint_hash()
+        .unwrap();
+
+    let (rt, port) = start_local_backup_service(src_db);
+    let client = Arc::new(BackupServiceClient::new(format!(
+        "http://localhost:{}",
+        port
+    )));
+    let global_backup_opt = GlobalBackupOpt {
+        max_chunk_size: 500,
+    };
+
+    // A full snapshot at the first epoch ending, and a chain of incremental ones at each of the
+    // following.
+    let base_manifest_handle = rt
+        .block_on(
+            StateSnapshotBackupController::new(
+                StateSnapshotBackupOpt {
+                    epoch: epoch_ending_lis[0].ledger_info().epoch(),
+                },
+                global_backup_opt.clone(),
+                Arc::clone(&client),
+                Arc::clone(&store),
+            )
+            .run(),
+        )
+        .unwrap();
+    let mut manifest_handles = vec![];
+    for li in &epoch_ending_lis[1..] {
+        let manifest_handle = rt
+            .block_on(
+                IncrementalStateSnapshotBackupController::new(
+                    IncrementalStateSnapshotBackupOpt {
+                        epoch: li.ledger_info().epoch(),
+                        base_manifest_handle: manifest_handles
+                            .last()
+                            .unwrap_or(&base_manifest_handle)
+                            .clone(),
+                    },
+                    global_backup_opt.clone(),
+                    Arc::clone(&client),
+                    Arc::clone(&store),
+                )
+                .run(),
+            )
+            .unwrap();
+        manifest_handles.push(manifest_handle);
+    }
+
+    let metadata_view = rt
+        .block_on(cache::sync_and_load(
+            &MetadataCacheOpt::new(Some(metadata_cache_dir.path())),
+            Arc::clone(&store),
+            1, /* concurrent_downloads */
+        ))
+        .unwrap();
+    let chain = metadata_view
+        .select_state_snapshot_chain(Version::MAX)
+        .unwrap()
+        .unwrap();
+    assert_eq!(chain.version(), version);
+    assert_eq!(chain.base.manifest, base_manifest_handle);
+    assert_eq!(
+        chain
+            .incrementals
+            .iter()
+            .map(|m| m.manifest.clone())
+            .collect::<Vec<_>>(),
+        manifest_handles
+    );
+
+    rt.block_on(
+        IncrementalStateSnapshotRestoreController::new(
+            IncrementalStateSnapshotRestoreOpt {
+                base_manifest_handle,
+                manifest_handles,
+                restore_mode: StateSnapshotRestoreMode::Default,
+            },
+            GlobalRestoreOpt {
+                dry_run: false,
+                db_dir: Some(tgt_db_dir.path().to_path_buf()),
+                target_version: None, // max
+                trusted_waypoints: TrustedWaypointOpt::default(),
+                rocksdb_opt: RocksdbOpt::default(),
+                concurrent_downloads: ConcurrentDownloadsOpt::default(),
+                replay_concurrency_level: ReplayConcurrencyLevelOpt::default(),
+            }
+            .try_into()
+            .unwrap(),
+            store,
+            None, /* epoch_history */
+        )
+        .run(),
+    )
+    .unwrap();
+
+    let tgt_db = AptosDB::new_readonly_for_test(&tgt_db_dir);
+    assert_eq!(
+        tgt_db
+            .get_state_snapshot_before(version + 1)
+            .unwrap()
+            .unwrap(),
+        (version, state_root_hash)
+    );
+
+    rt.shutdown_timeout(Duration::from_secs(1));
+}
@@ -4,6 +4,7 @@
 
 #![allow(clippy::arithmetic_side_effects)]
 
+pub mod archive;
 pub mod backup_types;
 pub mod coordinators;
 pub mod metadata;
@@ -4,8 +4,9 @@
 
 use crate::{
     metadata::{
-        CompactionTimestampsMeta, EpochEndingBackupMeta, IdentityMeta, Metadata,
-        StateSnapshotBackupMeta, TransactionBackupMeta,
+        CompactionTimestampsMeta, EpochEndingBackupMeta, IdentityMeta,
+        IncrementalStateSnapshotBackupMeta, Metadata, StateSnapshotBackupMeta,
+        TransactionBackupMeta,
     },
     metrics::backup::COMPACTED_TXN_VERSION,
     storage::FileHandle,
@@ -14,12 +15,13 @@ use anyhow::{anyhow, ensure, Result};
 use aptos_infallible::duration_since_epoch;
 use aptos_types::transaction::Version;
 use itertools::Itertools;
-use std::{fmt, str::FromStr};
+use std::{collections::BTreeMap, fmt, str::FromStr};
 
 #[derive(Debug)]
 pub struct MetadataView {
     epoch_ending_backups: Vec<EpochEndingBackupMeta>,
     state_snapshot_backups: Vec<StateSnapshotBackupMeta>,
+    incremental_state_snapshot_backups: Vec<IncrementalStateSnapshotBackupMeta>,
     transaction_backups: Vec<TransactionBackupMeta>,
     _identity: Option<IdentityMeta>,
     // The compaction timestamps of the file handles producing this view
@@ -30,6 +32,7 @@ impl MetadataView {
     pub(crate) fn new(metadata_vec: Vec<Metadata>, file_handles: Vec<FileHandle>) -> Self {
         let mut epoch_ending_backups = Vec::new();
         let mut state_snapshot_backups = Vec::new();
+        let mut incremental_state_snapshot_backups = Vec::new();
         let mut transaction_backups = Vec::new();
         let mut identity = None;
         let mut compaction_timestamps = Vec::new();
@@ -38,6 +41,9 @@ impl MetadataView {
             match meta {
                 Metadata::EpochEndingBackup(e) => epoch_ending_backups.push(e),
                 Metadata::StateSnapshotBackup(s) => state_snapshot_backups.push(s),
+                Metadata::IncrementalStateSnapshotBackup(s) => {
+                    incremental_state_snapshot_backups.push(s)
+                },
                 Metadata::TransactionBackup(t) => transaction_backups.push(t),
                 Metadata::Identity(i) => identity = Some(i),
                 Metadata::CompactionTimestamps(t) => compaction_timestamps.push(t),
@@ -47,6 +53,8 @@ impl MetadataView {
         epoch_ending_backups.dedup();
         state_snapshot_backups.sort_unstable();
         state_snapshot_backups.dedup();
+        incremental_state_snapshot_backups.sort_unstable();
+        incremental_state_snapshot_backups.dedup();
         transaction_backups.sort_unstable();
         transaction_backups.dedup();
 
@@ -72,6 +80,7 @@ impl MetadataView {
         Self {
             epoch_ending_backups,
             state_snapshot_backups,
+            incremental_state_snapshot_backups,
             transaction_backups,
             _identity: identity,
             compaction_timestamps: compaction_meta_opt,
@@ -126,6 +135,49 @@ impl MetadataView {
             .ok_or_else(|| anyhow!("State snapshot not found at version {}", version))
     }
 
+    /// Selects the latest state at or before `target_version` that can be restored, as a full
+    /// state snapshot and the chain of incremental snapshots on top of it, preferring the shortest
+    /// chain among those reaching the same version.
+    pub fn select_state_snapshot_chain(
+        &self,
+        target_version: Version,
+    ) -> Result<Option<StateSnapshotChain>> {
+        let mut chains: BTreeMap<Version, StateSnapshotChain> = self
+            .state_snapshot_backups
+            .iter()
+            .filter(|m| m.version <= target_version)
+            .map(|m| {
+                (
+                    m.version,
+                    StateSnapshotChain {
+                        base: m.clone(),
+                        incrementals: vec![],
+                    },
+                )
+            })
+            .collect();
+        // Sorted by base version, so all chains reaching a base version are known by the time
+        // the incremental snapshots on top of it are visited.
+        for incremental in self
+            .incremental_state_snapshot_backups
+            .iter()
+            .filter(|m| m.version <= target_version)
+        {
+            let mut chain = match chains.get(&incremental.base_version) {
+                Some(chain) => chain.clone(),
+                None => continue,
+            };
+            chain.incrementals.push(incremental.clone());
+            let shorter = chains
+                .get(&incremental.version)
+                .map_or(true, |c| c.incrementals.len() > chain.incrementals.len());
+            if shorter {
+                chains.insert(incremental.version, chain);
+            }
+        }
+        Ok(chains.into_iter().last().map(|(_, chain)| chain))
+    }
+
     pub fn select_transaction_backups(
         &self,
         start_version: Version,
@@ -235,6 +287,13 @@ impl MetadataView {
         Self::compact_backups(&self.state_snapshot_backups, compaction_cnt)
     }
 
+    pub fn compact_incremental_state_backups(
+        &mut self,
+        compaction_cnt: usize,
+    ) -> Result<Vec<&[IncrementalStateSnapshotBackupMeta]>> {
+        Self::compact_backups(&self.incremental_state_snapshot_backups, compaction_cnt)
+    }
+
     pub fn get_file_handles(&self) -> Vec<FileHandle> {
         self.select_latest_compaction_timestamps()
             .as_ref()
@@ -243,6 +302,21 @@ impl MetadataView {
     }
 }
 
+/// A full state snapshot followed by zero or more incremental ones, each on top of the previous.
+#[derive(Clone, Debug)]
+pub struct StateSnapshotChain {
+    pub base: StateSnapshotBackupMeta,
+    pub incrementals: Vec<IncrementalStateSnapshotBackupMeta>,
+}
+
+impl StateSnapshotChain {
+    pub fn version(&self) -> Version {
+        self.incrementals
+            .last()
+            .map_or(self.base.version, |m| m.version)
+    }
+}
+
 pub struct BackupStorageState {
     pub latest_epoch_ending_epoch: Option<u64>,
     pub latest_state_snapshot_epoch: Option<u64>,
@@ -0,0 +1,179 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{anyhow, ensure, Result};
+use aptos_crypto::HashValue;
+use aptos_crypto_derive::{BCSCryptoHash, CryptoHasher};
+use ring::{
+    aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN},
+    rand::{SecureRandom, SystemRandom},
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{fmt, path::Path};
+
+pub const KEY_LEN: usize = 32;
+pub const KEY_ID_LEN: usize = 8;
+/// Segment nonces are this prefix, the big endian segment index and a "last segment" flag.
+pub const NONCE_PREFIX_LEN: usize = NONCE_LEN - 5;
+
+pub type KeyId = [u8; KEY_ID_LEN];
+pub type NoncePrefix = [u8; NONCE_PREFIX_LEN];
+
+/// What a backup signature is over, the variants keeping a signature on one kind of file from
+/// being passed off as one on another.
+#[derive(BCSCryptoHash, CryptoHasher, Deserialize, Serialize)]
+pub enum BackupSignatureMessage {
+    /// SHA-256 of an envelope, from the magic to the end of the last segment.
+    File(HashValue),
+    /// SHA-256 of the lines in a metadata file following the signature line.
+    MetadataFile(HashValue),
+}
+
+pub fn random_bytes<const N: usize>() -> Result<[u8; N]> {
+    let mut bytes = [0u8; N];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| anyhow!("Failed to generate random bytes."))?;
+    Ok(bytes)
+}
+
+fn aes_256_gcm_key(bytes: &[u8; KEY_LEN]) -> LessSafeKey {
+    LessSafeKey::new(UnboundKey::new(&AES_256_GCM, bytes).expect("Key length is right."))
+}
+
+/// Key-encryption key, wrapping the per file data keys.
+pub struct KeyEncryptionKey {
+    id: KeyId,
+    key: [u8; KEY_LEN],
+}
+
+impl KeyEncryptionKey {
+    pub fn new(key: [u8; KEY_LEN]) -> Self {
+        let mut id = [0u8; KEY_ID_LEN];
+        id.copy_from_slice(&Sha256::digest(&key)[..KEY_ID_LEN]);
+        Self { id, key }
+    }
+
+    /// Loads a key from a file containing it in hex.
+    pub fn load(path: &Path) -> Result<Self> {
+        let encoded = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))?;
+        let encoded = encoded.trim();
+        let bytes = hex::decode(encoded.strip_prefix("0x").unwrap_or(encoded))?;
+        ensure!(
+            bytes.len() == KEY_LEN,
+            "Expecting a {} byte key in {}, got {} bytes.",
+            KEY_LEN,
+            path.display(),
+            bytes.len(),
+        );
+        let mut key = [0u8; KEY_LEN];
+        key.copy_from_slice(&bytes);
+        Ok(Self::new(key))
+    }
+
+    pub fn id(&self) -> KeyId {
+        self.id
+    }
+
+    /// Returns the random nonce followed by the encrypted data key and tag. The nonce prefix of
+    /// the segments is bound to the data key as the additional authenticated data.
+    pub fn wrap(&self, data_key: &[u8; KEY_LEN], nonce_prefix: &NoncePrefix) -> Result<Vec<u8>> {
+        let nonce: [u8; NONCE_LEN] = random_bytes()?;
+        let mut wrapped = data_key.to_vec();
+        aes_256_gcm_key(&self.key)
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(nonce_prefix),
+                &mut wrapped,
+            )
+            .map_err(|_| anyhow!("Failed to wrap data key."))?;
+        Ok([nonce.as_ref(), &wrapped].concat())
+    }
+
+    pub fn unwrap(&self, wrapped: &[u8], nonce_prefix: &NoncePrefix) -> Result<[u8; KEY_LEN]> {
+        ensure!(
+            wrapped.len() == NONCE_LEN + KEY_LEN + AES_256_GCM.tag_len(),
+            "Wrapped data key of wrong length: {}",
+            wrapped.len(),
+        );
+        let mut nonce = [0u8; NONCE_LEN];
+        nonce.copy_from_slice(&wrapped[..NONCE_LEN]);
+        let mut in_out = wrapped[NONCE_LEN..].to_vec();
+        let data_key = aes_256_gcm_key(&self.key)
+            .open_in_place(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(nonce_prefix),
+                &mut in_out,
+            )
+            .map_err(|_| {
+                anyhow!(
+                    "Failed to unwrap data key with key {}.",
+                    hex::encode(self.id)
+                )
+            })?;
+        let mut key = [0u8; KEY_LEN];
+        key.copy_from_slice(data_key);
+        Ok(key)
+    }
+}
+
+impl fmt::Debug for KeyEncryptionKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "KeyEncryptionKey({})", hex::encode(self.id))
+    }
+}
+
+/// Seals or opens the segments of one file with its data key, in order.
+pub struct SegmentCipher {
+    key: LessSafeKey,
+    nonce_prefix: NoncePrefix,
+    next_segment: u32,
+}
+
+impl SegmentCipher {
+    pub fn new(data_key: &[u8; KEY_LEN], nonce_prefix: NoncePrefix) -> Self {
+        Self {
+            key: aes_256_gcm_key(data_key),
+            nonce_prefix,
+            next_segment: 0,
+        }
+    }
+
+    pub fn tag_len() -> usize {
+        AES_256_GCM.tag_len()
+    }
+
+    /// The segment index and the flag in the nonce make reordered, dropped or appended segments
+    /// fail to open.
+    fn next_nonce(&mut self, is_last: bool) -> Result<Nonce> {
+        let mut nonce = [0u8; NONCE_LEN];
+        nonce[..NONCE_PREFIX_LEN].copy_from_slice(&self.nonce_prefix);
+        nonce[NONCE_PREFIX_LEN..NONCE_LEN - 1].copy_from_slice(&self.next_segment.to_be_bytes());
+        nonce[NONCE_LEN - 1] = is_last as u8;
+        self.next_segment = self
+            .next_segment
+            .checked_add(1)
+            .ok_or_else(|| anyhow!("Too many segments."))?;
+        Ok(Nonce::assume_unique_for_key(nonce))
+    }
+
+    pub fn seal(&mut self, payload: &mut Vec<u8>, is_last: bool) -> Result<()> {
+        let nonce = self.next_nonce(is_last)?;
+        self.key
+            .seal_in_place_append_tag(nonce, Aad::empty(), payload)
+            .map_err(|_| anyhow!("Failed to encrypt segment."))
+    }
+
+    pub fn open(&mut self, mut payload: Vec<u8>, is_last: bool) -> Result<Vec<u8>> {
+        let nonce = self.next_nonce(is_last)?;
+        let len = self
+            .key
+            .open_in_place(nonce, Aad::empty(), &mut payload)
+            .map_err(|_| anyhow!("Failed to decrypt segment {}.", self.next_segment - 1))?
+            .len();
+        payload.truncate(len);
+        Ok(payload)
+    }
+}
@@ -0,0 +1,238 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+pub mod keys;
+mod stream;
+
+#[cfg(test)]
+mod tests;
+
+use crate::storage::{
+    envelope::{
+        keys::{BackupSignatureMessage, KeyEncryptionKey},
+        stream::{open_envelope, EnvelopeWriter, MAGIC},
+    },
+    BackupHandle, BackupHandleRef, BackupStorage, FileHandle, FileHandleRef, ShellSafeName,
+    TextLine,
+};
+use anyhow::{anyhow, bail, Result};
+use aptos_crypto::{
+    ed25519::{Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature},
+    HashValue, Signature, SigningKey, ValidCryptoMaterialStringExt,
+};
+use aptos_infallible::Mutex;
+use async_trait::async_trait;
+use clap::Parser;
+use sha2::{Digest, Sha256};
+use std::{collections::HashSet, convert::TryFrom, io::Cursor, path::PathBuf, sync::Arc};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite};
+
+/// The first line of a signed metadata file, followed by the signature in hex.
+const SIGNATURE_LINE_PREFIX: &str = "#aptos-backup-signature:";
+
+#[derive(Parser, Clone, Debug, Default)]
+pub struct EnvelopeOpt {
+    #[clap(
+        long = "backup-encryption-key-file",
+        help = "File containing a hex encoded 256-bit AES key, with which the key encrypting each \
+        backup file is encrypted. Can be repeated to be able to read files encrypted with older \
+        keys, the first one is used to encrypt new files. Metadata files are not encrypted."
+    )]
+    pub encryption_key_files: Vec<PathBuf>,
+    #[clap(
+        long = "backup-signing-key-file",
+        help = "File containing a hex encoded Ed25519 private key, with which backup and metadata \
+        files are signed when written."
+    )]
+    pub signing_key_file: Option<PathBuf>,
+    #[clap(
+        long = "backup-verifying-key",
+        value_parser = Ed25519PublicKey::from_encoded_string,
+        help = "Hex encoded Ed25519 public key. If set, backup and metadata files that are not \
+        signed by the corresponding private key are rejected when read."
+    )]
+    pub verifying_key: Option<Ed25519PublicKey>,
+}
+
+impl EnvelopeOpt {
+    pub fn is_enabled(&self) -> bool {
+        !self.encryption_key_files.is_empty()
+            || self.signing_key_file.is_some()
+            || self.verifying_key.is_some()
+    }
+}
+
+/// Wraps another storage, sealing every backup file written in an envelope, in which it's
+/// optionally encrypted and signed, and opening the envelopes transparently when read.
+///
+/// Each file is encrypted with AES-256-GCM under its own data key, which is in turn encrypted by
+/// the key-encryption key and kept in the envelope header. Metadata files are kept in plain text
+/// for the storage to be able to list and reorganise them, but they are signed as well. Once a
+/// key-encryption key is given, only the metadata files listed or saved through this storage are
+/// read in plain text, so that a file can't be downgraded by replacing it with a plain one.
+pub struct EnvelopeStorage {
+    inner: Arc<dyn BackupStorage>,
+    keks: Vec<KeyEncryptionKey>,
+    signing_key: Option<Arc<Ed25519PrivateKey>>,
+    verifying_key: Option<Ed25519PublicKey>,
+    metadata_files: Mutex<HashSet<FileHandle>>,
+}
+
+impl EnvelopeStorage {
+    pub fn new(
+        inner: Arc<dyn BackupStorage>,
+        keks: Vec<KeyEncryptionKey>,
+        signing_key: Option<Ed25519PrivateKey>,
+        verifying_key: Option<Ed25519PublicKey>,
+    ) -> Self {
+        Self {
+            inner,
+            keks,
+            signing_key: signing_key.map(Arc::new),
+            verifying_key,
+            metadata_files: Mutex::new(HashSet::new()),
+        }
+    }
+
+    pub fn new_with_opt(opt: EnvelopeOpt, inner: Arc<dyn BackupStorage>) -> Result<Self> {
+        let keks = opt
+            .encryption_key_files
+            .iter()
+            .map(|path| KeyEncryptionKey::load(path))
+            .collect::<Result<_>>()?;
+        let signing_key = opt
+            .signing_key_file
+            .map(|path| {
+                let encoded = std::fs::read_to_string(&path)
+                    .map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))?;
+                Ed25519PrivateKey::from_encoded_string(encoded.trim())
+                    .map_err(|e| anyhow!("Invalid signing key in {}: {}", path.display(), e))
+            })
+            .transpose()?;
+        Ok(Self::new(inner, keks, signing_key, opt.verifying_key))
+    }
+
+    fn sign_metadata(&self, lines: &[TextLine]) -> Result<Option<TextLine>> {
+        let signing_key = match self.signing_key.as_ref() {
+            Some(signing_key) => signing_key,
+            None => return Ok(None),
+        };
+        let mut hasher = Sha256::new();
+        for line in lines {
+            hasher.update(line.as_ref());
+        }
+        let signature = signing_key
+            .sign(&BackupSignatureMessage::MetadataFile(
+                HashValue::from_slice(&hasher.finalize())?,
+            ))
+            .map_err(|e| anyhow!("Failed to sign: {}", e))?;
+        Ok(Some(TextLine::new(&format!(
+            "{}{}",
+            SIGNATURE_LINE_PREFIX,
+            hex::encode(signature.to_bytes())
+        ))?))
+    }
+
+    /// Checks the signature line of a metadata file and returns the rest of it.
+    fn open_signed_metadata(&self, file_handle: &FileHandleRef, content: &[u8]) -> Result<Vec<u8>> {
+        let line_end = content
+            .iter()
+            .position(|b| *b == b'\n')
+            .ok_or_else(|| anyhow!("Unterminated signature line in {}.", file_handle))?;
+        let (signature_line, lines) = (&content[..line_end], &content[line_end + 1..]);
+        if let Some(verifying_key) = self.verifying_key.as_ref() {
+            let signature = hex::decode(&signature_line[SIGNATURE_LINE_PREFIX.len()..])?;
+            Ed25519Signature::try_from(signature.as_slice())?
+                .verify(
+                    &BackupSignatureMessage::MetadataFile(HashValue::from_slice(&Sha256::digest(
+                        lines,
+                    ))?),
+                    verifying_key,
+                )
+                .map_err(|e| anyhow!("Bad signature on {}: {}", file_handle, e))?;
+        }
+        Ok(lines.to_vec())
+    }
+}
+
+#[async_trait]
+impl BackupStorage for EnvelopeStorage {
+    async fn create_backup(&self, name: &ShellSafeName) -> Result<BackupHandle> {
+        self.inner.create_backup(name).await
+    }
+
+    async fn create_for_write(
+        &self,
+        backup_handle: &BackupHandleRef,
+        name: &ShellSafeName,
+    ) -> Result<(FileHandle, Box<dyn AsyncWrite + Send + Unpin>)> {
+        let (file_handle, file) = self.inner.create_for_write(backup_handle, name).await?;
+        let writer = EnvelopeWriter::new(file, self.keks.first(), self.signing_key.clone())?;
+        Ok((file_handle, Box::new(writer)))
+    }
+
+    async fn open_for_read(
+        &self,
+        file_handle: &FileHandleRef,
+    ) -> Result<Box<dyn AsyncRead + Send + Unpin>> {
+        // Tells envelopes, signed metadata files and plain files apart by how they start.
+        let mut file = self.inner.open_for_read(file_handle).await?;
+        let mut prefix = Vec::new();
+        (&mut file)
+            .take(std::cmp::max(MAGIC.len(), SIGNATURE_LINE_PREFIX.len()) as u64)
+            .read_to_end(&mut prefix)
+            .await?;
+
+        if prefix.starts_with(MAGIC) {
+            let rest = Cursor::new(prefix[MAGIC.len()..].to_vec()).chain(file);
+            open_envelope(
+                Box::new(rest),
+                file_handle.to_string(),
+                &self.keks,
+                self.verifying_key.clone(),
+            )
+            .await
+        } else if !self.keks.is_empty() && !self.metadata_files.lock().contains(file_handle) {
+            bail!("{} is not encrypted.", file_handle)
+        } else if prefix.starts_with(SIGNATURE_LINE_PREFIX.as_bytes()) {
+            // Metadata files are small.
+            file.read_to_end(&mut prefix).await?;
+            let lines = self.open_signed_metadata(file_handle, &prefix)?;
+            Ok(Box::new(Cursor::new(lines)))
+        } else if self.verifying_key.is_some() {
+            bail!("{} is not signed.", file_handle)
+        } else {
+            Ok(Box::new(Cursor::new(prefix).chain(file)))
+        }
+    }
+
+    async fn list_metadata_files(&self) -> Result<Vec<FileHandle>> {
+        let file_handles = self.inner.list_metadata_files().await?;
+        self.metadata_files
+            .lock()
+            .extend(file_handles.iter().cloned());
+        Ok(file_handles)
+    }
+
+    async fn backup_metadata_file(&self, file_handle: &FileHandleRef) -> Result<()> {
+        self.inner.backup_metadata_file(file_handle).await
+    }
+
+    async fn save_metadata_lines(
+        &self,
+        name: &ShellSafeName,
+        lines: &[TextLine],
+    ) -> Result<FileHandle> {
+        let file_handle = match self.sign_metadata(lines)? {
+            Some(signature_line) => {
+                let signed: Vec<TextLine> = std::iter::once(signature_line)
+                    .chain(lines.iter().cloned())
+                    .collect();
+                self.inner.save_metadata_lines(name, &signed).await?
+            },
+            None => self.inner.save_metadata_lines(name, lines).await?,
+        };
+        self.metadata_files.lock().insert(file_handle.clone());
+        Ok(file_handle)
+    }
+}
@@ -0,0 +1,339 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! An envelope is laid out as:
+//!
+//!   MAGIC | u32 header length | BCS `EnvelopeHeader` | segment ... | signature, if signed
+//!
+//! where each segment is `u32 payload length | u8 is_last | payload`, the payload being the
+//! plaintext or, if encrypted, the ciphertext followed by the tag. All integers are big endian.
+
+use crate::storage::envelope::keys::{
+    random_bytes, BackupSignatureMessage, KeyEncryptionKey, KeyId, NoncePrefix, SegmentCipher,
+};
+use anyhow::{anyhow, bail, ensure, Result};
+use aptos_crypto::{
+    ed25519::{Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature, ED25519_SIGNATURE_LENGTH},
+    HashValue, Signature, SigningKey,
+};
+use bytes::Bytes;
+use futures::{stream, TryStreamExt};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    convert::TryFrom,
+    io,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite};
+use tokio_util::compat::FuturesAsyncReadCompatExt;
+
+pub const MAGIC: &[u8] = b"APTBKENV";
+pub const SEGMENT_SIZE: usize = 1 << 20;
+
+#[derive(Deserialize, Serialize)]
+struct EnvelopeHeader {
+    encryption: Option<EncryptionHeader>,
+    signed: bool,
+}
+
+#[derive(Deserialize, Serialize)]
+struct EncryptionHeader {
+    kek_id: KeyId,
+    wrapped_data_key: Vec<u8>,
+    nonce_prefix: NoncePrefix,
+}
+
+fn invalid_data(e: anyhow::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
+
+pub struct EnvelopeWriter {
+    inner: Box<dyn AsyncWrite + Send + Unpin>,
+    cipher: Option<SegmentCipher>,
+    signing_key: Option<Arc<Ed25519PrivateKey>>,
+    hasher: Sha256,
+    buf: Vec<u8>,
+    /// Sealed bytes not yet accepted by `inner`.
+    out: Vec<u8>,
+    out_pos: usize,
+    finished: bool,
+}
+
+impl EnvelopeWriter {
+    pub fn new(
+        inner: Box<dyn AsyncWrite + Send + Unpin>,
+        kek: Option<&KeyEncryptionKey>,
+        signing_key: Option<Arc<Ed25519PrivateKey>>,
+    ) -> Result<Self> {
+        let (encryption, cipher) = match kek {
+            Some(kek) => {
+                let data_key = random_bytes()?;
+                let nonce_prefix = random_bytes()?;
+                (
+                    Some(EncryptionHeader {
+                        kek_id: kek.id(),
+                        wrapped_data_key: kek.wrap(&data_key, &nonce_prefix)?,
+                        nonce_prefix,
+                    }),
+                    Some(SegmentCipher::new(&data_key, nonce_prefix)),
+                )
+            },
+            None => (None, None),
+        };
+        let header = bcs::to_bytes(&EnvelopeHeader {
+            encryption,
+            signed: signing_key.is_some(),
+        })?;
+
+        let mut writer = Self {
+            inner,
+            cipher,
+            signing_key,
+            hasher: Sha256::new(),
+            buf: Vec::with_capacity(SEGMENT_SIZE),
+            out: Vec::new(),
+            out_pos: 0,
+            finished: false,
+        };
+        writer.queue(MAGIC);
+        writer.queue(&(header.len() as u32).to_be_bytes());
+        writer.queue(&header);
+        Ok(writer)
+    }
+
+    fn queue(&mut self, bytes: &[u8]) {
+        self.hasher.update(bytes);
+        self.out.extend_from_slice(bytes);
+    }
+
+    fn seal_segment(&mut self, is_last: bool) -> Result<()> {
+        let mut payload = std::mem::take(&mut self.buf);
+        if let Some(cipher) = self.cipher.as_mut() {
+            cipher.seal(&mut payload, is_last)?;
+        }
+        self.queue(&(payload.len() as u32).to_be_bytes());
+        self.queue(&[is_last as u8]);
+        self.queue(&payload);
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        // The last segment can be empty, it's there to tell a complete file from a truncated one.
+        self.seal_segment(true)?;
+        if let Some(signing_key) = self.signing_key.as_ref() {
+            let digest = std::mem::take(&mut self.hasher).finalize();
+            let signature = signing_key
+                .sign(&BackupSignatureMessage::File(HashValue::from_slice(
+                    &digest,
+                )?))
+                .map_err(|e| anyhow!("Failed to sign: {}", e))?;
+            self.out.extend_from_slice(&signature.to_bytes());
+        }
+        self.finished = true;
+        Ok(())
+    }
+
+    fn poll_drain(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        while self.out_pos < self.out.len() {
+            let n = futures::ready!(
+                Pin::new(&mut self.inner).poll_write(cx, &self.out[self.out_pos..])
+            )?;
+            if n == 0 {
+                return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+            }
+            self.out_pos += n;
+        }
+        self.out.clear();
+        self.out_pos = 0;
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncWrite for EnvelopeWriter {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        data: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        // Bounds the memory held by sealed segments.
+        futures::ready!(this.poll_drain(cx))?;
+        if this.finished {
+            return Poll::Ready(Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Write after shutdown.",
+            )));
+        }
+
+        let len = std::cmp::min(data.len(), SEGMENT_SIZE - this.buf.len());
+        this.buf.extend_from_slice(&data[..len]);
+        if this.buf.len() == SEGMENT_SIZE {
+            this.seal_segment(false).map_err(invalid_data)?;
+        }
+        Poll::Ready(Ok(len))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        // Bytes of an unfinished segment stay buffered, the segment can't be sealed before it's
+        // full or the file ends.
+        let this = self.get_mut();
+        futures::ready!(this.poll_drain(cx))?;
+        Pin::new(&mut this.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        if !this.finished {
+            this.finish().map_err(invalid_data)?;
+        }
+        futures::ready!(this.poll_drain(cx))?;
+        Pin::new(&mut this.inner).poll_shutdown(cx)
+    }
+}
+
+struct EnvelopeReader {
+    inner: Box<dyn AsyncRead + Send + Unpin>,
+    file_handle: String,
+    cipher: Option<SegmentCipher>,
+    signed: bool,
+    verifying_key: Option<Ed25519PublicKey>,
+    hasher: Sha256,
+    done: bool,
+}
+
+impl EnvelopeReader {
+    /// `inner` is positioned right after the magic.
+    async fn new(
+        inner: Box<dyn AsyncRead + Send + Unpin>,
+        file_handle: String,
+        keks: &[KeyEncryptionKey],
+        verifying_key: Option<Ed25519PublicKey>,
+    ) -> Result<Self> {
+        let mut reader = Self {
+            inner,
+            file_handle,
+            cipher: None,
+            signed: false,
+            verifying_key,
+            hasher: Sha256::new(),
+            done: false,
+        };
+        reader.hasher.update(MAGIC);
+
+        let header_len = reader.read_u32().await? as usize;
+        ensure!(
+            header_len <= 1024,
+            "Envelope header too long: {}",
+            header_len
+        );
+        let header: EnvelopeHeader = bcs::from_bytes(&reader.read_hashed(header_len).await?)?;
+        reader.signed = header.signed;
+        if reader.verifying_key.is_some() && !header.signed {
+            bail!("{} is not signed.", reader.file_handle);
+        }
+        // Files are always encrypted once there is a key to encrypt them with.
+        if !keks.is_empty() && header.encryption.is_none() {
+            bail!("{} is not encrypted.", reader.file_handle);
+        }
+        if let Some(encryption) = header.encryption {
+            let kek = keks
+                .iter()
+                .find(|kek| kek.id() == encryption.kek_id)
+                .ok_or_else(|| {
+                    anyhow!(
+                        "{} is encrypted with key {}, which is not given.",
+                        reader.file_handle,
+                        hex::encode(encryption.kek_id),
+                    )
+                })?;
+            let data_key = kek.unwrap(&encryption.wrapped_data_key, &encryption.nonce_prefix)?;
+            reader.cipher = Some(SegmentCipher::new(&data_key, encryption.nonce_prefix));
+        }
+        Ok(reader)
+    }
+
+    async fn read_hashed(&mut self, len: usize) -> Result<Vec<u8>> {
+        let mut buf = vec![0u8; len];
+        self.inner.read_exact(&mut buf).await?;
+        self.hasher.update(&buf);
+        Ok(buf)
+    }
+
+    async fn read_u32(&mut self) -> Result<u32> {
+        let bytes = self.read_hashed(4).await?;
+        Ok(u32::from_be_bytes(bytes.try_into().expect("Read 4 bytes.")))
+    }
+
+    async fn next_segment(&mut self) -> Result<Option<Bytes>> {
+        if self.done {
+            return Ok(None);
+        }
+        let len = self.read_u32().await? as usize;
+        ensure!(
+            len <= SEGMENT_SIZE + SegmentCipher::tag_len(),
+            "Segment too long: {}",
+            len
+        );
+        let is_last = match self.read_hashed(1).await?[0] {
+            0 => false,
+            1 => true,
+            flag => bail!("Invalid segment flag: {}", flag),
+        };
+        let mut payload = self.read_hashed(len).await?;
+        if let Some(cipher) = self.cipher.as_mut() {
+            payload = cipher.open(payload, is_last)?;
+        }
+        if is_last {
+            self.finish().await?;
+        }
+        Ok(Some(payload.into()))
+    }
+
+    async fn finish(&mut self) -> Result<()> {
+        self.done = true;
+        if self.signed {
+            let mut signature = [0u8; ED25519_SIGNATURE_LENGTH];
+            self.inner.read_exact(&mut signature).await?;
+            if let Some(verifying_key) = self.verifying_key.as_ref() {
+                let digest = std::mem::take(&mut self.hasher).finalize();
+                Ed25519Signature::try_from(&signature[..])?
+                    .verify(
+                        &BackupSignatureMessage::File(HashValue::from_slice(&digest)?),
+                        verifying_key,
+                    )
+                    .map_err(|e| anyhow!("Bad signature on {}: {}", self.file_handle, e))?;
+            }
+        }
+        ensure!(
+            self.inner.read(&mut [0u8; 1]).await? == 0,
+            "Unexpected bytes at the end of {}.",
+            self.file_handle,
+        );
+        Ok(())
+    }
+}
+
+/// Opens an envelope whose magic has been consumed from `inner`. The header is checked before
+/// returning. Segments are authenticated as they are read if the file is encrypted, and the
+/// signature, if any, at the end of the file, failing the last read if it doesn't verify.
+pub async fn open_envelope(
+    inner: Box<dyn AsyncRead + Send + Unpin>,
+    file_handle: String,
+    keks: &[KeyEncryptionKey],
+    verifying_key: Option<Ed25519PublicKey>,
+) -> Result<Box<dyn AsyncRead + Send + Unpin>> {
+    let reader = EnvelopeReader::new(inner, file_handle, keks, verifying_key).await?;
+    let segments = stream::try_unfold(reader, |mut reader| async move {
+        Result::<_>::Ok(
+            reader
+                .next_segment()
+                .await?
+                .map(|segment| (segment, reader)),
+        )
+    })
+    .map_err(invalid_data);
+    Ok(Box::new(Box::pin(segments).into_async_read().compat()))
+}
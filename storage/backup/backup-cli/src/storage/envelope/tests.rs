@@ -0,0 +1,281 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::storage::{
+    local_fs::LocalFs,
+    test_util::{
+        arb_backups, arb_metadata_files, test_save_and_list_metadata_files_impl,
+        test_write_and_read_impl,
+    },
+};
+use aptos_crypto::{PrivateKey, Uniform};
+use aptos_temppath::TempPath;
+use proptest::prelude::*;
+use rand::{rngs::StdRng, SeedableRng};
+use std::path::Path;
+use tokio::{io::AsyncWriteExt, runtime::Runtime};
+
+fn signing_key(seed: u8) -> Ed25519PrivateKey {
+    Ed25519PrivateKey::generate(&mut StdRng::from_seed([seed; 32]))
+}
+
+fn new_store(
+    dir: &Path,
+    keks: Vec<KeyEncryptionKey>,
+    signing_key: Option<Ed25519PrivateKey>,
+    verifying_key: Option<Ed25519PublicKey>,
+) -> EnvelopeStorage {
+    EnvelopeStorage::new(
+        Arc::new(LocalFs::new(dir.to_path_buf())),
+        keks,
+        signing_key,
+        verifying_key,
+    )
+}
+
+fn new_sealing_store(dir: &Path) -> EnvelopeStorage {
+    new_store(
+        dir,
+        vec![KeyEncryptionKey::new([1; 32])],
+        Some(signing_key(1)),
+        Some(signing_key(1).public_key()),
+    )
+}
+
+async fn write_file(store: &dyn BackupStorage, name: &str, content: &[u8]) -> FileHandle {
+    let backup_handle = store
+        .create_backup(&"backup".parse().unwrap())
+        .await
+        .unwrap();
+    let (file_handle, mut file) = store
+        .create_for_write(&backup_handle, &name.parse().unwrap())
+        .await
+        .unwrap();
+    file.write_all(content).await.unwrap();
+    file.shutdown().await.unwrap();
+    file_handle
+}
+
+async fn read_file(store: &dyn BackupStorage, file_handle: &FileHandleRef) -> Result<Vec<u8>> {
+    let mut content = Vec::new();
+    store
+        .open_for_read(file_handle)
+        .await?
+        .read_to_end(&mut content)
+        .await?;
+    Ok(content)
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(10))]
+
+    #[test]
+    fn test_write_and_read(
+        backups in arb_backups()
+    ) {
+        let tmpdir = TempPath::new();
+        tmpdir.create_as_dir().unwrap();
+        let store = new_sealing_store(tmpdir.path());
+
+        let rt = Runtime::new().unwrap();
+        rt.block_on(test_write_and_read_impl(Box::new(store), backups));
+    }
+
+    #[test]
+    fn test_save_list_metadata_files(
+        input in arb_metadata_files(),
+    ) {
+        let tmpdir = TempPath::new();
+        tmpdir.create_as_dir().unwrap();
+        let store = new_sealing_store(tmpdir.path());
+
+        let rt = Runtime::new().unwrap();
+        rt.block_on(test_save_and_list_metadata_files_impl(Box::new(store), input));
+    }
+}
+
+#[test]
+fn test_encrypted_at_rest() {
+    let tmpdir = TempPath::new();
+    tmpdir.create_as_dir().unwrap();
+    let store = new_store(
+        tmpdir.path(),
+        vec![KeyEncryptionKey::new([1; 32])],
+        None,
+        None,
+    );
+    let plain_store = LocalFs::new(tmpdir.path().to_path_buf());
+
+    let rt = Runtime::new().unwrap();
+    // Spans a few segments.
+    let content: Vec<u8> = (0..stream::SEGMENT_SIZE * 5 / 2)
+        .map(|i| (i % 251) as u8)
+        .collect();
+    let file_handle = rt.block_on(write_file(&store, "file", &content));
+
+    let at_rest = rt.block_on(read_file(&plain_store, &file_handle)).unwrap();
+    assert!(at_rest.starts_with(MAGIC));
+    assert!(!at_rest
+        .windows(1024)
+        .any(|window| window == &content[..1024]));
+    assert_eq!(
+        rt.block_on(read_file(&store, &file_handle)).unwrap(),
+        content
+    );
+
+    // Can't be read without the key, but can after the key is rotated.
+    let no_key_store = new_store(tmpdir.path(), vec![], None, None);
+    assert!(rt.block_on(read_file(&no_key_store, &file_handle)).is_err());
+    let rotated_store = new_store(
+        tmpdir.path(),
+        vec![
+            KeyEncryptionKey::new([2; 32]),
+            KeyEncryptionKey::new([1; 32]),
+        ],
+        None,
+        None,
+    );
+    assert_eq!(
+        rt.block_on(read_file(&rotated_store, &file_handle))
+            .unwrap(),
+        content
+    );
+}
+
+#[test]
+fn test_detect_tampering() {
+    let tmpdir = TempPath::new();
+    tmpdir.create_as_dir().unwrap();
+    let file_path = |file_handle: &str| tmpdir.path().join(file_handle);
+    let rt = Runtime::new().unwrap();
+
+    // Encrypted, or only signed.
+    let mut num_files = 0;
+    for keks in [vec![KeyEncryptionKey::new([1; 32])], vec![]] {
+        let store = new_store(
+            tmpdir.path(),
+            keks,
+            Some(signing_key(1)),
+            Some(signing_key(1).public_key()),
+        );
+        let content = vec![7u8; 1000];
+        for (offset, truncate) in [(-10, false), (-70, false), (-1, true), (-80, true)] {
+            num_files += 1;
+            let file_handle =
+                rt.block_on(write_file(&store, &format!("file{}", num_files), &content));
+            let mut bytes = std::fs::read(file_path(&file_handle)).unwrap();
+            let pos = (bytes.len() as isize + offset) as usize;
+            if truncate {
+                bytes.truncate(pos);
+            } else {
+                bytes[pos] ^= 1;
+            }
+            std::fs::write(file_path(&file_handle), &bytes).unwrap();
+            assert!(rt.block_on(read_file(&store, &file_handle)).is_err());
+        }
+    }
+}
+
+#[test]
+fn test_verify_signatures() {
+    let tmpdir = TempPath::new();
+    tmpdir.create_as_dir().unwrap();
+    let rt = Runtime::new().unwrap();
+    let unsigned_store = new_store(tmpdir.path(), vec![], None, None);
+    let signed_store = new_store(tmpdir.path(), vec![], Some(signing_key(1)), None);
+    let verifying_store = new_store(
+        tmpdir.path(),
+        vec![],
+        None,
+        Some(signing_key(1).public_key()),
+    );
+    let other_verifying_store = new_store(
+        tmpdir.path(),
+        vec![],
+        None,
+        Some(signing_key(2).public_key()),
+    );
+
+    let content = b"content".to_vec();
+    let unsigned = rt.block_on(write_file(&unsigned_store, "unsigned", &content));
+    assert!(rt.block_on(read_file(&verifying_store, &unsigned)).is_err());
+    let signed = rt.block_on(write_file(&signed_store, "signed", &content));
+    assert_eq!(
+        rt.block_on(read_file(&verifying_store, &signed)).unwrap(),
+        content
+    );
+    assert!(rt
+        .block_on(read_file(&other_verifying_store, &signed))
+        .is_err());
+    // Signatures are not checked without a verifying key.
+    assert_eq!(
+        rt.block_on(read_file(&unsigned_store, &signed)).unwrap(),
+        content
+    );
+
+    let line = TextLine::new("{\"some\":\"metadata\"}").unwrap();
+    let unsigned = rt
+        .block_on(unsigned_store.save_metadata_line(&"unsigned.meta".parse().unwrap(), &line))
+        .unwrap();
+    assert!(rt.block_on(read_file(&verifying_store, &unsigned)).is_err());
+    let signed = rt
+        .block_on(signed_store.save_metadata_line(&"signed.meta".parse().unwrap(), &line))
+        .unwrap();
+    assert_eq!(
+        rt.block_on(read_file(&verifying_store, &signed)).unwrap(),
+        line.as_ref().as_bytes()
+    );
+    assert!(rt
+        .block_on(read_file(&other_verifying_store, &signed))
+        .is_err());
+
+    // Metadata lines tampered with.
+    let path = tmpdir.path().join(&signed);
+    let tampered = std::fs::read_to_string(&path)
+        .unwrap()
+        .replace("metadata", "tampered");
+    std::fs::write(&path, tampered).unwrap();
+    assert!(rt.block_on(read_file(&verifying_store, &signed)).is_err());
+}
+
+#[test]
+fn test_reject_unencrypted() {
+    let tmpdir = TempPath::new();
+    tmpdir.create_as_dir().unwrap();
+    let rt = Runtime::new().unwrap();
+    let store = new_store(
+        tmpdir.path(),
+        vec![KeyEncryptionKey::new([1; 32])],
+        None,
+        None,
+    );
+    let plain_store = LocalFs::new(tmpdir.path().to_path_buf());
+    let unencrypted_store = new_store(tmpdir.path(), vec![], None, None);
+
+    // A file that's not an envelope, or an envelope without encryption, in place of a backup file.
+    let content = b"content".to_vec();
+    let plain = rt.block_on(write_file(&plain_store, "plain", &content));
+    assert!(rt.block_on(read_file(&store, &plain)).is_err());
+    let unencrypted = rt.block_on(write_file(&unencrypted_store, "unencrypted", &content));
+    assert_eq!(
+        rt.block_on(read_file(&unencrypted_store, &unencrypted))
+            .unwrap(),
+        content
+    );
+    assert!(rt.block_on(read_file(&store, &unencrypted)).is_err());
+
+    // Metadata files are plain text, but only once known to be metadata files.
+    let line = TextLine::new("{\"some\":\"metadata\"}").unwrap();
+    let metadata = rt
+        .block_on(plain_store.save_metadata_line(&"plain.meta".parse().unwrap(), &line))
+        .unwrap();
+    assert!(rt.block_on(read_file(&store, &metadata)).is_err());
+    assert_eq!(rt.block_on(store.list_metadata_files()).unwrap(), vec![
+        metadata.clone()
+    ]);
+    assert_eq!(
+        rt.block_on(read_file(&store, &metadata)).unwrap(),
+        line.as_ref().as_bytes()
+    );
+}
@@ -3,7 +3,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod command_adapter;
+pub mod envelope;
 pub mod local_fs;
+pub mod s3;
 
 #[cfg(test)]
 mod test_util;
@@ -12,7 +14,9 @@ mod tests;
 
 use crate::storage::{
     command_adapter::{CommandAdapter, CommandAdapterOpt},
+    envelope::{EnvelopeOpt, EnvelopeStorage},
     local_fs::{LocalFs, LocalFsOpt},
+    s3::{S3Opt, S3},
 };
 use anyhow::{ensure, Result};
 use async_trait::async_trait;
@@ -198,6 +202,12 @@ pub enum StorageOpt {
     https://github.com/aptos-labs/aptos-core/tree/main/storage/backup/backup-cli/src/storage/command_adapter/sample_configs/"
     )]
     CommandAdapter(CommandAdapterOpt),
+    #[clap(
+        about = "Select the S3 backup storage type, which talks to S3 or an S3 compatible object \
+    store directly. Credentials are read from $AWS_ACCESS_KEY_ID, $AWS_SECRET_ACCESS_KEY and \
+    optionally $AWS_SESSION_TOKEN."
+    )]
+    S3(S3Opt),
 }
 
 impl StorageOpt {
@@ -205,6 +215,7 @@ impl StorageOpt {
         Ok(match self {
             StorageOpt::LocalFs(opt) => Arc::new(LocalFs::new_with_opt(opt)),
             StorageOpt::CommandAdapter(opt) => Arc::new(CommandAdapter::new_with_opt(opt).await?),
+            StorageOpt::S3(opt) => Arc::new(S3::new_with_opt(opt)?),
         })
     }
 }
@@ -213,7 +224,7 @@ impl StorageOpt {
 #[clap(group(
     ArgGroup::new("storage")
     .required(true)
-    .args(&["local_fs_dir", "command_adapter_config"]),
+    .args(&["local_fs_dir", "command_adapter_config", "s3_url"]),
 ))]
 pub struct DBToolStorageOpt {
     #[clap(
@@ -229,14 +240,30 @@ pub struct DBToolStorageOpt {
     https://github.com/aptos-labs/aptos-networks/tree/main/testnet/backups "
     )]
     command_adapter_config: Option<CommandAdapterOpt>,
+    #[clap(
+        long,
+        help = "Select the S3 backup storage type, with an URL like s3://bucket/prefix. \
+    Credentials are read from $AWS_ACCESS_KEY_ID, $AWS_SECRET_ACCESS_KEY and optionally \
+    $AWS_SESSION_TOKEN, the region from $AWS_REGION and a custom endpoint from $AWS_ENDPOINT_URL."
+    )]
+    s3_url: Option<S3Opt>,
+    #[clap(flatten)]
+    envelope_opt: EnvelopeOpt,
 }
 
 impl DBToolStorageOpt {
     pub async fn init_storage(self) -> Result<Arc<dyn BackupStorage>> {
-        Ok(if self.local_fs_dir.is_some() {
+        let storage: Arc<dyn BackupStorage> = if self.local_fs_dir.is_some() {
             Arc::new(LocalFs::new_with_opt(self.local_fs_dir.unwrap()))
+        } else if self.s3_url.is_some() {
+            Arc::new(S3::new_with_opt(self.s3_url.unwrap())?)
         } else {
             Arc::new(CommandAdapter::new_with_opt(self.command_adapter_config.unwrap()).await?)
+        };
+        Ok(if self.envelope_opt.is_enabled() {
+            Arc::new(EnvelopeStorage::new_with_opt(self.envelope_opt, storage)?)
+        } else {
+            storage
         })
     }
 }
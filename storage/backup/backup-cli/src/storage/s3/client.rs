@@ -0,0 +1,444 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::storage::s3::sigv4::{self, sha256_hex, uri_encode_path, Credentials};
+use anyhow::{bail, ensure, format_err, Result};
+use aptos_logger::warn;
+use bytes::Bytes;
+use chrono::Utc;
+use futures::TryStreamExt;
+use rand::Rng;
+use reqwest::{header::HeaderMap, Method, Response, StatusCode};
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
+use tokio::{io::AsyncRead, sync::Semaphore};
+use tokio_util::compat::FuturesAsyncReadCompatExt;
+
+/// Minimal client of the S3 REST API, covering what the backup storage needs.
+///
+/// Requests are signed with SigV4, retried with exponential backoff on throttling, server errors
+/// and network errors, and at most `max_concurrent_requests` are in flight at the same time.
+pub struct S3Client {
+    http: reqwest::Client,
+    /// Like "https://s3.us-east-1.amazonaws.com" or "http://localhost:9000".
+    endpoint: String,
+    bucket: String,
+    region: String,
+    path_style: bool,
+    credentials: Credentials,
+    max_retries: usize,
+    concurrency: Arc<Semaphore>,
+}
+
+struct Request {
+    method: Method,
+    key: String,
+    query: Vec<(String, String)>,
+    /// Lower case names, all of them are signed.
+    headers: BTreeMap<String, String>,
+    body: Bytes,
+}
+
+impl Request {
+    fn new(method: Method, key: &str) -> Self {
+        Self {
+            method,
+            key: key.to_string(),
+            query: Vec::new(),
+            headers: BTreeMap::new(),
+            body: Bytes::new(),
+        }
+    }
+
+    fn query(mut self, key: &str, value: &str) -> Self {
+        self.query.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.insert(name.to_lowercase(), value.to_string());
+        self
+    }
+
+    fn body(mut self, body: Bytes) -> Self {
+        self.body = body;
+        self
+    }
+}
+
+impl S3Client {
+    const BACKOFF_BASE_MS: u64 = 200;
+    const BACKOFF_MAX_MS: u64 = 10_000;
+    const METADATA_PREFIX: &'static str = "x-amz-meta-";
+    const TIMEOUT_SECS: u64 = 300;
+
+    pub fn new(
+        endpoint: String,
+        bucket: String,
+        region: String,
+        path_style: bool,
+        credentials: Credentials,
+        max_concurrent_requests: usize,
+        max_retries: usize,
+    ) -> Result<Self> {
+        ensure!(
+            endpoint.starts_with("http://") || endpoint.starts_with("https://"),
+            "S3 endpoint {} has no http(s) scheme.",
+            endpoint,
+        );
+        ensure!(
+            max_concurrent_requests > 0,
+            "Request concurrency can't be 0."
+        );
+        Ok(Self {
+            http: reqwest::Client::builder()
+                .timeout(Duration::from_secs(Self::TIMEOUT_SECS))
+                .build()?,
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            bucket,
+            region,
+            path_style,
+            credentials,
+            max_retries,
+            concurrency: Arc::new(Semaphore::new(max_concurrent_requests)),
+        })
+    }
+
+    pub async fn put_object(
+        &self,
+        key: &str,
+        body: Bytes,
+        metadata: &[(&str, &str)],
+    ) -> Result<()> {
+        let mut request = Request::new(Method::PUT, key).body(body);
+        for (name, value) in metadata {
+            request = request.header(&format!("{}{}", Self::METADATA_PREFIX, name), value);
+        }
+        self.send(request).await?;
+        Ok(())
+    }
+
+    /// Returns the user metadata of the object, and a reader of its content.
+    pub async fn get_object(
+        &self,
+        key: &str,
+    ) -> Result<(BTreeMap<String, String>, Option<u64>, impl AsyncRead)> {
+        let response = self.send(Request::new(Method::GET, key)).await?;
+        let metadata = Self::user_metadata(response.headers());
+        let content_length = response.content_length();
+        let reader = response
+            .bytes_stream()
+            .map_err(|e| futures::io::Error::new(futures::io::ErrorKind::Other, e))
+            .into_async_read()
+            .compat();
+        Ok((metadata, content_length, reader))
+    }
+
+    pub async fn delete_object(&self, key: &str) -> Result<()> {
+        self.send(Request::new(Method::DELETE, key)).await?;
+        Ok(())
+    }
+
+    /// Server side copy, replacing the user metadata if `metadata` is given.
+    pub async fn copy_object(
+        &self,
+        src_key: &str,
+        dst_key: &str,
+        metadata: Option<&[(&str, &str)]>,
+    ) -> Result<()> {
+        let mut request = Request::new(Method::PUT, dst_key).header(
+            "x-amz-copy-source",
+            &uri_encode_path(&format!("/{}/{}", self.bucket, src_key)),
+        );
+        if let Some(metadata) = metadata {
+            request = request.header("x-amz-metadata-directive", "REPLACE");
+            for (name, value) in metadata {
+                request = request.header(&format!("{}{}", Self::METADATA_PREFIX, name), value);
+            }
+        }
+        // A copy can fail after the 200 OK has been sent, in which case the body is an error.
+        let body = self.send_and_read(request).await?;
+        Self::ensure_no_error(&body)
+    }
+
+    /// Lists all keys starting with `prefix`.
+    pub async fn list_objects(&self, prefix: &str) -> Result<Vec<String>> {
+        let mut keys = Vec::new();
+        let mut continuation_token = None;
+        loop {
+            let mut request = Request::new(Method::GET, "")
+                .query("list-type", "2")
+                .query("prefix", prefix);
+            if let Some(token) = &continuation_token {
+                request = request.query("continuation-token", token);
+            }
+            let body = self.send_and_read(request).await?;
+            keys.extend(xml_values(&body, "Key"));
+            if xml_values(&body, "IsTruncated").first().map(String::as_str) != Some("true") {
+                break;
+            }
+            continuation_token = Some(
+                xml_values(&body, "NextContinuationToken")
+                    .pop()
+                    .ok_or_else(|| format_err!("Truncated listing without continuation token."))?,
+            );
+        }
+        Ok(keys)
+    }
+
+    /// Returns the upload ID. The user metadata of the object is fixed here.
+    pub async fn create_multipart_upload(
+        &self,
+        key: &str,
+        metadata: &[(&str, &str)],
+    ) -> Result<String> {
+        let mut request = Request::new(Method::POST, key).query("uploads", "");
+        for (name, value) in metadata {
+            request = request.header(&format!("{}{}", Self::METADATA_PREFIX, name), value);
+        }
+        let body = self.send_and_read(request).await?;
+        xml_values(&body, "UploadId")
+            .pop()
+            .ok_or_else(|| format_err!("No UploadId in response: {}", body))
+    }
+
+    /// Returns the ETag of the part.
+    pub async fn upload_part(
+        &self,
+        key: &str,
+        upload_id: &str,
+        part_number: u32,
+        body: Bytes,
+    ) -> Result<String> {
+        let response = self
+            .send(
+                Request::new(Method::PUT, key)
+                    .query("partNumber", &part_number.to_string())
+                    .query("uploadId", upload_id)
+                    .body(body),
+            )
+            .await?;
+        Ok(response
+            .headers()
+            .get("etag")
+            .ok_or_else(|| format_err!("No ETag for part {} of {}", part_number, key))?
+            .to_str()?
+            .to_string())
+    }
+
+    /// `parts` are (part number, ETag) in part number order.
+    pub async fn complete_multipart_upload(
+        &self,
+        key: &str,
+        upload_id: &str,
+        parts: &[(u32, String)],
+    ) -> Result<()> {
+        let xml = format!(
+            "<CompleteMultipartUpload>{}</CompleteMultipartUpload>",
+            parts
+                .iter()
+                .map(|(number, etag)| format!(
+                    "<Part><PartNumber>{}</PartNumber><ETag>{}</ETag></Part>",
+                    number,
+                    xml_escape(etag)
+                ))
+                .collect::<String>(),
+        );
+        // Like a copy, completing an upload can fail after the 200 OK.
+        let body = self
+            .send_and_read(
+                Request::new(Method::POST, key)
+                    .query("uploadId", upload_id)
+                    .body(Bytes::from(xml)),
+            )
+            .await?;
+        Self::ensure_no_error(&body)
+    }
+
+    pub async fn abort_multipart_upload(&self, key: &str, upload_id: &str) -> Result<()> {
+        self.send(Request::new(Method::DELETE, key).query("uploadId", upload_id))
+            .await?;
+        Ok(())
+    }
+
+    async fn send_and_read(&self, request: Request) -> Result<String> {
+        let description = format!("{} {}", request.method, request.key);
+        let _permit = self.concurrency.acquire().await?;
+        let response = self.send_with_retries(request).await?;
+        response
+            .text()
+            .await
+            .map_err(|e| format_err!("{} failed reading response: {}", description, e))
+    }
+
+    async fn send(&self, request: Request) -> Result<Response> {
+        let _permit = self.concurrency.acquire().await?;
+        self.send_with_retries(request).await
+    }
+
+    async fn send_with_retries(&self, request: Request) -> Result<Response> {
+        let mut attempt = 0;
+        loop {
+            let error = match self.send_once(&request).await {
+                Ok(response) if response.status().is_success() => return Ok(response),
+                Ok(response) => {
+                    let status = response.status();
+                    let body = response.text().await.unwrap_or_default();
+                    let error = format_err!(
+                        "{} {} failed with {}: {} {}",
+                        request.method,
+                        request.key,
+                        status,
+                        xml_values(&body, "Code").join(""),
+                        xml_values(&body, "Message").join(""),
+                    );
+                    if !Self::is_retryable(status) {
+                        return Err(error);
+                    }
+                    error
+                },
+                Err(e) => format_err!("{} {} failed: {}", request.method, request.key, e),
+            };
+            if attempt >= self.max_retries {
+                return Err(error);
+            }
+            let backoff_ms = std::cmp::min(Self::BACKOFF_BASE_MS << attempt, Self::BACKOFF_MAX_MS)
+                + rand::thread_rng().gen_range(0, Self::BACKOFF_BASE_MS);
+            warn!(
+                error = %error,
+                attempt = attempt,
+                backoff_ms = backoff_ms,
+                "S3 request failed, retrying."
+            );
+            tokio::time::sleep(Duration::from_millis(backoff_ms)).await;
+            attempt += 1;
+        }
+    }
+
+    fn is_retryable(status: StatusCode) -> bool {
+        status.is_server_error()
+            || status == StatusCode::TOO_MANY_REQUESTS
+            || status == StatusCode::REQUEST_TIMEOUT
+    }
+
+    async fn send_once(&self, request: &Request) -> Result<Response> {
+        let (host, path) = self.host_and_path(&request.key)?;
+        let mut headers = request.headers.clone();
+        headers.insert("host".to_string(), host);
+        headers.insert(
+            "x-amz-date".to_string(),
+            Utc::now().format("%Y%m%dT%H%M%SZ").to_string(),
+        );
+        headers.insert(
+            "x-amz-content-sha256".to_string(),
+            sha256_hex(&request.body),
+        );
+        if let Some(token) = &self.credentials.session_token {
+            headers.insert("x-amz-security-token".to_string(), token.clone());
+        }
+        let authorization = sigv4::authorization(
+            &self.credentials,
+            &self.region,
+            request.method.as_str(),
+            &path,
+            &request.query,
+            &headers,
+        )?;
+
+        let mut url = format!("{}{}", self.scheme_and_host(&headers["host"]), path);
+        if !request.query.is_empty() {
+            url.push('?');
+            url.push_str(&sigv4::canonical_query_string(&request.query));
+        }
+        let mut builder = self
+            .http
+            .request(request.method.clone(), url)
+            .header("authorization", authorization)
+            .body(request.body.clone());
+        for (name, value) in headers.iter().filter(|(name, _)| *name != "host") {
+            builder = builder.header(name, value);
+        }
+        Ok(builder.send().await?)
+    }
+
+    /// Host header and encoded path of an object, or the bucket if `key` is empty.
+    fn host_and_path(&self, key: &str) -> Result<(String, String)> {
+        let endpoint_host = self
+            .endpoint
+            .split_once("://")
+            .map(|(_, host)| host.to_string())
+            .ok_or_else(|| format_err!("Bad endpoint {}", self.endpoint))?;
+        Ok(if self.path_style {
+            let path = if key.is_empty() {
+                format!("/{}", self.bucket)
+            } else {
+                format!("/{}/{}", self.bucket, key)
+            };
+            (endpoint_host, uri_encode_path(&path))
+        } else {
+            (
+                format!("{}.{}", self.bucket, endpoint_host),
+                uri_encode_path(&format!("/{}", key)),
+            )
+        })
+    }
+
+    fn scheme_and_host(&self, host: &str) -> String {
+        let scheme = self.endpoint.split_once("://").map_or("https", |(s, _)| s);
+        format!("{}://{}", scheme, host)
+    }
+
+    fn user_metadata(headers: &HeaderMap) -> BTreeMap<String, String> {
+        headers
+            .iter()
+            .filter_map(|(name, value)| {
+                let name = name.as_str().strip_prefix(Self::METADATA_PREFIX)?;
+                Some((name.to_string(), value.to_str().ok()?.to_string()))
+            })
+            .collect()
+    }
+
+    fn ensure_no_error(body: &str) -> Result<()> {
+        if body.contains("<Error>") {
+            bail!(
+                "S3 request failed: {} {}",
+                xml_values(body, "Code").join(""),
+                xml_values(body, "Message").join(""),
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Contents of all the `<tag>` elements, good enough for the flat responses of S3.
+pub(crate) fn xml_values(xml: &str, tag: &str) -> Vec<String> {
+    let (open, close) = (format!("<{}>", tag), format!("</{}>", tag));
+    let mut values = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find(&open) {
+        rest = &rest[start + open.len()..];
+        match rest.find(&close) {
+            Some(end) => {
+                values.push(xml_unescape(&rest[..end]));
+                rest = &rest[end + close.len()..];
+            },
+            None => break,
+        }
+    }
+    values
+}
+
+pub(crate) fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn xml_unescape(s: &str) -> String {
+    s.replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
@@ -0,0 +1,559 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+mod client;
+pub mod sigv4;
+
+#[cfg(test)]
+mod tests;
+
+use crate::storage::{
+    s3::{client::S3Client, sigv4::Credentials},
+    BackupHandle, BackupHandleRef, BackupStorage, FileHandle, FileHandleRef, ShellSafeName,
+    TextLine,
+};
+use anyhow::{ensure, format_err, Result};
+use aptos_logger::warn;
+use async_trait::async_trait;
+use bytes::Bytes;
+use clap::Parser;
+use futures::{
+    future::{BoxFuture, Shared},
+    stream::FuturesOrdered,
+    FutureExt, StreamExt,
+};
+use sha2::{Digest, Sha256};
+use std::{
+    ffi::OsStr,
+    io,
+    path::Path,
+    pin::Pin,
+    str::FromStr,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, ReadBuf},
+    task::JoinHandle,
+};
+
+#[derive(Parser, Clone, Debug)]
+pub struct S3Opt {
+    #[clap(long = "bucket", help = "Bucket to hold backups.")]
+    pub bucket: String,
+    #[clap(
+        long = "prefix",
+        default_value = "",
+        help = "Key prefix under which backups are stored, like \"backup1/e1\"."
+    )]
+    pub prefix: String,
+    #[clap(
+        long = "endpoint",
+        help = "Endpoint of an S3 compatible object store, like \"http://localhost:9000\". \
+        Defaults to $AWS_ENDPOINT_URL, or AWS S3 in the region."
+    )]
+    pub endpoint: Option<String>,
+    #[clap(
+        long = "region",
+        help = "Defaults to $AWS_REGION or $AWS_DEFAULT_REGION, or us-east-1 if neither is set."
+    )]
+    pub region: Option<String>,
+    #[clap(
+        long = "path-style",
+        help = "Address the bucket in the URL path instead of the host name, which most S3 \
+        compatible object stores need."
+    )]
+    pub path_style: bool,
+    #[clap(
+        long = "max-concurrent-requests",
+        default_value_t = S3Opt::DEFAULT_MAX_CONCURRENT_REQUESTS,
+        help = "Maximum number of requests in flight at any time."
+    )]
+    pub max_concurrent_requests: usize,
+    #[clap(
+        long = "part-size",
+        default_value_t = S3Opt::DEFAULT_PART_SIZE,
+        help = "Files larger than this are uploaded in parts of this size, in bytes."
+    )]
+    pub part_size: usize,
+    #[clap(
+        long = "max-retries",
+        default_value_t = S3Opt::DEFAULT_MAX_RETRIES,
+        help = "Times a failed request is retried, with exponential backoff."
+    )]
+    pub max_retries: usize,
+}
+
+impl S3Opt {
+    const DEFAULT_MAX_CONCURRENT_REQUESTS: usize = 16;
+    const DEFAULT_MAX_RETRIES: usize = 5;
+    const DEFAULT_PART_SIZE: usize = 16 * 1024 * 1024;
+}
+
+impl FromStr for S3Opt {
+    type Err = anyhow::Error;
+
+    /// Parses "s3://bucket/prefix", with everything else left to the defaults.
+    fn from_str(s: &str) -> Result<Self> {
+        let path = s
+            .strip_prefix("s3://")
+            .ok_or_else(|| format_err!("Expecting s3://bucket/prefix, got {}", s))?;
+        let (bucket, prefix) = path.split_once('/').unwrap_or((path, ""));
+        ensure!(!bucket.is_empty(), "No bucket in {}", s);
+        Ok(Self {
+            bucket: bucket.to_string(),
+            prefix: prefix.to_string(),
+            endpoint: None,
+            region: None,
+            path_style: false,
+            max_concurrent_requests: Self::DEFAULT_MAX_CONCURRENT_REQUESTS,
+            part_size: Self::DEFAULT_PART_SIZE,
+            max_retries: Self::DEFAULT_MAX_RETRIES,
+        })
+    }
+}
+
+/// A storage backend that talks to S3 or an S3 compatible object store directly.
+///
+/// Files are uploaded in parts when they are larger than the part size, with up to
+/// `max_concurrent_requests` parts in flight. The SHA-256 of each file is kept in the object
+/// metadata, and verified when it's read back. The metadata of a multipart upload is fixed
+/// before its content is known, so the SHA-256 of a file uploaded in parts is kept in a sidecar
+/// object instead, which the metadata points to. The sidecar is written before the upload
+/// completes, so every object pointing to one has it, while a sidecar left behind by an upload
+/// that never completed is ignored.
+pub struct S3 {
+    client: Arc<S3Client>,
+    /// Without leading or trailing slashes, possibly empty.
+    prefix: String,
+    part_size: usize,
+    max_parts_in_flight: usize,
+}
+
+impl S3 {
+    const CHECKSUM_METADATA: &'static str = "sha256";
+    const CHECKSUM_SIDECAR_METADATA: &'static str = "sha256-sidecar";
+    const CHECKSUM_SIDECAR_SUFFIX: &'static str = ".sha256";
+    const DEFAULT_REGION: &'static str = "us-east-1";
+    const METADATA_BACKUP_DIR: &'static str = "metadata_backup";
+    const METADATA_DIR: &'static str = "metadata";
+    /// S3 doesn't accept more parts for an object.
+    const MAX_PARTS: u32 = 10_000;
+
+    pub fn new(opt: S3Opt, credentials: Credentials) -> Result<Self> {
+        ensure!(opt.part_size > 0, "Part size can't be 0.");
+        let region = opt
+            .region
+            .or_else(|| std::env::var("AWS_REGION").ok())
+            .or_else(|| std::env::var("AWS_DEFAULT_REGION").ok())
+            .unwrap_or_else(|| Self::DEFAULT_REGION.to_string());
+        let endpoint = opt
+            .endpoint
+            .or_else(|| std::env::var("AWS_ENDPOINT_URL").ok())
+            .unwrap_or_else(|| format!("https://s3.{}.amazonaws.com", region));
+        let client = S3Client::new(
+            endpoint,
+            opt.bucket,
+            region,
+            opt.path_style,
+            credentials,
+            opt.max_concurrent_requests,
+            opt.max_retries,
+        )?;
+        Ok(Self {
+            client: Arc::new(client),
+            prefix: opt.prefix.trim_matches('/').to_string(),
+            part_size: opt.part_size,
+            max_parts_in_flight: opt.max_concurrent_requests,
+        })
+    }
+
+    pub fn new_with_opt(opt: S3Opt) -> Result<Self> {
+        Self::new(opt, Credentials::from_env()?)
+    }
+
+    fn key(&self, file_handle: &FileHandleRef) -> String {
+        if self.prefix.is_empty() {
+            file_handle.to_string()
+        } else {
+            format!("{}/{}", self.prefix, file_handle)
+        }
+    }
+
+    fn file_handle<'a>(&self, key: &'a str) -> Option<&'a str> {
+        if self.prefix.is_empty() {
+            Some(key)
+        } else {
+            key.strip_prefix(&self.prefix)?.strip_prefix('/')
+        }
+    }
+
+    fn checksum_sidecar_key(key: &str) -> String {
+        format!("{}{}", key, Self::CHECKSUM_SIDECAR_SUFFIX)
+    }
+
+    async fn read_checksum_sidecar(&self, key: &str) -> Result<String> {
+        let (_metadata, _content_length, reader) = self
+            .client
+            .get_object(&Self::checksum_sidecar_key(key))
+            .await?;
+        let mut checksum = String::new();
+        Box::pin(reader).read_to_string(&mut checksum).await?;
+        Ok(checksum)
+    }
+}
+
+#[async_trait]
+impl BackupStorage for S3 {
+    async fn create_backup(&self, name: &ShellSafeName) -> Result<BackupHandle> {
+        // There are no directories to create in an object store.
+        Ok(name.to_string())
+    }
+
+    async fn create_for_write(
+        &self,
+        backup_handle: &BackupHandleRef,
+        name: &ShellSafeName,
+    ) -> Result<(FileHandle, Box<dyn AsyncWrite + Send + Unpin>)> {
+        let file_handle = format!("{}/{}", backup_handle, name.as_ref());
+        let writer = S3Writer::new(
+            self.client.clone(),
+            self.key(&file_handle),
+            self.part_size,
+            self.max_parts_in_flight,
+        );
+        Ok((file_handle, Box::new(writer)))
+    }
+
+    async fn open_for_read(
+        &self,
+        file_handle: &FileHandleRef,
+    ) -> Result<Box<dyn AsyncRead + Send + Unpin>> {
+        let key = self.key(file_handle);
+        let (metadata, content_length, reader) = self.client.get_object(&key).await?;
+        let expected_sha256 = match metadata.get(Self::CHECKSUM_METADATA) {
+            Some(checksum) => Some(checksum.clone()),
+            None if metadata.contains_key(Self::CHECKSUM_SIDECAR_METADATA) => {
+                Some(self.read_checksum_sidecar(&key).await?)
+            },
+            None => None,
+        };
+        if expected_sha256.is_none() {
+            warn!(
+                key = key,
+                "No checksum in object metadata, not verifying it."
+            );
+        }
+        Ok(Box::new(ChecksumVerifyingReader {
+            inner: Box::pin(reader),
+            key,
+            hasher: Sha256::new(),
+            len: 0,
+            expected_len: content_length,
+            expected_sha256,
+            verified: false,
+        }))
+    }
+
+    async fn list_metadata_files(&self) -> Result<Vec<FileHandle>> {
+        let keys = self
+            .client
+            .list_objects(&format!("{}/", self.key(Self::METADATA_DIR)))
+            .await?;
+        Ok(keys
+            .iter()
+            .filter(|key| !key.ends_with(Self::CHECKSUM_SIDECAR_SUFFIX))
+            .filter_map(|key| self.file_handle(key))
+            .map(str::to_string)
+            .collect())
+    }
+
+    /// file_handle are expected to be the return results from list_metadata_files
+    /// file_handle is a path with `metadata` in the path, Ex: metadata/epoch_ending_1.meta
+    async fn backup_metadata_file(&self, file_handle: &FileHandleRef) -> Result<()> {
+        let name = Path::new(file_handle)
+            .file_name()
+            .and_then(OsStr::to_str)
+            .ok_or_else(|| format_err!("cannot extract filename from {}", file_handle))?;
+        let src_key = self.key(file_handle);
+        let dst_key = self.key(&format!("{}/{}", Self::METADATA_BACKUP_DIR, name));
+        // No renaming in an object store, copy and delete instead.
+        self.client.copy_object(&src_key, &dst_key, None).await?;
+        self.client.delete_object(&src_key).await
+    }
+
+    async fn save_metadata_lines(
+        &self,
+        name: &ShellSafeName,
+        lines: &[TextLine],
+    ) -> Result<FileHandle> {
+        let content = lines
+            .iter()
+            .map(|e| e.as_ref())
+            .collect::<Vec<&str>>()
+            .join("");
+        let checksum = sigv4::sha256_hex(content.as_bytes());
+        let file_handle = format!("{}/{}", Self::METADATA_DIR, name.as_ref());
+        self.client
+            .put_object(
+                &self.key(&file_handle),
+                Bytes::from(content),
+                &[(Self::CHECKSUM_METADATA, &checksum)],
+            )
+            .await?;
+        Ok(file_handle)
+    }
+}
+
+type PartFut = JoinHandle<Result<(u32, String)>>;
+type UploadIdFut = Shared<BoxFuture<'static, Result<String, String>>>;
+
+/// Buffers what's written into parts, starting a multipart upload once the first part is full.
+/// A file smaller than a part is uploaded in one request on shutdown.
+struct S3Writer {
+    client: Arc<S3Client>,
+    key: String,
+    part_size: usize,
+    max_parts_in_flight: usize,
+    buf: Vec<u8>,
+    hasher: Sha256,
+    upload_id: Option<UploadIdFut>,
+    num_parts: u32,
+    parts_in_flight: FuturesOrdered<PartFut>,
+    parts_done: Vec<(u32, String)>,
+    shutdown: Option<BoxFuture<'static, Result<()>>>,
+}
+
+impl S3Writer {
+    fn new(
+        client: Arc<S3Client>,
+        key: String,
+        part_size: usize,
+        max_parts_in_flight: usize,
+    ) -> Self {
+        Self {
+            client,
+            key,
+            part_size,
+            max_parts_in_flight,
+            buf: Vec::with_capacity(part_size),
+            hasher: Sha256::new(),
+            upload_id: None,
+            num_parts: 0,
+            parts_in_flight: FuturesOrdered::new(),
+            parts_done: Vec::new(),
+            shutdown: None,
+        }
+    }
+
+    fn upload_id(&mut self) -> UploadIdFut {
+        let (client, key) = (self.client.clone(), self.key.clone());
+        self.upload_id
+            .get_or_insert_with(|| {
+                let handle = tokio::spawn(async move {
+                    client
+                        .create_multipart_upload(&key, &[(S3::CHECKSUM_SIDECAR_METADATA, "true")])
+                        .await
+                });
+                async move {
+                    match handle.await {
+                        Ok(Ok(upload_id)) => Ok(upload_id),
+                        Ok(Err(e)) => Err(e.to_string()),
+                        Err(e) => Err(e.to_string()),
+                    }
+                }
+                .boxed()
+                .shared()
+            })
+            .clone()
+    }
+
+    fn start_part(&mut self) -> io::Result<()> {
+        if self.num_parts == S3::MAX_PARTS {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                format!("{} needs more than {} parts.", self.key, S3::MAX_PARTS),
+            ));
+        }
+        self.num_parts += 1;
+        let part_number = self.num_parts;
+        let body = Bytes::from(std::mem::replace(
+            &mut self.buf,
+            Vec::with_capacity(self.part_size),
+        ));
+        let (client, key, upload_id) = (self.client.clone(), self.key.clone(), self.upload_id());
+        self.parts_in_flight.push_back(tokio::spawn(async move {
+            let upload_id = upload_id.await.map_err(|e| format_err!(e))?;
+            let etag = client
+                .upload_part(&key, &upload_id, part_number, body)
+                .await?;
+            Ok((part_number, etag))
+        }));
+        Ok(())
+    }
+
+    fn poll_parts_in_flight(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        while let Poll::Ready(Some(result)) = self.parts_in_flight.poll_next_unpin(cx) {
+            self.parts_done.push(flatten(result)?);
+        }
+        if self.parts_in_flight.len() < self.max_parts_in_flight {
+            Poll::Ready(Ok(()))
+        } else {
+            Poll::Pending
+        }
+    }
+
+    fn finish(&mut self) -> BoxFuture<'static, Result<()>> {
+        let client = self.client.clone();
+        let key = self.key.clone();
+        let checksum = hex::encode(std::mem::take(&mut self.hasher).finalize());
+        if self.upload_id.is_none() {
+            let body = Bytes::from(std::mem::take(&mut self.buf));
+            return async move {
+                client
+                    .put_object(&key, body, &[(S3::CHECKSUM_METADATA, &checksum)])
+                    .await
+            }
+            .boxed();
+        }
+
+        let last_part = if self.buf.is_empty() {
+            Ok(())
+        } else {
+            self.start_part()
+        };
+        let upload_id = self.upload_id();
+        let parts_in_flight = std::mem::take(&mut self.parts_in_flight);
+        let mut parts = std::mem::take(&mut self.parts_done);
+        async move {
+            let upload_id = upload_id.await.map_err(|e| format_err!(e))?;
+            let completed = async {
+                last_part?;
+                for part in parts_in_flight.collect::<Vec<_>>().await {
+                    parts.push(flatten(part)?);
+                }
+                parts.sort();
+                // The object points to its sidecar as soon as the upload completes, so the
+                // sidecar has to exist by then.
+                client
+                    .put_object(&S3::checksum_sidecar_key(&key), Bytes::from(checksum), &[])
+                    .await?;
+                client
+                    .complete_multipart_upload(&key, &upload_id, &parts)
+                    .await
+            }
+            .await;
+            if let Err(e) = completed {
+                // Only an upload that hasn't completed can be aborted. The sidecar stays, since the
+                // upload may have completed without us hearing back.
+                if let Err(e) = client.abort_multipart_upload(&key, &upload_id).await {
+                    warn!(key = key, error = %e, "Failed to abort multipart upload.");
+                }
+                return Err(e);
+            }
+            Ok(())
+        }
+        .boxed()
+    }
+}
+
+fn flatten<T>(result: Result<Result<T>, tokio::task::JoinError>) -> io::Result<T> {
+    result
+        .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?
+        .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+}
+
+impl AsyncWrite for S3Writer {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        data: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        // Bounds the memory held by parts being uploaded.
+        futures::ready!(this.poll_parts_in_flight(cx))?;
+
+        let len = std::cmp::min(data.len(), this.part_size - this.buf.len());
+        this.buf.extend_from_slice(&data[..len]);
+        this.hasher.update(&data[..len]);
+        if this.buf.len() == this.part_size {
+            this.start_part()?;
+        }
+        Poll::Ready(Ok(len))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        // Nothing is visible until the upload completes on shutdown.
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        if this.shutdown.is_none() {
+            this.shutdown = Some(this.finish());
+        }
+        this.shutdown
+            .as_mut()
+            .expect("Set above.")
+            .poll_unpin(cx)
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+    }
+}
+
+/// Fails the read at the end of the object if its length or SHA-256 isn't what's expected.
+struct ChecksumVerifyingReader {
+    inner: Pin<Box<dyn AsyncRead + Send>>,
+    key: String,
+    hasher: Sha256,
+    len: u64,
+    expected_len: Option<u64>,
+    expected_sha256: Option<String>,
+    verified: bool,
+}
+
+impl ChecksumVerifyingReader {
+    fn verify(&mut self) -> io::Result<()> {
+        if std::mem::replace(&mut self.verified, true) {
+            return Ok(());
+        }
+        let invalid_data = |msg: String| Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+        if let Some(expected_len) = self.expected_len {
+            if self.len != expected_len {
+                return invalid_data(format!(
+                    "{} truncated, got {} bytes of {}.",
+                    self.key, self.len, expected_len
+                ));
+            }
+        }
+        if let Some(expected_sha256) = &self.expected_sha256 {
+            let sha256 = hex::encode(std::mem::take(&mut self.hasher).finalize());
+            if &sha256 != expected_sha256 {
+                return invalid_data(format!(
+                    "{} corrupted, SHA-256 is {} instead of {}.",
+                    self.key, sha256, expected_sha256
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl AsyncRead for ChecksumVerifyingReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let filled_before = buf.filled().len();
+        futures::ready!(this.inner.as_mut().poll_read(cx, buf))?;
+        let read = &buf.filled()[filled_before..];
+        if read.is_empty() && buf.remaining() > 0 {
+            this.verify()?;
+        } else {
+            this.hasher.update(read);
+            this.len += read.len() as u64;
+        }
+        Poll::Ready(Ok(()))
+    }
+}
@@ -0,0 +1,161 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! AWS Signature Version 4 for S3 requests, see
+//! https://docs.aws.amazon.com/AmazonS3/latest/API/sig-v4-header-based-auth.html
+
+use anyhow::{format_err, Result};
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use ring::hmac;
+use sha2::{Digest, Sha256};
+use std::{collections::BTreeMap, fmt};
+
+/// Everything but the unreserved characters is encoded.
+const URI_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+const PATH_ENCODE_SET: &AsciiSet = &URI_ENCODE_SET.remove(b'/');
+
+pub const ALGORITHM: &str = "AWS4-HMAC-SHA256";
+
+#[derive(Clone)]
+pub struct Credentials {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    pub session_token: Option<String>,
+}
+
+impl Credentials {
+    /// Reads the credentials from the environment variables the AWS CLI uses, so they never show
+    /// up in process arguments.
+    pub fn from_env() -> Result<Self> {
+        let var = |name: &str| {
+            std::env::var(name).map_err(|_| format_err!("Environment variable {} not set.", name))
+        };
+        Ok(Self {
+            access_key_id: var("AWS_ACCESS_KEY_ID")?,
+            secret_access_key: var("AWS_SECRET_ACCESS_KEY")?,
+            session_token: var("AWS_SESSION_TOKEN").ok(),
+        })
+    }
+}
+
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Credentials")
+            .field("access_key_id", &self.access_key_id)
+            .finish_non_exhaustive()
+    }
+}
+
+pub fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+/// Encodes a query string key or value.
+pub fn uri_encode(s: &str) -> String {
+    utf8_percent_encode(s, URI_ENCODE_SET).to_string()
+}
+
+/// Encodes an object key into a URI path, keeping the slashes.
+pub fn uri_encode_path(s: &str) -> String {
+    utf8_percent_encode(s, PATH_ENCODE_SET).to_string()
+}
+
+/// Query string with the keys and values encoded, sorted as the signature expects.
+pub fn canonical_query_string(query: &[(String, String)]) -> String {
+    let mut encoded = query
+        .iter()
+        .map(|(k, v)| (uri_encode(k), uri_encode(v)))
+        .collect::<Vec<_>>();
+    encoded.sort();
+    encoded
+        .into_iter()
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// Signs a request.
+///
+/// `path` is the already encoded URI path, `headers` are all the headers to sign, keyed by lower
+/// case name. They must include `host`, `x-amz-date` and `x-amz-content-sha256`.
+pub fn signature(
+    credentials: &Credentials,
+    region: &str,
+    method: &str,
+    path: &str,
+    query: &[(String, String)],
+    headers: &BTreeMap<String, String>,
+) -> Result<String> {
+    let amz_date = headers
+        .get("x-amz-date")
+        .ok_or_else(|| format_err!("x-amz-date header missing."))?;
+    let payload_sha256 = headers
+        .get("x-amz-content-sha256")
+        .ok_or_else(|| format_err!("x-amz-content-sha256 header missing."))?;
+    let date = &amz_date[..8];
+
+    let canonical_headers = headers
+        .iter()
+        .map(|(k, v)| format!("{}:{}\n", k, v.trim()))
+        .collect::<String>();
+    let canonical_request = [
+        method,
+        path,
+        &canonical_query_string(query),
+        &canonical_headers,
+        &signed_headers(headers),
+        payload_sha256,
+    ]
+    .join("\n");
+
+    let scope = format!("{}/{}/s3/aws4_request", date, region);
+    let string_to_sign = [
+        ALGORITHM,
+        amz_date,
+        &scope,
+        &sha256_hex(canonical_request.as_bytes()),
+    ]
+    .join("\n");
+
+    let key = [date, region, "s3", "aws4_request"].iter().fold(
+        format!("AWS4{}", credentials.secret_access_key).into_bytes(),
+        |key, data| hmac_sha256(&key, data.as_bytes()),
+    );
+    Ok(hex::encode(hmac_sha256(&key, string_to_sign.as_bytes())))
+}
+
+/// Value of the Authorization header for a request, see `signature()`.
+pub fn authorization(
+    credentials: &Credentials,
+    region: &str,
+    method: &str,
+    path: &str,
+    query: &[(String, String)],
+    headers: &BTreeMap<String, String>,
+) -> Result<String> {
+    let signature = signature(credentials, region, method, path, query, headers)?;
+    let amz_date = &headers["x-amz-date"];
+    Ok(format!(
+        "{} Credential={}/{}/{}/s3/aws4_request,SignedHeaders={},Signature={}",
+        ALGORITHM,
+        credentials.access_key_id,
+        &amz_date[..8],
+        region,
+        signed_headers(headers),
+        signature,
+    ))
+}
+
+fn signed_headers(headers: &BTreeMap<String, String>) -> String {
+    headers.keys().cloned().collect::<Vec<_>>().join(";")
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    hmac::sign(&hmac::Key::new(hmac::HMAC_SHA256, key), data)
+        .as_ref()
+        .to_vec()
+}
@@ -108,6 +108,8 @@ impl From<RocksdbOpt> for RocksdbConfigs {
                 max_background_jobs: opt.max_background_jobs,
                 ..Default::default()
             },
+            // Restoring never moves data to the cold db.
+            cold_ledger_db_config: RocksdbConfigs::default().cold_ledger_db_config,
         }
     }
 }
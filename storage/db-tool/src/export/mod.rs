@@ -0,0 +1,272 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Exports ledger and state data of a version range to Parquet or Arrow IPC files for analytics.
+//!
+//! The range is cut into shards of `--versions-per-shard` versions, and each table of each shard
+//! goes to `<output-dir>/<table>/<first version>-<end version>.<ext>`. A file only appears under
+//! that name once it's completely written, so an interrupted export is resumed by running the
+//! same command again, which skips the files already there. The end version is kept in
+//! `<output-dir>/end_version`, so that a resumed export ends where the first run did rather than
+//! at the latest version at the time, which would cut the last shard differently.
+
+mod tables;
+
+use crate::export::tables::Table;
+use anyhow::{ensure, Context, Result};
+use aptos_config::config::{
+    RocksdbConfigs, StorageDirPaths, BUFFERED_STATE_TARGET_ITEMS,
+    DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD, NO_OP_STORAGE_PRUNER_CONFIG,
+};
+use aptos_db::AptosDB;
+use aptos_resource_viewer::AptosValueAnnotator;
+use aptos_storage_interface::{state_view::DbStateViewAtVersion, DbReader, MAX_REQUEST_LIMIT};
+use aptos_types::transaction::Version;
+use aptos_vm::data_cache::AsMoveResolver;
+use arrow::{datatypes::SchemaRef, ipc::writer::FileWriter, record_batch::RecordBatch};
+use clap::{Parser, ValueEnum};
+use parquet::{arrow::ArrowWriter, basic::Compression, file::properties::WriterProperties};
+use rayon::prelude::*;
+use std::{
+    cmp::min,
+    fs::{self, File},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+#[derive(Parser)]
+#[clap(
+    about = "Export transactions, events, write sets and state values of a version range \
+    to Parquet or Arrow IPC files."
+)]
+pub struct Command {
+    #[clap(long, value_parser)]
+    db_dir: PathBuf,
+
+    #[clap(long)]
+    enable_storage_sharding: bool,
+
+    #[clap(long, value_parser)]
+    output_dir: PathBuf,
+
+    #[clap(long, default_value_t = 0)]
+    start_version: Version,
+
+    /// Exclusive, defaults to the one a previous run into the same output dir used, or to the
+    /// version after the latest one in the DB.
+    #[clap(long)]
+    end_version: Option<Version>,
+
+    #[clap(long, value_enum, default_value_t = ExportFormat::Parquet)]
+    format: ExportFormat,
+
+    /// Tables to export, all if not specified.
+    #[clap(long, value_enum, value_delimiter = ',')]
+    tables: Vec<Table>,
+
+    #[clap(long, default_value_t = 1_000_000)]
+    versions_per_shard: u64,
+
+    /// Number of shards exported in parallel, defaults to the number of CPUs.
+    #[clap(long)]
+    concurrency: Option<usize>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+pub enum ExportFormat {
+    Parquet,
+    ArrowIpc,
+}
+
+impl ExportFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Parquet => "parquet",
+            ExportFormat::ArrowIpc => "arrow",
+        }
+    }
+}
+
+impl Command {
+    const END_VERSION_FILE: &'static str = "end_version";
+
+    pub fn run(self) -> Result<()> {
+        let db: Arc<dyn DbReader> = Arc::new(AptosDB::open(
+            StorageDirPaths::from_path(&self.db_dir),
+            /*readonly=*/ true,
+            NO_OP_STORAGE_PRUNER_CONFIG,
+            RocksdbConfigs {
+                enable_storage_sharding: self.enable_storage_sharding,
+                ..Default::default()
+            },
+            /*enable_indexer=*/ false,
+            BUFFERED_STATE_TARGET_ITEMS,
+            DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+        )?);
+        let end_version = self.resolve_end_version(&db)?;
+        ensure!(
+            self.start_version < end_version,
+            "Nothing to export between {} and {}.",
+            self.start_version,
+            end_version,
+        );
+        ensure!(
+            self.versions_per_shard > 0,
+            "--versions-per-shard must be positive."
+        );
+
+        let tables = if self.tables.is_empty() {
+            Table::ALL.to_vec()
+        } else {
+            self.tables.clone()
+        };
+        for table in &tables {
+            fs::create_dir_all(self.output_dir.join(table.name()))?;
+        }
+        let mut pending = vec![];
+        for shard_start in
+            (self.start_version..end_version).step_by(self.versions_per_shard as usize)
+        {
+            let shard_end = min(shard_start + self.versions_per_shard, end_version);
+            for table in &tables {
+                if !self.output_path(*table, shard_start, shard_end).exists() {
+                    pending.push((*table, shard_start, shard_end));
+                }
+            }
+        }
+        println!(
+            "Exporting {} files for versions [{}, {}), skipping the ones already exported.",
+            pending.len(),
+            self.start_version,
+            end_version,
+        );
+
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(self.concurrency.unwrap_or_else(num_cpus::get))
+            .thread_name(|index| format!("db_export_{}", index))
+            .build()?;
+        let num_exported = AtomicUsize::new(0);
+        pool.install(|| {
+            pending
+                .par_iter()
+                .try_for_each(|(table, shard_start, shard_end)| {
+                    self.export_shard(&db, *table, *shard_start, *shard_end)?;
+                    println!(
+                        "Exported {} of versions [{}, {}), {}/{} done.",
+                        table,
+                        shard_start,
+                        shard_end,
+                        num_exported.fetch_add(1, Ordering::Relaxed) + 1,
+                        pending.len(),
+                    );
+                    Ok(())
+                })
+        })
+    }
+
+    /// Records the end version in the output dir when it's new, so a resumed run reuses it.
+    fn resolve_end_version(&self, db: &Arc<dyn DbReader>) -> Result<Version> {
+        let path = self.output_dir.join(Self::END_VERSION_FILE);
+        let recorded = if path.exists() {
+            let content = fs::read_to_string(&path)?;
+            Some(
+                content
+                    .trim()
+                    .parse::<Version>()
+                    .with_context(|| format!("Invalid end version in {}.", path.display()))?,
+            )
+        } else {
+            None
+        };
+        let end_version = match (self.end_version, recorded) {
+            (Some(end_version), _) => end_version,
+            (None, Some(recorded)) => recorded,
+            (None, None) => db.get_latest_version()? + 1,
+        };
+        if recorded != Some(end_version) {
+            fs::create_dir_all(&self.output_dir)?;
+            fs::write(&path, end_version.to_string())?;
+        }
+        Ok(end_version)
+    }
+
+    fn output_path(&self, table: Table, shard_start: Version, shard_end: Version) -> PathBuf {
+        self.output_dir.join(table.name()).join(format!(
+            "{:020}-{:020}.{}",
+            shard_start,
+            shard_end,
+            self.format.extension(),
+        ))
+    }
+
+    fn export_shard(
+        &self,
+        db: &Arc<dyn DbReader>,
+        table: Table,
+        shard_start: Version,
+        shard_end: Version,
+    ) -> Result<()> {
+        // Modules can only be upgraded compatibly, so the ones at the end of the shard can decode
+        // every value written in it.
+        let state_view = db.state_view_at_version(Some(shard_end - 1))?;
+        let resolver = state_view.as_move_resolver();
+        let annotator = AptosValueAnnotator::new(&resolver);
+
+        let path = self.output_path(table, shard_start, shard_end);
+        let tmp_path = path.with_extension("tmp");
+        let mut writer = BatchWriter::new(self.format, &tmp_path, table.schema())?;
+        for chunk_start in (shard_start..shard_end).step_by(MAX_REQUEST_LIMIT as usize) {
+            let num_versions = min(MAX_REQUEST_LIMIT, shard_end - chunk_start);
+            writer.write(&table.record_batch(
+                db.as_ref(),
+                &annotator,
+                chunk_start,
+                num_versions,
+            )?)?;
+        }
+        writer.finish()?;
+        fs::rename(tmp_path, path)?;
+        Ok(())
+    }
+}
+
+enum BatchWriter {
+    Parquet(ArrowWriter<File>),
+    ArrowIpc(FileWriter<File>),
+}
+
+impl BatchWriter {
+    fn new(format: ExportFormat, path: &Path, schema: SchemaRef) -> Result<Self> {
+        let file = File::create(path)?;
+        Ok(match format {
+            ExportFormat::Parquet => {
+                let props = WriterProperties::builder()
+                    .set_compression(Compression::SNAPPY)
+                    .build();
+                Self::Parquet(ArrowWriter::try_new(file, schema, Some(props))?)
+            },
+            ExportFormat::ArrowIpc => Self::ArrowIpc(FileWriter::try_new(file, &schema)?),
+        })
+    }
+
+    fn write(&mut self, batch: &RecordBatch) -> Result<()> {
+        match self {
+            Self::Parquet(writer) => writer.write(batch)?,
+            Self::ArrowIpc(writer) => writer.write(batch)?,
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Result<()> {
+        match self {
+            Self::Parquet(writer) => {
+                writer.close()?;
+            },
+            Self::ArrowIpc(mut writer) => writer.finish()?,
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,406 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! The exported tables, their schemas and how their rows are read from the DB.
+//!
+//! All tables are keyed by `version`. State keys are spread over the same columns wherever they
+//! appear, see [`StateKeyColumns`]. JSON columns hold Move values decoded by the resource viewer
+//! and are null where the value can't be decoded, e.g. for table items, whose types aren't known
+//! from the key.
+
+use anyhow::{format_err, Result};
+use aptos_crypto::hash::CryptoHash;
+use aptos_resource_viewer::AptosValueAnnotator;
+use aptos_storage_interface::DbReader;
+use aptos_types::{
+    access_path::Path,
+    contract_event::ContractEvent,
+    state_store::state_key::{StateKey, StateKeyInner},
+    transaction::{Transaction, Version},
+    write_set::{TransactionWrite, WriteOp},
+};
+use arrow::{
+    array::{ArrayRef, BinaryBuilder, BooleanBuilder, StringBuilder, UInt64Builder},
+    datatypes::{DataType, Field, Schema, SchemaRef},
+    record_batch::RecordBatch,
+};
+use clap::ValueEnum;
+use move_core_types::{language_storage::StructTag, resolver::MoveResolver};
+use std::{collections::BTreeMap, fmt, sync::Arc};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+pub enum Table {
+    Transactions,
+    Events,
+    WriteSets,
+    StateValues,
+}
+
+impl Table {
+    pub const ALL: [Table; 4] = [
+        Table::Transactions,
+        Table::Events,
+        Table::WriteSets,
+        Table::StateValues,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Table::Transactions => "transactions",
+            Table::Events => "events",
+            Table::WriteSets => "write_sets",
+            Table::StateValues => "state_values",
+        }
+    }
+
+    pub fn schema(&self) -> SchemaRef {
+        let fields = match self {
+            Table::Transactions => vec![
+                Field::new("version", DataType::UInt64, false),
+                Field::new("hash", DataType::Utf8, false),
+                Field::new("type", DataType::Utf8, false),
+                Field::new("sender", DataType::Utf8, true),
+                Field::new("sequence_number", DataType::UInt64, true),
+                Field::new("success", DataType::Boolean, false),
+                Field::new("vm_status", DataType::Utf8, false),
+                Field::new("gas_used", DataType::UInt64, false),
+                Field::new("state_change_hash", DataType::Utf8, false),
+                Field::new("event_root_hash", DataType::Utf8, false),
+                Field::new("bcs", DataType::Binary, false),
+            ],
+            Table::Events => vec![
+                Field::new("version", DataType::UInt64, false),
+                Field::new("event_index", DataType::UInt64, false),
+                Field::new("type_tag", DataType::Utf8, false),
+                Field::new("creator_address", DataType::Utf8, true),
+                Field::new("creation_number", DataType::UInt64, true),
+                Field::new("sequence_number", DataType::UInt64, true),
+                Field::new("data", DataType::Binary, false),
+                Field::new("data_json", DataType::Utf8, true),
+            ],
+            Table::WriteSets => [
+                vec![
+                    Field::new("version", DataType::UInt64, false),
+                    Field::new("write_index", DataType::UInt64, false),
+                ],
+                StateKeyColumns::fields(),
+                vec![
+                    Field::new("op", DataType::Utf8, false),
+                    Field::new("value_size", DataType::UInt64, true),
+                ],
+            ]
+            .concat(),
+            Table::StateValues => [
+                vec![Field::new("version", DataType::UInt64, false)],
+                StateKeyColumns::fields(),
+                vec![
+                    Field::new("value", DataType::Binary, false),
+                    Field::new("value_json", DataType::Utf8, true),
+                ],
+            ]
+            .concat(),
+        };
+        Arc::new(Schema::new(fields))
+    }
+
+    /// Reads the rows of `num_versions` versions from `start_version` on. `num_versions` must
+    /// not exceed `MAX_REQUEST_LIMIT`.
+    pub fn record_batch<R: MoveResolver>(
+        &self,
+        db: &dyn DbReader,
+        annotator: &AptosValueAnnotator<R>,
+        start_version: Version,
+        num_versions: u64,
+    ) -> Result<RecordBatch> {
+        let columns = match self {
+            Table::Transactions => transactions(db, start_version, num_versions)?,
+            Table::Events => events(db, annotator, start_version, num_versions)?,
+            Table::WriteSets => write_sets(db, start_version, num_versions)?,
+            Table::StateValues => state_values(db, annotator, start_version, num_versions)?,
+        };
+        Ok(RecordBatch::try_new(self.schema(), columns)?)
+    }
+}
+
+impl fmt::Display for Table {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+fn transactions(
+    db: &dyn DbReader,
+    start_version: Version,
+    num_versions: u64,
+) -> Result<Vec<ArrayRef>> {
+    let mut version = UInt64Builder::new();
+    let mut hash = StringBuilder::new();
+    let mut txn_type = StringBuilder::new();
+    let mut sender = StringBuilder::new();
+    let mut sequence_number = UInt64Builder::new();
+    let mut success = BooleanBuilder::new();
+    let mut vm_status = StringBuilder::new();
+    let mut gas_used = UInt64Builder::new();
+    let mut state_change_hash = StringBuilder::new();
+    let mut event_root_hash = StringBuilder::new();
+    let mut bcs_bytes = BinaryBuilder::new();
+
+    let txns = db.get_transaction_iterator(start_version, num_versions)?;
+    let txn_infos = db.get_transaction_info_iterator(start_version, num_versions)?;
+    for (v, (txn, txn_info)) in (start_version..).zip(txns.zip(txn_infos)) {
+        let (txn, txn_info) = (txn?, txn_info?);
+        version.append_value(v);
+        hash.append_value(txn_info.transaction_hash().to_hex_literal());
+        txn_type.append_value(transaction_type(&txn));
+        let user_txn = txn.try_as_signed_user_txn();
+        sender.append_option(user_txn.map(|t| t.sender().to_hex_literal()));
+        sequence_number.append_option(user_txn.map(|t| t.sequence_number()));
+        success.append_value(txn_info.status().is_success());
+        vm_status.append_value(format!("{:?}", txn_info.status()));
+        gas_used.append_value(txn_info.gas_used());
+        state_change_hash.append_value(txn_info.state_change_hash().to_hex_literal());
+        event_root_hash.append_value(txn_info.event_root_hash().to_hex_literal());
+        bcs_bytes.append_value(bcs::to_bytes(&txn)?);
+    }
+
+    Ok(vec![
+        Arc::new(version.finish()),
+        Arc::new(hash.finish()),
+        Arc::new(txn_type.finish()),
+        Arc::new(sender.finish()),
+        Arc::new(sequence_number.finish()),
+        Arc::new(success.finish()),
+        Arc::new(vm_status.finish()),
+        Arc::new(gas_used.finish()),
+        Arc::new(state_change_hash.finish()),
+        Arc::new(event_root_hash.finish()),
+        Arc::new(bcs_bytes.finish()),
+    ])
+}
+
+fn transaction_type(txn: &Transaction) -> &'static str {
+    match txn {
+        Transaction::UserTransaction(_) => "user_transaction",
+        Transaction::GenesisTransaction(_) => "genesis_transaction",
+        Transaction::BlockMetadata(_) => "block_metadata",
+        Transaction::StateCheckpoint(_) => "state_checkpoint",
+        Transaction::SystemTransaction(_) => "system_transaction",
+    }
+}
+
+fn events<R: MoveResolver>(
+    db: &dyn DbReader,
+    annotator: &AptosValueAnnotator<R>,
+    start_version: Version,
+    num_versions: u64,
+) -> Result<Vec<ArrayRef>> {
+    let mut version = UInt64Builder::new();
+    let mut event_index = UInt64Builder::new();
+    let mut type_tag = StringBuilder::new();
+    let mut creator_address = StringBuilder::new();
+    let mut creation_number = UInt64Builder::new();
+    let mut sequence_number = UInt64Builder::new();
+    let mut data = BinaryBuilder::new();
+    let mut data_json = StringBuilder::new();
+
+    let events_iter = db.get_events_iterator(start_version, num_versions)?;
+    for (v, events) in (start_version..).zip(events_iter) {
+        for (index, event) in events?.iter().enumerate() {
+            version.append_value(v);
+            event_index.append_value(index as u64);
+            type_tag.append_value(event.type_tag().to_string());
+            let event_v1 = match event {
+                ContractEvent::V1(event_v1) => Some(event_v1),
+                ContractEvent::V2(_) => None,
+            };
+            creator_address
+                .append_option(event_v1.map(|e| e.key().get_creator_address().to_hex_literal()));
+            creation_number.append_option(event_v1.map(|e| e.key().get_creation_number()));
+            sequence_number.append_option(event_v1.map(|e| e.sequence_number()));
+            data.append_value(event.event_data());
+            data_json.append_option(
+                annotator
+                    .view_contract_event(event)
+                    .ok()
+                    .and_then(|value| serde_json::to_string(&value).ok()),
+            );
+        }
+    }
+
+    Ok(vec![
+        Arc::new(version.finish()),
+        Arc::new(event_index.finish()),
+        Arc::new(type_tag.finish()),
+        Arc::new(creator_address.finish()),
+        Arc::new(creation_number.finish()),
+        Arc::new(sequence_number.finish()),
+        Arc::new(data.finish()),
+        Arc::new(data_json.finish()),
+    ])
+}
+
+fn write_sets(
+    db: &dyn DbReader,
+    start_version: Version,
+    num_versions: u64,
+) -> Result<Vec<ArrayRef>> {
+    let mut version = UInt64Builder::new();
+    let mut write_index = UInt64Builder::new();
+    let mut state_key = StateKeyColumns::default();
+    let mut op = StringBuilder::new();
+    let mut value_size = UInt64Builder::new();
+
+    let write_sets = db.get_write_set_iterator(start_version, num_versions)?;
+    for (v, write_set) in (start_version..).zip(write_sets) {
+        for (index, (key, write_op)) in write_set?.iter().enumerate() {
+            version.append_value(v);
+            write_index.append_value(index as u64);
+            state_key.append(key);
+            op.append_value(match write_op {
+                WriteOp::Creation(_) | WriteOp::CreationWithMetadata { .. } => "creation",
+                WriteOp::Modification(_) | WriteOp::ModificationWithMetadata { .. } => {
+                    "modification"
+                },
+                WriteOp::Deletion | WriteOp::DeletionWithMetadata { .. } => "deletion",
+            });
+            value_size.append_option(write_op.bytes().map(|bytes| bytes.len() as u64));
+        }
+    }
+
+    let mut columns: Vec<ArrayRef> =
+        vec![Arc::new(version.finish()), Arc::new(write_index.finish())];
+    columns.extend(state_key.finish());
+    columns.push(Arc::new(op.finish()));
+    columns.push(Arc::new(value_size.finish()));
+    Ok(columns)
+}
+
+/// The values written at each version, read from the state KV db for the keys in the write sets.
+fn state_values<R: MoveResolver>(
+    db: &dyn DbReader,
+    annotator: &AptosValueAnnotator<R>,
+    start_version: Version,
+    num_versions: u64,
+) -> Result<Vec<ArrayRef>> {
+    let mut version = UInt64Builder::new();
+    let mut state_key = StateKeyColumns::default();
+    let mut value = BinaryBuilder::new();
+    let mut value_json = StringBuilder::new();
+
+    let write_sets = db.get_write_set_iterator(start_version, num_versions)?;
+    for (v, write_set) in (start_version..).zip(write_sets) {
+        for (key, write_op) in write_set?.iter() {
+            if write_op.is_deletion() {
+                continue;
+            }
+            let state_value = db.get_state_value_by_version(key, v)?.ok_or_else(|| {
+                format_err!("State value of {:?} written at {} is missing.", key, v)
+            })?;
+            version.append_value(v);
+            state_key.append(key);
+            value.append_value(state_value.bytes());
+            value_json.append_option(decode_state_value(annotator, key, state_value.bytes()));
+        }
+    }
+
+    let mut columns: Vec<ArrayRef> = vec![Arc::new(version.finish())];
+    columns.extend(state_key.finish());
+    columns.push(Arc::new(value.finish()));
+    columns.push(Arc::new(value_json.finish()));
+    Ok(columns)
+}
+
+fn decode_state_value<R: MoveResolver>(
+    annotator: &AptosValueAnnotator<R>,
+    key: &StateKey,
+    bytes: &[u8],
+) -> Option<String> {
+    let access_path = match key.inner() {
+        StateKeyInner::AccessPath(access_path) => access_path,
+        StateKeyInner::TableItem { .. } | StateKeyInner::Raw(_) => return None,
+    };
+    match access_path.get_path() {
+        Path::Resource(tag) => {
+            serde_json::to_string(&annotator.view_resource(&tag, bytes).ok()?).ok()
+        },
+        Path::ResourceGroup(_) => {
+            let group: BTreeMap<StructTag, Vec<u8>> = bcs::from_bytes(bytes).ok()?;
+            let members = group
+                .iter()
+                .map(|(tag, blob)| Ok((tag.to_string(), annotator.view_resource(tag, blob)?)))
+                .collect::<Result<BTreeMap<_, _>>>()
+                .ok()?;
+            serde_json::to_string(&members).ok()
+        },
+        Path::Code(_) => None,
+    }
+}
+
+/// A state key spread over columns: its hash, its kind, and depending on the kind, the account
+/// address and resource type or module of an access path, or the handle and key of a table item.
+#[derive(Default)]
+struct StateKeyColumns {
+    hash: StringBuilder,
+    kind: StringBuilder,
+    address: StringBuilder,
+    path: StringBuilder,
+    table_handle: StringBuilder,
+    table_key: BinaryBuilder,
+}
+
+impl StateKeyColumns {
+    fn fields() -> Vec<Field> {
+        vec![
+            Field::new("state_key_hash", DataType::Utf8, false),
+            Field::new("state_key_type", DataType::Utf8, false),
+            Field::new("address", DataType::Utf8, true),
+            Field::new("path", DataType::Utf8, true),
+            Field::new("table_handle", DataType::Utf8, true),
+            Field::new("table_key", DataType::Binary, true),
+        ]
+    }
+
+    fn append(&mut self, key: &StateKey) {
+        self.hash.append_value(key.hash().to_hex_literal());
+        match key.inner() {
+            StateKeyInner::AccessPath(access_path) => {
+                let (kind, path) = match access_path.get_path() {
+                    Path::Code(module_id) => ("module", module_id.to_string()),
+                    Path::Resource(tag) => ("resource", tag.to_string()),
+                    Path::ResourceGroup(tag) => ("resource_group", tag.to_string()),
+                };
+                self.kind.append_value(kind);
+                self.address
+                    .append_value(access_path.address.to_hex_literal());
+                self.path.append_value(path);
+                self.table_handle.append_null();
+                self.table_key.append_null();
+            },
+            StateKeyInner::TableItem { handle, key } => {
+                self.kind.append_value("table_item");
+                self.address.append_null();
+                self.path.append_null();
+                self.table_handle.append_value(handle.0.to_hex_literal());
+                self.table_key.append_value(key);
+            },
+            StateKeyInner::Raw(_) => {
+                self.kind.append_value("raw");
+                self.address.append_null();
+                self.path.append_null();
+                self.table_handle.append_null();
+                self.table_key.append_null();
+            },
+        }
+    }
+
+    fn finish(mut self) -> Vec<ArrayRef> {
+        vec![
+            Arc::new(self.hash.finish()),
+            Arc::new(self.kind.finish()),
+            Arc::new(self.address.finish()),
+            Arc::new(self.path.finish()),
+            Arc::new(self.table_handle.finish()),
+            Arc::new(self.table_key.finish()),
+        ]
+    }
+}
@@ -6,6 +6,7 @@ extern crate core;
 mod backup;
 mod backup_maintenance;
 mod bootstrap;
+mod export;
 mod replay_verify;
 pub mod restore;
 #[cfg(test)]
@@ -30,6 +31,8 @@ pub enum DBTool {
     #[clap(subcommand)]
     Debug(db_debugger::Cmd),
 
+    Export(export::Command),
+
     ReplayVerify(replay_verify::Opt),
 
     #[clap(subcommand)]
@@ -43,6 +46,7 @@ impl DBTool {
             DBTool::BackupMaintenance(cmd) => cmd.run().await,
             DBTool::Bootstrap(cmd) => cmd.run(),
             DBTool::Debug(cmd) => cmd.run(),
+            DBTool::Export(cmd) => cmd.run(),
             DBTool::ReplayVerify(cmd) => cmd.run().await,
             DBTool::Restore(cmd) => cmd.run().await,
         }
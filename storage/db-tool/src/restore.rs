@@ -5,6 +5,9 @@ use anyhow::Result;
 use aptos_backup_cli::{
     backup_types::{
         epoch_ending::restore::{EpochEndingRestoreController, EpochEndingRestoreOpt},
+        incremental_state_snapshot::restore::{
+            IncrementalStateSnapshotRestoreController, IncrementalStateSnapshotRestoreOpt,
+        },
         state_snapshot::restore::{StateSnapshotRestoreController, StateSnapshotRestoreOpt},
         transaction::restore::{TransactionRestoreController, TransactionRestoreOpt},
     },
@@ -52,6 +55,14 @@ pub enum Oneoff {
         #[clap(flatten)]
         global: GlobalRestoreOpt,
     },
+    IncrementalStateSnapshot {
+        #[clap(flatten)]
+        storage: DBToolStorageOpt,
+        #[clap(flatten)]
+        opt: IncrementalStateSnapshotRestoreOpt,
+        #[clap(flatten)]
+        global: GlobalRestoreOpt,
+    },
     Transaction {
         #[clap(flatten)]
         storage: DBToolStorageOpt,
@@ -94,6 +105,20 @@ impl Command {
                         .run()
                         .await?;
                     },
+                    Oneoff::IncrementalStateSnapshot {
+                        storage,
+                        opt,
+                        global,
+                    } => {
+                        IncrementalStateSnapshotRestoreController::new(
+                            opt,
+                            global.try_into()?,
+                            storage.init_storage().await?,
+                            None, /* epoch_history */
+                        )
+                        .run()
+                        .await?;
+                    },
                     Oneoff::Transaction {
                         storage,
                         opt,
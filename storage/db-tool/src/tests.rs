@@ -45,6 +45,41 @@ fn test_various_cmd_parsing() {
         "--db-dir",
         ".",
     ]);
+    run_cmd(&[
+        "aptos-db-tool",
+        "debug",
+        "verify-all",
+        "--db-dir",
+        ".",
+        "--progress-file",
+        "progress.jsonl",
+        "--report",
+        "report.json",
+    ]);
+    run_cmd(&[
+        "aptos-db-tool",
+        "debug",
+        "snapshot-bundle",
+        "verify",
+        "--bundle-dir",
+        ".",
+        "--waypoint",
+        "0:0000000000000000000000000000000000000000000000000000000000000000",
+        "--skip-full-verification",
+    ]);
+
+    run_cmd(&[
+        "aptos-db-tool",
+        "export",
+        "--db-dir",
+        ".",
+        "--output-dir",
+        ".",
+        "--format",
+        "arrow-ipc",
+        "--tables",
+        "events,state-values",
+    ]);
 
     run_cmd(&["aptos-db-tool", "backup", "verify", "--local-fs-dir", "."]);
     run_cmd(&[
@@ -623,6 +658,118 @@ mod dbtool_tests {
         rt.shutdown_timeout(Duration::from_secs(1));
     }
 
+    #[test]
+    fn test_export_db() {
+        use arrow::ipc::reader::FileReader;
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+        let db_dir = TempPath::new();
+        let export_dir = TempPath::new();
+        export_dir.create_as_dir().unwrap();
+        let db = test_execution_with_storage_impl_inner(false, db_dir.path());
+        let num_versions = db.get_latest_version().unwrap() + 1;
+
+        let export_to = |export_dir: &TempPath, format: &str| {
+            Runtime::new()
+                .unwrap()
+                .block_on(
+                    DBTool::try_parse_from([
+                        "aptos-db-tool",
+                        "export",
+                        "--db-dir",
+                        db_dir.path().to_str().unwrap(),
+                        "--output-dir",
+                        export_dir.path().to_str().unwrap(),
+                        "--format",
+                        format,
+                        "--versions-per-shard",
+                        "10",
+                    ])
+                    .unwrap()
+                    .run(),
+                )
+                .unwrap()
+        };
+        let export = |format: &str| export_to(&export_dir, format);
+        let files = |table: &str| {
+            let mut files: Vec<_> = fs::read_dir(export_dir.path().join(table))
+                .unwrap()
+                .map(|entry| entry.unwrap().path())
+                .collect();
+            files.sort();
+            files
+        };
+
+        export("parquet");
+        let transaction_files = files("transactions");
+        assert_eq!(transaction_files.len() as u64, (num_versions + 9) / 10);
+        let num_rows: usize = transaction_files
+            .iter()
+            .map(|path| {
+                ParquetRecordBatchReaderBuilder::try_new(fs::File::open(path).unwrap())
+                    .unwrap()
+                    .build()
+                    .unwrap()
+                    .map(|batch| batch.unwrap().num_rows())
+                    .sum::<usize>()
+            })
+            .sum();
+        assert_eq!(num_rows as u64, num_versions);
+        for table in ["events", "write_sets", "state_values"] {
+            assert_eq!(files(table).len(), transaction_files.len());
+        }
+
+        // Only what's missing is exported again.
+        fs::remove_file(&transaction_files[1]).unwrap();
+        let modified = fs::metadata(&transaction_files[0])
+            .unwrap()
+            .modified()
+            .unwrap();
+        export("parquet");
+        assert_eq!(files("transactions"), transaction_files);
+        assert_eq!(
+            fs::metadata(&transaction_files[0])
+                .unwrap()
+                .modified()
+                .unwrap(),
+            modified
+        );
+
+        // Arrow IPC files sit next to the Parquet ones.
+        export("arrow-ipc");
+        let ipc_files: Vec<_> = files("state_values")
+            .into_iter()
+            .filter(|path| path.extension().unwrap() == "arrow")
+            .collect();
+        assert_eq!(ipc_files.len(), transaction_files.len());
+        let reader = FileReader::try_new(fs::File::open(&ipc_files[0]).unwrap(), None).unwrap();
+        assert_eq!(reader.schema().field(0).name(), "version");
+
+        // A resumed export ends where the first run did, even if the DB moved on since.
+        assert_eq!(
+            fs::read_to_string(export_dir.path().join("end_version")).unwrap(),
+            num_versions.to_string()
+        );
+        let resumed_dir = TempPath::new();
+        resumed_dir.create_as_dir().unwrap();
+        let end_version = num_versions - 5;
+        fs::write(
+            resumed_dir.path().join("end_version"),
+            end_version.to_string(),
+        )
+        .unwrap();
+        export_to(&resumed_dir, "parquet");
+        let last_file = fs::read_dir(resumed_dir.path().join("transactions"))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .max()
+            .unwrap();
+        assert!(last_file
+            .to_str()
+            .unwrap()
+            .ends_with(&format!("-{:020}.parquet", end_version)));
+    }
+
     fn dir_size<P: AsRef<Path>>(path: P) -> u64 {
         let mut size = 0;
 
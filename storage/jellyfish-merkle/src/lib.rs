@@ -89,7 +89,9 @@ use aptos_crypto::{hash::CryptoHash, HashValue};
 use aptos_experimental_runtimes::thread_manager::THREAD_MANAGER;
 use aptos_types::{
     nibble::{nibble_path::NibblePath, Nibble, ROOT_NIBBLE_HEIGHT},
-    proof::{SparseMerkleProof, SparseMerkleProofExt, SparseMerkleRangeProof},
+    proof::{
+        SparseMerkleMultiProof, SparseMerkleProof, SparseMerkleProofExt, SparseMerkleRangeProof,
+    },
     state_store::{state_key::StateKey, state_value::StateValue},
     transaction::Version,
 };
@@ -786,6 +788,26 @@ where
         bail!("Jellyfish Merkle tree has cyclic graph inside.");
     }
 
+    /// Returns the values (if applicable) of `keys`, which must be strictly ascending, and a single
+    /// proof for all of them.
+    pub fn get_with_multi_proof(
+        &self,
+        keys: &[HashValue],
+        version: Version,
+    ) -> Result<(Vec<Option<(HashValue, (K, Version))>>, SparseMerkleMultiProof)> {
+        ensure!(
+            keys.windows(2).all(|pair| pair[0] < pair[1]),
+            "Keys must be strictly ascending."
+        );
+        let (values, proofs): (Vec<_>, Vec<_>) = keys
+            .iter()
+            .map(|key| self.get_with_proof(*key, version))
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .unzip();
+        Ok((values, SparseMerkleMultiProof::from_proofs(keys, &proofs)?))
+    }
+
     /// Gets the proof that shows a list of keys up to `rightmost_key_to_prove` exist at `version`.
     pub fn get_range_proof(
         &self,
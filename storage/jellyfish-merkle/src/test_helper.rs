@@ -20,7 +20,7 @@ use proptest::{
 use proptest_derive::Arbitrary;
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::{BTreeMap, HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     ops::Bound,
 };
 
@@ -137,6 +137,44 @@ pub fn test_get_with_proof<V: TestKey>(
     test_nonexistent_keys_impl(&tree, version, &nonexistent_keys);
 }
 
+pub fn test_get_with_multi_proof<V: TestKey>(
+    (existent_kvs, nonexistent_keys): (HashMap<HashValue, (HashValue, V)>, Vec<HashValue>),
+) {
+    let (db, version) = init_mock_db(&existent_kvs);
+    let tree = JellyfishMerkleTree::new(&db);
+    let root_hash = tree.get_root_hash(version).unwrap();
+
+    let keys: Vec<_> = existent_kvs
+        .keys()
+        .chain(nonexistent_keys.iter())
+        .copied()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    let (values, proof) = tree.get_with_multi_proof(&keys, version).unwrap();
+    let value_hashes: Vec<_> = values.iter().map(|v| v.as_ref().map(|v| v.0)).collect();
+    assert!(proof
+        .verify_by_hash(root_hash, &keys, &value_hashes)
+        .is_ok());
+
+    let mut num_single_proof_siblings = 0;
+    for (key, value) in keys.iter().zip(values) {
+        match existent_kvs.get(key) {
+            Some(expected) => {
+                assert_eq!((value.as_ref().unwrap().0, value.unwrap().1 .0), *expected)
+            },
+            None => assert!(value.is_none()),
+        }
+        num_single_proof_siblings += tree
+            .get_with_proof(*key, version)
+            .unwrap()
+            .1
+            .siblings()
+            .len();
+    }
+    assert!(proof.siblings().len() <= num_single_proof_siblings);
+}
+
 pub fn arb_kv_pair_with_distinct_last_nibble<V: TestKey>(
 ) -> impl Strategy<Value = ((HashValue, (HashValue, V)), (HashValue, (HashValue, V)))> {
     (
@@ -187,12 +187,23 @@ impl CachedStateView {
     pub fn prime_cache_by_write_set<'a, T: IntoIterator<Item = &'a WriteSet> + Send>(
         &self,
         write_sets: T,
+    ) -> Result<()> {
+        let keys = write_sets
+            .into_iter()
+            .flat_map(|write_set| write_set.iter())
+            .map(|(key, _)| key)
+            .collect::<Vec<_>>();
+        self.prime_cache_by_keys(keys)
+    }
+
+    /// Fetches the values of the given keys into the cache in parallel, e.g. before executing
+    /// transactions that are expected to read them.
+    pub fn prime_cache_by_keys<'a, T: IntoIterator<Item = &'a StateKey> + Send>(
+        &self,
+        keys: T,
     ) -> Result<()> {
         IO_POOL.scope(|s| {
-            write_sets
-                .into_iter()
-                .flat_map(|write_set| write_set.iter())
-                .map(|(key, _)| key)
+            keys.into_iter()
                 .collect::<HashSet<_>>()
                 .into_iter()
                 .for_each(|key| {
@@ -17,8 +17,9 @@ use aptos_types::{
     move_resource::MoveStorage,
     on_chain_config::{access_path_for_config, ConfigID},
     proof::{
-        AccumulatorConsistencyProof, SparseMerkleProof, SparseMerkleProofExt,
-        SparseMerkleRangeProof, TransactionAccumulatorRangeProof, TransactionAccumulatorSummary,
+        AccumulatorConsistencyProof, SparseMerkleMultiProof, SparseMerkleProof,
+        SparseMerkleProofExt, SparseMerkleRangeProof, TransactionAccumulatorRangeProof,
+        TransactionAccumulatorSummary,
     },
     state_proof::StateProof,
     state_store::{
@@ -45,10 +46,11 @@ mod executed_trees;
 mod metrics;
 #[cfg(any(test, feature = "fuzzing"))]
 pub mod mock;
+pub mod pruner_status;
 pub mod state_delta;
 pub mod state_view;
 
-use crate::state_delta::StateDelta;
+use crate::{pruner_status::PrunerStatus, state_delta::StateDelta};
 use aptos_scratchpad::SparseMerkleTree;
 pub use executed_trees::ExecutedTrees;
 
@@ -371,6 +373,16 @@ pub trait DbReader: Send + Sync {
             version: Version,
         ) -> Result<(Option<StateValue>, SparseMerkleProofExt)>;
 
+        /// Gets the state values of multiple state keys along with a single proof for all of them,
+        /// out of the ledger state indicated by the state Merkle tree root at `version`. Values are
+        /// returned in the order of `state_keys`, while the proof is for the distinct hashes of
+        /// the keys in ascending order, see [SparseMerkleMultiProof::verify].
+        fn get_state_proof_with_multiple_keys(
+            &self,
+            state_keys: &[StateKey],
+            version: Version,
+        ) -> Result<(Vec<Option<StateValue>>, SparseMerkleMultiProof)>;
+
         /// Gets the latest ExecutedTrees no matter if db has been bootstrapped.
         /// Used by the Db-bootstrapper.
         fn get_latest_executed_trees(&self) -> Result<ExecutedTrees>;
@@ -440,6 +452,9 @@ pub trait DbReader: Send + Sync {
         /// Get the ledger prune window config value.
         fn get_ledger_prune_window(&self) -> Result<usize>;
 
+        /// Returns how far each of the pruners has progressed.
+        fn get_pruner_status(&self) -> Result<Vec<PrunerStatus>>;
+
         /// Get table info from the internal indexer.
         fn get_table_info(&self, handle: TableHandle) -> Result<TableInfo>;
 
@@ -558,6 +573,12 @@ pub trait DbWriter: Send + Sync {
     ) -> Result<()> {
         unimplemented!()
     }
+
+    /// Changes the prune window of the named pruner at runtime, effective from the next commit.
+    /// See [`pruner_status::PrunerStatus::name`] for the names.
+    fn set_prune_window(&self, pruner_name: &str, prune_window: Version) -> Result<()> {
+        unimplemented!()
+    }
 }
 
 #[derive(Clone)]
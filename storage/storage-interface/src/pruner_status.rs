@@ -0,0 +1,33 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_types::transaction::Version;
+use serde::{Deserialize, Serialize};
+
+/// Where a background pruner is at, as reported by [`crate::DbReader::get_pruner_status`].
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PrunerStatus {
+    /// One of "ledger_pruner", "state_kv_pruner", "state_merkle_pruner" and
+    /// "epoch_snapshot_pruner", also what `DbWriter::set_prune_window` takes.
+    pub name: String,
+    pub enabled: bool,
+    pub prune_window: Version,
+    /// Data below this version is considered pruned, whether or not it's deleted yet.
+    pub min_readable_version: Version,
+    /// Data below this version has been deleted from the DB.
+    pub progress: Version,
+    /// The version the pruner is working towards.
+    pub target_version: Version,
+    /// Per shard progress, empty if the DB is not sharded.
+    pub shards: Vec<PrunerShardStatus>,
+    /// Rough estimate of the bytes that will be freed once `target_version` is reached, assuming
+    /// the data is evenly spread across versions. RocksDB reclaims them on compaction.
+    pub estimated_reclaimable_bytes: u64,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PrunerShardStatus {
+    pub shard_id: u8,
+    pub progress: Version,
+    pub estimated_reclaimable_bytes: u64,
+}
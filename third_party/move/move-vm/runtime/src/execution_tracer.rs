@@ -0,0 +1,81 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A hook to observe the execution of Move code in a session.
+//!
+//! A tracer is registered for a session by adding an [`ExecutionTracerExtension`] to the native
+//! context extensions of the session. The interpreter looks the extension up once when it is
+//! entered, and does no tracing work at all if it is absent. Tracing never fails the execution.
+
+use better_any::{Tid, TidAble};
+use move_core_types::{
+    account_address::AccountAddress,
+    ident_str,
+    language_storage::{ModuleId, StructTag, TypeTag},
+};
+use parking_lot::Mutex;
+use std::sync::Arc;
+
+/// Receives the events of executing Move code. A function without a module id is the script
+/// executed by the session.
+pub trait ExecutionTracer: Send {
+    /// A Move function was entered, including the function executed by the session.
+    fn on_call(&mut self, module_id: Option<&ModuleId>, function: &str, ty_args: &[TypeTag]);
+
+    /// A Move function returned to its caller, or was unwound because the execution failed.
+    fn on_return(&mut self, module_id: Option<&ModuleId>, function: &str);
+
+    /// A native function is about to be executed, with its arguments rendered as strings.
+    fn on_native_call(
+        &mut self,
+        module_id: &ModuleId,
+        function: &str,
+        ty_args: &[TypeTag],
+        args: &[String],
+    );
+
+    /// A resource was loaded from storage, which happens when it is first accessed in the
+    /// session.
+    fn on_resource_read(
+        &mut self,
+        address: AccountAddress,
+        struct_tag: &StructTag,
+        exists: bool,
+        bytes_loaded: u64,
+    );
+}
+
+/// A tracer shared by all sessions that should be traced, e.g. the sessions of a transaction.
+pub type SharedExecutionTracer = Arc<Mutex<dyn ExecutionTracer>>;
+
+/// Wraps a tracer so it can be registered as a [`SharedExecutionTracer`], while the caller keeps
+/// access to it.
+pub fn share_tracer<T: ExecutionTracer + 'static>(tracer: T) -> Arc<Mutex<T>> {
+    Arc::new(Mutex::new(tracer))
+}
+
+/// Reported in place of a type the interpreter failed to convert into a type tag.
+pub fn placeholder_type_tag() -> TypeTag {
+    TypeTag::Struct(Box::new(StructTag {
+        address: AccountAddress::ZERO,
+        module: ident_str!("unknown").to_owned(),
+        name: ident_str!("Unknown").to_owned(),
+        type_params: vec![],
+    }))
+}
+
+/// The native context extension that registers a tracer for a session.
+#[derive(Tid)]
+pub struct ExecutionTracerExtension {
+    tracer: SharedExecutionTracer,
+}
+
+impl ExecutionTracerExtension {
+    pub fn new(tracer: SharedExecutionTracer) -> Self {
+        Self { tracer }
+    }
+
+    pub fn tracer(&self) -> &SharedExecutionTracer {
+        &self.tracer
+    }
+}
@@ -4,6 +4,7 @@
 
 use crate::{
     data_cache::TransactionDataCache,
+    execution_tracer::{placeholder_type_tag, ExecutionTracerExtension, SharedExecutionTracer},
     loader::{Function, Loader, Resolver},
     native_extensions::NativeContextExtensions,
     native_functions::NativeContext,
@@ -30,7 +31,7 @@ use move_vm_types::{
     },
     views::TypeView,
 };
-use std::{cmp::min, collections::VecDeque, fmt::Write, sync::Arc};
+use std::{cmp::min, collections::VecDeque, fmt::Write, mem, sync::Arc};
 
 macro_rules! debug_write {
     ($($toks: tt)*) => {
@@ -68,6 +69,24 @@ pub(crate) struct Interpreter {
     call_stack: CallStack,
     /// Whether to perform a paranoid type safety checks at runtime.
     paranoid_type_checks: bool,
+    /// The tracer registered for the session, if any.
+    tracer: Option<SharedExecutionTracer>,
+    /// The functions reported as entered to the tracer and not returned from yet. Always empty
+    /// without a tracer.
+    traced_frames: Vec<Arc<Function>>,
+}
+
+/// Converts a type into a type tag for the tracer. Tracing doesn't fail the execution, so a type
+/// that can't be converted is logged and reported as a placeholder.
+fn type_tag_for_trace(loader: &Loader, ty: &Type) -> TypeTag {
+    loader.type_to_type_tag(ty).unwrap_or_else(|err| {
+        ::tracing::warn!(
+            "Failed to convert {:?} into a type tag for tracing: {:?}",
+            ty,
+            err
+        );
+        placeholder_type_tag()
+    })
 }
 
 struct TypeWithLoader<'a, 'b> {
@@ -93,14 +112,21 @@ impl Interpreter {
         extensions: &mut NativeContextExtensions,
         loader: &Loader,
     ) -> VMResult<Vec<Value>> {
-        Interpreter {
+        let mut interpreter = Interpreter {
             operand_stack: Stack::new(),
             call_stack: CallStack::new(),
             paranoid_type_checks: loader.vm_config().paranoid_type_checks,
-        }
-        .execute_main(
+            tracer: extensions
+                .try_get::<ExecutionTracerExtension>()
+                .map(|ext| ext.tracer().clone()),
+            traced_frames: vec![],
+        };
+        let result = interpreter.execute_main(
             loader, data_store, gas_meter, extensions, function, ty_args, args,
-        )
+        );
+        // The frames left when the execution fails are popped without returning
+        while interpreter.trace_return() {}
+        result
     }
 
     /// Main loop for the execution of a function.
@@ -110,7 +136,7 @@ impl Interpreter {
     /// on call. When that happens the frame is changes to a new one (call) or to the one
     /// at the top of the stack (return). If the call stack is empty execution is completed.
     fn execute_main(
-        mut self,
+        &mut self,
         loader: &Loader,
         data_store: &mut TransactionDataCache,
         gas_meter: &mut impl GasMeter,
@@ -132,15 +158,15 @@ impl Interpreter {
                 .map_err(|e| self.set_location(e))?;
         }
 
+        self.trace_call(loader, &function, &ty_args);
         let mut current_frame = self
             .make_new_frame(loader, function, ty_args, locals)
             .map_err(|err| self.set_location(err))?;
         loop {
             let resolver = current_frame.resolver(loader);
-            let exit_code =
-                current_frame //self
-                    .execute_code(&resolver, &mut self, data_store, gas_meter)
-                    .map_err(|err| self.maybe_core_dump(err, &current_frame))?;
+            let exit_code = current_frame //self
+                .execute_code(&resolver, self, data_store, gas_meter)
+                .map_err(|err| self.maybe_core_dump(err, &current_frame))?;
             match exit_code {
                 ExitCode::Return => {
                     let non_ref_vals = current_frame
@@ -154,13 +180,15 @@ impl Interpreter {
                         .charge_drop_frame(non_ref_vals.iter())
                         .map_err(|e| self.set_location(e))?;
 
+                    self.trace_return();
+
                     if let Some(frame) = self.call_stack.pop() {
                         // Note: the caller will find the callee's return values at the top of the shared operand stack
                         current_frame = frame;
                         current_frame.pc += 1; // advance past the Call instruction in the caller
                     } else {
                         // end of execution. `self` should no longer be used afterward
-                        return Ok(self.operand_stack.value);
+                        return Ok(mem::take(&mut self.operand_stack.value));
                     }
                 },
                 ExitCode::Call(fh_idx) => {
@@ -203,6 +231,7 @@ impl Interpreter {
                         current_frame.pc += 1; // advance past the Call instruction in the caller
                         continue;
                     }
+                    self.trace_call(loader, &func, &[]);
                     let frame = self
                         .make_call_frame(loader, func, vec![])
                         .map_err(|e| self.set_location(e))
@@ -255,6 +284,7 @@ impl Interpreter {
                         current_frame.pc += 1; // advance past the Call instruction in the caller
                         continue;
                     }
+                    self.trace_call(loader, &func, &ty_args);
                     let frame = self
                         .make_call_frame(loader, func, ty_args)
                         .map_err(|e| self.set_location(e))
@@ -270,6 +300,34 @@ impl Interpreter {
         }
     }
 
+    /// Reports entering a Move function to the tracer, if any.
+    fn trace_call(&mut self, loader: &Loader, function: &Arc<Function>, ty_args: &[Type]) {
+        if let Some(tracer) = &self.tracer {
+            let ty_args = ty_args
+                .iter()
+                .map(|ty| type_tag_for_trace(loader, ty))
+                .collect::<Vec<_>>();
+            tracer
+                .lock()
+                .on_call(function.module_id(), function.name(), &ty_args);
+            self.traced_frames.push(function.clone());
+        }
+    }
+
+    /// Reports returning from the innermost function entered to the tracer, if any. Returns
+    /// whether there was such a function.
+    fn trace_return(&mut self) -> bool {
+        match (&self.tracer, self.traced_frames.pop()) {
+            (Some(tracer), Some(function)) => {
+                tracer
+                    .lock()
+                    .on_return(function.module_id(), function.name());
+                true
+            },
+            _ => false,
+        }
+    }
+
     /// Returns a `Frame` if the call is to a Move function. Calls to native functions are
     /// "inlined" and this returns `None`.
     ///
@@ -404,6 +462,17 @@ impl Interpreter {
             }
         }
 
+        if let (Some(tracer), Some(module_id)) = (&self.tracer, function.module_id()) {
+            let ty_arg_tags = ty_args
+                .iter()
+                .map(|ty| type_tag_for_trace(resolver.loader(), ty))
+                .collect::<Vec<_>>();
+            let rendered_args = args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+            tracer
+                .lock()
+                .on_native_call(module_id, function.name(), &ty_arg_tags, &rendered_args);
+        }
+
         let mut native_context = NativeContext::new(
             self,
             data_store,
@@ -549,6 +618,7 @@ impl Interpreter {
         loader: &Loader,
         data_store: &'c mut TransactionDataCache,
         gas_meter: &mut impl GasMeter,
+        tracer: Option<&SharedExecutionTracer>,
         addr: AccountAddress,
         ty: &Type,
     ) -> PartialVMResult<&'c mut GlobalValue> {
@@ -561,6 +631,16 @@ impl Interpreter {
                         gv.view(),
                         bytes_loaded,
                     )?;
+                    if let Some(tracer) = tracer {
+                        if let TypeTag::Struct(struct_tag) = type_tag_for_trace(loader, ty) {
+                            tracer.lock().on_resource_read(
+                                addr,
+                                &struct_tag,
+                                gv.exists()?,
+                                bytes_loaded.into(),
+                            );
+                        }
+                    }
                 }
                 Ok(gv)
             },
@@ -579,7 +659,15 @@ impl Interpreter {
         addr: AccountAddress,
         ty: &Type,
     ) -> PartialVMResult<()> {
-        let res = Self::load_resource(loader, data_store, gas_meter, addr, ty)?.borrow_global();
+        let res = Self::load_resource(
+            loader,
+            data_store,
+            gas_meter,
+            self.tracer.as_ref(),
+            addr,
+            ty,
+        )?
+        .borrow_global();
         gas_meter.charge_borrow_global(
             is_mut,
             is_generic,
@@ -602,7 +690,14 @@ impl Interpreter {
         addr: AccountAddress,
         ty: &Type,
     ) -> PartialVMResult<()> {
-        let gv = Self::load_resource(loader, data_store, gas_meter, addr, ty)?;
+        let gv = Self::load_resource(
+            loader,
+            data_store,
+            gas_meter,
+            self.tracer.as_ref(),
+            addr,
+            ty,
+        )?;
         let exists = gv.exists()?;
         gas_meter.charge_exists(is_generic, TypeWithLoader { ty, loader }, exists)?;
         self.operand_stack.push(Value::bool(exists))?;
@@ -619,8 +714,15 @@ impl Interpreter {
         addr: AccountAddress,
         ty: &Type,
     ) -> PartialVMResult<()> {
-        let resource = match Self::load_resource(loader, data_store, gas_meter, addr, ty)?
-            .move_from()
+        let resource = match Self::load_resource(
+            loader,
+            data_store,
+            gas_meter,
+            self.tracer.as_ref(),
+            addr,
+            ty,
+        )?
+        .move_from()
         {
             Ok(resource) => {
                 gas_meter.charge_move_from(
@@ -651,7 +753,14 @@ impl Interpreter {
         ty: &Type,
         resource: Value,
     ) -> PartialVMResult<()> {
-        let gv = Self::load_resource(loader, data_store, gas_meter, addr, ty)?;
+        let gv = Self::load_resource(
+            loader,
+            data_store,
+            gas_meter,
+            self.tracer.as_ref(),
+            addr,
+            ty,
+        )?;
         // NOTE(Gas): To maintain backward compatibility, we need to charge gas after attempting
         //            the move_to operation.
         match gv.move_to(resource) {
@@ -35,6 +35,12 @@ impl<'a> NativeContextExtensions<'a> {
             .unwrap()
     }
 
+    pub fn try_get<T: TidAble<'a>>(&self) -> Option<&T> {
+        self.map
+            .get(&T::id())
+            .map(|ext| ext.as_ref().downcast_ref::<T>().unwrap())
+    }
+
     pub fn get_mut<T: TidAble<'a>>(&mut self) -> &mut T {
         self.map
             .get_mut(&T::id())
@@ -80,4 +86,13 @@ mod tests {
         let e1 = exts.remove::<Ext>();
         assert_eq!(*e1.a, 25)
     }
+
+    #[test]
+    fn try_get_ext() {
+        let mut v: u64 = 23;
+        let mut exts = NativeContextExtensions::default();
+        assert!(exts.try_get::<Ext>().is_none());
+        exts.add(Ext { a: &mut v });
+        assert_eq!(*exts.try_get::<Ext>().unwrap().a, 23);
+    }
 }
@@ -50,6 +50,9 @@ pub enum FeatureFlag {
     VM_BINARY_FORMAT_V7 = 40,
     RESOURCE_GROUPS_CHARGE_AS_SIZE_SUM = 41,
     COMMISSION_CHANGE_DELEGATION_POOL = 42,
+    BN254_STRUCTURES = 43,
+    WEBAUTHN_SIGNATURE = 44,
+    POSEIDON_NATIVES = 45,
 }
 
 /// Representation of features on chain as a bitset.
@@ -68,6 +71,7 @@ impl Default for Features {
         use FeatureFlag::*;
         features.enable(VM_BINARY_FORMAT_V6);
         features.enable(BLS12_381_STRUCTURES);
+        features.enable(BN254_STRUCTURES);
         features.enable(SIGNATURE_CHECKER_V2);
         features.enable(STORAGE_SLOT_METADATA);
         features.enable(APTOS_UNIQUE_IDENTIFIERS);
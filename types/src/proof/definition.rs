@@ -374,6 +374,292 @@ impl SparseMerkleProof {
     }
 }
 
+/// A proof that can be used to authenticate a batch of elements in a Sparse Merkle Tree given
+/// trusted root hash. It carries the same information as one `SparseMerkleProof` per key, but the
+/// siblings the paths of different keys share, and the ones that are nodes on the path of another
+/// key in the batch, are included only once, or not at all.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SparseMerkleMultiProof {
+    /// For each key, in ascending order of the keys, the leaf its path ends at, with the same
+    /// meaning as `SparseMerkleProof::leaf`.
+    leaves: Vec<Option<SparseMerkleLeafNode>>,
+
+    /// For each key, in ascending order of the keys, the depth its path ends at, i.e. the number
+    /// of siblings a `SparseMerkleProof` for it would have.
+    depths: Vec<u16>,
+
+    /// The siblings that are not computable from the other paths, in the order they are met by
+    /// a depth-first, left-to-right walk of the paths from the root.
+    siblings: Vec<HashValue>,
+}
+
+impl SparseMerkleMultiProof {
+    /// Constructs a new `SparseMerkleMultiProof` using leaves, depths and a list of siblings.
+    pub fn new(
+        leaves: Vec<Option<SparseMerkleLeafNode>>,
+        depths: Vec<u16>,
+        siblings: Vec<HashValue>,
+    ) -> Self {
+        Self {
+            leaves,
+            depths,
+            siblings,
+        }
+    }
+
+    /// Combines the proofs of `keys`, which must be strictly ascending, against the same root.
+    pub fn from_proofs(keys: &[HashValue], proofs: &[SparseMerkleProof]) -> Result<Self> {
+        ensure!(
+            keys.len() == proofs.len(),
+            "Number of keys ({}) does not match number of proofs ({}).",
+            keys.len(),
+            proofs.len(),
+        );
+        ensure_strictly_ascending(keys)?;
+        ensure!(
+            proofs
+                .iter()
+                .all(|proof| proof.siblings.len() <= HashValue::LENGTH_IN_BITS),
+            "Sparse Merkle Tree proof has more than {} siblings.",
+            HashValue::LENGTH_IN_BITS,
+        );
+
+        let mut siblings = vec![];
+        if !keys.is_empty() {
+            Self::collect_siblings(keys, proofs, 0, &mut siblings)?;
+        }
+        Ok(Self {
+            leaves: proofs.iter().map(|proof| proof.leaf).collect(),
+            depths: proofs
+                .iter()
+                .map(|proof| proof.siblings.len() as u16)
+                .collect(),
+            siblings,
+        })
+    }
+
+    fn collect_siblings(
+        keys: &[HashValue],
+        proofs: &[SparseMerkleProof],
+        depth: usize,
+        siblings: &mut Vec<HashValue>,
+    ) -> Result<()> {
+        if proofs.iter().any(|proof| proof.siblings.len() <= depth) {
+            ensure!(
+                proofs
+                    .iter()
+                    .all(|proof| proof.siblings.len() == depth && proof.leaf == proofs[0].leaf),
+                "Proofs of keys sharing a path end in different subtrees at depth {}.",
+                depth,
+            );
+            return Ok(());
+        }
+
+        let mid = split_by_bit(keys, depth);
+        if mid == 0 || mid == keys.len() {
+            // Siblings are ordered from the bottom in a single proof.
+            let proof = &proofs[0];
+            siblings.push(proof.siblings[proof.siblings.len() - 1 - depth]);
+            Self::collect_siblings(keys, proofs, depth + 1, siblings)
+        } else {
+            Self::collect_siblings(&keys[..mid], &proofs[..mid], depth + 1, siblings)?;
+            Self::collect_siblings(&keys[mid..], &proofs[mid..], depth + 1, siblings)
+        }
+    }
+
+    /// Returns the leaf nodes in this proof, one for each key in ascending order.
+    pub fn leaves(&self) -> &[Option<SparseMerkleLeafNode>] {
+        &self.leaves
+    }
+
+    /// Returns the depths the paths of the keys end at, one for each key in ascending order.
+    pub fn depths(&self) -> &[u16] {
+        &self.depths
+    }
+
+    /// Returns the list of deduplicated siblings in this proof.
+    pub fn siblings(&self) -> &[HashValue] {
+        &self.siblings
+    }
+
+    pub fn verify<V: CryptoHash>(
+        &self,
+        expected_root_hash: HashValue,
+        element_keys: &[HashValue],
+        element_values: &[Option<&V>],
+    ) -> Result<()> {
+        let element_hashes: Vec<_> = element_values
+            .iter()
+            .map(|value| value.map(|v| v.hash()))
+            .collect();
+        self.verify_by_hash(expected_root_hash, element_keys, &element_hashes)
+    }
+
+    /// Verifies, for each of `element_keys`, which must be strictly ascending, what
+    /// `SparseMerkleProof::verify_by_hash` does for a single key given the corresponding entry of
+    /// `element_hashes`.
+    pub fn verify_by_hash(
+        &self,
+        expected_root_hash: HashValue,
+        element_keys: &[HashValue],
+        element_hashes: &[Option<HashValue>],
+    ) -> Result<()> {
+        ensure!(
+            element_keys.len() == element_hashes.len()
+                && element_keys.len() == self.leaves.len()
+                && element_keys.len() == self.depths.len(),
+            "Sparse Merkle Tree multi proof is for {} keys, got {} keys and {} element hashes.",
+            self.leaves.len(),
+            element_keys.len(),
+            element_hashes.len(),
+        );
+        ensure!(
+            !element_keys.is_empty(),
+            "Sparse Merkle Tree multi proof proves no keys."
+        );
+        ensure_strictly_ascending(element_keys)?;
+
+        for ((element_key, element_hash), (leaf, depth)) in element_keys
+            .iter()
+            .zip(element_hashes)
+            .zip(self.leaves.iter().zip(&self.depths))
+        {
+            let depth = *depth as usize;
+            ensure!(
+                depth <= HashValue::LENGTH_IN_BITS,
+                "Sparse Merkle Tree multi proof has a path deeper than {} ({}).",
+                HashValue::LENGTH_IN_BITS,
+                depth,
+            );
+            match (element_hash, leaf) {
+                (Some(hash), Some(leaf)) => {
+                    ensure!(
+                        *element_key == leaf.key,
+                        "Keys do not match. Key in proof: {:x}. Expected key: {:x}.",
+                        leaf.key,
+                        element_key,
+                    );
+                    ensure!(
+                        *hash == leaf.value_hash,
+                        "Value hashes do not match for key {:x}. Value hash in proof: {:x}. \
+                         Expected value hash: {:x}. ",
+                        element_key,
+                        leaf.value_hash,
+                        hash
+                    );
+                },
+                (Some(hash), None) => {
+                    bail!(
+                        "Expected inclusion proof for key {:x}, value hash: {:x}. Found \
+                         non-inclusion proof.",
+                        element_key,
+                        hash
+                    )
+                },
+                (None, Some(leaf)) => {
+                    ensure!(
+                        *element_key != leaf.key,
+                        "Expected non-inclusion proof, but key exists in proof. \
+                         Key: {:x}. Key in proof: {:x}.",
+                        element_key,
+                        leaf.key,
+                    );
+                    ensure!(
+                        element_key.common_prefix_bits_len(leaf.key) >= depth,
+                        "Key would not have ended up in the subtree where the provided key in \
+                         proof is the only existing key, if it existed. So this is not a valid \
+                         non-inclusion proof. Key: {:x}. Key in proof: {:x}.",
+                        element_key,
+                        leaf.key
+                    );
+                },
+                (None, None) => {},
+            }
+        }
+
+        let mut siblings = self.siblings.iter();
+        let actual_root_hash =
+            Self::compute_hash(element_keys, &self.leaves, &self.depths, 0, &mut siblings)?;
+        ensure!(
+            siblings.next().is_none(),
+            "Sparse Merkle Tree multi proof has more siblings than the paths of the keys need."
+        );
+        ensure!(
+            actual_root_hash == expected_root_hash,
+            "{}: Root hashes do not match. Actual root hash: {:x}. Expected root hash: {:x}.",
+            type_name::<Self>(),
+            actual_root_hash,
+            expected_root_hash,
+        );
+
+        Ok(())
+    }
+
+    /// Computes the hash of the subtree at `depth` the paths of `keys` go through.
+    fn compute_hash<'a>(
+        keys: &[HashValue],
+        leaves: &[Option<SparseMerkleLeafNode>],
+        depths: &[u16],
+        depth: usize,
+        siblings: &mut impl Iterator<Item = &'a HashValue>,
+    ) -> Result<HashValue> {
+        if depths.iter().any(|d| *d as usize == depth) {
+            ensure!(
+                depths.iter().all(|d| *d as usize == depth)
+                    && leaves.iter().all(|leaf| *leaf == leaves[0]),
+                "Paths of keys sharing a subtree at depth {} end differently.",
+                depth,
+            );
+            return Ok(leaves[0].map_or(*SPARSE_MERKLE_PLACEHOLDER_HASH, |leaf| leaf.hash()));
+        }
+
+        let mid = split_by_bit(keys, depth);
+        let (left_hash, right_hash) = if mid == 0 || mid == keys.len() {
+            let sibling = *siblings.next().ok_or_else(|| {
+                format_err!("Sparse Merkle Tree multi proof ran out of siblings.")
+            })?;
+            let hash = Self::compute_hash(keys, leaves, depths, depth + 1, siblings)?;
+            if mid == 0 {
+                (sibling, hash)
+            } else {
+                (hash, sibling)
+            }
+        } else {
+            (
+                Self::compute_hash(
+                    &keys[..mid],
+                    &leaves[..mid],
+                    &depths[..mid],
+                    depth + 1,
+                    siblings,
+                )?,
+                Self::compute_hash(
+                    &keys[mid..],
+                    &leaves[mid..],
+                    &depths[mid..],
+                    depth + 1,
+                    siblings,
+                )?,
+            )
+        };
+        Ok(SparseMerkleInternalNode::new(left_hash, right_hash).hash())
+    }
+}
+
+fn ensure_strictly_ascending(keys: &[HashValue]) -> Result<()> {
+    ensure!(
+        keys.windows(2).all(|pair| pair[0] < pair[1]),
+        "Keys must be strictly ascending."
+    );
+    Ok(())
+}
+
+/// Returns the index of the first of the ascending `keys` whose bit at `depth` is set, i.e. the
+/// first one going to the right subtree at `depth`.
+fn split_by_bit(keys: &[HashValue], depth: usize) -> usize {
+    keys.partition_point(|key| key.as_ref()[depth / 8] & (0x80 >> (depth % 8)) == 0)
+}
+
 /// An in-memory accumulator for storing a summary of the core transaction info
 /// accumulator. It is a summary in the sense that it only stores maximally
 /// frozen subtree nodes rather than storing all leaves and internal nodes.
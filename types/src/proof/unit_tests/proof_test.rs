@@ -11,9 +11,9 @@ use crate::{
     ledger_info::LedgerInfo,
     proof::{
         definition::MAX_ACCUMULATOR_PROOF_DEPTH, AccumulatorExtensionProof, AccumulatorRangeProof,
-        SparseMerkleInternalNode, SparseMerkleLeafNode, TestAccumulatorInternalNode,
-        TestAccumulatorProof, TransactionAccumulatorInternalNode, TransactionAccumulatorProof,
-        TransactionInfoListWithProof, TransactionInfoWithProof,
+        SparseMerkleInternalNode, SparseMerkleLeafNode, SparseMerkleMultiProof,
+        TestAccumulatorInternalNode, TestAccumulatorProof, TransactionAccumulatorInternalNode,
+        TransactionAccumulatorProof, TransactionInfoListWithProof, TransactionInfoWithProof,
     },
     state_store::state_value::StateValue,
     transaction::{
@@ -266,6 +266,140 @@ fn test_verify_three_element_sparse_merkle() {
     }
 }
 
+#[test]
+fn test_verify_sparse_merkle_multi_proof() {
+    // Same tree as in `test_verify_three_element_sparse_merkle`.
+    //            root
+    //           /    \
+    //          a      default
+    //         / \
+    //     key1   b
+    //           / \
+    //       key2   key3
+    let key1 = b"hello".test_only_hash();
+    let key2 = b"world".test_only_hash();
+    let key3 = b"!".test_only_hash();
+    let non_existing_key1 = b"abc".test_only_hash();
+    let non_existing_key2 = b"def".test_only_hash();
+
+    let blob1 = StateValue::from(b"1".to_vec());
+    let blob2 = StateValue::from(b"2".to_vec());
+    let blob3 = StateValue::from(b"3".to_vec());
+
+    let leaf1 = SparseMerkleLeafNode::new(key1, blob1.hash());
+    let leaf2 = SparseMerkleLeafNode::new(key2, blob2.hash());
+    let leaf3 = SparseMerkleLeafNode::new(key3, blob3.hash());
+    let internal_b_hash = SparseMerkleInternalNode::new(leaf2.hash(), leaf3.hash()).hash();
+    let internal_a_hash = SparseMerkleInternalNode::new(leaf1.hash(), internal_b_hash).hash();
+    let root_hash =
+        SparseMerkleInternalNode::new(internal_a_hash, *SPARSE_MERKLE_PLACEHOLDER_HASH).hash();
+
+    let key1_proof = SparseMerkleProof::new(Some(leaf1), vec![
+        internal_b_hash,
+        *SPARSE_MERKLE_PLACEHOLDER_HASH,
+    ]);
+    let key2_proof = SparseMerkleProof::new(Some(leaf2), vec![
+        leaf3.hash(),
+        leaf1.hash(),
+        *SPARSE_MERKLE_PLACEHOLDER_HASH,
+    ]);
+    let key3_proof = SparseMerkleProof::new(Some(leaf3), vec![
+        leaf2.hash(),
+        leaf1.hash(),
+        *SPARSE_MERKLE_PLACEHOLDER_HASH,
+    ]);
+    let non_existing_key2_proof = SparseMerkleProof::new(None, vec![internal_a_hash]);
+
+    {
+        // All keys together: every sibling is on the path of another key.
+        let keys = [key1, non_existing_key1, key2, key3, non_existing_key2];
+        let proof = SparseMerkleMultiProof::from_proofs(&keys, &[
+            key1_proof.clone(),
+            key1_proof.clone(),
+            key2_proof.clone(),
+            key3_proof.clone(),
+            non_existing_key2_proof.clone(),
+        ])
+        .unwrap();
+        assert!(proof.siblings().is_empty());
+        assert_eq!(proof.depths(), &[2, 2, 3, 3, 1]);
+
+        assert!(proof
+            .verify(root_hash, &keys, &[
+                Some(&blob1),
+                None,
+                Some(&blob2),
+                Some(&blob3),
+                None
+            ])
+            .is_ok());
+        // Trying to show that key2 has another value.
+        assert!(proof
+            .verify(root_hash, &keys, &[
+                Some(&blob1),
+                None,
+                Some(&blob1),
+                Some(&blob3),
+                None
+            ])
+            .is_err());
+        // Trying to show that key1 doesn't exist.
+        assert!(proof
+            .verify(root_hash, &keys, &[
+                None,
+                None,
+                Some(&blob2),
+                Some(&blob3),
+                None
+            ])
+            .is_err());
+    }
+
+    {
+        // Only the sibling of b is left.
+        let keys = [key2, key3, non_existing_key2];
+        let proof = SparseMerkleMultiProof::from_proofs(&keys, &[
+            key2_proof.clone(),
+            key3_proof.clone(),
+            non_existing_key2_proof.clone(),
+        ])
+        .unwrap();
+        assert_eq!(proof.siblings(), &[leaf1.hash()]);
+
+        let values = [Some(&blob2), Some(&blob3), None];
+        assert!(proof.verify(root_hash, &keys, &values).is_ok());
+        assert!(proof.verify(internal_a_hash, &keys, &values).is_err());
+        // Keys must be in the same order as the proof was built for.
+        assert!(proof
+            .verify(root_hash, &[key3, key2, non_existing_key2], &values)
+            .is_err());
+        // The proof doesn't cover fewer keys.
+        assert!(proof
+            .verify(root_hash, &keys[..2], &values[..2])
+            .is_err());
+        // Unused siblings are rejected.
+        let padded = SparseMerkleMultiProof::new(
+            proof.leaves().to_vec(),
+            proof.depths().to_vec(),
+            vec![leaf1.hash(), *SPARSE_MERKLE_PLACEHOLDER_HASH],
+        );
+        assert!(padded.verify(root_hash, &keys, &values).is_err());
+
+        let decoded: SparseMerkleMultiProof =
+            bcs::from_bytes(&bcs::to_bytes(&proof).unwrap()).unwrap();
+        assert_eq!(decoded, proof);
+    }
+
+    // Proofs ending in different subtrees can't be for keys sharing a path there.
+    assert!(SparseMerkleMultiProof::from_proofs(&[key1, non_existing_key1], &[
+        key1_proof,
+        non_existing_key2_proof,
+    ])
+    .is_err());
+    // Keys must be ascending.
+    assert!(SparseMerkleMultiProof::from_proofs(&[key3, key2], &[key3_proof, key2_proof]).is_err());
+}
+
 #[test]
 fn test_verify_transaction() {
     //            root
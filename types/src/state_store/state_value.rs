@@ -2,9 +2,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    on_chain_config::CurrentTimeMicroseconds, proof::SparseMerkleRangeProof,
-    state_store::state_key::StateKey, transaction::Version,
+    on_chain_config::CurrentTimeMicroseconds,
+    proof::{SparseMerkleMultiProof, SparseMerkleRangeProof},
+    state_store::state_key::StateKey,
+    transaction::Version,
 };
+use anyhow::ensure;
 use aptos_crypto::{
     hash::{CryptoHash, SPARSE_MERKLE_PLACEHOLDER_HASH},
     HashValue,
@@ -232,6 +235,39 @@ impl StateValueChunkWithProof {
     }
 }
 
+/// The state values of a set of state keys at a specific version, with a single proof for all of
+/// them.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct StateValuesWithMultiProof {
+    pub version: Version, // The version of the state the values are read at
+    pub raw_values: Vec<(StateKey, Option<StateValue>)>, // The requested keys and their values, if any.
+    pub proof: SparseMerkleMultiProof, // The proof for the distinct keys, in ascending hash order
+}
+
+impl StateValuesWithMultiProof {
+    /// Verifies the values against the root hash of the state at `version`.
+    pub fn verify(&self, expected_root_hash: HashValue) -> anyhow::Result<()> {
+        let mut proved: Vec<_> = self
+            .raw_values
+            .iter()
+            .map(|(key, value)| (key.hash(), value.as_ref()))
+            .collect();
+        proved.sort_by_key(|(key_hash, _)| *key_hash);
+        // The same key can only have one value.
+        for pair in proved.windows(2) {
+            ensure!(
+                pair[0].0 != pair[1].0 || pair[0].1 == pair[1].1,
+                "Conflicting values for state key hash {:x}.",
+                pair[0].0,
+            );
+        }
+        proved.dedup_by_key(|(key_hash, _)| *key_hash);
+
+        let (key_hashes, values): (Vec<_>, Vec<_>) = proved.into_iter().unzip();
+        self.proof.verify(expected_root_hash, &key_hashes, &values)
+    }
+}
+
 /// Indicates a state value becomes stale since `stale_since_version`.
 #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 #[cfg_attr(any(test, feature = "fuzzing"), derive(proptest_derive::Arbitrary))]
@@ -0,0 +1,39 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::state_store::state_key::StateKey;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// The state keys a transaction read and wrote when it was executed.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct AccessList {
+    read_set: BTreeSet<StateKey>,
+    write_set: BTreeSet<StateKey>,
+}
+
+impl AccessList {
+    pub fn new(read_set: BTreeSet<StateKey>, write_set: BTreeSet<StateKey>) -> Self {
+        Self {
+            read_set,
+            write_set,
+        }
+    }
+
+    pub fn read_set(&self) -> &BTreeSet<StateKey> {
+        &self.read_set
+    }
+
+    pub fn write_set(&self) -> &BTreeSet<StateKey> {
+        &self.write_set
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.read_set.is_empty() && self.write_set.is_empty()
+    }
+
+    /// Returns all keys that were accessed, each key once.
+    pub fn keys(&self) -> impl Iterator<Item = &StateKey> {
+        self.read_set.union(&self.write_set)
+    }
+}
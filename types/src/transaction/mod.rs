@@ -12,7 +12,7 @@ use crate::{
     contract_event::{ContractEvent, FEE_STATEMENT_EVENT_TYPE},
     ledger_info::LedgerInfo,
     proof::{TransactionInfoListWithProof, TransactionInfoWithProof},
-    state_store::ShardedStateUpdates,
+    state_store::{state_key::StateKey, ShardedStateUpdates},
     transaction::authenticator::{
         AccountAuthenticator, AnyPublicKey, AnySignature, SingleKeyAuthenticator,
         TransactionAuthenticator,
@@ -30,17 +30,20 @@ use aptos_crypto::{
     CryptoMaterialError, HashValue,
 };
 use aptos_crypto_derive::{BCSCryptoHash, CryptoHasher};
+use derivative::Derivative;
 use move_core_types::transaction_argument::convert_txn_args;
 #[cfg(any(test, feature = "fuzzing"))]
 use proptest_derive::Arbitrary;
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::BTreeSet,
     convert::TryFrom,
     fmt,
     fmt::{Debug, Display, Formatter},
 };
 
+mod access_list;
 pub mod analyzed_transaction;
 pub mod authenticator;
 mod change_set;
@@ -48,12 +51,14 @@ mod module;
 mod multisig;
 mod script;
 pub mod signature_verified_transaction;
+pub mod webauthn;
 
 use crate::{
     contract_event::TransactionEvent, executable::ModulePath, fee_statement::FeeStatement,
     proof::accumulator::InMemoryEventAccumulator, system_txn::SystemTransaction,
     write_set::TransactionWrite,
 };
+pub use access_list::AccessList;
 pub use change_set::ChangeSet;
 pub use module::{Module, ModuleBundle};
 pub use move_core_types::transaction_argument::TransactionArgument;
@@ -1083,7 +1088,8 @@ impl VMValidatorResult {
 }
 
 /// The output of executing a transaction.
-#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Derivative, Serialize, Deserialize)]
+#[derivative(Eq, PartialEq)]
 pub struct TransactionOutput {
     /// The list of writes this transaction intends to do.
     write_set: WriteSet,
@@ -1096,6 +1102,12 @@ pub struct TransactionOutput {
 
     /// The execution status.
     status: TransactionStatus,
+
+    /// The keys of the state read during execution, if recording is enabled in the executor.
+    /// This is a hint for prefetching that is neither persisted nor compared.
+    #[serde(skip)]
+    #[derivative(PartialEq = "ignore")]
+    read_set: BTreeSet<StateKey>,
 }
 
 impl TransactionOutput {
@@ -1110,9 +1122,15 @@ impl TransactionOutput {
             events,
             gas_used,
             status,
+            read_set: BTreeSet::new(),
         }
     }
 
+    pub fn with_read_set(mut self, read_set: BTreeSet<StateKey>) -> Self {
+        self.read_set = read_set;
+        self
+    }
+
     pub fn into(self) -> (WriteSet, Vec<ContractEvent>) {
         (self.write_set, self.events)
     }
@@ -1142,12 +1160,25 @@ impl TransactionOutput {
         &self.status
     }
 
+    pub fn read_set(&self) -> &BTreeSet<StateKey> {
+        &self.read_set
+    }
+
+    /// Returns the keys read and written by the transaction.
+    pub fn access_list(&self) -> AccessList {
+        AccessList::new(
+            self.read_set.clone(),
+            self.write_set.iter().map(|(key, _)| key.clone()).collect(),
+        )
+    }
+
     pub fn unpack(self) -> (WriteSet, Vec<ContractEvent>, u64, TransactionStatus) {
         let Self {
             write_set,
             events,
             gas_used,
             status,
+            read_set: _,
         } = self;
         (write_set, events, gas_used, status)
     }
@@ -0,0 +1,261 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Support for WebAuthn (passkey) assertions as transaction signatures.
+//!
+//! A WebAuthn authenticator never signs the transaction directly. Instead, the wallet passes the
+//! SHA3-256 hash of the transaction's signing message as the WebAuthn `challenge`, and the
+//! authenticator signs `authenticatorData || SHA2-256(clientDataJSON)`, where `clientDataJSON`
+//! embeds the base64url-encoded challenge. Verification therefore checks that the challenge
+//! commits to the transaction and that the signature is valid over the assertion data.
+
+use anyhow::{ensure, Result};
+use aptos_crypto::{
+    hash::CryptoHash, p256_ecdsa, signing_message, traits::Signature, CryptoMaterialError,
+    HashValue,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// The `type` of `clientDataJSON` for an assertion (as opposed to `webauthn.create`).
+const WEBAUTHN_GET_TYPE: &str = "webauthn.get";
+
+/// `authenticatorData` is a 32-byte RP ID hash, a 1-byte flags field and a 4-byte sign counter,
+/// optionally followed by attested credential data and extensions.
+const MIN_AUTHENTICATOR_DATA_LENGTH: usize = 37;
+
+/// Offset of the flags byte in `authenticatorData`.
+const AUTHENTICATOR_DATA_FLAGS_OFFSET: usize = 32;
+
+/// The "User Present" bit of the `authenticatorData` flags.
+const USER_PRESENT_FLAG: u8 = 0x01;
+
+/// The signature carried by an assertion, tagged by the algorithm of the credential.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum AssertionSignature {
+    Secp256r1Ecdsa { signature: p256_ecdsa::Signature },
+}
+
+/// The subset of an `AuthenticatorAssertionResponse` needed to verify it on chain. The
+/// `userHandle` is omitted since the account is identified by its public key.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct PartialAuthenticatorAssertionResponse {
+    signature: AssertionSignature,
+    authenticator_data: Vec<u8>,
+    client_data_json: Vec<u8>,
+}
+
+/// The fields of `clientDataJSON` that are relevant for verification. Unknown fields such as
+/// `origin` or `crossOrigin` are ignored since the chain has no notion of a relying party.
+#[derive(Debug, Deserialize)]
+struct CollectedClientData {
+    #[serde(rename = "type")]
+    ty: String,
+    challenge: String,
+}
+
+impl PartialAuthenticatorAssertionResponse {
+    pub fn new(
+        signature: AssertionSignature,
+        authenticator_data: Vec<u8>,
+        client_data_json: Vec<u8>,
+    ) -> Self {
+        Self {
+            signature,
+            authenticator_data,
+            client_data_json,
+        }
+    }
+
+    pub fn signature(&self) -> &AssertionSignature {
+        &self.signature
+    }
+
+    pub fn authenticator_data(&self) -> &[u8] {
+        &self.authenticator_data
+    }
+
+    pub fn client_data_json(&self) -> &[u8] {
+        &self.client_data_json
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        bcs::to_bytes(self).expect("Only unhandleable errors happen here.")
+    }
+
+    /// The challenge a wallet must request the authenticator to sign for `message`.
+    pub fn challenge<T: Serialize + CryptoHash>(message: &T) -> Result<HashValue> {
+        Ok(HashValue::sha3_256_of(&signing_message(message)?))
+    }
+
+    /// The data an authenticator signs: `authenticatorData || SHA2-256(clientDataJSON)`.
+    pub fn verification_data(authenticator_data: &[u8], client_data_json: &[u8]) -> Vec<u8> {
+        let mut data = authenticator_data.to_vec();
+        data.extend_from_slice(Sha256::digest(client_data_json).as_slice());
+        data
+    }
+
+    /// Return Ok if this assertion was produced by `public_key` over a challenge committing to
+    /// `message`, Err otherwise.
+    pub fn verify<T: Serialize + CryptoHash>(
+        &self,
+        message: &T,
+        public_key: &p256_ecdsa::PublicKey,
+    ) -> Result<()> {
+        ensure!(
+            self.authenticator_data.len() >= MIN_AUTHENTICATOR_DATA_LENGTH,
+            "WebAuthn authenticator data is too short: {} bytes",
+            self.authenticator_data.len()
+        );
+        ensure!(
+            self.authenticator_data[AUTHENTICATOR_DATA_FLAGS_OFFSET] & USER_PRESENT_FLAG != 0,
+            "WebAuthn assertion does not have the user present flag set"
+        );
+
+        let client_data: CollectedClientData = serde_json::from_slice(&self.client_data_json)?;
+        ensure!(
+            client_data.ty == WEBAUTHN_GET_TYPE,
+            "Unexpected WebAuthn client data type: {}",
+            client_data.ty
+        );
+        let challenge = base64::decode_config(&client_data.challenge, base64::URL_SAFE_NO_PAD)?;
+        ensure!(
+            challenge == Self::challenge(message)?.to_vec(),
+            "WebAuthn challenge does not match the signed message"
+        );
+
+        let verification_data =
+            Self::verification_data(&self.authenticator_data, &self.client_data_json);
+        match &self.signature {
+            AssertionSignature::Secp256r1Ecdsa { signature } => {
+                signature.verify_arbitrary_msg(&verification_data, public_key)
+            },
+        }
+    }
+}
+
+impl TryFrom<&[u8]> for PartialAuthenticatorAssertionResponse {
+    type Error = CryptoMaterialError;
+
+    fn try_from(bytes: &[u8]) -> std::result::Result<Self, CryptoMaterialError> {
+        bcs::from_bytes(bytes).map_err(|_| CryptoMaterialError::DeserializationError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_crypto::{PrivateKey, SigningKey, Uniform};
+    use aptos_crypto_derive::{BCSCryptoHash, CryptoHasher};
+    use rand::rngs::OsRng;
+
+    #[derive(Serialize, Deserialize, CryptoHasher, BCSCryptoHash)]
+    struct TestMessage(String);
+
+    fn client_data_json(ty: &str, challenge: &[u8]) -> Vec<u8> {
+        format!(
+            r#"{{"type":"{}","challenge":"{}","origin":"https://wallet.example","crossOrigin":false}}"#,
+            ty,
+            base64::encode_config(challenge, base64::URL_SAFE_NO_PAD)
+        )
+        .into_bytes()
+    }
+
+    fn sign_assertion(
+        private_key: &p256_ecdsa::PrivateKey,
+        authenticator_data: Vec<u8>,
+        client_data_json: Vec<u8>,
+    ) -> PartialAuthenticatorAssertionResponse {
+        let signature = private_key.sign_arbitrary_message(
+            &PartialAuthenticatorAssertionResponse::verification_data(
+                &authenticator_data,
+                &client_data_json,
+            ),
+        );
+        PartialAuthenticatorAssertionResponse::new(
+            AssertionSignature::Secp256r1Ecdsa { signature },
+            authenticator_data,
+            client_data_json,
+        )
+    }
+
+    fn authenticator_data(flags: u8) -> Vec<u8> {
+        let mut data = vec![0xAB; 32];
+        data.push(flags);
+        data.extend_from_slice(&7u32.to_be_bytes());
+        data
+    }
+
+    #[test]
+    fn verify_webauthn_assertion() {
+        let private_key = p256_ecdsa::PrivateKey::generate_for_testing();
+        let public_key = private_key.public_key();
+        let message = TestMessage("transfer".to_string());
+        let challenge = PartialAuthenticatorAssertionResponse::challenge(&message).unwrap();
+
+        let assertion = sign_assertion(
+            &private_key,
+            authenticator_data(USER_PRESENT_FLAG),
+            client_data_json(WEBAUTHN_GET_TYPE, challenge.as_ref()),
+        );
+        assertion.verify(&message, &public_key).unwrap();
+        assert_eq!(
+            PartialAuthenticatorAssertionResponse::try_from(assertion.to_bytes().as_slice())
+                .unwrap(),
+            assertion
+        );
+
+        // Wrong message
+        assertion
+            .verify(&TestMessage("withdraw".to_string()), &public_key)
+            .unwrap_err();
+
+        // Wrong key
+        let other_public_key = p256_ecdsa::PrivateKey::generate(&mut OsRng).public_key();
+        assertion.verify(&message, &other_public_key).unwrap_err();
+    }
+
+    #[test]
+    fn reject_malformed_webauthn_assertions() {
+        let private_key = p256_ecdsa::PrivateKey::generate_for_testing();
+        let public_key = private_key.public_key();
+        let message = TestMessage("transfer".to_string());
+        let challenge = PartialAuthenticatorAssertionResponse::challenge(&message).unwrap();
+
+        // Registration rather than assertion
+        sign_assertion(
+            &private_key,
+            authenticator_data(USER_PRESENT_FLAG),
+            client_data_json("webauthn.create", challenge.as_ref()),
+        )
+        .verify(&message, &public_key)
+        .unwrap_err();
+
+        // User not present
+        sign_assertion(
+            &private_key,
+            authenticator_data(0),
+            client_data_json(WEBAUTHN_GET_TYPE, challenge.as_ref()),
+        )
+        .verify(&message, &public_key)
+        .unwrap_err();
+
+        // Truncated authenticator data
+        sign_assertion(
+            &private_key,
+            authenticator_data(USER_PRESENT_FLAG)[..MIN_AUTHENTICATOR_DATA_LENGTH - 1].to_vec(),
+            client_data_json(WEBAUTHN_GET_TYPE, challenge.as_ref()),
+        )
+        .verify(&message, &public_key)
+        .unwrap_err();
+
+        // Not JSON
+        sign_assertion(
+            &private_key,
+            authenticator_data(USER_PRESENT_FLAG),
+            challenge.to_vec(),
+        )
+        .verify(&message, &public_key)
+        .unwrap_err();
+    }
+}